@@ -205,6 +205,12 @@ pub struct Artifact {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub path: Option<LocalPath>,
+    /// The URL the artifact will be downloadable from once it's released
+    ///
+    /// This is only known if the artifacts are being uploaded somewhere (e.g. a GitHub Release).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub download_url: Option<String>,
     /// Assets included in the bundle (like executables and READMEs)
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default)]
@@ -221,6 +227,18 @@ pub struct Artifact {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub checksum: Option<String>,
+    /// id of an artifact that contains a detached signature for this artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// A note on why this artifact is being built (only included with --verbose-plan)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub provenance: Option<String>,
+    /// The SPDX license expression of the app this artifact is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub license: Option<String>,
 }
 
 /// An asset contained in an artifact (executable, license, etc.)
@@ -281,6 +299,24 @@ pub enum ArtifactKind {
     /// A checksum of another artifact
     #[serde(rename = "checksum")]
     Checksum,
+    /// A detached signature of another artifact
+    #[serde(rename = "signature")]
+    Signature,
+    /// A checksum of every other artifact in a release (like a SHA256SUMS file)
+    #[serde(rename = "unified-checksum")]
+    UnifiedChecksum,
+    /// Citation metadata for the release (a CITATION.cff file)
+    #[serde(rename = "citation")]
+    Citation,
+    /// The full release notes for the announcement (a RELEASE_NOTES.md file)
+    #[serde(rename = "release-notes")]
+    ReleaseNotes,
+    /// A binary delta from the previous release's version of another artifact
+    #[serde(rename = "delta")]
+    Delta,
+    /// A script for rebuilding artifacts from deltas
+    #[serde(rename = "delta-script")]
+    DeltaScript,
     /// Unknown to this version of cargo-dist-schema
     ///
     /// This is a fallback for forward/backward-compat
@@ -0,0 +1,424 @@
+//! Reproducible creation of archives (zips and tarballs)
+//!
+//! Rebuilding the same inputs should produce byte-for-byte identical archives,
+//! so that the checksums we publish can be compared across rebuilds. To achieve
+//! this we:
+//!
+//! * add entries in a sorted order (instead of whatever order the filesystem reports)
+//! * pin every entry's mtime to `SOURCE_DATE_EPOCH` (or the earliest time the format supports)
+//! * normalize uid/gid/owner names and permissions in tar headers
+//!
+//! Permissions don't come from the filesystem at all: the binaries we copied into the
+//! archive dir (and all directories) are marked `0o755`, and everything else (static
+//! assets like READMEs and licenses) is marked `0o644`.
+
+use std::{fs::File, io::Write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::{Compression, GzBuilder};
+use xz2::write::XzEncoder;
+
+use crate::{
+    config::{CompressionImpl, ZipStyle},
+    errors::{DistError, DistResult},
+};
+
+/// The env var used by the [reproducible builds][] project to pin timestamps
+///
+/// [reproducible builds]: https://reproducible-builds.org/docs/source-date-epoch/
+const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+/// Mode for directories and binaries
+const MODE_EXECUTABLE: u32 = 0o755;
+/// Mode for everything else (static assets)
+const MODE_FILE: u32 = 0o644;
+
+/// An entry to put in an archive
+struct ArchiveEntry {
+    /// Path on disk
+    src_path: Utf8PathBuf,
+    /// Path inside the archive (including any root prefix)
+    archive_path: Utf8PathBuf,
+    /// Whether this is a directory
+    is_dir: bool,
+    /// The normalized mode to record for it
+    mode: u32,
+}
+
+/// Zip/tarball up `src_path` to `dest_path` according to `zip_style`
+///
+/// `executables` are the paths (relative to `src_path`) of the binaries in the dir,
+/// which will be marked executable in the archive.
+pub(crate) fn zip_dir(
+    src_path: &Utf8Path,
+    dest_path: &Utf8Path,
+    zip_style: &ZipStyle,
+    with_root: Option<&Utf8Path>,
+    executables: &[Utf8PathBuf],
+) -> DistResult<()> {
+    let mtime = source_date_epoch()?;
+    let result = archive_entries(src_path, with_root, executables).and_then(|entries| {
+        match zip_style {
+            ZipStyle::Zip => write_zip(dest_path, &entries, mtime),
+            ZipStyle::Tar(compression) => write_tar(dest_path, &entries, compression, mtime),
+            ZipStyle::TempDir => {
+                // no-op
+                Ok(())
+            }
+        }
+    });
+    result.map_err(|details| DistError::Archive {
+        dest_path: dest_path.to_owned(),
+        details,
+    })
+}
+
+/// Get the timestamp that all archive entries should have
+///
+/// This is `SOURCE_DATE_EPOCH` if set, and otherwise 0 (the unix epoch).
+fn source_date_epoch() -> DistResult<u64> {
+    parse_source_date_epoch(std::env::var(SOURCE_DATE_EPOCH).ok().as_deref())
+}
+
+/// Parse the value of `SOURCE_DATE_EPOCH` (if it's set)
+///
+/// Gzip headers only have room for 32 bits, so anything bigger than that is
+/// rejected here instead of quietly wrapping around.
+fn parse_source_date_epoch(value: Option<&str>) -> DistResult<u64> {
+    let Some(value) = value else {
+        return Ok(0);
+    };
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|epoch| u32::try_from(epoch).ok())
+        .map(u64::from)
+        .ok_or_else(|| DistError::InvalidSourceDateEpoch {
+            value: value.to_owned(),
+        })
+}
+
+/// Gather up all the entries of the archive in a deterministic order
+fn archive_entries(
+    src_path: &Utf8Path,
+    with_root: Option<&Utf8Path>,
+    executables: &[Utf8PathBuf],
+) -> std::io::Result<Vec<ArchiveEntry>> {
+    let mut entries = vec![];
+
+    // If there's a root prefix, add entries for all of its components
+    if let Some(root) = with_root {
+        let mut ancestors = root
+            .ancestors()
+            .filter(|path| !path.as_str().is_empty())
+            .collect::<Vec<_>>();
+        ancestors.reverse();
+        for path in ancestors {
+            entries.push(ArchiveEntry {
+                src_path: src_path.to_owned(),
+                archive_path: path.to_owned(),
+                is_dir: true,
+                mode: MODE_EXECUTABLE,
+            });
+        }
+    }
+
+    let root = with_root.unwrap_or_else(|| Utf8Path::new(""));
+    add_dir_entries(&mut entries, src_path, Utf8Path::new(""), root, executables)?;
+    Ok(entries)
+}
+
+/// Recursively add the contents of `dir` to `entries`, sorted by name
+///
+/// `rel_dir` is the path of `dir` relative to the dir being archived, which is
+/// what `executables` are matched against.
+fn add_dir_entries(
+    entries: &mut Vec<ArchiveEntry>,
+    dir: &Utf8Path,
+    rel_dir: &Utf8Path,
+    archive_dir: &Utf8Path,
+    executables: &[Utf8PathBuf],
+) -> std::io::Result<()> {
+    let mut children = dir
+        .read_dir_utf8()?
+        .map(|entry| Ok(entry?.path().to_owned()))
+        .collect::<std::io::Result<Vec<Utf8PathBuf>>>()?;
+    children.sort();
+
+    for child in children {
+        let name = child.file_name().expect("dir entry had no name!?");
+        let rel_path = rel_dir.join(name);
+        let archive_path = archive_dir.join(name);
+        if child.is_dir() {
+            entries.push(ArchiveEntry {
+                src_path: child.clone(),
+                archive_path: archive_path.clone(),
+                is_dir: true,
+                mode: MODE_EXECUTABLE,
+            });
+            add_dir_entries(entries, &child, &rel_path, &archive_path, executables)?;
+        } else {
+            let mode = if executables.contains(&rel_path) {
+                MODE_EXECUTABLE
+            } else {
+                MODE_FILE
+            };
+            entries.push(ArchiveEntry {
+                src_path: child,
+                archive_path,
+                is_dir: false,
+                mode,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn write_tar(
+    dest_path: &Utf8Path,
+    entries: &[ArchiveEntry],
+    compression: &CompressionImpl,
+    mtime: u64,
+) -> std::io::Result<()> {
+    let final_zip_file = File::create(dest_path)?;
+    match compression {
+        CompressionImpl::Gzip => {
+            let zip_contents_name = format!("{}.tar", dest_path.file_name().unwrap());
+            let zip_output = GzBuilder::new()
+                .filename(zip_contents_name)
+                .mtime(u32::try_from(mtime).map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("{mtime} is too late to fit in a gzip header"),
+                    )
+                })?)
+                .write(final_zip_file, Compression::default());
+            let zip_output = write_tar_contents(zip_output, entries, mtime)?;
+            zip_output.finish()?;
+        }
+        CompressionImpl::Xzip => {
+            let zip_output = XzEncoder::new(final_zip_file, 9);
+            let zip_output = write_tar_contents(zip_output, entries, mtime)?;
+            zip_output.finish()?;
+        }
+        CompressionImpl::Zstd => {
+            let zip_output = zstd::Encoder::new(final_zip_file, 0)?;
+            let zip_output = write_tar_contents(zip_output, entries, mtime)?;
+            zip_output.finish()?;
+        }
+    }
+    Ok(())
+}
+
+fn write_tar_contents<W: Write>(
+    output: W,
+    entries: &[ArchiveEntry],
+    mtime: u64,
+) -> std::io::Result<W> {
+    let mut tar = tar::Builder::new(output);
+    for entry in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(entry.mode);
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("")?;
+        header.set_groupname("")?;
+        if entry.is_dir {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_size(0);
+            tar.append_data(&mut header, &entry.archive_path, std::io::empty())?;
+        } else {
+            let file = File::open(&entry.src_path)?;
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_size(file.metadata()?.len());
+            tar.append_data(&mut header, &entry.archive_path, file)?;
+        }
+    }
+    tar.into_inner()
+}
+
+fn write_zip(dest_path: &Utf8Path, entries: &[ArchiveEntry], mtime: u64) -> std::io::Result<()> {
+    use zip::{write::FileOptions, CompressionMethod};
+
+    let file = File::create(dest_path)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::STORE)
+        .last_modified_time(zip_datetime(mtime));
+
+    for entry in entries {
+        let options = options.unix_permissions(entry.mode);
+        // Write file or directory explicitly
+        // Some unzip tools unzip files with directory paths correctly, some do not!
+        if entry.is_dir {
+            zip.add_directory(entry.archive_path.as_str(), options)?;
+        } else {
+            zip.start_file(entry.archive_path.as_str(), options)?;
+            let mut file = File::open(&entry.src_path)?;
+            std::io::copy(&mut file, &mut zip)?;
+        }
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Convert a unix timestamp to a zip (MS-DOS) timestamp
+///
+/// MS-DOS timestamps can't represent anything before 1980, so earlier times
+/// (including the default of 0) are clamped to 1980-01-01 00:00:00.
+fn zip_datetime(timestamp: u64) -> zip::DateTime {
+    let days = (timestamp / 86400) as i64;
+    let secs_of_day = timestamp % 86400;
+
+    // Convert days-since-epoch to a civil date
+    // (see http://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let hour = (secs_of_day / 3600) as u8;
+    let minute = (secs_of_day % 3600 / 60) as u8;
+    let second = (secs_of_day % 60) as u8;
+
+    u16::try_from(year)
+        .ok()
+        .and_then(|year| {
+            zip::DateTime::from_date_and_time(year, month, day, hour, minute, second).ok()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::ChecksumStyle;
+
+    /// Make a scratch dir with some contents, creating the files in the given order
+    fn make_src_dir(name: &str, file_order: &[&str]) -> Utf8PathBuf {
+        let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("cargo-dist-archive-{}-{name}", std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        for file in file_order {
+            std::fs::write(dir.join(file), format!("contents of {file}\n")).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn archives_are_reproducible() {
+        let files = ["axolotlsay", "README.md", "docs/LICENSE-MIT"];
+        let mut reversed = files;
+        reversed.reverse();
+        let executables = [Utf8PathBuf::from("axolotlsay")];
+
+        let styles = [
+            ZipStyle::Zip,
+            ZipStyle::Tar(CompressionImpl::Gzip),
+            ZipStyle::Tar(CompressionImpl::Xzip),
+            ZipStyle::Tar(CompressionImpl::Zstd),
+        ];
+        let src1 = make_src_dir("src1", &files);
+        // Make sure the mtimes of the two copies differ
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        let src2 = make_src_dir("src2", &reversed);
+
+        for style in styles {
+            // Some formats record their own file name, so both runs need to use the same one
+            let with_root = Utf8PathBuf::from("axolotlsay-x86_64-unknown-linux-gnu");
+            let dest = src1.with_extension(style.ext());
+
+            zip_dir(&src1, &dest, &style, Some(&with_root), &executables).unwrap();
+            let sum1 = crate::generate_checksum(&ChecksumStyle::Sha256, &dest).unwrap();
+            zip_dir(&src2, &dest, &style, Some(&with_root), &executables).unwrap();
+            let sum2 = crate::generate_checksum(&ChecksumStyle::Sha256, &dest).unwrap();
+            assert_eq!(sum1, sum2, "{} archives weren't reproducible", style.ext());
+
+            std::fs::remove_file(dest).unwrap();
+        }
+
+        std::fs::remove_dir_all(src1).unwrap();
+        std::fs::remove_dir_all(src2).unwrap();
+    }
+
+    #[test]
+    fn tarballs_mark_binaries_executable() {
+        let src = make_src_dir("modes", &["axolotlsay", "README.md", "docs/LICENSE-MIT"]);
+        let with_root = Utf8PathBuf::from("axolotlsay-x86_64-unknown-linux-gnu");
+        let style = ZipStyle::Tar(CompressionImpl::Gzip);
+        let dest = src.with_extension(style.ext());
+        let executables = [Utf8PathBuf::from("axolotlsay")];
+        zip_dir(&src, &dest, &style, Some(&with_root), &executables).unwrap();
+
+        let tarball = flate2::read::GzDecoder::new(File::open(&dest).unwrap());
+        let mut modes = std::collections::BTreeMap::new();
+        for entry in tar::Archive::new(tarball).entries().unwrap() {
+            let entry = entry.unwrap();
+            let path = Utf8PathBuf::from_path_buf(entry.path().unwrap().into_owned()).unwrap();
+            modes.insert(path, entry.header().mode().unwrap());
+        }
+        assert_eq!(modes[&with_root.join("axolotlsay")], 0o755);
+        assert_eq!(modes[&with_root.join("README.md")], 0o644);
+        assert_eq!(modes[&with_root.join("docs/LICENSE-MIT")], 0o644);
+        assert_eq!(modes[&with_root.join("docs")], 0o755);
+
+        std::fs::remove_file(dest).unwrap();
+        std::fs::remove_dir_all(src).unwrap();
+    }
+
+    #[test]
+    fn source_date_epochs() {
+        assert_eq!(parse_source_date_epoch(None).unwrap(), 0);
+        assert_eq!(
+            parse_source_date_epoch(Some(" 1695904496\n")).unwrap(),
+            1695904496
+        );
+        for invalid in ["", "yesterday", "-1", "4294967296"] {
+            let err = parse_source_date_epoch(Some(invalid)).unwrap_err();
+            assert!(
+                matches!(err, DistError::InvalidSourceDateEpoch { ref value } if value == invalid),
+                "{err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn gzip_rejects_mtimes_past_2106() {
+        let src = make_src_dir("late", &["axolotlsay"]);
+        let dest = src.with_extension("tar.gz");
+        let entries = archive_entries(&src, None, &[]).unwrap();
+        let err = write_tar(&dest, &entries, &CompressionImpl::Gzip, 1 << 32).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+        let _ = std::fs::remove_file(dest);
+        std::fs::remove_dir_all(src).unwrap();
+    }
+
+    #[test]
+    fn zip_datetime_from_epoch() {
+        fn parts(time: zip::DateTime) -> (u16, u8, u8, u8, u8, u8) {
+            (
+                time.year(),
+                time.month(),
+                time.day(),
+                time.hour(),
+                time.minute(),
+                time.second(),
+            )
+        }
+        // Clamped to the earliest time a zip can represent
+        assert_eq!(parts(zip_datetime(0)), (1980, 1, 1, 0, 0, 0));
+        // 2023-09-28 12:34:56 UTC
+        assert_eq!(parts(zip_datetime(1695904496)), (2023, 9, 28, 12, 34, 56));
+    }
+}
@@ -0,0 +1,164 @@
+//! Code for writing `[package.metadata.binstall]`
+//!
+//! cargo-binstall reads this section of a crate's Cargo.toml to find prebuilt
+//! archives, so pointing it at our archives lets `cargo binstall my-app` fetch
+//! them straight from the release.
+
+use camino::Utf8PathBuf;
+
+use crate::{
+    backend::{diff_files, write_if_changed},
+    config::{self, CompressionImpl, ZipStyle},
+    errors::DistResult,
+    SortedMap, TargetTriple,
+};
+
+/// Info needed to write `[package.metadata.binstall]` for a release
+#[derive(Debug, Clone)]
+pub struct BinstallInfo {
+    /// The Cargo.toml of the package
+    pub manifest_path: Utf8PathBuf,
+    /// How to fetch the archives of most targets
+    pub format: BinstallFormat,
+    /// How to fetch the archives of targets that use a different kind of archive (windows)
+    pub overrides: SortedMap<TargetTriple, BinstallFormat>,
+}
+
+/// The fields binstall uses to find and unpack an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinstallFormat {
+    /// URL template of the archive (e.g. "{ repo }/{ name }-{ target }.tar.xz")
+    pub pkg_url: String,
+    /// binstall's name for the kind of archive (e.g. "txz")
+    pub pkg_fmt: String,
+    /// Path template of the binaries within the archive
+    pub bin_dir: String,
+}
+
+impl BinstallFormat {
+    /// Compute the format for archives with the given zip style
+    ///
+    /// `archive_prefix` is the URL template of the archive without its extension,
+    /// while `dir_name` is the template of the dir tarballs nest their contents in.
+    pub fn new(archive_prefix: &str, dir_name: &str, zip_style: ZipStyle) -> Option<Self> {
+        let pkg_fmt = binstall_pkg_fmt(zip_style)?;
+        let bin_dir = if let ZipStyle::Zip = zip_style {
+            "{ bin }{ binary-ext }".to_owned()
+        } else {
+            format!("{dir_name}/{{ bin }}{{ binary-ext }}")
+        };
+        Some(Self {
+            // Our extensions don't always agree with binstall's ("{ archive-suffix }"),
+            // so spell them out
+            pkg_url: format!("{archive_prefix}{}", zip_style.ext()),
+            pkg_fmt: pkg_fmt.to_owned(),
+            bin_dir,
+        })
+    }
+
+    fn apply(&self, table: &mut toml_edit::Table) {
+        table["pkg-url"] = toml_edit::value(&self.pkg_url);
+        table["pkg-fmt"] = toml_edit::value(&self.pkg_fmt);
+        table["bin-dir"] = toml_edit::value(&self.bin_dir);
+    }
+}
+
+impl BinstallInfo {
+    /// Update the package's Cargo.toml in place, returning its new contents
+    fn render(&self) -> DistResult<String> {
+        let mut toml = config::load_cargo_toml(&self.manifest_path)?;
+        let metadata = config::get_toml_metadata(&mut toml, false);
+        let binstall = metadata["binstall"].or_insert(toml_edit::table());
+        if let Some(table) = binstall.as_table_mut() {
+            self.format.apply(table);
+            if self.overrides.is_empty() {
+                table.remove("overrides");
+            } else {
+                let overrides = table["overrides"].or_insert(toml_edit::table());
+                if let Some(overrides) = overrides.as_table_mut() {
+                    overrides.set_implicit(true);
+                    overrides.retain(|target, _| self.overrides.contains_key(target));
+                    for (target, format) in &self.overrides {
+                        if let Some(table) = overrides[target]
+                            .or_insert(toml_edit::table())
+                            .as_table_mut()
+                        {
+                            format.apply(table);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(toml.to_string())
+    }
+
+    /// Write the metadata to the package's Cargo.toml
+    pub fn write_to_disk(&self) -> DistResult<()> {
+        let contents = self.render()?;
+        write_if_changed(&self.manifest_path, &contents)?;
+        Ok(())
+    }
+
+    /// Check that the package's Cargo.toml already has this metadata
+    pub fn check(&self) -> DistResult<()> {
+        let contents = self.render()?;
+        diff_files(&self.manifest_path, &contents)
+    }
+}
+
+/// Get binstall's name for a kind of archive (if it has one)
+pub fn binstall_pkg_fmt(zip_style: ZipStyle) -> Option<&'static str> {
+    let fmt = match zip_style {
+        ZipStyle::Zip => "zip",
+        ZipStyle::Tar(CompressionImpl::Gzip) => "tgz",
+        ZipStyle::Tar(CompressionImpl::Xzip) => "txz",
+        ZipStyle::Tar(CompressionImpl::Zstd) => "tzstd",
+        ZipStyle::TempDir => return None,
+    };
+    Some(fmt)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn writes_and_checks_manifest() {
+        let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("cargo-dist-binstall-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest_path = dir.join("Cargo.toml");
+        std::fs::write(
+            &manifest_path,
+            "[package]\nname = \"axolotlsay\"\nversion = \"0.1.0\"\n\n[dependencies]\nclap = \"4\"\n",
+        )
+        .unwrap();
+        let prefix = "https://github.com/axodotdev/axolotlsay/releases/download/v{ version }/{ name }-{ target }";
+        let dir_name = "{ name }-{ target }";
+        let info = BinstallInfo {
+            manifest_path: manifest_path.clone(),
+            format: BinstallFormat::new(prefix, dir_name, ZipStyle::Tar(CompressionImpl::Gzip))
+                .unwrap(),
+            overrides: [(
+                "x86_64-pc-windows-msvc".to_owned(),
+                BinstallFormat::new(prefix, dir_name, ZipStyle::Zip).unwrap(),
+            )]
+            .into_iter()
+            .collect(),
+        };
+        assert!(info.check().is_err());
+        info.write_to_disk().unwrap();
+        info.check().unwrap();
+
+        let toml = std::fs::read_to_string(&manifest_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(toml.contains("[dependencies]\nclap = \"4\"\n"), "{toml}");
+        assert!(toml.contains(&format!(
+            "[package.metadata.binstall]\npkg-url = \"{prefix}.tar.gz\"\npkg-fmt = \"tgz\"\nbin-dir = \"{dir_name}/{{ bin }}{{ binary-ext }}\"\n"
+        )), "{toml}");
+        assert!(toml.contains(&format!(
+            "[package.metadata.binstall.overrides.x86_64-pc-windows-msvc]\npkg-url = \"{prefix}.zip\"\npkg-fmt = \"zip\"\nbin-dir = \"{{ bin }}{{ binary-ext }}\"\n"
+        )), "{toml}");
+    }
+}
@@ -0,0 +1,122 @@
+//! Gitea (and Forgejo) Actions script generation
+//!
+//! Gitea Actions are mostly compatible with Github Actions, so this reuses the
+//! plan/upload/announce structure (and task matrix) of the Github backend, and only
+//! swaps out the parts that talk to the forge.
+
+use cargo_dist_schema::{GithubMatrix, GithubMatrixEntry, PrRunMode};
+use serde::Serialize;
+
+use crate::{
+    backend::{diff_files, templates::TEMPLATE_CI_GITEA, write_if_changed},
+    errors::DistResult,
+    DistGraph,
+};
+
+const GITEA_CI_DIR: &str = ".gitea/workflows/";
+const GITEA_CI_FILE: &str = "release.yml";
+
+/// Info about running cargo-dist in Gitea Actions
+#[derive(Debug, Serialize)]
+pub struct GiteaCiInfo {
+    /// Version of rust toolchain to install (deprecated)
+    pub rust_version: Option<String>,
+    /// expression to use for installing cargo-dist via shell script
+    pub install_dist_sh: String,
+    /// expression to use for installing cargo-dist via powershell script
+    pub install_dist_ps1: String,
+    /// Whether to fail-fast
+    pub fail_fast: bool,
+    /// The glob of git tags that should trigger a release
+    pub tag_glob: String,
+    /// Matrix for upload-local-artifacts
+    pub artifacts_matrix: GithubMatrix,
+    /// What kind of job to run on pull request
+    pub pr_run_mode: PrRunMode,
+    /// global task
+    pub global_task: Option<GithubMatrixEntry>,
+    /// whether to create the release or assume an existing one
+    pub create_release: bool,
+    /// The name of the dir in target/ that cargo-dist builds in
+    pub dist_subdir: String,
+    /// Whether the plan job needs the full git history (to generate release notes)
+    pub fetch_history: bool,
+}
+
+impl GiteaCiInfo {
+    /// Compute the Gitea CI stuff
+    pub fn new(dist: &DistGraph) -> GiteaCiInfo {
+        // Legacy deprecated support
+        let rust_version = dist.desired_rust_toolchain.clone();
+
+        // If they don't specify a cargo-dist version, use this one
+        let self_dist_version = super::SELF_DIST_VERSION.parse().unwrap();
+        let dist_version = dist
+            .desired_cargo_dist_version
+            .as_ref()
+            .unwrap_or(&self_dist_version);
+        let dist_subdir = dist
+            .dist_dir
+            .file_name()
+            .expect("dist dir had no name!?")
+            .to_owned();
+
+        // Get the platform-specific installation methods
+        let install_dist_sh = super::install_dist_sh_for_version(dist_version);
+        let install_dist_ps1 = super::install_dist_ps1_for_version(dist_version);
+
+        let (global_task, artifacts_matrix) =
+            super::github::artifacts_matrix(dist, &install_dist_sh, &install_dist_ps1);
+
+        GiteaCiInfo {
+            rust_version,
+            install_dist_sh,
+            install_dist_ps1,
+            fail_fast: dist.fail_fast,
+            tag_glob: dist.tag_glob(),
+            artifacts_matrix,
+            pr_run_mode: dist.pr_run_mode,
+            global_task,
+            create_release: dist.create_release,
+            dist_subdir,
+            fetch_history: dist.needs_git_history(),
+        }
+    }
+
+    fn gitea_ci_path(&self, dist: &DistGraph) -> camino::Utf8PathBuf {
+        let ci_dir = dist.workspace_dir.join(GITEA_CI_DIR);
+        ci_dir.join(GITEA_CI_FILE)
+    }
+
+    /// Generate the requested configuration and returns it as a string.
+    pub fn generate_gitea_ci(&self, dist: &DistGraph) -> DistResult<String> {
+        let rendered = dist
+            .templates
+            .render_file_to_clean_string(TEMPLATE_CI_GITEA, self)?;
+
+        Ok(rendered)
+    }
+
+    /// Write release.yml to disk
+    pub fn write_to_disk(&self, dist: &DistGraph) -> Result<(), miette::Report> {
+        let ci_file = self.gitea_ci_path(dist);
+        let rendered = self.generate_gitea_ci(dist)?;
+
+        if write_if_changed(&ci_file, &rendered)? {
+            eprintln!("generated Gitea CI to {}", ci_file);
+        } else {
+            eprintln!("Gitea CI at {} is already up to date", ci_file);
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the new configuration differs from the config on disk
+    /// writhout actually writing the result.
+    pub fn check(&self, dist: &DistGraph) -> DistResult<()> {
+        let ci_file = self.gitea_ci_path(dist);
+
+        let rendered = self.generate_gitea_ci(dist)?;
+        diff_files(&ci_file, &rendered)
+    }
+}
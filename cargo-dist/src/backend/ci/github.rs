@@ -2,20 +2,25 @@
 //!
 //! In the future this may get split up into submodules.
 
-use axoasset::LocalAsset;
 use cargo_dist_schema::{GithubMatrix, GithubMatrixEntry};
 use serde::Serialize;
 use tracing::warn;
 
 use crate::{
-    backend::{diff_files, templates::TEMPLATE_CI_GITHUB},
-    config::{DependencyKind, ProductionMode, SystemDependencies},
+    backend::installer::InstallerImpl,
+    backend::{diff_files, templates::TEMPLATE_CI_GITHUB, write_if_changed},
+    config::{
+        CiCacheStyle, DependencyKind, ExtraPublishJob, ProductionMode, PublishStyle,
+        SystemDependencies, SystemDependency,
+    },
     errors::DistResult,
-    DistGraph, SortedMap, SortedSet, TargetTriple,
+    ArtifactKind, DistGraph, SortedMap, SortedSet, TargetTriple,
 };
 
 const GITHUB_CI_DIR: &str = ".github/workflows/";
 const GITHUB_CI_FILE: &str = "release.yml";
+/// The repository WinGet manifests get submitted to
+const WINGET_PKGS_REPO: &str = "microsoft/winget-pkgs";
 
 /// Info about running cargo-dist in Github CI
 #[derive(Debug, Serialize)]
@@ -28,6 +33,8 @@ pub struct GithubCiInfo {
     pub install_dist_ps1: String,
     /// Whether to fail-fast
     pub fail_fast: bool,
+    /// The glob of git tags that should trigger a release
+    pub tag_glob: String,
     /// Matrix for upload-local-artifacts
     pub artifacts_matrix: cargo_dist_schema::GithubMatrix,
     /// What kind of job to run on pull request
@@ -36,14 +43,151 @@ pub struct GithubCiInfo {
     pub global_task: Option<GithubMatrixEntry>,
     /// homebrew tap
     pub tap: Option<String>,
+    /// scoop bucket
+    pub bucket: Option<String>,
+    /// Where to upload debug symbols (if there are any to upload)
+    pub sentry: Option<GithubSentryInfo>,
+    /// Where to upload snaps (if there are any to upload)
+    pub snap: Option<GithubSnapInfo>,
+    /// The Docker image to build and push (if there are linux archives to put in it)
+    pub docker: Option<GithubDockerInfo>,
+    /// The OCI repository to push the release's artifacts to (if requested)
+    pub oci: Option<GithubOciInfo>,
+    /// Publish jobs that open pull requests with updated manifests
+    pub pr_publish_jobs: Vec<GithubPrPublishInfo>,
     /// publish jobs
     pub publish_jobs: Vec<String>,
     /// user-specified publish jobs
     pub user_publish_jobs: Vec<String>,
+    /// publish jobs defined in the config
+    pub extra_publish_jobs: Vec<ExtraPublishJob>,
     /// whether to create the release or assume an existing one
     pub create_release: bool,
     /// \[unstable\] whether to add ssl.com windows binary signing
     pub ssldotcom_windows_sign: Option<ProductionMode>,
+    /// The name of the dir in target/ that cargo-dist builds in
+    pub dist_subdir: String,
+    /// Whether to cache the builds with Swatinem/rust-cache
+    pub rust_cache: bool,
+    /// How to cache the builds with sccache (if enabled)
+    pub sccache: Option<GithubSccacheInfo>,
+    /// Whether the linux build jobs need cross installed
+    pub install_cross: bool,
+    /// Whether the plan job needs the full git history (to generate release notes)
+    pub fetch_history: bool,
+}
+
+/// Details for caching builds with sccache
+#[derive(Debug, Serialize)]
+pub struct GithubSccacheInfo {
+    /// Environment variables to set for the build jobs
+    ///
+    /// This includes RUSTC_WRAPPER, and whatever the backend needs.
+    pub env: Vec<GithubEnvVar>,
+}
+
+/// An environment variable to set in a job
+#[derive(Debug, Serialize)]
+pub struct GithubEnvVar {
+    /// The name of the variable
+    pub name: String,
+    /// The value (which may be a github expression like `${{ secrets.FOO }}`)
+    pub value: String,
+}
+
+/// Details for a publish job that opens a pull request against some repository
+///
+/// The pull request is opened from a fork of the repository owned by whoever
+/// the token belongs to, so this works for community repositories too.
+#[derive(Debug, Serialize)]
+pub struct GithubPrPublishInfo {
+    /// The name of the job (the CI job will be `publish-<name>`)
+    pub name: String,
+    /// The repository to open the pull request against (owner/name)
+    pub repo: String,
+    /// The name of the user's fork of the repository
+    pub fork_name: String,
+    /// The secret containing a token that can make the fork and open the pull request
+    pub token_secret: String,
+    /// The manifests to add to the repository
+    pub files: Vec<GithubPrFile>,
+}
+
+/// A manifest that should be added to a repository by a pull request
+#[derive(Debug, Serialize)]
+pub struct GithubPrFile {
+    /// The app the manifest is for
+    pub app_name: String,
+    /// The name of the artifact containing the manifest
+    pub artifact: String,
+    /// The path to put it at in the repository (may refer to `${version}`)
+    pub dest_path: String,
+}
+
+/// Details for uploading debug symbols to Sentry
+#[derive(Debug, Serialize)]
+pub struct GithubSentryInfo {
+    /// The Sentry organization
+    pub org: String,
+    /// The Sentry project
+    pub project: String,
+}
+
+/// Details for building and pushing a Docker image
+#[derive(Debug, Serialize)]
+pub struct GithubDockerInfo {
+    /// The registry to log in to
+    pub registry: String,
+    /// The full name of the image, including the registry (but no tag)
+    pub image: String,
+    /// The image to base ours on
+    pub base_image: String,
+    /// The platforms to build the image for
+    pub platforms: Vec<GithubDockerPlatform>,
+    /// The comma-separated list of platforms, for buildx
+    pub platform_list: String,
+}
+
+/// The binaries to put in a Docker image for one platform
+#[derive(Debug, Serialize)]
+pub struct GithubDockerPlatform {
+    /// The docker name for the platform (e.g. "linux/amd64")
+    pub platform: String,
+    /// The archive the binaries come from
+    pub archive: String,
+    /// The paths of the binaries within the archive
+    pub executables: Vec<String>,
+}
+
+/// Details for pushing the release's artifacts to a registry as an OCI artifact
+#[derive(Debug, Serialize)]
+pub struct GithubOciInfo {
+    /// The registry to log in to
+    pub registry: String,
+    /// The repository to push to, including the registry (but no tag)
+    pub reference: String,
+    /// The files to push
+    pub files: Vec<GithubOciFile>,
+}
+
+/// A file to push as a layer of an OCI artifact
+#[derive(Debug, Serialize)]
+pub struct GithubOciFile {
+    /// The name of the artifact
+    pub name: String,
+    /// The media type to give the layer
+    pub media_type: String,
+    /// The annotation to record the checksum of the artifact under (if it has one)
+    pub checksum_annotation: Option<String>,
+    /// The file the checksum of the artifact can be read from (if it has one)
+    pub checksum_file: Option<String>,
+}
+
+/// Details for uploading snaps to the Snap Store
+#[derive(Debug, Serialize)]
+pub struct GithubSnapInfo {
+    /// The channel to release the snaps to
+    pub channel: String,
 }
 
 impl GithubCiInfo {
@@ -59,83 +203,118 @@ impl GithubCiInfo {
             .as_ref()
             .unwrap_or(&self_dist_version);
         let fail_fast = dist.fail_fast;
+        let tag_glob = dist.tag_glob();
         let create_release = dist.create_release;
         let ssldotcom_windows_sign = dist.ssldotcom_windows_sign.clone();
-        let mut dependencies = SystemDependencies::default();
-
-        // Figure out what builds we need to do
-        let mut needs_global_build = false;
-        let mut local_targets = SortedSet::new();
-        for release in &dist.releases {
-            if !release.global_artifacts.is_empty() {
-                needs_global_build = true;
-            }
-            local_targets.extend(release.targets.iter());
-            dependencies.append(&mut release.system_dependencies.clone());
-        }
+        let dist_subdir = dist
+            .dist_dir
+            .file_name()
+            .expect("dist dir had no name!?")
+            .to_owned();
 
         // Get the platform-specific installation methods
         let install_dist_sh = super::install_dist_sh_for_version(dist_version);
         let install_dist_ps1 = super::install_dist_ps1_for_version(dist_version);
 
-        // Build up the task matrix for building Artifacts
-        let mut tasks = vec![];
-
-        // If we have Global Artifacts, we need one task for that. If we've done a Good Job
-        // then these artifacts should be possible to build on *any* platform. Linux is usually
-        // fast/cheap, so that's a reasonable choice.s
-        let global_task = if needs_global_build {
-            Some(GithubMatrixEntry {
-                runner: Some(GITHUB_LINUX_RUNNER.into()),
-                dist_args: Some("--artifacts=global".into()),
-                install_dist: Some(install_dist_sh.clone()),
-                packages_install: None,
-            })
-        } else {
-            None
-        };
+        let (global_task, artifacts_matrix) =
+            artifacts_matrix(dist, &install_dist_sh, &install_dist_ps1);
 
         let pr_run_mode = dist.pr_run_mode;
 
         let tap = dist.tap.clone();
+        let bucket = dist.bucket.clone();
+        // Only bother uploading to Sentry if we'll actually produce some symbols
+        let has_symbols = dist
+            .artifacts
+            .iter()
+            .any(|artifact| matches!(artifact.kind, ArtifactKind::Symbols(_)));
+        let sentry = match (&dist.sentry_org, &dist.sentry_project) {
+            (Some(org), Some(project))
+                if has_symbols && dist.publish_jobs.contains(&PublishStyle::Sentry) =>
+            {
+                Some(GithubSentryInfo {
+                    org: org.clone(),
+                    project: project.clone(),
+                })
+            }
+            _ => None,
+        };
+        // Likewise only bother with the Snap Store if we'll produce some snaps
+        let has_snaps = dist.artifacts.iter().any(|artifact| {
+            matches!(
+                artifact.kind,
+                ArtifactKind::Installer(InstallerImpl::Snap(_))
+            )
+        });
+        let snap = (has_snaps && dist.publish_jobs.contains(&PublishStyle::Snap)).then(|| {
+            GithubSnapInfo {
+                channel: dist.snap_channel.clone(),
+            }
+        });
+        let docker = if dist.publish_jobs.contains(&PublishStyle::Docker) {
+            docker_info(dist)
+        } else {
+            None
+        };
+        let oci = if dist.publish_jobs.contains(&PublishStyle::Oci) {
+            oci_info(dist)
+        } else {
+            None
+        };
+        let pr_publish_jobs = pr_publish_jobs(dist);
         let publish_jobs = dist.publish_jobs.iter().map(|j| j.to_string()).collect();
         let user_publish_jobs = dist.user_publish_jobs.clone();
+        let extra_publish_jobs = dist.extra_publish_jobs.clone();
+
+        // Only the jobs that run cargo builds need caching. sccache is set up with just
+        // env-vars so that it doesn't get involved in the RUSTFLAGS cargo-dist computes.
+        let rust_cache = dist.ci_cache == CiCacheStyle::RustCache;
+        let sccache = (dist.ci_cache == CiCacheStyle::Sccache).then(|| {
+            let wrapper = ("RUSTC_WRAPPER", "sccache");
+            let env = std::iter::once(wrapper)
+                .chain(dist.sccache_backend.github_env())
+                .map(|(name, value)| GithubEnvVar {
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                })
+                .collect();
+            GithubSccacheInfo { env }
+        });
 
-        // Figure out what Local Artifact tasks we need
-        let local_runs = if dist.merge_tasks {
-            distribute_targets_to_runners_merged(local_targets)
-        } else {
-            distribute_targets_to_runners_split(local_targets)
-        };
-        for (runner, targets) in local_runs {
-            use std::fmt::Write;
-            let install_dist =
-                install_dist_for_github_runner(runner, &install_dist_sh, &install_dist_ps1);
-            let mut dist_args = String::from("--artifacts=local");
-            for target in &targets {
-                write!(dist_args, " --target={target}").unwrap();
-            }
-            tasks.push(GithubMatrixEntry {
-                runner: Some(runner.to_owned()),
-                dist_args: Some(dist_args),
-                install_dist: Some(install_dist.to_owned()),
-                packages_install: package_install_for_targets(&targets, &dependencies),
+        let install_cross = dist.use_cross
+            && dist.releases.iter().any(|release| {
+                release
+                    .targets
+                    .iter()
+                    .any(|target| crate::tasks::uses_cross("x86_64-unknown-linux-gnu", target))
             });
-        }
 
         GithubCiInfo {
             rust_version,
             install_dist_sh,
             install_dist_ps1,
             fail_fast,
+            tag_glob,
             tap,
+            bucket,
+            sentry,
+            snap,
+            docker,
+            oci,
+            pr_publish_jobs,
             publish_jobs,
             user_publish_jobs,
-            artifacts_matrix: GithubMatrix { include: tasks },
+            extra_publish_jobs,
+            artifacts_matrix,
             pr_run_mode,
             global_task,
             create_release,
             ssldotcom_windows_sign,
+            dist_subdir,
+            rust_cache,
+            sccache,
+            install_cross,
+            fetch_history: dist.needs_git_history(),
         }
     }
 
@@ -158,8 +337,11 @@ impl GithubCiInfo {
         let ci_file = self.github_ci_path(dist);
         let rendered = self.generate_github_ci(dist)?;
 
-        LocalAsset::write_new_all(&rendered, &ci_file)?;
-        eprintln!("generated Github CI to {}", ci_file);
+        if write_if_changed(&ci_file, &rendered)? {
+            eprintln!("generated Github CI to {}", ci_file);
+        } else {
+            eprintln!("Github CI at {} is already up to date", ci_file);
+        }
 
         Ok(())
     }
@@ -174,6 +356,284 @@ impl GithubCiInfo {
     }
 }
 
+/// Compute the tasks that build artifacts: the global task (if there are any global
+/// artifacts), and the matrix of tasks that build local artifacts
+///
+/// Backends for other forges that run Github-style workflows use this too.
+pub(crate) fn artifacts_matrix(
+    dist: &DistGraph,
+    install_dist_sh: &str,
+    install_dist_ps1: &str,
+) -> (Option<GithubMatrixEntry>, GithubMatrix) {
+    let mut dependencies = SystemDependencies::default();
+
+    // Figure out what builds we need to do
+    let mut needs_global_build = false;
+    let mut local_targets = SortedSet::new();
+    for release in &dist.releases {
+        if !release.global_artifacts.is_empty() {
+            needs_global_build = true;
+        }
+        local_targets.extend(release.targets.iter());
+        dependencies.append(&mut release.system_dependencies.clone());
+    }
+
+    // If we have Global Artifacts, we need one task for that. If we've done a Good Job
+    // then these artifacts should be possible to build on *any* platform. Linux is usually
+    // fast/cheap, so that's a reasonable choice.s
+    let global_task = if needs_global_build {
+        Some(GithubMatrixEntry {
+            runner: Some(GITHUB_LINUX_RUNNER.into()),
+            dist_args: Some("--artifacts=global".into()),
+            install_dist: Some(install_dist_sh.to_owned()),
+            packages_install: None,
+        })
+    } else {
+        None
+    };
+
+    // Build up the task matrix for building Artifacts
+    let mut tasks = vec![];
+
+    // Figure out what Local Artifact tasks we need
+    let custom_runners = &dist.github_custom_runners;
+    let local_runs = if dist.merge_tasks {
+        distribute_targets_to_runners_merged(local_targets, custom_runners)
+    } else {
+        distribute_targets_to_runners_split(local_targets, custom_runners)
+    };
+    for (runner, targets) in local_runs {
+        use std::fmt::Write;
+        let install_dist = install_dist_for_targets(&targets, install_dist_sh, install_dist_ps1);
+        let mut dist_args = String::from("--artifacts=local");
+        for target in &targets {
+            write!(dist_args, " --target={target}").unwrap();
+        }
+        tasks.push(GithubMatrixEntry {
+            runner: Some(runner.to_owned()),
+            dist_args: Some(dist_args),
+            install_dist: Some(install_dist.to_owned()),
+            packages_install: package_install_for_targets(
+                &targets,
+                &dependencies,
+                GITHUB_APT_INSTALL,
+            ),
+        });
+    }
+
+    (global_task, GithubMatrix { include: tasks })
+}
+
+/// Compute the Docker image to build from the linux archives we'll make
+///
+/// The image holds one archive per platform, so we prefer gnu over musl builds,
+/// and full archives over slim ones. If there aren't any linux archives there's no
+/// image to build.
+fn docker_info(dist: &DistGraph) -> Option<GithubDockerInfo> {
+    let registry = dist.docker_registry.clone()?;
+    let image = dist.docker_image.as_deref()?;
+    let mut candidates = SortedMap::<&str, ((bool, bool), GithubDockerPlatform)>::new();
+    for artifact in &dist.artifacts {
+        let (ArtifactKind::ExecutableZip(_), Some(archive), [target]) = (
+            &artifact.kind,
+            &artifact.archive,
+            &artifact.target_triples[..],
+        ) else {
+            continue;
+        };
+        if !target.contains("linux") {
+            continue;
+        }
+        let platform = match target.split('-').next() {
+            Some("x86_64") => "linux/amd64",
+            Some("aarch64") => "linux/arm64",
+            _ => continue,
+        };
+        let rank = (target.contains("musl"), archive.strip_binaries);
+        if let Some((best, _)) = candidates.get(platform) {
+            if *best <= rank {
+                continue;
+            }
+        }
+        let mut executables = artifact
+            .required_binaries
+            .values()
+            .filter_map(|dest_path| dest_path.strip_prefix(&archive.dir_path).ok())
+            .map(|path| match &archive.with_root {
+                Some(root) => root.join(path).to_string(),
+                None => path.to_string(),
+            })
+            .collect::<Vec<_>>();
+        executables.sort();
+        let entry = GithubDockerPlatform {
+            platform: platform.to_owned(),
+            archive: artifact.id.clone(),
+            executables,
+        };
+        candidates.insert(platform, (rank, entry));
+    }
+    if candidates.is_empty() {
+        return None;
+    }
+    let platforms = candidates
+        .into_values()
+        .map(|(_, platform)| platform)
+        .collect::<Vec<_>>();
+    let platform_list = platforms
+        .iter()
+        .map(|platform| platform.platform.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    Some(GithubDockerInfo {
+        image: format!("{registry}/{image}"),
+        registry,
+        base_image: dist.docker_base_image.clone(),
+        platforms,
+        platform_list,
+    })
+}
+
+/// Compute the files to push as an OCI artifact
+///
+/// This is every artifact of the release except for checksums, which instead get
+/// recorded as annotations on the files they checksum.
+fn oci_info(dist: &DistGraph) -> Option<GithubOciInfo> {
+    let reference = dist.oci_registry.clone()?;
+    let registry = reference.split('/').next().unwrap_or(&reference).to_owned();
+    let mut files = vec![];
+    for artifact in &dist.artifacts {
+        if matches!(artifact.kind, ArtifactKind::Checksum(_)) {
+            continue;
+        }
+        let (checksum_annotation, checksum_file) = match artifact.checksum {
+            Some(checksum_idx) => {
+                let checksum = dist.artifact(checksum_idx);
+                let ArtifactKind::Checksum(checksum_impl) = &checksum.kind else {
+                    unreachable!("artifact checksum wasn't a checksum");
+                };
+                (
+                    Some(format!(
+                        "dev.cargo-dist.checksum.{}",
+                        checksum_impl.checksum.ext()
+                    )),
+                    Some(checksum.id.clone()),
+                )
+            }
+            None => (None, None),
+        };
+        files.push(GithubOciFile {
+            media_type: oci_media_type(&artifact.id).to_owned(),
+            name: artifact.id.clone(),
+            checksum_annotation,
+            checksum_file,
+        });
+    }
+    if files.is_empty() {
+        return None;
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(GithubOciInfo {
+        registry,
+        reference,
+        files,
+    })
+}
+
+/// Get the media type to give an artifact with the given file name
+fn oci_media_type(name: &str) -> &'static str {
+    const MEDIA_TYPES: &[(&str, &str)] = &[
+        (".tar.gz", "application/gzip"),
+        (".tar.xz", "application/x-xz"),
+        (".tar.zst", "application/zstd"),
+        (".zip", "application/zip"),
+        (".sh", "application/x-sh"),
+        (".ps1", "text/plain"),
+        (".rb", "text/x-ruby"),
+        (".json", "application/json"),
+        (".msi", "application/x-msi"),
+        (".md", "text/markdown"),
+    ];
+    MEDIA_TYPES
+        .iter()
+        .find(|(ext, _)| name.ends_with(ext))
+        .map(|(_, media_type)| *media_type)
+        .unwrap_or("application/octet-stream")
+}
+
+/// Compute the publish jobs that open pull requests with updated manifests
+///
+/// Each is only enabled if its publish job was requested, there's a repository to
+/// open the pull request against, and we'll actually build a manifest to put in it.
+fn pr_publish_jobs(dist: &DistGraph) -> Vec<GithubPrPublishInfo> {
+    let mut jobs = vec![];
+    if let Some(tap) = &dist.tap {
+        if dist.publish_jobs.contains(&PublishStyle::HomebrewPr) {
+            let files = dist
+                .releases
+                .iter()
+                .flat_map(|release| {
+                    release.global_artifacts.iter().filter_map(|&idx| {
+                        let artifact = dist.artifact(idx);
+                        matches!(
+                            artifact.kind,
+                            ArtifactKind::Installer(InstallerImpl::Homebrew(_))
+                        )
+                        .then(|| GithubPrFile {
+                            app_name: release.app_name.clone(),
+                            artifact: artifact.id.clone(),
+                            dest_path: format!("Formula/{}", artifact.id),
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !files.is_empty() {
+                jobs.push(GithubPrPublishInfo {
+                    name: "homebrew-pr".to_owned(),
+                    repo: tap.clone(),
+                    fork_name: fork_name(tap),
+                    token_secret: "HOMEBREW_TAP_TOKEN".to_owned(),
+                    files,
+                });
+            }
+        }
+    }
+    if dist.publish_jobs.contains(&PublishStyle::WinGet) {
+        let files = dist
+            .releases
+            .iter()
+            .flat_map(|release| {
+                release.global_artifacts.iter().filter_map(|&idx| {
+                    let artifact = dist.artifact(idx);
+                    let ArtifactKind::Installer(InstallerImpl::WinGet(info)) = &artifact.kind
+                    else {
+                        return None;
+                    };
+                    Some(GithubPrFile {
+                        app_name: release.app_name.clone(),
+                        artifact: artifact.id.clone(),
+                        dest_path: info.repo_path(),
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        if !files.is_empty() {
+            jobs.push(GithubPrPublishInfo {
+                name: "winget".to_owned(),
+                repo: WINGET_PKGS_REPO.to_owned(),
+                fork_name: fork_name(WINGET_PKGS_REPO),
+                token_secret: "WINGET_TOKEN".to_owned(),
+                files,
+            });
+        }
+    }
+    jobs
+}
+
+/// The name a fork of the given (owner/name) repository will get
+fn fork_name(repo: &str) -> String {
+    repo.rsplit('/').next().unwrap_or(repo).to_owned()
+}
+
 /// Given a set of targets we want to build local artifacts for, map them to Github Runners
 /// while preferring to merge builds that can happen on the same machine.
 ///
@@ -185,12 +645,13 @@ impl GithubCiInfo {
 /// succeed (uploading itself to the draft release).
 ///
 /// In priniciple it does remove some duplicated setup work, so this is ostensibly "cheaper".
-fn distribute_targets_to_runners_merged(
-    targets: SortedSet<&TargetTriple>,
-) -> std::vec::IntoIter<(GithubRunner, Vec<&TargetTriple>)> {
+fn distribute_targets_to_runners_merged<'a>(
+    targets: SortedSet<&'a TargetTriple>,
+    custom_runners: &'a SortedMap<String, String>,
+) -> std::vec::IntoIter<(GithubRunner<'a>, Vec<&'a TargetTriple>)> {
     let mut groups = SortedMap::<GithubRunner, Vec<&TargetTriple>>::new();
     for target in targets {
-        let runner = github_runner_for_target(target);
+        let runner = github_runner_for_target(target, custom_runners);
         let runner = runner.unwrap_or_else(|| {
             let default = GITHUB_LINUX_RUNNER;
             warn!("not sure which github runner should be used for {target}, assuming {default}");
@@ -205,12 +666,13 @@ fn distribute_targets_to_runners_merged(
 
 /// Given a set of targets we want to build local artifacts for, map them to Github Runners
 /// while preferring each target gets its own runner for latency and fault-isolation.
-fn distribute_targets_to_runners_split(
-    targets: SortedSet<&TargetTriple>,
-) -> std::vec::IntoIter<(GithubRunner, Vec<&TargetTriple>)> {
+fn distribute_targets_to_runners_split<'a>(
+    targets: SortedSet<&'a TargetTriple>,
+    custom_runners: &'a SortedMap<String, String>,
+) -> std::vec::IntoIter<(GithubRunner<'a>, Vec<&'a TargetTriple>)> {
     let mut groups = vec![];
     for target in targets {
-        let runner = github_runner_for_target(target);
+        let runner = github_runner_for_target(target, custom_runners);
         let runner = runner.unwrap_or_else(|| {
             let default = GITHUB_LINUX_RUNNER;
             warn!("not sure which github runner should be used for {target}, assuming {default}");
@@ -222,7 +684,7 @@ fn distribute_targets_to_runners_split(
 }
 
 /// A string representing a Github Runner
-type GithubRunner = &'static str;
+type GithubRunner<'a> = &'a str;
 /// The Github Runner to use for Linux
 const GITHUB_LINUX_RUNNER: &str = "ubuntu-20.04";
 /// The Github Runner to use for macos
@@ -231,7 +693,15 @@ const GITHUB_MACOS_RUNNER: &str = "macos-11";
 const GITHUB_WINDOWS_RUNNER: &str = "windows-2019";
 
 /// Get the appropriate Github Runner for building a target
-fn github_runner_for_target(target: &TargetTriple) -> Option<GithubRunner> {
+///
+/// Runners the user asked for in github-custom-runners take precedence
+fn github_runner_for_target<'a>(
+    target: &TargetTriple,
+    custom_runners: &'a SortedMap<String, String>,
+) -> Option<GithubRunner<'a>> {
+    if let Some(runner) = custom_runners.get(target) {
+        return Some(runner);
+    }
     // We want to default to older runners to minimize the places
     // where random system dependencies can creep in and be very
     // recent. This helps with portability!
@@ -246,18 +716,19 @@ fn github_runner_for_target(target: &TargetTriple) -> Option<GithubRunner> {
     }
 }
 
-/// Select the cargo-dist installer approach for a given Github Runner
-fn install_dist_for_github_runner<'a>(
-    runner: GithubRunner,
+/// Select the cargo-dist installer approach for the machine building some targets
+///
+/// We can't tell what a custom runner is from its name, but all the targets on one
+/// runner share an OS, so we can go by those instead.
+fn install_dist_for_targets<'a>(
+    targets: &[&TargetTriple],
     install_sh: &'a str,
     install_ps1: &'a str,
 ) -> &'a str {
-    if runner == GITHUB_LINUX_RUNNER || runner == GITHUB_MACOS_RUNNER {
-        install_sh
-    } else if runner == GITHUB_WINDOWS_RUNNER {
+    if targets.iter().any(|target| target.contains("windows")) {
         install_ps1
     } else {
-        unreachable!("internal error: unknown github runner!?")
+        install_sh
     }
 }
 
@@ -281,77 +752,78 @@ brew bundle install"#,
     )
 }
 
-fn package_install_for_targets(
+/// The build dependencies of a target from one package manager
+fn build_packages_for_target<'a>(
+    target: &'a TargetTriple,
+    packages: &'a SortedMap<String, SystemDependency>,
+) -> impl Iterator<Item = (&'a String, &'a SystemDependency)> {
+    packages.iter().filter(move |(_, package)| {
+        package.0.wanted_for_target(target) && package.0.stage_wanted(&DependencyKind::Build)
+    })
+}
+
+/// The command the Github runners install apt packages with
+const GITHUB_APT_INSTALL: &str = "sudo apt-get install";
+
+/// Get the commands to install the build dependencies of a runner's targets
+///
+/// The package manager is picked based on each target's OS. With merge-tasks a runner
+/// can build several targets (say, gnu and musl linux), so this installs what any of
+/// them need. `apt_install` is the command to prefix apt packages with, since that
+/// varies with whether the runner is root.
+pub(crate) fn package_install_for_targets(
     targets: &Vec<&TargetTriple>,
     packages: &SystemDependencies,
+    apt_install: &str,
 ) -> Option<String> {
-    // TODO handle mixed-OS targets
+    let mut brews = Vec::<String>::new();
+    let mut apts = Vec::<String>::new();
+    let mut chocos = Vec::<String>::new();
+    let add = |list: &mut Vec<String>, item: String| {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    };
     for target in targets {
-        match target.as_str() {
-            "i686-apple-darwin" | "x86_64-apple-darwin" | "aarch64-apple-darwin" => {
-                let packages: Vec<String> = packages
-                    .homebrew
-                    .clone()
-                    .into_iter()
-                    .filter(|(_, package)| package.0.wanted_for_target(target))
-                    .filter(|(_, package)| package.0.stage_wanted(&DependencyKind::Build))
-                    .map(|(name, _)| name)
-                    .collect();
-
-                if packages.is_empty() {
-                    return None;
-                }
-
-                return Some(brew_bundle_command(&packages));
+        if target.contains("apple-darwin") {
+            for (name, package) in build_packages_for_target(target, &packages.homebrew) {
+                add(&mut brews, package.0.homebrew_formula(name));
             }
-            "i686-unknown-linux-gnu" | "x86_64-unknown-linux-gnu" | "aarch64-unknown-linux-gnu" => {
-                let packages: Vec<String> = packages
-                    .apt
-                    .clone()
-                    .into_iter()
-                    .filter(|(_, package)| package.0.wanted_for_target(target))
-                    .filter(|(_, package)| package.0.stage_wanted(&DependencyKind::Build))
-                    .map(|(name, spec)| {
-                        if let Some(version) = spec.0.version {
-                            format!("{name}={version}")
-                        } else {
-                            name
-                        }
-                    })
-                    .collect();
-
-                if packages.is_empty() {
-                    return None;
-                }
-
-                let apts = packages.join(" ");
-                return Some(format!("sudo apt-get install {apts}").to_owned());
+        } else if target.contains("linux") {
+            if target.contains("musl") && !apts.iter().any(|p| p == "musl-tools") {
+                // The runners don't come with a musl toolchain, which anything
+                // with C code in it needs to link statically
+                apts.insert(0, "musl-tools".to_owned());
             }
-            "i686-pc-windows-msvc" | "x86_64-pc-windows-msvc" | "aarch64-pc-windows-msvc" => {
-                let commands: Vec<String> = packages
-                    .chocolatey
-                    .clone()
-                    .into_iter()
-                    .filter(|(_, package)| package.0.wanted_for_target(target))
-                    .filter(|(_, package)| package.0.stage_wanted(&DependencyKind::Build))
-                    .map(|(name, package)| {
-                        if let Some(version) = package.0.version {
-                            format!("choco install {name} --version={version}")
-                        } else {
-                            format!("choco install {name}")
-                        }
-                    })
-                    .collect();
-
-                if commands.is_empty() {
-                    return None;
-                }
-
-                return Some(commands.join("\n"));
+            for (name, package) in build_packages_for_target(target, &packages.apt) {
+                let apt = match &package.0.version {
+                    Some(version) => format!("{name}={version}"),
+                    None => name.clone(),
+                };
+                add(&mut apts, apt);
+            }
+        } else if target.contains("windows") {
+            for (name, package) in build_packages_for_target(target, &packages.chocolatey) {
+                let choco = match &package.0.version {
+                    Some(version) => format!("choco install {name} --version={version}"),
+                    None => format!("choco install {name}"),
+                };
+                add(&mut chocos, choco);
             }
-            _ => {}
         }
     }
 
-    None
+    let mut commands = vec![];
+    if !brews.is_empty() {
+        commands.push(brew_bundle_command(&brews));
+    }
+    if !apts.is_empty() {
+        commands.push(format!("{apt_install} {}", apts.join(" ")));
+    }
+    commands.extend(chocos);
+    if commands.is_empty() {
+        None
+    } else {
+        Some(commands.join("\n"))
+    }
 }
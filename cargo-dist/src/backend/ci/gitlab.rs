@@ -0,0 +1,235 @@
+//! GitLab CI script generation
+//!
+//! This mirrors the Github CI backend: a plan job, one build job per group of
+//! targets, an optional global build job, and finally a job that uploads everything
+//! and announces it as a GitLab Release.
+
+use cargo_dist_schema::PrRunMode;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::{
+    backend::{diff_files, templates::TEMPLATE_CI_GITLAB, write_if_changed},
+    config::SystemDependencies,
+    errors::DistResult,
+    DistGraph, SortedMap, SortedSet, TargetTriple,
+};
+
+const GITLAB_CI_FILE: &str = ".gitlab-ci.yml";
+
+/// Info about running cargo-dist in GitLab CI
+#[derive(Debug, Serialize)]
+pub struct GitLabCiInfo {
+    /// Version of rust toolchain to install (deprecated)
+    pub rust_version: Option<String>,
+    /// expression to use for installing cargo-dist via shell script
+    pub install_dist_sh: String,
+    /// expression to use for installing cargo-dist via powershell script
+    pub install_dist_ps1: String,
+    /// Whether to fail-fast
+    pub fail_fast: bool,
+    /// The regex of git tags that should trigger a release
+    pub tag_regex: String,
+    /// What kind of job to run on merge requests
+    pub pr_run_mode: PrRunMode,
+    /// Jobs that build local artifacts
+    pub local_jobs: Vec<GitLabJob>,
+    /// The job that builds global artifacts (if there are any)
+    pub global_job: Option<GitLabJob>,
+    /// whether to create the release or assume an existing one
+    pub create_release: bool,
+    /// The name of the dir in target/ that cargo-dist builds in
+    pub dist_subdir: String,
+    /// Whether the plan job needs the full git history (to generate release notes)
+    pub fetch_history: bool,
+}
+
+/// A job that runs `cargo dist build`
+#[derive(Debug, Serialize)]
+pub struct GitLabJob {
+    /// The name of the job
+    pub name: String,
+    /// The kind of runner the job needs ("linux", "macos", or "windows")
+    pub os: GitLabRunner,
+    /// cli flags to pass to cargo dist
+    pub dist_args: String,
+    /// commands to run to install the system dependencies needed to build
+    pub packages_install: Option<String>,
+}
+
+impl GitLabCiInfo {
+    /// Compute the GitLab CI stuff
+    pub fn new(dist: &DistGraph) -> GitLabCiInfo {
+        // Legacy deprecated support
+        let rust_version = dist.desired_rust_toolchain.clone();
+
+        // If they don't specify a cargo-dist version, use this one
+        let self_dist_version = super::SELF_DIST_VERSION.parse().unwrap();
+        let dist_version = dist
+            .desired_cargo_dist_version
+            .as_ref()
+            .unwrap_or(&self_dist_version);
+        let dist_subdir = dist
+            .dist_dir
+            .file_name()
+            .expect("dist dir had no name!?")
+            .to_owned();
+
+        // Figure out what builds we need to do
+        let mut needs_global_build = false;
+        let mut local_targets = SortedSet::new();
+        let mut dependencies = SystemDependencies::default();
+        for release in &dist.releases {
+            if !release.global_artifacts.is_empty() {
+                needs_global_build = true;
+            }
+            local_targets.extend(release.targets.iter());
+            dependencies.append(&mut release.system_dependencies.clone());
+        }
+
+        // Global artifacts should be buildable anywhere, so use the cheap linux runners
+        let global_job = needs_global_build.then(|| GitLabJob {
+            name: "build-global".to_owned(),
+            os: GITLAB_LINUX_RUNNER,
+            dist_args: "--artifacts=global".to_owned(),
+            packages_install: None,
+        });
+
+        // Figure out what Local Artifact jobs we need
+        let local_runs = if dist.merge_tasks {
+            distribute_targets_to_runners_merged(local_targets)
+        } else {
+            distribute_targets_to_runners_split(local_targets)
+        };
+        let local_jobs = local_runs
+            .into_iter()
+            .map(|(os, targets)| {
+                use std::fmt::Write;
+                // Merged jobs are named after their runner, otherwise after their target
+                let name = match &targets[..] {
+                    [target] => format!("build-local-{target}"),
+                    _ => format!("build-local-{os}"),
+                };
+                let mut dist_args = String::from("--artifacts=local");
+                for target in &targets {
+                    write!(dist_args, " --target={target}").unwrap();
+                }
+                let packages_install = super::github::package_install_for_targets(
+                    &targets,
+                    &dependencies,
+                    GITLAB_APT_INSTALL,
+                );
+                GitLabJob {
+                    name,
+                    os,
+                    dist_args,
+                    packages_install,
+                }
+            })
+            .collect();
+
+        GitLabCiInfo {
+            rust_version,
+            install_dist_sh: super::install_dist_sh_for_version(dist_version),
+            install_dist_ps1: super::install_dist_ps1_for_version(dist_version),
+            fail_fast: dist.fail_fast,
+            tag_regex: dist.tag_regex(),
+            pr_run_mode: dist.pr_run_mode,
+            local_jobs,
+            global_job,
+            create_release: dist.create_release,
+            dist_subdir,
+            fetch_history: dist.needs_git_history(),
+        }
+    }
+
+    fn gitlab_ci_path(&self, dist: &DistGraph) -> camino::Utf8PathBuf {
+        dist.workspace_dir.join(GITLAB_CI_FILE)
+    }
+
+    /// Generate the requested configuration and returns it as a string.
+    pub fn generate_gitlab_ci(&self, dist: &DistGraph) -> DistResult<String> {
+        let rendered = dist
+            .templates
+            .render_file_to_clean_string(TEMPLATE_CI_GITLAB, self)?;
+
+        Ok(rendered)
+    }
+
+    /// Write .gitlab-ci.yml to disk
+    pub fn write_to_disk(&self, dist: &DistGraph) -> Result<(), miette::Report> {
+        let ci_file = self.gitlab_ci_path(dist);
+        let rendered = self.generate_gitlab_ci(dist)?;
+
+        if write_if_changed(&ci_file, &rendered)? {
+            eprintln!("generated GitLab CI to {}", ci_file);
+        } else {
+            eprintln!("GitLab CI at {} is already up to date", ci_file);
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the new configuration differs from the config on disk
+    /// writhout actually writing the result.
+    pub fn check(&self, dist: &DistGraph) -> DistResult<()> {
+        let ci_file = self.gitlab_ci_path(dist);
+
+        let rendered = self.generate_gitlab_ci(dist)?;
+        diff_files(&ci_file, &rendered)
+    }
+}
+
+/// Given a set of targets we want to build local artifacts for, map them to GitLab runners
+/// while preferring to merge builds that can happen on the same machine.
+///
+/// See the Github version of this for the tradeoffs involved.
+fn distribute_targets_to_runners_merged(
+    targets: SortedSet<&TargetTriple>,
+) -> Vec<(GitLabRunner, Vec<&TargetTriple>)> {
+    let mut groups = SortedMap::<GitLabRunner, Vec<&TargetTriple>>::new();
+    for target in targets {
+        groups
+            .entry(gitlab_runner_for_target(target))
+            .or_default()
+            .push(target);
+    }
+    groups.into_iter().collect()
+}
+
+/// Given a set of targets we want to build local artifacts for, map them to GitLab runners
+/// while preferring each target gets its own runner for latency and fault-isolation.
+fn distribute_targets_to_runners_split(
+    targets: SortedSet<&TargetTriple>,
+) -> Vec<(GitLabRunner, Vec<&TargetTriple>)> {
+    targets
+        .into_iter()
+        .map(|target| (gitlab_runner_for_target(target), vec![target]))
+        .collect()
+}
+
+/// The kind of GitLab runner to use, which the template maps to runner tags/images
+type GitLabRunner = &'static str;
+/// The command the GitLab linux image installs apt packages with (it runs as root)
+const GITLAB_APT_INSTALL: &str = "apt-get install -y";
+/// The GitLab runner to use for Linux
+const GITLAB_LINUX_RUNNER: &str = "linux";
+/// The GitLab runner to use for macos
+const GITLAB_MACOS_RUNNER: &str = "macos";
+/// The GitLab runner to use for windows
+const GITLAB_WINDOWS_RUNNER: &str = "windows";
+
+/// Get the appropriate GitLab runner for building a target
+fn gitlab_runner_for_target(target: &TargetTriple) -> GitLabRunner {
+    if target.contains("linux") {
+        GITLAB_LINUX_RUNNER
+    } else if target.contains("apple") {
+        GITLAB_MACOS_RUNNER
+    } else if target.contains("windows") {
+        GITLAB_WINDOWS_RUNNER
+    } else {
+        let default = GITLAB_LINUX_RUNNER;
+        warn!("not sure which gitlab runner should be used for {target}, assuming {default}");
+        default
+    }
+}
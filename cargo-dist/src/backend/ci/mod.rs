@@ -2,9 +2,11 @@
 
 use semver::Version;
 
-use self::github::GithubCiInfo;
+use self::{gitea::GiteaCiInfo, github::GithubCiInfo, gitlab::GitLabCiInfo};
 
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 
 /// The current version of cargo-dist
 const SELF_DIST_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -15,6 +17,32 @@ const BASE_DIST_FETCH_URL: &str = "https://github.com/axodotdev/cargo-dist/relea
 pub struct CiInfo {
     /// Github CI
     pub github: Option<GithubCiInfo>,
+    /// GitLab CI
+    pub gitlab: Option<GitLabCiInfo>,
+    /// Gitea (or Forgejo) Actions
+    pub gitea: Option<GiteaCiInfo>,
+}
+
+/// Whether we're running in CI on behalf of a pull (or merge) request
+pub fn is_pull_request_run() -> bool {
+    is_pull_request_env(|name| std::env::var(name).ok())
+}
+
+/// [`is_pull_request_run`][] for an environment where `var` looks up variables
+///
+/// If you add a CI backend, teach this how it says so.
+pub(crate) fn is_pull_request_env(var: impl Fn(&str) -> Option<String>) -> bool {
+    // Github names the event that triggered the workflow (and Gitea does the same,
+    // for compatibility). pull_request_target runs in the context of the base branch,
+    // but it's still a pull request.
+    let github = var("GITHUB_EVENT_NAME")
+        .is_some_and(|event| event == "pull_request" || event == "pull_request_target");
+    // GitLab names the source of the pipeline: either a merge request, or a pull request
+    // on a GitHub repo that's mirrored to GitLab
+    let gitlab = var("CI_PIPELINE_SOURCE").is_some_and(|source| {
+        source == "merge_request_event" || source == "external_pull_request_event"
+    });
+    github || gitlab
 }
 
 /// Get the command to invoke to install cargo-dist via sh script
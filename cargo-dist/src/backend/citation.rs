@@ -0,0 +1,144 @@
+//! Code for generating CITATION.cff release metadata
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use serde::Serialize;
+use tracing::info;
+
+use crate::{
+    backend::templates::{Templates, TEMPLATE_METADATA_CITATION},
+    errors::DistResult,
+};
+
+/// Info needed to build a CITATION.cff file for a release
+#[derive(Debug, Clone, Serialize)]
+pub struct CitationInfo {
+    /// Final file path of the citation file
+    pub dest_path: Utf8PathBuf,
+    /// App name to cite
+    pub app_name: String,
+    /// App version to cite
+    pub app_version: String,
+    /// A brief description of the app
+    pub app_desc: Option<String>,
+    /// The authors to credit
+    pub authors: Vec<CitationAuthor>,
+    /// The SPDX ids of the app's licenses (any of which it can be used under)
+    pub licenses: Vec<String>,
+    /// The URL to the app's source repository
+    pub app_repository_url: Option<String>,
+    /// The URL to the app's homepage
+    pub app_homepage_url: Option<String>,
+}
+
+/// An author to credit in a CITATION.cff
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CitationAuthor {
+    /// The name of the author
+    pub name: String,
+    /// The email of the author, if known
+    pub email: Option<String>,
+}
+
+impl CitationAuthor {
+    /// Parse a Cargo-style author string like `Jane Doe <jane@example.com>`
+    pub fn from_cargo_author(author: &str) -> Self {
+        if let Some((name, rest)) = author.split_once('<') {
+            if let Some(email) = rest.strip_suffix('>') {
+                return Self {
+                    name: name.trim().to_owned(),
+                    email: Some(email.trim().to_owned()),
+                };
+            }
+        }
+        Self {
+            name: author.trim().to_owned(),
+            email: None,
+        }
+    }
+}
+
+/// Get the licenses to list in a CITATION.cff from a Cargo license expression
+///
+/// CFF can only list several licenses to choose from, so `A OR B` (or the older `A/B`)
+/// becomes both ids, and expressions it can't represent (like `A AND B`) are left out.
+pub(crate) fn cff_licenses(license: Option<&str>) -> Vec<String> {
+    let Some(license) = license else {
+        return vec![];
+    };
+    let licenses = license
+        .split(" OR ")
+        .flat_map(|license| license.split('/'))
+        .map(|license| license.trim())
+        .collect::<Vec<_>>();
+    let representable = licenses.iter().all(|license| {
+        !license.is_empty()
+            && !license.contains(|c: char| c.is_whitespace() || c == '(' || c == ')')
+    });
+    if !representable {
+        info!("not listing license {license} in CITATION.cff, which can't express it");
+        return vec![];
+    }
+    licenses
+        .into_iter()
+        .map(|license| license.to_owned())
+        .collect()
+}
+
+pub(crate) fn write_citation_file(templates: &Templates, info: &CitationInfo) -> DistResult<()> {
+    let contents = render_citation_file(templates, info)?;
+    LocalAsset::write_new(&contents, &info.dest_path)?;
+    Ok(())
+}
+
+fn render_citation_file(templates: &Templates, info: &CitationInfo) -> DistResult<String> {
+    templates.render_file_to_clean_string(TEMPLATE_METADATA_CITATION, info)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn citation_lists_authors_and_version() {
+        let templates = Templates::new().unwrap();
+        let info = CitationInfo {
+            dest_path: Utf8PathBuf::from("axolotlsay-citation.cff"),
+            app_name: "axolotlsay".to_owned(),
+            app_version: "1.2.3".to_owned(),
+            app_desc: Some("💬 a CLI for learning to distribute CLIs in rust".to_owned()),
+            authors: ["Axo Developer Co. <hello@axo.dev>", "Jane Doe"]
+                .iter()
+                .map(|a| CitationAuthor::from_cargo_author(a))
+                .collect(),
+            licenses: cff_licenses(Some("MIT OR Apache-2.0")),
+            app_repository_url: Some("https://github.com/axodotdev/axolotlsay".to_owned()),
+            app_homepage_url: None,
+        };
+
+        let contents = render_citation_file(&templates, &info).unwrap();
+        assert!(contents.contains("cff-version: 1.2.0\n"));
+        assert!(contents.contains("version: \"1.2.3\"\n"));
+        assert!(
+            contents.contains("  - name: \"Axo Developer Co.\"\n    email: \"hello@axo.dev\"\n")
+        );
+        assert!(contents.contains("  - name: \"Jane Doe\"\n"));
+        assert!(contents.contains("repository-code: \"https://github.com/axodotdev/axolotlsay\"\n"));
+        assert!(!contents.contains("url: null"));
+        assert!(contents.contains("license:\n  - \"MIT\"\n  - \"Apache-2.0\"\n"));
+    }
+
+    #[test]
+    fn citation_licenses() {
+        assert_eq!(cff_licenses(Some("MIT")), ["MIT"]);
+        assert_eq!(
+            cff_licenses(Some("MIT OR Apache-2.0")),
+            ["MIT", "Apache-2.0"]
+        );
+        assert_eq!(cff_licenses(Some("MIT/Apache-2.0")), ["MIT", "Apache-2.0"]);
+        assert!(cff_licenses(Some("MIT AND Apache-2.0")).is_empty());
+        assert!(cff_licenses(Some("(MIT OR Apache-2.0) AND Unicode-3.0")).is_empty());
+        assert!(cff_licenses(Some("Apache-2.0 WITH LLVM-exception")).is_empty());
+        assert!(cff_licenses(None).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! macOS .app bundle
+//!
+//! A `.app` is just a dir with a particular layout: the binaries go in `Contents/MacOS/`,
+//! the icon in `Contents/Resources/`, and an `Info.plist` describing the whole thing goes
+//! in `Contents/`. We stage that layout (the binaries get copied in like any other archive)
+//! and the usual zip step bundles it up for distribution.
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use tracing::info;
+
+use crate::errors::*;
+
+/// Info needed to build a .app bundle
+#[derive(Debug, Clone)]
+pub struct AppBundleInstallerInfo {
+    /// The `Foo.app` dir the bundle is staged in
+    pub bundle_dir: Utf8PathBuf,
+    /// Name of the app
+    pub app_name: String,
+    /// Version of the app
+    pub version: String,
+    /// The CFBundleIdentifier of the app (e.g. "dev.axo.axolotlsay")
+    pub identifier: String,
+    /// The binary (in `Contents/MacOS/`) that runs when the app is opened
+    pub executable: String,
+    /// The .icns icon to include (if any)
+    pub icon: Option<Utf8PathBuf>,
+}
+
+impl AppBundleInstallerInfo {
+    /// Where the bundle's binaries go
+    pub fn macos_dir(&self) -> Utf8PathBuf {
+        self.bundle_dir.join("Contents").join("MacOS")
+    }
+
+    /// Where the bundle's icon goes
+    pub fn resources_dir(&self) -> Utf8PathBuf {
+        self.bundle_dir.join("Contents").join("Resources")
+    }
+
+    fn icon_file_name(&self) -> Option<String> {
+        self.icon
+            .as_ref()
+            .map(|_| format!("{}.icns", self.app_name))
+    }
+
+    /// Render the bundle's Info.plist
+    pub fn info_plist(&self) -> String {
+        let mut entries = vec![
+            ("CFBundleName", self.app_name.clone()),
+            ("CFBundleDisplayName", self.app_name.clone()),
+            ("CFBundleIdentifier", self.identifier.clone()),
+            ("CFBundleVersion", self.version.clone()),
+            ("CFBundleShortVersionString", self.version.clone()),
+            ("CFBundleExecutable", self.executable.clone()),
+            ("CFBundlePackageType", "APPL".to_owned()),
+            ("CFBundleInfoDictionaryVersion", "6.0".to_owned()),
+        ];
+        if let Some(icon) = self.icon_file_name() {
+            entries.push(("CFBundleIconFile", icon));
+        }
+
+        let mut plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+"#
+        .to_owned();
+        for (key, val) in entries {
+            plist.push_str(&format!(
+                "    <key>{key}</key>\n    <string>{}</string>\n",
+                xml_str(&val)
+            ));
+        }
+        plist.push_str("    <key>NSHighResolutionCapable</key>\n    <true/>\n");
+        plist.push_str("</dict>\n</plist>\n");
+        plist
+    }
+
+    /// Build the bundle
+    ///
+    /// This assumes the binaries have already been copied into [`Self::macos_dir`][].
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a macOS app bundle: {}", self.bundle_dir);
+
+        let contents_dir = self.bundle_dir.join("Contents");
+        LocalAsset::write_new_all(&self.info_plist(), contents_dir.join("Info.plist"))?;
+        if let (Some(icon), Some(icon_file_name)) = (&self.icon, self.icon_file_name()) {
+            let resources_dir = self.resources_dir();
+            LocalAsset::create_dir_all(&resources_dir)?;
+            LocalAsset::copy_named(icon, resources_dir.join(icon_file_name))?;
+        }
+        Ok(())
+    }
+}
+
+/// Escape a value for use in plist (XML) text
+fn xml_str(val: &str) -> String {
+    val.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn info_plist_describes_app() {
+        let info = AppBundleInstallerInfo {
+            bundle_dir: "/dist/axolotlsay-aarch64-apple-darwin_app/axolotlsay.app".into(),
+            app_name: "axolotlsay".to_owned(),
+            version: "0.2.1".to_owned(),
+            identifier: "dev.axo.axolotlsay".to_owned(),
+            executable: "axolotlsay".to_owned(),
+            icon: Some("/axolotlsay/icon.icns".into()),
+        };
+        let plist = info.info_plist();
+        assert!(plist.contains(
+            "    <key>CFBundleIdentifier</key>\n    <string>dev.axo.axolotlsay</string>\n"
+        ));
+        assert!(
+            plist.contains("    <key>CFBundleExecutable</key>\n    <string>axolotlsay</string>\n")
+        );
+        assert!(plist
+            .contains("    <key>CFBundleIconFile</key>\n    <string>axolotlsay.icns</string>\n"));
+        assert!(plist.ends_with("</dict>\n</plist>\n"));
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(xml_str("<R&D>"), "&lt;R&amp;D&gt;");
+    }
+}
@@ -0,0 +1,167 @@
+//! AppImage installer
+//!
+//! An AppImage is a self-mounting filesystem image of an "AppDir":
+//!
+//! * `AppRun`: the entrypoint, which we make launch the main binary
+//! * `<name>.desktop`: a freedesktop.org desktop entry describing the app
+//! * an icon, named after the desktop entry's `Icon` key
+//! * `usr/bin/`: the binaries
+//!
+//! We lay out the AppDir ourselves and hand it to `appimagetool` to do the rest.
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use tracing::info;
+
+use crate::errors::*;
+
+/// The directory binaries get put in (relative to the AppDir)
+pub const APPIMAGE_BIN_DIR: &str = "usr/bin";
+
+/// Info needed to build an AppImage
+#[derive(Debug, Clone)]
+pub struct AppImageInstallerInfo {
+    /// Final file path of the AppImage
+    pub file_path: Utf8PathBuf,
+    /// The AppDir to turn into an AppImage
+    pub app_dir: Utf8PathBuf,
+    /// The binary (in [`APPIMAGE_BIN_DIR`][]) AppRun should launch
+    pub main_binary: String,
+    /// appimagetool's name for the architecture (e.g. "x86_64")
+    pub arch: String,
+    /// The desktop entry for the app
+    pub desktop: DesktopEntry,
+}
+
+/// The fields of a freedesktop.org desktop entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopEntry {
+    /// Name of the app (also used for the file name of the entry)
+    pub name: String,
+    /// The binary to run
+    pub exec: String,
+    /// Name of the icon (its file name in the AppDir, without the extension)
+    pub icon: String,
+    /// A brief description of the app
+    pub comment: Option<String>,
+}
+
+impl DesktopEntry {
+    /// Render the desktop entry
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("[Desktop Entry]\n");
+        out.push_str("Type=Application\n");
+        out.push_str(&format!("Name={}\n", self.name));
+        out.push_str(&format!("Exec={}\n", self.exec));
+        out.push_str(&format!("Icon={}\n", self.icon));
+        if let Some(comment) = &self.comment {
+            // Values can't span lines, so only keep the first
+            if let Some(comment) = comment.trim().lines().next() {
+                out.push_str(&format!("Comment={comment}\n"));
+            }
+        }
+        out.push_str("Categories=Utility;\n");
+        out.push_str("Terminal=false\n");
+        out
+    }
+}
+
+impl AppImageInstallerInfo {
+    /// Render the AppRun script
+    pub fn render_app_run(&self) -> String {
+        format!(
+            r#"#!/bin/sh
+HERE="$(dirname "$(readlink -f "$0")")"
+exec "$HERE/{APPIMAGE_BIN_DIR}/{}" "$@"
+"#,
+            self.main_binary
+        )
+    }
+
+    /// Build the AppImage
+    ///
+    /// This assumes the binaries and icon have already been copied into `app_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building an AppImage: {}", self.file_path);
+
+        let desktop_path = self.app_dir.join(format!("{}.desktop", self.desktop.name));
+        LocalAsset::write_new(&self.desktop.render(), desktop_path)?;
+        let app_run_path = self.app_dir.join("AppRun");
+        LocalAsset::write_new(&self.render_app_run(), &app_run_path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&app_run_path, std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        let appimage = self
+            .file_path
+            .file_name()
+            .expect("AppImage had no file name!?");
+        let status = Command::new("appimagetool")
+            .env("ARCH", &self.arch)
+            .arg(&self.app_dir)
+            .arg(&self.file_path)
+            .status()
+            .map_err(|details| DistError::AppImageToolNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::AppImageTool {
+                appimage: appimage.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Get appimagetool's name for the architecture of a target triple (if it has one)
+pub fn appimage_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let appimage = match arch {
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        "i686" => "i686",
+        "armv7" => "armhf",
+        _ => return None,
+    };
+    Some(appimage)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn desktop_entry() {
+        let desktop = DesktopEntry {
+            name: "axolotlsay".to_owned(),
+            exec: "axolotlsay".to_owned(),
+            icon: "axolotl".to_owned(),
+            comment: Some("a cool app\n\nit says things".to_owned()),
+        };
+        assert_eq!(
+            desktop.render(),
+            "[Desktop Entry]
+Type=Application
+Name=axolotlsay
+Exec=axolotlsay
+Icon=axolotl
+Comment=a cool app
+Categories=Utility;
+Terminal=false
+"
+        );
+    }
+
+    #[test]
+    fn arches() {
+        assert_eq!(appimage_arch("x86_64-unknown-linux-gnu"), Some("x86_64"));
+        assert_eq!(
+            appimage_arch("armv7-unknown-linux-gnueabihf"),
+            Some("armhf")
+        );
+        assert_eq!(appimage_arch("s390x-unknown-linux-gnu"), None);
+    }
+}
@@ -0,0 +1,419 @@
+//! deb installer
+//!
+//! A .deb is just an `ar` archive containing (in this order):
+//!
+//! * `debian-binary`: the format version (`2.0\n`)
+//! * `control.tar.gz`: the package's metadata (the `control` file)
+//! * `data.tar.gz`: the files to install, rooted at `/` (including the license, in
+//!   `/usr/share/doc/<package>/copyright`, since control files have no field for it)
+//!
+//! We build all of that ourselves (reusing our reproducible tarball logic) so that
+//! .debs can be made on any machine, not just ones with `dpkg-deb` installed.
+
+use std::{fs::File, io::Write};
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use tracing::info;
+
+use crate::{
+    archive::zip_dir,
+    config::{CompressionImpl, ZipStyle},
+    errors::*,
+};
+
+/// The directory binaries get installed to
+pub const DEB_BIN_DIR: &str = "usr/bin";
+/// The directory man pages get installed to
+pub const DEB_MAN_DIR: &str = "usr/share/man";
+/// The directory each package's docs (and copyright file) get installed to
+pub const DEB_DOC_DIR: &str = "usr/share/doc";
+
+/// Info needed to build a deb
+#[derive(Debug, Clone)]
+pub struct DebInstallerInfo {
+    /// Final file path of the deb
+    pub file_path: Utf8PathBuf,
+    /// Dir the files to install are put in (mirroring where they'll end up under `/`)
+    pub package_dir: Utf8PathBuf,
+    /// Scratch dir for the control file and the inner tarballs
+    pub control_dir: Utf8PathBuf,
+    /// Names of the binaries in [`DEB_BIN_DIR`][]
+    pub binaries: Vec<String>,
+    /// The contents of the control file
+    pub control: DebControl,
+    /// The contents of the copyright file (if we know the license)
+    pub copyright: Option<DebCopyright>,
+}
+
+/// The fields of a deb's control file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebControl {
+    /// Name of the package
+    pub package: String,
+    /// Version of the package (as semver, it's converted to Debian's format when rendered)
+    pub version: String,
+    /// Debian's name for the architecture (e.g. "amd64")
+    pub architecture: String,
+    /// Who maintains the package
+    pub maintainer: Option<String>,
+    /// A brief description of the package
+    pub description: String,
+    /// The package's homepage
+    pub homepage: Option<String>,
+    /// Other (apt) packages this one needs at runtime
+    pub depends: Vec<String>,
+    /// Other (apt) packages that are installed alongside this one by default
+    pub recommends: Vec<String>,
+    /// Other (apt) packages that enhance this one
+    pub suggests: Vec<String>,
+}
+
+impl DebControl {
+    /// Render the control file
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut field = |key: &str, val: &str| push_field(&mut out, key, val);
+        field("Package", &self.package);
+        field("Version", &deb_version(&self.version));
+        field("Architecture", &self.architecture);
+        if let Some(maintainer) = &self.maintainer {
+            field("Maintainer", maintainer);
+        }
+        if let Some(homepage) = &self.homepage {
+            field("Homepage", homepage);
+        }
+        if !self.depends.is_empty() {
+            field("Depends", &self.depends.join(", "));
+        }
+        if !self.recommends.is_empty() {
+            field("Recommends", &self.recommends.join(", "));
+        }
+        if !self.suggests.is_empty() {
+            field("Suggests", &self.suggests.join(", "));
+        }
+        field("Description", &self.description);
+        out
+    }
+}
+
+/// The fields of a deb's copyright file
+///
+/// This is written in Debian's [machine-readable format][format], with a single
+/// paragraph covering every file in the package.
+///
+/// [format]: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebCopyright {
+    /// Name of the upstream project
+    pub upstream_name: String,
+    /// Who holds the copyright (the package's authors)
+    pub holders: Vec<String>,
+    /// The license of the package
+    pub license: String,
+}
+
+impl DebCopyright {
+    /// Render the copyright file
+    pub fn render(&self) -> String {
+        let holders = if self.holders.is_empty() {
+            format!("the {} authors", self.upstream_name)
+        } else {
+            self.holders.join("\n")
+        };
+        // A header paragraph, then the one for all the files
+        let mut out = String::new();
+        push_field(
+            &mut out,
+            "Format",
+            "https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/",
+        );
+        push_field(&mut out, "Upstream-Name", &self.upstream_name);
+        out.push('\n');
+        push_field(&mut out, "Files", "*");
+        push_field(&mut out, "Copyright", &holders);
+        push_field(&mut out, "License", &self.license);
+        out
+    }
+}
+
+/// Add a `key: val` field to a control-style file
+fn push_field(out: &mut String, key: &str, val: &str) {
+    // Continuation lines need to be indented, and blank ones are written as " ."
+    let val = val
+        .trim()
+        .lines()
+        .map(|line| if line.trim().is_empty() { "." } else { line })
+        .collect::<Vec<_>>()
+        .join("\n ");
+    out.push_str(&format!("{key}: {val}\n"));
+}
+
+impl DebInstallerInfo {
+    /// Build the deb
+    ///
+    /// This assumes the binaries have already been copied into `package_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a deb: {}", self.file_path);
+
+        if let Some(copyright) = &self.copyright {
+            let doc_dir = self
+                .package_dir
+                .join(DEB_DOC_DIR)
+                .join(&self.control.package);
+            LocalAsset::create_dir_all(&doc_dir)?;
+            LocalAsset::write_new(&copyright.render(), doc_dir.join("copyright"))?;
+        }
+
+        // Start with a clean scratch dir
+        if self.control_dir.exists() {
+            LocalAsset::remove_dir_all(&self.control_dir)?;
+        }
+        let control_contents_dir = self.control_dir.join("control");
+        LocalAsset::create_dir_all(&control_contents_dir)?;
+        LocalAsset::write_new(&self.control.render(), control_contents_dir.join("control"))?;
+
+        let tarball = ZipStyle::Tar(CompressionImpl::Gzip);
+        let control_tarball = self.control_dir.join("control.tar.gz");
+        zip_dir(
+            &control_contents_dir,
+            &control_tarball,
+            &tarball,
+            Some(Utf8Path::new(".")),
+            &[],
+        )?;
+        let executables = self
+            .binaries
+            .iter()
+            .map(|bin| Utf8Path::new(DEB_BIN_DIR).join(bin))
+            .collect::<Vec<_>>();
+        let data_tarball = self.control_dir.join("data.tar.gz");
+        zip_dir(
+            &self.package_dir,
+            &data_tarball,
+            &tarball,
+            Some(Utf8Path::new(".")),
+            &executables,
+        )?;
+
+        write_ar(
+            &self.file_path,
+            &[
+                ("debian-binary", b"2.0\n".to_vec()),
+                ("control.tar.gz", LocalAsset::load_bytes(&control_tarball)?),
+                ("data.tar.gz", LocalAsset::load_bytes(&data_tarball)?),
+            ],
+        )
+        .map_err(|details| DistError::Archive {
+            dest_path: self.file_path.clone(),
+            details,
+        })?;
+        Ok(())
+    }
+}
+
+/// Write out an `ar` archive of the given (name, contents) pairs
+///
+/// All the metadata (mtime, owner, mode) is normalized so the output is reproducible.
+fn write_ar(dest_path: &Utf8Path, entries: &[(&str, Vec<u8>)]) -> std::io::Result<()> {
+    let mut file = File::create(dest_path)?;
+    file.write_all(b"!<arch>\n")?;
+    for (name, contents) in entries {
+        // name, mtime, uid, gid, mode, size, magic
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            0,
+            0,
+            0,
+            "100644",
+            contents.len()
+        );
+        debug_assert_eq!(header.len(), 60);
+        file.write_all(header.as_bytes())?;
+        file.write_all(contents)?;
+        // Entries are aligned to even offsets
+        if contents.len() % 2 != 0 {
+            file.write_all(b"\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Get Debian's name for the architecture of a target triple (if it has one)
+pub fn deb_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let deb = match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "i686" | "i586" => "i386",
+        "armv7" => "armhf",
+        "arm" if target.ends_with("eabihf") => "armhf",
+        "arm" => "armel",
+        "powerpc64le" => "ppc64el",
+        "s390x" => "s390x",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(deb)
+}
+
+/// Convert a semver version to a Debian one
+///
+/// Debian sorts `~` *before* the empty string, which is how semver treats prereleases,
+/// while `-` would be interpreted as a Debian revision.
+pub fn deb_version(version: &str) -> String {
+    version.replacen('-', "~", 1)
+}
+
+/// Convert a package name to a valid Debian one (lowercase, no underscores)
+pub fn deb_package_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace('_', "-")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn control_file() {
+        let control = DebControl {
+            package: "axolotlsay".to_owned(),
+            version: "0.1.0-alpha.1".to_owned(),
+            architecture: "amd64".to_owned(),
+            maintainer: Some("axodotdev <hello@axo.dev>".to_owned()),
+            description: "a cool app\n\nit says things".to_owned(),
+            homepage: None,
+            depends: vec!["libssl3".to_owned(), "libc6 (>= 2.31)".to_owned()],
+            recommends: vec!["bash-completion".to_owned()],
+            suggests: vec![],
+        };
+        assert_eq!(
+            control.render(),
+            "Package: axolotlsay
+Version: 0.1.0~alpha.1
+Architecture: amd64
+Maintainer: axodotdev <hello@axo.dev>
+Depends: libssl3, libc6 (>= 2.31)
+Recommends: bash-completion
+Description: a cool app
+ .
+ it says things
+"
+        );
+    }
+
+    #[test]
+    fn copyright_file() {
+        let copyright = DebCopyright {
+            upstream_name: "axolotlsay".to_owned(),
+            holders: vec![
+                "axodotdev <hello@axo.dev>".to_owned(),
+                "Axolotl <axolotl@axo.dev>".to_owned(),
+            ],
+            license: "MIT OR Apache-2.0".to_owned(),
+        };
+        assert_eq!(
+            copyright.render(),
+            "Format: https://www.debian.org/doc/packaging-manuals/copyright-format/1.0/
+Upstream-Name: axolotlsay
+
+Files: *
+Copyright: axodotdev <hello@axo.dev>
+ Axolotl <axolotl@axo.dev>
+License: MIT OR Apache-2.0
+"
+        );
+    }
+
+    #[test]
+    fn builds_an_ar_of_tarballs() {
+        let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("cargo-dist-deb-{}", std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        let package_dir = dir.join("package");
+        std::fs::create_dir_all(package_dir.join(DEB_BIN_DIR)).unwrap();
+        std::fs::write(package_dir.join(DEB_BIN_DIR).join("axolotlsay"), "binary!").unwrap();
+        let info = DebInstallerInfo {
+            file_path: dir.join("axolotlsay.deb"),
+            package_dir,
+            control_dir: dir.join("control"),
+            binaries: vec!["axolotlsay".to_owned()],
+            control: DebControl {
+                package: "axolotlsay".to_owned(),
+                version: "0.1.0".to_owned(),
+                architecture: "amd64".to_owned(),
+                maintainer: None,
+                description: "a cool app".to_owned(),
+                homepage: None,
+                depends: vec![],
+                recommends: vec![],
+                suggests: vec![],
+            },
+            copyright: Some(DebCopyright {
+                upstream_name: "axolotlsay".to_owned(),
+                holders: vec![],
+                license: "MIT".to_owned(),
+            }),
+        };
+        info.build().unwrap();
+
+        let deb = std::fs::read(&info.file_path).unwrap();
+        assert!(deb.starts_with(b"!<arch>\n"));
+        // Walk the ar entries
+        let mut entries = vec![];
+        let mut offset = 8;
+        while offset < deb.len() {
+            let header = std::str::from_utf8(&deb[offset..offset + 60]).unwrap();
+            let name = header[..16].trim().to_owned();
+            let size: usize = header[48..58].trim().parse().unwrap();
+            offset += 60;
+            entries.push((name, deb[offset..offset + size].to_vec()));
+            offset += size + size % 2;
+        }
+        let names = entries
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["debian-binary", "control.tar.gz", "data.tar.gz"]);
+        assert_eq!(entries[0].1, b"2.0\n");
+
+        let mut data = tar::Archive::new(flate2::read::GzDecoder::new(&entries[2].1[..]));
+        let bin = data
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.path().unwrap().ends_with("usr/bin/axolotlsay"))
+            .expect("binary wasn't in data.tar.gz");
+        assert_eq!(bin.header().mode().unwrap(), 0o755);
+        let mut data = tar::Archive::new(flate2::read::GzDecoder::new(&entries[2].1[..]));
+        let copyright = data
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| {
+                entry
+                    .path()
+                    .unwrap()
+                    .ends_with("usr/share/doc/axolotlsay/copyright")
+            })
+            .expect("copyright wasn't in data.tar.gz");
+        assert_eq!(copyright.header().mode().unwrap(), 0o644);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn versions_and_names() {
+        assert_eq!(deb_version("1.2.3"), "1.2.3");
+        assert_eq!(deb_version("1.2.3-beta.1"), "1.2.3~beta.1");
+        assert_eq!(deb_package_name("My_App"), "my-app");
+        assert_eq!(deb_arch("x86_64-unknown-linux-gnu"), Some("amd64"));
+        assert_eq!(deb_arch("aarch64-unknown-linux-gnu"), Some("arm64"));
+        assert_eq!(deb_arch("armv7-unknown-linux-gnueabihf"), Some("armhf"));
+        assert_eq!(deb_arch("sparc64-unknown-linux-gnu"), None);
+    }
+}
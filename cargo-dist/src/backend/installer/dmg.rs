@@ -0,0 +1,104 @@
+//! macOS disk image installer
+//!
+//! We stage the binaries (and the usual README/LICENSE/etc) in a dir along with a
+//! symlink to `/Applications`, and have `hdiutil` turn that dir into a compressed
+//! disk image the user can mount and drag things out of.
+
+use std::process::Command;
+
+use camino::Utf8PathBuf;
+use tracing::info;
+
+use crate::errors::*;
+
+/// The name of the symlink to `/Applications` we put in the disk image
+pub const DMG_APPLICATIONS_LINK: &str = "Applications";
+
+/// Info needed to build a dmg
+#[derive(Debug, Clone)]
+pub struct DmgInstallerInfo {
+    /// Final file path of the dmg
+    pub file_path: Utf8PathBuf,
+    /// Dir the contents of the dmg are staged in
+    pub package_dir: Utf8PathBuf,
+    /// The name of the volume the dmg mounts as
+    pub volume_name: String,
+}
+
+impl DmgInstallerInfo {
+    /// The arguments to hdiutil, which builds the dmg
+    pub fn hdiutil_args(&self) -> Vec<String> {
+        vec![
+            "create".to_owned(),
+            "-volname".to_owned(),
+            self.volume_name.clone(),
+            "-srcfolder".to_owned(),
+            self.package_dir.to_string(),
+            // replace any dmg left over from a previous build
+            "-ov".to_owned(),
+            // zlib-compressed, the usual format for distributing apps
+            "-format".to_owned(),
+            "UDZO".to_owned(),
+            self.file_path.to_string(),
+        ]
+    }
+
+    /// Build the dmg
+    ///
+    /// This assumes the binaries and static assets have already been copied into `package_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a dmg: {}", self.file_path);
+
+        // Check for hdiutil before touching anything, it only exists on macOS
+        Command::new("hdiutil")
+            .arg("help")
+            .output()
+            .map_err(|details| DistError::HdiutilNotFound { details })?;
+
+        let link = self.package_dir.join(DMG_APPLICATIONS_LINK);
+        if link.symlink_metadata().is_err() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink("/Applications", &link)?;
+        }
+
+        let dmg = self.file_path.file_name().expect("dmg had no file name!?");
+        let status = Command::new("hdiutil")
+            .args(self.hdiutil_args())
+            .status()
+            .map_err(|details| DistError::HdiutilNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::Hdiutil {
+                dmg: dmg.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hdiutil_makes_compressed_image() {
+        let info = DmgInstallerInfo {
+            file_path: "/dist/axolotlsay-aarch64-apple-darwin.dmg".into(),
+            package_dir: "/dist/axolotlsay-aarch64-apple-darwin_dmg".into(),
+            volume_name: "axolotlsay 0.2.1".to_owned(),
+        };
+        assert_eq!(
+            info.hdiutil_args(),
+            [
+                "create",
+                "-volname",
+                "axolotlsay 0.2.1",
+                "-srcfolder",
+                "/dist/axolotlsay-aarch64-apple-darwin_dmg",
+                "-ov",
+                "-format",
+                "UDZO",
+                "/dist/axolotlsay-aarch64-apple-darwin.dmg",
+            ]
+        );
+    }
+}
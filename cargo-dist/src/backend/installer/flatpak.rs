@@ -0,0 +1,294 @@
+//! Flatpak installer
+//!
+//! Flatpaks are normally built from source by `flatpak-builder` following a manifest.
+//! We already have the binaries, so our manifest just has a single module that
+//! installs the contents of a staging dir (binaries, and optionally an icon and
+//! desktop entry) into `/app`. The result is exported to a local repo and then
+//! turned into a single-file `.flatpak` bundle with `flatpak build-bundle`.
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use serde::Serialize;
+use tracing::info;
+
+use crate::errors::*;
+
+use super::appimage::DesktopEntry;
+
+/// The runtime Flatpak bundles run on
+pub const FLATPAK_RUNTIME: &str = "org.freedesktop.Platform";
+/// The SDK matching [`FLATPAK_RUNTIME`][]
+pub const FLATPAK_SDK: &str = "org.freedesktop.Sdk";
+/// The version of [`FLATPAK_RUNTIME`][] to use
+pub const FLATPAK_RUNTIME_VERSION: &str = "23.08";
+
+/// Info needed to build a Flatpak bundle
+#[derive(Debug, Clone)]
+pub struct FlatpakInstallerInfo {
+    /// Final file path of the bundle
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries (and icon) are staged in, which is the manifest's source
+    pub source_dir: Utf8PathBuf,
+    /// Scratch dir for the manifest and flatpak-builder's outputs
+    pub build_dir: Utf8PathBuf,
+    /// flatpak's name for the architecture (e.g. "x86_64")
+    pub arch: String,
+    /// The app-id (e.g. "io.github.axodotdev.axolotlsay")
+    pub app_id: String,
+    /// Names of the binaries in `source_dir`
+    pub binaries: Vec<String>,
+    /// The binary `flatpak run` should launch
+    pub main_binary: String,
+    /// The icon's file name in `source_dir` (if there is one), and the desktop entry to go with it
+    pub icon: Option<(String, DesktopEntry)>,
+}
+
+/// A flatpak-builder manifest
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FlatpakManifest {
+    /// The app-id
+    pub app_id: String,
+    /// The runtime to run on
+    pub runtime: String,
+    /// The version of the runtime
+    pub runtime_version: String,
+    /// The SDK to build with
+    pub sdk: String,
+    /// The binary to launch
+    pub command: String,
+    /// Sandbox permissions
+    pub finish_args: Vec<String>,
+    /// The things to build (just ours)
+    pub modules: Vec<FlatpakModule>,
+}
+
+/// A module in a flatpak-builder manifest
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FlatpakModule {
+    /// Name of the module
+    pub name: String,
+    /// How to build the module ("simple" just runs `build-commands`)
+    pub buildsystem: String,
+    /// The commands to install our files
+    pub build_commands: Vec<String>,
+    /// Where the files come from
+    pub sources: Vec<FlatpakSource>,
+}
+
+/// A source in a flatpak-builder manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct FlatpakSource {
+    /// The kind of source ("dir")
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The path of the dir
+    pub path: Utf8PathBuf,
+}
+
+impl FlatpakInstallerInfo {
+    /// The manifest for flatpak-builder
+    pub fn manifest(&self) -> FlatpakManifest {
+        let app_id = &self.app_id;
+        let mut build_commands = self
+            .binaries
+            .iter()
+            .map(|bin| format!("install -Dm755 {bin} /app/bin/{bin}"))
+            .collect::<Vec<_>>();
+        if let Some((icon, _)) = &self.icon {
+            // Exported icons and desktop entries have to be named after the app-id
+            let ext = icon.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("png");
+            let size = if ext == "svg" { "scalable" } else { "256x256" };
+            build_commands.push(format!(
+                "install -Dm644 {icon} /app/share/icons/hicolor/{size}/apps/{app_id}.{ext}"
+            ));
+            build_commands.push(format!(
+                "install -Dm644 {app_id}.desktop /app/share/applications/{app_id}.desktop"
+            ));
+        }
+        FlatpakManifest {
+            app_id: app_id.clone(),
+            runtime: FLATPAK_RUNTIME.to_owned(),
+            runtime_version: FLATPAK_RUNTIME_VERSION.to_owned(),
+            sdk: FLATPAK_SDK.to_owned(),
+            command: self.main_binary.clone(),
+            // Most apps we package are CLIs, which are useless if they can't see your files
+            finish_args: vec!["--filesystem=host".to_owned(), "--share=network".to_owned()],
+            modules: vec![FlatpakModule {
+                name: self.main_binary.clone(),
+                buildsystem: "simple".to_owned(),
+                build_commands,
+                sources: vec![FlatpakSource {
+                    kind: "dir".to_owned(),
+                    path: self.source_dir.clone(),
+                }],
+            }],
+        }
+    }
+
+    /// Build the Flatpak bundle
+    ///
+    /// This assumes the binaries and icon have already been copied into `source_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a Flatpak bundle: {}", self.file_path);
+
+        // Start with a clean scratch dir
+        if self.build_dir.exists() {
+            LocalAsset::remove_dir_all(&self.build_dir)?;
+        }
+        LocalAsset::create_dir_all(&self.build_dir)?;
+        if let Some((_, desktop)) = &self.icon {
+            let desktop_path = self.source_dir.join(format!("{}.desktop", self.app_id));
+            LocalAsset::write_new(&desktop.render(), desktop_path)?;
+        }
+        let manifest_path = self.build_dir.join(format!("{}.json", self.app_id));
+        let manifest = serde_json::to_string_pretty(&self.manifest())
+            .expect("failed to serialize a Flatpak manifest!?");
+        LocalAsset::write_new(&manifest, &manifest_path)?;
+
+        let bundle = self
+            .file_path
+            .file_name()
+            .expect("Flatpak bundle had no file name!?");
+        let repo = self.build_dir.join("repo");
+        let status = Command::new("flatpak-builder")
+            .arg(format!("--arch={}", self.arch))
+            .arg("--force-clean")
+            .arg(format!("--repo={repo}"))
+            .arg(self.build_dir.join("build"))
+            .arg(&manifest_path)
+            .status()
+            .map_err(|details| DistError::FlatpakBuilderNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::FlatpakBuild {
+                bundle: bundle.to_owned(),
+            });
+        }
+        let status = Command::new("flatpak")
+            .arg("build-bundle")
+            .arg(format!("--arch={}", self.arch))
+            .arg(&repo)
+            .arg(&self.file_path)
+            .arg(&self.app_id)
+            .status()
+            .map_err(|details| DistError::FlatpakBuilderNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::FlatpakBuild {
+                bundle: bundle.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Get flatpak's name for the architecture of a target triple (if it has one)
+pub fn flatpak_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let flatpak = match arch {
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        _ => return None,
+    };
+    Some(flatpak)
+}
+
+/// Derive an app-id from a repository URL (e.g. "io.github.axodotdev.axolotlsay")
+///
+/// Flatpak app-ids are reverse-DNS names whose elements can't start with digits, and
+/// only the last element may contain `-`.
+pub fn flatpak_app_id(repository_url: &str, app_name: &str) -> Option<String> {
+    let path = repository_url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .split_once("://")?
+        .1;
+    let mut parts = path.split('/');
+    let host = parts.next()?;
+    let owner = parts.next()?;
+    let prefix = match host {
+        "github.com" => "io.github",
+        "gitlab.com" => "io.gitlab",
+        "codeberg.org" => "page.codeberg",
+        _ => return None,
+    };
+    let element = |part: &str| {
+        let part = part.replace(['-', '.'], "_");
+        if part.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("_{part}")
+        } else {
+            part
+        }
+    };
+    let name = element(app_name).replace('_', "-");
+    Some(format!("{prefix}.{}.{name}", element(owner)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(icon: Option<&str>) -> FlatpakInstallerInfo {
+        FlatpakInstallerInfo {
+            file_path: "axolotlsay-x86_64-unknown-linux-gnu.flatpak".into(),
+            source_dir: "/dist/axolotlsay-x86_64-unknown-linux-gnu_flatpak".into(),
+            build_dir: "/dist/axolotlsay-x86_64-unknown-linux-gnu_flatpak_build".into(),
+            arch: "x86_64".to_owned(),
+            app_id: "io.github.axodotdev.axolotlsay".to_owned(),
+            binaries: vec!["axolotlsay".to_owned()],
+            main_binary: "axolotlsay".to_owned(),
+            icon: icon.map(|icon| {
+                (
+                    icon.to_owned(),
+                    DesktopEntry {
+                        name: "axolotlsay".to_owned(),
+                        exec: "axolotlsay".to_owned(),
+                        icon: "io.github.axodotdev.axolotlsay".to_owned(),
+                        comment: None,
+                    },
+                )
+            }),
+        }
+    }
+
+    #[test]
+    fn manifest_installs_binaries() {
+        let manifest = info(None).manifest();
+        assert_eq!(manifest.command, "axolotlsay");
+        assert_eq!(
+            manifest.modules[0].build_commands,
+            ["install -Dm755 axolotlsay /app/bin/axolotlsay"]
+        );
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["app-id"], "io.github.axodotdev.axolotlsay");
+        assert_eq!(json["runtime-version"], FLATPAK_RUNTIME_VERSION);
+        assert_eq!(json["modules"][0]["sources"][0]["type"], "dir");
+    }
+
+    #[test]
+    fn manifest_exports_icon() {
+        let manifest = info(Some("logo.svg")).manifest();
+        assert_eq!(
+            manifest.modules[0].build_commands[1..],
+            [
+                "install -Dm644 logo.svg /app/share/icons/hicolor/scalable/apps/io.github.axodotdev.axolotlsay.svg",
+                "install -Dm644 io.github.axodotdev.axolotlsay.desktop /app/share/applications/io.github.axodotdev.axolotlsay.desktop",
+            ]
+        );
+    }
+
+    #[test]
+    fn app_ids() {
+        assert_eq!(
+            flatpak_app_id("https://github.com/axodotdev/axolotlsay", "axolotlsay").as_deref(),
+            Some("io.github.axodotdev.axolotlsay")
+        );
+        assert_eq!(
+            flatpak_app_id("https://github.com/0xdead-beef/my_app.git", "my_app").as_deref(),
+            Some("io.github._0xdead_beef.my-app")
+        );
+        assert_eq!(flatpak_app_id("https://example.com/me/app", "app"), None);
+    }
+}
@@ -0,0 +1,331 @@
+//! FreeBSD pkg installer
+//!
+//! A FreeBSD package is just a tarball containing (in this order):
+//!
+//! * `+COMPACT_MANIFEST`: the package's metadata
+//! * `+MANIFEST`: the same metadata, plus a listing of the files with their checksums
+//! * the files to install, stored under their absolute paths (`/usr/local/bin/...`)
+//!
+//! The manifests are UCL, which is a superset of JSON, so we just write JSON.
+//! Like with debs, we build all of that ourselves so that packages can be made on any
+//! machine, not just ones with FreeBSD's `pkg` installed.
+
+use std::{collections::BTreeMap, fs::File};
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use tracing::info;
+
+use crate::{config::ChecksumStyle, errors::*};
+
+/// The prefix packages get installed under
+pub const FREEBSD_PKG_PREFIX: &str = "/usr/local";
+/// The directory binaries get installed to (relative to the package dir)
+pub const FREEBSD_PKG_BIN_DIR: &str = "usr/local/bin";
+
+/// Info needed to build a FreeBSD pkg
+#[derive(Debug, Clone)]
+pub struct FreebsdPkgInstallerInfo {
+    /// Final file path of the pkg
+    pub file_path: Utf8PathBuf,
+    /// Dir the files to install are put in (mirroring where they'll end up under `/`)
+    pub package_dir: Utf8PathBuf,
+    /// Names of the binaries in [`FREEBSD_PKG_BIN_DIR`][]
+    pub binaries: Vec<String>,
+    /// The metadata for the manifest
+    pub manifest: FreebsdPkgManifest,
+}
+
+/// The metadata of a pkg's `+MANIFEST`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FreebsdPkgManifest {
+    /// Name of the package
+    pub name: String,
+    /// Version of the package
+    pub version: String,
+    /// The "port" the package claims to come from (e.g. "misc/axolotlsay")
+    pub origin: String,
+    /// A one-line description of the package
+    pub comment: String,
+    /// The full description of the package
+    pub desc: String,
+    /// Who maintains the package
+    pub maintainer: String,
+    /// The package's homepage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub www: Option<String>,
+    /// The platforms the package can be installed on (e.g. "FreeBSD:*:amd64")
+    pub abi: String,
+    /// Where the package's files get installed
+    pub prefix: String,
+    /// How to interpret `licenses` ("single", "or", or "and")
+    pub licenselogic: String,
+    /// The licenses of the package
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<String>,
+    /// The categories of the package
+    pub categories: Vec<String>,
+    /// Other packages this one needs at runtime
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub deps: BTreeMap<String, FreebsdPkgDep>,
+}
+
+/// A dependency of a pkg
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FreebsdPkgDep {
+    /// The "port" the dependency comes from (e.g. "security/openssl")
+    pub origin: String,
+    /// The version of the dependency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// The parts of `+MANIFEST` that only exist once the files do
+#[derive(Serialize)]
+struct FreebsdPkgContents<'a> {
+    #[serde(flatten)]
+    manifest: &'a FreebsdPkgManifest,
+    flatsize: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files: Option<&'a BTreeMap<String, String>>,
+}
+
+impl FreebsdPkgManifest {
+    /// Render the manifest
+    ///
+    /// `files` maps the absolute path of each file to its checksum, and is left out
+    /// for `+COMPACT_MANIFEST`.
+    pub fn render(&self, flatsize: u64, files: Option<&BTreeMap<String, String>>) -> String {
+        let contents = FreebsdPkgContents {
+            manifest: self,
+            flatsize,
+            files,
+        };
+        let mut out = serde_json::to_string_pretty(&contents)
+            .expect("failed to serialize a FreeBSD pkg manifest!?");
+        out.push('\n');
+        out
+    }
+}
+
+impl FreebsdPkgInstallerInfo {
+    /// Build the pkg
+    ///
+    /// This assumes the binaries have already been copied into `package_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a FreeBSD pkg: {}", self.file_path);
+
+        let mut flatsize = 0;
+        let mut files = BTreeMap::new();
+        let mut contents = vec![];
+        for bin in &self.binaries {
+            let src_path = self.package_dir.join(FREEBSD_PKG_BIN_DIR).join(bin);
+            let bytes = LocalAsset::load_bytes(&src_path)?;
+            let checksum = crate::generate_checksum(&ChecksumStyle::Sha256, &src_path)?;
+            let path = format!("{FREEBSD_PKG_PREFIX}/bin/{bin}");
+            flatsize += bytes.len() as u64;
+            // pkg prefixes checksums with their kind, 1 being hex-encoded sha256
+            files.insert(path.clone(), format!("1${checksum}"));
+            contents.push((path, 0o755, bytes));
+        }
+        let compact_manifest = self.manifest.render(flatsize, None);
+        let manifest = self.manifest.render(flatsize, Some(&files));
+
+        let mut entries = vec![
+            (
+                "+COMPACT_MANIFEST".to_owned(),
+                0o644,
+                compact_manifest.into_bytes(),
+            ),
+            ("+MANIFEST".to_owned(), 0o644, manifest.into_bytes()),
+        ];
+        entries.extend(contents);
+        write_pkg_tarball(&self.file_path, &entries).map_err(|details| DistError::Archive {
+            dest_path: self.file_path.clone(),
+            details,
+        })?;
+        Ok(())
+    }
+}
+
+/// Write out an xz tarball of the given (path, mode, contents) entries
+///
+/// pkg wants the files stored under their absolute paths, which the tar crate refuses
+/// to do, so we poke the paths into the headers ourselves. All the other metadata
+/// (mtime, owner) is normalized so the output is reproducible.
+fn write_pkg_tarball(
+    dest_path: &Utf8Path,
+    entries: &[(String, u32, Vec<u8>)],
+) -> std::io::Result<()> {
+    let file = File::create(dest_path)?;
+    let compressor = xz2::write::XzEncoder::new(file, 9);
+    let mut builder = tar::Builder::new(compressor);
+    for (path, mode, contents) in entries {
+        let mut header = tar::Header::new_ustar();
+        let name = &mut header.as_old_mut().name;
+        if path.len() > name.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("path too long for a FreeBSD pkg: {path}"),
+            ));
+        }
+        name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_size(contents.len() as u64);
+        header.set_mode(*mode);
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("root")?;
+        header.set_groupname("wheel")?;
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+        builder.append(&header, &contents[..])?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Get pkg's name for the architecture of a target triple (if it has one)
+pub fn freebsd_pkg_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let pkg = match arch {
+        "x86_64" => "amd64",
+        "i686" => "i386",
+        "aarch64" => "aarch64",
+        "armv7" => "armv7",
+        "powerpc64" => "powerpc64",
+        "powerpc64le" => "powerpc64le",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(pkg)
+}
+
+/// Convert a semver version to a pkg one
+///
+/// pkg versions can't contain `-` (it separates the name from the version in
+/// `name-version`).
+pub fn freebsd_pkg_version(version: &str) -> String {
+    version.replace('-', ".")
+}
+
+/// Convert an SPDX license expression to pkg's `licenselogic` and `licenses`
+///
+/// pkg can only express a flat list of licenses that are all ORed or all ANDed together,
+/// so anything fancier is kept verbatim as a single license.
+pub fn freebsd_pkg_licenses(license: &str) -> (&'static str, Vec<String>) {
+    let split = |sep: &str| {
+        license
+            .split(sep)
+            .map(|license| license.trim().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let has_or = license.contains(" OR ");
+    let has_and = license.contains(" AND ");
+    if license.contains('(') || (has_or && has_and) {
+        ("single", vec![license.to_owned()])
+    } else if has_or {
+        ("or", split(" OR "))
+    } else if has_and {
+        ("and", split(" AND "))
+    } else {
+        ("single", vec![license.to_owned()])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn manifest() -> FreebsdPkgManifest {
+        FreebsdPkgManifest {
+            name: "axolotlsay".to_owned(),
+            version: "0.1.0".to_owned(),
+            origin: "misc/axolotlsay".to_owned(),
+            comment: "a cool app".to_owned(),
+            desc: "a cool app".to_owned(),
+            maintainer: "axodotdev <hello@axo.dev>".to_owned(),
+            www: None,
+            abi: "FreeBSD:*:amd64".to_owned(),
+            prefix: FREEBSD_PKG_PREFIX.to_owned(),
+            licenselogic: "or".to_owned(),
+            licenses: vec!["MIT".to_owned(), "Apache-2.0".to_owned()],
+            categories: vec!["misc".to_owned()],
+            deps: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn builds_a_tarball_with_manifests_first() {
+        let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("cargo-dist-freebsd-pkg-{}", std::process::id()));
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        let package_dir = dir.join("package");
+        std::fs::create_dir_all(package_dir.join(FREEBSD_PKG_BIN_DIR)).unwrap();
+        std::fs::write(
+            package_dir.join(FREEBSD_PKG_BIN_DIR).join("axolotlsay"),
+            "binary!",
+        )
+        .unwrap();
+        let info = FreebsdPkgInstallerInfo {
+            file_path: dir.join("axolotlsay.pkg"),
+            package_dir,
+            binaries: vec!["axolotlsay".to_owned()],
+            manifest: manifest(),
+        };
+        info.build().unwrap();
+
+        let pkg = std::fs::read(&info.file_path).unwrap();
+        let mut archive = tar::Archive::new(xz2::read::XzDecoder::new(&pkg[..]));
+        let mut entries = vec![];
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = String::from_utf8(entry.path_bytes().into_owned()).unwrap();
+            let mode = entry.header().mode().unwrap();
+            let mut contents = String::new();
+            std::io::Read::read_to_string(&mut entry, &mut contents).unwrap();
+            entries.push((path, mode, contents));
+        }
+        let paths = entries
+            .iter()
+            .map(|(path, _, _)| path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "+COMPACT_MANIFEST",
+                "+MANIFEST",
+                "/usr/local/bin/axolotlsay"
+            ]
+        );
+        assert_eq!(entries[2].1, 0o755);
+        assert!(!entries[0].2.contains("\"files\""));
+        assert!(entries[1].2.contains("\"flatsize\": 7"));
+        assert!(entries[1].2.contains("\"/usr/local/bin/axolotlsay\": \"1$"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn versions_and_licenses() {
+        assert_eq!(freebsd_pkg_version("1.2.3"), "1.2.3");
+        assert_eq!(freebsd_pkg_version("1.2.3-beta.1"), "1.2.3.beta.1");
+        assert_eq!(
+            freebsd_pkg_licenses("MIT OR Apache-2.0"),
+            ("or", vec!["MIT".to_owned(), "Apache-2.0".to_owned()])
+        );
+        assert_eq!(
+            freebsd_pkg_licenses("MIT AND (Apache-2.0 OR BSD-3-Clause)"),
+            (
+                "single",
+                vec!["MIT AND (Apache-2.0 OR BSD-3-Clause)".to_owned()]
+            )
+        );
+        assert_eq!(freebsd_pkg_arch("x86_64-unknown-freebsd"), Some("amd64"));
+        assert_eq!(freebsd_pkg_arch("sparc64-unknown-freebsd"), None);
+    }
+}
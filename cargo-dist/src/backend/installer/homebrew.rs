@@ -1,7 +1,7 @@
 //! Code for generating installer.sh
 
 use axoasset::LocalAsset;
-use camino::Utf8PathBuf;
+use camino::Utf8Path;
 use serde::Serialize;
 
 use super::InstallerInfo;
@@ -32,14 +32,36 @@ pub struct HomebrewInstallerInfo {
     pub x86_64: Option<ExecutableZipFragment>,
     /// sha256 of AMD64 artifact
     pub x86_64_sha256: Option<String>,
+    /// The sha256 checksum artifact of the AMD64 artifact (if we're making one)
+    #[serde(skip)]
+    pub x86_64_sha256_artifact: Option<String>,
     /// ARM64 artifact
     pub arm64: Option<ExecutableZipFragment>,
     /// sha256 of ARM64 artifact
     pub arm64_sha256: Option<String>,
+    /// The sha256 checksum artifact of the ARM64 artifact (if we're making one)
+    #[serde(skip)]
+    pub arm64_sha256_artifact: Option<String>,
+    /// AMD64 Linux artifact
+    pub x86_64_linux: Option<ExecutableZipFragment>,
+    /// sha256 of AMD64 Linux artifact
+    pub x86_64_linux_sha256: Option<String>,
+    /// The sha256 checksum artifact of the AMD64 Linux artifact (if we're making one)
+    #[serde(skip)]
+    pub x86_64_linux_sha256_artifact: Option<String>,
+    /// ARM64 Linux artifact
+    pub arm64_linux: Option<ExecutableZipFragment>,
+    /// sha256 of ARM64 Linux artifact
+    pub arm64_linux_sha256: Option<String>,
+    /// The sha256 checksum artifact of the ARM64 Linux artifact (if we're making one)
+    #[serde(skip)]
+    pub arm64_linux_sha256_artifact: Option<String>,
     /// Generic installer info
     pub inner: InstallerInfo,
     /// Additional packages to specify as dependencies
     pub dependencies: Vec<String>,
+    /// Binaries to install to libexec instead of bin
+    pub libexec: Vec<String>,
 }
 
 pub(crate) fn write_homebrew_formula(
@@ -48,25 +70,217 @@ pub(crate) fn write_homebrew_formula(
     source_info: &HomebrewInstallerInfo,
 ) -> DistResult<()> {
     let mut info = source_info.clone();
+    fill_sha256s(&graph.dist_dir, &mut info)?;
 
-    // Generate sha256 as late as possible; the artifacts might not exist
-    // earlier to do that.
+    let script = render_homebrew_formula(templates, &info)?;
+    LocalAsset::write_new(&script, &info.inner.dest_path)?;
+    Ok(())
+}
+
+/// Fill in the sha256s of the formula's archives
+///
+/// This happens as late as possible, since the artifacts might not exist earlier.
+/// We prefer the archives' checksum files (in CI those are all we might have),
+/// and otherwise hash the archives ourselves.
+pub(crate) fn fill_sha256s(
+    dist_dir: &Utf8Path,
+    info: &mut HomebrewInstallerInfo,
+) -> DistResult<()> {
     if let Some(arm64_ref) = &info.arm64 {
-        let path = Utf8PathBuf::from(&graph.dist_dir).join(&arm64_ref.id);
-        if path.exists() {
-            let sha256 = generate_checksum(&crate::config::ChecksumStyle::Sha256, &path)?;
-            info.arm64_sha256 = Some(sha256);
-        }
+        info.arm64_sha256 = archive_sha256(dist_dir, &arm64_ref.id, &info.arm64_sha256_artifact)?;
     }
     if let Some(x86_64_ref) = &info.x86_64 {
-        let path = Utf8PathBuf::from(&graph.dist_dir).join(&x86_64_ref.id);
+        info.x86_64_sha256 =
+            archive_sha256(dist_dir, &x86_64_ref.id, &info.x86_64_sha256_artifact)?;
+    }
+    if let Some(arm64_ref) = &info.arm64_linux {
+        info.arm64_linux_sha256 =
+            archive_sha256(dist_dir, &arm64_ref.id, &info.arm64_linux_sha256_artifact)?;
+    }
+    if let Some(x86_64_ref) = &info.x86_64_linux {
+        info.x86_64_linux_sha256 =
+            archive_sha256(dist_dir, &x86_64_ref.id, &info.x86_64_linux_sha256_artifact)?;
+    }
+    Ok(())
+}
+
+/// Get the sha256 of an archive, from its checksum file if we have one
+fn archive_sha256(
+    dist_dir: &Utf8Path,
+    archive_id: &str,
+    checksum_id: &Option<String>,
+) -> DistResult<Option<String>> {
+    if let Some(checksum_id) = checksum_id {
+        let path = dist_dir.join(checksum_id);
         if path.exists() {
-            let sha256 = generate_checksum(&crate::config::ChecksumStyle::Sha256, &path)?;
-            info.x86_64_sha256 = Some(sha256);
+            // These are in sha256sum's `<checksum> *<path>` format
+            let contents = LocalAsset::load_string(&path)?;
+            if let Some(sha256) = contents.split_whitespace().next() {
+                return Ok(Some(sha256.to_owned()));
+            }
         }
     }
+    let path = dist_dir.join(archive_id);
+    if path.exists() {
+        let sha256 = generate_checksum(&crate::config::ChecksumStyle::Sha256, &path)?;
+        return Ok(Some(sha256));
+    }
+    Ok(None)
+}
 
-    let script = templates.render_file_to_clean_string(TEMPLATE_INSTALLER_RB, &info)?;
-    LocalAsset::write_new(&script, &info.inner.dest_path)?;
-    Ok(())
+pub(crate) fn render_homebrew_formula(
+    templates: &Templates,
+    info: &HomebrewInstallerInfo,
+) -> DistResult<String> {
+    templates.render_file_to_clean_string(TEMPLATE_INSTALLER_RB, info)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{JinjaInstallPathStrategy, ZipStyle};
+    use camino::Utf8PathBuf;
+
+    fn mock_formula(libexec: Vec<String>) -> HomebrewInstallerInfo {
+        let fragment = ExecutableZipFragment {
+            id: "axolotlsay-aarch64-apple-darwin.tar.gz".to_owned(),
+            target_triples: vec!["aarch64-apple-darwin".to_owned()],
+            binaries: vec!["axolotlsay".to_owned(), "axolotlsay-helper".to_owned()],
+            bin_aliases: Default::default(),
+            man_pages: vec![],
+            zip_style: ZipStyle::Tar(crate::config::CompressionImpl::Gzip),
+            checksum: None,
+        };
+        HomebrewInstallerInfo {
+            name: "axolotlsay".to_owned(),
+            formula_class: "Axolotlsay".to_owned(),
+            license: None,
+            homepage: None,
+            desc: None,
+            tap: None,
+            x86_64: Some(fragment.clone()),
+            x86_64_sha256: None,
+            x86_64_sha256_artifact: None,
+            arm64: Some(fragment.clone()),
+            arm64_sha256: None,
+            arm64_sha256_artifact: None,
+            x86_64_linux: None,
+            x86_64_linux_sha256: None,
+            x86_64_linux_sha256_artifact: None,
+            arm64_linux: None,
+            arm64_linux_sha256: None,
+            arm64_linux_sha256_artifact: None,
+            inner: InstallerInfo {
+                dest_path: Utf8PathBuf::from("axolotlsay.rb"),
+                app_name: "axolotlsay".to_owned(),
+                app_version: "1.0.0".to_owned(),
+                base_url: "https://example.com/releases".to_owned(),
+                artifacts: vec![fragment],
+                desc: "Install prebuilt binaries via Homebrew".to_owned(),
+                hint: "brew install axolotlsay".to_owned(),
+                install_path: JinjaInstallPathStrategy::CargoHome,
+                offline: false,
+                embedded_archives: None,
+            },
+            dependencies: vec![],
+            libexec,
+        }
+    }
+
+    #[test]
+    fn formula_installs_to_bin() {
+        let templates = Templates::new().unwrap();
+        let formula = render_homebrew_formula(&templates, &mock_formula(vec![])).unwrap();
+        assert!(formula.contains("    bin.install \"axolotlsay\", \"axolotlsay-helper\"\n"));
+        assert!(!formula.contains("libexec"));
+    }
+
+    #[test]
+    fn formula_installs_to_libexec() {
+        let templates = Templates::new().unwrap();
+        let info = mock_formula(vec!["axolotlsay-helper".to_owned()]);
+        let formula = render_homebrew_formula(&templates, &info).unwrap();
+        assert!(formula.contains(
+            "  def install\n    bin.install \"axolotlsay\"\n    libexec.install \"axolotlsay-helper\"\n    bin.write_exec_script libexec/\"axolotlsay-helper\"\n"
+        ));
+    }
+
+    #[test]
+    fn formula_wraps_aliased_libexec_binaries() {
+        let templates = Templates::new().unwrap();
+        let mut info = mock_formula(vec!["axolotlsay-helper".to_owned()]);
+        let fragment = info.arm64.as_mut().unwrap();
+        fragment
+            .bin_aliases
+            .insert("axolotlsay-helper".to_owned(), "axo-helper".to_owned());
+        info.x86_64 = info.arm64.clone();
+        let formula = render_homebrew_formula(&templates, &info).unwrap();
+        assert!(formula.contains(
+            "    libexec.install \"axolotlsay-helper\" => \"axo-helper\"\n    bin.write_exec_script libexec/\"axo-helper\"\n"
+        ));
+    }
+
+    #[test]
+    fn formula_installs_aliases() {
+        let templates = Templates::new().unwrap();
+        let mut info = mock_formula(vec![]);
+        let fragment = info.arm64.as_mut().unwrap();
+        fragment
+            .bin_aliases
+            .insert("axolotlsay".to_owned(), "axo".to_owned());
+        info.x86_64 = info.arm64.clone();
+        let formula = render_homebrew_formula(&templates, &info).unwrap();
+        assert!(formula.contains(
+            "  def install\n    bin.install \"axolotlsay-helper\"\n    bin.install \"axolotlsay\" => \"axo\"\n"
+        ));
+    }
+
+    #[test]
+    fn formula_uses_checksum_files() {
+        let dist_dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .unwrap()
+            .join(format!("cargo-dist-homebrew-{}", std::process::id()));
+        std::fs::create_dir_all(&dist_dir).unwrap();
+        let mut info = mock_formula(vec![]);
+        let arm64_id = "axolotlsay-aarch64-apple-darwin.tar.gz";
+        let x86_64_id = "axolotlsay-x86_64-apple-darwin.tar.gz";
+        info.arm64.as_mut().unwrap().id = arm64_id.to_owned();
+        info.x86_64.as_mut().unwrap().id = x86_64_id.to_owned();
+        info.arm64_sha256_artifact = Some(format!("{arm64_id}.sha256"));
+        info.x86_64_sha256_artifact = Some(format!("{x86_64_id}.sha256"));
+        // Only the checksums exist (like in CI), not the archives themselves
+        std::fs::write(
+            dist_dir.join(format!("{arm64_id}.sha256")),
+            format!("{} *{arm64_id}\n", "a".repeat(64)),
+        )
+        .unwrap();
+        std::fs::write(
+            dist_dir.join(format!("{x86_64_id}.sha256")),
+            format!("{} *{x86_64_id}\n", "b".repeat(64)),
+        )
+        .unwrap();
+
+        fill_sha256s(&dist_dir, &mut info).unwrap();
+        assert_eq!(info.arm64_sha256, Some("a".repeat(64)));
+        assert_eq!(info.x86_64_sha256, Some("b".repeat(64)));
+
+        let templates = Templates::new().unwrap();
+        let formula = render_homebrew_formula(&templates, &info).unwrap();
+        assert!(formula.contains(&format!("sha256 \"{}\"", "a".repeat(64))));
+        assert!(formula.contains(&format!("sha256 \"{}\"", "b".repeat(64))));
+    }
+
+    #[test]
+    fn formula_installs_per_os() {
+        let templates = Templates::new().unwrap();
+        let mut info = mock_formula(vec![]);
+        let mut linux = info.x86_64.clone().unwrap();
+        linux.id = "axolotlsay-x86_64-unknown-linux-gnu.tar.gz".to_owned();
+        linux.binaries = vec!["axolotlsay".to_owned()];
+        info.x86_64_linux = Some(linux);
+        let formula = render_homebrew_formula(&templates, &info).unwrap();
+        assert!(formula.contains(
+            "  def install\n    if OS.mac?\n      bin.install \"axolotlsay\", \"axolotlsay-helper\"\n    else\n      bin.install \"axolotlsay\"\n    end\n"
+        ));
+    }
 }
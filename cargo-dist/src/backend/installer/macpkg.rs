@@ -0,0 +1,155 @@
+//! macOS pkg installer
+//!
+//! `pkgbuild` turns a dir of binaries into a component package that installs them
+//! to the install location, and `productbuild` wraps that up in the flat "product
+//! archive" users double-click (signing it, if we have an identity to sign with).
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use tracing::info;
+
+use crate::errors::*;
+
+/// Where pkgs install binaries if mac-pkg-install-location isn't set
+pub const MAC_PKG_INSTALL_LOCATION: &str = "/usr/local/bin";
+
+/// Info needed to build a pkg
+#[derive(Debug, Clone)]
+pub struct MacPkgInstallerInfo {
+    /// Final file path of the pkg
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries are staged in, which becomes the install location
+    pub package_dir: Utf8PathBuf,
+    /// Scratch dir for the component package
+    pub build_dir: Utf8PathBuf,
+    /// The identifier of the package (e.g. "dev.axo.axolotlsay")
+    pub identifier: String,
+    /// Version of the package
+    pub version: String,
+    /// Where the binaries get installed
+    pub install_location: String,
+    /// The identity to sign the pkg with (if any)
+    pub sign_identity: Option<String>,
+}
+
+impl MacPkgInstallerInfo {
+    fn component_path(&self) -> Utf8PathBuf {
+        self.build_dir.join(format!("{}.pkg", self.identifier))
+    }
+
+    /// The arguments to pkgbuild, which builds the component package
+    pub fn pkgbuild_args(&self) -> Vec<String> {
+        vec![
+            "--root".to_owned(),
+            self.package_dir.to_string(),
+            "--identifier".to_owned(),
+            self.identifier.clone(),
+            "--version".to_owned(),
+            self.version.clone(),
+            "--install-location".to_owned(),
+            self.install_location.clone(),
+            self.component_path().to_string(),
+        ]
+    }
+
+    /// The arguments to productbuild, which builds the final pkg
+    pub fn productbuild_args(&self) -> Vec<String> {
+        let mut args = vec!["--package".to_owned(), self.component_path().to_string()];
+        if let Some(identity) = &self.sign_identity {
+            args.push("--sign".to_owned());
+            args.push(identity.clone());
+        }
+        args.push(self.file_path.to_string());
+        args
+    }
+
+    /// Build the pkg
+    ///
+    /// This assumes the binaries have already been copied into `package_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a macOS pkg: {}", self.file_path);
+
+        // Start with a clean scratch dir
+        if self.build_dir.exists() {
+            LocalAsset::remove_dir_all(&self.build_dir)?;
+        }
+        LocalAsset::create_dir_all(&self.build_dir)?;
+
+        let pkg = self.file_path.file_name().expect("pkg had no file name!?");
+        for (tool, args) in [
+            ("pkgbuild", self.pkgbuild_args()),
+            ("productbuild", self.productbuild_args()),
+        ] {
+            let status = Command::new(tool).args(args).status().map_err(|details| {
+                DistError::PkgbuildNotFound {
+                    tool: tool.to_owned(),
+                    details,
+                }
+            })?;
+            if !status.success() {
+                return Err(DistError::MacPkgBuild {
+                    tool: tool.to_owned(),
+                    pkg: pkg.to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(sign_identity: Option<&str>) -> MacPkgInstallerInfo {
+        MacPkgInstallerInfo {
+            file_path: "/dist/axolotlsay-aarch64-apple-darwin.pkg".into(),
+            package_dir: "/dist/axolotlsay-aarch64-apple-darwin_pkg".into(),
+            build_dir: "/dist/axolotlsay-aarch64-apple-darwin_pkg_build".into(),
+            identifier: "dev.axo.axolotlsay".to_owned(),
+            version: "0.2.1".to_owned(),
+            install_location: MAC_PKG_INSTALL_LOCATION.to_owned(),
+            sign_identity: sign_identity.map(|i| i.to_owned()),
+        }
+    }
+
+    #[test]
+    fn pkgbuild_installs_to_location() {
+        assert_eq!(
+            info(None).pkgbuild_args(),
+            [
+                "--root",
+                "/dist/axolotlsay-aarch64-apple-darwin_pkg",
+                "--identifier",
+                "dev.axo.axolotlsay",
+                "--version",
+                "0.2.1",
+                "--install-location",
+                "/usr/local/bin",
+                "/dist/axolotlsay-aarch64-apple-darwin_pkg_build/dev.axo.axolotlsay.pkg",
+            ]
+        );
+    }
+
+    #[test]
+    fn productbuild_signs_if_asked() {
+        let component = "/dist/axolotlsay-aarch64-apple-darwin_pkg_build/dev.axo.axolotlsay.pkg";
+        let pkg = "/dist/axolotlsay-aarch64-apple-darwin.pkg";
+        assert_eq!(
+            info(None).productbuild_args(),
+            ["--package", component, pkg]
+        );
+        assert_eq!(
+            info(Some("Developer ID Installer: Axo (ABCDE12345)")).productbuild_args(),
+            [
+                "--package",
+                component,
+                "--sign",
+                "Developer ID Installer: Axo (ABCDE12345)",
+                pkg,
+            ]
+        );
+    }
+}
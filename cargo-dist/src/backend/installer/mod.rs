@@ -2,23 +2,52 @@
 //!
 //! In the future this might get split up into submodules.
 
-use camino::Utf8PathBuf;
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
 use serde::Serialize;
 
 use crate::{
-    config::{JinjaInstallPathStrategy, ZipStyle},
+    config::{ChecksumStyle, JinjaInstallPathStrategy, ZipStyle},
+    errors::DistResult,
+    generate_checksum,
+    tasks::SortedMap,
     TargetTriple,
 };
 
+use self::app::AppBundleInstallerInfo;
+use self::appimage::AppImageInstallerInfo;
+use self::deb::DebInstallerInfo;
+use self::dmg::DmgInstallerInfo;
+use self::flatpak::FlatpakInstallerInfo;
+use self::freebsd::FreebsdPkgInstallerInfo;
 use self::homebrew::HomebrewInstallerInfo;
+use self::macpkg::MacPkgInstallerInfo;
 use self::msi::MsiInstallerInfo;
 use self::npm::NpmInstallerInfo;
+use self::nsis::NsisInstallerInfo;
+use self::rpm::RpmInstallerInfo;
+use self::scoop::ScoopInstallerInfo;
+use self::shell::ShellSuiteInstallerInfo;
+use self::snap::SnapInstallerInfo;
+use self::winget::WinGetInstallerInfo;
 
+pub mod app;
+pub mod appimage;
+pub mod deb;
+pub mod dmg;
+pub mod flatpak;
+pub mod freebsd;
 pub mod homebrew;
+pub mod macpkg;
 pub mod msi;
 pub mod npm;
+pub mod nsis;
 pub mod powershell;
+pub mod rpm;
+pub mod scoop;
 pub mod shell;
+pub mod snap;
+pub mod winget;
 
 /// A kind of an installer
 #[derive(Debug, Clone)]
@@ -26,14 +55,42 @@ pub mod shell;
 pub enum InstallerImpl {
     /// shell installer script
     Shell(InstallerInfo),
+    /// shell installer script that can install any app in the announcement
+    ShellSuite(ShellSuiteInstallerInfo),
+    /// tarball of every platform's archives with a shell installer that needs no network
+    OfflineBundle(InstallerInfo),
     /// powershell installer script
     Powershell(InstallerInfo),
     /// npm installer package
     Npm(NpmInstallerInfo),
     /// Homebrew formula
     Homebrew(HomebrewInstallerInfo),
+    /// Scoop manifest
+    Scoop(ScoopInstallerInfo),
     /// Windows msi installer
     Msi(MsiInstallerInfo),
+    /// Debian deb package
+    Deb(DebInstallerInfo),
+    /// rpm package
+    Rpm(RpmInstallerInfo),
+    /// Linux AppImage
+    AppImage(AppImageInstallerInfo),
+    /// FreeBSD pkg
+    FreebsdPkg(FreebsdPkgInstallerInfo),
+    /// Flatpak bundle
+    Flatpak(FlatpakInstallerInfo),
+    /// snap package
+    Snap(SnapInstallerInfo),
+    /// macOS pkg installer
+    MacPkg(MacPkgInstallerInfo),
+    /// macOS disk image
+    Dmg(DmgInstallerInfo),
+    /// macOS .app bundle
+    AppBundle(AppBundleInstallerInfo),
+    /// NSIS installer
+    Nsis(NsisInstallerInfo),
+    /// WinGet manifest
+    WinGet(WinGetInstallerInfo),
 }
 
 /// Generic info about an installer
@@ -55,6 +112,14 @@ pub struct InstallerInfo {
     pub hint: String,
     /// Where to install binaries
     pub install_path: JinjaInstallPathStrategy,
+    /// Whether the archives sit next to the installer, instead of being downloaded
+    pub offline: bool,
+    /// The archives carried inside the installer, instead of being downloaded (id => base64)
+    ///
+    /// None if the installer downloads them. The contents are only read in when the
+    /// installer is written (see [`shell::fill_embedded_archives`][]), since the
+    /// archives don't exist any earlier.
+    pub embedded_archives: Option<SortedMap<String, String>>,
 }
 
 /// A fake fragment of an ExecutableZip artifact for installers
@@ -66,6 +131,50 @@ pub struct ExecutableZipFragment {
     pub target_triples: Vec<TargetTriple>,
     /// The binaries the artifact contains (name, assumed at root)
     pub binaries: Vec<String>,
+    /// Binaries that should be installed under another name (name in the archive => installed name)
+    pub bin_aliases: SortedMap<String, String>,
+    /// The man pages the artifact contains (relative to its root), for installers that install them
+    pub man_pages: Vec<String>,
     /// The style of zip this is
     pub zip_style: ZipStyle,
+    /// The archive's checksum (if we're making one)
+    pub checksum: Option<FragmentChecksum>,
+}
+
+/// The checksum of an ExecutableZipFragment, for installers that verify what they download
+#[derive(Debug, Clone, Serialize)]
+pub struct FragmentChecksum {
+    /// The checksumming algorithm
+    pub style: ChecksumStyle,
+    /// The id of the archive's checksum artifact
+    pub artifact: String,
+    /// The checksum itself (only known once the archive is built, see [`fill_checksums`][])
+    pub value: Option<String>,
+}
+
+/// Fill in the checksums of the archives an installer fetches
+///
+/// This happens as late as possible, since the archives might not exist earlier.
+/// We prefer the archives' checksum files (in CI those are all we might have),
+/// and otherwise hash the archives ourselves. If neither exists, the checksum
+/// is left unknown, and the installer won't verify that archive.
+pub(crate) fn fill_checksums(
+    dist_dir: &Utf8Path,
+    artifacts: &mut [ExecutableZipFragment],
+) -> DistResult<()> {
+    for artifact in artifacts {
+        let Some(checksum) = &mut artifact.checksum else {
+            continue;
+        };
+        let checksum_path = dist_dir.join(&checksum.artifact);
+        let archive_path = dist_dir.join(&artifact.id);
+        if checksum_path.exists() {
+            // These are in sha256sum's `<checksum> *<path>` format
+            let contents = LocalAsset::load_string(&checksum_path)?;
+            checksum.value = contents.split_whitespace().next().map(|s| s.to_owned());
+        } else if archive_path.exists() {
+            checksum.value = Some(generate_checksum(&checksum.style, &archive_path)?);
+        }
+    }
+    Ok(())
 }
@@ -1,10 +1,13 @@
 //! msi installer
 
-use axoasset::LocalAsset;
 use camino::Utf8PathBuf;
 use tracing::info;
 
-use crate::{backend::diff_files, config, errors::*};
+use crate::{
+    backend::{diff_files, write_if_changed},
+    config,
+    errors::*,
+};
 
 const METADATA_WIX: &str = "wix";
 const WIX_GUID_KEYS: &[&str] = &["upgrade-guid", "path-guid"];
@@ -16,6 +19,8 @@ pub struct MsiInstallerInfo {
     pub pkg_spec: String,
     /// Binaries we'll be baking into the msi
     pub target: String,
+    /// The cargo profile the binaries were built with
+    pub profile: String,
     /// Final file path of the msi
     pub file_path: Utf8PathBuf,
     /// Dir stuff goes to
@@ -39,8 +44,8 @@ impl MsiInstallerInfo {
         b.package(Some(&self.pkg_spec));
         // cargo-dist already did the build for us
         b.no_build(true);
-        // It built with the `dist` profile
-        b.profile(Some("dist"));
+        // It built with the build-profile (`dist` by default)
+        b.profile(Some(&self.profile));
         // It explicitly built with this --target
         b.target(Some(&self.target));
         // We want the output to go here
@@ -92,8 +97,11 @@ impl MsiInstallerInfo {
         let file = &self.wxs_path;
         let rendered = self.generate_wxs_string()?;
 
-        LocalAsset::write_new_all(&rendered, file)?;
-        eprintln!("generated msi definition to {}", file);
+        if write_if_changed(file, &rendered)? {
+            eprintln!("generated msi definition to {}", file);
+        } else {
+            eprintln!("msi definition at {} is already up to date", file);
+        }
 
         Ok(())
     }
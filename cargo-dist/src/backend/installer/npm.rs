@@ -6,8 +6,9 @@ use serde::Serialize;
 
 use super::InstallerInfo;
 use crate::{
-    backend::templates::{Templates, TEMPLATE_INSTALLER_NPM},
+    backend::templates::{Templates, TEMPLATE_INSTALLER_NPM, TEMPLATE_INSTALLER_NPM_RUN},
     errors::Result,
+    SortedMap,
 };
 
 /// Info about an npm installer
@@ -29,17 +30,41 @@ pub struct NpmInstallerInfo {
     pub npm_package_license: Option<String>,
     /// Array of keywords for this package
     pub npm_package_keywords: Option<Vec<String>>,
-    /// Name of the binary this package installs (without .exe extension)
-    pub bin: String,
+    /// Names of the binaries this package installs (without .exe extension)
+    pub bins: Vec<String>,
+    /// Names to expose binaries as, if not their own (bin name => command name)
+    pub bin_aliases: SortedMap<String, String>,
     /// Dir to build the package in
     pub package_dir: Utf8PathBuf,
     /// Generic installer info
     pub inner: InstallerInfo,
 }
 
+/// The context the run script of a binary is rendered with
+#[derive(Debug, Serialize)]
+struct NpmRunContext<'a> {
+    /// Name of the binary to run (without .exe extension)
+    bin: &'a str,
+}
+
+/// Render the files of the npm package (relative path => contents)
+pub fn render_npm_project(
+    templates: &Templates,
+    info: &NpmInstallerInfo,
+) -> Result<SortedMap<Utf8PathBuf, String>> {
+    let mut results = templates.render_dir_to_clean_strings(TEMPLATE_INSTALLER_NPM, info)?;
+    // Each binary gets its own script for package.json's "bin" to point at
+    for bin in &info.bins {
+        let rendered = templates
+            .render_file_to_clean_string(TEMPLATE_INSTALLER_NPM_RUN, &NpmRunContext { bin })?;
+        results.insert(Utf8PathBuf::from(format!("run-{bin}.js")), rendered);
+    }
+    Ok(results)
+}
+
 pub(crate) fn write_npm_project(templates: &Templates, info: &NpmInstallerInfo) -> Result<()> {
     let zip_dir = &info.package_dir;
-    let results = templates.render_dir_to_clean_strings(TEMPLATE_INSTALLER_NPM, info)?;
+    let results = render_npm_project(templates, info)?;
     for (relpath, rendered) in results {
         LocalAsset::write_new_all(&rendered, zip_dir.join(relpath))?;
     }
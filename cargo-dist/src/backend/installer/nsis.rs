@@ -0,0 +1,165 @@
+//! NSIS installer
+//!
+//! We render an `.nsi` script for a classic windows "next, next, finish" wizard
+//! installer, and have `makensis` compile it into an `.exe` with the binaries baked in.
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use serde::Serialize;
+use tracing::info;
+
+use crate::{
+    backend::templates::{Templates, TEMPLATE_INSTALLER_NSI},
+    errors::*,
+};
+
+/// Info needed to build an NSIS installer
+#[derive(Debug, Clone)]
+pub struct NsisInstallerInfo {
+    /// Final file path of the installer
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries are staged in (and the .nsi script is written to)
+    pub package_dir: Utf8PathBuf,
+    /// Name of the app
+    pub app_name: String,
+    /// Version of the app
+    pub version: String,
+    /// Who publishes the app
+    pub publisher: Option<String>,
+    /// The app's homepage
+    pub homepage: Option<String>,
+    /// Names of the binaries in `package_dir`
+    pub binaries: Vec<String>,
+    /// The NSIS variable for the dir to install to a subdir of (e.g. "$LOCALAPPDATA\Programs")
+    pub install_root: String,
+    /// Whether to install for every user (to Program Files) instead of just this one
+    pub program_files: bool,
+    /// Whether to add the install dir to PATH
+    pub modify_path: bool,
+}
+
+/// The values the .nsi template is rendered with, escaped for NSIS strings
+#[derive(Debug, Serialize)]
+struct NsisTemplateContext {
+    file_path: String,
+    app_name: String,
+    version: String,
+    publisher: Option<String>,
+    homepage: Option<String>,
+    binaries: Vec<String>,
+    install_dir: String,
+    program_files: bool,
+    modify_path: bool,
+    reg_root: &'static str,
+}
+
+impl NsisInstallerInfo {
+    /// Render the .nsi script
+    pub fn render_nsi(&self, templates: &Templates) -> DistResult<String> {
+        let app_name = nsis_str(&self.app_name);
+        let context = NsisTemplateContext {
+            file_path: nsis_str(self.file_path.as_str()),
+            install_dir: format!("{}\\{app_name}", self.install_root),
+            app_name,
+            version: nsis_str(&self.version),
+            publisher: self.publisher.as_deref().map(nsis_str),
+            homepage: self.homepage.as_deref().map(nsis_str),
+            binaries: self.binaries.iter().map(|bin| nsis_str(bin)).collect(),
+            program_files: self.program_files,
+            modify_path: self.modify_path,
+            // Per-user installs can only touch the per-user registry
+            reg_root: if self.program_files { "HKLM" } else { "HKCU" },
+        };
+        templates.render_file_to_clean_string(TEMPLATE_INSTALLER_NSI, &context)
+    }
+
+    /// Build the installer
+    ///
+    /// This assumes the binaries have already been copied into `package_dir`.
+    pub fn build(&self, templates: &Templates) -> DistResult<()> {
+        info!("building an NSIS installer: {}", self.file_path);
+
+        let nsi_path = self.package_dir.join("installer.nsi");
+        LocalAsset::write_new(&self.render_nsi(templates)?, &nsi_path)?;
+
+        let installer = self
+            .file_path
+            .file_name()
+            .expect("installer had no file name!?");
+        // makensis resolves `File` paths relative to the script's dir
+        let status = Command::new("makensis")
+            .arg("-V2")
+            .arg(&nsi_path)
+            .status()
+            .map_err(|details| DistError::MakensisNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::Makensis {
+                installer: installer.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Escape a value for use in a double-quoted NSIS string
+fn nsis_str(val: &str) -> String {
+    val.replace('$', "$$").replace('"', "$\\\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(program_files: bool, modify_path: bool) -> NsisInstallerInfo {
+        NsisInstallerInfo {
+            file_path: "C:\\dist\\axolotlsay-x86_64-pc-windows-msvc-setup.exe".into(),
+            package_dir: "C:\\dist\\axolotlsay-x86_64-pc-windows-msvc_nsis".into(),
+            app_name: "axolotlsay".to_owned(),
+            version: "0.2.1".to_owned(),
+            publisher: Some("axodotdev".to_owned()),
+            homepage: None,
+            binaries: vec!["axolotlsay.exe".to_owned()],
+            install_root: if program_files {
+                "$PROGRAMFILES64".to_owned()
+            } else {
+                "$LOCALAPPDATA\\Programs".to_owned()
+            },
+            program_files,
+            modify_path,
+        }
+    }
+
+    #[test]
+    fn per_user_install() {
+        let templates = Templates::new().unwrap();
+        let nsi = info(false, false).render_nsi(&templates).unwrap();
+        assert!(nsi.contains("InstallDir \"$LOCALAPPDATA\\Programs\\axolotlsay\"\n"));
+        assert!(nsi.contains("RequestExecutionLevel user\n"));
+        assert!(nsi.contains("    File \"axolotlsay.exe\"\n"));
+        assert!(nsi.contains("    Delete \"$INSTDIR\\axolotlsay.exe\"\n"));
+        assert!(nsi.contains("\"Publisher\" \"axodotdev\"\n"));
+        assert!(!nsi.contains("URLInfoAbout"));
+        assert!(!nsi.contains("HKLM"));
+        assert!(!nsi.contains("\"Path\""));
+    }
+
+    #[test]
+    fn program_files_install_with_path() {
+        let templates = Templates::new().unwrap();
+        let nsi = info(true, true).render_nsi(&templates).unwrap();
+        assert!(nsi.contains("InstallDir \"$PROGRAMFILES64\\axolotlsay\"\n"));
+        assert!(nsi.contains("RequestExecutionLevel admin\n"));
+        assert!(nsi.contains("ReadRegStr $0 HKLM \"${ENV_KEY}\" \"Path\"\n"));
+        assert!(nsi.contains(
+            "!define ENV_KEY \"SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment\"\n"
+        ));
+        assert!(!nsi.contains("HKCU"));
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(nsis_str("say \"$5\""), "say $\\\"$$5$\\\"");
+    }
+}
@@ -5,15 +5,24 @@ use axoasset::LocalAsset;
 use crate::{
     backend::templates::{Templates, TEMPLATE_INSTALLER_PS1},
     errors::DistResult,
+    tasks::DistGraph,
 };
 
-use super::InstallerInfo;
+use super::{fill_checksums, InstallerInfo};
+
+/// Render the installer.ps1 for an app
+pub fn render_install_ps_script(templates: &Templates, info: &InstallerInfo) -> DistResult<String> {
+    templates.render_file_to_clean_string(TEMPLATE_INSTALLER_PS1, info)
+}
 
 pub(crate) fn write_install_ps_script(
     templates: &Templates,
-    info: &InstallerInfo,
+    graph: &DistGraph,
+    source_info: &InstallerInfo,
 ) -> DistResult<()> {
-    let script = templates.render_file_to_clean_string(TEMPLATE_INSTALLER_PS1, info)?;
+    let mut info = source_info.clone();
+    fill_checksums(&graph.dist_dir, &mut info.artifacts)?;
+    let script = render_install_ps_script(templates, &info)?;
     LocalAsset::write_new(&script, &info.dest_path)?;
     Ok(())
 }
@@ -0,0 +1,235 @@
+//! rpm installer
+//!
+//! Unlike debs, rpms have a fairly involved binary format, so we synthesize a spec
+//! file and let the user's `rpmbuild` do the heavy lifting.
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use tracing::info;
+
+use crate::errors::*;
+
+/// The directory binaries get installed to
+pub const RPM_BIN_DIR: &str = "usr/bin";
+/// The directory man pages get installed to
+pub const RPM_MAN_DIR: &str = "usr/share/man";
+
+/// Info needed to build an rpm
+#[derive(Debug, Clone)]
+pub struct RpmInstallerInfo {
+    /// Final file path of the rpm
+    pub file_path: Utf8PathBuf,
+    /// Dir the files to install are put in (mirroring where they'll end up under `/`)
+    pub package_dir: Utf8PathBuf,
+    /// Scratch dir for rpmbuild (its `_topdir`)
+    pub build_dir: Utf8PathBuf,
+    /// Names of the binaries in [`RPM_BIN_DIR`][]
+    pub binaries: Vec<String>,
+    /// Man pages to install (relative to `package_dir`, so under [`RPM_MAN_DIR`][])
+    pub man_pages: Vec<Utf8PathBuf>,
+    /// The metadata for the spec file
+    pub spec: RpmSpec,
+}
+
+/// The metadata of an rpm's spec file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpmSpec {
+    /// Name of the package
+    pub name: String,
+    /// Version of the package
+    pub version: String,
+    /// rpm's name for the architecture (e.g. "x86_64")
+    pub arch: String,
+    /// A brief description of the package
+    pub summary: String,
+    /// The license of the package
+    pub license: Option<String>,
+    /// The package's homepage
+    pub url: Option<String>,
+    /// Who made the package
+    pub packager: Option<String>,
+    /// Other packages this one needs at runtime
+    pub requires: Vec<String>,
+    /// Other packages that are installed alongside this one by default
+    pub recommends: Vec<String>,
+}
+
+impl RpmInstallerInfo {
+    /// Render the spec file
+    pub fn render_spec(&self) -> String {
+        let spec = &self.spec;
+        let mut out = String::new();
+        out.push_str(&format!("Name: {}\n", spec.name));
+        out.push_str(&format!("Version: {}\n", spec.version));
+        out.push_str("Release: 1\n");
+        // Summary must be a single line, the rest goes in %description
+        let summary = spec.summary.trim().lines().next().unwrap_or(&spec.name);
+        out.push_str(&format!("Summary: {summary}\n"));
+        // License is mandatory, so say so if we don't know it
+        let license = spec.license.as_deref().unwrap_or("Unknown");
+        out.push_str(&format!("License: {license}\n"));
+        if let Some(url) = &spec.url {
+            out.push_str(&format!("URL: {url}\n"));
+        }
+        if let Some(packager) = &spec.packager {
+            out.push_str(&format!("Packager: {packager}\n"));
+        }
+        for requires in &spec.requires {
+            out.push_str(&format!("Requires: {requires}\n"));
+        }
+        for recommends in &spec.recommends {
+            out.push_str(&format!("Recommends: {recommends}\n"));
+        }
+        // We ship the binaries exactly as cargo built them, so don't let rpmbuild
+        // strip them or split out a debuginfo package.
+        out.push_str("\n%global debug_package %{nil}\n");
+        out.push_str("%global __os_install_post %{nil}\n");
+        out.push_str(&format!("\n%description\n{}\n", spec.summary.trim()));
+        out.push_str("\n%install\n");
+        out.push_str(&format!("mkdir -p %{{buildroot}}/{RPM_BIN_DIR}\n"));
+        for bin in &self.binaries {
+            out.push_str(&format!(
+                "cp -p {} %{{buildroot}}/{RPM_BIN_DIR}/{bin}\n",
+                self.package_dir.join(RPM_BIN_DIR).join(bin)
+            ));
+        }
+        for page in &self.man_pages {
+            out.push_str(&format!(
+                "install -D -p -m 0644 {} %{{buildroot}}/{page}\n",
+                self.package_dir.join(page)
+            ));
+        }
+        out.push_str("\n%files\n");
+        for bin in &self.binaries {
+            out.push_str(&format!("%attr(0755, root, root) /{RPM_BIN_DIR}/{bin}\n"));
+        }
+        for page in &self.man_pages {
+            out.push_str(&format!("%attr(0644, root, root) /{page}\n"));
+        }
+        out
+    }
+
+    /// Build the rpm
+    ///
+    /// This assumes the binaries have already been copied into `package_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building an rpm: {}", self.file_path);
+
+        // Start with a clean scratch dir
+        if self.build_dir.exists() {
+            LocalAsset::remove_dir_all(&self.build_dir)?;
+        }
+        LocalAsset::create_dir_all(&self.build_dir)?;
+        let spec_path = self.build_dir.join(format!("{}.spec", self.spec.name));
+        LocalAsset::write_new(&self.render_spec(), &spec_path)?;
+
+        let rpm_dir = self.file_path.parent().expect("rpm had no parent dir!?");
+        let rpm_name = self.file_path.file_name().expect("rpm had no file name!?");
+        let status = Command::new("rpmbuild")
+            .arg("-bb")
+            .arg("--target")
+            .arg(&self.spec.arch)
+            .arg("--define")
+            .arg(format!("_topdir {}", self.build_dir))
+            .arg("--define")
+            .arg(format!("_rpmdir {rpm_dir}"))
+            .arg("--define")
+            .arg(format!("_rpmfilename {rpm_name}"))
+            .arg(&spec_path)
+            .status()
+            .map_err(|details| DistError::RpmbuildNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::Rpmbuild {
+                rpm: rpm_name.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Get rpm's name for the architecture of a target triple (if it has one)
+pub fn rpm_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let rpm = match arch {
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        "i686" => "i686",
+        "armv7" => "armv7hl",
+        "powerpc64le" => "ppc64le",
+        "s390x" => "s390x",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(rpm)
+}
+
+/// Convert a semver version to an rpm one
+///
+/// rpm doesn't allow `-` in versions, but sorts `~` *before* the empty string,
+/// which is how semver treats prereleases.
+pub fn rpm_version(version: &str) -> String {
+    version.replacen('-', "~", 1).replace('-', "_")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spec_file() {
+        let info = RpmInstallerInfo {
+            file_path: "/dist/axolotlsay.rpm".into(),
+            package_dir: "/dist/axolotlsay_rpm".into(),
+            build_dir: "/dist/axolotlsay_rpm_build".into(),
+            binaries: vec!["axolotlsay".to_owned()],
+            man_pages: vec![],
+            spec: RpmSpec {
+                name: "axolotlsay".to_owned(),
+                version: "0.1.0".to_owned(),
+                arch: "x86_64".to_owned(),
+                summary: "a cool app".to_owned(),
+                license: Some("MIT OR Apache-2.0".to_owned()),
+                url: None,
+                packager: Some("axodotdev <hello@axo.dev>".to_owned()),
+                requires: vec!["openssl-libs >= 3.0".to_owned()],
+                recommends: vec!["bash-completion".to_owned()],
+            },
+        };
+        assert_eq!(
+            info.render_spec(),
+            "Name: axolotlsay
+Version: 0.1.0
+Release: 1
+Summary: a cool app
+License: MIT OR Apache-2.0
+Packager: axodotdev <hello@axo.dev>
+Requires: openssl-libs >= 3.0
+Recommends: bash-completion
+
+%global debug_package %{nil}
+%global __os_install_post %{nil}
+
+%description
+a cool app
+
+%install
+mkdir -p %{buildroot}/usr/bin
+cp -p /dist/axolotlsay_rpm/usr/bin/axolotlsay %{buildroot}/usr/bin/axolotlsay
+
+%files
+%attr(0755, root, root) /usr/bin/axolotlsay
+"
+        );
+    }
+
+    #[test]
+    fn versions_and_arches() {
+        assert_eq!(rpm_version("1.2.3"), "1.2.3");
+        assert_eq!(rpm_version("1.2.3-beta.1"), "1.2.3~beta.1");
+        assert_eq!(rpm_arch("x86_64-unknown-linux-musl"), Some("x86_64"));
+        assert_eq!(rpm_arch("armv7-unknown-linux-gnueabihf"), Some("armv7hl"));
+        assert_eq!(rpm_arch("sparc64-unknown-linux-gnu"), None);
+    }
+}
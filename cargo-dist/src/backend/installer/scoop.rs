@@ -0,0 +1,337 @@
+//! Code for generating Scoop manifests
+//!
+//! A Scoop manifest is a json file listing the archive to download for each windows
+//! architecture (and its hash), along with an `autoupdate` section, which tells a
+//! Scoop bucket how to find the next release's archives so it can bump the manifest
+//! on its own.
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use serde::Serialize;
+
+use crate::{
+    config::ChecksumStyle, errors::DistResult, generate_checksum, tasks::DistGraph,
+    tasks::SortedMap,
+};
+
+use super::{ExecutableZipFragment, InstallerInfo};
+
+/// Info about a Scoop manifest
+#[derive(Debug, Clone)]
+pub struct ScoopInstallerInfo {
+    /// The contents of the manifest
+    pub manifest: ScoopManifest,
+    /// The archive (in the dist dir) each architecture downloads, for computing hashes
+    pub archives: SortedMap<String, String>,
+    /// Generic installer info
+    pub inner: InstallerInfo,
+}
+
+/// The contents of a Scoop manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopManifest {
+    /// The version of the app
+    pub version: String,
+    /// A brief description of the app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The app's homepage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// The app's license
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// The archive to install for each architecture
+    pub architecture: SortedMap<String, ScoopArch>,
+    /// How a bucket should check for new versions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkver: Option<ScoopCheckver>,
+    /// How a bucket should update the manifest for new versions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autoupdate: Option<ScoopAutoupdate>,
+}
+
+/// What Scoop should install for one architecture
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopArch {
+    /// Where to download the archive
+    pub url: String,
+    /// The sha256 of the archive (computed when generating, if it was built)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// The binaries in the archive to put on the PATH
+    pub bin: Vec<ScoopBin>,
+}
+
+/// A binary Scoop should put on the PATH
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ScoopBin {
+    /// The binary, under its own name
+    Path(String),
+    /// The binary, and the name to run it as (without `.exe`)
+    Alias(String, String),
+}
+
+/// How Scoop should check for new versions
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopCheckver {
+    /// The GitHub repository whose latest release is the latest version
+    pub github: String,
+}
+
+/// The `autoupdate` section of a Scoop manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopAutoupdate {
+    /// URL templates for each architecture we have an archive for
+    pub architecture: SortedMap<String, ScoopAutoupdateArch>,
+    /// Where to find the checksum for `$url` (if we make checksums)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<ScoopAutoupdateHash>,
+}
+
+/// The autoupdate info for one architecture
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopAutoupdateArch {
+    /// The download URL, with the version replaced by `$version`
+    pub url: String,
+}
+
+/// How Scoop should find the hash of an autoupdated archive
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopAutoupdateHash {
+    /// The URL of the checksum file, relative to `$url`
+    pub url: String,
+}
+
+/// Compute the autoupdate section for the given windows archives
+///
+/// Returns None if the archives' URLs don't contain the version, because then
+/// there's no way to tell Scoop where the next release will be.
+pub fn scoop_autoupdate(
+    base_url: &str,
+    version: &str,
+    artifacts: &[ExecutableZipFragment],
+    checksum: ChecksumStyle,
+) -> Option<ScoopAutoupdate> {
+    let mut architecture = SortedMap::new();
+    for artifact in artifacts {
+        for target in &artifact.target_triples {
+            let Some(arch) = scoop_arch(target) else {
+                continue;
+            };
+            let url = format!("{base_url}/{}", artifact.id);
+            let url = version_template(&url, version)?;
+            architecture.insert(arch.to_owned(), ScoopAutoupdateArch { url });
+        }
+    }
+    if architecture.is_empty() {
+        return None;
+    }
+    let hash = match checksum {
+        ChecksumStyle::False => None,
+        checksum => Some(ScoopAutoupdateHash {
+            url: format!("$url.{}", checksum.ext()),
+        }),
+    };
+    Some(ScoopAutoupdate { architecture, hash })
+}
+
+/// Compute what Scoop should install for each architecture of the given windows archives
+pub fn scoop_architectures(
+    base_url: &str,
+    artifacts: &[ExecutableZipFragment],
+) -> (SortedMap<String, ScoopArch>, SortedMap<String, String>) {
+    let mut architecture = SortedMap::new();
+    let mut archives = SortedMap::new();
+    for artifact in artifacts {
+        for target in &artifact.target_triples {
+            let Some(arch) = scoop_arch(target) else {
+                continue;
+            };
+            architecture.insert(
+                arch.to_owned(),
+                ScoopArch {
+                    url: format!("{base_url}/{}", artifact.id),
+                    hash: None,
+                    bin: artifact
+                        .binaries
+                        .iter()
+                        .map(|bin| match artifact.bin_aliases.get(bin) {
+                            Some(alias) => ScoopBin::Alias(
+                                bin.clone(),
+                                alias.strip_suffix(".exe").unwrap_or(alias).to_owned(),
+                            ),
+                            None => ScoopBin::Path(bin.clone()),
+                        })
+                        .collect(),
+                },
+            );
+            archives.insert(arch.to_owned(), artifact.id.clone());
+        }
+    }
+    (architecture, archives)
+}
+
+pub(crate) fn write_scoop_manifest(
+    graph: &DistGraph,
+    source_info: &ScoopInstallerInfo,
+) -> DistResult<()> {
+    let mut info = source_info.clone();
+
+    // Generate sha256 as late as possible; the archives might not exist
+    // earlier to do that.
+    for (arch, entry) in &mut info.manifest.architecture {
+        let Some(archive) = info.archives.get(arch) else {
+            continue;
+        };
+        let path = Utf8PathBuf::from(&graph.dist_dir).join(archive);
+        if path.exists() {
+            entry.hash = Some(generate_checksum(&ChecksumStyle::Sha256, &path)?);
+        }
+    }
+
+    let manifest = render_scoop_manifest(&info.manifest);
+    LocalAsset::write_new(&manifest, &info.inner.dest_path)?;
+    Ok(())
+}
+
+fn render_scoop_manifest(manifest: &ScoopManifest) -> String {
+    let mut out =
+        serde_json::to_string_pretty(manifest).expect("failed to serialize a Scoop manifest!?");
+    out.push('\n');
+    out
+}
+
+/// Replace every instance of the version in a URL with Scoop's `$version` variable
+///
+/// Returns None if the version doesn't appear in the URL at all.
+pub fn version_template(url: &str, version: &str) -> Option<String> {
+    if version.is_empty() || !url.contains(version) {
+        return None;
+    }
+    Some(url.replace(version, "$version"))
+}
+
+/// Get the name Scoop uses for the architecture of a windows target
+pub fn scoop_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("windows") {
+        None
+    } else if target.starts_with("x86_64") {
+        Some("64bit")
+    } else if target.starts_with("i686") {
+        Some("32bit")
+    } else if target.starts_with("aarch64") {
+        Some("arm64")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::ZipStyle;
+
+    const BASE_URL: &str = "https://github.com/axodotdev/axolotlsay/releases/download/v1.2.3";
+
+    fn fragment(target: &str) -> ExecutableZipFragment {
+        ExecutableZipFragment {
+            id: format!("axolotlsay-v1.2.3-{target}.zip"),
+            target_triples: vec![target.to_owned()],
+            binaries: vec!["axolotlsay.exe".to_owned()],
+            bin_aliases: Default::default(),
+            man_pages: vec![],
+            zip_style: ZipStyle::Zip,
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn autoupdate_substitutes_version() {
+        let artifacts = [
+            fragment("x86_64-pc-windows-msvc"),
+            fragment("aarch64-pc-windows-msvc"),
+        ];
+        let autoupdate =
+            scoop_autoupdate(BASE_URL, "1.2.3", &artifacts, ChecksumStyle::Sha256).unwrap();
+        assert_eq!(
+            autoupdate.architecture["64bit"].url,
+            "https://github.com/axodotdev/axolotlsay/releases/download/v$version/axolotlsay-v$version-x86_64-pc-windows-msvc.zip"
+        );
+        assert_eq!(
+            autoupdate.architecture["arm64"].url,
+            "https://github.com/axodotdev/axolotlsay/releases/download/v$version/axolotlsay-v$version-aarch64-pc-windows-msvc.zip"
+        );
+        assert_eq!(autoupdate.hash.unwrap().url, "$url.sha256");
+    }
+
+    #[test]
+    fn autoupdate_needs_versioned_urls() {
+        let artifacts = [ExecutableZipFragment {
+            id: "axolotlsay-x86_64-pc-windows-msvc.zip".to_owned(),
+            ..fragment("x86_64-pc-windows-msvc")
+        }];
+        let base_url = "https://example.com/latest";
+        assert!(scoop_autoupdate(base_url, "1.2.3", &artifacts, ChecksumStyle::Sha256).is_none());
+    }
+
+    #[test]
+    fn manifest_lists_windows_archives() {
+        let artifacts = [
+            fragment("x86_64-pc-windows-msvc"),
+            fragment("x86_64-unknown-linux-gnu"),
+        ];
+        let (architecture, archives) = scoop_architectures(BASE_URL, &artifacts);
+        assert_eq!(
+            archives["64bit"],
+            "axolotlsay-v1.2.3-x86_64-pc-windows-msvc.zip"
+        );
+        let manifest = ScoopManifest {
+            version: "1.2.3".to_owned(),
+            description: None,
+            homepage: None,
+            license: Some("MIT".to_owned()),
+            architecture,
+            checkver: None,
+            autoupdate: None,
+        };
+        assert_eq!(
+            render_scoop_manifest(&manifest),
+            r#"{
+  "version": "1.2.3",
+  "license": "MIT",
+  "architecture": {
+    "64bit": {
+      "url": "https://github.com/axodotdev/axolotlsay/releases/download/v1.2.3/axolotlsay-v1.2.3-x86_64-pc-windows-msvc.zip",
+      "bin": [
+        "axolotlsay.exe"
+      ]
+    }
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn manifest_aliases_binaries() {
+        let artifacts = [ExecutableZipFragment {
+            bin_aliases: [("axolotlsay.exe".to_owned(), "axo.exe".to_owned())]
+                .into_iter()
+                .collect(),
+            ..fragment("x86_64-pc-windows-msvc")
+        }];
+        let (architecture, _) = scoop_architectures(BASE_URL, &artifacts);
+        assert_eq!(
+            serde_json::to_value(&architecture["64bit"].bin).unwrap(),
+            serde_json::json!([["axolotlsay.exe", "axo"]])
+        );
+    }
+
+    #[test]
+    fn autoupdate_ignores_non_windows() {
+        let artifacts = [fragment("x86_64-unknown-linux-gnu")];
+        assert!(scoop_autoupdate(BASE_URL, "1.2.3", &artifacts, ChecksumStyle::False).is_none());
+    }
+}
@@ -1,19 +1,132 @@
 //! Code for generating installer.sh
 
 use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
 
 use crate::{
-    backend::templates::{Templates, TEMPLATE_INSTALLER_SH},
-    errors::DistResult,
+    backend::templates::{Templates, TEMPLATE_INSTALLER_SH, TEMPLATE_INSTALLER_SUITE_SH},
+    errors::{DistError, DistResult},
+    tasks::DistGraph,
 };
 
-use super::InstallerInfo;
+use super::{fill_checksums, InstallerInfo};
+
+/// Info about an install.sh that installs any of the apps in a suite
+#[derive(Debug, Clone)]
+pub struct ShellSuiteInstallerInfo {
+    /// The path to generate the installer at
+    pub dest_path: Utf8PathBuf,
+    /// Description of the installer (a good heading)
+    pub desc: String,
+    /// Hint for how to run the installer
+    pub hint: String,
+    /// The shell installers of each app, which the suite installer dispatches to
+    pub apps: Vec<InstallerInfo>,
+}
+
+/// The context install-suite.sh is rendered with
+#[derive(Debug, Clone, Serialize)]
+struct ShellSuiteContext {
+    apps: Vec<ShellSuiteApp>,
+}
+
+/// An app in install-suite.sh
+#[derive(Debug, Clone, Serialize)]
+struct ShellSuiteApp {
+    /// The name users select the app by
+    name: String,
+    /// The version of the app (display only)
+    version: String,
+    /// The app's own installer.sh
+    script: String,
+}
+
+/// Render the install.sh for an app
+pub fn render_install_sh_script(templates: &Templates, info: &InstallerInfo) -> DistResult<String> {
+    templates.render_file_to_clean_string(TEMPLATE_INSTALLER_SH, info)
+}
 
 pub(crate) fn write_install_sh_script(
     templates: &Templates,
-    info: &InstallerInfo,
+    graph: &DistGraph,
+    source_info: &InstallerInfo,
+) -> DistResult<()> {
+    let mut info = source_info.clone();
+    fill_checksums(&graph.dist_dir, &mut info.artifacts)?;
+    fill_embedded_archives(&graph.dist_dir, &mut info)?;
+    let script = render_install_sh_script(templates, &info)?;
+    LocalAsset::write_new(&script, &info.dest_path)?;
+    Ok(())
+}
+
+/// Read in the archives an installer embeds (if it embeds them)
+///
+/// They're base64-encoded (in lines short enough that every `base64` can decode them),
+/// so they can go in a heredoc in the script.
+pub(crate) fn fill_embedded_archives(
+    dist_dir: &Utf8Path,
+    info: &mut InstallerInfo,
+) -> DistResult<()> {
+    use base64::Engine;
+
+    let Some(embedded) = &mut info.embedded_archives else {
+        return Ok(());
+    };
+    for artifact in &info.artifacts {
+        let archive_path = dist_dir.join(&artifact.id);
+        if !archive_path.exists() {
+            return Err(DistError::MissingEmbeddedArchive {
+                installer: info.dest_path.file_name().unwrap_or_default().to_owned(),
+                archive_path,
+            });
+        }
+        let bytes = LocalAsset::load_bytes(&archive_path)?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+        let lines = encoded
+            .as_bytes()
+            .chunks(76)
+            // base64 is always ascii
+            .map(|line| std::str::from_utf8(line).expect("base64 wasn't ascii!?"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        embedded.insert(artifact.id.clone(), lines);
+    }
+    Ok(())
+}
+
+/// Render the install.sh for a suite of apps
+pub fn render_install_suite_sh_script(
+    templates: &Templates,
+    info: &ShellSuiteInstallerInfo,
+) -> DistResult<String> {
+    let apps = info
+        .apps
+        .iter()
+        .map(|app| {
+            Ok(ShellSuiteApp {
+                name: app.app_name.clone(),
+                version: app.app_version.clone(),
+                script: templates.render_file_to_clean_string(TEMPLATE_INSTALLER_SH, app)?,
+            })
+        })
+        .collect::<DistResult<Vec<_>>>()?;
+    let script = templates
+        .render_file_to_clean_string(TEMPLATE_INSTALLER_SUITE_SH, &ShellSuiteContext { apps })?;
+    Ok(script)
+}
+
+pub(crate) fn write_install_suite_sh_script(
+    templates: &Templates,
+    graph: &DistGraph,
+    source_info: &ShellSuiteInstallerInfo,
 ) -> DistResult<()> {
-    let script = templates.render_file_to_clean_string(TEMPLATE_INSTALLER_SH, info)?;
+    let mut info = source_info.clone();
+    for app in &mut info.apps {
+        fill_checksums(&graph.dist_dir, &mut app.artifacts)?;
+        fill_embedded_archives(&graph.dist_dir, app)?;
+    }
+    let script = render_install_suite_sh_script(templates, &info)?;
     LocalAsset::write_new(&script, &info.dest_path)?;
     Ok(())
 }
@@ -0,0 +1,225 @@
+//! Snap installer
+//!
+//! We generate a `snapcraft.yaml` whose only part dumps the binaries we already
+//! built into the snap, and let the user's `snapcraft` pack it up.
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use tracing::info;
+
+use crate::config::{SnapConfinement, SnapGrade};
+use crate::errors::*;
+
+/// The directory binaries get put in within the snap
+pub const SNAP_BIN_DIR: &str = "bin";
+/// The base snap (runtime) our snaps are built on
+pub const SNAP_BASE: &str = "core22";
+
+/// Info needed to build a snap
+#[derive(Debug, Clone)]
+pub struct SnapInstallerInfo {
+    /// Final file path of the snap
+    pub file_path: Utf8PathBuf,
+    /// Dir the snapcraft project lives in (binaries go in [`SNAP_BIN_DIR`][])
+    pub package_dir: Utf8PathBuf,
+    /// Names of the binaries in [`SNAP_BIN_DIR`][]
+    pub binaries: Vec<String>,
+    /// The metadata for snapcraft.yaml
+    pub spec: SnapcraftSpec,
+}
+
+/// The metadata of a snapcraft.yaml
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapcraftSpec {
+    /// Name of the snap
+    pub name: String,
+    /// Version of the snap
+    pub version: String,
+    /// snap's name for the architecture (e.g. "amd64")
+    pub arch: String,
+    /// A brief description of the snap
+    pub summary: String,
+    /// A longer description of the snap
+    pub description: String,
+    /// The license of the snap
+    pub license: Option<String>,
+    /// How much the snap is sandboxed
+    pub confinement: SnapConfinement,
+    /// How production-ready the snap is
+    pub grade: SnapGrade,
+}
+
+impl SnapInstallerInfo {
+    /// Render snapcraft.yaml
+    pub fn render_snapcraft_yaml(&self) -> String {
+        let spec = &self.spec;
+        let mut out = String::new();
+        out.push_str(&format!("name: {}\n", spec.name));
+        out.push_str(&format!("base: {SNAP_BASE}\n"));
+        out.push_str(&format!("version: {}\n", yaml_str(&spec.version)));
+        // The summary must be one line of at most 78 characters
+        let summary = spec.summary.trim().lines().next().unwrap_or(&spec.name);
+        let summary = if summary.chars().count() > 78 {
+            let mut short = summary.chars().take(75).collect::<String>();
+            short.push_str("...");
+            short
+        } else {
+            summary.to_owned()
+        };
+        out.push_str(&format!("summary: {}\n", yaml_str(&summary)));
+        out.push_str("description: |\n");
+        for line in spec.description.trim().lines() {
+            if line.trim().is_empty() {
+                out.push('\n');
+            } else {
+                out.push_str(&format!("  {line}\n"));
+            }
+        }
+        if let Some(license) = &spec.license {
+            out.push_str(&format!("license: {}\n", yaml_str(license)));
+        }
+        out.push_str(&format!("grade: {}\n", spec.grade));
+        out.push_str(&format!("confinement: {}\n", spec.confinement));
+        out.push_str("architectures:\n");
+        out.push_str(&format!("  - build-on: [{}]\n", spec.arch));
+        out.push_str(&format!("    build-for: [{}]\n", spec.arch));
+        out.push_str("apps:\n");
+        for bin in &self.binaries {
+            out.push_str(&format!("  {}:\n", snap_name(bin)));
+            out.push_str(&format!("    command: {SNAP_BIN_DIR}/{bin}\n"));
+        }
+        // The binaries are already built, so the only part just copies them in
+        out.push_str("parts:\n");
+        out.push_str(&format!("  {}:\n", spec.name));
+        out.push_str("    plugin: dump\n");
+        out.push_str("    source: .\n");
+        out.push_str("    stage:\n");
+        out.push_str(&format!("      - {SNAP_BIN_DIR}\n"));
+        out
+    }
+
+    /// Build the snap
+    ///
+    /// This assumes the binaries have already been copied into `package_dir`.
+    pub fn build(&self) -> DistResult<()> {
+        info!("building a snap: {}", self.file_path);
+
+        let snap_dir = self.package_dir.join("snap");
+        LocalAsset::create_dir_all(&snap_dir)?;
+        LocalAsset::write_new(
+            &self.render_snapcraft_yaml(),
+            snap_dir.join("snapcraft.yaml"),
+        )?;
+
+        // There's nothing to compile, so skip the build VM and pack on this machine
+        let snap_name = self.file_path.file_name().expect("snap had no file name!?");
+        let status = Command::new("snapcraft")
+            .arg("pack")
+            .arg("--destructive-mode")
+            .arg("--output")
+            .arg(&self.file_path)
+            .current_dir(&self.package_dir)
+            .status()
+            .map_err(|details| DistError::SnapcraftNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::Snapcraft {
+                snap: snap_name.to_owned(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Quote a string for yaml
+fn yaml_str(val: &str) -> String {
+    format!("'{}'", val.replace('\'', "''"))
+}
+
+/// Get snap's name for the architecture of a target triple (if it has one)
+pub fn snap_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let snap = match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "armv7" => "armhf",
+        "i686" => "i386",
+        "powerpc64le" => "ppc64el",
+        "s390x" => "s390x",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(snap)
+}
+
+/// Convert a name to a valid snap (or snap app) name
+///
+/// Snap names can only contain lowercase letters, digits, and `-`.
+pub fn snap_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(description: &str) -> SnapInstallerInfo {
+        SnapInstallerInfo {
+            file_path: "axolotlsay-x86_64-unknown-linux-gnu.snap".into(),
+            package_dir: "/dist/axolotlsay-x86_64-unknown-linux-gnu_snap".into(),
+            binaries: vec!["axolotlsay".to_owned(), "axolotl_helper".to_owned()],
+            spec: SnapcraftSpec {
+                name: "axolotlsay".to_owned(),
+                version: "0.2.1".to_owned(),
+                arch: "amd64".to_owned(),
+                summary: "a cool app".to_owned(),
+                description: description.to_owned(),
+                license: Some("MIT OR Apache-2.0".to_owned()),
+                confinement: SnapConfinement::default(),
+                grade: SnapGrade::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn snapcraft_yaml() {
+        assert_eq!(
+            info("a cool app\n\nit's got axolotls").render_snapcraft_yaml(),
+            "name: axolotlsay
+base: core22
+version: '0.2.1'
+summary: 'a cool app'
+description: |
+  a cool app
+
+  it's got axolotls
+license: 'MIT OR Apache-2.0'
+grade: stable
+confinement: strict
+architectures:
+  - build-on: [amd64]
+    build-for: [amd64]
+apps:
+  axolotlsay:
+    command: bin/axolotlsay
+  axolotl-helper:
+    command: bin/axolotl_helper
+parts:
+  axolotlsay:
+    plugin: dump
+    source: .
+    stage:
+      - bin
+"
+        );
+    }
+
+    #[test]
+    fn names_and_arches() {
+        assert_eq!(snap_name("My_App.rs"), "my-app-rs");
+        assert_eq!(snap_arch("x86_64-unknown-linux-gnu"), Some("amd64"));
+        assert_eq!(snap_arch("aarch64-unknown-linux-musl"), Some("arm64"));
+        assert_eq!(snap_arch("mips-unknown-linux-gnu"), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Code for generating WinGet manifests
+//!
+//! A WinGet package version is described by (at least) three manifests, which all
+//! live together in `manifests/<letter>/<Publisher>/<Name>/<version>/` of
+//! microsoft/winget-pkgs:
+//!
+//! * `<id>.yaml`: the "version" manifest, which ties the others together
+//! * `<id>.installer.yaml`: where to download the installers (our msis) and their sha256s
+//! * `<id>.locale.en-US.yaml`: the human-readable metadata (name, license, ...)
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use serde::Serialize;
+
+use crate::{
+    backend::templates::{
+        Templates, TEMPLATE_INSTALLER_WINGET_INSTALLER, TEMPLATE_INSTALLER_WINGET_LOCALE,
+        TEMPLATE_INSTALLER_WINGET_VERSION,
+    },
+    errors::{DistError, DistResult},
+};
+
+/// Which of the manifests of a WinGet package version to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WinGetManifestKind {
+    /// `<id>.yaml`
+    Version,
+    /// `<id>.installer.yaml`
+    Installer,
+    /// `<id>.locale.en-US.yaml`
+    Locale,
+}
+
+impl WinGetManifestKind {
+    /// The file name of this kind of manifest for the given package identifier
+    pub fn file_name(&self, package_identifier: &str) -> String {
+        match self {
+            WinGetManifestKind::Version => format!("{package_identifier}.yaml"),
+            WinGetManifestKind::Installer => format!("{package_identifier}.installer.yaml"),
+            WinGetManifestKind::Locale => format!("{package_identifier}.locale.en-US.yaml"),
+        }
+    }
+}
+
+/// Info about a WinGet manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct WinGetInstallerInfo {
+    /// The path to generate the manifest at
+    pub dest_path: Utf8PathBuf,
+    /// Which manifest this is
+    pub kind: WinGetManifestKind,
+    /// The package identifier (e.g. "Axodotdev.Axolotlsay")
+    pub package_identifier: String,
+    /// The version of the package
+    pub package_version: String,
+    /// The application's name
+    pub package_name: String,
+    /// Who publishes the package
+    pub publisher: String,
+    /// The application's license (WinGet requires one)
+    pub license: String,
+    /// A brief description of the application
+    pub short_description: String,
+    /// The URL to the application's homepage
+    pub package_url: Option<String>,
+    /// The msis the package can be installed from
+    pub installers: Vec<WinGetInstaller>,
+}
+
+/// An msi that a WinGet package can be installed from
+#[derive(Debug, Clone, Serialize)]
+pub struct WinGetInstaller {
+    /// WinGet's name for the msi's architecture (e.g. "x64")
+    pub architecture: String,
+    /// Where to download the msi
+    pub url: String,
+    /// The sha256 checksum file that was made for the msi
+    pub checksum_path: Utf8PathBuf,
+    /// The sha256 of the msi (read from `checksum_path` when generating)
+    pub sha256: Option<String>,
+}
+
+impl WinGetInstallerInfo {
+    /// The path the manifest should end up at in microsoft/winget-pkgs
+    ///
+    /// The version is left as `${version}` for the publish job to fill in.
+    pub fn repo_path(&self) -> String {
+        let first = self
+            .package_identifier
+            .chars()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let id_path = self.package_identifier.replace('.', "/");
+        let file_name = self.kind.file_name(&self.package_identifier);
+        format!("manifests/{first}/{id_path}/${{version}}/{file_name}")
+    }
+}
+
+pub(crate) fn write_winget_manifest(
+    templates: &Templates,
+    source_info: &WinGetInstallerInfo,
+) -> DistResult<()> {
+    let mut info = source_info.clone();
+
+    // Read the checksums as late as possible; the msis won't exist until then
+    for installer in &mut info.installers {
+        let checksum = LocalAsset::load_string(&installer.checksum_path).map_err(|_| {
+            DistError::WinGetMissingChecksum {
+                checksum_path: installer.checksum_path.clone(),
+            }
+        })?;
+        // Checksum files are formatted like sha256sum's output: `<hash> *<file>`
+        let sha256 = checksum.split_whitespace().next().unwrap_or_default();
+        installer.sha256 = Some(sha256.to_ascii_uppercase());
+    }
+
+    let manifest = render_winget_manifest(templates, &info)?;
+    LocalAsset::write_new(&manifest, &info.dest_path)?;
+    Ok(())
+}
+
+fn render_winget_manifest(templates: &Templates, info: &WinGetInstallerInfo) -> DistResult<String> {
+    let template = match info.kind {
+        WinGetManifestKind::Version => TEMPLATE_INSTALLER_WINGET_VERSION,
+        WinGetManifestKind::Installer => TEMPLATE_INSTALLER_WINGET_INSTALLER,
+        WinGetManifestKind::Locale => TEMPLATE_INSTALLER_WINGET_LOCALE,
+    };
+    templates.render_file_to_clean_string(template, info)
+}
+
+/// Get WinGet's name for the architecture of a target triple (if it has one)
+pub fn winget_arch(target: &str) -> Option<&'static str> {
+    let arch = target.split('-').next()?;
+    let winget = match arch {
+        "x86_64" => "x64",
+        "i686" => "x86",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    Some(winget)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(kind: WinGetManifestKind) -> WinGetInstallerInfo {
+        WinGetInstallerInfo {
+            dest_path: "Axodotdev.Axolotlsay.yaml".into(),
+            kind,
+            package_identifier: "Axodotdev.Axolotlsay".to_owned(),
+            package_version: "0.1.0".to_owned(),
+            package_name: "axolotlsay".to_owned(),
+            publisher: "axodotdev".to_owned(),
+            license: "MIT OR Apache-2.0".to_owned(),
+            short_description: "a cool app: it says things".to_owned(),
+            package_url: None,
+            installers: vec![WinGetInstaller {
+                architecture: "x64".to_owned(),
+                url: "https://example.com/axolotlsay-x86_64-pc-windows-msvc.msi".to_owned(),
+                checksum_path: "axolotlsay-x86_64-pc-windows-msvc.msi.sha256".into(),
+                sha256: Some("ABCD".to_owned()),
+            }],
+        }
+    }
+
+    #[test]
+    fn installer_manifest() {
+        let templates = Templates::new().unwrap();
+        let manifest =
+            render_winget_manifest(&templates, &info(WinGetManifestKind::Installer)).unwrap();
+        assert_eq!(
+            manifest,
+            r#"# Generated by cargo-dist
+PackageIdentifier: "Axodotdev.Axolotlsay"
+PackageVersion: "0.1.0"
+InstallerType: wix
+Installers:
+- Architecture: "x64"
+  InstallerUrl: "https://example.com/axolotlsay-x86_64-pc-windows-msvc.msi"
+  InstallerSha256: "ABCD"
+ManifestType: installer
+ManifestVersion: 1.5.0
+"#
+        );
+    }
+
+    #[test]
+    fn locale_manifest_quotes_values() {
+        let templates = Templates::new().unwrap();
+        let manifest =
+            render_winget_manifest(&templates, &info(WinGetManifestKind::Locale)).unwrap();
+        assert!(manifest.contains("ShortDescription: \"a cool app: it says things\"\n"));
+        assert!(!manifest.contains("PackageUrl"));
+    }
+
+    #[test]
+    fn repo_paths() {
+        assert_eq!(
+            info(WinGetManifestKind::Locale).repo_path(),
+            "manifests/a/Axodotdev/Axolotlsay/${version}/Axodotdev.Axolotlsay.locale.en-US.yaml"
+        );
+        assert_eq!(winget_arch("aarch64-pc-windows-msvc"), Some("arm64"));
+    }
+}
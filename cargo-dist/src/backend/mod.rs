@@ -1,14 +1,29 @@
 //! The backend of cargo-dist -- things it outputs
 
-use axoasset::SourceFile;
+use axoasset::{LocalAsset, SourceFile};
 use camino::Utf8Path;
 
 use crate::errors::{DistError, DistResult};
 
+pub mod binstall;
 pub mod ci;
+pub mod citation;
 pub mod installer;
 pub mod templates;
 
+/// Write a file we generated, unless it already has the same contents
+///
+/// Rewriting an identical file would needlessly bump its mtime (and could change its
+/// line endings out from under git's crlf settings), so "identical" means whatever
+/// [`diff_files`][] accepts. Returns whether the file was written.
+pub fn write_if_changed(file: &Utf8Path, new_file_contents: &str) -> DistResult<bool> {
+    if file.exists() && diff_files(file, new_file_contents).is_ok() {
+        return Ok(false);
+    }
+    LocalAsset::write_new_all(new_file_contents, file)?;
+    Ok(true)
+}
+
 /// Check if the given file has the same contents we generated
 pub fn diff_files(existing_file: &Utf8Path, new_file_contents: &str) -> DistResult<()> {
     // FIXME: should we catch all errors, or only LocalAssetNotFound?
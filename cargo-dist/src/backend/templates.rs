@@ -15,12 +15,32 @@ pub type TemplateId = &'static str;
 pub const TEMPLATE_INSTALLER_PS1: TemplateId = "installer/installer.ps1";
 /// Template key for installer.sh
 pub const TEMPLATE_INSTALLER_SH: TemplateId = "installer/installer.sh";
+/// Template key for the install.sh that dispatches to each app's installer.sh
+pub const TEMPLATE_INSTALLER_SUITE_SH: TemplateId = "installer/installer-suite.sh";
+/// Template key for the NSIS installer script
+pub const TEMPLATE_INSTALLER_NSI: TemplateId = "installer/installer.nsi";
 /// Template key for Homebrew formula
 pub const TEMPLATE_INSTALLER_RB: TemplateId = "installer/homebrew.rb";
+/// Template key for the WinGet version manifest
+pub const TEMPLATE_INSTALLER_WINGET_VERSION: TemplateId = "installer/winget.version.yaml";
+/// Template key for the WinGet installer manifest
+pub const TEMPLATE_INSTALLER_WINGET_INSTALLER: TemplateId = "installer/winget.installer.yaml";
+/// Template key for the WinGet (default) locale manifest
+pub const TEMPLATE_INSTALLER_WINGET_LOCALE: TemplateId = "installer/winget.locale.yaml";
 /// Template key for the npm installer dir
 pub const TEMPLATE_INSTALLER_NPM: TemplateId = "installer/npm";
+/// Template key for the script an npm installer runs for one of its binaries
+pub const TEMPLATE_INSTALLER_NPM_RUN: TemplateId = "installer/npm-run.js";
+/// Template key for CITATION.cff release metadata
+pub const TEMPLATE_METADATA_CITATION: TemplateId = "metadata/citation.cff";
+/// Template key for the script that applies deltas
+pub const TEMPLATE_METADATA_APPLY_DELTA: TemplateId = "metadata/apply-delta.sh";
 /// Template key for the github ci.yml
 pub const TEMPLATE_CI_GITHUB: TemplateId = "ci/github_ci.yml";
+/// Template key for Gitea ci.yml
+pub const TEMPLATE_CI_GITEA: TemplateId = "ci/gitea_ci.yml";
+/// Template key for GitLab ci.yml
+pub const TEMPLATE_CI_GITLAB: TemplateId = "ci/gitlab_ci.yml";
 
 /// ID used to look up an environment in [`Templates::envs`][]
 type EnvId = &'static str;
@@ -310,9 +330,28 @@ mod test {
         let templates = Templates::new().unwrap();
 
         templates.get_template_file(TEMPLATE_INSTALLER_SH).unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_SUITE_SH)
+            .unwrap();
         templates.get_template_file(TEMPLATE_INSTALLER_RB).unwrap();
         templates.get_template_file(TEMPLATE_INSTALLER_PS1).unwrap();
+        templates.get_template_file(TEMPLATE_INSTALLER_NSI).unwrap();
         templates.get_template_dir(TEMPLATE_INSTALLER_NPM).unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_NPM_RUN)
+            .unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_WINGET_VERSION)
+            .unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_WINGET_INSTALLER)
+            .unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_WINGET_LOCALE)
+            .unwrap();
+        templates
+            .get_template_file(TEMPLATE_METADATA_CITATION)
+            .unwrap();
 
         templates.get_template_file(TEMPLATE_CI_GITHUB).unwrap();
     }
@@ -103,10 +103,33 @@ pub struct Cli {
     #[clap(long)]
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub tag: Option<String>,
+    /// The target triple to treat as the host, instead of the one cargo reports
+    ///
+    /// This is what gets built when no --target is given, which is useful in
+    /// containers where cargo reports a host you don't want to build for.
+    /// If left unspecified we will use the host-target in [workspace.metadata.dist],
+    /// or detect it from `cargo -vV`.
+    #[clap(long)]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub host_target: Option<String>,
+    /// A package to leave out of the announcement, even if it would otherwise be included
+    ///
+    /// This can be passed multiple times, and adds to the exclude-packages in
+    /// [workspace.metadata.dist]. Useful for internal tools you never ship.
+    #[clap(long)]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub exclude: Vec<String>,
     /// Allow generated files like CI scripts to be out of date
     #[clap(long)]
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub allow_dirty: bool,
+    /// Explain why each artifact is being built
+    ///
+    /// Every artifact in the output will note what added it (e.g. which release's
+    /// installers setting) and why the selected --artifacts mode kept it.
+    #[clap(long)]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub verbose_plan: bool,
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -143,12 +166,26 @@ pub enum Commands {
     /// by forcing a couple flags to have specific values. You probably want that.
     #[clap(disable_version_flag = true)]
     Manifest(ManifestArgs),
+    /// Print everything cargo-dist computed about a build as json, without running it
+    ///
+    /// This includes every release, variant, binary, artifact, and build step (and the
+    /// dependencies between the steps), for tools that want to know exactly what
+    /// 'cargo dist build' would do when passed the same flags.
+    ///
+    /// Unlike dist-manifest.json the format of this is not stable, it changes whenever
+    /// cargo-dist's internals do.
+    #[clap(disable_version_flag = true)]
+    Graph(GraphArgs),
     /// Print --help as markdown (for generating docs)
     ///
     /// The output of this is not stable or guaranteed.
     #[clap(disable_version_flag = true)]
     #[clap(hide = true)]
     HelpMarkdown(HelpMarkdownArgs),
+    /// Print the json schema for the [workspace.metadata.dist] config
+    #[clap(disable_version_flag = true)]
+    #[clap(hide = true)]
+    ConfigSchema(ConfigSchemaArgs),
     /// Print the json schema for dist-manifest.json
     #[clap(disable_version_flag = true)]
     #[clap(hide = true)]
@@ -189,9 +226,31 @@ pub struct BuildArgs {
     /// The specifics of "host" mode are intentionally unspecified to enable us to provider better
     /// out-of-the-box UX for local usage. In CI environments you should always specify "global"
     /// or "local" to get consistent behaviour!
+    ///
+    /// "metadata" is a faster subset of "global" that doesn't bother computing what archives
+    /// would be built, and so skips every artifact that needs that (installers, unified checksums).
     #[clap(long, short, value_enum)]
     #[clap(default_value_t = ArtifactMode::Host)]
     pub artifacts: ArtifactMode,
+
+    /// Skip rebuilding artifacts that are already up to date
+    ///
+    /// cargo-dist will remember which files each archive (and its checksum, symbols...) was
+    /// built from, and if none of them have changed since the last --incremental build, that
+    /// artifact and the cargo builds for it are skipped. Installers and metadata are always
+    /// regenerated.
+    ///
+    /// This is intended for quick local iteration. CI should always do full builds.
+    #[clap(long)]
+    pub incremental: bool,
+
+    /// Print the steps a build would run instead of running them
+    ///
+    /// Every step is listed in order: the cargo builds (with their exact flags, RUSTFLAGS,
+    /// and the binaries they're expected to produce), copies, zips, installers, checksums...
+    /// The manifest is then printed as usual, as if the build had happened.
+    #[clap(long)]
+    pub dry_run: bool,
 }
 
 /// How we should select the artifacts to build
@@ -205,6 +264,8 @@ pub enum ArtifactMode {
     Host,
     /// Build all the artifacts; useful for `cargo dist manifest`
     All,
+    /// Build only metadata like the dist-manifest (no installers, which need archive info)
+    Metadata,
 }
 
 impl ArtifactMode {
@@ -215,6 +276,7 @@ impl ArtifactMode {
             ArtifactMode::Global => cargo_dist::config::ArtifactMode::Global,
             ArtifactMode::Host => cargo_dist::config::ArtifactMode::Host,
             ArtifactMode::All => cargo_dist::config::ArtifactMode::All,
+            ArtifactMode::Metadata => cargo_dist::config::ArtifactMode::Metadata,
         }
     }
 }
@@ -248,6 +310,8 @@ pub enum GenerateMode {
     Ci,
     /// Generate .wxs tempaltes for msi installers
     Msi,
+    /// Generate [package.metadata.binstall] for cargo-binstall
+    Binstall,
 }
 
 impl GenerateMode {
@@ -256,6 +320,7 @@ impl GenerateMode {
         match self {
             GenerateMode::Ci => cargo_dist::config::GenerateMode::Ci,
             GenerateMode::Msi => cargo_dist::config::GenerateMode::Msi,
+            GenerateMode::Binstall => cargo_dist::config::GenerateMode::Binstall,
         }
     }
 }
@@ -287,6 +352,11 @@ pub struct HelpMarkdownArgs {}
 pub enum CiStyle {
     /// Generate github CI that uploads to github releases
     Github,
+    /// Generate gitlab CI that uploads to gitlab releases
+    #[value(name = "gitlab")]
+    GitLab,
+    /// Generate gitea (or forgejo) actions that upload to gitea releases
+    Gitea,
 }
 
 impl CiStyle {
@@ -294,6 +364,8 @@ impl CiStyle {
     pub fn to_lib(self) -> cargo_dist::config::CiStyle {
         match self {
             CiStyle::Github => cargo_dist::config::CiStyle::Github,
+            CiStyle::GitLab => cargo_dist::config::CiStyle::GitLab,
+            CiStyle::Gitea => cargo_dist::config::CiStyle::Gitea,
         }
     }
 }
@@ -309,8 +381,31 @@ pub enum InstallerStyle {
     Npm,
     /// Generates a Homebrew formula
     Homebrew,
+    /// Generates a Scoop manifest
+    Scoop,
     /// Generates an msi for each windows platform
     Msi,
+    /// Generates a deb for each gnu linux platform
+    Deb,
+    /// Generates an rpm for each linux platform
+    Rpm,
+    /// Generates an AppImage for each linux platform
+    #[value(name = "appimage")]
+    AppImage,
+    /// Generates a FreeBSD pkg for each freebsd platform
+    FreebsdPkg,
+    /// Generates a Flatpak bundle for each linux platform
+    Flatpak,
+    /// Generates a snap for each linux platform
+    Snap,
+    /// Generates a .pkg for each macOS platform
+    MacPkg,
+    /// Generates a .dmg for each macOS platform
+    Dmg,
+    /// Generates a zipped .app bundle for each macOS platform
+    App,
+    /// Generates an NSIS installer .exe for each windows platform
+    Nsis,
 }
 
 impl InstallerStyle {
@@ -321,7 +416,18 @@ impl InstallerStyle {
             InstallerStyle::Powershell => cargo_dist::config::InstallerStyle::Powershell,
             InstallerStyle::Npm => cargo_dist::config::InstallerStyle::Npm,
             InstallerStyle::Homebrew => cargo_dist::config::InstallerStyle::Homebrew,
+            InstallerStyle::Scoop => cargo_dist::config::InstallerStyle::Scoop,
             InstallerStyle::Msi => cargo_dist::config::InstallerStyle::Msi,
+            InstallerStyle::Deb => cargo_dist::config::InstallerStyle::Deb,
+            InstallerStyle::Rpm => cargo_dist::config::InstallerStyle::Rpm,
+            InstallerStyle::AppImage => cargo_dist::config::InstallerStyle::AppImage,
+            InstallerStyle::FreebsdPkg => cargo_dist::config::InstallerStyle::FreebsdPkg,
+            InstallerStyle::Flatpak => cargo_dist::config::InstallerStyle::Flatpak,
+            InstallerStyle::Snap => cargo_dist::config::InstallerStyle::Snap,
+            InstallerStyle::MacPkg => cargo_dist::config::InstallerStyle::MacPkg,
+            InstallerStyle::Dmg => cargo_dist::config::InstallerStyle::Dmg,
+            InstallerStyle::App => cargo_dist::config::InstallerStyle::App,
+            InstallerStyle::Nsis => cargo_dist::config::InstallerStyle::Nsis,
         }
     }
 }
@@ -334,7 +440,21 @@ pub struct ManifestArgs {
 }
 
 #[derive(Args, Clone, Debug)]
-pub struct PlanArgs {}
+pub struct GraphArgs {
+    // Add the args from the "real" build command
+    #[clap(flatten)]
+    pub build_args: BuildArgs,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct PlanArgs {
+    /// Just list every package, with its binaries, tags, and targets
+    ///
+    /// This doesn't compute any artifacts, it's for figuring out why something
+    /// is (or isn't) going to be released. Packages that won't be released say why.
+    #[clap(long)]
+    pub list: bool,
+}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 pub enum OutputFormat {
@@ -344,3 +464,6 @@ pub enum OutputFormat {
 
 #[derive(Args, Clone, Debug)]
 pub struct ManifestSchemaArgs {}
+
+#[derive(Args, Clone, Debug)]
+pub struct ConfigSchemaArgs {}
@@ -0,0 +1,262 @@
+//! Release notes synthesized from git history
+//!
+//! If there are no notes for a release in the changelogs (and `changelog-from-commits = true`),
+//! we can instead list the commits since the previous tag (found with `git describe`),
+//! grouped by their [conventional commit](https://www.conventionalcommits.org) type.
+//! With `contributors = true` we also list who authored those commits.
+
+use std::process::Command;
+
+use camino::Utf8Path;
+use tracing::info;
+
+use crate::errors::{DistError, DistResult};
+
+/// The headings commits are grouped under, in the order they're listed
+const GROUPS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactors"),
+    ("docs", "Documentation"),
+];
+
+/// The heading for commits that aren't any of the types in GROUPS
+const OTHER_GROUP: &str = "Other Changes";
+
+/// Generate release notes for the commits that went into tag
+///
+/// See [`tag_range`][] for how the commits are found.
+pub(crate) fn notes_from_commits(
+    repo_dir: &Utf8Path,
+    tag: &str,
+    package: Option<(&str, &Utf8Path)>,
+) -> DistResult<Option<String>> {
+    let range = tag_range(repo_dir, tag, package);
+    info!(
+        "generating release notes from the commits in {}",
+        range.revs
+    );
+    let subjects = log(repo_dir, &range, "%s", package).map_err(|details| DistError::GitLog {
+        tag: tag.to_owned(),
+        details,
+    })?;
+    Ok(group_commits(subjects.lines()))
+}
+
+/// List who contributed to the commits that went into tag (as a markdown list)
+///
+/// Authors with GitHub noreply emails are listed by their handle, everyone else by
+/// name, and anyone who had no commits before the previous tag gets a shout-out for
+/// their first contribution. If the git history can't be read this is just None.
+pub(crate) fn contributors(
+    repo_dir: &Utf8Path,
+    tag: &str,
+    package: Option<(&str, &Utf8Path)>,
+) -> Option<String> {
+    use std::fmt::Write;
+
+    let range = tag_range(repo_dir, tag, package);
+    let authors = match log(repo_dir, &range, "%aN%x00%aE", package) {
+        Ok(authors) => authors,
+        Err(details) => {
+            info!("couldn't read the git history to find contributors: {details}");
+            return None;
+        }
+    };
+    let previous_authors = range
+        .previous
+        .as_ref()
+        .and_then(|previous| {
+            let earlier = TagRange {
+                revs: previous.clone(),
+                previous: None,
+            };
+            log(repo_dir, &earlier, "%aN%x00%aE", package).ok()
+        })
+        .map(|authors| authors.lines().map(author_handle).collect::<Vec<_>>());
+
+    // Oldest commits first, so contributors are listed in the order they showed up
+    let mut handles = Vec::<String>::new();
+    for author in authors.lines().rev() {
+        let handle = author_handle(author);
+        if !handle.is_empty() && !handles.contains(&handle) {
+            handles.push(handle);
+        }
+    }
+    if handles.is_empty() {
+        return None;
+    }
+    let mut list = String::new();
+    for handle in handles {
+        // (If this is the first release, everyone is new, so don't bother)
+        let first_time = previous_authors
+            .as_ref()
+            .is_some_and(|previous| !previous.contains(&handle));
+        if first_time {
+            writeln!(list, "- {handle} (first contribution!)").unwrap();
+        } else {
+            writeln!(list, "- {handle}").unwrap();
+        }
+    }
+    Some(list)
+}
+
+/// How to refer to the author of a commit, from a `name\0email` line
+///
+/// Emails like `1234+someone@users.noreply.github.com` belong to the GitHub user
+/// `@someone`, which is much nicer to show in a GitHub Release than a name.
+fn author_handle(author: &str) -> String {
+    let (name, email) = author.split_once('\0').unwrap_or((author, ""));
+    if let Some(user) = email.strip_suffix("@users.noreply.github.com") {
+        let user = user.split_once('+').map(|(_, user)| user).unwrap_or(user);
+        return format!("@{user}");
+    }
+    name.trim().to_owned()
+}
+
+/// The commits that went into a tag
+struct TagRange {
+    /// The revision range to pass to git log
+    revs: String,
+    /// The previous tag (if there was one)
+    previous: Option<String>,
+}
+
+/// Find the commits that went into tag
+///
+/// These are the commits since the previous tag (found with `git describe`). If the tag
+/// doesn't exist yet it's assumed to be for HEAD. `package` is the name and
+/// (workspace-relative) directory of the package being announced, if we're announcing
+/// just one, in which case only that package's tags are considered.
+fn tag_range(repo_dir: &Utf8Path, tag: &str, package: Option<(&str, &Utf8Path)>) -> TagRange {
+    let tag_ref = format!("refs/tags/{tag}");
+    let rev = if git(repo_dir, &["rev-parse", "--quiet", "--verify", &tag_ref]).is_ok() {
+        tag
+    } else {
+        "HEAD"
+    };
+
+    // The previous tag is the closest one before this commit (so other tags for the
+    // same commit, like other packages' tags, don't count)
+    let mut describe = vec!["describe", "--tags", "--abbrev=0"];
+    let package_tags = package.map(|(name, _)| format!("*{name}[-@/]*"));
+    if let Some(pattern) = &package_tags {
+        describe.extend(["--match", pattern]);
+    }
+    let parent = format!("{rev}^");
+    describe.push(&parent);
+    // No previous tag just means this is the first release
+    match git(repo_dir, &describe) {
+        Ok(previous) => {
+            let previous = previous.trim().to_owned();
+            TagRange {
+                revs: format!("{previous}..{rev}"),
+                previous: Some(previous),
+            }
+        }
+        Err(_) => TagRange {
+            revs: rev.to_owned(),
+            previous: None,
+        },
+    }
+}
+
+/// Run git log over a range (with the given --format), limited to the package's files
+fn log(
+    repo_dir: &Utf8Path,
+    range: &TagRange,
+    format: &str,
+    package: Option<(&str, &Utf8Path)>,
+) -> Result<String, String> {
+    let format = format!("--format={format}");
+    let mut args = vec!["log", "--no-merges", &format, &range.revs];
+    if let Some((_, dir)) = package {
+        if !dir.as_str().is_empty() {
+            args.extend(["--", dir.as_str()]);
+        }
+    }
+    git(repo_dir, &args)
+}
+
+/// Group commit subjects into markdown sections by conventional commit type
+///
+/// Commits are listed oldest first, and the type (and scope) prefix is replaced
+/// with the heading, so `fix(parser): handle tabs` becomes `**parser:** handle tabs`
+/// under "Bug Fixes".
+pub(crate) fn group_commits<'a>(
+    subjects: impl DoubleEndedIterator<Item = &'a str>,
+) -> Option<String> {
+    use std::fmt::Write;
+
+    let mut groups = vec![vec![]; GROUPS.len() + 1];
+    for subject in subjects.rev() {
+        let subject = subject.trim();
+        if subject.is_empty() {
+            continue;
+        }
+        // Anything that isn't one of the types we know is listed as-is
+        let parsed = parse_conventional(subject).and_then(|(kind, scope, description)| {
+            let group = GROUPS
+                .iter()
+                .position(|(group_kind, _)| *group_kind == kind)?;
+            let line = match scope {
+                Some(scope) => format!("**{scope}:** {description}"),
+                None => description.to_owned(),
+            };
+            Some((group, line))
+        });
+        let (group, line) = parsed.unwrap_or_else(|| (GROUPS.len(), subject.to_owned()));
+        groups[group].push(line);
+    }
+
+    let headings = GROUPS
+        .iter()
+        .map(|(_, heading)| *heading)
+        .chain([OTHER_GROUP]);
+    let mut notes = String::new();
+    for (heading, lines) in headings.zip(&groups) {
+        if lines.is_empty() {
+            continue;
+        }
+        if !notes.is_empty() {
+            notes.push('\n');
+        }
+        writeln!(notes, "### {heading}\n").unwrap();
+        for line in lines {
+            writeln!(notes, "- {line}").unwrap();
+        }
+    }
+    if notes.is_empty() {
+        None
+    } else {
+        Some(notes)
+    }
+}
+
+/// Split `type(scope)!: description` into its parts
+fn parse_conventional(subject: &str) -> Option<(&str, Option<&str>, &str)> {
+    let (prefix, description) = subject.split_once(": ")?;
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+        None => (prefix, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((kind, scope, description.trim()))
+}
+
+/// Run a git command in the repo, returning its stdout (or what went wrong)
+fn git(repo_dir: &Utf8Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .current_dir(repo_dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("couldn't run git: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
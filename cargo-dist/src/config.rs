@@ -5,6 +5,7 @@ use std::collections::BTreeMap;
 use axoproject::WorkspaceSearch;
 use camino::{Utf8Path, Utf8PathBuf};
 use miette::Report;
+use schemars::JsonSchema;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use tracing::log::warn;
@@ -16,7 +17,8 @@ use crate::{
 };
 
 /// Contents of METADATA_DIST in Cargo.toml files
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct DistMetadata {
     /// The intended version of cargo-dist to build with. (normal Cargo SemVer syntax)
     ///
@@ -28,6 +30,7 @@ pub struct DistMetadata {
     /// things other cargo-dist versions can't handle!
     #[serde(rename = "cargo-dist-version")]
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
     pub cargo_dist_version: Option<Version>,
 
     /// (deprecated) The intended version of Rust/Cargo to build with (rustup toolchain syntax)
@@ -117,6 +120,7 @@ pub struct DistMetadata {
     /// Files like `README*`, `(UN)LICENSE*`, `RELEASES*`, and `CHANGELOG*` are already
     /// automatically detected and included (use [`DistMetadata::auto_includes`][] to prevent this).
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<String>>")]
     pub include: Option<Vec<Utf8PathBuf>>,
 
     /// Whether to auto-include files like `README*`, `(UN)LICENSE*`, `RELEASES*`, and `CHANGELOG*`
@@ -272,9 +276,609 @@ pub struct DistMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "ssldotcom-windows-sign")]
     pub ssldotcom_windows_sign: Option<ProductionMode>,
+
+    /// Whether to generate a CITATION.cff file for each release
+    ///
+    /// (defaults to false)
+    ///
+    /// This is a global artifact listing the app's version, authors, and repository
+    /// so that academic citation tooling can reference the release.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "citation")]
+    pub citation: Option<bool>,
+
+    /// Whether to attach the full release notes to the Github Release as a RELEASE_NOTES.md
+    ///
+    /// (defaults to false)
+    ///
+    /// If true, the Github Release body will only contain install instructions and
+    /// the download table, and the complete changelog will live in the asset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "release-notes-asset")]
+    pub release_notes_asset: Option<bool>,
+
+    /// Whether to generate a single checksum file (like SHA256SUMS) for each release
+    ///
+    /// (defaults to false)
+    ///
+    /// This is a global artifact listing the checksums of every other artifact in the
+    /// release, in the format `sha256sum --check` expects.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "unified-checksum")]
+    pub unified_checksum: Option<bool>,
+
+    /// Binaries the Homebrew formula should install to libexec instead of bin
+    ///
+    /// (defaults to none, so every binary is installed to bin)
+    ///
+    /// Each binary in libexec gets a wrapper script in bin that runs it from there, which
+    /// is useful for binaries that expect to find other files next to themselves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "homebrew-libexec")]
+    pub homebrew_libexec: Option<Vec<String>>,
+
+    /// The id or fingerprint of a gpg key to sign artifacts with
+    ///
+    /// (defaults to none, so nothing is signed)
+    ///
+    /// If set, every archive and installer gets a detached `.asc` signature.
+    /// The secret key must be available to gpg on the machine building artifacts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "gpg-key")]
+    pub gpg_key: Option<String>,
+
+    /// How many checksums to compute at once
+    ///
+    /// Checksums only depend on the file they're hashing, so they can all be computed
+    /// in parallel once those files exist. This also limits how many files are hashed at
+    /// once for the unified checksum file. This is independent of cargo's own parallelism.
+    ///
+    /// (defaults to the number of cpus available)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "checksum-jobs")]
+    pub checksum_jobs: Option<usize>,
+
+    /// The format of the git tags you announce releases with
+    ///
+    /// `{version}` is replaced with the version being released, so the default is
+    /// `"v{version}"`. This is used when inferring the announcement tag, when parsing
+    /// tags, and when generating the CI triggers that will react to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "tag-format")]
+    pub tag_format: Option<String>,
+
+    /// The Sentry organization to upload debug symbols to
+    ///
+    /// Only used if "sentry" is in publish-jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sentry-org")]
+    pub sentry_org: Option<String>,
+
+    /// The Sentry project to upload debug symbols to
+    ///
+    /// Only used if "sentry" is in publish-jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sentry-project")]
+    pub sentry_project: Option<String>,
+
+    /// Whether to build both "slim" and "full" archives for each platform
+    ///
+    /// The slim archive has its binaries stripped of debuginfo and is what installers
+    /// will fetch, while the full archive keeps the debuginfo for developers.
+    ///
+    /// (defaults to false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "slim-archives")]
+    pub slim_archives: Option<bool>,
+
+    /// Publish jobs to run in CI that are fully defined here (instead of in their own workflow)
+    ///
+    /// (defaults to none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "extra-publish-jobs")]
+    pub extra_publish_jobs: Option<Vec<ExtraPublishJob>>,
+
+    /// A file containing more publish job definitions (like extra-publish-jobs)
+    ///
+    /// (defaults to none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "extra-publish-jobs-file")]
+    #[schemars(with = "Option<String>")]
+    pub extra_publish_jobs_file: Option<Utf8PathBuf>,
+
+    /// Binaries this package must have
+    ///
+    /// If any of these aren't among the package's binaries when we go to release it,
+    /// that's an error (instead of quietly shipping fewer binaries).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "required-binaries")]
+    pub required_binaries: Option<Vec<String>>,
+
+    /// The icon to put in AppImages (relative to the package's Cargo.toml)
+    ///
+    /// AppImages can't be made without one, so this is required for the appimage installer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "appimage-icon")]
+    #[schemars(with = "Option<String>")]
+    pub appimage_icon: Option<Utf8PathBuf>,
+
+    /// The name of the dir in target/ to build packages in (defaults to "distrib")
+    ///
+    /// This can't be the same as the name of a cargo profile, as cargo builds
+    /// each profile in a dir of the same name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "dist-subdir")]
+    pub dist_subdir: Option<String>,
+
+    /// The WinGet package identifier (e.g. "Axodotdev.Axolotlsay")
+    ///
+    /// If set, WinGet manifests will be generated for this package's msi installers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "winget-id")]
+    pub winget_id: Option<String>,
+
+    /// A Scoop bucket to push the Scoop manifest to, if built
+    ///
+    /// (In GitHub owner/name format)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "bucket")]
+    pub bucket: Option<String>,
+
+    /// Whether to put the directories artifacts are staged in under a directory named after
+    /// their target triple (so `target/distrib/<target>/<name>/` instead of `target/distrib/<name>/`)
+    ///
+    /// (defaults to false)
+    ///
+    /// This keeps parallel builds of different targets that share a dist dir
+    /// (e.g. a mounted volume) from stepping on each other's toes. The final artifacts
+    /// keep their usual names and locations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "per-target-staging-dirs")]
+    pub per_target_staging_dirs: Option<bool>,
+
+    /// The app-id of the Flatpak bundle (e.g. "dev.axo.axolotlsay")
+    ///
+    /// Defaults to one derived from the package's repository (io.github.<owner>.<name>).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "flatpak-app-id")]
+    pub flatpak_app_id: Option<String>,
+
+    /// The icon to put in Flatpak bundles (relative to the package's Cargo.toml)
+    ///
+    /// If set, the bundle also gets a desktop entry, so the app shows up in app launchers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "flatpak-icon")]
+    #[schemars(with = "Option<String>")]
+    pub flatpak_icon: Option<Utf8PathBuf>,
+
+    /// The confinement of snaps (defaults to "strict")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "snap-confinement")]
+    pub snap_confinement: Option<SnapConfinement>,
+
+    /// The grade of snaps (defaults to "stable")
+    ///
+    /// Only "stable" snaps can be released to the stable or candidate channels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "snap-grade")]
+    pub snap_grade: Option<SnapGrade>,
+
+    /// The Snap Store channel to release snaps to (defaults to "stable")
+    ///
+    /// Only used if "snap" is in publish-jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "snap-channel")]
+    pub snap_channel: Option<String>,
+
+    /// The container registry to push the Docker image to (e.g. "ghcr.io")
+    ///
+    /// Only used if "docker" is in publish-jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "docker-registry")]
+    pub docker_registry: Option<String>,
+
+    /// The name of the Docker image to push (e.g. "axodotdev/axolotlsay")
+    ///
+    /// Only used if "docker" is in publish-jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "docker-image")]
+    pub docker_image: Option<String>,
+
+    /// The image to base the Docker image on (defaults to "gcr.io/distroless/cc-debian12")
+    ///
+    /// Statically linked (musl) binaries can use "scratch" instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "docker-base-image")]
+    pub docker_base_image: Option<String>,
+
+    /// Whether to generate an install.sh that lets users pick which of the workspace's apps to install
+    ///
+    /// This dispatches to each app's shell installer, so it needs "shell" in installers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "shell-suite-installer")]
+    pub shell_suite_installer: Option<bool>,
+
+    /// Whether to write `[package.metadata.binstall]` so cargo-binstall can fetch our archives
+    ///
+    /// This is (re)written by `cargo dist generate` (and `init`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "binstall-metadata")]
+    pub binstall_metadata: Option<bool>,
+
+    /// The identifier of macOS .pkg installers (e.g. "dev.axo.axolotlsay")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "mac-pkg-identifier")]
+    pub mac_pkg_identifier: Option<String>,
+
+    /// Where macOS .pkg installers put the binaries (defaults to "/usr/local/bin")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "mac-pkg-install-location")]
+    pub mac_pkg_install_location: Option<String>,
+
+    /// The signing identity to sign macOS .pkg installers with (e.g. "Developer ID Installer: Axo (ABCDE12345)")
+    ///
+    /// This identity needs to be in the keychain of the machine building the installer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "mac-pkg-sign-identity")]
+    pub mac_pkg_sign_identity: Option<String>,
+
+    /// Whether to generate a checksums.json for each release
+    ///
+    /// (defaults to false)
+    ///
+    /// This is a global artifact listing the file name, checksum algorithm, checksum, and
+    /// size of every other artifact in the release, for tools that would rather not parse
+    /// a SHA256SUMS file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "checksums-json")]
+    pub checksums_json: Option<bool>,
+
+    /// Whether NSIS installers should install for every user, to Program Files
+    ///
+    /// (defaults to false, installing just for the current user, which doesn't need admin)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "nsis-program-files")]
+    pub nsis_program_files: Option<bool>,
+
+    /// Whether NSIS installers should add the install dir to PATH
+    ///
+    /// (defaults to true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "nsis-modify-path")]
+    pub nsis_modify_path: Option<bool>,
+
+    /// The target triple to treat as the host, instead of the one cargo reports
+    ///
+    /// (defaults to the `host:` of `cargo -vV`)
+    ///
+    /// This is the target built in host mode (e.g. `cargo dist build` with no --target),
+    /// which is useful in containers where cargo's host isn't what you want to build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "host-target")]
+    pub host_target: Option<String>,
+
+    /// Whether to generate an offline bundle for each release
+    ///
+    /// (defaults to false)
+    ///
+    /// This is a global `<release>-offline.tar.gz` containing the archives of every platform,
+    /// along with an install.sh that installs the right one without touching the network.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "offline-bundle")]
+    pub offline_bundle: Option<bool>,
+
+    /// How to show this release's download table in the Github Release notes
+    ///
+    /// (defaults to "table")
+    ///
+    /// Big announcements with lots of apps can "collapse" the table into a `<details>` block,
+    /// or leave it out with "none" (the install instructions are kept either way).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "download-table")]
+    pub download_table: Option<DownloadTableStyle>,
+
+    /// Targets to additionally build a "demo" of the app for
+    ///
+    /// (defaults to none)
+    ///
+    /// A demo is an extra archive of the app (with a "-demo" suffix) built with `--all-features`,
+    /// no matter what `features`, `all-features`, and `default-features` say. Only targets the
+    /// app is already built for get one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "demo-targets")]
+    pub demo_targets: Option<Vec<String>>,
+
+    /// The CFBundleIdentifier of macOS .app bundles (e.g. "dev.axo.axolotlsay")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "app-bundle-identifier")]
+    pub app_bundle_identifier: Option<String>,
+
+    /// The .icns icon to put in macOS .app bundles (relative to the package's Cargo.toml)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "app-bundle-icon")]
+    #[schemars(with = "Option<String>")]
+    pub app_bundle_icon: Option<Utf8PathBuf>,
+
+    /// Whether packages with `publish = false` should still be distributed
+    ///
+    /// Packages can still opt out with `dist = false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "dist-unpublished")]
+    pub dist_unpublished: Option<bool>,
+
+    /// Only dist these binaries of the package (by default all of them are)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "bin-filter")]
+    pub bin_filter: Option<Vec<String>>,
+
+    /// Only install these binaries with the given installers (by default they install all of them)
+    ///
+    /// e.g. `{ npm = ["my-cli"] }` to leave a daemon out of the npm package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "installer-bin-filter")]
+    pub installer_bin_filter: Option<BTreeMap<InstallerStyle, Vec<String>>>,
+
+    /// Names installers should install binaries as, instead of their Cargo bin names
+    ///
+    /// e.g. `{ foobar-cli = "foo" }`. Archives still contain the original names.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "bin-aliases")]
+    pub bin_aliases: Option<BTreeMap<String, String>>,
+
+    /// The OCI repository to push the release's artifacts to (e.g. "ghcr.io/axodotdev/axolotlsay-artifacts")
+    ///
+    /// Only used if "oci" is in publish-jobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "oci-registry")]
+    pub oci_registry: Option<String>,
+
+    /// Extra feature settings for the targets matching each pattern
+    ///
+    /// e.g. `{ "*-linux-*" = { features = ["dbus"] } }`. Patterns are target triples
+    /// where `*` matches anything. These are applied on top of `features` and
+    /// `default-features`, in the order of their patterns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "target-features")]
+    pub target_features: Option<BTreeMap<String, TargetFeatures>>,
+
+    /// Whether to refuse to release if the changelog has no notes for the release
+    ///
+    /// (defaults to false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "require-changelog")]
+    pub require_changelog: Option<bool>,
+
+    /// Custom Github Actions runners to use for particular targets
+    ///
+    /// Keys are target triples, values are runner labels. Any target not listed
+    /// here uses the default runner for its platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "github-custom-runners")]
+    pub github_custom_runners: Option<BTreeMap<String, String>>,
+
+    /// The license files to include in archives
+    ///
+    /// (defaults to the `(UN)LICENSE*` files that are auto-included)
+    ///
+    /// If this is set, only these files are treated as licenses, and the auto-detected ones are
+    /// ignored (list them here too if you want to keep them). Paths are relative to the
+    /// Cargo.toml this is defined in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "license-files")]
+    #[schemars(with = "Option<Vec<String>>")]
+    pub license_files: Option<Vec<Utf8PathBuf>>,
+
+    /// How CI should cache builds between runs
+    ///
+    /// (defaults to "rust-cache")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ci-cache")]
+    pub ci_cache: Option<CiCacheStyle>,
+
+    /// Where sccache should store builds, if `ci-cache = "sccache"`
+    ///
+    /// (defaults to "gha")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sccache-backend")]
+    pub sccache_backend: Option<SccacheBackend>,
+
+    /// Where to find the previous release's archives, to build deltas against
+    ///
+    /// This is either a URL (like the previous release's artifact download URL), or a path
+    /// to a directory relative to the workspace root. If set, every archive gets a binary
+    /// delta from the archive of the same name in that location.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "delta-from")]
+    pub delta_from: Option<String>,
+
+    /// The tool to use to build deltas, if `delta-from` is set
+    ///
+    /// (defaults to "zstd")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "delta-format")]
+    pub delta_format: Option<DeltaFormat>,
+
+    /// Whether to build non-host linux targets with `cross` instead of cargo
+    ///
+    /// (defaults to false)
+    ///
+    /// cross builds in a container with the right toolchains already set up, so you don't
+    /// need a local cross-compiler for things like musl or arm linux.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "use-cross")]
+    pub use_cross: Option<bool>,
+
+    /// Extra builds of the app with different features, by name
+    ///
+    /// (defaults to none)
+    ///
+    /// Each one is an extra archive of the app for every target (with a "-{name}" suffix),
+    /// built with the variant's features instead of `features`, `all-features`, and `default-features`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "feature-variants")]
+    pub feature_variants: Option<BTreeMap<String, FeatureVariant>>,
+
+    /// The cargo profile to build with
+    ///
+    /// (defaults to "dist")
+    ///
+    /// Useful if your workspace already has a profile you want releases built with.
+    /// This can't be the same as dist-subdir, as cargo builds each profile in target/<profile>.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "build-profile")]
+    pub build_profile: Option<String>,
+
+    /// How the versions in your announcement tags are interpreted
+    ///
+    /// With "calver", date-style versions like 2024.06.1 are accepted (leading zeros
+    /// and all), and a release is only a prerelease if its version has an explicit
+    /// marker like `-rc.1`, so respins like `2024.06.1-2` are still full releases.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "versioning")]
+    pub versioning: Option<VersioningScheme>,
+
+    /// Whether to generate the announcement's release notes with git-cliff
+    ///
+    /// Instead of reading them out of your CHANGELOG/RELEASES, git-cliff is run
+    /// (with your cliff.toml) on the commits that belong to the announcement tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "git-cliff")]
+    pub git_cliff: Option<bool>,
+
+    /// Whether to generate release notes from git history if the changelogs have none
+    ///
+    /// The commits since the previous tag are listed, grouped by their conventional
+    /// commit type (feat, fix, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "changelog-from-commits")]
+    pub changelog_from_commits: Option<bool>,
+
+    /// Whether to list the release's contributors in the GitHub Release body
+    ///
+    /// These are the authors of the commits since the previous tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "contributors")]
+    pub contributors: Option<bool>,
+
+    /// A custom base URL that artifacts will be downloadable from
+    ///
+    /// This replaces the URL we'd derive from your CI backend (e.g. a GitHub Release),
+    /// for when you upload your artifacts somewhere else (like S3 or a CDN). `{tag}` is
+    /// replaced with the announcement tag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "artifact-download-url")]
+    pub artifact_download_url: Option<String>,
+
+    /// Whether the shell installer should carry its archives instead of downloading them
+    ///
+    /// (defaults to false)
+    ///
+    /// The installer embeds every archive it can install (base64-encoded), so it works without
+    /// network access, at the cost of being as big as all of those archives put together.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "embed-archives")]
+    pub embed_archives: Option<bool>,
+
+    /// Named groups of packages that can be announced together (group name => package names)
+    ///
+    /// (defaults to none)
+    ///
+    /// A tag like `tools-v1.0.0` announces every package in the "tools" group, each at its own
+    /// version, in a single announcement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "announcement-groups")]
+    pub announcement_groups: Option<BTreeMap<String, Vec<String>>>,
+
+    /// Packages that should never be released, even if they otherwise would be
+    ///
+    /// (defaults to none)
+    ///
+    /// This is useful for internal tools that happen to be distable. `--exclude` adds to this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "exclude-packages")]
+    pub exclude_packages: Option<Vec<String>>,
+
+    /// Targets to build this package for on top of its `targets`
+    ///
+    /// (defaults to none)
+    ///
+    /// `targets` set on a package replaces the workspace's, while these add to them, so a
+    /// package can support a platform the rest of the workspace doesn't without repeating
+    /// the whole list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "extra-targets")]
+    pub extra_targets: Option<Vec<String>>,
+
+    /// Other packages in the workspace whose binaries this package's release includes
+    ///
+    /// (defaults to none)
+    ///
+    /// For workspaces where the top-level package is a thin wrapper around binaries that
+    /// are defined in member crates. The binaries are built from the packages that define
+    /// them (with `--package`), and those packages aren't released on their own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "binaries-from")]
+    pub binaries_from: Option<Vec<String>>,
+
+    /// Files to generate by running a command, and include in archives
+    ///
+    /// Each command is run in the package's directory after the binaries are built, and what
+    /// it prints to stdout is written to `path` in the archive.
+    ///
+    /// (defaults to none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "generated-assets")]
+    pub generated_assets: Option<Vec<GeneratedAsset>>,
+
+    /// Shells to generate completions for, by running each built binary
+    ///
+    /// The completions are included in archives (in a `completions` dir). Binaries that
+    /// can't run on the machine building them (because they're cross-compiled) are skipped.
+    ///
+    /// (defaults to none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "completions")]
+    pub completions: Option<Vec<CompletionShell>>,
+
+    /// The arguments that make a binary print its completions for a shell
+    ///
+    /// `{shell}` is replaced with the name of the shell.
+    ///
+    /// (defaults to `["completions", "{shell}"]`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "completions-args")]
+    pub completions_args: Option<Vec<String>>,
+
+    /// A directory of man pages to include in archives and installers (relative to the Cargo.toml)
+    ///
+    /// Each file's extension is its section (e.g. `my-app.1`), which decides where it goes
+    /// (`man/man1/` in archives, `/usr/share/man/man1/` for debs and rpms).
+    ///
+    /// (defaults to none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "man-dir")]
+    #[schemars(with = "Option<String>")]
+    pub man_dir: Option<Utf8PathBuf>,
+
+    /// The arguments that make a binary print its man page, to generate man pages by running
+    /// each built binary
+    ///
+    /// Each binary's page is included as `<binary>.1`, alongside any from
+    /// [`DistMetadata::man_dir`][]. Like completions, binaries that can't run on the machine
+    /// building them are skipped.
+    ///
+    /// (defaults to none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "man-pages-args")]
+    pub man_pages_args: Option<Vec<String>>,
 }
 
 impl DistMetadata {
+    /// Get the JSON Schema for a METADATA_DIST table (for editors to validate Cargo.toml)
+    pub fn json_schema() -> schemars::schema::RootSchema {
+        schemars::schema_for!(DistMetadata)
+    }
+
     /// Apply the base path to any relative paths contained in this DistMetadata
     pub fn make_relative_to(&mut self, base_path: &Utf8Path) {
         // This is intentionally written awkwardly to make you update it
@@ -306,12 +910,103 @@ impl DistMetadata {
             pr_run_mode: _,
             allow_dirty: _,
             ssldotcom_windows_sign: _,
+            citation: _,
+            release_notes_asset: _,
+            unified_checksum: _,
+            homebrew_libexec: _,
+            gpg_key: _,
+            checksum_jobs: _,
+            tag_format: _,
+            sentry_org: _,
+            sentry_project: _,
+            slim_archives: _,
+            extra_publish_jobs: _,
+            extra_publish_jobs_file,
+            required_binaries: _,
+            appimage_icon,
+            dist_subdir: _,
+            winget_id: _,
+            bucket: _,
+            per_target_staging_dirs: _,
+            flatpak_app_id: _,
+            flatpak_icon,
+            snap_confinement: _,
+            snap_grade: _,
+            snap_channel: _,
+            docker_registry: _,
+            docker_image: _,
+            docker_base_image: _,
+            shell_suite_installer: _,
+            binstall_metadata: _,
+            mac_pkg_identifier: _,
+            mac_pkg_install_location: _,
+            mac_pkg_sign_identity: _,
+            checksums_json: _,
+            nsis_program_files: _,
+            nsis_modify_path: _,
+            host_target: _,
+            offline_bundle: _,
+            download_table: _,
+            demo_targets: _,
+            app_bundle_identifier: _,
+            app_bundle_icon,
+            dist_unpublished: _,
+            bin_filter: _,
+            installer_bin_filter: _,
+            bin_aliases: _,
+            oci_registry: _,
+            target_features: _,
+            require_changelog: _,
+            github_custom_runners: _,
+            license_files,
+            ci_cache: _,
+            sccache_backend: _,
+            delta_from: _,
+            delta_format: _,
+            use_cross: _,
+            feature_variants: _,
+            build_profile: _,
+            versioning: _,
+            git_cliff: _,
+            changelog_from_commits: _,
+            contributors: _,
+            artifact_download_url: _,
+            embed_archives: _,
+            announcement_groups: _,
+            exclude_packages: _,
+            extra_targets: _,
+            binaries_from: _,
+            generated_assets: _,
+            completions: _,
+            completions_args: _,
+            man_dir,
+            man_pages_args: _,
         } = self;
         if let Some(include) = include {
             for include in include {
                 *include = base_path.join(&*include);
             }
         }
+        if let Some(man_dir) = man_dir {
+            *man_dir = base_path.join(&*man_dir);
+        }
+        if let Some(license_files) = license_files {
+            for license in license_files {
+                *license = base_path.join(&*license);
+            }
+        }
+        if let Some(extra_publish_jobs_file) = extra_publish_jobs_file {
+            *extra_publish_jobs_file = base_path.join(&*extra_publish_jobs_file);
+        }
+        if let Some(appimage_icon) = appimage_icon {
+            *appimage_icon = base_path.join(&*appimage_icon);
+        }
+        if let Some(flatpak_icon) = flatpak_icon {
+            *flatpak_icon = base_path.join(&*flatpak_icon);
+        }
+        if let Some(app_bundle_icon) = app_bundle_icon {
+            *app_bundle_icon = base_path.join(&*app_bundle_icon);
+        }
     }
 
     /// Merge a workspace config into a package config (self)
@@ -349,43 +1044,211 @@ impl DistMetadata {
             pr_run_mode,
             allow_dirty,
             ssldotcom_windows_sign,
+            citation,
+            release_notes_asset,
+            unified_checksum,
+            homebrew_libexec,
+            gpg_key,
+            checksum_jobs,
+            tag_format,
+            sentry_org,
+            sentry_project,
+            slim_archives,
+            extra_publish_jobs,
+            extra_publish_jobs_file,
+            required_binaries,
+            appimage_icon,
+            dist_subdir,
+            winget_id,
+            bucket,
+            per_target_staging_dirs,
+            flatpak_app_id,
+            flatpak_icon,
+            snap_confinement,
+            snap_grade,
+            snap_channel,
+            docker_registry,
+            docker_image,
+            docker_base_image,
+            shell_suite_installer,
+            binstall_metadata,
+            mac_pkg_identifier,
+            mac_pkg_install_location,
+            mac_pkg_sign_identity,
+            checksums_json,
+            nsis_program_files,
+            nsis_modify_path,
+            host_target,
+            offline_bundle,
+            download_table,
+            demo_targets,
+            app_bundle_identifier,
+            app_bundle_icon,
+            dist_unpublished,
+            bin_filter,
+            installer_bin_filter,
+            bin_aliases,
+            oci_registry,
+            target_features,
+            require_changelog,
+            github_custom_runners,
+            license_files,
+            ci_cache,
+            sccache_backend,
+            delta_from,
+            delta_format,
+            use_cross,
+            feature_variants,
+            build_profile,
+            versioning,
+            git_cliff,
+            changelog_from_commits,
+            contributors,
+            artifact_download_url,
+            embed_archives,
+            announcement_groups,
+            exclude_packages,
+            extra_targets,
+            // Names other packages, so it wouldn't make sense to inherit this
+            binaries_from: _,
+            generated_assets,
+            completions,
+            completions_args,
+            man_dir,
+            man_pages_args,
         } = self;
 
         // Check for global settings on local packages
         if cargo_dist_version.is_some() {
             warn!("package.metadata.dist.cargo-dist-version is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if rust_toolchain_version.is_some() {
-            warn!("package.metadata.dist.rust-toolchain-version is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if rust_toolchain_version.is_some() {
+            warn!("package.metadata.dist.rust-toolchain-version is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if ci.is_some() {
+            warn!("package.metadata.dist.ci is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if precise_builds.is_some() {
+            warn!("package.metadata.dist.precise-builds is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if merge_tasks.is_some() {
+            warn!("package.metadata.dist.merge-tasks is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if fail_fast.is_some() {
+            warn!("package.metadata.dist.fail-fast is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if create_release.is_some() {
+            warn!("package.metadata.dist.create-release is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        // Arguably should be package-local for things like msi installers, but doesn't make sense for CI,
+        // so let's not support that yet for its complexity!
+        if allow_dirty.is_some() {
+            warn!("package.metadata.dist.allow-dirty is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if publish_prereleases.is_some() {
+            warn!("package.metadata.dist.publish-prereleases is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if pr_run_mode.is_some() {
+            warn!("package.metadata.dist.pr-run-mode is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if ssldotcom_windows_sign.is_some() {
+            warn!("package.metadata.dist.ssldotcom-windows-sign is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if release_notes_asset.is_some() {
+            warn!("package.metadata.dist.release-notes-asset is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if checksum_jobs.is_some() {
+            warn!("package.metadata.dist.checksum-jobs is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if tag_format.is_some() {
+            warn!("package.metadata.dist.tag-format is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if sentry_org.is_some() {
+            warn!("package.metadata.dist.sentry-org is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if sentry_project.is_some() {
+            warn!("package.metadata.dist.sentry-project is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if extra_publish_jobs.is_some() {
+            warn!("package.metadata.dist.extra-publish-jobs is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if extra_publish_jobs_file.is_some() {
+            warn!("package.metadata.dist.extra-publish-jobs-file is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if dist_subdir.is_some() {
+            warn!("package.metadata.dist.dist-subdir is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if per_target_staging_dirs.is_some() {
+            warn!("package.metadata.dist.per-target-staging-dirs is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if snap_channel.is_some() {
+            warn!("package.metadata.dist.snap-channel is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if docker_registry.is_some() {
+            warn!("package.metadata.dist.docker-registry is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if docker_image.is_some() {
+            warn!("package.metadata.dist.docker-image is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if docker_base_image.is_some() {
+            warn!("package.metadata.dist.docker-base-image is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if shell_suite_installer.is_some() {
+            warn!("package.metadata.dist.shell-suite-installer is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if host_target.is_some() {
+            warn!("package.metadata.dist.host-target is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if ci.is_some() {
-            warn!("package.metadata.dist.ci is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if dist_unpublished.is_some() {
+            warn!("package.metadata.dist.dist-unpublished is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if precise_builds.is_some() {
-            warn!("package.metadata.dist.precise-builds is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if oci_registry.is_some() {
+            warn!("package.metadata.dist.oci-registry is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if merge_tasks.is_some() {
-            warn!("package.metadata.dist.merge-tasks is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if require_changelog.is_some() {
+            warn!("package.metadata.dist.require-changelog is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if fail_fast.is_some() {
-            warn!("package.metadata.dist.fail-fast is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if github_custom_runners.is_some() {
+            warn!("package.metadata.dist.github-custom-runners is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if create_release.is_some() {
-            warn!("package.metadata.dist.create-release is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if ci_cache.is_some() {
+            warn!("package.metadata.dist.ci-cache is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        // Arguably should be package-local for things like msi installers, but doesn't make sense for CI,
-        // so let's not support that yet for its complexity!
-        if allow_dirty.is_some() {
-            warn!("package.metadata.dist.allow-dirty is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if sccache_backend.is_some() {
+            warn!("package.metadata.dist.sccache-backend is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if publish_prereleases.is_some() {
-            warn!("package.metadata.dist.publish-prereleases is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if delta_from.is_some() {
+            warn!("package.metadata.dist.delta-from is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if pr_run_mode.is_some() {
-            warn!("package.metadata.dist.pr-run-mode is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if delta_format.is_some() {
+            warn!("package.metadata.dist.delta-format is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
-        if ssldotcom_windows_sign.is_some() {
-            warn!("package.metadata.dist.ssldotcom-windows-sign is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        if use_cross.is_some() {
+            warn!("package.metadata.dist.use-cross is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if build_profile.is_some() {
+            warn!("package.metadata.dist.build-profile is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if versioning.is_some() {
+            warn!("package.metadata.dist.versioning is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if git_cliff.is_some() {
+            warn!("package.metadata.dist.git-cliff is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if changelog_from_commits.is_some() {
+            warn!("package.metadata.dist.changelog-from-commits is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if contributors.is_some() {
+            warn!("package.metadata.dist.contributors is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if artifact_download_url.is_some() {
+            warn!("package.metadata.dist.artifact-download-url is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if announcement_groups.is_some() {
+            warn!("package.metadata.dist.announcement-groups is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if exclude_packages.is_some() {
+            warn!("package.metadata.dist.exclude-packages is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
 
         // Merge non-global settings
@@ -434,6 +1297,124 @@ impl DistMetadata {
         if publish_jobs.is_none() {
             *publish_jobs = workspace_config.publish_jobs.clone();
         }
+        if citation.is_none() {
+            *citation = workspace_config.citation;
+        }
+        if unified_checksum.is_none() {
+            *unified_checksum = workspace_config.unified_checksum;
+        }
+        if homebrew_libexec.is_none() {
+            *homebrew_libexec = workspace_config.homebrew_libexec.clone();
+        }
+        if gpg_key.is_none() {
+            *gpg_key = workspace_config.gpg_key.clone();
+        }
+        if slim_archives.is_none() {
+            *slim_archives = workspace_config.slim_archives;
+        }
+        if required_binaries.is_none() {
+            *required_binaries = workspace_config.required_binaries.clone();
+        }
+        if appimage_icon.is_none() {
+            *appimage_icon = workspace_config.appimage_icon.clone();
+        }
+        if winget_id.is_none() {
+            *winget_id = workspace_config.winget_id.clone();
+        }
+        if bucket.is_none() {
+            *bucket = workspace_config.bucket.clone();
+        }
+        if flatpak_app_id.is_none() {
+            *flatpak_app_id = workspace_config.flatpak_app_id.clone();
+        }
+        if flatpak_icon.is_none() {
+            *flatpak_icon = workspace_config.flatpak_icon.clone();
+        }
+        if snap_confinement.is_none() {
+            *snap_confinement = workspace_config.snap_confinement;
+        }
+        if snap_grade.is_none() {
+            *snap_grade = workspace_config.snap_grade;
+        }
+        if binstall_metadata.is_none() {
+            *binstall_metadata = workspace_config.binstall_metadata;
+        }
+        if mac_pkg_identifier.is_none() {
+            *mac_pkg_identifier = workspace_config.mac_pkg_identifier.clone();
+        }
+        if mac_pkg_install_location.is_none() {
+            *mac_pkg_install_location = workspace_config.mac_pkg_install_location.clone();
+        }
+        if mac_pkg_sign_identity.is_none() {
+            *mac_pkg_sign_identity = workspace_config.mac_pkg_sign_identity.clone();
+        }
+        if checksums_json.is_none() {
+            *checksums_json = workspace_config.checksums_json;
+        }
+        if nsis_program_files.is_none() {
+            *nsis_program_files = workspace_config.nsis_program_files;
+        }
+        if nsis_modify_path.is_none() {
+            *nsis_modify_path = workspace_config.nsis_modify_path;
+        }
+        if offline_bundle.is_none() {
+            *offline_bundle = workspace_config.offline_bundle;
+        }
+        if download_table.is_none() {
+            *download_table = workspace_config.download_table;
+        }
+        if demo_targets.is_none() {
+            *demo_targets = workspace_config.demo_targets.clone();
+        }
+        if app_bundle_identifier.is_none() {
+            *app_bundle_identifier = workspace_config.app_bundle_identifier.clone();
+        }
+        if app_bundle_icon.is_none() {
+            *app_bundle_icon = workspace_config.app_bundle_icon.clone();
+        }
+        if bin_filter.is_none() {
+            *bin_filter = workspace_config.bin_filter.clone();
+        }
+        if installer_bin_filter.is_none() {
+            *installer_bin_filter = workspace_config.installer_bin_filter.clone();
+        }
+        if bin_aliases.is_none() {
+            *bin_aliases = workspace_config.bin_aliases.clone();
+        }
+        if target_features.is_none() {
+            *target_features = workspace_config.target_features.clone();
+        }
+        if license_files.is_none() {
+            *license_files = workspace_config.license_files.clone();
+        }
+        if feature_variants.is_none() {
+            *feature_variants = workspace_config.feature_variants.clone();
+        }
+        if embed_archives.is_none() {
+            *embed_archives = workspace_config.embed_archives;
+        }
+        if extra_targets.is_none() {
+            *extra_targets = workspace_config.extra_targets.clone();
+        }
+
+        if workspace_config.binaries_from.is_some() {
+            warn!("workspace.metadata.dist.binaries-from is set, but this is only accepted in package.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if generated_assets.is_none() {
+            *generated_assets = workspace_config.generated_assets.clone();
+        }
+        if completions.is_none() {
+            *completions = workspace_config.completions.clone();
+        }
+        if completions_args.is_none() {
+            *completions_args = workspace_config.completions_args.clone();
+        }
+        if man_dir.is_none() {
+            *man_dir = workspace_config.man_dir.clone();
+        }
+        if man_pages_args.is_none() {
+            *man_pages_args = workspace_config.man_pages_args.clone();
+        }
 
         // This was historically implemented as extend, but I'm not convinced the
         // inconsistency is worth the inconvenience...
@@ -460,6 +1441,8 @@ pub struct Config {
     pub no_local_paths: bool,
     /// If true, override allow-dirty in the config and ignore all dirtyness
     pub allow_all_dirty: bool,
+    /// Whether to explain why each artifact is being built in the dist json output
+    pub verbose_plan: bool,
     /// Target triples we want to build for
     pub targets: Vec<TargetTriple>,
     /// CI kinds we want to support
@@ -468,6 +1451,10 @@ pub struct Config {
     pub installers: Vec<InstallerStyle>,
     /// The (git) tag to use for this Announcement.
     pub announcement_tag: Option<String>,
+    /// The target triple to treat as the host, overriding what cargo reports
+    pub host_target: Option<TargetTriple>,
+    /// Packages to leave out of the announcement (on top of exclude-packages)
+    pub exclude: Vec<String>,
 }
 
 /// How we should select the artifacts to build
@@ -481,27 +1468,54 @@ pub enum ArtifactMode {
     Host,
     /// Build all the artifacts; only really appropriate for `cargo-dist manifest`
     All,
+    /// Build only the globally unique artifacts that don't need to know anything about
+    /// archives (dist-manifest, citation, release notes...), skipping installers
+    Metadata,
 }
 
 /// The style of CI we should generate
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
 pub enum CiStyle {
     /// Generate Github CI
     #[serde(rename = "github")]
     Github,
+    /// Generate GitLab CI
+    #[serde(rename = "gitlab")]
+    GitLab,
+    /// Generate Gitea (or Forgejo) Actions
+    #[serde(rename = "gitea")]
+    Gitea,
+}
+
+impl CiStyle {
+    /// Get the url template for where the forge this CI runs on serves a release's artifacts
+    ///
+    /// `{repo_url}` and `{tag}` should be substituted in.
+    pub fn artifact_download_url_template(self) -> &'static str {
+        match self {
+            CiStyle::Github | CiStyle::Gitea => "{repo_url}/releases/download/{tag}",
+            CiStyle::GitLab => "{repo_url}/-/releases/{tag}/downloads",
+        }
+    }
 }
 
 impl std::fmt::Display for CiStyle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
             CiStyle::Github => "github",
+            CiStyle::GitLab => "gitlab",
+            CiStyle::Gitea => "gitea",
         };
         string.fmt(f)
     }
 }
 
 /// The style of Installer we should generate
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
 pub enum InstallerStyle {
     /// Generate a shell script that fetches from [`crate::tasks::DistGraph::artifact_download_url`][]
     #[serde(rename = "shell")]
@@ -515,9 +1529,42 @@ pub enum InstallerStyle {
     /// Generate a Homebrew formula that fetches from [`crate::tasks::DistGraph::artifact_download_url`][]
     #[serde(rename = "homebrew")]
     Homebrew,
+    /// Generate a Scoop manifest that fetches from [`crate::tasks::DistGraph::artifact_download_url`][]
+    #[serde(rename = "scoop")]
+    Scoop,
     /// Generate an msi installer that embeds the binary
     #[serde(rename = "msi")]
     Msi,
+    /// Generate a deb package that embeds the binary
+    #[serde(rename = "deb")]
+    Deb,
+    /// Generate an rpm package that embeds the binary
+    #[serde(rename = "rpm")]
+    Rpm,
+    /// Generate an AppImage that embeds the binary
+    #[serde(rename = "appimage")]
+    AppImage,
+    /// Generate a FreeBSD pkg that embeds the binary
+    #[serde(rename = "freebsd-pkg")]
+    FreebsdPkg,
+    /// Generate a Flatpak bundle that embeds the binary
+    #[serde(rename = "flatpak")]
+    Flatpak,
+    /// Generate a snap that embeds the binary
+    #[serde(rename = "snap")]
+    Snap,
+    /// Generate a macOS .pkg installer that embeds the binary
+    #[serde(rename = "mac-pkg")]
+    MacPkg,
+    /// Generate a macOS .dmg disk image that embeds the binary
+    #[serde(rename = "dmg")]
+    Dmg,
+    /// Generate a zipped macOS .app bundle that embeds the binary
+    #[serde(rename = "app")]
+    App,
+    /// Generate an NSIS .exe installer that embeds the binary
+    #[serde(rename = "nsis")]
+    Nsis,
 }
 
 impl std::fmt::Display for InstallerStyle {
@@ -527,7 +1574,18 @@ impl std::fmt::Display for InstallerStyle {
             InstallerStyle::Powershell => "powershell",
             InstallerStyle::Npm => "npm",
             InstallerStyle::Homebrew => "homebrew",
+            InstallerStyle::Scoop => "scoop",
             InstallerStyle::Msi => "msi",
+            InstallerStyle::Deb => "deb",
+            InstallerStyle::Rpm => "rpm",
+            InstallerStyle::AppImage => "appimage",
+            InstallerStyle::FreebsdPkg => "freebsd-pkg",
+            InstallerStyle::Flatpak => "flatpak",
+            InstallerStyle::Snap => "snap",
+            InstallerStyle::MacPkg => "mac-pkg",
+            InstallerStyle::Dmg => "dmg",
+            InstallerStyle::App => "app",
+            InstallerStyle::Nsis => "nsis",
         };
         string.fmt(f)
     }
@@ -539,6 +1597,27 @@ pub enum PublishStyle {
     /// Publish a Homebrew formula to a tap repository
     #[serde(rename = "homebrew")]
     Homebrew,
+    /// Open a pull request updating the Homebrew formula in a tap repository
+    #[serde(rename = "homebrew-pr")]
+    HomebrewPr,
+    /// Publish a Scoop manifest to a bucket repository
+    #[serde(rename = "scoop")]
+    Scoop,
+    /// Open a pull request adding the WinGet manifests to microsoft/winget-pkgs
+    #[serde(rename = "winget")]
+    WinGet,
+    /// Upload debug symbols to Sentry
+    #[serde(rename = "sentry")]
+    Sentry,
+    /// Upload snaps to the Snap Store
+    #[serde(rename = "snap")]
+    Snap,
+    /// Build a Docker image from the linux archives and push it to a registry
+    #[serde(rename = "docker")]
+    Docker,
+    /// Push the release's artifacts to a container registry as an OCI artifact
+    #[serde(rename = "oci")]
+    Oci,
     /// User-supplied value
     User(String),
 }
@@ -550,6 +1629,20 @@ impl std::str::FromStr for PublishStyle {
             Ok(Self::User(slug.to_owned()))
         } else if s == "homebrew" {
             Ok(Self::Homebrew)
+        } else if s == "homebrew-pr" {
+            Ok(Self::HomebrewPr)
+        } else if s == "scoop" {
+            Ok(Self::Scoop)
+        } else if s == "winget" {
+            Ok(Self::WinGet)
+        } else if s == "sentry" {
+            Ok(Self::Sentry)
+        } else if s == "snap" {
+            Ok(Self::Snap)
+        } else if s == "docker" {
+            Ok(Self::Docker)
+        } else if s == "oci" {
+            Ok(Self::Oci)
         } else {
             Err(DistError::UnrecognizedStyle {
                 style: s.to_owned(),
@@ -570,15 +1663,155 @@ impl<'de> serde::Deserialize<'de> for PublishStyle {
     }
 }
 
+impl JsonSchema for PublishStyle {
+    fn schema_name() -> String {
+        "PublishStyle".to_owned()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject, StringValidation, SubschemaValidation};
+
+        let builtin = [
+            PublishStyle::Homebrew,
+            PublishStyle::HomebrewPr,
+            PublishStyle::Scoop,
+            PublishStyle::WinGet,
+            PublishStyle::Sentry,
+            PublishStyle::Snap,
+            PublishStyle::Docker,
+            PublishStyle::Oci,
+        ];
+        let builtin = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(builtin.iter().map(|s| s.to_string().into()).collect()),
+            ..Default::default()
+        };
+        // User-supplied jobs are named like "./my-job"
+        let user = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^\\./.+$".to_owned()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![builtin.into(), user.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl std::fmt::Display for PublishStyle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PublishStyle::Homebrew => write!(f, "homebrew"),
+            PublishStyle::HomebrewPr => write!(f, "homebrew-pr"),
+            PublishStyle::Scoop => write!(f, "scoop"),
+            PublishStyle::WinGet => write!(f, "winget"),
+            PublishStyle::Sentry => write!(f, "sentry"),
+            PublishStyle::Snap => write!(f, "snap"),
+            PublishStyle::Docker => write!(f, "docker"),
+            PublishStyle::Oci => write!(f, "oci"),
             PublishStyle::User(s) => write!(f, "./{s}"),
         }
     }
 }
 
+/// Feature settings for the targets matching a pattern (see `target-features`)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TargetFeatures {
+    /// Extra features to enable
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// Whether to enable default features (overrides `default-features`)
+    #[serde(rename = "default-features")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_features: Option<bool>,
+}
+
+/// The features of an extra build of an app (see `feature-variants`)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct FeatureVariant {
+    /// Features to enable
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// Whether to enable default features (defaults to true)
+    #[serde(rename = "default-features")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_features: Option<bool>,
+    /// Whether to enable all features (defaults to false)
+    #[serde(rename = "all-features")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_features: Option<bool>,
+}
+
+/// A file to generate by running a command (see `generated-assets`)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct GeneratedAsset {
+    /// Where to put the file, relative to the root of the archive
+    #[schemars(with = "String")]
+    pub path: Utf8PathBuf,
+    /// The program to run and its arguments (stdout becomes the file)
+    pub command: Vec<String>,
+}
+
+/// Check if a target triple matches a pattern, where `*` in the pattern matches anything
+pub fn target_matches_pattern(pattern: &str, target: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = target.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcards, so the pattern must be the whole target
+        return rest.is_empty();
+    };
+    for part in middle {
+        let Some(idx) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[idx + part.len()..];
+    }
+    rest.ends_with(last)
+}
+
+/// A publish job that's fully defined in cargo-dist's config
+///
+/// Unlike [`PublishStyle::User`][], which refers to a workflow you wrote yourself,
+/// these get rendered into the generated CI as real jobs.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct ExtraPublishJob {
+    /// The name of the job (the CI job will be called `custom-<name>`)
+    pub name: String,
+    /// The shell command(s) to run
+    pub command: String,
+    /// Other CI jobs that need to finish before this one runs
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub needs: Vec<String>,
+    /// Environment variables to set for the command
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// The contents of an extra-publish-jobs-file
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExtraPublishJobsFile {
+    /// The jobs (as `[[publish-job]]` tables)
+    #[serde(default)]
+    #[serde(rename = "publish-job")]
+    pub publish_jobs: Vec<ExtraPublishJob>,
+}
+
 /// The style of zip/tarball to make
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ZipStyle {
@@ -644,14 +1877,42 @@ impl<'de> Deserialize<'de> for ZipStyle {
     }
 }
 
+impl JsonSchema for ZipStyle {
+    fn schema_name() -> String {
+        "ZipStyle".to_owned()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject};
+
+        // TempDir isn't something you can ask for, so it's not listed
+        let styles = [
+            ZipStyle::Zip,
+            ZipStyle::Tar(CompressionImpl::Gzip),
+            ZipStyle::Tar(CompressionImpl::Xzip),
+            ZipStyle::Tar(CompressionImpl::Zstd),
+        ];
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(styles.iter().map(|s| s.ext().into()).collect()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 /// key for the install-path config that selects [`InstallPathStrategyCargoHome`][]
 const CARGO_HOME_INSTALL_PATH: &str = "CARGO_HOME";
+/// key for the install-path config that selects [`InstallPathStrategyXdg`][]
+const XDG_INSTALL_PATH: &str = "xdg";
 
 /// Strategy for install binaries
 #[derive(Debug, Clone)]
 pub enum InstallPathStrategy {
     /// install to $CARGO_HOME, falling back to ~/.cargo/
     CargoHome,
+    /// install to $XDG_BIN_HOME, falling back to ~/.local/bin/
+    Xdg,
     /// install to this subdir of the user's home
     ///
     /// syntax: `~/subdir`
@@ -675,6 +1936,8 @@ impl std::str::FromStr for InstallPathStrategy {
     fn from_str(path: &str) -> DistResult<Self> {
         if path == CARGO_HOME_INSTALL_PATH {
             Ok(InstallPathStrategy::CargoHome)
+        } else if path == XDG_INSTALL_PATH {
+            Ok(InstallPathStrategy::Xdg)
         } else if let Some(subdir) = path.strip_prefix("~/") {
             if subdir.is_empty() {
                 Err(DistError::InstallPathHomeSubdir {
@@ -710,6 +1973,7 @@ impl std::fmt::Display for InstallPathStrategy {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             InstallPathStrategy::CargoHome => write!(f, "{}", CARGO_HOME_INSTALL_PATH),
+            InstallPathStrategy::Xdg => write!(f, "{}", XDG_INSTALL_PATH),
             InstallPathStrategy::HomeSubdir { subdir } => write!(f, "~/{subdir}"),
             InstallPathStrategy::EnvSubdir { env_key, subdir } => write!(f, "${env_key}/{subdir}"),
         }
@@ -737,6 +2001,28 @@ impl<'de> serde::Deserialize<'de> for InstallPathStrategy {
     }
 }
 
+impl JsonSchema for InstallPathStrategy {
+    fn schema_name() -> String {
+        "InstallPathStrategy".to_owned()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject, StringValidation};
+
+        // Mirrors the FromStr impl: CARGO_HOME, xdg, ~/subdir, or $ENV_VAR/subdir
+        let pattern = format!("^({CARGO_HOME_INSTALL_PATH}|{XDG_INSTALL_PATH}|~/.+|\\$[^/]+/.*)$");
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(pattern),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 /// Strategy for install binaries (replica to have different Serialize for jinja)
 ///
 /// The serialize/deserialize impls are already required for loading/saving the config
@@ -748,6 +2034,8 @@ impl<'de> serde::Deserialize<'de> for InstallPathStrategy {
 pub enum JinjaInstallPathStrategy {
     /// install to $CARGO_HOME, falling back to ~/.cargo/
     CargoHome,
+    /// install to $XDG_BIN_HOME, falling back to ~/.local/bin/
+    Xdg,
     /// install to this subdir of the user's home
     ///
     /// syntax: `~/subdir`
@@ -771,6 +2059,7 @@ impl InstallPathStrategy {
     pub fn into_jinja(self) -> JinjaInstallPathStrategy {
         match self {
             InstallPathStrategy::CargoHome => JinjaInstallPathStrategy::CargoHome,
+            InstallPathStrategy::Xdg => JinjaInstallPathStrategy::Xdg,
             InstallPathStrategy::HomeSubdir { subdir } => {
                 JinjaInstallPathStrategy::HomeSubdir { subdir }
             }
@@ -782,7 +2071,9 @@ impl InstallPathStrategy {
 }
 
 /// A checksumming algorithm
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ChecksumStyle {
     /// sha256sum (using the sha2 crate)
     #[serde(rename = "sha256")]
@@ -804,10 +2095,260 @@ impl ChecksumStyle {
             ChecksumStyle::False => "false",
         }
     }
+
+    /// Get the conventional name of a file listing several checksums (e.g. SHA256SUMS)
+    pub fn unified_file_name(self) -> String {
+        format!("{}SUMS", self.ext().to_uppercase())
+    }
+}
+
+/// How to show a release's download table in the Github Release notes
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum DownloadTableStyle {
+    /// A plain markdown table
+    #[serde(rename = "table")]
+    Table,
+    /// A markdown table inside a collapsed `<details>` block
+    #[serde(rename = "collapsed")]
+    Collapsed,
+    /// No table at all
+    #[serde(rename = "none")]
+    None,
+}
+
+impl std::fmt::Display for DownloadTableStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            DownloadTableStyle::Table => "table",
+            DownloadTableStyle::Collapsed => "collapsed",
+            DownloadTableStyle::None => "none",
+        };
+        string.fmt(f)
+    }
+}
+
+/// How CI should cache builds between runs
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum CiCacheStyle {
+    /// Cache the target dir with Swatinem/rust-cache
+    #[serde(rename = "rust-cache")]
+    RustCache,
+    /// Cache individual compilations with sccache
+    #[serde(rename = "sccache")]
+    Sccache,
+    /// Don't cache anything
+    #[serde(rename = "none")]
+    None,
+}
+
+impl std::fmt::Display for CiCacheStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            CiCacheStyle::RustCache => "rust-cache",
+            CiCacheStyle::Sccache => "sccache",
+            CiCacheStyle::None => "none",
+        };
+        string.fmt(f)
+    }
+}
+
+/// Where sccache stores its cache
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum SccacheBackend {
+    /// The Github Actions cache
+    #[serde(rename = "gha")]
+    Gha,
+    /// An S3 bucket (configured with repository variables/secrets)
+    #[serde(rename = "s3")]
+    S3,
+    /// A redis server (configured with a repository secret)
+    #[serde(rename = "redis")]
+    Redis,
+}
+
+impl SccacheBackend {
+    /// The environment variables sccache needs to use this backend in Github Actions
+    pub fn github_env(self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            SccacheBackend::Gha => vec![("SCCACHE_GHA_ENABLED", "true")],
+            SccacheBackend::S3 => vec![
+                ("SCCACHE_BUCKET", "${{ vars.SCCACHE_BUCKET }}"),
+                ("SCCACHE_REGION", "${{ vars.SCCACHE_REGION }}"),
+                ("AWS_ACCESS_KEY_ID", "${{ secrets.AWS_ACCESS_KEY_ID }}"),
+                (
+                    "AWS_SECRET_ACCESS_KEY",
+                    "${{ secrets.AWS_SECRET_ACCESS_KEY }}",
+                ),
+            ],
+            SccacheBackend::Redis => vec![("SCCACHE_REDIS", "${{ secrets.SCCACHE_REDIS }}")],
+        }
+    }
+}
+
+impl std::fmt::Display for SccacheBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            SccacheBackend::Gha => "gha",
+            SccacheBackend::S3 => "s3",
+            SccacheBackend::Redis => "redis",
+        };
+        string.fmt(f)
+    }
+}
+
+/// A tool for building binary deltas between archives
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum DeltaFormat {
+    /// `zstd --patch-from`
+    #[serde(rename = "zstd")]
+    Zstd,
+    /// bsdiff (applied with bspatch)
+    #[serde(rename = "bsdiff")]
+    Bsdiff,
+}
+
+impl DeltaFormat {
+    /// Get the extension used for deltas in this format
+    pub fn ext(self) -> &'static str {
+        match self {
+            DeltaFormat::Zstd => "patch.zst",
+            DeltaFormat::Bsdiff => "bsdiff",
+        }
+    }
+}
+
+impl std::fmt::Display for DeltaFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            DeltaFormat::Zstd => "zstd",
+            DeltaFormat::Bsdiff => "bsdiff",
+        };
+        string.fmt(f)
+    }
+}
+
+/// A shell to generate completions for (see `completions`)
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub enum CompletionShell {
+    /// bash
+    #[serde(rename = "bash")]
+    Bash,
+    /// zsh
+    #[serde(rename = "zsh")]
+    Zsh,
+    /// fish
+    #[serde(rename = "fish")]
+    Fish,
+    /// PowerShell
+    #[serde(rename = "powershell")]
+    Powershell,
+    /// elvish
+    #[serde(rename = "elvish")]
+    Elvish,
+}
+
+impl CompletionShell {
+    /// Get the name the completions for a binary should have (what each shell looks for)
+    pub fn file_name(self, binary: &str) -> String {
+        match self {
+            CompletionShell::Bash => format!("{binary}.bash"),
+            CompletionShell::Zsh => format!("_{binary}"),
+            CompletionShell::Fish => format!("{binary}.fish"),
+            CompletionShell::Powershell => format!("_{binary}.ps1"),
+            CompletionShell::Elvish => format!("{binary}.elv"),
+        }
+    }
+}
+
+impl std::fmt::Display for CompletionShell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            CompletionShell::Bash => "bash",
+            CompletionShell::Zsh => "zsh",
+            CompletionShell::Fish => "fish",
+            CompletionShell::Powershell => "powershell",
+            CompletionShell::Elvish => "elvish",
+        };
+        string.fmt(f)
+    }
+}
+
+/// How the versions in announcement tags are interpreted
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum VersioningScheme {
+    /// Cargo-style SemVer, where any pre-release suffix makes a prerelease
+    #[default]
+    #[serde(rename = "semver")]
+    Semver,
+    /// Date-based versions like 2024.06.1, where only a marker like `-rc.1` makes a prerelease
+    #[serde(rename = "calver")]
+    Calver,
+}
+
+impl VersioningScheme {
+    /// Parse the version component of a tag
+    ///
+    /// Calver versions may have leading zeros (2024.06.1), which are normalized away
+    /// so the result can be compared to the Cargo.toml version (2024.6.1).
+    pub fn parse_version(self, version: &str) -> std::result::Result<Version, semver::Error> {
+        match self {
+            VersioningScheme::Semver => version.parse(),
+            VersioningScheme::Calver => {
+                let split = version.find(['-', '+']).unwrap_or(version.len());
+                let (core, rest) = version.split_at(split);
+                let core = core
+                    .split('.')
+                    .map(|part| {
+                        let trimmed = part.trim_start_matches('0');
+                        if trimmed.is_empty() && !part.is_empty() {
+                            "0"
+                        } else {
+                            trimmed
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".");
+                format!("{core}{rest}").parse()
+            }
+        }
+    }
+
+    /// Whether releasing this version should be announced as a prerelease
+    pub fn is_prerelease(self, version: &Version) -> bool {
+        match self {
+            VersioningScheme::Semver => !version.pre.is_empty(),
+            VersioningScheme::Calver => version
+                .pre
+                .as_str()
+                .starts_with(|c: char| c.is_ascii_alphabetic()),
+        }
+    }
+}
+
+impl std::fmt::Display for VersioningScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            VersioningScheme::Semver => "semver",
+            VersioningScheme::Calver => "calver",
+        };
+        string.fmt(f)
+    }
 }
 
 /// Which style(s) of configuration to generate
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
 pub enum GenerateMode {
     /// Generate CI scripts for orchestrating cargo-dist
     #[serde(rename = "ci")]
@@ -815,6 +2356,9 @@ pub enum GenerateMode {
     /// Generate wsx (WiX) templates for msi installers
     #[serde(rename = "msi")]
     Msi,
+    /// Generate `[package.metadata.binstall]` for cargo-binstall
+    #[serde(rename = "binstall")]
+    Binstall,
 }
 
 impl std::fmt::Display for GenerateMode {
@@ -822,12 +2366,15 @@ impl std::fmt::Display for GenerateMode {
         match self {
             GenerateMode::Ci => "ci".fmt(f),
             GenerateMode::Msi => "msi".fmt(f),
+            GenerateMode::Binstall => "binstall".fmt(f),
         }
     }
 }
 
 /// Packages to install before build from the system package manager
-#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
 pub struct SystemDependencies {
     /// Packages to install in Homebrew
     #[serde(default)]
@@ -842,6 +2389,14 @@ pub struct SystemDependencies {
     #[serde(default)]
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub chocolatey: BTreeMap<String, SystemDependency>,
+    /// Packages to install in dnf (currently only used for rpm dependencies)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub dnf: BTreeMap<String, SystemDependency>,
+    /// Packages to install in FreeBSD's pkg (currently only used for FreeBSD pkg dependencies)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub pkg: BTreeMap<String, SystemDependency>,
 }
 
 impl SystemDependencies {
@@ -850,6 +2405,8 @@ impl SystemDependencies {
         self.homebrew.append(&mut other.homebrew);
         self.apt.append(&mut other.apt);
         self.chocolatey.append(&mut other.chocolatey);
+        self.dnf.append(&mut other.dnf);
+        self.pkg.append(&mut other.pkg);
     }
 }
 
@@ -859,7 +2416,7 @@ impl SystemDependencies {
 pub struct SystemDependency(pub SystemDependencyComplex);
 
 /// Backing type for SystemDependency
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, JsonSchema)]
 pub struct SystemDependencyComplex {
     /// The version to install, as expected by the underlying package manager
     pub version: Option<String>,
@@ -881,12 +2438,23 @@ impl SystemDependencyComplex {
         }
     }
 
+    /// The Homebrew formula to install for this dependency (named `name`)
+    ///
+    /// Homebrew can't install arbitrary versions of a formula, but it does have versioned
+    /// formulae like `openssl@3`, so a version picks one of those.
+    pub fn homebrew_formula(&self, name: &str) -> String {
+        match &self.version {
+            Some(version) => format!("{name}@{version}"),
+            None => name.to_owned(),
+        }
+    }
+
     /// Checks if this dependency should used in the specified stage.
     pub fn stage_wanted(&self, stage: &DependencyKind) -> bool {
         if self.stage.is_empty() {
             match stage {
                 DependencyKind::Build => true,
-                DependencyKind::Run => false,
+                DependencyKind::Run | DependencyKind::Recommend | DependencyKind::Suggest => false,
             }
         } else {
             self.stage.contains(stage)
@@ -895,7 +2463,7 @@ impl SystemDependencyComplex {
 }
 
 /// Definition for a single package
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum SystemDependencyKind {
     /// Simple specification format, parsed as cmake = 'version'
@@ -906,7 +2474,7 @@ pub enum SystemDependencyKind {
 }
 
 /// Provides detail on when a specific dependency is required
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
 pub enum DependencyKind {
     /// A dependency that must be present when the software is being built
     #[serde(rename = "build")]
@@ -914,6 +2482,12 @@ pub enum DependencyKind {
     /// A dependency that must be present when the software is being used
     #[serde(rename = "run")]
     Run,
+    /// A dependency that should be installed alongside the software unless the user opts out
+    #[serde(rename = "recommend")]
+    Recommend,
+    /// A dependency that can enhance the software, but that users have to opt into
+    #[serde(rename = "suggest")]
+    Suggest,
 }
 
 impl std::fmt::Display for DependencyKind {
@@ -921,6 +2495,8 @@ impl std::fmt::Display for DependencyKind {
         match self {
             DependencyKind::Build => "build".fmt(f),
             DependencyKind::Run => "run".fmt(f),
+            DependencyKind::Recommend => "recommend".fmt(f),
+            DependencyKind::Suggest => "suggest".fmt(f),
         }
     }
 }
@@ -948,6 +2524,17 @@ impl<'de> Deserialize<'de> for SystemDependency {
     }
 }
 
+impl JsonSchema for SystemDependency {
+    fn schema_name() -> String {
+        "SystemDependency".to_owned()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        // We deserialize through SystemDependencyKind, so that's our schema too
+        SystemDependencyKind::json_schema(gen)
+    }
+}
+
 /// Settings for which Generate targets can be dirty
 #[derive(Debug, Clone)]
 pub enum DirtyMode {
@@ -955,6 +2542,9 @@ pub enum DirtyMode {
     AllowList(Vec<GenerateMode>),
     /// Allow all targets
     AllowAll,
+    /// Allow all the files cargo-dist generates to be dirty, but still
+    /// enforce other integrity checks (like cargo-dist-version)
+    GeneratedOnly,
 }
 
 impl DirtyMode {
@@ -962,13 +2552,30 @@ impl DirtyMode {
     pub fn should_run(&self, mode: GenerateMode) -> bool {
         match self {
             DirtyMode::AllowAll => false,
+            DirtyMode::GeneratedOnly => false,
             DirtyMode::AllowList(list) => !list.contains(&mode),
         }
     }
+
+    /// Get the mode integrity checks should use for `cargo dist plan` on a pull request
+    ///
+    /// When pr-run-mode is "plan" the PR is only checking that we *can* plan a release,
+    /// and regenerated files (like CI scripts) commonly differ from the committed ones
+    /// while a PR is in progress, so we tolerate that instead of failing the check.
+    pub fn for_pr_plan(self, pr_run_mode: cargo_dist_schema::PrRunMode) -> Self {
+        match (self, pr_run_mode) {
+            (DirtyMode::AllowList(_), cargo_dist_schema::PrRunMode::Plan) => {
+                DirtyMode::GeneratedOnly
+            }
+            (mode, _) => mode,
+        }
+    }
 }
 
 /// For features that can be generated in "test" or "production" mode
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ProductionMode {
     /// test mode
     #[serde(rename = "test")]
@@ -987,19 +2594,119 @@ impl std::fmt::Display for ProductionMode {
     }
 }
 
+/// How much a snap is sandboxed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum SnapConfinement {
+    /// Fully sandboxed, only reaching out through interfaces
+    #[default]
+    #[serde(rename = "strict")]
+    Strict,
+    /// Not sandboxed (needs approval from the Snap Store to publish)
+    #[serde(rename = "classic")]
+    Classic,
+    /// Sandboxed, but violations only produce warnings (can't be released to stable)
+    #[serde(rename = "devmode")]
+    Devmode,
+}
+
+impl std::fmt::Display for SnapConfinement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapConfinement::Strict => "strict".fmt(f),
+            SnapConfinement::Classic => "classic".fmt(f),
+            SnapConfinement::Devmode => "devmode".fmt(f),
+        }
+    }
+}
+
+/// How production-ready a snap is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum SnapGrade {
+    /// Ready to be released to any channel
+    #[default]
+    #[serde(rename = "stable")]
+    Stable,
+    /// Only releasable to the edge and beta channels
+    #[serde(rename = "devel")]
+    Devel,
+}
+
+impl std::fmt::Display for SnapGrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapGrade::Stable => "stable".fmt(f),
+            SnapGrade::Devel => "devel".fmt(f),
+        }
+    }
+}
+
 pub(crate) fn parse_metadata_table(
     manifest_path: &Utf8Path,
     metadata_table: Option<&serde_json::Value>,
 ) -> DistResult<DistMetadata> {
-    Ok(metadata_table
-        .and_then(|t| t.get(METADATA_DIST))
-        .map(DistMetadata::deserialize)
-        .transpose()
-        .map_err(|cause| DistError::CargoTomlParse {
+    let Some(table) = metadata_table.and_then(|t| t.get(METADATA_DIST)) else {
+        return Ok(DistMetadata::default());
+    };
+    let meta = DistMetadata::deserialize(table).map_err(|cause| DistError::CargoTomlParse {
+        manifest_path: manifest_path.to_owned(),
+        cause,
+    })?;
+    check_unknown_keys(manifest_path, table, &meta)?;
+    Ok(meta)
+}
+
+/// Error out on keys in a METADATA_DIST table that we don't know (probably typos)
+///
+/// serde would otherwise silently ignore them. If the config is for a newer cargo-dist
+/// than us, the key may just be something we don't know about yet, so that's only a warning.
+fn check_unknown_keys(
+    manifest_path: &Utf8Path,
+    table: &serde_json::Value,
+    meta: &DistMetadata,
+) -> DistResult<()> {
+    let Some(table) = table.as_object() else {
+        return Ok(());
+    };
+    let schema = DistMetadata::json_schema();
+    let known = &schema
+        .schema
+        .object
+        .as_ref()
+        .expect("DistMetadata schema wasn't an object!?")
+        .properties;
+    let current_version: Version = std::env!("CARGO_PKG_VERSION").parse().unwrap();
+    for key in table.keys() {
+        if known.contains_key(key) {
+            continue;
+        }
+        if let Some(version) = meta
+            .cargo_dist_version
+            .as_ref()
+            .filter(|v| **v > current_version)
+        {
+            warn!("{manifest_path} has a metadata.dist key we don't know ({key}), it may be for cargo-dist {version}");
+            continue;
+        }
+        // Common mistakes: snake_case instead of kebab-case, and singular vs plural
+        let kebab = key.replace('_', "-");
+        let suggestion = [
+            kebab.clone(),
+            format!("{kebab}s"),
+            kebab.strip_suffix('s').unwrap_or(&kebab).to_owned(),
+        ]
+        .into_iter()
+        .find(|candidate| candidate != key && known.contains_key(candidate));
+        let help = match suggestion {
+            Some(suggestion) => format!("did you mean {suggestion}?"),
+            None => "see https://opensource.axo.dev/cargo-dist/book/reference/config.html for the supported keys".to_owned(),
+        };
+        return Err(DistError::UnknownConfigKey {
             manifest_path: manifest_path.to_owned(),
-            cause,
-        })?
-        .unwrap_or_default())
+            key: key.to_owned(),
+            help,
+        });
+    }
+    Ok(())
 }
 
 /// Get the general info about the project (via axo-project)
@@ -0,0 +1,163 @@
+//! Building binary deltas between releases
+//!
+//! We shell out to `zstd --patch-from` or `bsdiff` to diff each archive against the
+//! previous release's version of it, and generate a little script that does the reverse
+//! (with `zstd -d --patch-from` or `bspatch`) for users that want to apply them.
+
+use std::process::Command;
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use tracing::info;
+
+use crate::{
+    backend::templates::{Templates, TEMPLATE_METADATA_APPLY_DELTA},
+    config::DeltaFormat,
+    errors::{DistError, DistResult},
+    DeltaImpl, DeltaScriptImpl, Tools,
+};
+
+/// The values the apply-delta script is rendered with
+#[derive(Debug, Serialize)]
+struct DeltaScriptInfo<'a> {
+    /// The app the deltas are for
+    app_name: &'a str,
+    /// The tool the deltas were built with
+    format: String,
+    /// The extension of the deltas
+    ext: &'static str,
+}
+
+/// Build a delta from the previous version of an archive (fetching it if need be)
+pub(crate) fn build_delta(tools: &Tools, delta: &DeltaImpl) -> DistResult<()> {
+    let tool_name = delta.format.to_string();
+    let tool = match delta.format {
+        DeltaFormat::Zstd => &tools.zstd,
+        DeltaFormat::Bsdiff => &tools.bsdiff,
+    };
+    let tool = tool.as_ref().ok_or_else(|| DistError::DeltaToolNotFound {
+        tool: tool_name.clone(),
+    })?;
+
+    let is_url = delta.old_path.starts_with("https://") || delta.old_path.starts_with("http://");
+    let old_path = if is_url {
+        let old_path = Utf8PathBuf::from(format!("{}.old", delta.dest_path));
+        fetch(&delta.old_path, &old_path)?;
+        old_path
+    } else {
+        let old_path = Utf8PathBuf::from(&delta.old_path);
+        if !old_path.exists() {
+            return Err(DistError::DeltaPreviousMissing {
+                old_path: delta.old_path.clone(),
+            });
+        }
+        old_path
+    };
+
+    info!(
+        "building {tool_name} delta of {} from {}",
+        delta.src_path, delta.old_path
+    );
+    let mut cmd = Command::new(&tool.cmd);
+    match delta.format {
+        DeltaFormat::Zstd => {
+            cmd.arg("-q")
+                .arg("-f")
+                .arg("-19")
+                .arg(format!("--patch-from={old_path}"))
+                .arg(&delta.src_path)
+                .arg("-o")
+                .arg(&delta.dest_path);
+        }
+        DeltaFormat::Bsdiff => {
+            cmd.arg(&old_path)
+                .arg(&delta.src_path)
+                .arg(&delta.dest_path);
+        }
+    }
+    let status = cmd.status();
+    if is_url {
+        // Only clean up the file if we're the ones who made it
+        let _ = std::fs::remove_file(&old_path);
+    }
+    let status = status.map_err(|_| DistError::DeltaToolNotFound {
+        tool: tool_name.clone(),
+    })?;
+    if !status.success() {
+        return Err(DistError::DeltaBuild {
+            tool: tool_name,
+            src_path: delta.src_path.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Download the previous version of an archive
+///
+/// We don't have an http client of our own, so like the installers we lean on curl.
+fn fetch(url: &str, dest_path: &Utf8Path) -> DistResult<()> {
+    info!("fetching {url} to build a delta against");
+    let status = Command::new("curl")
+        .arg("--fail")
+        .arg("--silent")
+        .arg("--show-error")
+        .arg("--location")
+        .arg("--output")
+        .arg(dest_path)
+        .arg(url)
+        .status();
+    if !status.is_ok_and(|status| status.success()) {
+        return Err(DistError::DeltaPreviousMissing {
+            old_path: url.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Write the script for applying a release's deltas
+pub(crate) fn write_delta_script(
+    templates: &Templates,
+    script: &DeltaScriptImpl,
+) -> DistResult<()> {
+    let contents = render_delta_script(templates, script)?;
+    LocalAsset::write_new(&contents, &script.dest_path)?;
+    Ok(())
+}
+
+fn render_delta_script(templates: &Templates, script: &DeltaScriptImpl) -> DistResult<String> {
+    let info = DeltaScriptInfo {
+        app_name: &script.app_name,
+        format: script.format.to_string(),
+        ext: script.format.ext(),
+    };
+    templates.render_file_to_clean_string(TEMPLATE_METADATA_APPLY_DELTA, &info)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn script(format: DeltaFormat) -> String {
+        let templates = Templates::new().unwrap();
+        let script = DeltaScriptImpl {
+            format,
+            app_name: "axolotlsay".to_owned(),
+            dest_path: Utf8PathBuf::from("axolotlsay-apply-delta.sh"),
+        };
+        render_delta_script(&templates, &script).unwrap()
+    }
+
+    #[test]
+    fn delta_script_uses_the_right_tool() {
+        let zstd = script(DeltaFormat::Zstd);
+        assert!(zstd.starts_with("#!/bin/sh\n# Rebuilds an archive of axolotlsay"));
+        assert!(zstd.contains("(the `.patch.zst` files)"));
+        assert!(zstd.contains("\nzstd -q -d --long=31 --patch-from=\"$1\" \"$2\" -o \"$3\"\n"));
+        assert!(!zstd.contains("bspatch"));
+
+        let bsdiff = script(DeltaFormat::Bsdiff);
+        assert!(bsdiff.contains("\nbspatch \"$1\" \"$3\" \"$2\"\n"));
+        assert!(!bsdiff.contains("zstd"));
+    }
+}
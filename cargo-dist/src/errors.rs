@@ -43,6 +43,16 @@ pub enum DistError {
         details: minijinja::Error,
     },
 
+    /// Failed to write an archive (zip/tarball)
+    #[error("Failed to write archive {dest_path}")]
+    Archive {
+        /// The archive we were trying to write
+        dest_path: Utf8PathBuf,
+        /// The underlying error
+        #[source]
+        details: std::io::Error,
+    },
+
     /// Error from (cargo-)wix
     #[error("WiX returned an error while building {msi}")]
     Wix {
@@ -73,6 +83,18 @@ pub enum DistError {
         cause: serde_json::Error,
     },
 
+    /// metadata.dist in Cargo.toml has a key we don't know (probably a typo)
+    #[error("Unknown key {key} in metadata.dist in {manifest_path}")]
+    UnknownConfigKey {
+        /// path to file
+        manifest_path: Utf8PathBuf,
+        /// The key
+        key: String,
+        /// Suggested fix
+        #[help]
+        help: String,
+    },
+
     /// User declined to update cargo-dist, refuse to make progress
     #[error(
         "to update your cargo-dist config you must use the version your project is configured for"
@@ -98,10 +120,6 @@ pub enum DistError {
     #[error("Github CI support requires you to specify the URL of your repository")]
     #[diagnostic(help(r#"Set the repository = "https://github.com/..." key in your Cargo.toml"#))]
     CantEnableGithubNoUrl,
-    /// User declined to force tar.gz with npm
-    #[error("Cannot enable npm support without forcing artifacts to be .tar.gz")]
-    MustEnableTarGz,
-
     /// Completely unknown format to install-path
     ///
     /// NOTE: we can't use `diagnostic(help)` here because this will get crammed into
@@ -145,6 +163,55 @@ pub enum DistError {
     NoTagMatch {
         /// The --tag
         tag: String,
+        /// The formats we would have accepted
+        #[help]
+        help: String,
+    },
+
+    /// A unified version tag was given, but no package has that version
+    #[error("The provided announcement tag ({tag}) is for version {version}, but none of the packages in your workspace have that version")]
+    NoPackageWithTagVersion {
+        /// The --tag
+        tag: String,
+        /// The version the tag was for
+        version: semver::Version,
+        /// The versions we would have accepted
+        #[help]
+        help: String,
+    },
+
+    /// An announcement-group lists a package that isn't in the workspace
+    #[error(
+        "announcement-groups.{group} includes {package}, but there's no package with that name"
+    )]
+    #[diagnostic(help(
+        "announcement-groups should only list the names of packages in your workspace"
+    ))]
+    UnknownGroupPackage {
+        /// The group
+        group: String,
+        /// The package that doesn't exist
+        package: String,
+    },
+
+    /// exclude-packages (or --exclude) names a package that isn't in the workspace
+    #[error("couldn't exclude {package}, there's no package with that name")]
+    #[diagnostic(help(
+        "--exclude and exclude-packages take the names of packages in your workspace"
+    ))]
+    UnknownExcludedPackage {
+        /// The package that doesn't exist
+        package: String,
+    },
+
+    /// binaries-from names a package that isn't in the workspace
+    #[error("{package} takes binaries from {member}, but there's no package with that name")]
+    #[diagnostic(help("binaries-from takes the names of packages in your workspace"))]
+    UnknownBinariesFromPackage {
+        /// The package with the binaries-from
+        package: String,
+        /// The package that doesn't exist
+        member: String,
     },
 
     /// parse_tag concluded there was nothing to release
@@ -233,6 +300,295 @@ pub enum DistError {
         /// The missing keys
         keys: &'static [&'static str],
     },
+    /// gpg-key was set but gpg doesn't have the secret key
+    #[error("couldn't find a gpg secret key for gpg-key = \"{key}\"")]
+    #[diagnostic(help(
+        "import the key on the machine building your artifacts (e.g. with 'gpg --import')"
+    ))]
+    GpgKeyNotFound {
+        /// The key id or fingerprint we were told to sign with
+        key: String,
+    },
+    /// gpg-key was set but gpg couldn't be run
+    #[error("failed to run gpg to sign artifacts")]
+    #[diagnostic(help("gpg-key is set, so gpg needs to be installed to build artifacts"))]
+    GpgNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// gpg ran but didn't sign the file
+    #[error("gpg failed to sign {src_path}")]
+    GpgSign {
+        /// The file we were signing
+        src_path: Utf8PathBuf,
+    },
+    /// delta-from was set but the tool for building deltas couldn't be run
+    #[error("failed to run {tool} to build deltas")]
+    #[diagnostic(help(
+        "delta-from is set, so {tool} needs to be installed to build deltas against the previous release"
+    ))]
+    DeltaToolNotFound {
+        /// The tool we needed
+        tool: String,
+    },
+    /// The previous version of an archive couldn't be found
+    #[error("couldn't get {old_path} to build a delta against")]
+    #[diagnostic(help(
+        "delta-from should point at the previous release's archives (targets that weren't in the previous release can't have deltas)"
+    ))]
+    DeltaPreviousMissing {
+        /// The URL or path we looked at
+        old_path: String,
+    },
+    /// The delta tool ran but didn't build the delta
+    #[error("{tool} failed to build a delta for {src_path}")]
+    DeltaBuild {
+        /// The tool we ran
+        tool: String,
+        /// The file we were building a delta for
+        src_path: Utf8PathBuf,
+    },
+    /// rpm installers were requested but rpmbuild couldn't be run
+    #[error("failed to run rpmbuild to build an rpm")]
+    #[diagnostic(help(
+        "rpm installers are enabled, so rpmbuild needs to be installed to build them"
+    ))]
+    RpmbuildNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// rpmbuild ran but didn't build the rpm
+    #[error("rpmbuild failed to build {rpm}")]
+    Rpmbuild {
+        /// The rpm we were trying to build
+        rpm: String,
+    },
+    /// AppImages were requested but appimagetool couldn't be run
+    #[error("failed to run appimagetool to build an AppImage")]
+    #[diagnostic(help(
+        "appimage installers are enabled, so appimagetool needs to be installed to build them (see https://github.com/AppImage/appimagetool)"
+    ))]
+    AppImageToolNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// appimagetool ran but didn't build the AppImage
+    #[error("appimagetool failed to build {appimage}")]
+    AppImageTool {
+        /// The AppImage we were trying to build
+        appimage: String,
+    },
+    /// Snaps were requested but snapcraft couldn't be run
+    #[error("failed to run snapcraft to build a snap")]
+    #[diagnostic(help(
+        "snap installers are enabled, so snapcraft needs to be installed to build them (see https://snapcraft.io/docs/snapcraft-overview)"
+    ))]
+    SnapcraftNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// snapcraft ran but didn't build the snap
+    #[error("snapcraft failed to build {snap}")]
+    Snapcraft {
+        /// The snap we were trying to build
+        snap: String,
+    },
+    /// macOS pkgs were requested but pkgbuild/productbuild couldn't be run
+    #[error("failed to run {tool} to build a macOS pkg")]
+    #[diagnostic(help(
+        "mac-pkg installers are enabled, so they need to be built on macOS (which comes with pkgbuild and productbuild)"
+    ))]
+    PkgbuildNotFound {
+        /// The tool we tried to run
+        tool: String,
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// pkgbuild or productbuild ran but didn't build the pkg
+    #[error("{tool} failed to build {pkg}")]
+    MacPkgBuild {
+        /// The tool that failed
+        tool: String,
+        /// The pkg we were trying to build
+        pkg: String,
+    },
+    /// NSIS installers were requested but makensis couldn't be run
+    #[error("failed to run makensis to build an NSIS installer")]
+    #[diagnostic(help(
+        "nsis installers are enabled, so makensis needs to be installed to build them (see https://nsis.sourceforge.io)"
+    ))]
+    MakensisNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// makensis ran but didn't build the installer
+    #[error("makensis failed to build {installer}")]
+    Makensis {
+        /// The installer we were trying to build
+        installer: String,
+    },
+    /// dmgs were requested but hdiutil couldn't be run
+    #[error("failed to run hdiutil to build a dmg")]
+    #[diagnostic(help(
+        "dmg installers are enabled, so they need to be built on macOS (which comes with hdiutil)"
+    ))]
+    HdiutilNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// hdiutil ran but didn't build the dmg
+    #[error("hdiutil failed to build {dmg}")]
+    Hdiutil {
+        /// The dmg we were trying to build
+        dmg: String,
+    },
+    /// Flatpaks were requested but flatpak-builder couldn't be run
+    #[error("failed to run flatpak-builder to build a Flatpak bundle")]
+    #[diagnostic(help(
+        "flatpak installers are enabled, so flatpak-builder needs to be installed to build them (see https://docs.flatpak.org/en/latest/flatpak-builder.html)"
+    ))]
+    FlatpakBuilderNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// flatpak-builder ran but didn't build the bundle
+    #[error("flatpak-builder failed to build {bundle}")]
+    FlatpakBuild {
+        /// The bundle we were trying to build
+        bundle: String,
+    },
+    /// A WinGet manifest needs the sha256 of an msi that wasn't built
+    #[error("couldn't read {checksum_path} to put in the WinGet installer manifest")]
+    #[diagnostic(help(
+        "the msis (and their checksums) need to be built before the WinGet manifests"
+    ))]
+    WinGetMissingChecksum {
+        /// The checksum file we expected
+        checksum_path: Utf8PathBuf,
+    },
+    /// An installer that embeds its archives is being built before them
+    #[error("couldn't find {archive_path} to embed in {installer}")]
+    #[diagnostic(help(
+        "with embed-archives = true the archives need to be built before the shell installer (try --artifacts=all)"
+    ))]
+    MissingEmbeddedArchive {
+        /// The installer we were writing
+        installer: String,
+        /// The archive we expected
+        archive_path: Utf8PathBuf,
+    },
+    /// SOURCE_DATE_EPOCH isn't a timestamp we can put in an archive
+    #[error("SOURCE_DATE_EPOCH is set to {value:?}, which isn't a valid timestamp")]
+    #[diagnostic(help(
+        "SOURCE_DATE_EPOCH should be a number of seconds since 1970-01-01 (and before 2106)"
+    ))]
+    InvalidSourceDateEpoch {
+        /// The value it was set to
+        value: String,
+    },
+    /// slim-archives was set but strip couldn't be run
+    #[error("failed to run strip to make slim archives")]
+    #[diagnostic(help("slim-archives is set, so strip needs to be installed to build archives"))]
+    StripNotFound {
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// strip ran but didn't strip the binary
+    #[error("strip failed to remove debuginfo from {path}")]
+    Strip {
+        /// The binary we were stripping
+        path: Utf8PathBuf,
+    },
+    /// A generated-assets command couldn't be run
+    #[error("failed to run {command} to generate {dest_path}")]
+    GeneratedAssetCommand {
+        /// The command
+        command: String,
+        /// The file it was generating
+        dest_path: Utf8PathBuf,
+        /// The error
+        #[source]
+        details: std::io::Error,
+    },
+    /// A generated-assets command ran but failed
+    #[error("{command} failed to generate {dest_path}")]
+    #[diagnostic(help("this is one of the generated-assets in your config"))]
+    GeneratedAsset {
+        /// The command
+        command: String,
+        /// The file it was generating
+        dest_path: Utf8PathBuf,
+    },
+    /// tag-format doesn't say where the version goes
+    #[error("tag-format = \"{tag_format}\" must contain {{version}} exactly once")]
+    #[diagnostic(help("the default is \"v{{version}}\""))]
+    TagFormatVersion {
+        /// The bad tag-format
+        tag_format: String,
+    },
+    /// host-target doesn't look like a target triple
+    #[error("host target \"{host_target}\" doesn't look like a target triple")]
+    #[diagnostic(help("target triples look like \"x86_64-unknown-linux-gnu\""))]
+    InvalidHostTarget {
+        /// The bad host target
+        host_target: String,
+    },
+    /// dist-subdir isn't something we can put in target/
+    #[error("dist-subdir = \"{subdir}\" must be the name of a single directory")]
+    #[diagnostic(help("the default is \"distrib\""))]
+    DistSubdirInvalid {
+        /// The bad dist-subdir
+        subdir: String,
+    },
+    /// dist-subdir would collide with a dir cargo uses
+    #[error("dist-subdir = \"{subdir}\" clashes with a directory cargo builds in")]
+    #[diagnostic(help(
+        "cargo builds each profile in target/<profile>, so pick a name that isn't a profile (or debug, release, doc, package, tmp)"
+    ))]
+    DistSubdirCollision {
+        /// The bad dist-subdir
+        subdir: String,
+    },
+    /// build-profile would build in the same dir cargo-dist puts its own things in
+    #[error("build-profile = \"{profile}\" clashes with dist-subdir")]
+    #[diagnostic(help(
+        "cargo builds each profile in target/<profile>, which is where cargo-dist puts its artifacts (target/distrib by default), so pick a different profile or dist-subdir"
+    ))]
+    BuildProfileCollision {
+        /// The profile
+        profile: String,
+    },
+    /// An extra publish job has the same name as another publish job
+    #[error("there's more than one publish job called \"{name}\"")]
+    #[diagnostic(help(
+        "extra-publish-jobs can't reuse the names of built-in publish jobs or each other"
+    ))]
+    DuplicatePublishJob {
+        /// The name of the job
+        name: String,
+    },
+    /// A package is missing a binary it was configured to require
+    #[error("{package} is missing required binary \"{binary}\"")]
+    #[diagnostic(help(
+        "{package}'s binaries are {available:?}, either add the binary back or remove it from required-binaries"
+    ))]
+    MissingRequiredBinary {
+        /// The package
+        package: String,
+        /// The binary that's gone missing
+        binary: String,
+        /// The binaries the package actually has
+        available: Vec<String>,
+    },
     /// unrecognized style
     #[error("{style} is not a recognized value")]
     #[diagnostic(help("Jobs that do not come with cargo-dist should be prefixed with ./"))]
@@ -240,6 +596,69 @@ pub enum DistError {
         /// Name of the msi
         style: String,
     },
+    /// The Docker publish job is enabled but doesn't know where to push to
+    #[error("the docker publish job is enabled, but {setting} isn't set")]
+    #[diagnostic(help(
+        "set docker-registry and docker-image in [workspace.metadata.dist], or remove \"docker\" from publish-jobs"
+    ))]
+    DockerConfigMissing {
+        /// The missing setting
+        setting: String,
+    },
+    /// The OCI publish job is enabled but doesn't know where to push to
+    #[error("the oci publish job is enabled, but oci-registry isn't set")]
+    #[diagnostic(help(
+        "set oci-registry in [workspace.metadata.dist], or remove \"oci\" from publish-jobs"
+    ))]
+    OciRegistryMissing,
+    /// require-changelog is set but there are no notes for the release
+    #[error("couldn't find any release notes for {version} in the {changelog_owner} changelogs")]
+    #[diagnostic(help(
+        "add a section for {version} to your CHANGELOG/RELEASES, or unset require-changelog"
+    ))]
+    ChangelogMissing {
+        /// The version we were looking for
+        version: String,
+        /// The workspace or package whose changelogs we looked in
+        changelog_owner: String,
+    },
+    /// changelog-from-commits is enabled but the git history couldn't be read
+    #[error("couldn't read the git history to generate release notes for {tag}: {details}")]
+    #[diagnostic(help(
+        "changelog-from-commits needs the repository's history and tags (in CI, check that your checkout isn't shallow)"
+    ))]
+    GitLog {
+        /// The tag we were generating notes for
+        tag: String,
+        /// What git had to say about it
+        details: String,
+    },
+    /// git-cliff is enabled but couldn't be run
+    #[error("git-cliff is set, but git-cliff couldn't be run")]
+    #[diagnostic(help("install git-cliff (cargo install git-cliff), or unset git-cliff"))]
+    GitCliffNotFound,
+    /// git-cliff ran but failed to generate release notes
+    #[error("git-cliff failed to generate release notes for {tag}")]
+    GitCliff {
+        /// The tag we were generating notes for
+        tag: String,
+        /// What git-cliff had to say about it
+        #[help]
+        details: String,
+    },
+    /// A feature variant has a name that can't be used in artifact names
+    #[error(
+        "{package} has a feature variant named \"{name}\", which can't be used in artifact names"
+    )]
+    #[diagnostic(help(
+        "feature variant names should be made of letters, numbers, '-', and '_' (and \"demo\" is reserved for demo-targets)"
+    ))]
+    FeatureVariantName {
+        /// The package with the variant
+        package: String,
+        /// The name of the variant
+        name: String,
+    },
 }
 
 impl From<minijinja::Error> for DistError {
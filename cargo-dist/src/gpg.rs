@@ -0,0 +1,56 @@
+//! Signing artifacts with gpg
+//!
+//! We shell out to the user's `gpg` so that it can use whatever keyring (or agent,
+//! or smartcard) they've already set up, producing ASCII-armored detached signatures
+//! that can be checked with `gpg --verify my-app.tar.gz.asc my-app.tar.gz`.
+
+use std::process::Command;
+
+use camino::Utf8Path;
+use tracing::info;
+
+use crate::errors::{DistError, DistResult};
+
+/// Sign src_path with the given key, writing a detached signature to dest_path
+///
+/// The key should already have been checked with [`ensure_secret_key`][].
+pub(crate) fn sign_file(key: &str, src_path: &Utf8Path, dest_path: &Utf8Path) -> DistResult<()> {
+    info!("signing {src_path} with gpg key {key}");
+    let status = Command::new("gpg")
+        .arg("--batch")
+        .arg("--yes")
+        .arg("--local-user")
+        .arg(key)
+        .arg("--armor")
+        .arg("--detach-sign")
+        .arg("--output")
+        .arg(dest_path)
+        .arg(src_path)
+        .status()
+        .map_err(|details| DistError::GpgNotFound { details })?;
+    if !status.success() {
+        return Err(DistError::GpgSign {
+            src_path: src_path.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Check that gpg actually has the secret half of the key
+///
+/// Without this gpg may silently pick some other key (or prompt for one), so
+/// we'd rather fail loudly before building anything.
+pub(crate) fn ensure_secret_key(key: &str) -> DistResult<()> {
+    let output = Command::new("gpg")
+        .arg("--batch")
+        .arg("--list-secret-keys")
+        .arg(key)
+        .output()
+        .map_err(|details| DistError::GpgNotFound { details })?;
+    if !output.status.success() {
+        return Err(DistError::GpgKeyNotFound {
+            key: key.to_owned(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,477 @@
+//! A machine-readable dump of the whole [`DistGraph`][]
+//!
+//! dist-manifest.json only describes the artifacts a build produces (and is a stable
+//! format). This instead exposes everything that gather_work computed (releases, variants,
+//! binaries, artifacts, and the build steps that will make them) so that tooling can
+//! introspect exactly what a build would do. Everything refers to everything else by its
+//! index in the relevant list, just like the DistGraph itself does.
+//!
+//! This format is not stable, it changes whenever the DistGraph does.
+//!
+//! There's also a human-readable listing of the build steps ([`format_build_steps`][]),
+//! for `cargo dist build --dry-run`.
+
+use std::fmt::Write;
+
+use camino::Utf8PathBuf;
+use serde::Serialize;
+
+use crate::{
+    backend::{citation::CitationInfo, installer::InstallerImpl},
+    ArtifactKind, BuildStep, CargoBuildCommand, CargoBuildStep, CargoTargetFeatureList,
+    CargoTargetPackages, ChecksumImpl, CopyDirStep, CopyFileStep, DeltaImpl, DeltaScriptImpl,
+    DistGraph, GenerateAssetStep, ReleaseNotesImpl, SignatureImpl, StripStep, UnifiedChecksumStep,
+    ZipDirStep,
+};
+
+/// The whole DistGraph
+#[derive(Debug, Serialize)]
+pub struct GraphJson {
+    /// The root directory of the cargo workspace
+    pub workspace_dir: Utf8PathBuf,
+    /// The cargo target dir
+    pub target_dir: Utf8PathBuf,
+    /// cargo-dist's dir in the target dir, where artifacts end up
+    pub dist_dir: Utf8PathBuf,
+    /// The Releases of the Announcement
+    pub releases: Vec<ReleaseJson>,
+    /// Every Variant of every Release
+    pub variants: Vec<VariantJson>,
+    /// Every Binary that will be built
+    pub binaries: Vec<BinaryJson>,
+    /// Every Artifact that will be produced
+    pub artifacts: Vec<ArtifactJson>,
+    /// The steps to build everything, in an order they could be run one at a time
+    pub build_steps: Vec<BuildStepJson>,
+}
+
+/// A Release of an app
+#[derive(Debug, Serialize)]
+pub struct ReleaseJson {
+    /// The unique id of the release (e.g. "my-app-v1.0.0")
+    pub id: String,
+    /// The name of the app
+    pub app_name: String,
+    /// The version of the app
+    pub version: String,
+    /// Targets this Release has artifacts for
+    pub targets: Vec<String>,
+    /// The names of the binaries every variant should provide
+    pub bins: Vec<String>,
+    /// The variants of this release (including demos and feature variants)
+    pub variants: Vec<usize>,
+    /// The artifacts that aren't specific to any one variant
+    pub global_artifacts: Vec<usize>,
+}
+
+/// A Variant of a Release (e.g. "the macos build")
+#[derive(Debug, Serialize)]
+pub struct VariantJson {
+    /// The unique id of the variant (e.g. "my-app-v1.0.0-x86_64-pc-windows-msvc")
+    pub id: String,
+    /// The target triple this variant is for
+    pub target: String,
+    /// The binaries in this variant
+    pub binaries: Vec<usize>,
+    /// The artifacts specific to this variant (archives, symbols, msi installers...)
+    pub local_artifacts: Vec<usize>,
+}
+
+/// A Binary we need to build
+#[derive(Debug, Serialize)]
+pub struct BinaryJson {
+    /// The unique id of the binary (e.g. "my-binary-v1.0.0-x86_64-pc-windows-msvc")
+    pub id: String,
+    /// The name of the binary (as defined by the Cargo.toml)
+    pub name: String,
+    /// The package that defines the binary (in a form cargo -p accepts)
+    pub pkg_spec: String,
+    /// The target triple to build it for
+    pub target: String,
+    /// The file name the binary will have
+    pub file_name: String,
+    /// Places the executable will be copied to
+    pub copy_exe_to: Vec<Utf8PathBuf>,
+    /// Places the symbols will be copied to
+    pub copy_symbols_to: Vec<Utf8PathBuf>,
+    /// The artifact for the binary's symbols
+    pub symbols_artifact: Option<usize>,
+}
+
+/// An Artifact we will produce
+#[derive(Debug, Serialize)]
+pub struct ArtifactJson {
+    /// The unique id of the artifact (its file name)
+    pub id: String,
+    /// The kind of artifact (e.g. "executable-zip", "checksum", "installer")
+    pub kind: &'static str,
+    /// For installers, the kind of installer (e.g. "shell", "msi")
+    pub installer: Option<&'static str>,
+    /// The target triples this artifact is for
+    pub target_triples: Vec<String>,
+    /// Where the artifact will end up
+    pub file_path: Utf8PathBuf,
+    /// If the artifact is an archive, the dir its contents are staged in
+    pub archive_dir: Option<Utf8PathBuf>,
+    /// The binaries this artifact contains
+    pub required_binaries: Vec<usize>,
+    /// The checksum of this artifact
+    pub checksum: Option<usize>,
+    /// The signature of this artifact
+    pub signature: Option<usize>,
+    /// Whether this is a global artifact (as opposed to one local to a variant)
+    pub is_global: bool,
+}
+
+/// A step we will run to build things
+#[derive(Debug, Serialize)]
+pub struct BuildStepJson {
+    /// The kind of step (e.g. "cargo", "zip", "checksum")
+    pub kind: &'static str,
+    /// The files (or dirs) this step reads, or None if it can read anything
+    pub inputs: Option<Vec<Utf8PathBuf>>,
+    /// The files (or dirs) this step writes, or None if it can write anything
+    pub outputs: Option<Vec<Utf8PathBuf>>,
+    /// The steps that have to be done before this one can run
+    pub deps: Vec<usize>,
+    /// If this is a cargo build, the details of it
+    pub cargo: Option<CargoBuildJson>,
+}
+
+/// The details of a cargo build
+#[derive(Debug, Serialize)]
+pub struct CargoBuildJson {
+    /// The tool that runs the build ("cargo" or "cross")
+    pub command: &'static str,
+    /// The --target triple to pass
+    pub target_triple: String,
+    /// The package to build, or None for the whole workspace
+    pub package: Option<String>,
+    /// The --profile to pass
+    pub profile: String,
+    /// The value to set for RUSTFLAGS
+    pub rustflags: String,
+    /// Whether default features are enabled
+    pub default_features: bool,
+    /// Whether all features are enabled
+    pub all_features: bool,
+    /// Features to enable
+    pub features: Vec<String>,
+    /// The binaries this build will produce
+    pub expected_binaries: Vec<usize>,
+}
+
+impl GraphJson {
+    /// Dump a DistGraph
+    pub fn new(dist: &DistGraph) -> Self {
+        let releases = dist
+            .releases
+            .iter()
+            .map(|release| ReleaseJson {
+                id: release.id.clone(),
+                app_name: release.app_name.clone(),
+                version: release.version.to_string(),
+                targets: release.targets.clone(),
+                bins: release.bins.iter().map(|(_, bin)| bin.clone()).collect(),
+                variants: release.all_variants().map(|idx| idx.0).collect(),
+                global_artifacts: release.global_artifacts.iter().map(|idx| idx.0).collect(),
+            })
+            .collect();
+        let variants = dist
+            .variants
+            .iter()
+            .map(|variant| VariantJson {
+                id: variant.id.clone(),
+                target: variant.target.clone(),
+                binaries: variant.binaries.iter().map(|idx| idx.0).collect(),
+                local_artifacts: variant.local_artifacts.iter().map(|idx| idx.0).collect(),
+            })
+            .collect();
+        let binaries = dist
+            .binaries
+            .iter()
+            .map(|binary| BinaryJson {
+                id: binary.id.clone(),
+                name: binary.name.clone(),
+                pkg_spec: binary.pkg_spec.clone(),
+                target: binary.target.clone(),
+                file_name: binary.file_name.clone(),
+                copy_exe_to: binary.copy_exe_to.clone(),
+                copy_symbols_to: binary.copy_symbols_to.clone(),
+                symbols_artifact: binary.symbols_artifact.map(|idx| idx.0),
+            })
+            .collect();
+        let artifacts = dist
+            .artifacts
+            .iter()
+            .map(|artifact| {
+                let mut required_binaries = artifact
+                    .required_binaries
+                    .keys()
+                    .map(|idx| idx.0)
+                    .collect::<Vec<_>>();
+                required_binaries.sort();
+                let (kind, installer) = artifact_kind(&artifact.kind);
+                ArtifactJson {
+                    id: artifact.id.clone(),
+                    kind,
+                    installer,
+                    target_triples: artifact.target_triples.clone(),
+                    file_path: artifact.file_path.clone(),
+                    archive_dir: artifact.archive.as_ref().map(|a| a.dir_path.clone()),
+                    required_binaries,
+                    checksum: artifact.checksum.map(|idx| idx.0),
+                    signature: artifact.signature.map(|idx| idx.0),
+                    is_global: artifact.is_global,
+                }
+            })
+            .collect();
+        let to_owned = |paths: Option<Vec<&camino::Utf8Path>>| {
+            paths.map(|paths| paths.into_iter().map(|p| p.to_owned()).collect())
+        };
+        let build_steps = dist
+            .build_steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| BuildStepJson {
+                kind: step_kind(step),
+                inputs: to_owned(step.inputs()),
+                outputs: to_owned(step.outputs(dist)),
+                deps: dist.build_plan.deps.get(idx).cloned().unwrap_or_default(),
+                cargo: match step {
+                    BuildStep::Cargo(cargo) => Some(CargoBuildJson {
+                        command: match cargo.command {
+                            CargoBuildCommand::Cargo => "cargo",
+                            CargoBuildCommand::Cross => "cross",
+                        },
+                        target_triple: cargo.target_triple.clone(),
+                        package: match &cargo.package {
+                            CargoTargetPackages::Workspace => None,
+                            CargoTargetPackages::Package(package) => Some(package.clone()),
+                        },
+                        profile: cargo.profile.clone(),
+                        rustflags: cargo.rustflags.clone(),
+                        default_features: cargo.features.default_features,
+                        all_features: matches!(
+                            cargo.features.features,
+                            CargoTargetFeatureList::All
+                        ),
+                        features: match &cargo.features.features {
+                            CargoTargetFeatureList::All => vec![],
+                            CargoTargetFeatureList::List(features) => features.clone(),
+                        },
+                        expected_binaries: cargo.expected_binaries.iter().map(|i| i.0).collect(),
+                    }),
+                    _ => None,
+                },
+            })
+            .collect();
+
+        GraphJson {
+            workspace_dir: dist.workspace_dir.clone(),
+            target_dir: dist.target_dir.clone(),
+            dist_dir: dist.dist_dir.clone(),
+            releases,
+            variants,
+            binaries,
+            artifacts,
+            build_steps,
+        }
+    }
+}
+
+/// The name of a kind of artifact (and of the kind of installer, if it's one)
+fn artifact_kind(kind: &ArtifactKind) -> (&'static str, Option<&'static str>) {
+    let installer = match kind {
+        ArtifactKind::ExecutableZip(_) => return ("executable-zip", None),
+        ArtifactKind::Symbols(_) => return ("symbols", None),
+        ArtifactKind::Checksum(_) => return ("checksum", None),
+        ArtifactKind::Signature(_) => return ("signature", None),
+        ArtifactKind::Citation(_) => return ("citation", None),
+        ArtifactKind::ReleaseNotes(_) => return ("release-notes", None),
+        ArtifactKind::UnifiedChecksum(_) => return ("unified-checksum", None),
+        ArtifactKind::Delta(_) => return ("delta", None),
+        ArtifactKind::DeltaScript(_) => return ("delta-script", None),
+        ArtifactKind::Installer(installer) => installer,
+    };
+    ("installer", Some(installer_kind(installer)))
+}
+
+/// The name of a kind of installer
+fn installer_kind(installer: &InstallerImpl) -> &'static str {
+    match installer {
+        InstallerImpl::Shell(_) => "shell",
+        InstallerImpl::ShellSuite(_) => "shell-suite",
+        InstallerImpl::OfflineBundle(_) => "offline-bundle",
+        InstallerImpl::Powershell(_) => "powershell",
+        InstallerImpl::Npm(_) => "npm",
+        InstallerImpl::Homebrew(_) => "homebrew",
+        InstallerImpl::Scoop(_) => "scoop",
+        InstallerImpl::Msi(_) => "msi",
+        InstallerImpl::Deb(_) => "deb",
+        InstallerImpl::Rpm(_) => "rpm",
+        InstallerImpl::AppImage(_) => "appimage",
+        InstallerImpl::FreebsdPkg(_) => "freebsd-pkg",
+        InstallerImpl::Flatpak(_) => "flatpak",
+        InstallerImpl::Snap(_) => "snap",
+        InstallerImpl::MacPkg(_) => "mac-pkg",
+        InstallerImpl::Dmg(_) => "dmg",
+        InstallerImpl::AppBundle(_) => "app",
+        InstallerImpl::Nsis(_) => "nsis",
+        InstallerImpl::WinGet(_) => "winget",
+    }
+}
+
+/// The name of a kind of build step
+fn step_kind(step: &BuildStep) -> &'static str {
+    match step {
+        BuildStep::Cargo(_) => "cargo",
+        BuildStep::Rustup(_) => "rustup",
+        BuildStep::CopyFile(_) => "copy-file",
+        BuildStep::CopyDir(_) => "copy-dir",
+        BuildStep::Zip(_) => "zip",
+        BuildStep::GenerateAsset(_) => "generate-asset",
+        BuildStep::GenerateInstaller(_) => "generate-installer",
+        BuildStep::Checksum(_) => "checksum",
+        BuildStep::Sign(_) => "sign",
+        BuildStep::GenerateCitation(_) => "generate-citation",
+        BuildStep::GenerateReleaseNotes(_) => "generate-release-notes",
+        BuildStep::UnifiedChecksum(_) => "unified-checksum",
+        BuildStep::Strip(_) => "strip",
+        BuildStep::Delta(_) => "delta",
+        BuildStep::GenerateDeltaScript(_) => "generate-delta-script",
+    }
+}
+
+/// List every build step in the order they'd be run one at a time, for humans
+///
+/// Steps that are `skipped` (because an incremental build found them up to date) are
+/// still listed, but marked as such.
+pub fn format_build_steps(dist: &DistGraph, skipped: &[bool]) -> String {
+    let mut output = String::new();
+    for (idx, step) in dist.build_steps.iter().enumerate() {
+        let mut lines = describe_step(dist, step).into_iter();
+        let first = lines.next().unwrap_or_default();
+        let skipped = if skipped.get(idx).copied().unwrap_or(false) {
+            " (up to date, skipped)"
+        } else {
+            ""
+        };
+        writeln!(output, "  {}. {first}{skipped}", idx + 1).unwrap();
+        for line in lines {
+            writeln!(output, "       {line}").unwrap();
+        }
+    }
+    output
+}
+
+/// Describe a build step as a few lines of text (the first one says what it does)
+fn describe_step(dist: &DistGraph, step: &BuildStep) -> Vec<String> {
+    let line = match step {
+        BuildStep::Cargo(cargo) => return describe_cargo_build(dist, cargo),
+        BuildStep::Rustup(rustup) => rustup.command(),
+        BuildStep::CopyFile(CopyFileStep {
+            src_path,
+            dest_path,
+        }) => format!("copy {src_path} -> {dest_path}"),
+        BuildStep::CopyDir(CopyDirStep {
+            src_path,
+            dest_path,
+        }) => format!("copy dir {src_path} -> {dest_path}"),
+        BuildStep::Zip(ZipDirStep {
+            src_path,
+            dest_path,
+            ..
+        }) => format!("zip {src_path} -> {dest_path}"),
+        BuildStep::GenerateAsset(GenerateAssetStep {
+            command, dest_path, ..
+        }) => format!("run {} -> {dest_path}", command.join(" ")),
+        BuildStep::GenerateInstaller(installer) => {
+            format!("generate {} installer", installer_kind(installer))
+        }
+        BuildStep::Checksum(ChecksumImpl {
+            checksum,
+            src_path,
+            dest_path,
+        }) => format!("checksum ({}) {src_path} -> {dest_path}", checksum.ext()),
+        BuildStep::Sign(SignatureImpl {
+            key,
+            src_path,
+            dest_path,
+        }) => format!("sign (gpg key {key}) {src_path} -> {dest_path}"),
+        BuildStep::GenerateCitation(CitationInfo { dest_path, .. }) => {
+            format!("generate citation {dest_path}")
+        }
+        BuildStep::GenerateReleaseNotes(ReleaseNotesImpl { dest_path }) => {
+            format!("generate release notes {dest_path}")
+        }
+        BuildStep::UnifiedChecksum(UnifiedChecksumStep {
+            checksum,
+            src_paths,
+            dest_path,
+            ..
+        }) => format!(
+            "checksum ({}) {} files -> {dest_path}",
+            checksum.ext(),
+            src_paths.len()
+        ),
+        BuildStep::Strip(StripStep { paths }) => format!(
+            "strip {}",
+            paths
+                .iter()
+                .map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        BuildStep::Delta(DeltaImpl {
+            format,
+            old_path,
+            src_path,
+            dest_path,
+        }) => format!("delta ({format}) {old_path} to {src_path} -> {dest_path}"),
+        BuildStep::GenerateDeltaScript(DeltaScriptImpl { dest_path, .. }) => {
+            format!("generate delta script {dest_path}")
+        }
+    };
+    vec![line]
+}
+
+/// Describe a cargo build: the command line, its RUSTFLAGS, and what it should produce
+fn describe_cargo_build(dist: &DistGraph, cargo: &CargoBuildStep) -> Vec<String> {
+    let mut command = match cargo.command {
+        CargoBuildCommand::Cargo => "cargo".to_owned(),
+        CargoBuildCommand::Cross => "cross".to_owned(),
+    };
+    write!(
+        command,
+        " build --profile {} --target {}",
+        cargo.profile, cargo.target_triple
+    )
+    .unwrap();
+    if !cargo.features.default_features {
+        command.push_str(" --no-default-features");
+    }
+    match &cargo.features.features {
+        CargoTargetFeatureList::All => command.push_str(" --all-features"),
+        CargoTargetFeatureList::List(features) => {
+            if !features.is_empty() {
+                write!(command, " --features {}", features.join(",")).unwrap();
+            }
+        }
+    }
+    match &cargo.package {
+        CargoTargetPackages::Workspace => command.push_str(" --workspace"),
+        CargoTargetPackages::Package(package) => write!(command, " --package {package}").unwrap(),
+    }
+
+    let expected_binaries = cargo
+        .expected_binaries
+        .iter()
+        .map(|&idx| dist.binary(idx).id.as_str())
+        .collect::<Vec<_>>();
+    vec![
+        command,
+        format!("RUSTFLAGS={:?}", cargo.rustflags),
+        format!("expected binaries: {}", expected_binaries.join(", ")),
+    ]
+}
@@ -0,0 +1,237 @@
+//! Incremental builds (`cargo dist build --incremental`)
+//!
+//! Artifacts that know which files they're derived from ([`Artifact::inputs`][]) get a
+//! fingerprint of those files (their paths, sizes, and modification times) saved in the
+//! dist dir once they're built. On the next incremental build, an artifact that still
+//! exists and has the same fingerprint is left alone, and so is any build step that
+//! only produces files for artifacts like that.
+
+use std::time::UNIX_EPOCH;
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use tracing::info;
+
+use crate::{config::load_cargo_toml, errors::DistResult, Artifact, DistGraph};
+
+/// The dir (under the dist dir) that fingerprints are saved in
+const FINGERPRINT_DIR: &str = ".fingerprints";
+
+/// Which artifacts an incremental build can skip
+#[derive(Debug)]
+pub(crate) struct Freshness {
+    /// The current fingerprint of each artifact (None if it has no tracked inputs)
+    fingerprints: Vec<Option<String>>,
+    /// Whether each artifact is already up to date
+    pub fresh: Vec<bool>,
+}
+
+impl Freshness {
+    /// Check every artifact in the graph against the fingerprint from its last build
+    pub(crate) fn check(dist: &DistGraph) -> Self {
+        let fingerprints = dist
+            .artifacts
+            .iter()
+            .map(|artifact| fingerprint(dist, artifact))
+            .collect::<Vec<_>>();
+        let fresh = dist
+            .artifacts
+            .iter()
+            .zip(&fingerprints)
+            .map(|(artifact, fingerprint)| {
+                let Some(fingerprint) = fingerprint else {
+                    return false;
+                };
+                if !artifact.file_path.exists() {
+                    return false;
+                }
+                let saved = std::fs::read_to_string(fingerprint_path(dist, artifact));
+                saved.is_ok_and(|saved| saved == *fingerprint)
+            })
+            .collect();
+        Self {
+            fingerprints,
+            fresh,
+        }
+    }
+
+    /// Whether each of the graph's build steps can be skipped
+    ///
+    /// A step can be skipped if everything it writes belongs to an artifact that's up to
+    /// date. Steps that don't write anything, or that we don't know the outputs of
+    /// (installers), always run.
+    pub(crate) fn skipped_steps(&self, dist: &DistGraph) -> Vec<bool> {
+        let is_fresh_output = |path: &Utf8Path| {
+            dist.artifacts
+                .iter()
+                .zip(&self.fresh)
+                .any(|(artifact, &fresh)| {
+                    fresh
+                        && (artifact.file_path == path
+                            || artifact
+                                .archive
+                                .as_ref()
+                                .is_some_and(|archive| path.starts_with(&archive.dir_path)))
+                })
+        };
+        dist.build_steps
+            .iter()
+            .map(|step| {
+                step.outputs(dist).is_some_and(|outputs| {
+                    !outputs.is_empty() && outputs.into_iter().all(is_fresh_output)
+                })
+            })
+            .collect()
+    }
+
+    /// Forget the fingerprints of artifacts that are about to be rebuilt
+    ///
+    /// This way an artifact that fails to build (or is interrupted) won't look fresh next time.
+    pub(crate) fn clear_stale(&self, dist: &DistGraph) -> DistResult<()> {
+        for (artifact, &fresh) in dist.artifacts.iter().zip(&self.fresh) {
+            let path = fingerprint_path(dist, artifact);
+            if !fresh && path.exists() {
+                LocalAsset::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the fingerprints of every artifact we just built (or kept)
+    pub(crate) fn save(&self, dist: &DistGraph) -> DistResult<()> {
+        for (artifact, fingerprint) in dist.artifacts.iter().zip(&self.fingerprints) {
+            let Some(fingerprint) = fingerprint else {
+                continue;
+            };
+            let path = fingerprint_path(dist, artifact);
+            LocalAsset::create_dir_all(path.parent().expect("fingerprint had no parent dir!?"))?;
+            LocalAsset::write_new(fingerprint, &path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the dirs of every local (path or workspace path) dependency a package pulls in
+///
+/// Edits to those are just as much an edit to the binary as editing the package itself,
+/// but they don't show up in the lockfile. This follows dependencies of dependencies
+/// (including build-dependencies and target-specific ones), and quietly skips any
+/// manifest it can't read, since the build will complain about that far better than we can.
+pub(crate) fn local_dependency_roots(
+    workspace_manifest: &Utf8Path,
+    package_manifest: &Utf8Path,
+) -> Vec<Utf8PathBuf> {
+    let workspace_toml = load_cargo_toml(workspace_manifest).ok();
+    let workspace_dir = workspace_manifest.parent().unwrap_or(workspace_manifest);
+    let workspace_dep_path = |name: &str| {
+        let path = workspace_toml
+            .as_ref()?
+            .get("workspace")?
+            .get("dependencies")?
+            .get(name)?
+            .get("path")?
+            .as_str()?;
+        Some(workspace_dir.join(path))
+    };
+
+    let mut roots = vec![];
+    let mut manifests = vec![package_manifest.to_owned()];
+    while let Some(manifest) = manifests.pop() {
+        let Ok(toml) = load_cargo_toml(&manifest) else {
+            continue;
+        };
+        let manifest_dir = manifest.parent().unwrap_or(&manifest);
+        let mut dep_tables = vec![toml.get("dependencies"), toml.get("build-dependencies")];
+        if let Some(targets) = toml.get("target").and_then(|t| t.as_table_like()) {
+            for (_, target) in targets.iter() {
+                dep_tables.push(target.get("dependencies"));
+                dep_tables.push(target.get("build-dependencies"));
+            }
+        }
+        let dep_tables = dep_tables.into_iter().flatten();
+        for deps in dep_tables.filter_map(|deps| deps.as_table_like()) {
+            for (name, dep) in deps.iter() {
+                let root = if let Some(path) = dep.get("path").and_then(|path| path.as_str()) {
+                    Some(manifest_dir.join(path))
+                } else if dep.get("workspace").and_then(|ws| ws.as_bool()) == Some(true) {
+                    workspace_dep_path(name)
+                } else {
+                    None
+                };
+                if let Some(root) = root.filter(|root| !roots.contains(root)) {
+                    manifests.push(root.join("Cargo.toml"));
+                    roots.push(root);
+                }
+            }
+        }
+    }
+    roots
+}
+
+/// Where the fingerprint of an artifact's last build is saved
+fn fingerprint_path(dist: &DistGraph, artifact: &Artifact) -> Utf8PathBuf {
+    dist.dist_dir
+        .join(FINGERPRINT_DIR)
+        .join(format!("{}.fingerprint", artifact.id))
+}
+
+/// Compute the fingerprint of an artifact's inputs (if it has any)
+///
+/// This is just a listing of every input file and its size/mtime, hashed so it's a
+/// reasonable size to save.
+fn fingerprint(dist: &DistGraph, artifact: &Artifact) -> Option<String> {
+    use sha2::Digest;
+    use std::fmt::Write;
+
+    let inputs = artifact.inputs.as_ref()?;
+    let mut listing = format!("{}\n", artifact.id);
+    for input in inputs {
+        list_files(dist, input, &mut listing);
+    }
+    info!("fingerprinted inputs of {}", artifact.id);
+
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(listing.as_bytes());
+    let mut output = String::new();
+    for byte in hasher.finalize() {
+        write!(&mut output, "{:02x}", byte).unwrap();
+    }
+    Some(output)
+}
+
+/// Add a line for every file under path to the listing
+///
+/// Hidden files (like .git) and the target dir are skipped, since they change all the
+/// time but aren't sources that make it into the build.
+fn list_files(dist: &DistGraph, path: &Utf8Path, listing: &mut String) {
+    let Ok(metadata) = path.metadata() else {
+        listing.push_str(&format!("{path}\tmissing\n"));
+        return;
+    };
+    if !metadata.is_dir() {
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|time| time.as_nanos())
+            .unwrap_or_default();
+        listing.push_str(&format!("{path}\t{}\t{mtime}\n", metadata.len()));
+        return;
+    }
+    if path == dist.target_dir {
+        return;
+    }
+    let Ok(entries) = path.read_dir_utf8() else {
+        listing.push_str(&format!("{path}\tunreadable\n"));
+        return;
+    };
+    let mut children = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().starts_with('.'))
+        .map(|entry| entry.into_path())
+        .collect::<Vec<_>>();
+    children.sort();
+    for child in children {
+        list_files(dist, &child, listing);
+    }
+}
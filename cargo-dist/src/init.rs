@@ -6,10 +6,7 @@ use semver::Version;
 use serde::Deserialize;
 
 use crate::{
-    config::{
-        self, CiStyle, CompressionImpl, Config, DistMetadata, InstallerStyle, PublishStyle,
-        ZipStyle,
-    },
+    config::{self, CiStyle, Config, DistMetadata, InstallerStyle, PublishStyle},
     do_generate,
     errors::{DistError, DistResult, Result},
     GenerateArgs, SortedMap, METADATA_DIST, PROFILE_DIST,
@@ -223,6 +220,77 @@ fn get_new_dist_metadata(
             pr_run_mode: None,
             allow_dirty: None,
             ssldotcom_windows_sign: None,
+            citation: None,
+            release_notes_asset: None,
+            unified_checksum: None,
+            homebrew_libexec: None,
+            gpg_key: None,
+            checksum_jobs: None,
+            tag_format: None,
+            sentry_org: None,
+            sentry_project: None,
+            slim_archives: None,
+            extra_publish_jobs: None,
+            extra_publish_jobs_file: None,
+            required_binaries: None,
+            appimage_icon: None,
+            dist_subdir: None,
+            winget_id: None,
+            bucket: None,
+            per_target_staging_dirs: None,
+            flatpak_app_id: None,
+            flatpak_icon: None,
+            snap_confinement: None,
+            snap_grade: None,
+            snap_channel: None,
+            docker_registry: None,
+            docker_image: None,
+            docker_base_image: None,
+            shell_suite_installer: None,
+            binstall_metadata: None,
+            mac_pkg_identifier: None,
+            mac_pkg_install_location: None,
+            mac_pkg_sign_identity: None,
+            checksums_json: None,
+            nsis_program_files: None,
+            nsis_modify_path: None,
+            host_target: None,
+            offline_bundle: None,
+            download_table: None,
+            demo_targets: None,
+            app_bundle_identifier: None,
+            app_bundle_icon: None,
+            dist_unpublished: None,
+            bin_filter: None,
+            installer_bin_filter: None,
+            bin_aliases: None,
+            oci_registry: None,
+            target_features: None,
+            require_changelog: None,
+            github_custom_runners: None,
+            license_files: None,
+            ci_cache: None,
+            sccache_backend: None,
+            delta_from: None,
+            delta_format: None,
+            use_cross: None,
+            feature_variants: None,
+            build_profile: None,
+            versioning: None,
+            git_cliff: None,
+            changelog_from_commits: None,
+            contributors: None,
+            artifact_download_url: None,
+            embed_archives: None,
+            announcement_groups: None,
+            exclude_packages: None,
+            extra_targets: None,
+            binaries_from: None,
+            generated_assets: None,
+            completions: None,
+            completions_args: None,
+            man_dir: None,
+            man_pages_args: None,
         }
     };
 
@@ -357,17 +425,11 @@ fn get_new_dist_metadata(
     }
 
     // Enable CI backends
-    // FIXME: when there is more than one option we maybe shouldn't hide this
-    // once the user has any one enabled, right now it's just annoying to always
-    // prompt for Github CI support.
+    // FIXME: we maybe shouldn't hide this once the user has any one enabled
     if meta.ci.as_deref().unwrap_or_default().is_empty() {
-        // FIXME: when there is more than one option this should be a proper
-        // multiselect like the installer selector is! For now we do
-        // most of the multi-select logic and then just give a prompt.
-        let known = &[CiStyle::Github];
+        let known = &[CiStyle::Github, CiStyle::GitLab, CiStyle::Gitea];
         let mut defaults = vec![];
         let mut keys = vec![];
-        let mut github_key = 0;
         for item in known {
             // If this CI style is in their config, keep it
             // If they passed it on the CLI, flip it on
@@ -378,14 +440,15 @@ fn get_new_dist_metadata(
                 .unwrap_or(false)
                 || cfg.ci.contains(item);
 
-            // If they have a well-defined repo url and it's github, default enable it
-            #[allow(irrefutable_let_patterns)]
-            if let CiStyle::Github = item {
-                github_key = 0;
-                if let Some(repo_url) = &workspace_info.repository_url {
-                    if repo_url.contains("github.com") {
-                        default = true;
-                    }
+            // If they have a well-defined repo url that's hosted here, default enable it
+            let host = match item {
+                CiStyle::Github => "github.com",
+                CiStyle::GitLab => "gitlab",
+                CiStyle::Gitea => "gitea",
+            };
+            if let Some(repo_url) = &workspace_info.repository_url {
+                if repo_url.contains(host) {
+                    default = true;
                 }
             }
             defaults.push(default);
@@ -393,30 +456,30 @@ fn get_new_dist_metadata(
             // to `known` above!
             keys.push(match item {
                 CiStyle::Github => "github",
+                CiStyle::GitLab => "gitlab",
+                CiStyle::Gitea => "gitea",
             });
         }
 
         // Prompt the user
-        let prompt = r#"enable Github CI and Releases?"#;
-        let default = defaults[github_key];
-
-        let github_selected = if args.yes {
-            default
+        let prompt = r#"enable CI and Releases for which services?
+    (select with arrow keys and space, submit with enter)"#;
+        let selected = if args.yes {
+            defaults
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, enabled)| enabled.then_some(idx))
+                .collect()
         } else {
-            let res = Confirm::with_theme(&theme)
+            let res = MultiSelect::with_theme(&theme)
+                .items(&keys)
+                .defaults(&defaults)
                 .with_prompt(prompt)
-                .default(default)
                 .interact()?;
             eprintln!();
             res
         };
 
-        let selected = if github_selected {
-            vec![github_key]
-        } else {
-            vec![]
-        };
-
         // Apply the results
         let ci: Vec<_> = selected.into_iter().map(|i| known[i]).collect();
         meta.ci = if ci.is_empty() { None } else { Some(ci) };
@@ -458,7 +521,13 @@ fn get_new_dist_metadata(
         }
     }
 
-    if has_github_ci && meta.pr_run_mode.is_none() {
+    // Other CIs check PRs the same way Github CI does
+    let has_other_pr_ci = meta
+        .ci
+        .as_ref()
+        .map(|ci| ci.contains(&CiStyle::GitLab) || ci.contains(&CiStyle::Gitea))
+        .unwrap_or(false);
+    if (has_github_ci || has_other_pr_ci) && meta.pr_run_mode.is_none() {
         let default_val = PrRunMode::default();
         let cur_val = meta.pr_run_mode.unwrap_or(default_val);
 
@@ -504,11 +573,34 @@ fn get_new_dist_metadata(
                 InstallerStyle::Powershell,
                 InstallerStyle::Npm,
                 InstallerStyle::Homebrew,
+                InstallerStyle::Scoop,
                 InstallerStyle::Msi,
+                InstallerStyle::Deb,
+                InstallerStyle::Rpm,
+                InstallerStyle::AppImage,
+                InstallerStyle::FreebsdPkg,
+                InstallerStyle::Flatpak,
+                InstallerStyle::Snap,
+                InstallerStyle::MacPkg,
+                InstallerStyle::Dmg,
+                InstallerStyle::App,
+                InstallerStyle::Nsis,
             ]
         } else {
             eprintln!("{notice} no CI backends enabled, most installers have been hidden");
-            &[InstallerStyle::Msi]
+            &[
+                InstallerStyle::Msi,
+                InstallerStyle::Deb,
+                InstallerStyle::Rpm,
+                InstallerStyle::AppImage,
+                InstallerStyle::FreebsdPkg,
+                InstallerStyle::Flatpak,
+                InstallerStyle::Snap,
+                InstallerStyle::MacPkg,
+                InstallerStyle::Dmg,
+                InstallerStyle::App,
+                InstallerStyle::Nsis,
+            ]
         };
         let mut defaults = vec![];
         let mut keys = vec![];
@@ -532,7 +624,18 @@ fn get_new_dist_metadata(
                 InstallerStyle::Powershell => "powershell",
                 InstallerStyle::Npm => "npm",
                 InstallerStyle::Homebrew => "homebrew",
+                InstallerStyle::Scoop => "scoop",
                 InstallerStyle::Msi => "msi",
+                InstallerStyle::Deb => "deb",
+                InstallerStyle::Rpm => "rpm",
+                InstallerStyle::AppImage => "appimage",
+                InstallerStyle::FreebsdPkg => "freebsd-pkg",
+                InstallerStyle::Flatpak => "flatpak",
+                InstallerStyle::Snap => "snap",
+                InstallerStyle::MacPkg => "mac-pkg",
+                InstallerStyle::Dmg => "dmg",
+                InstallerStyle::App => "app",
+                InstallerStyle::Nsis => "nsis",
             });
         }
 
@@ -610,6 +713,55 @@ fn get_new_dist_metadata(
         }
     }
 
+    // Special handling of the Scoop installer
+    if meta
+        .installers
+        .as_deref()
+        .unwrap_or_default()
+        .contains(&InstallerStyle::Scoop)
+    {
+        let scoop_is_new = !orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Scoop);
+
+        if scoop_is_new {
+            let prompt = r#"you've enabled Scoop support; if you want cargo-dist
+    to automatically push manifest updates to a bucket (repository) for you,
+    please enter the bucket name (in GitHub owner/name format)"#;
+            let default = "".to_string();
+
+            let bucket: String = if args.yes {
+                default
+            } else {
+                let res = Input::with_theme(&theme)
+                    .with_prompt(prompt)
+                    .allow_empty(true)
+                    .interact_text()?;
+                eprintln!();
+                res
+            };
+            let bucket = bucket.trim();
+            if bucket.is_empty() {
+                eprintln!("Scoop manifests will not be automatically published");
+                meta.bucket = None;
+            } else {
+                meta.bucket = Some(bucket.to_owned());
+                publish_jobs.push(PublishStyle::Scoop);
+
+                eprintln!("{check} Scoop manifest will be published to {bucket}");
+
+                eprintln!(
+                    r#"{check} You must provision a GitHub token and expose it as a secret named
+    SCOOP_BUCKET_TOKEN in GitHub Actions. For more information,
+    see the documentation:
+    https://opensource.axo.dev/cargo-dist/book/installers/scoop.html"#
+                );
+            }
+        }
+    }
+
     meta.publish_jobs = if publish_jobs.is_empty() {
         None
     } else {
@@ -669,31 +821,6 @@ fn get_new_dist_metadata(
             }
             eprintln!();
         }
-
-        // FIXME (#226): If they have an npm installer, force on tar.gz compression
-        const TAR_GZ: Option<ZipStyle> = Some(ZipStyle::Tar(CompressionImpl::Gzip));
-        if meta.unix_archive != TAR_GZ || meta.windows_archive != TAR_GZ {
-            let prompt = r#"the npm installer requires binaries to be distributed as .tar.gz, is that ok?
-    otherwise we would distribute your binaries as .zip on windows, .tar.xz everywhere else
-    (this is a hopefully temporary limitation of the npm installer's implementation)"#;
-            let default = true;
-            let force_targz = if args.yes {
-                default
-            } else {
-                let res = Confirm::with_theme(&theme)
-                    .with_prompt(prompt)
-                    .default(default)
-                    .interact()?;
-                eprintln!();
-                res
-            };
-            if force_targz {
-                meta.unix_archive = TAR_GZ;
-                meta.windows_archive = TAR_GZ;
-            } else {
-                return Err(DistError::MustEnableTarGz)?;
-            }
-        }
     }
 
     Ok(meta)
@@ -740,6 +867,77 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         pr_run_mode,
         allow_dirty,
         ssldotcom_windows_sign,
+        citation,
+        release_notes_asset,
+        unified_checksum,
+        homebrew_libexec,
+        gpg_key,
+        checksum_jobs,
+        tag_format,
+        sentry_org,
+        sentry_project,
+        slim_archives,
+        extra_publish_jobs: _,
+        extra_publish_jobs_file: _,
+        required_binaries: _,
+        appimage_icon: _,
+        dist_subdir: _,
+        winget_id: _,
+        bucket,
+        per_target_staging_dirs,
+        flatpak_app_id: _,
+        flatpak_icon: _,
+        snap_confinement,
+        snap_grade,
+        snap_channel,
+        docker_registry,
+        docker_image,
+        docker_base_image,
+        shell_suite_installer,
+        binstall_metadata,
+        mac_pkg_identifier: _,
+        mac_pkg_install_location,
+        mac_pkg_sign_identity,
+        checksums_json,
+        nsis_program_files,
+        nsis_modify_path,
+        host_target,
+        offline_bundle,
+        download_table,
+        demo_targets,
+        app_bundle_identifier: _,
+        app_bundle_icon: _,
+        dist_unpublished: _,
+        bin_filter: _,
+        installer_bin_filter: _,
+        bin_aliases: _,
+        oci_registry,
+        target_features: _,
+        require_changelog: _,
+        github_custom_runners: _,
+        license_files: _,
+        ci_cache,
+        sccache_backend,
+        delta_from: _,
+        delta_format,
+        use_cross,
+        feature_variants: _,
+        build_profile,
+        versioning,
+        git_cliff: _,
+        changelog_from_commits: _,
+        contributors: _,
+        artifact_download_url,
+        embed_archives,
+        announcement_groups: _,
+        exclude_packages: _,
+        extra_targets: _,
+        binaries_from: _,
+        generated_assets: _,
+        completions: _,
+        completions_args: _,
+        man_dir: _,
+        man_pages_args: _,
     } = &meta;
 
     apply_optional_value(
@@ -772,6 +970,13 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         tap.clone(),
     );
 
+    apply_optional_value(
+        table,
+        "bucket",
+        "# A GitHub repo to push Scoop manifests to\n",
+        bucket.clone(),
+    );
+
     apply_string_list(
         table,
         "targets",
@@ -919,6 +1124,265 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         ssldotcom_windows_sign.as_ref().map(|p| p.to_string()),
     );
 
+    apply_optional_value(
+        table,
+        "citation",
+        "# Whether to generate a CITATION.cff file for each App\n",
+        *citation,
+    );
+
+    apply_optional_value(
+        table,
+        "release-notes-asset",
+        "# Whether to attach the full release notes as a RELEASE_NOTES.md\n",
+        *release_notes_asset,
+    );
+
+    apply_optional_value(
+        table,
+        "unified-checksum",
+        "# Whether to generate a single checksum file for each release\n",
+        *unified_checksum,
+    );
+
+    apply_string_list(
+        table,
+        "homebrew-libexec",
+        "# Binaries the Homebrew formula should install to libexec instead of bin\n",
+        homebrew_libexec.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "gpg-key",
+        "# The gpg key to sign artifacts with\n",
+        gpg_key.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "checksum-jobs",
+        "# How many checksums to compute at once\n",
+        checksum_jobs.map(|jobs| jobs as i64),
+    );
+
+    apply_optional_value(
+        table,
+        "tag-format",
+        "# The format of the git tags you announce releases with\n",
+        tag_format.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "sentry-org",
+        "# The Sentry organization to upload debug symbols to\n",
+        sentry_org.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "sentry-project",
+        "# The Sentry project to upload debug symbols to\n",
+        sentry_project.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "slim-archives",
+        "# Whether to build both slim (stripped) and full archives for each platform\n",
+        *slim_archives,
+    );
+
+    apply_optional_value(
+        table,
+        "per-target-staging-dirs",
+        "# Whether to stage each target's artifacts in their own directory\n",
+        *per_target_staging_dirs,
+    );
+
+    apply_optional_value(
+        table,
+        "snap-confinement",
+        "# The confinement of snaps\n",
+        snap_confinement.map(|c| c.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "snap-grade",
+        "# The grade of snaps\n",
+        snap_grade.map(|g| g.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "snap-channel",
+        "# The Snap Store channel to release snaps to\n",
+        snap_channel.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "docker-registry",
+        "# The container registry to push the Docker image to\n",
+        docker_registry.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "docker-image",
+        "# The name of the Docker image to push\n",
+        docker_image.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "docker-base-image",
+        "# The image to base the Docker image on\n",
+        docker_base_image.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "shell-suite-installer",
+        "# Whether to generate an install.sh that can install any of the apps\n",
+        *shell_suite_installer,
+    );
+
+    apply_optional_value(
+        table,
+        "binstall-metadata",
+        "# Whether to write [package.metadata.binstall] for cargo-binstall\n",
+        *binstall_metadata,
+    );
+
+    apply_optional_value(
+        table,
+        "mac-pkg-install-location",
+        "# Where the macOS .pkg installer puts the binaries\n",
+        mac_pkg_install_location.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "mac-pkg-sign-identity",
+        "# The identity to sign the macOS .pkg installer with\n",
+        mac_pkg_sign_identity.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "checksums-json",
+        "# Whether to generate a checksums.json for each release\n",
+        *checksums_json,
+    );
+
+    apply_optional_value(
+        table,
+        "nsis-program-files",
+        "# Whether the NSIS installer should install to Program Files\n",
+        *nsis_program_files,
+    );
+
+    apply_optional_value(
+        table,
+        "nsis-modify-path",
+        "# Whether the NSIS installer should add the install dir to PATH\n",
+        *nsis_modify_path,
+    );
+
+    apply_optional_value(
+        table,
+        "host-target",
+        "# The target triple to treat as the host\n",
+        host_target.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "offline-bundle",
+        "# Whether to generate an offline bundle of every platform's archives\n",
+        *offline_bundle,
+    );
+
+    apply_optional_value(
+        table,
+        "download-table",
+        "# How to show the download table in the Github Release notes\n",
+        download_table.map(|style| style.to_string()),
+    );
+
+    apply_string_list(
+        table,
+        "demo-targets",
+        "# Targets to also build an all-features demo of the app for\n",
+        demo_targets.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "oci-registry",
+        "# The OCI repository to push the release's artifacts to\n",
+        oci_registry.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "ci-cache",
+        "# How CI should cache builds between runs\n",
+        ci_cache.map(|style| style.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "sccache-backend",
+        "# Where sccache should store builds\n",
+        sccache_backend.map(|backend| backend.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "delta-format",
+        "# The tool to use to build deltas against the previous release\n",
+        delta_format.map(|format| format.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "use-cross",
+        "# Whether to build non-host linux targets with cross\n",
+        *use_cross,
+    );
+
+    apply_optional_value(
+        table,
+        "build-profile",
+        "# The cargo profile to build with\n",
+        build_profile.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "versioning",
+        "# How the versions in your announcement tags are interpreted\n",
+        versioning.map(|scheme| scheme.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "artifact-download-url",
+        "# A custom base URL that artifacts will be downloadable from\n",
+        artifact_download_url.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "embed-archives",
+        "# Whether the shell installer should carry its archives instead of downloading them\n",
+        *embed_archives,
+    );
+
     // Finalize the table
     table
         .decor_mut()
@@ -5,27 +5,33 @@
 //!
 //! This is the library at the core of the 'cargo dist' CLI. It currently mostly exists
 //! for the sake of internal documentation/testing, and isn't intended to be used by anyone else.
-//! That said, if you have a reason to use it, let us know!
+//! That said, if you have a reason to use it, let us know! If you just want to know what
+//! cargo-dist would build, [`DistGraphBuilder`][] lets you plan a release without a Cargo.toml.
 //!
 //! It's currently not terribly well-suited to being used as a pure library because it happily
 //! writes to stderr/stdout whenever it pleases. Suboptimal for a library.
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     process::Command,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Condvar, Mutex,
+    },
 };
 
 use axoasset::LocalAsset;
 use backend::{
-    ci::CiInfo,
-    installer::{self, homebrew::HomebrewInstallerInfo, npm::NpmInstallerInfo, InstallerImpl},
+    ci::{self, CiInfo},
+    installer::{
+        self, homebrew::HomebrewInstallerInfo, npm::NpmInstallerInfo, scoop::ScoopInstallerInfo,
+        InstallerImpl,
+    },
     templates::{TemplateEntry, TEMPLATE_INSTALLER_NPM},
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use cargo_dist_schema::{Asset, AssetKind, DistManifest, ExecutableAsset};
-use config::{
-    ArtifactMode, ChecksumStyle, CompressionImpl, Config, DirtyMode, GenerateMode, ZipStyle,
-};
+use config::{ArtifactMode, ChecksumStyle, Config, DirtyMode, GenerateMode};
 use semver::Version;
 use tracing::{info, warn};
 
@@ -34,41 +40,90 @@ pub use init::{do_init, InitArgs};
 use miette::{miette, Context, IntoDiagnostic};
 pub use tasks::*;
 
+mod archive;
 pub mod backend;
+mod commits;
 pub mod config;
+mod delta;
 pub mod errors;
+mod gpg;
+pub mod graph;
+mod incremental;
 mod init;
 pub mod tasks;
 #[cfg(test)]
 mod tests;
 
+/// Arguments for `cargo dist build` ([`do_build`][])
+#[derive(Debug, Default)]
+pub struct BuildArgs {
+    /// Skip artifacts (and the build steps for them) that are already up to date
+    pub incremental: bool,
+    /// Just print the build steps instead of running them
+    pub dry_run: bool,
+}
+
 /// cargo dist build -- actually build binaries and installers!
-pub fn do_build(cfg: &Config) -> Result<DistManifest> {
+pub fn do_build(cfg: &Config, args: &BuildArgs) -> Result<DistManifest> {
     check_integrity(cfg)?;
 
     let dist = tasks::gather_work(cfg)?;
 
-    // FIXME: parallelize this by working this like a dependency graph, so we can start
-    // bundling up an executable the moment it's built! Note however that you shouldn't
-    // parallelize Cargo invocations because it has global state that can get clobbered.
-    // Most problematically if you do two builds with different feature flags the final
-    // binaries will get copied to the same location and clobber each other :(
+    let freshness = args
+        .incremental
+        .then(|| incremental::Freshness::check(&dist));
+    let skipped = match &freshness {
+        Some(freshness) => freshness.skipped_steps(&dist),
+        None => vec![false; dist.build_steps.len()],
+    };
+
+    if args.dry_run {
+        eprintln!("build steps (dry run, nothing will be built):");
+        eprintln!("{}", graph::format_build_steps(&dist, &skipped));
+        return Ok(build_manifest(cfg, &dist));
+    }
+
+    // Make sure we can sign things before spending ages building them
+    let mut sign_keys = dist
+        .build_steps
+        .iter()
+        .zip(&skipped)
+        .filter_map(|(step, &skipped)| match step {
+            BuildStep::Sign(SignatureImpl { key, .. }) if !skipped => Some(key),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    sign_keys.sort();
+    sign_keys.dedup();
+    for key in sign_keys {
+        gpg::ensure_secret_key(key)?;
+    }
 
     // First set up our target dirs so things don't have to race to do it later
     if !dist.dist_dir.exists() {
         LocalAsset::create_dir_all(&dist.dist_dir)?;
     }
+    if let Some(freshness) = &freshness {
+        freshness.clear_stale(&dist)?;
+    }
+    let is_fresh = |idx: usize| freshness.as_ref().is_some_and(|f| f.fresh[idx]);
 
     eprintln!("building artifacts:");
-    for artifact in &dist.artifacts {
+    for (idx, artifact) in dist.artifacts.iter().enumerate() {
+        if is_fresh(idx) {
+            eprintln!("  {} (up to date)", artifact.id);
+            continue;
+        }
         eprintln!("  {}", artifact.id);
         init_artifact_dir(&dist, artifact)?;
     }
     eprintln!();
 
     // Run all the build steps
-    for step in &dist.build_steps {
-        run_build_step(&dist, step)?;
+    run_build_plan(&dist, &skipped)?;
+
+    if let Some(freshness) = &freshness {
+        freshness.save(&dist)?;
     }
 
     Ok(build_manifest(cfg, &dist))
@@ -82,6 +137,14 @@ pub fn do_manifest(cfg: &Config) -> Result<DistManifest> {
     Ok(build_manifest(cfg, &dist))
 }
 
+/// Just dump the whole DistGraph that `cargo dist build` would run, without building
+pub fn do_graph(cfg: &Config) -> Result<graph::GraphJson> {
+    check_integrity(cfg)?;
+    let dist = gather_work(cfg)?;
+
+    Ok(graph::GraphJson::new(&dist))
+}
+
 fn build_manifest(cfg: &Config, dist: &DistGraph) -> DistManifest {
     // Report the releases
     let mut releases = vec![];
@@ -89,19 +152,16 @@ fn build_manifest(cfg: &Config, dist: &DistGraph) -> DistManifest {
     for release in &dist.releases {
         // Gather up all the local and global artifacts
         let mut artifacts = vec![];
-        for &artifact_idx in &release.global_artifacts {
+        let local_artifacts = release
+            .all_variants()
+            .flat_map(|variant_idx| dist.variant(variant_idx).local_artifacts.iter());
+        for &artifact_idx in release.global_artifacts.iter().chain(local_artifacts) {
             let id = &dist.artifact(artifact_idx).id;
-            all_artifacts.insert(id.clone(), manifest_artifact(cfg, dist, artifact_idx));
+            let mut artifact = manifest_artifact(cfg, dist, artifact_idx);
+            artifact.license = release.app_license.clone();
+            all_artifacts.insert(id.clone(), artifact);
             artifacts.push(id.clone());
         }
-        for &variant_idx in &release.variants {
-            let variant = dist.variant(variant_idx);
-            for &artifact_idx in &variant.local_artifacts {
-                let id = &dist.artifact(artifact_idx).id;
-                all_artifacts.insert(id.clone(), manifest_artifact(cfg, dist, artifact_idx));
-                artifacts.push(id.clone());
-            }
-        }
 
         // And report the release
         releases.push(cargo_dist_schema::Release {
@@ -131,7 +191,11 @@ fn build_manifest(cfg: &Config, dist: &DistGraph) -> DistManifest {
 
     // ci metadata
     if !dist.ci_style.is_empty() {
-        let CiInfo { github } = &dist.ci;
+        let CiInfo {
+            github,
+            gitlab: _,
+            gitea: _,
+        } = &dist.ci;
         let github = github.as_ref().map(|info| cargo_dist_schema::GithubCiInfo {
             artifacts_matrix: Some(info.artifacts_matrix.clone()),
             pr_run_mode: Some(info.pr_run_mode),
@@ -174,19 +238,29 @@ fn manifest_artifact(
             archive
                 .static_assets
                 .iter()
-                .map(|(kind, asset)| {
-                    let kind = match kind {
+                .map(|(static_kind, asset)| {
+                    let kind = match static_kind {
                         StaticAssetKind::Changelog => AssetKind::Changelog,
                         StaticAssetKind::License => AssetKind::License,
                         StaticAssetKind::Readme => AssetKind::Readme,
-                        StaticAssetKind::Other => AssetKind::Unknown,
+                        StaticAssetKind::ManPage | StaticAssetKind::Other => AssetKind::Unknown,
                     };
+                    let path = archive.static_asset_dest(*static_kind, asset);
+                    let path = path.strip_prefix(&archive.dir_path).unwrap();
                     Asset {
                         name: Some(asset.file_name().unwrap().to_owned()),
-                        path: Some(asset.file_name().unwrap().to_owned()),
+                        path: Some(path.to_string()),
                         kind,
                     }
                 })
+                .chain(archive.generated_assets.iter().map(|asset| {
+                    let path = asset.dest_path.strip_prefix(&archive.dir_path).unwrap();
+                    Asset {
+                        name: Some(asset.dest_path.file_name().unwrap().to_owned()),
+                        path: Some(path.to_string()),
+                        kind: AssetKind::Unknown,
+                    }
+                }))
                 .collect::<Vec<_>>()
         })
         .unwrap_or_default();
@@ -195,7 +269,7 @@ fn manifest_artifact(
     //
     // These can't be pre-included in the normal static assets list above because
     // they're generated from templates, and not copied from the user's project.
-    if let ArtifactKind::Installer(InstallerImpl::Npm(..)) = &artifact.kind {
+    if let ArtifactKind::Installer(InstallerImpl::Npm(info)) = &artifact.kind {
         let root_dir = dist
             .templates
             .get_template_dir(TEMPLATE_INSTALLER_NPM)
@@ -217,6 +291,15 @@ fn manifest_artifact(
                 }
             }
         }
+        // Plus the run script that each binary gets
+        for bin in &info.bins {
+            let name = format!("run-{bin}.js");
+            static_assets.push(Asset {
+                name: Some(name.clone()),
+                path: Some(name),
+                kind: AssetKind::Unknown,
+            });
+        }
     }
 
     assets.extend(built_assets);
@@ -242,26 +325,131 @@ fn manifest_artifact(
         ArtifactKind::Installer(
             InstallerImpl::Powershell(info)
             | InstallerImpl::Shell(info)
+            | InstallerImpl::OfflineBundle(info)
             | InstallerImpl::Homebrew(HomebrewInstallerInfo { inner: info, .. })
+            | InstallerImpl::Scoop(ScoopInstallerInfo { inner: info, .. })
             | InstallerImpl::Npm(NpmInstallerInfo { inner: info, .. }),
         ) => {
             install_hint = Some(info.hint.clone());
             description = Some(info.desc.clone());
             kind = cargo_dist_schema::ArtifactKind::Installer;
         }
+        ArtifactKind::Installer(InstallerImpl::ShellSuite(info)) => {
+            install_hint = Some(info.hint.clone());
+            description = Some(info.desc.clone());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
         ArtifactKind::Installer(InstallerImpl::Msi(..)) => {
             install_hint = None;
             description = Some("install via msi".to_owned());
             kind = cargo_dist_schema::ArtifactKind::Installer;
         }
+        ArtifactKind::Installer(InstallerImpl::Deb(..)) => {
+            install_hint = None;
+            description = Some("install via deb".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Rpm(..)) => {
+            install_hint = None;
+            description = Some("install via rpm".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::AppImage(..)) => {
+            install_hint = None;
+            description = Some("portable AppImage".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::FreebsdPkg(..)) => {
+            install_hint = None;
+            description = Some("install via FreeBSD pkg".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Flatpak(..)) => {
+            install_hint = None;
+            description = Some("install via Flatpak".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Snap(..)) => {
+            install_hint = None;
+            description = Some("install via snap".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::MacPkg(..)) => {
+            install_hint = None;
+            description = Some("install via macOS pkg".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Dmg(..)) => {
+            install_hint = None;
+            description = Some("install via macOS disk image".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::AppBundle(..)) => {
+            install_hint = None;
+            description = Some("macOS app bundle".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Nsis(..)) => {
+            install_hint = None;
+            description = Some("install via NSIS installer".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::WinGet(..)) => {
+            install_hint = None;
+            description = Some("WinGet manifest".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
         ArtifactKind::Checksum(_) => {
             install_hint = None;
             description = None;
             kind = cargo_dist_schema::ArtifactKind::Checksum;
         }
+        ArtifactKind::Citation(_) => {
+            install_hint = None;
+            description = Some("citation metadata (CITATION.cff)".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Citation;
+        }
+        ArtifactKind::Signature(_) => {
+            install_hint = None;
+            description = None;
+            kind = cargo_dist_schema::ArtifactKind::Signature;
+        }
+        ArtifactKind::UnifiedChecksum(unified) => {
+            install_hint = None;
+            description = Some(
+                match unified.format {
+                    UnifiedChecksumFormat::Sums => "checksums for every artifact in the release",
+                    UnifiedChecksumFormat::Json => {
+                        "checksums and sizes of every artifact in the release (json)"
+                    }
+                }
+                .to_owned(),
+            );
+            kind = cargo_dist_schema::ArtifactKind::UnifiedChecksum;
+        }
+        ArtifactKind::ReleaseNotes(_) => {
+            install_hint = None;
+            description = Some("full release notes".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::ReleaseNotes;
+        }
+        ArtifactKind::Delta(delta) => {
+            install_hint = None;
+            description = Some(format!(
+                "{} delta from the previous release's {}",
+                delta.format,
+                delta.src_path.file_name().unwrap_or_default()
+            ));
+            kind = cargo_dist_schema::ArtifactKind::Delta;
+        }
+        ArtifactKind::DeltaScript(_) => {
+            install_hint = None;
+            description = Some("script for applying deltas".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::DeltaScript;
+        }
     };
 
     let checksum = artifact.checksum.map(|idx| dist.artifact(idx).id.clone());
+    let signature = artifact.signature.map(|idx| dist.artifact(idx).id.clone());
 
     cargo_dist_schema::Artifact {
         name: Some(artifact.id.clone()),
@@ -270,12 +458,30 @@ fn manifest_artifact(
         } else {
             Some(artifact.file_path.to_string())
         },
+        download_url: dist.artifact_url(artifact),
         target_triples: artifact.target_triples.clone(),
         install_hint,
         description,
         assets,
         kind,
         checksum,
+        signature,
+        provenance: if cfg.verbose_plan {
+            artifact.provenance.clone()
+        } else {
+            None
+        },
+        license: None,
+    }
+}
+
+/// Convert a path in cross's container to the same path on the host
+///
+/// cross mounts the target dir at /target, so that's the part that needs fixing up.
+fn cross_path_to_host(dist_graph: &DistGraph, path: &Utf8Path) -> Utf8PathBuf {
+    match path.strip_prefix("/target") {
+        Ok(rest) => dist_graph.target_dir.join(rest),
+        Err(_) => path.to_owned(),
     }
 }
 
@@ -297,13 +503,173 @@ fn run_build_step(dist_graph: &DistGraph, target: &BuildStep) -> Result<()> {
             dest_path,
             zip_style,
             with_root,
-        }) => zip_dir(src_path, dest_path, zip_style, with_root.as_deref()),
+            executables,
+        }) => Ok(archive::zip_dir(
+            src_path,
+            dest_path,
+            zip_style,
+            with_root.as_deref(),
+            executables,
+        )?),
+        BuildStep::GenerateAsset(step) => generate_asset(step),
         BuildStep::GenerateInstaller(installer) => generate_installer(dist_graph, installer),
         BuildStep::Checksum(ChecksumImpl {
             checksum,
             src_path,
             dest_path,
         }) => Ok(generate_and_write_checksum(checksum, src_path, dest_path)?),
+        BuildStep::Sign(SignatureImpl {
+            key,
+            src_path,
+            dest_path,
+        }) => Ok(gpg::sign_file(key, src_path, dest_path)?),
+        BuildStep::UnifiedChecksum(UnifiedChecksumStep {
+            checksum,
+            format,
+            src_paths,
+            dest_path,
+        }) => Ok(generate_and_write_unified_checksum(
+            checksum,
+            *format,
+            src_paths,
+            dest_path,
+            dist_graph.checksum_jobs,
+        )?),
+        BuildStep::Strip(StripStep { paths }) => strip_binaries(paths),
+        BuildStep::Delta(delta) => Ok(delta::build_delta(&dist_graph.tools, delta)?),
+        BuildStep::GenerateDeltaScript(script) => {
+            Ok(delta::write_delta_script(&dist_graph.templates, script)?)
+        }
+        BuildStep::GenerateCitation(info) => Ok(backend::citation::write_citation_file(
+            &dist_graph.templates,
+            info,
+        )?),
+        BuildStep::GenerateReleaseNotes(ReleaseNotesImpl { dest_path }) => {
+            let notes = dist_graph
+                .announcement_release_notes
+                .as_deref()
+                .unwrap_or_default();
+            LocalAsset::write_new(notes, dest_path)?;
+            Ok(())
+        }
+    }
+}
+
+/// Run all the build steps, running the ones that don't depend on each other at the same time
+///
+/// This bundles up an executable the moment it's built, while the next cargo build runs.
+/// (cargo invocations themselves always run one at a time, see [`BuildPlan`][].)
+///
+/// Checksums are capped at dist.checksum_jobs at once. If a step fails we stop starting
+/// new ones, except for checksums when fail_fast isn't set (we compute all of those that
+/// we can before reporting the error). The error reported is the one a serial run would
+/// have hit first. Steps that are `skipped` (because an incremental build found them up
+/// to date) count as done without running.
+fn run_build_plan(dist: &DistGraph, skipped: &[bool]) -> Result<()> {
+    struct State {
+        /// How many deps each step is still waiting for
+        waiting_on: Vec<usize>,
+        /// Steps that can run now
+        ready: BTreeSet<usize>,
+        running: usize,
+        running_checksums: usize,
+        errors: Vec<(usize, miette::Report)>,
+    }
+
+    let steps = &dist.build_steps;
+    let dependents = dist.build_plan.dependents();
+    let waiting_on = dist
+        .build_plan
+        .deps
+        .iter()
+        .map(|deps| deps.len())
+        .collect::<Vec<_>>();
+    let ready = (0..steps.len())
+        .filter(|&idx| waiting_on[idx] == 0)
+        .collect();
+    let state = Mutex::new(State {
+        waiting_on,
+        ready,
+        running: 0,
+        running_checksums: 0,
+        errors: vec![],
+    });
+    let wakeup = Condvar::new();
+    let is_checksum = |idx: usize| matches!(steps[idx], BuildStep::Checksum(_));
+    let jobs = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .max(dist.checksum_jobs)
+        .clamp(1, steps.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                // Wait for something we're allowed to run (or for there to be nothing left)
+                let idx = {
+                    let mut state = state.lock().unwrap();
+                    loop {
+                        let failed = !state.errors.is_empty();
+                        let can_run = |idx: usize| {
+                            if !is_checksum(idx) {
+                                !failed
+                            } else {
+                                !(failed && dist.fail_fast)
+                                    && state.running_checksums < dist.checksum_jobs
+                            }
+                        };
+                        if let Some(idx) = state.ready.iter().copied().find(|&idx| can_run(idx)) {
+                            state.ready.remove(&idx);
+                            state.running += 1;
+                            if is_checksum(idx) {
+                                state.running_checksums += 1;
+                            }
+                            break Some(idx);
+                        }
+                        if state.running == 0 {
+                            break None;
+                        }
+                        state = wakeup.wait(state).unwrap();
+                    }
+                };
+                let Some(idx) = idx else {
+                    wakeup.notify_all();
+                    break;
+                };
+
+                let result = if skipped[idx] {
+                    info!("skipping up to date step: {:?}", steps[idx]);
+                    Ok(())
+                } else {
+                    run_build_step(dist, &steps[idx])
+                };
+
+                let mut state = state.lock().unwrap();
+                state.running -= 1;
+                if is_checksum(idx) {
+                    state.running_checksums -= 1;
+                }
+                match result {
+                    Ok(()) => {
+                        for &dependent in &dependents[idx] {
+                            state.waiting_on[dependent] -= 1;
+                            if state.waiting_on[dependent] == 0 {
+                                state.ready.insert(dependent);
+                            }
+                        }
+                    }
+                    Err(e) => state.errors.push((idx, e)),
+                }
+                wakeup.notify_all();
+            });
+        }
+    });
+
+    let mut errors = state.into_inner().unwrap().errors;
+    errors.sort_by_key(|(idx, _)| *idx);
+    match errors.into_iter().next() {
+        Some((_, e)) => Err(e),
+        None => Ok(()),
     }
 }
 
@@ -347,6 +713,118 @@ fn generate_checksum(checksum: &ChecksumStyle, src_path: &Utf8Path) -> DistResul
     Ok(output)
 }
 
+/// Generate checksums for several files, up to `jobs` at once
+///
+/// The checksums are returned in the same order as src_paths, no matter what order
+/// they finish in. If any fail, the error for the earliest src_path is returned.
+pub(crate) fn generate_checksums(
+    checksum: &ChecksumStyle,
+    src_paths: &[Utf8PathBuf],
+    jobs: usize,
+) -> DistResult<Vec<String>> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(
+        std::iter::repeat_with(|| None)
+            .take(src_paths.len())
+            .collect::<Vec<_>>(),
+    );
+    let jobs = jobs.clamp(1, src_paths.len().max(1));
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(src_path) = src_paths.get(idx) else {
+                    break;
+                };
+                let result = generate_checksum(checksum, src_path);
+                results.lock().unwrap()[idx] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("checksum was never computed!?"))
+        .collect()
+}
+
+/// Generate checksums for all the src_paths and write them to one dest_path
+///
+/// The files are hashed in parallel (up to `jobs` at once).
+pub(crate) fn generate_and_write_unified_checksum(
+    checksum: &ChecksumStyle,
+    format: UnifiedChecksumFormat,
+    src_paths: &[Utf8PathBuf],
+    dest_path: &Utf8Path,
+    jobs: usize,
+) -> DistResult<()> {
+    let hashes = generate_checksums(checksum, src_paths, jobs)?;
+    let checksums = hashes
+        .into_iter()
+        .zip(src_paths.iter().map(|p| p.as_path()))
+        .collect::<Vec<_>>();
+    let contents = match format {
+        UnifiedChecksumFormat::Sums => format_unified_checksum(&checksums),
+        UnifiedChecksumFormat::Json => {
+            let mut entries = vec![];
+            for (hash, src_path) in checksums {
+                let size = std::fs::metadata(src_path)?.len();
+                entries.push((hash, size, src_path));
+            }
+            format_checksums_json(checksum, &entries)
+        }
+    };
+    axoasset::LocalAsset::write_new(&contents, dest_path)?;
+    Ok(())
+}
+
+/// One file listed in a checksums.json
+#[derive(serde::Serialize)]
+struct ChecksumsJsonEntry<'a> {
+    file: &'a str,
+    algorithm: &'a str,
+    hash: &'a str,
+    size: u64,
+}
+
+/// Format several checksums (and the sizes of their files) as a checksums.json
+///
+/// This is an array with a `{file, algorithm, hash, size}` object per file, which is
+/// easier for tools to consume than a SHA256SUMS file.
+pub(crate) fn format_checksums_json(
+    checksum: &ChecksumStyle,
+    checksums: &[(String, u64, &Utf8Path)],
+) -> String {
+    let entries = checksums
+        .iter()
+        .map(|(hash, size, src_path)| ChecksumsJsonEntry {
+            file: src_path.file_name().expect("hashing file with no name!?"),
+            algorithm: checksum.ext(),
+            hash,
+            size: *size,
+        })
+        .collect::<Vec<_>>();
+    let mut out =
+        serde_json::to_string_pretty(&entries).expect("failed to serialize checksums.json!?");
+    out.push('\n');
+    out
+}
+
+/// Format several checksums in the style of a SHA256SUMS file
+///
+/// This is one `<checksum>  <path>` line per file (*two* spaces, for "text" mode), which
+/// is what most published SHA256SUMS files use, so that commands like
+/// `sha256sum --check SHA256SUMS` will verify every file listed.
+pub(crate) fn format_unified_checksum(checksums: &[(String, &Utf8Path)]) -> String {
+    let mut output = String::new();
+    for (checksum, src_path) in checksums {
+        let file_path = src_path.file_name().expect("hashing file with no name!?");
+        output.push_str(&format!("{checksum}  {file_path}\n"));
+    }
+    output
+}
+
 /// Write the checksum to dest_path
 fn write_checksum(checksum: &str, src_path: &Utf8Path, dest_path: &Utf8Path) -> DistResult<()> {
     // Tools like sha256sum expect a new-line-delimited format of
@@ -372,16 +850,43 @@ fn build_cargo_target(dist_graph: &DistGraph, target: &CargoBuildStep) -> Result
         target.target_triple, target.profile
     );
 
-    let mut command = Command::new(&dist_graph.tools.cargo.cmd);
+    let uses_cross = target.command == CargoBuildCommand::Cross;
+    let cmd = if uses_cross {
+        let Some(cross) = &dist_graph.tools.cross else {
+            return Err(miette!(
+                "use-cross is enabled, but cross isn't installed, so {} can't be built (try cargo install cross)",
+                target.target_triple
+            ));
+        };
+        &cross.cmd
+    } else {
+        &dist_graph.tools.cargo.cmd
+    };
+    // Custom target specs are relative to the workspace, not wherever we're being run from
+    let target_arg = if is_custom_target_spec(&target.target_triple) {
+        dist_graph
+            .workspace_dir
+            .join(&target.target_triple)
+            .into_string()
+    } else {
+        target.target_triple.clone()
+    };
+    let mut command = Command::new(cmd);
     command
         .arg("build")
         .arg("--profile")
         .arg(&target.profile)
         .arg("--message-format=json-render-diagnostics")
         .arg("--target")
-        .arg(&target.target_triple)
+        .arg(target_arg)
         .env("RUSTFLAGS", &target.rustflags)
         .stdout(std::process::Stdio::piped());
+    if uses_cross {
+        // cross doesn't forward RUSTFLAGS into its container unless it's asked to
+        let mut passthrough = std::env::var("CROSS_BUILD_ENV_PASSTHROUGH").unwrap_or_default();
+        passthrough.push_str(" RUSTFLAGS");
+        command.env("CROSS_BUILD_ENV_PASSTHROUGH", passthrough.trim_start());
+    }
     if !target.features.default_features {
         command.arg("--no-default-features");
     }
@@ -466,9 +971,28 @@ fn build_cargo_target(dist_graph: &DistGraph, target: &CargoBuildStep) -> Result
             cargo_metadata::Message::CompilerArtifact(artifact) => {
                 // Hey we got an executable, is it one we wanted?
                 if let Some(new_exe) = artifact.executable {
+                    let mut new_exe = new_exe;
+                    let mut filenames = artifact.filenames;
+                    if uses_cross {
+                        new_exe = cross_path_to_host(dist_graph, &new_exe);
+                        for path in &mut filenames {
+                            *path = cross_path_to_host(dist_graph, path);
+                        }
+                    }
                     info!("got a new exe: {}", new_exe);
-                    let package_id = artifact.package_id.to_string();
+                    let mut package_id = artifact.package_id.to_string();
                     let exe_name = new_exe.file_stem().unwrap();
+                    if uses_cross {
+                        // Package ids refer to the paths in the container, so go by binary name
+                        let expected_id = expected_exes
+                            .iter()
+                            .chain(&expected_symbols)
+                            .find(|(_, exes)| exes.contains_key(exe_name))
+                            .map(|(id, _)| id.clone());
+                        if let Some(expected_id) = expected_id {
+                            package_id = expected_id;
+                        }
+                    }
 
                     // If we expected some symbols, pull them out of the paths of this executable
                     let expected_sym = expected_symbols
@@ -476,10 +1000,9 @@ fn build_cargo_target(dist_graph: &DistGraph, target: &CargoBuildStep) -> Result
                         .and_then(|m| m.get_mut(exe_name));
                     if let Some(expected) = expected_sym {
                         for (src_sym_path, _) in expected {
-                            for path in &artifact.filenames {
+                            for path in &filenames {
                                 // FIXME: unhardcode this when we add support for other symbol kinds!
-                                let is_symbols =
-                                    path.extension().map(|e| e == "pdb").unwrap_or(false);
+                                let is_symbols = matches!(path.extension(), Some("pdb" | "dSYM"));
                                 if is_symbols {
                                     // These are symbols we expected! Save the path.
                                     *src_sym_path = path.to_owned();
@@ -531,7 +1054,12 @@ fn build_cargo_target(dist_graph: &DistGraph, target: &CargoBuildStep) -> Result
                         package_id
                     ));
                 }
-                copy_file(src_path, dest_path)?;
+                // dSYMs are directories
+                if src_path.is_dir() {
+                    copy_dir(src_path, dest_path)?;
+                } else {
+                    copy_file(src_path, dest_path)?;
+                }
             }
         }
     }
@@ -541,11 +1069,14 @@ fn build_cargo_target(dist_graph: &DistGraph, target: &CargoBuildStep) -> Result
 
 /// Build a cargo target
 fn rustup_toolchain(_dist_graph: &DistGraph, cmd: &RustupStep) -> Result<()> {
-    eprintln!("running rustup to ensure you have {} installed", cmd.target);
+    let what = cmd
+        .target
+        .as_deref()
+        .or(cmd.toolchain.as_deref())
+        .unwrap_or("the toolchain");
+    eprintln!("running rustup to ensure you have {what} installed");
     let status = Command::new(&cmd.rustup.cmd)
-        .arg("target")
-        .arg("add")
-        .arg(&cmd.target)
+        .args(cmd.args())
         .status()
         .into_diagnostic()
         .wrap_err("Failed to install rustup toolchain")?;
@@ -573,12 +1104,17 @@ fn init_artifact_dir(_dist: &DistGraph, artifact: &Artifact) -> Result<()> {
     if archive.dir_path.exists() {
         LocalAsset::remove_dir_all(&archive.dir_path)?;
     }
-    LocalAsset::create_dir(&archive.dir_path)?;
+    // (per-target staging dirs are nested, so the parent might not exist yet)
+    LocalAsset::create_dir_all(&archive.dir_path)?;
 
     Ok(())
 }
 
 pub(crate) fn copy_file(src_path: &Utf8Path, dest_path: &Utf8Path) -> Result<()> {
+    // Some assets go in subdirs of the artifact's dir (like man pages)
+    if let Some(parent) = dest_path.parent() {
+        LocalAsset::create_dir_all(parent)?;
+    }
     LocalAsset::copy_named(src_path, dest_path)?;
     Ok(())
 }
@@ -588,25 +1124,60 @@ pub(crate) fn copy_dir(src_path: &Utf8Path, dest_path: &Utf8Path) -> Result<()>
     Ok(())
 }
 
-fn zip_dir(
-    src_path: &Utf8Path,
-    dest_path: &Utf8Path,
-    zip_style: &ZipStyle,
-    with_root: Option<&Utf8Path>,
-) -> Result<()> {
-    match zip_style {
-        ZipStyle::Zip => LocalAsset::zip_dir(src_path, dest_path, with_root)?,
-        ZipStyle::Tar(CompressionImpl::Gzip) => {
-            LocalAsset::tar_gz_dir(src_path, dest_path, with_root)?
-        }
-        ZipStyle::Tar(CompressionImpl::Xzip) => {
-            LocalAsset::tar_xz_dir(src_path, dest_path, with_root)?
-        }
-        ZipStyle::Tar(CompressionImpl::Zstd) => {
-            LocalAsset::tar_zstd_dir(src_path, dest_path, with_root)?
+/// Run a generated-assets command, writing its stdout to the asset's path
+fn generate_asset(step: &GenerateAssetStep) -> Result<()> {
+    let GenerateAssetStep {
+        command,
+        working_dir,
+        dest_path,
+    } = step;
+    let command_line = command.join(" ");
+    info!("generating {dest_path} with {command_line}");
+    let output = match command.split_first() {
+        Some((program, args)) => Command::new(program)
+            .args(args)
+            .current_dir(working_dir)
+            .output(),
+        None => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "the command is empty",
+        )),
+    }
+    .map_err(|details| DistError::GeneratedAssetCommand {
+        command: command_line.clone(),
+        dest_path: dest_path.clone(),
+        details,
+    })?;
+    if !output.status.success() {
+        eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+        return Err(DistError::GeneratedAsset {
+            command: command_line,
+            dest_path: dest_path.clone(),
         }
-        ZipStyle::TempDir => {
-            // no-op
+        .into());
+    }
+    // The asset can be nested in the archive (e.g. completions/my-app.bash)
+    let dest_dir = dest_path.parent().unwrap_or(Utf8Path::new("."));
+    LocalAsset::create_dir_all(dest_dir)?;
+    LocalAsset::new(dest_path, output.stdout)?.write(dest_dir)?;
+    Ok(())
+}
+
+/// Strip debuginfo from some binaries in place (for slim archives)
+fn strip_binaries(paths: &[Utf8PathBuf]) -> Result<()> {
+    for path in paths {
+        info!("stripping debuginfo from {path}");
+        // -S is "strip debuginfo" for both GNU binutils and Apple's strip
+        let status = Command::new("strip")
+            .arg("-S")
+            .arg(path)
+            .status()
+            .map_err(|details| DistError::StripNotFound { details })?;
+        if !status.success() {
+            return Err(DistError::Strip {
+                path: path.to_owned(),
+            }
+            .into());
         }
     }
     Ok(())
@@ -664,7 +1235,7 @@ pub fn run_generate(dist: &DistGraph, args: &GenerateArgs) -> Result<()> {
     // Otherwise, choose any modes that are appropriate
     let inferred = args.modes.is_empty();
     let modes = if inferred {
-        &[GenerateMode::Ci, GenerateMode::Msi]
+        &[GenerateMode::Ci, GenerateMode::Msi, GenerateMode::Binstall]
     } else {
         // Check that we're not being told to do a contradiction
         for &mode in &args.modes {
@@ -686,7 +1257,11 @@ pub fn run_generate(dist: &DistGraph, args: &GenerateArgs) -> Result<()> {
             match mode {
                 GenerateMode::Ci => {
                     // If you add a CI backend, call it here
-                    let CiInfo { github } = &dist.ci;
+                    let CiInfo {
+                        github,
+                        gitlab,
+                        gitea,
+                    } = &dist.ci;
                     if let Some(github) = github {
                         if args.check {
                             github.check(dist)?;
@@ -694,6 +1269,20 @@ pub fn run_generate(dist: &DistGraph, args: &GenerateArgs) -> Result<()> {
                             github.write_to_disk(dist)?;
                         }
                     }
+                    if let Some(gitlab) = gitlab {
+                        if args.check {
+                            gitlab.check(dist)?;
+                        } else {
+                            gitlab.write_to_disk(dist)?;
+                        }
+                    }
+                    if let Some(gitea) = gitea {
+                        if args.check {
+                            gitea.check(dist)?;
+                        } else {
+                            gitea.write_to_disk(dist)?;
+                        }
+                    }
                 }
                 GenerateMode::Msi => {
                     for artifact in &dist.artifacts {
@@ -706,6 +1295,15 @@ pub fn run_generate(dist: &DistGraph, args: &GenerateArgs) -> Result<()> {
                         }
                     }
                 }
+                GenerateMode::Binstall => {
+                    for binstall in dist.releases.iter().filter_map(|r| r.binstall.as_ref()) {
+                        if args.check {
+                            binstall.check()?;
+                        } else {
+                            binstall.write_to_disk()?;
+                        }
+                    }
+                }
             }
         }
     }
@@ -724,15 +1322,29 @@ pub fn check_integrity(cfg: &Config) -> Result<()> {
         artifact_mode: ArtifactMode::All,
         no_local_paths: false,
         allow_all_dirty: cfg.allow_all_dirty,
+        verbose_plan: false,
         targets: vec![],
         ci: vec![],
         installers: vec![],
         announcement_tag: None,
+        host_target: cfg.host_target.clone(),
+        exclude: cfg.exclude.clone(),
     };
-    let dist = tasks::gather_work(&check_config)?;
+    let mut dist = tasks::gather_work(&check_config)?;
+    check_generated_files(&mut dist, ci::is_pull_request_run())
+}
+
+/// Check that the generated files are up to date
+///
+/// Pull requests that are only planning a release don't care if the CI scripts drifted
+/// (see [`DirtyMode::for_pr_plan`][]).
+fn check_generated_files(dist: &mut DistGraph, is_pull_request: bool) -> Result<()> {
+    if is_pull_request {
+        dist.allow_dirty = dist.allow_dirty.clone().for_pr_plan(dist.pr_run_mode);
+    }
 
     run_generate(
-        &dist,
+        dist,
         &GenerateArgs {
             modes: vec![],
             check: true,
@@ -743,17 +1355,34 @@ pub fn check_integrity(cfg: &Config) -> Result<()> {
 /// Build a cargo target
 fn generate_installer(dist: &DistGraph, style: &InstallerImpl) -> Result<()> {
     match style {
-        InstallerImpl::Shell(info) => {
-            installer::shell::write_install_sh_script(&dist.templates, info)?
+        InstallerImpl::Shell(info) | InstallerImpl::OfflineBundle(info) => {
+            installer::shell::write_install_sh_script(&dist.templates, dist, info)?
+        }
+        InstallerImpl::ShellSuite(info) => {
+            installer::shell::write_install_suite_sh_script(&dist.templates, dist, info)?
         }
         InstallerImpl::Powershell(info) => {
-            installer::powershell::write_install_ps_script(&dist.templates, info)?
+            installer::powershell::write_install_ps_script(&dist.templates, dist, info)?
         }
         InstallerImpl::Npm(info) => installer::npm::write_npm_project(&dist.templates, info)?,
         InstallerImpl::Homebrew(info) => {
             installer::homebrew::write_homebrew_formula(&dist.templates, dist, info)?
         }
+        InstallerImpl::Scoop(info) => installer::scoop::write_scoop_manifest(dist, info)?,
         InstallerImpl::Msi(info) => info.build()?,
+        InstallerImpl::Deb(info) => info.build()?,
+        InstallerImpl::Rpm(info) => info.build()?,
+        InstallerImpl::AppImage(info) => info.build()?,
+        InstallerImpl::FreebsdPkg(info) => info.build()?,
+        InstallerImpl::Flatpak(info) => info.build()?,
+        InstallerImpl::Snap(info) => info.build()?,
+        InstallerImpl::MacPkg(info) => info.build()?,
+        InstallerImpl::Dmg(info) => info.build()?,
+        InstallerImpl::AppBundle(info) => info.build()?,
+        InstallerImpl::Nsis(info) => info.build(&dist.templates)?,
+        InstallerImpl::WinGet(info) => {
+            installer::winget::write_winget_manifest(&dist.templates, info)?
+        }
     }
     Ok(())
 }
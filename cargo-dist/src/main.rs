@@ -15,7 +15,7 @@ use cli::{
 use console::Term;
 use miette::IntoDiagnostic;
 
-use crate::cli::{BuildArgs, GenerateArgs, GenerateCiArgs, InitArgs};
+use crate::cli::{BuildArgs, GenerateArgs, GenerateCiArgs, GraphArgs, InitArgs};
 
 mod cli;
 
@@ -34,9 +34,11 @@ fn real_main(cli: &axocli::CliApp<Cli>) -> Result<(), miette::Report> {
         Commands::Generate(args) => cmd_generate(config, args),
         Commands::GenerateCi(args) => cmd_generate_ci(config, args),
         Commands::Manifest(args) => cmd_manifest(config, args),
+        Commands::Graph(args) => cmd_graph(config, args),
         Commands::Plan(args) => cmd_plan(config, args),
         Commands::HelpMarkdown(args) => cmd_help_md(config, args),
         Commands::ManifestSchema(args) => cmd_manifest_schema(config, args),
+        Commands::ConfigSchema(args) => cmd_config_schema(config, args),
         Commands::Build(args) => cmd_dist(config, args),
     }
 }
@@ -60,13 +62,18 @@ fn print_human(out: &mut Term, manifest: &DistManifest) -> Result<(), std::io::E
         )?;
         for artifact_id in &release.artifacts {
             let artifact = &manifest.artifacts[artifact_id];
-            if let cargo_dist_schema::ArtifactKind::Checksum = &artifact.kind {
-                // Don't print shasums at top-level
+            if let cargo_dist_schema::ArtifactKind::Checksum
+            | cargo_dist_schema::ArtifactKind::Signature = &artifact.kind
+            {
+                // Don't print shasums or signatures at top-level
                 continue;
             }
 
             write!(out, "    ")?;
             print_human_artifact_path(out, artifact)?;
+            if let Some(provenance) = &artifact.provenance {
+                writeln!(out, "      (why: {provenance})")?;
+            }
 
             // Print out all the binaries first, those are the money!
             for asset in &artifact.assets {
@@ -106,6 +113,12 @@ fn print_human(out: &mut Term, manifest: &DistManifest) -> Result<(), std::io::E
                 write!(out, "      [checksum] ")?;
                 print_human_artifact_path(out, checksum_artifact)?;
             }
+            // Likewise for signatures
+            if let Some(signature_id) = &artifact.signature {
+                let signature_artifact = &manifest.artifacts[signature_id];
+                write!(out, "      [signature] ")?;
+                print_human_artifact_path(out, signature_artifact)?;
+            }
         }
     }
     Ok(())
@@ -145,12 +158,19 @@ fn cmd_dist(cli: &Cli, args: &BuildArgs) -> Result<(), miette::Report> {
         artifact_mode: args.artifacts.to_lib(),
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        verbose_plan: cli.verbose_plan,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
         announcement_tag: cli.tag.clone(),
+        host_target: cli.host_target.clone(),
+        exclude: cli.exclude.clone(),
     };
-    let report = do_build(&config)?;
+    let build_args = cargo_dist::BuildArgs {
+        incremental: args.incremental,
+        dry_run: args.dry_run,
+    };
+    let report = do_build(&config, &build_args)?;
     let mut out = Term::stdout();
     match cli.output_format {
         OutputFormat::Human => print_human(&mut out, &report).into_diagnostic()?,
@@ -165,10 +185,13 @@ fn cmd_manifest(cli: &Cli, args: &ManifestArgs) -> Result<(), miette::Report> {
         artifact_mode: args.build_args.artifacts.to_lib(),
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        verbose_plan: cli.verbose_plan,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
         announcement_tag: cli.tag.clone(),
+        host_target: cli.host_target.clone(),
+        exclude: cli.exclude.clone(),
     };
     let report = do_manifest(&config)?;
     let mut out = Term::stdout();
@@ -187,7 +210,32 @@ fn cmd_manifest(cli: &Cli, args: &ManifestArgs) -> Result<(), miette::Report> {
     Ok(())
 }
 
-fn cmd_plan(cli: &Cli, _args: &PlanArgs) -> Result<(), miette::Report> {
+fn cmd_graph(cli: &Cli, args: &GraphArgs) -> Result<(), miette::Report> {
+    let config = cargo_dist::config::Config {
+        needs_coherent_announcement_tag: true,
+        artifact_mode: args.build_args.artifacts.to_lib(),
+        no_local_paths: cli.no_local_paths,
+        allow_all_dirty: cli.allow_dirty,
+        verbose_plan: cli.verbose_plan,
+        targets: cli.target.clone(),
+        ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
+        installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
+        announcement_tag: cli.tag.clone(),
+        host_target: cli.host_target.clone(),
+        exclude: cli.exclude.clone(),
+    };
+    let graph = do_graph(&config)?;
+    // This is always json, there's no sensible human version of it
+    let string = serde_json::to_string_pretty(&graph).into_diagnostic()?;
+    writeln!(Term::stdout(), "{string}").into_diagnostic()?;
+    Ok(())
+}
+
+fn cmd_plan(cli: &Cli, args: &PlanArgs) -> Result<(), miette::Report> {
+    if args.list {
+        return cmd_list(cli);
+    }
+
     // Force --no-local-paths and --artifacts=all
     // No need to force --output-format=human
     let mut new_cli = cli.clone();
@@ -195,22 +243,75 @@ fn cmd_plan(cli: &Cli, _args: &PlanArgs) -> Result<(), miette::Report> {
     let args = &ManifestArgs {
         build_args: BuildArgs {
             artifacts: cli::ArtifactMode::All,
+            incremental: false,
+            dry_run: false,
         },
     };
 
     cmd_manifest(&new_cli, args)
 }
 
+fn cmd_list(cli: &Cli) -> Result<(), miette::Report> {
+    let config = cargo_dist::config::Config {
+        needs_coherent_announcement_tag: false,
+        artifact_mode: cargo_dist::config::ArtifactMode::All,
+        no_local_paths: true,
+        allow_all_dirty: cli.allow_dirty,
+        verbose_plan: cli.verbose_plan,
+        targets: cli.target.clone(),
+        ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
+        installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
+        announcement_tag: cli.tag.clone(),
+        host_target: cli.host_target.clone(),
+        exclude: cli.exclude.clone(),
+    };
+    let listing = list_apps(&config)?;
+    let mut out = Term::stdout();
+    match cli.output_format {
+        OutputFormat::Human => print_human_listing(&mut out, &listing).into_diagnostic()?,
+        OutputFormat::Json => {
+            let string = serde_json::to_string_pretty(&listing).into_diagnostic()?;
+            writeln!(out, "{string}").into_diagnostic()?;
+        }
+    }
+    Ok(())
+}
+
+fn print_human_listing(out: &mut Term, listing: &DistListing) -> Result<(), std::io::Error> {
+    for package in &listing.packages {
+        let name = format!("{} {}", package.name, package.version);
+        if let Some(reason) = &package.disabled_reason {
+            let line = format!("{name} (not released: {reason})");
+            writeln!(out, "{}", out.style().dim().apply_to(line))?;
+        } else {
+            writeln!(out, "{}", out.style().blue().apply_to(name))?;
+        }
+        for (label, values) in [
+            ("binaries", &package.binaries),
+            ("tags", &package.tags),
+            ("targets", &package.targets),
+        ] {
+            if !values.is_empty() {
+                writeln!(out, "  {label}: {}", values.join(", "))?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn cmd_init(cli: &Cli, args: &InitArgs) -> Result<(), miette::Report> {
     let config = cargo_dist::config::Config {
         needs_coherent_announcement_tag: false,
         artifact_mode: cargo_dist::config::ArtifactMode::All,
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        verbose_plan: cli.verbose_plan,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
         announcement_tag: cli.tag.clone(),
+        host_target: cli.host_target.clone(),
+        exclude: cli.exclude.clone(),
     };
     let args = cargo_dist::InitArgs {
         yes: args.yes,
@@ -226,10 +327,13 @@ fn cmd_generate(cli: &Cli, args: &GenerateArgs) -> Result<(), miette::Report> {
         artifact_mode: cargo_dist::config::ArtifactMode::All,
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        verbose_plan: cli.verbose_plan,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
         announcement_tag: cli.tag.clone(),
+        host_target: cli.host_target.clone(),
+        exclude: cli.exclude.clone(),
     };
     let args = cargo_dist::GenerateArgs {
         check: args.check,
@@ -414,3 +518,13 @@ fn cmd_manifest_schema(
     println!("{json_schema}");
     Ok(())
 }
+
+fn cmd_config_schema(
+    _config: &Cli,
+    _args: &cli::ConfigSchemaArgs,
+) -> Result<(), miette::ErrReport> {
+    let schema = cargo_dist::config::DistMetadata::json_schema();
+    let json_schema = serde_json::to_string_pretty(&schema).expect("failed to stringify schema!?");
+    println!("{json_schema}");
+    Ok(())
+}
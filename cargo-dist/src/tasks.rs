@@ -51,40 +51,93 @@
 use itertools::Itertools;
 use std::process::Command;
 
+use axoasset::SourceFile;
+use axoproject::changelog::ChangelogInfo;
 use axoproject::platforms::triple_to_display_name;
 use axoproject::{PackageIdx, WorkspaceInfo};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use cruet::to_class_case;
 use guppy::PackageId;
 use miette::{miette, Context, IntoDiagnostic};
 use semver::Version;
 use tracing::{info, warn};
 
+use crate::backend::ci::gitea::GiteaCiInfo;
 use crate::backend::ci::github::GithubCiInfo;
+use crate::backend::ci::gitlab::GitLabCiInfo;
 use crate::backend::ci::CiInfo;
-use crate::config::{DependencyKind, DirtyMode, ProductionMode, SystemDependencies};
+use crate::config::{
+    CompletionShell, DependencyKind, DirtyMode, ExtraPublishJob, ExtraPublishJobsFile,
+    GeneratedAsset, ProductionMode, SnapConfinement, SnapGrade, SystemDependencies,
+    VersioningScheme,
+};
 use crate::{
     backend::{
+        binstall::{BinstallFormat, BinstallInfo},
+        citation::{cff_licenses, CitationAuthor, CitationInfo},
         installer::{
-            homebrew::HomebrewInstallerInfo, msi::MsiInstallerInfo, npm::NpmInstallerInfo,
-            ExecutableZipFragment, InstallerImpl, InstallerInfo,
+            app::AppBundleInstallerInfo,
+            appimage::{appimage_arch, AppImageInstallerInfo, DesktopEntry, APPIMAGE_BIN_DIR},
+            deb::{
+                deb_arch, deb_package_name, DebControl, DebCopyright, DebInstallerInfo,
+                DEB_BIN_DIR, DEB_MAN_DIR,
+            },
+            dmg::DmgInstallerInfo,
+            flatpak::{flatpak_app_id, flatpak_arch, FlatpakInstallerInfo},
+            freebsd::{
+                freebsd_pkg_arch, freebsd_pkg_licenses, freebsd_pkg_version, FreebsdPkgDep,
+                FreebsdPkgInstallerInfo, FreebsdPkgManifest, FREEBSD_PKG_BIN_DIR,
+                FREEBSD_PKG_PREFIX,
+            },
+            homebrew::HomebrewInstallerInfo,
+            macpkg::{MacPkgInstallerInfo, MAC_PKG_INSTALL_LOCATION},
+            msi::MsiInstallerInfo,
+            npm::NpmInstallerInfo,
+            nsis::NsisInstallerInfo,
+            rpm::{rpm_arch, rpm_version, RpmInstallerInfo, RpmSpec, RPM_BIN_DIR, RPM_MAN_DIR},
+            scoop::{
+                scoop_architectures, scoop_autoupdate, ScoopCheckver, ScoopInstallerInfo,
+                ScoopManifest,
+            },
+            shell::ShellSuiteInstallerInfo,
+            snap::{snap_arch, snap_name, SnapInstallerInfo, SnapcraftSpec, SNAP_BIN_DIR},
+            winget::{winget_arch, WinGetInstaller, WinGetInstallerInfo, WinGetManifestKind},
+            ExecutableZipFragment, FragmentChecksum, InstallerImpl, InstallerInfo,
         },
         templates::Templates,
     },
+    commits,
     config::{
-        self, ArtifactMode, ChecksumStyle, CiStyle, CompressionImpl, Config, DistMetadata,
-        InstallPathStrategy, InstallerStyle, PublishStyle, ZipStyle,
+        self, ArtifactMode, ChecksumStyle, CiCacheStyle, CiStyle, CompressionImpl, Config,
+        DeltaFormat, DistMetadata, DownloadTableStyle, InstallPathStrategy, InstallerStyle,
+        PublishStyle, SccacheBackend, ZipStyle,
     },
     errors::{DistError, DistResult, Result},
 };
 
 /// Key in workspace.metadata or package.metadata for our config
 pub const METADATA_DIST: &str = "dist";
-/// Dir in target/ for us to build our packages in
+/// Dir in target/ for us to build our packages in (unless dist-subdir is set)
 /// NOTE: DO NOT GIVE THIS THE SAME NAME AS A PROFILE!
 pub const TARGET_DIST: &str = "distrib";
-/// The profile we will build with
+/// Dirs in target/ that cargo uses for itself (on top of ones named after profiles)
+const CARGO_TARGET_SUBDIRS: &[&str] = &["debug", "release", "doc", "package", "tmp"];
+/// The profile we will build with (unless build-profile is set)
 pub const PROFILE_DIST: &str = "dist";
+/// The tag format we use if tag-format isn't set
+pub const DEFAULT_TAG_FORMAT: &str = "v{version}";
+/// The placeholder in a tag-format that gets replaced with the version
+const TAG_FORMAT_VERSION: &str = "{version}";
+/// The image we base Docker images on if docker-base-image isn't set
+pub const DEFAULT_DOCKER_BASE_IMAGE: &str = "gcr.io/distroless/cc-debian12";
+/// The name of the release notes asset we attach to an announcement
+pub const RELEASE_NOTES_FILE_NAME: &str = "RELEASE_NOTES.md";
+/// The name of the installer that can install any app in an announcement
+pub const SHELL_SUITE_INSTALLER_FILE_NAME: &str = "install.sh";
+/// The suffix of the machine-readable checksum file of a release
+pub const CHECKSUMS_JSON_FILE_NAME: &str = "checksums.json";
+/// The dir man pages go in, in archives
+pub const ARCHIVE_MAN_DIR: &str = "man";
 
 /// The key for referring to linux as an "os"
 pub const OS_LINUX: &str = "linux";
@@ -148,10 +201,26 @@ pub struct DistGraph {
     pub workspace_dir: Utf8PathBuf,
     /// cargo-dist's target dir (generally nested under `target_dir`).
     pub dist_dir: Utf8PathBuf,
+    /// The cargo profile to build with (the build-profile setting, or "dist")
+    pub build_profile: String,
     /// Whether to bother using --package instead of --workspace when building apps
     pub precise_builds: bool,
     /// Whether to try to merge otherwise-parallelizable tasks the same machine
     pub merge_tasks: bool,
+    /// Github runners to use instead of the defaults for particular targets
+    pub github_custom_runners: SortedMap<String, String>,
+    /// How CI should cache builds between runs
+    pub ci_cache: CiCacheStyle,
+    /// Where sccache should store builds (if ci_cache is sccache)
+    pub sccache_backend: SccacheBackend,
+    /// Where to find the previous release's archives to build deltas against (URL or path)
+    pub delta_from: Option<String>,
+    /// The tool to build deltas with
+    pub delta_format: DeltaFormat,
+    /// Whether to build non-host linux targets with cross
+    pub use_cross: bool,
+    /// Whether staging dirs are namespaced by target triple
+    pub per_target_staging_dirs: bool,
     /// Whether failing tasks should make us give up on all other tasks
     pub fail_fast: bool,
     /// Whether to create a github release or edit an existing draft
@@ -182,13 +251,22 @@ pub struct DistGraph {
     pub announcement_title: Option<String>,
     /// Raw changelog for the announcement
     pub announcement_changelog: Option<String>,
+    /// The contributors to the announcement (as a markdown list)
+    pub announcement_contributors: Option<String>,
     /// Github Releases body for the announcement
+    ///
+    /// If release_notes_asset is set this is the short version (install instructions
+    /// and downloads), with the full version in announcement_release_notes.
     pub announcement_github_body: Option<String>,
+    /// Full release notes for the announcement (the contents of RELEASE_NOTES.md)
+    pub announcement_release_notes: Option<String>,
     /// Base URL that artifacts are downloadable from ("{artifact_download_url}/{artifact.id}")
     pub artifact_download_url: Option<String>,
 
     /// Targets we need to build
     pub build_steps: Vec<BuildStep>,
+    /// The dependencies between the build_steps
+    pub build_plan: BuildPlan,
     /// Distributable artifacts we want to produce for the releases
     pub artifacts: Vec<Artifact>,
     /// Binaries we want to build
@@ -203,10 +281,46 @@ pub struct DistGraph {
     pub publish_jobs: Vec<PublishStyle>,
     /// Extra user-specified publish jobs to run
     pub user_publish_jobs: Vec<String>,
+    /// Publish jobs that are fully defined in the config (or an extra-publish-jobs-file)
+    pub extra_publish_jobs: Vec<ExtraPublishJob>,
     /// Whether to publish prerelease builds to package managers
     pub publish_prereleases: bool,
+    /// Whether to attach the full release notes to the Github Release as an asset
+    pub release_notes_asset: bool,
+    /// Whether it's an error for the changelog to have no notes for the release
+    pub require_changelog: bool,
+    /// Whether to generate release notes with git-cliff instead of reading changelogs
+    pub git_cliff: bool,
+    /// Whether to generate release notes from git history if the changelogs have none
+    pub changelog_from_commits: bool,
+    /// Whether to list the release's contributors in the GitHub Release body
+    pub contributors: bool,
+    /// Whether to generate an install.sh that can install any app being announced
+    pub shell_suite_installer: bool,
+    /// How many checksums to compute at once
+    pub checksum_jobs: usize,
+    /// The format of announcement tags (with a `{version}` placeholder)
+    pub tag_format: String,
+    /// How the versions in announcement tags are interpreted
+    pub versioning: VersioningScheme,
     /// A GitHub repo to publish the Homebrew formula to
     pub tap: Option<String>,
+    /// A GitHub repo to publish the Scoop manifest to
+    pub bucket: Option<String>,
+    /// The Sentry organization to upload debug symbols to
+    pub sentry_org: Option<String>,
+    /// The Sentry project to upload debug symbols to
+    pub sentry_project: Option<String>,
+    /// The Snap Store channel to release snaps to
+    pub snap_channel: String,
+    /// The container registry to push the Docker image to
+    pub docker_registry: Option<String>,
+    /// The name of the Docker image to push
+    pub docker_image: Option<String>,
+    /// The image to base the Docker image on
+    pub docker_base_image: String,
+    /// The OCI repository to push the release's artifacts to
+    pub oci_registry: Option<String>,
 }
 
 /// Various tools we have found installed on the system
@@ -216,6 +330,22 @@ pub struct Tools {
     pub cargo: CargoInfo,
     /// rustup, useful for getting specific toolchains
     pub rustup: Option<Tool>,
+    /// appimagetool, needed to build AppImages
+    pub appimagetool: Option<Tool>,
+    /// flatpak-builder, needed to build Flatpaks
+    pub flatpak_builder: Option<Tool>,
+    /// snapcraft, needed to build snaps
+    pub snapcraft: Option<Tool>,
+    /// oras, needed to push OCI artifacts
+    pub oras: Option<Tool>,
+    /// zstd, needed to build zstd deltas
+    pub zstd: Option<Tool>,
+    /// bsdiff, needed to build bsdiff deltas
+    pub bsdiff: Option<Tool>,
+    /// cross, needed to build linux targets in containers (if use-cross is enabled)
+    pub cross: Option<Tool>,
+    /// git-cliff, needed to generate release notes (if git-cliff is enabled)
+    pub git_cliff: Option<Tool>,
 }
 
 /// Info about the cargo toolchain we're using
@@ -256,6 +386,8 @@ pub struct Binary {
     pub name: String,
     /// The filename the binary will have
     pub file_name: String,
+    /// The filename installers should install the binary as (usually the same as file_name)
+    pub installed_file_name: String,
     /// The target triple to build it for
     pub target: TargetTriple,
     /// The artifact for this Binary's symbols
@@ -286,17 +418,217 @@ pub enum BuildStep {
     CopyDir(CopyDirStep),
     /// Zip up a directory
     Zip(ZipDirStep),
+    /// Run a command to generate a file
+    GenerateAsset(GenerateAssetStep),
     /// Generate some kind of installer
     GenerateInstaller(InstallerImpl),
     /// Checksum a file
     Checksum(ChecksumImpl),
+    /// Sign a file
+    Sign(SignatureImpl),
+    /// Generate a CITATION.cff file
+    GenerateCitation(CitationInfo),
+    /// Write out the full release notes
+    GenerateReleaseNotes(ReleaseNotesImpl),
+    /// Checksum several files into one file
+    UnifiedChecksum(UnifiedChecksumStep),
+    /// Strip debuginfo from some binaries
+    Strip(StripStep),
+    /// Build a binary delta between two versions of a file
+    Delta(DeltaImpl),
+    /// Generate a script for applying deltas
+    GenerateDeltaScript(DeltaScriptImpl),
     // FIXME: For macos universal builds we'll want
     // Lipo(LipoStep)
 }
 
+/// The dependencies between a DistGraph's build steps
+///
+/// DistGraph::build_steps is a valid order to run the steps in one at a time, but most
+/// steps only care about a few of the ones before them (a zip needs the files that get
+/// copied into its dir, a checksum needs the file it's hashing...), so anything that
+/// doesn't depend on each other can be run at the same time.
+#[derive(Debug, Clone, Default)]
+pub struct BuildPlan {
+    /// For each build step, the (earlier) steps that have to be done before it can run
+    pub deps: Vec<Vec<usize>>,
+}
+
+impl BuildPlan {
+    /// Work out the dependencies between some build steps (in the order they'd be run serially)
+    pub fn new(graph: &DistGraph, steps: &[BuildStep]) -> Self {
+        let io = steps
+            .iter()
+            .map(|step| (step.inputs(), step.outputs(graph)))
+            .collect::<Vec<_>>();
+        let deps = (0..steps.len())
+            .map(|later| {
+                (0..later)
+                    .filter(|&earlier| {
+                        // cargo has global state that can get clobbered, and the toolchains
+                        // have to be set up before the builds, so these all go in order
+                        if steps[earlier].uses_toolchain() && steps[later].uses_toolchain() {
+                            return true;
+                        }
+                        let (earlier_in, earlier_out) = &io[earlier];
+                        let (later_in, later_out) = &io[later];
+                        paths_conflict(earlier_out, later_in)
+                            || paths_conflict(earlier_out, later_out)
+                            || paths_conflict(earlier_in, later_out)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { deps }
+    }
+
+    /// For each build step, the steps that have to wait for it
+    pub fn dependents(&self) -> Vec<Vec<usize>> {
+        let mut dependents = vec![vec![]; self.deps.len()];
+        for (step, deps) in self.deps.iter().enumerate() {
+            for &dep in deps {
+                dependents[dep].push(step);
+            }
+        }
+        dependents
+    }
+
+    /// Whether `step` has to wait for `on` (directly or indirectly)
+    pub fn depends_on(&self, step: usize, on: usize) -> bool {
+        let mut seen = vec![false; self.deps.len()];
+        let mut todo = vec![step];
+        while let Some(step) = todo.pop() {
+            for &dep in &self.deps[step] {
+                if dep == on {
+                    return true;
+                }
+                // deps are always earlier, so anything before `on` can't lead to it
+                if dep > on && !seen[dep] {
+                    seen[dep] = true;
+                    todo.push(dep);
+                }
+            }
+        }
+        false
+    }
+
+    /// The steps, topologically sorted into waves
+    ///
+    /// Every step in a wave only depends on steps in earlier waves, so each wave
+    /// can be run in parallel once the ones before it are done.
+    pub fn waves(&self) -> Vec<Vec<usize>> {
+        let mut levels = Vec::<usize>::with_capacity(self.deps.len());
+        let mut waves = Vec::<Vec<usize>>::new();
+        for (step, deps) in self.deps.iter().enumerate() {
+            let level = deps.iter().map(|&dep| levels[dep] + 1).max().unwrap_or(0);
+            levels.push(level);
+            if waves.len() <= level {
+                waves.resize_with(level + 1, Vec::new);
+            }
+            waves[level].push(step);
+        }
+        waves
+    }
+}
+
+/// Whether one step touching `a` and another touching `b` would step on each other
+///
+/// `None` means "we don't know" so it's assumed to conflict with everything.
+fn paths_conflict(a: &Option<Vec<&Utf8Path>>, b: &Option<Vec<&Utf8Path>>) -> bool {
+    let (Some(a), Some(b)) = (a, b) else {
+        return true;
+    };
+    // Paths can be dirs, so anything inside of them counts too
+    a.iter()
+        .any(|a| b.iter().any(|b| a.starts_with(b) || b.starts_with(a)))
+}
+
+impl BuildStep {
+    /// Whether this step runs cargo or rustup
+    fn uses_toolchain(&self) -> bool {
+        matches!(self, BuildStep::Cargo(_) | BuildStep::Rustup(_))
+    }
+
+    /// The files (or dirs) this step reads, or None if we don't know
+    pub(crate) fn inputs(&self) -> Option<Vec<&Utf8Path>> {
+        let inputs = match self {
+            // Builds only read the sources
+            BuildStep::Cargo(_) | BuildStep::Rustup(_) => vec![],
+            BuildStep::CopyFile(CopyFileStep { src_path, .. })
+            | BuildStep::CopyDir(CopyDirStep { src_path, .. })
+            | BuildStep::Zip(ZipDirStep { src_path, .. })
+            | BuildStep::Checksum(ChecksumImpl { src_path, .. })
+            | BuildStep::Sign(SignatureImpl { src_path, .. }) => vec![&**src_path],
+            BuildStep::UnifiedChecksum(UnifiedChecksumStep { src_paths, .. }) => {
+                src_paths.iter().map(|p| &**p).collect()
+            }
+            BuildStep::Strip(StripStep { paths }) => paths.iter().map(|p| &**p).collect(),
+            BuildStep::Delta(DeltaImpl {
+                old_path, src_path, ..
+            }) => vec![Utf8Path::new(old_path), src_path],
+            BuildStep::GenerateDeltaScript(_)
+            | BuildStep::GenerateCitation(_)
+            | BuildStep::GenerateReleaseNotes(_) => vec![],
+            // The command could read anything (often the binaries we just built),
+            // so it has to wait for everything before it
+            BuildStep::GenerateAsset(_) => return None,
+            // Installers can be arbitrarily complicated (some even embed the archives
+            // they install), so assume the worst and run them after everything else
+            BuildStep::GenerateInstaller(_) => return None,
+        };
+        Some(inputs)
+    }
+
+    /// The files (or dirs) this step writes, or None if we don't know
+    pub(crate) fn outputs<'a>(&'a self, graph: &'a DistGraph) -> Option<Vec<&'a Utf8Path>> {
+        let outputs = match self {
+            // The binaries get copied out of the target dir as soon as they're built
+            BuildStep::Cargo(CargoBuildStep {
+                expected_binaries, ..
+            }) => expected_binaries
+                .iter()
+                .flat_map(|&idx| {
+                    let binary = graph.binary(idx);
+                    binary.copy_exe_to.iter().chain(&binary.copy_symbols_to)
+                })
+                .map(|p| &**p)
+                .collect(),
+            BuildStep::Rustup(_) => vec![],
+            BuildStep::CopyFile(CopyFileStep { dest_path, .. })
+            | BuildStep::CopyDir(CopyDirStep { dest_path, .. })
+            | BuildStep::Zip(ZipDirStep { dest_path, .. })
+            | BuildStep::Checksum(ChecksumImpl { dest_path, .. })
+            | BuildStep::Sign(SignatureImpl { dest_path, .. })
+            | BuildStep::UnifiedChecksum(UnifiedChecksumStep { dest_path, .. })
+            | BuildStep::Delta(DeltaImpl { dest_path, .. })
+            | BuildStep::GenerateDeltaScript(DeltaScriptImpl { dest_path, .. })
+            | BuildStep::GenerateCitation(CitationInfo { dest_path, .. })
+            | BuildStep::GenerateAsset(GenerateAssetStep { dest_path, .. })
+            | BuildStep::GenerateReleaseNotes(ReleaseNotesImpl { dest_path }) => vec![&**dest_path],
+            // Stripping edits the binaries in place
+            BuildStep::Strip(StripStep { paths }) => paths.iter().map(|p| &**p).collect(),
+            BuildStep::GenerateInstaller(_) => return None,
+        };
+        Some(outputs)
+    }
+}
+
+/// Run a command and write what it prints to a file
+#[derive(Debug, Clone)]
+pub struct GenerateAssetStep {
+    /// The program to run and its arguments
+    pub command: Vec<String>,
+    /// The dir to run it in
+    pub working_dir: Utf8PathBuf,
+    /// Where to write its stdout
+    pub dest_path: Utf8PathBuf,
+}
+
 /// A cargo build (and copy the outputs to various locations)
 #[derive(Debug)]
 pub struct CargoBuildStep {
+    /// The tool to run the build with
+    pub command: CargoBuildCommand,
     /// The --target triple to pass
     pub target_triple: TargetTriple,
     /// The feature flags to pass
@@ -311,13 +643,56 @@ pub struct CargoBuildStep {
     pub expected_binaries: Vec<BinaryIdx>,
 }
 
-/// A cargo build (and copy the outputs to various locations)
-#[derive(Debug)]
+/// The tool a [`CargoBuildStep`][] runs
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CargoBuildCommand {
+    /// Plain old cargo
+    Cargo,
+    /// cross, which runs cargo in a container with the target's toolchain
+    Cross,
+}
+
+/// A rustup invocation to get a toolchain (or a target for one)
+#[derive(Debug, Clone)]
 pub struct RustupStep {
     /// The rustup to invoke (mostly here to prove you Have rustup)
     pub rustup: Tool,
-    /// The target to install
-    pub target: String,
+    /// The toolchain to install (or add the target to), if not the default one
+    pub toolchain: Option<String>,
+    /// The target to install (if None, this just installs the toolchain)
+    pub target: Option<String>,
+}
+
+impl RustupStep {
+    /// The arguments to pass to rustup
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![];
+        match &self.target {
+            Some(target) => {
+                args.extend(["target".to_owned(), "add".to_owned(), target.clone()]);
+                if let Some(toolchain) = &self.toolchain {
+                    args.push(format!("--toolchain={toolchain}"));
+                }
+            }
+            None => {
+                args.extend(["toolchain".to_owned(), "install".to_owned()]);
+                args.extend(self.toolchain.clone());
+            }
+        }
+        args
+    }
+
+    /// The full command to run, for when something else (CI) needs to run it
+    pub fn command(&self) -> String {
+        format!("rustup {}", self.args().join(" "))
+    }
+}
+
+/// Strip debuginfo from some binaries (in place)
+#[derive(Debug)]
+pub struct StripStep {
+    /// The binaries to strip
+    pub paths: Vec<Utf8PathBuf>,
 }
 
 /// zip/tarball some directory
@@ -331,6 +706,8 @@ pub struct ZipDirStep {
     pub with_root: Option<Utf8PathBuf>,
     /// The kind of zip/tarball to make
     pub zip_style: ZipStyle,
+    /// The binaries in the directory (relative to src_path), which should be marked executable
+    pub executables: Vec<Utf8PathBuf>,
 }
 
 /// Copy a file
@@ -362,6 +739,85 @@ pub struct ChecksumImpl {
     pub dest_path: Utf8PathBuf,
 }
 
+/// Create a detached gpg signature
+#[derive(Debug, Clone)]
+pub struct SignatureImpl {
+    /// the id or fingerprint of the gpg key to sign with
+    pub key: String,
+    /// of this file
+    pub src_path: Utf8PathBuf,
+    /// and write it to here
+    pub dest_path: Utf8PathBuf,
+}
+
+/// Build a binary delta from the previous release's version of a file
+#[derive(Debug, Clone)]
+pub struct DeltaImpl {
+    /// the tool to build the delta with
+    pub format: DeltaFormat,
+    /// from the previous version of the file (a URL or a path)
+    pub old_path: String,
+    /// to this file
+    pub src_path: Utf8PathBuf,
+    /// and write it to here
+    pub dest_path: Utf8PathBuf,
+}
+
+/// Write a script that rebuilds archives from deltas
+#[derive(Debug, Clone)]
+pub struct DeltaScriptImpl {
+    /// the tool the deltas were built with
+    pub format: DeltaFormat,
+    /// the app the deltas are for
+    pub app_name: String,
+    /// write it to here
+    pub dest_path: Utf8PathBuf,
+}
+
+/// Create a checksum file covering every artifact of a release
+#[derive(Debug, Clone)]
+pub struct UnifiedChecksumImpl {
+    /// the checksumming algorithm
+    pub checksum: ChecksumStyle,
+    /// the kind of file to write
+    pub format: UnifiedChecksumFormat,
+    /// of the artifacts of this release
+    pub release: ReleaseIdx,
+    /// and write it to here
+    pub dest_path: Utf8PathBuf,
+}
+
+/// The kinds of file a unified checksum can be written as
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnifiedChecksumFormat {
+    /// A SHA256SUMS-style file, as `sha256sum --check` expects
+    Sums,
+    /// A checksums.json, which also records the algorithm and size of each file
+    Json,
+}
+
+/// Checksum several files into one file
+///
+/// This is computed from a [`UnifiedChecksumImpl`][] once all the other artifacts are known.
+#[derive(Debug, Clone)]
+pub struct UnifiedChecksumStep {
+    /// the checksumming algorithm
+    pub checksum: ChecksumStyle,
+    /// the kind of file to write
+    pub format: UnifiedChecksumFormat,
+    /// of these files
+    pub src_paths: Vec<Utf8PathBuf>,
+    /// and write them all to here
+    pub dest_path: Utf8PathBuf,
+}
+
+/// Write the announcement's full release notes
+#[derive(Debug, Clone)]
+pub struct ReleaseNotesImpl {
+    /// to this file
+    pub dest_path: Utf8PathBuf,
+}
+
 /// A kind of symbols (debuginfo)
 #[derive(Copy, Clone, Debug)]
 pub enum SymbolKind {
@@ -411,8 +867,19 @@ pub struct Artifact {
     pub kind: ArtifactKind,
     /// A checksum for this artifact, if any
     pub checksum: Option<ArtifactIdx>,
+    /// A detached signature for this artifact, if any
+    pub signature: Option<ArtifactIdx>,
     /// Indicates whether the artifact is local or global
     pub is_global: bool,
+    /// A note on what added this artifact to the graph, and why it was kept
+    ///
+    /// This is None for hypothetical artifacts that were never added to the graph.
+    pub provenance: Option<String>,
+    /// The files this artifact is derived from, for incremental builds
+    ///
+    /// If none of these have changed since the artifact was last built, it doesn't need
+    /// to be rebuilt. This is None for artifacts we can't track (they're always rebuilt).
+    pub inputs: Option<Vec<Utf8PathBuf>>,
 }
 
 /// Info about an archive (zip/tarball) that should be made. Currently this is always part
@@ -428,10 +895,74 @@ pub struct Archive {
     pub dir_path: Utf8PathBuf,
     /// The style of zip to make
     pub zip_style: ZipStyle,
-    /// Static assets to copy to the root of the artifact's dir (path is src)
+    /// Static assets to copy to the artifact's dir (path is src)
     ///
-    /// In the future this might add a custom relative dest path
+    /// These go in the root of the dir, except for man pages
+    /// (see [`Archive::static_asset_dest`][])
     pub static_assets: Vec<(StaticAssetKind, Utf8PathBuf)>,
+    /// Files to generate in the artifact's dir by running commands
+    pub generated_assets: Vec<GenerateAssetStep>,
+    /// Where man pages go in the artifact's dir, if not [`ARCHIVE_MAN_DIR`][]
+    pub man_dir: Option<Utf8PathBuf>,
+    /// Whether to strip debuginfo from the binaries before archiving them
+    pub strip_binaries: bool,
+}
+
+impl Archive {
+    /// The dir man pages go in (with a `man<section>` subdir per section)
+    pub fn man_dir_path(&self) -> Utf8PathBuf {
+        self.dir_path.join(
+            self.man_dir
+                .as_deref()
+                .unwrap_or(Utf8Path::new(ARCHIVE_MAN_DIR)),
+        )
+    }
+
+    /// Where a static asset gets copied to
+    pub fn static_asset_dest(&self, kind: StaticAssetKind, src_path: &Utf8Path) -> Utf8PathBuf {
+        let file_name = src_path.file_name().unwrap();
+        match kind {
+            StaticAssetKind::ManPage => {
+                let section = man_section(file_name).expect("man page without a section!?");
+                self.man_dir_path().join(section).join(file_name)
+            }
+            _ => self.dir_path.join(file_name),
+        }
+    }
+
+    /// Every man page that ends up in the artifact's dir (relative to it)
+    pub fn man_pages(&self) -> Vec<Utf8PathBuf> {
+        let man_dir = self.man_dir_path();
+        let static_pages = self
+            .static_assets
+            .iter()
+            .filter(|(kind, _)| *kind == StaticAssetKind::ManPage)
+            .map(|(kind, src_path)| self.static_asset_dest(*kind, src_path));
+        let generated_pages = self
+            .generated_assets
+            .iter()
+            .filter(|asset| asset.dest_path.starts_with(&man_dir))
+            .map(|asset| asset.dest_path.clone());
+        static_pages
+            .chain(generated_pages)
+            .filter_map(|path| Some(path.strip_prefix(&self.dir_path).ok()?.to_owned()))
+            .collect()
+    }
+}
+
+/// Get the `man<section>` dir a man page belongs in, from its extension (`my-app.1` => `man1`)
+pub fn man_section(file_name: &str) -> Option<String> {
+    let section = Utf8Path::new(file_name).extension()?.chars().next()?;
+    section.is_ascii_digit().then(|| format!("man{section}"))
+}
+
+/// Which of a pair of slim/full archives we're talking about
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ArchiveFlavor {
+    /// Binaries stripped of debuginfo (what installers fetch)
+    Slim,
+    /// Binaries with all their debuginfo
+    Full,
 }
 
 /// A kind of artifact (more specific fields)
@@ -446,6 +977,18 @@ pub enum ArtifactKind {
     Installer(InstallerImpl),
     /// A checksum
     Checksum(ChecksumImpl),
+    /// A detached signature
+    Signature(SignatureImpl),
+    /// A CITATION.cff file describing the release
+    Citation(CitationInfo),
+    /// The full release notes for the announcement
+    ReleaseNotes(ReleaseNotesImpl),
+    /// A single checksum file covering every other artifact of a release
+    UnifiedChecksum(UnifiedChecksumImpl),
+    /// A binary delta from the previous release's version of an archive
+    Delta(DeltaImpl),
+    /// A script for applying deltas
+    DeltaScript(DeltaScriptImpl),
 }
 
 /// An Archive containing binaries (aka ExecutableZip)
@@ -458,7 +1001,7 @@ pub struct ExecutableZip {
 #[derive(Debug)]
 pub struct Symbols {
     /// The kind of symbols this is
-    kind: SymbolKind,
+    pub kind: SymbolKind,
 }
 
 /// A logical release of an application that artifacts are grouped under
@@ -495,6 +1038,14 @@ pub struct Release {
     pub global_artifacts: Vec<ArtifactIdx>,
     /// Variants of this Release (e.g. "the macos build") that can have "local" Artifacts.
     pub variants: Vec<ReleaseVariantIdx>,
+    /// Extra all-features variants of this Release, built for the demo-targets setting
+    ///
+    /// These share targets with the normal variants, so installers ignore them.
+    pub demo_variants: Vec<ReleaseVariantIdx>,
+    /// Extra variants of this Release with different features, built for the feature-variants setting
+    ///
+    /// Like demos, these share targets with the normal variants, so installers ignore them.
+    pub feature_variants: Vec<ReleaseVariantIdx>,
     /// The body of the changelog for this release
     pub changelog_body: Option<String>,
     /// The title of the changelog for this release
@@ -509,12 +1060,83 @@ pub struct Release {
     pub npm_scope: Option<String>,
     /// Static assets that should be included in bundles like archives
     pub static_assets: Vec<(StaticAssetKind, Utf8PathBuf)>,
+    /// Files to generate for archives, with the dir to run their commands in (the package's)
+    pub generated_assets: Vec<(Utf8PathBuf, GeneratedAsset)>,
+    /// Shells to generate completions for (by running the binaries), for archives
+    pub completions: Vec<CompletionShell>,
+    /// The arguments that make a binary print its completions (`{shell}` is the shell)
+    pub completions_args: Vec<String>,
+    /// Man pages to include in archives and installers (from the man-dir)
+    pub man_pages: Vec<Utf8PathBuf>,
+    /// The arguments that make a binary print its man page, if we should generate them
+    pub man_pages_args: Option<Vec<String>>,
     /// Strategy for selecting paths to install to
     pub install_path: InstallPathStrategy,
     /// GitHub repository to push the Homebrew formula to, if built
     pub tap: Option<String>,
+    /// Binaries the Homebrew formula should install to libexec instead of bin
+    pub homebrew_libexec: Vec<String>,
+    /// GitHub repository to push the Scoop manifest to, if built
+    pub bucket: Option<String>,
+    /// The gpg key to sign this release's artifacts with
+    pub gpg_key: Option<String>,
     /// Packages to install from a system package manager
     pub system_dependencies: SystemDependencies,
+    /// Whether to generate a CITATION.cff file for this release
+    pub citation: bool,
+    /// Whether to generate a single checksum file covering every artifact of this release
+    pub unified_checksum: bool,
+    /// Whether to generate a checksums.json covering every artifact of this release
+    pub checksums_json: bool,
+    /// Whether to generate a tarball of every platform's archives and an offline installer
+    pub offline_bundle: bool,
+    /// Whether the shell installer should embed its archives instead of downloading them
+    pub embed_archives: bool,
+    /// How to show this release's download table in the Github Release notes
+    pub download_table: DownloadTableStyle,
+    /// Whether to build both slim (stripped) and full archives for each variant
+    pub slim_archives: bool,
+    /// The icon to put in this release's AppImages
+    pub appimage_icon: Option<Utf8PathBuf>,
+    /// The app-id for this release's Flatpaks (derived from the repository if unset)
+    pub flatpak_app_id: Option<String>,
+    /// The icon to put in this release's Flatpaks
+    pub flatpak_icon: Option<Utf8PathBuf>,
+    /// How much this release's snaps are sandboxed
+    pub snap_confinement: SnapConfinement,
+    /// How production-ready this release's snaps are
+    pub snap_grade: SnapGrade,
+    /// The WinGet package identifier for this release
+    pub winget_id: Option<String>,
+    /// Whether this release's NSIS installers install to Program Files
+    pub nsis_program_files: bool,
+    /// Whether this release's NSIS installers add the install dir to PATH
+    pub nsis_modify_path: bool,
+    /// The identifier of this release's macOS pkgs
+    pub mac_pkg_identifier: Option<String>,
+    /// Where this release's macOS pkgs install binaries
+    pub mac_pkg_install_location: String,
+    /// The identity to sign this release's macOS pkgs with
+    pub mac_pkg_sign_identity: Option<String>,
+    /// The identifier of this release's macOS .app bundles
+    pub app_bundle_identifier: Option<String>,
+    /// The icon to put in this release's macOS .app bundles
+    pub app_bundle_icon: Option<Utf8PathBuf>,
+    /// The binaries each installer is restricted to (installers not listed get all of them)
+    pub installer_bin_filter: SortedMap<InstallerStyle, Vec<String>>,
+    /// The `[package.metadata.binstall]` to write for this release
+    pub binstall: Option<BinstallInfo>,
+}
+
+impl Release {
+    /// Every variant of this Release, including demos and feature variants
+    pub fn all_variants(&self) -> impl Iterator<Item = ReleaseVariantIdx> + '_ {
+        self.variants
+            .iter()
+            .chain(&self.demo_variants)
+            .chain(&self.feature_variants)
+            .copied()
+    }
 }
 
 /// A particular variant of a Release (e.g. "the macos build")
@@ -542,6 +1164,8 @@ pub enum StaticAssetKind {
     License,
     /// A CHANGLEOG or RELEASES file
     Changelog,
+    /// A man page (which goes in the man dir for its section)
+    ManPage,
     /// Some other miscellaneous file
     Other,
 }
@@ -581,17 +1205,111 @@ pub enum CargoTargetPackages {
     Package(String),
 }
 
-pub(crate) struct DistGraphBuilder<'pkg_graph> {
+/// Assembles a [`DistGraph`][] piece by piece
+///
+/// [`gather_work`][] drives this from the `[metadata.dist]` config, but other tools can use it
+/// to plan a release of their own: add releases, their binaries, variants and installers, then
+/// [`build`][DistGraphBuilder::build] it to get the planned [`DistGraph::build_steps`][].
+///
+/// The workspace can be described in memory, no Cargo.toml needed:
+///
+/// ```
+/// use axoproject::{AutoIncludes, PackageIdx, PackageInfo, WorkspaceInfo, WorkspaceKind};
+/// use cargo_dist::{
+///     config::ArtifactMode, BuildStep, CargoInfo, DistGraphBuilder, Tools,
+/// };
+///
+/// let package = PackageInfo {
+///     name: "axolotlsay".to_owned(),
+///     version: Some(axoproject::Version::Cargo("1.0.0".parse().unwrap())),
+///     manifest_path: "axolotlsay/Cargo.toml".into(),
+///     package_root: "axolotlsay".into(),
+///     description: None,
+///     authors: vec![],
+///     license: None,
+///     publish: true,
+///     keywords: None,
+///     repository_url: None,
+///     homepage_url: None,
+///     documentation_url: None,
+///     readme_file: None,
+///     license_files: vec![],
+///     changelog_file: None,
+///     binaries: vec!["axolotlsay".to_owned()],
+///     cstaticlibs: vec![],
+///     cdylibs: vec![],
+///     cargo_metadata_table: None,
+///     cargo_package_id: Some(guppy::PackageId::new(
+///         "axolotlsay 1.0.0 (path+file:///axolotlsay)",
+///     )),
+/// };
+/// let workspace = WorkspaceInfo {
+///     kind: WorkspaceKind::Rust,
+///     target_dir: "target".into(),
+///     workspace_dir: ".".into(),
+///     package_info: vec![package],
+///     manifest_path: "Cargo.toml".into(),
+///     repository_url: None,
+///     root_auto_includes: AutoIncludes {
+///         readme: None,
+///         licenses: vec![],
+///         changelog: None,
+///     },
+///     warnings: vec![],
+///     cargo_metadata_table: None,
+///     cargo_profiles: Default::default(),
+/// };
+/// let tools = Tools {
+///     cargo: CargoInfo {
+///         cmd: "cargo".to_owned(),
+///         version_line: None,
+///         host_target: "x86_64-unknown-linux-gnu".to_owned(),
+///     },
+///     rustup: None,
+///     appimagetool: None,
+///     flatpak_builder: None,
+///     snapcraft: None,
+///     oras: None,
+///     zstd: None,
+///     bsdiff: None,
+///     cross: None,
+///     git_cliff: None,
+/// };
+///
+/// let mut builder = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true)?;
+/// let release = builder.add_release(PackageIdx(0));
+/// builder.add_binary(release, PackageIdx(0), "axolotlsay".to_owned());
+/// builder.add_variant(release, "x86_64-unknown-linux-gnu".to_owned(), None);
+/// builder.add_executable_zip(release);
+/// let graph = builder.build();
+///
+/// // One cargo build of the binary, then archiving it
+/// assert!(matches!(graph.build_steps[0], BuildStep::Cargo(_)));
+/// assert!(graph
+///     .build_steps
+///     .iter()
+///     .any(|step| matches!(step, BuildStep::Zip(_))));
+/// # Ok::<(), cargo_dist::errors::DistError>(())
+/// ```
+pub struct DistGraphBuilder<'pkg_graph> {
     pub(crate) inner: DistGraph,
     pub(crate) workspace: &'pkg_graph WorkspaceInfo,
     artifact_mode: ArtifactMode,
     binaries_by_id: FastMap<String, BinaryIdx>,
     workspace_metadata: DistMetadata,
     package_metadata: Vec<DistMetadata>,
+    /// The local artifacts of each release that other machines build
+    ///
+    /// Only known in --artifacts=global mode, for the unified checksums to list.
+    remote_artifacts: Vec<Vec<Utf8PathBuf>>,
 }
 
 impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
-    pub(crate) fn new(
+    /// Start a graph for a workspace, reading any `[metadata.dist]` config it has
+    ///
+    /// In [`ArtifactMode::All`][] both local and global artifacts get planned, and
+    /// `allow_all_dirty` skips checking generated files are up to date.
+    pub fn new(
         tools: Tools,
         workspace: &'pkg_graph WorkspaceInfo,
         artifact_mode: ArtifactMode,
@@ -599,7 +1317,6 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
     ) -> DistResult<Self> {
         let target_dir = workspace.target_dir.clone();
         let workspace_dir = workspace.workspace_dir.clone();
-        let dist_dir = target_dir.join(TARGET_DIST);
 
         // Read the global config
         let dist_profile = workspace.cargo_profiles.get(PROFILE_DIST);
@@ -664,6 +1381,118 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             create_release,
             pr_run_mode: _,
             allow_dirty,
+            // Only the final value merged into a package_config matters
+            citation: _,
+            release_notes_asset,
+            // Only the final value merged into a package_config matters
+            unified_checksum: _,
+            // Only the final value merged into a package_config matters
+            homebrew_libexec: _,
+            // Only the final value merged into a package_config matters
+            gpg_key: _,
+            checksum_jobs,
+            tag_format,
+            sentry_org,
+            sentry_project,
+            // Only the final value merged into a package_config matters
+            slim_archives: _,
+            extra_publish_jobs,
+            extra_publish_jobs_file,
+            // Only the final value merged into a package_config matters
+            required_binaries: _,
+            // Only the final value merged into a package_config matters
+            appimage_icon: _,
+            dist_subdir,
+            // Only the final value merged into a package_config matters
+            winget_id: _,
+            bucket,
+            per_target_staging_dirs,
+            // Only the final value merged into a package_config matters
+            flatpak_app_id: _,
+            // Only the final value merged into a package_config matters
+            flatpak_icon: _,
+            // Only the final value merged into a package_config matters
+            snap_confinement: _,
+            // Only the final value merged into a package_config matters
+            snap_grade: _,
+            snap_channel,
+            docker_registry,
+            docker_image,
+            docker_base_image,
+            shell_suite_installer,
+            // Only the final value merged into a package_config matters
+            binstall_metadata: _,
+            // Only the final value merged into a package_config matters
+            mac_pkg_identifier: _,
+            // Only the final value merged into a package_config matters
+            mac_pkg_install_location: _,
+            // Only the final value merged into a package_config matters
+            mac_pkg_sign_identity: _,
+            // Only the final value merged into a package_config matters
+            checksums_json: _,
+            // Only the final value merged into a package_config matters
+            nsis_program_files: _,
+            // Only the final value merged into a package_config matters
+            nsis_modify_path: _,
+            host_target,
+            // Only the final value merged into a package_config matters
+            offline_bundle: _,
+            // Only the final value merged into a package_config matters
+            download_table: _,
+            // Only the final value merged into a package_config matters
+            demo_targets: _,
+            // Only the final value merged into a package_config matters
+            app_bundle_identifier: _,
+            // Only the final value merged into a package_config matters
+            app_bundle_icon: _,
+            // Read when selecting which packages to dist, see check_dist_package
+            dist_unpublished: _,
+            // Only the final value merged into a package_config matters
+            bin_filter: _,
+            // Only the final value merged into a package_config matters
+            installer_bin_filter: _,
+            // Only the final value merged into a package_config matters
+            bin_aliases: _,
+            oci_registry,
+            // Compared against package configs to see if workspace builds are possible
+            target_features,
+            require_changelog,
+            github_custom_runners,
+            // Only the final value merged into a package_config matters
+            license_files: _,
+            ci_cache,
+            sccache_backend,
+            delta_from,
+            delta_format,
+            use_cross,
+            feature_variants: _,
+            build_profile,
+            versioning,
+            git_cliff,
+            changelog_from_commits,
+            contributors,
+            // Read when computing the artifact download url, see artifact_download_url
+            artifact_download_url: _,
+            // Only the final value merged into a package_config matters
+            embed_archives: _,
+            // Read when parsing the announcement tag, see parse_tag
+            announcement_groups: _,
+            // Read when selecting packages, see check_dist_package
+            exclude_packages: _,
+            // Only the final value merged into a package_config matters
+            extra_targets: _,
+            // Only read from package configs (it isn't inherited from the workspace)
+            binaries_from: _,
+            // Only the final value merged into a package_config matters
+            generated_assets: _,
+            // Only the final value merged into a package_config matters
+            completions: _,
+            // Only the final value merged into a package_config matters
+            completions_args: _,
+            // Only the final value merged into a package_config matters
+            man_dir: _,
+            // Only the final value merged into a package_config matters
+            man_pages_args: _,
         } = &workspace_metadata;
 
         let desired_cargo_dist_version = cargo_dist_version.clone();
@@ -672,8 +1501,55 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             warn!("rust-toolchain-version is deprecated, use rust-toolchain.toml if you want pinned toolchains");
         }
         let merge_tasks = merge_tasks.unwrap_or(false);
+        let github_custom_runners = github_custom_runners.clone().unwrap_or_default();
+        let ci_cache = ci_cache.unwrap_or(CiCacheStyle::RustCache);
+        let sccache_backend = sccache_backend.unwrap_or(SccacheBackend::Gha);
+        let delta_format = delta_format.unwrap_or(DeltaFormat::Zstd);
+        let use_cross = use_cross.unwrap_or(false);
+        if use_cross && tools.cross.is_none() {
+            info!("cross isn't installed, so non-host linux targets can't be built locally (CI installs it itself)");
+        }
+        let delta_from = delta_from.as_deref().map(|from| {
+            if from.starts_with("https://") || from.starts_with("http://") {
+                from.trim_end_matches('/').to_owned()
+            } else {
+                workspace_dir.join(from).to_string()
+            }
+        });
+        if delta_from.is_some() {
+            let tool = match delta_format {
+                DeltaFormat::Zstd => &tools.zstd,
+                DeltaFormat::Bsdiff => &tools.bsdiff,
+            };
+            if tool.is_none() {
+                warn!("delta-from is set, but {delta_format} isn't installed, so building deltas will fail");
+            }
+        }
+        let per_target_staging_dirs = per_target_staging_dirs.unwrap_or(false);
         let fail_fast = fail_fast.unwrap_or(false);
+        let checksum_jobs = checksum_jobs
+            .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+            .unwrap_or(1)
+            .max(1);
         let create_release = create_release.unwrap_or(true);
+        let tag_format = tag_format
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TAG_FORMAT.to_owned());
+        if tag_format.matches(TAG_FORMAT_VERSION).count() != 1 {
+            return Err(DistError::TagFormatVersion { tag_format });
+        }
+        let dist_subdir = dist_subdir.as_deref().unwrap_or(TARGET_DIST);
+        let build_profile = build_profile
+            .clone()
+            .unwrap_or_else(|| PROFILE_DIST.to_owned());
+        check_dist_subdir(dist_subdir, &build_profile, &workspace.cargo_profiles)?;
+        // (--host-target gets a chance to override this in gather_work)
+        let mut tools = tools;
+        if let Some(host_target) = host_target {
+            check_host_target(host_target)?;
+            tools.cargo.host_target = host_target.clone();
+        }
+        let dist_dir = target_dir.join(dist_subdir);
         let ssldotcom_windows_sign = ssldotcom_windows_sign.clone();
         let mut packages_with_mismatched_features = vec![];
         // Compute/merge package configs
@@ -687,9 +1563,20 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             package_config.merge_workspace_config(&workspace_metadata, &package.manifest_path);
 
             // Only do workspace builds if all the packages agree with the workspace feature settings
+            // (demos and feature variants have their own features, so they always disagree)
             if &package_config.features != features
                 || &package_config.all_features != all_features
                 || &package_config.default_features != no_default_features
+                || &package_config.target_features != target_features
+                || !package_config
+                    .demo_targets
+                    .as_deref()
+                    .unwrap_or_default()
+                    .is_empty()
+                || package_config
+                    .feature_variants
+                    .as_ref()
+                    .is_some_and(|variants| !variants.is_empty())
             {
                 packages_with_mismatched_features.push(package.name.clone());
             }
@@ -731,7 +1618,52 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     string
                 }
             })
-            .collect();
+            .collect::<Vec<String>>();
+        let extra_publish_jobs =
+            load_extra_publish_jobs(extra_publish_jobs, extra_publish_jobs_file.as_deref())?;
+        {
+            // Every publish job needs a unique name, including the ones cargo-dist provides
+            let mut names = SortedSet::new();
+            for name in user_publish_jobs
+                .iter()
+                .chain(extra_publish_jobs.iter().map(|job| &job.name))
+            {
+                let is_builtin = name
+                    .parse::<PublishStyle>()
+                    .map(|style| !matches!(style, PublishStyle::User(_)))
+                    .unwrap_or(false);
+                if is_builtin || !names.insert(name) {
+                    return Err(DistError::DuplicatePublishJob { name: name.clone() });
+                }
+            }
+        }
+        if publish_jobs.contains(&PublishStyle::Sentry) {
+            if sentry_org.is_none() || sentry_project.is_none() {
+                warn!("The Sentry publish job is enabled but sentry-org or sentry-project is missing\n  consider setting both in Cargo.toml");
+            }
+        } else if sentry_org.is_some() || sentry_project.is_some() {
+            warn!("A Sentry project was specified but the Sentry publish job is disabled\n  consider adding \"sentry\" to publish-jobs in Cargo.toml");
+        }
+        if publish_jobs.contains(&PublishStyle::Docker) {
+            if docker_registry.is_none() {
+                return Err(DistError::DockerConfigMissing {
+                    setting: "docker-registry".to_owned(),
+                });
+            }
+            if docker_image.is_none() {
+                return Err(DistError::DockerConfigMissing {
+                    setting: "docker-image".to_owned(),
+                });
+            }
+        }
+        if publish_jobs.contains(&PublishStyle::Oci) {
+            if oci_registry.is_none() {
+                return Err(DistError::OciRegistryMissing);
+            }
+            if tools.oras.is_none() {
+                info!("oras isn't installed, so you won't be able to push OCI artifacts locally (CI installs it itself)");
+            }
+        }
         let publish_prereleases = publish_prereleases.unwrap_or(false);
 
         let allow_dirty = if allow_all_dirty {
@@ -746,9 +1678,17 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 target_dir,
                 workspace_dir,
                 dist_dir,
+                build_profile,
                 precise_builds,
                 fail_fast,
                 merge_tasks,
+                github_custom_runners,
+                ci_cache,
+                sccache_backend,
+                delta_from,
+                delta_format,
+                use_cross,
+                per_target_staging_dirs,
                 create_release,
                 ssldotcom_windows_sign,
                 desired_cargo_dist_version,
@@ -759,10 +1699,13 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 announcement_is_prerelease: false,
                 announcement_changelog: None,
                 announcement_github_body: None,
+                announcement_release_notes: None,
+                announcement_contributors: None,
                 announcement_title: None,
                 artifact_download_url: None,
                 ci_style: vec![],
                 build_steps: vec![],
+                build_plan: BuildPlan::default(),
                 artifacts: vec![],
                 binaries: vec![],
                 variants: vec![],
@@ -770,9 +1713,29 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 ci: CiInfo::default(),
                 pr_run_mode: workspace_metadata.pr_run_mode.unwrap_or_default(),
                 tap: workspace_metadata.tap.clone(),
+                bucket: bucket.clone(),
+                sentry_org: sentry_org.clone(),
+                sentry_project: sentry_project.clone(),
+                snap_channel: snap_channel.clone().unwrap_or_else(|| "stable".to_owned()),
+                docker_registry: docker_registry.clone(),
+                docker_image: docker_image.clone(),
+                docker_base_image: docker_base_image
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_DOCKER_BASE_IMAGE.to_owned()),
+                oci_registry: oci_registry.clone(),
                 publish_jobs,
                 user_publish_jobs,
+                extra_publish_jobs,
                 publish_prereleases,
+                release_notes_asset: release_notes_asset.unwrap_or(false),
+                require_changelog: require_changelog.unwrap_or(false),
+                git_cliff: git_cliff.unwrap_or(false),
+                changelog_from_commits: changelog_from_commits.unwrap_or(false),
+                contributors: contributors.unwrap_or(false),
+                shell_suite_installer: shell_suite_installer.unwrap_or(false),
+                checksum_jobs,
+                tag_format,
+                versioning: versioning.unwrap_or_default(),
                 allow_dirty,
             },
             package_metadata,
@@ -780,6 +1743,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             workspace,
             binaries_by_id: FastMap::new(),
             artifact_mode,
+            remote_artifacts: vec![],
         })
     }
 
@@ -787,11 +1751,36 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         &self.package_metadata[idx.0]
     }
 
+    /// The binaries a package borrows from other packages (see `binaries-from`)
+    fn borrowed_binaries(&self, pkg_idx: PackageIdx) -> DistResult<Vec<(PackageIdx, String)>> {
+        let mut binaries = vec![];
+        for name in self
+            .package_metadata(pkg_idx)
+            .binaries_from
+            .iter()
+            .flatten()
+        {
+            let Some((member_idx, member)) = self
+                .workspace
+                .packages()
+                .find(|(_, member)| &member.name == name)
+            else {
+                return Err(DistError::UnknownBinariesFromPackage {
+                    package: self.workspace.package(pkg_idx).name.clone(),
+                    member: name.clone(),
+                });
+            };
+            binaries.extend(member.binaries.iter().map(|bin| (member_idx, bin.clone())));
+        }
+        Ok(binaries)
+    }
+
     fn set_ci_style(&mut self, style: Vec<CiStyle>) {
         self.inner.ci_style = style;
     }
 
-    fn add_release(&mut self, pkg_idx: PackageIdx) -> ReleaseIdx {
+    /// Add a release of a package, with no binaries or variants yet
+    pub fn add_release(&mut self, pkg_idx: PackageIdx) -> ReleaseIdx {
         let package_info = self.workspace().package(pkg_idx);
         let package_config = self.package_metadata(pkg_idx);
 
@@ -809,12 +1798,44 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             .clone()
             .unwrap_or(InstallPathStrategy::CargoHome);
         let tap = package_config.tap.clone();
+        let homebrew_libexec = package_config.homebrew_libexec.clone().unwrap_or_default();
+        let bucket = package_config.bucket.clone();
+        let gpg_key = package_config.gpg_key.clone();
 
         let windows_archive = package_config.windows_archive.unwrap_or(ZipStyle::Zip);
         let unix_archive = package_config
             .unix_archive
             .unwrap_or(ZipStyle::Tar(CompressionImpl::Xzip));
         let checksum = package_config.checksum.unwrap_or(ChecksumStyle::Sha256);
+        let citation = package_config.citation.unwrap_or(false);
+        let unified_checksum = package_config.unified_checksum.unwrap_or(false);
+        let checksums_json = package_config.checksums_json.unwrap_or(false);
+        let offline_bundle = package_config.offline_bundle.unwrap_or(false);
+        let embed_archives = package_config.embed_archives.unwrap_or(false);
+        let download_table = package_config
+            .download_table
+            .unwrap_or(DownloadTableStyle::Table);
+        let slim_archives = package_config.slim_archives.unwrap_or(false);
+        let appimage_icon = package_config.appimage_icon.clone();
+        let flatpak_app_id = package_config.flatpak_app_id.clone();
+        let flatpak_icon = package_config.flatpak_icon.clone();
+        let snap_confinement = package_config.snap_confinement.unwrap_or_default();
+        let snap_grade = package_config.snap_grade.unwrap_or_default();
+        let winget_id = package_config.winget_id.clone();
+        let nsis_program_files = package_config.nsis_program_files.unwrap_or(false);
+        let nsis_modify_path = package_config.nsis_modify_path.unwrap_or(true);
+        let mac_pkg_identifier = package_config.mac_pkg_identifier.clone();
+        let mac_pkg_install_location = package_config
+            .mac_pkg_install_location
+            .clone()
+            .unwrap_or_else(|| MAC_PKG_INSTALL_LOCATION.to_owned());
+        let mac_pkg_sign_identity = package_config.mac_pkg_sign_identity.clone();
+        let app_bundle_identifier = package_config.app_bundle_identifier.clone();
+        let app_bundle_icon = package_config.app_bundle_icon.clone();
+        let installer_bin_filter = package_config
+            .installer_bin_filter
+            .clone()
+            .unwrap_or_default();
 
         // Add static assets
         let mut static_assets = vec![];
@@ -826,7 +1847,15 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             if let Some(changelog) = &package_info.changelog_file {
                 static_assets.push((StaticAssetKind::Changelog, changelog.clone()));
             }
-            for license in &package_info.license_files {
+            if package_config.license_files.is_none() {
+                for license in &package_info.license_files {
+                    static_assets.push((StaticAssetKind::License, license.clone()));
+                }
+            }
+        }
+        // Explicitly selected license files replace the auto-detected ones
+        if let Some(license_files) = &package_config.license_files {
+            for license in license_files {
                 static_assets.push((StaticAssetKind::License, license.clone()));
             }
         }
@@ -835,6 +1864,38 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 static_assets.push((StaticAssetKind::Other, static_asset.clone()));
             }
         }
+        let generated_assets = package_config
+            .generated_assets
+            .iter()
+            .flatten()
+            .map(|asset| (package_info.package_root.clone(), asset.clone()))
+            .collect();
+        let completions = package_config.completions.clone().unwrap_or_default();
+        let completions_args = package_config
+            .completions_args
+            .clone()
+            .unwrap_or_else(|| vec!["completions".to_owned(), "{shell}".to_owned()]);
+        let mut man_pages = vec![];
+        if let Some(man_dir) = &package_config.man_dir {
+            match man_dir.read_dir_utf8() {
+                Ok(entries) => {
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if !path.is_file() {
+                            continue;
+                        }
+                        if man_section(entry.file_name()).is_some() {
+                            man_pages.push(path.to_owned());
+                        } else {
+                            warn!("skipping {path}: man pages should be named for their section (e.g. {app_name}.1)");
+                        }
+                    }
+                }
+                Err(e) => warn!("couldn't read man-dir {man_dir}: {e}"),
+            }
+        }
+        man_pages.sort();
+        let man_pages_args = package_config.man_pages_args.clone();
 
         let system_dependencies = package_config
             .system_dependencies
@@ -858,37 +1919,140 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             bins: vec![],
             targets: vec![],
             variants: vec![],
+            demo_variants: vec![],
+            feature_variants: vec![],
             changelog_body: None,
             changelog_title: None,
             windows_archive,
             unix_archive,
             static_assets,
+            generated_assets,
+            completions,
+            completions_args,
+            man_pages,
+            man_pages_args,
             checksum,
             npm_scope,
             install_path,
             tap,
+            homebrew_libexec,
+            bucket,
+            gpg_key,
             system_dependencies,
+            citation,
+            unified_checksum,
+            checksums_json,
+            offline_bundle,
+            embed_archives,
+            download_table,
+            slim_archives,
+            appimage_icon,
+            flatpak_app_id,
+            flatpak_icon,
+            snap_confinement,
+            snap_grade,
+            winget_id,
+            nsis_program_files,
+            nsis_modify_path,
+            mac_pkg_identifier,
+            mac_pkg_install_location,
+            mac_pkg_sign_identity,
+            app_bundle_identifier,
+            app_bundle_icon,
+            installer_bin_filter,
+            binstall: None,
         });
         idx
     }
 
-    fn add_variant(&mut self, to_release: ReleaseIdx, target: TargetTriple) -> ReleaseVariantIdx {
+    /// Add a variant for a target to a release
+    ///
+    /// If `feature_variant` is set (to a name and the features to build with), this is
+    /// instead an extra build of a target the release already has, which only gets an archive.
+    pub fn add_variant(
+        &mut self,
+        to_release: ReleaseIdx,
+        target: TargetTriple,
+        feature_variant: Option<(String, CargoTargetFeatures)>,
+    ) -> ReleaseVariantIdx {
+        let is_feature_variant = feature_variant.is_some();
+        let idx = self.make_variant(to_release, target.clone(), feature_variant);
+        let release = self.release_mut(to_release);
+        if is_feature_variant {
+            release.feature_variants.push(idx);
+        } else {
+            release.variants.push(idx);
+            release.targets.push(target);
+        }
+        idx
+    }
+
+    /// Add an all-features "demo" variant, next to the normal variant for that target
+    fn add_demo_variant(
+        &mut self,
+        to_release: ReleaseIdx,
+        target: TargetTriple,
+    ) -> ReleaseVariantIdx {
+        let features = CargoTargetFeatures {
+            default_features: true,
+            features: CargoTargetFeatureList::All,
+        };
+        let idx = self.make_variant(to_release, target, Some(("demo".to_owned(), features)));
+        self.release_mut(to_release).demo_variants.push(idx);
+        idx
+    }
+
+    /// Add a variant (and its binaries) to the graph, without registering it with its release
+    ///
+    /// `feature_variant` is the name and features of a variant that doesn't use the
+    /// package's usual features (demos and feature-variants).
+    fn make_variant(
+        &mut self,
+        to_release: ReleaseIdx,
+        target: TargetTriple,
+        feature_variant: Option<(String, CargoTargetFeatures)>,
+    ) -> ReleaseVariantIdx {
         let idx = ReleaseVariantIdx(self.inner.variants.len());
         let Release {
             id: release_id,
-            variants,
-            targets,
             static_assets,
             bins,
+            completions,
+            man_pages_args,
             ..
-        } = self.release_mut(to_release);
+        } = self.release(to_release);
         let static_assets = static_assets.clone();
-        let id = format!("{release_id}-{target}");
+        // Completions (and man pages) come from running the binaries, so we need to be able
+        // to run them
+        let host = &self.inner.tools.cargo.host_target;
+        let mut generated = vec![];
+        if !completions.is_empty() {
+            generated.push("completions");
+        }
+        if man_pages_args.is_some() {
+            generated.push("man pages");
+        }
+        if !generated.is_empty() && &target != host {
+            let message = format!(
+                "skipping {} for {target}: binaries built for it can't run on {host}",
+                generated.join(" and ")
+            );
+            if matches!(self.artifact_mode, ArtifactMode::Local | ArtifactMode::Host) {
+                warn!("{message}");
+            } else {
+                info!("{message}");
+            }
+        }
+        // Feature variants get their own ids so their binaries and archives don't collide
+        // with the normal ones (binaries_by_id relies on this to not mix up their features)
+        let suffix = feature_variant
+            .as_ref()
+            .map(|(name, _)| format!("-{name}"))
+            .unwrap_or_default();
+        let target_name = target_name(&target);
+        let id = format!("{release_id}-{target_name}{suffix}");
         info!("added variant {id}");
 
-        variants.push(idx);
-        targets.push(target.clone());
-
         // Add all the binaries of the release to this variant
         let mut binaries = vec![];
         for (pkg_idx, binary_name) in bins.clone() {
@@ -901,28 +2065,56 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             // referring to a package in your workspace that you want to build an app for.
             // If they do exist, that's deeply cursed and I want a user to tell me about it.
             let pkg_spec = package.name.clone();
-            let id = format!("{binary_name}-v{version}-{target}");
+            let id = format!("{binary_name}-v{version}-{target_name}{suffix}");
 
             let idx = if let Some(&idx) = self.binaries_by_id.get(&id) {
                 // If we already are building this binary we don't need to do it again!
                 idx
             } else {
                 // Compute the rest of the details and add the binary
-                let features = CargoTargetFeatures {
-                    default_features: package_metadata.default_features.unwrap_or(true),
-                    features: if let Some(true) = package_metadata.all_features {
-                        CargoTargetFeatureList::All
-                    } else {
-                        CargoTargetFeatureList::List(
-                            package_metadata.features.clone().unwrap_or_default(),
-                        )
-                    },
-                };
+                let features = if let Some((_, features)) = &feature_variant {
+                    features.clone()
+                } else {
+                    let mut features = CargoTargetFeatures {
+                        default_features: package_metadata.default_features.unwrap_or(true),
+                        features: if let Some(true) = package_metadata.all_features {
+                            CargoTargetFeatureList::All
+                        } else {
+                            CargoTargetFeatureList::List(
+                                package_metadata.features.clone().unwrap_or_default(),
+                            )
+                        },
+                    };
+                    // Then apply any adjustments for this target
+                    let target_features = package_metadata.target_features.iter().flatten();
+                    for (pattern, adjustments) in target_features {
+                        if !config::target_matches_pattern(pattern, &target) {
+                            continue;
+                        }
+                        if let Some(default_features) = adjustments.default_features {
+                            features.default_features = default_features;
+                        }
+                        if let CargoTargetFeatureList::List(list) = &mut features.features {
+                            for feature in &adjustments.features {
+                                if !list.contains(feature) {
+                                    list.push(feature.clone());
+                                }
+                            }
+                        }
+                    }
+                    features
+                };
 
                 let target_is_windows = target.contains("windows");
                 let platform_exe_ext = if target_is_windows { ".exe" } else { "" };
 
                 let file_name = format!("{binary_name}{platform_exe_ext}");
+                let installed_name = package_metadata
+                    .bin_aliases
+                    .as_ref()
+                    .and_then(|aliases| aliases.get(&binary_name))
+                    .unwrap_or(&binary_name);
+                let installed_file_name = format!("{installed_name}{platform_exe_ext}");
 
                 info!("added binary {id}");
                 let idx = BinaryIdx(self.inner.binaries.len());
@@ -933,6 +2125,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     pkg_idx,
                     name: binary_name,
                     file_name,
+                    installed_file_name,
                     target: target.clone(),
                     copy_exe_to: vec![],
                     copy_symbols_to: vec![],
@@ -956,12 +2149,16 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         idx
     }
 
-    fn add_binary(&mut self, to_release: ReleaseIdx, pkg_idx: PackageIdx, binary_name: String) {
+    /// Include a binary in a release, built from the package that defines it
+    ///
+    /// Binaries must be added before the release's variants.
+    pub fn add_binary(&mut self, to_release: ReleaseIdx, pkg_idx: PackageIdx, binary_name: String) {
         let release = self.release_mut(to_release);
         release.bins.push((pkg_idx, binary_name));
     }
 
-    fn add_executable_zip(&mut self, to_release: ReleaseIdx) {
+    /// Add an archive of the release's binaries for each of its variants
+    pub fn add_executable_zip(&mut self, to_release: ReleaseIdx) {
         if !self.local_artifacts_enabled() {
             return;
         }
@@ -970,28 +2167,91 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             self.release(to_release).id
         );
 
-        // Create an archive for each Variant
+        // Create an archive for each Variant (demos included)
         let release = self.release(to_release);
-        let variants = release.variants.clone();
+        let variants = release.all_variants().collect::<Vec<_>>();
         let checksum = release.checksum;
         for variant_idx in variants {
-            let (zip_artifact, built_assets) =
-                self.make_executable_zip_for_variant(to_release, variant_idx);
+            for (zip_artifact, built_assets) in
+                self.make_executable_zips_for_variant(to_release, variant_idx)
+            {
+                let flavor = if zip_artifact.archive.as_ref().unwrap().strip_binaries {
+                    "stripped "
+                } else {
+                    ""
+                };
+                let why = format!(
+                    "archive of release {}'s {flavor}binaries for {}",
+                    self.release(to_release).id,
+                    self.variant(variant_idx).target
+                );
+                let zip_artifact_idx = self.add_local_artifact(variant_idx, zip_artifact, why);
+                for (binary, dest_path) in built_assets {
+                    self.require_binary(zip_artifact_idx, variant_idx, binary, dest_path);
+                }
 
-            let zip_artifact_idx = self.add_local_artifact(variant_idx, zip_artifact);
-            for (binary, dest_path) in built_assets {
-                self.require_binary(zip_artifact_idx, variant_idx, binary, dest_path);
+                if checksum != ChecksumStyle::False {
+                    self.add_artifact_checksum(
+                        to_release,
+                        Some(variant_idx),
+                        zip_artifact_idx,
+                        checksum,
+                    );
+                }
+                self.add_artifact_signature(to_release, Some(variant_idx), zip_artifact_idx);
+                self.add_artifact_delta(to_release, variant_idx, zip_artifact_idx);
             }
+        }
+    }
 
-            if checksum != ChecksumStyle::False {
-                self.add_artifact_checksum(variant_idx, zip_artifact_idx, checksum);
-            }
+    /// Add a binary delta from the previous release's version of an artifact, if wanted
+    ///
+    /// Like the artifact itself, the delta gets a checksum and a signature.
+    fn add_artifact_delta(
+        &mut self,
+        to_release: ReleaseIdx,
+        to_variant: ReleaseVariantIdx,
+        artifact_idx: ArtifactIdx,
+    ) -> Option<ArtifactIdx> {
+        let delta_from = self.inner.delta_from.as_deref()?;
+        let format = self.inner.delta_format;
+        let artifact = self.artifact(artifact_idx);
+        let delta_id = format!("{}.{}", artifact.id, format.ext());
+        let delta_path = artifact.file_path.parent().unwrap().join(&delta_id);
+        let old_path = format!("{delta_from}/{}", artifact.id);
+        let why = format!("{format} delta of {} from {old_path}", artifact.id);
+        let delta_artifact = Artifact {
+            id: delta_id,
+            kind: ArtifactKind::Delta(DeltaImpl {
+                format,
+                old_path,
+                src_path: artifact.file_path.clone(),
+                dest_path: delta_path.clone(),
+            }),
+
+            target_triples: artifact.target_triples.clone(),
+            archive: None,
+            file_path: delta_path,
+            required_binaries: Default::default(),
+            checksum: None,
+            signature: None,
+            is_global: false,
+            provenance: None,
+            inputs: None,
+        };
+        let delta_idx = self.add_local_artifact(to_variant, delta_artifact, why);
+        let checksum = self.release(to_release).checksum;
+        if checksum != ChecksumStyle::False {
+            self.add_artifact_checksum(to_release, Some(to_variant), delta_idx, checksum);
         }
+        self.add_artifact_signature(to_release, Some(to_variant), delta_idx);
+        Some(delta_idx)
     }
 
     fn add_artifact_checksum(
         &mut self,
-        to_variant: ReleaseVariantIdx,
+        to_release: ReleaseIdx,
+        to_variant: Option<ReleaseVariantIdx>,
         artifact_idx: ArtifactIdx,
         checksum: ChecksumStyle,
     ) -> ArtifactIdx {
@@ -1014,22 +2274,252 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 required_binaries: Default::default(),
                 // Who checksums the checksummers...
                 checksum: None,
-                is_global: false,
+                signature: None,
+                is_global: to_variant.is_none(),
+                provenance: None,
+                inputs: None,
             }
         };
-        let checksum_idx = self.add_local_artifact(to_variant, checksum_artifact);
+        let why = format!("{} checksum of {}", checksum.ext(), artifact.id);
+        let checksum_idx = if let Some(to_variant) = to_variant {
+            self.add_local_artifact(to_variant, checksum_artifact, why)
+        } else {
+            self.add_global_artifact(to_release, checksum_artifact, why)
+        };
         self.artifact_mut(artifact_idx).checksum = Some(checksum_idx);
         checksum_idx
     }
 
+    /// Get the checksum of an archive for an ExecutableZipFragment (if it has one)
+    fn fragment_checksum(
+        &self,
+        to_release: ReleaseIdx,
+        archive_id: &str,
+    ) -> Option<FragmentChecksum> {
+        let (style, artifact) = self.archive_checksum(to_release, archive_id)?;
+        Some(FragmentChecksum {
+            style,
+            artifact,
+            value: None,
+        })
+    }
+
+    /// Get the id of an archive's sha256 checksum (if it has one)
+    fn sha256_checksum_artifact(&self, to_release: ReleaseIdx, archive_id: &str) -> Option<String> {
+        match self.archive_checksum(to_release, archive_id)? {
+            (ChecksumStyle::Sha256, checksum_id) => Some(checksum_id),
+            _ => None,
+        }
+    }
+
+    /// Get the algorithm and id of the checksum `add_executable_zip` makes for an archive (if any)
+    ///
+    /// This is computed from the release's settings instead of looked up in the graph,
+    /// since with --artifacts=global the archives (and their checksums) aren't in it.
+    fn archive_checksum(
+        &self,
+        to_release: ReleaseIdx,
+        archive_id: &str,
+    ) -> Option<(ChecksumStyle, String)> {
+        let checksum = self.release(to_release).checksum;
+        if checksum == ChecksumStyle::False {
+            return None;
+        }
+        Some((checksum, format!("{archive_id}.{}", checksum.ext())))
+    }
+
+    /// Add a detached signature for an artifact, if the release wants them
+    ///
+    /// If a variant is given, the signature is a local artifact of that variant,
+    /// otherwise it's a global artifact of the release.
+    fn add_artifact_signature(
+        &mut self,
+        to_release: ReleaseIdx,
+        to_variant: Option<ReleaseVariantIdx>,
+        artifact_idx: ArtifactIdx,
+    ) -> Option<ArtifactIdx> {
+        let key = self.release(to_release).gpg_key.clone()?;
+        let artifact = self.artifact(artifact_idx);
+        let signature_id = format!("{}.asc", artifact.id);
+        let signature_path = artifact.file_path.parent().unwrap().join(&signature_id);
+        let why = format!("gpg signature of {} with key {key}", artifact.id);
+        let signature_artifact = Artifact {
+            id: signature_id,
+            kind: ArtifactKind::Signature(SignatureImpl {
+                key,
+                src_path: artifact.file_path.clone(),
+                dest_path: signature_path.clone(),
+            }),
+
+            target_triples: artifact.target_triples.clone(),
+            archive: None,
+            file_path: signature_path,
+            required_binaries: Default::default(),
+            checksum: None,
+            signature: None,
+            is_global: to_variant.is_none(),
+            provenance: None,
+            inputs: None,
+        };
+        let signature_idx = if let Some(to_variant) = to_variant {
+            self.add_local_artifact(to_variant, signature_artifact, why)
+        } else {
+            self.add_global_artifact(to_release, signature_artifact, why)
+        };
+        self.artifact_mut(artifact_idx).signature = Some(signature_idx);
+        Some(signature_idx)
+    }
+
     /// Make an executable zip for a variant, but don't yet integrate it into the graph
     ///
-    /// This is useful for installers which want to know about *potential* executable zips
+    /// This is useful for installers which want to know about *potential* executable zips.
+    /// If the release has slim-archives enabled, this is the slim one.
     fn make_executable_zip_for_variant(
         &self,
         release_idx: ReleaseIdx,
         variant_idx: ReleaseVariantIdx,
     ) -> (Artifact, Vec<(BinaryIdx, Utf8PathBuf)>) {
+        let flavor = self
+            .release(release_idx)
+            .slim_archives
+            .then_some(ArchiveFlavor::Slim);
+        self.make_archive_for_variant(release_idx, variant_idx, flavor)
+    }
+
+    /// Make the archive the npm installer unpacks for a variant, but don't yet integrate it into the graph
+    ///
+    /// npm only knows how to unpack .tar.gz archives, so if the variant's executable zip is
+    /// anything else, this is a separate .tar.gz of the same files (staged in its own dir).
+    /// The bool is whether it's separate like that.
+    fn make_npm_archive_for_variant(
+        &self,
+        release_idx: ReleaseIdx,
+        variant_idx: ReleaseVariantIdx,
+    ) -> (Artifact, Vec<(BinaryIdx, Utf8PathBuf)>, bool) {
+        const NPM_ZIP_STYLE: ZipStyle = ZipStyle::Tar(CompressionImpl::Gzip);
+        let (mut artifact, built_assets) =
+            self.make_executable_zip_for_variant(release_idx, variant_idx);
+        let archive = artifact.archive.as_mut().unwrap();
+        if archive.zip_style == NPM_ZIP_STYLE {
+            return (artifact, built_assets, false);
+        }
+
+        let artifact_dir_name = artifact
+            .id
+            .strip_suffix(&archive.zip_style.ext())
+            .expect("archive name didn't end in its extension!?")
+            .to_owned();
+        let target = &self.variant(variant_idx).target;
+        let old_dir_path = std::mem::replace(
+            &mut archive.dir_path,
+            self.staging_dir(target, &format!("{artifact_dir_name}-npm")),
+        );
+        let built_assets = built_assets
+            .into_iter()
+            .map(|(binary_idx, dest_path)| {
+                let rel_path = dest_path
+                    .strip_prefix(&old_dir_path)
+                    .expect("binary wasn't in its archive's dir!?");
+                (binary_idx, archive.dir_path.join(rel_path))
+            })
+            .collect();
+        archive.zip_style = NPM_ZIP_STYLE;
+        archive.with_root = Some(Utf8PathBuf::from(&artifact_dir_name));
+        artifact.id = format!("{artifact_dir_name}{}", NPM_ZIP_STYLE.ext());
+        artifact.file_path = self.inner.dist_dir.join(&artifact.id);
+        (artifact, built_assets, true)
+    }
+
+    /// Add the .tar.gz archives the npm installer needs, for variants whose executable zips aren't
+    fn add_npm_archives(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        for variant_idx in variants {
+            let (artifact, built_assets, separate) =
+                self.make_npm_archive_for_variant(to_release, variant_idx);
+            if !separate {
+                continue;
+            }
+            let why = format!(
+                "the npm installer of release {} needs a .tar.gz archive for {}",
+                self.release(to_release).id,
+                self.variant(variant_idx).target
+            );
+            let artifact_idx = self.add_local_artifact(variant_idx, artifact, why);
+            for (binary, dest_path) in built_assets {
+                self.require_binary(artifact_idx, variant_idx, binary, dest_path);
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), artifact_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), artifact_idx);
+        }
+    }
+
+    /// Make every executable zip a variant should have (full and slim, or just the one)
+    fn make_executable_zips_for_variant(
+        &self,
+        release_idx: ReleaseIdx,
+        variant_idx: ReleaseVariantIdx,
+    ) -> Vec<(Artifact, Vec<(BinaryIdx, Utf8PathBuf)>)> {
+        if self.release(release_idx).slim_archives {
+            vec![
+                self.make_archive_for_variant(release_idx, variant_idx, Some(ArchiveFlavor::Full)),
+                self.make_archive_for_variant(release_idx, variant_idx, Some(ArchiveFlavor::Slim)),
+            ]
+        } else {
+            vec![self.make_archive_for_variant(release_idx, variant_idx, None)]
+        }
+    }
+
+    /// Steps to generate man pages (in `man_dir`) by running a variant's binaries (in `bin_dir`)
+    ///
+    /// Only binaries built for the host can print their man pages, so other targets get none.
+    fn man_page_steps(
+        &self,
+        release_idx: ReleaseIdx,
+        variant_idx: ReleaseVariantIdx,
+        bin_dir: &Utf8Path,
+        man_dir: &Utf8Path,
+    ) -> Vec<GenerateAssetStep> {
+        let release = self.release(release_idx);
+        let variant = self.variant(variant_idx);
+        let Some(args) = &release.man_pages_args else {
+            return vec![];
+        };
+        if variant.target != self.inner.tools.cargo.host_target {
+            return vec![];
+        }
+        variant
+            .binaries
+            .iter()
+            .map(|&binary_idx| {
+                let binary = self.binary(binary_idx);
+                let mut command = vec![bin_dir.join(&binary.file_name).to_string()];
+                command.extend(args.iter().cloned());
+                GenerateAssetStep {
+                    command,
+                    working_dir: self.workspace.package(binary.pkg_idx).package_root.clone(),
+                    dest_path: man_dir.join("man1").join(format!("{}.1", binary.name)),
+                }
+            })
+            .collect()
+    }
+
+    fn make_archive_for_variant(
+        &self,
+        release_idx: ReleaseIdx,
+        variant_idx: ReleaseVariantIdx,
+        flavor: Option<ArchiveFlavor>,
+    ) -> (Artifact, Vec<(BinaryIdx, Utf8PathBuf)>) {
+        debug_assert!(
+            self.archive_layout_enabled(),
+            "computed an archive in --artifacts=metadata mode"
+        );
         // This is largely just a lot of path/name computation
         let dist_dir = &self.inner.dist_dir;
         let release = self.release(release_idx);
@@ -1042,13 +2532,64 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             release.unix_archive
         };
 
-        let artifact_dir_name = variant.id.clone();
-        let artifact_dir_path = dist_dir.join(&artifact_dir_name);
+        let artifact_dir_name = match flavor {
+            None => variant.id.clone(),
+            Some(ArchiveFlavor::Slim) => format!("{}-slim", variant.id),
+            Some(ArchiveFlavor::Full) => format!("{}-full", variant.id),
+        };
+        // On windows-msvc the debuginfo already lives in the pdb, so there's nothing to strip
+        let strip_binaries =
+            flavor == Some(ArchiveFlavor::Slim) && !variant.target.contains("windows-msvc");
+        let artifact_dir_path = self.staging_dir(&variant.target, &artifact_dir_name);
         let artifact_ext = zip_style.ext();
         let artifact_name = format!("{artifact_dir_name}{artifact_ext}");
         let artifact_path = dist_dir.join(&artifact_name);
 
-        let static_assets = variant.static_assets.clone();
+        let mut static_assets = variant.static_assets.clone();
+        static_assets.extend(
+            release
+                .man_pages
+                .iter()
+                .map(|page| (StaticAssetKind::ManPage, page.clone())),
+        );
+        let mut generated_assets = release
+            .generated_assets
+            .iter()
+            .map(|(working_dir, asset)| GenerateAssetStep {
+                command: asset.command.clone(),
+                working_dir: working_dir.clone(),
+                dest_path: artifact_dir_path.join(&asset.path),
+            })
+            .collect::<Vec<_>>();
+        // Only binaries built for the host can print their completions
+        if variant.target == self.inner.tools.cargo.host_target {
+            for &binary_idx in &variant.binaries {
+                let binary = self.binary(binary_idx);
+                let working_dir = &self.workspace.package(binary.pkg_idx).package_root;
+                for shell in &release.completions {
+                    let mut command = vec![artifact_dir_path.join(&binary.file_name).to_string()];
+                    command.extend(
+                        release
+                            .completions_args
+                            .iter()
+                            .map(|arg| arg.replace("{shell}", &shell.to_string())),
+                    );
+                    generated_assets.push(GenerateAssetStep {
+                        command,
+                        working_dir: working_dir.clone(),
+                        dest_path: artifact_dir_path
+                            .join("completions")
+                            .join(shell.file_name(&binary.name)),
+                    });
+                }
+            }
+        }
+        generated_assets.extend(self.man_page_steps(
+            release_idx,
+            variant_idx,
+            &artifact_dir_path,
+            &artifact_dir_path.join(ARCHIVE_MAN_DIR),
+        ));
         let mut built_assets = Vec::new();
         for &binary_idx in &variant.binaries {
             let binary = self.binary(binary_idx);
@@ -1075,11 +2616,17 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     dir_path: artifact_dir_path,
                     zip_style,
                     static_assets,
+                    generated_assets,
+                    man_dir: None,
+                    strip_binaries,
                 }),
                 kind: ArtifactKind::ExecutableZip(ExecutableZip {}),
                 // May get filled in later
                 checksum: None,
+                signature: None,
                 is_global: false,
+                provenance: None,
+                inputs: None,
             },
             built_assets,
         )
@@ -1092,10 +2639,11 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
     /// as soon as they're built.
     ///
     /// Note that it's important to use `dest_path`, as cargo does not guarantee that
-    /// multiple invocations will not overwrite each other's outputs. Since we always
-    /// explicitly pass --target and --profile, this is unlikely to be an issue. But if
-    /// we ever introduce the notion of "feature variants" (ReleaseVariants that differ
-    /// only in the feature flags they take), this will become a problem.
+    /// multiple invocations will not overwrite each other's outputs. We always explicitly
+    /// pass --target and --profile, but feature variants (and demos) are ReleaseVariants
+    /// that differ only in the feature flags they take, so their builds write to the exact
+    /// same place in the target dir. Each build's binaries must be copied to their
+    /// `dest_path` before the next build for that target runs.
     fn require_binary(
         &mut self,
         for_artifact: ArtifactIdx,
@@ -1104,6 +2652,14 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         dest_path: Utf8PathBuf,
     ) {
         let dist_dir = self.inner.dist_dir.clone();
+        let has_debuginfo =
+            profile_has_debuginfo(&self.workspace.cargo_profiles, &self.inner.build_profile);
+        // Where a dSYM would be staged, worked out before we start mutating the binary
+        let dsym_dir_path = {
+            let binary = self.binary(binary_idx);
+            let dir_name = format!("{}-{}", binary.id, SymbolKind::Dsym.ext());
+            self.staging_dir(&binary.target, &dir_name)
+        };
         let binary = self.binary_mut(binary_idx);
 
         // Tell the binary that it should copy the exe to the given path
@@ -1111,7 +2667,10 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
 
         // Try to make a symbols artifact for this binary now that we're building it
         if binary.symbols_artifact.is_none() {
-            if let Some(symbol_kind) = target_symbol_kind(&binary.target) {
+            // cargo only emits dSYMs if there's debuginfo to put in them
+            let symbol_kind = target_symbol_kind(&binary.target)
+                .filter(|kind| has_debuginfo || !matches!(kind, SymbolKind::Dsym));
+            if let Some(symbol_kind) = symbol_kind {
                 // FIXME: For some formats these won't be the same but for now stubbed out
 
                 // FIXME: rustc/cargo has so more complex logic to do platform-specifc name remapping
@@ -1126,17 +2685,45 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 let binary_id = &binary.id;
                 // let src_symbol_name = format!("{base_name}.{src_symbol_ext}");
                 let dest_symbol_name = format!("{binary_id}.{dest_symbol_ext}");
-                let artifact_path = dist_dir.join(&dest_symbol_name);
+
+                // dSYMs are actually directories, so we copy them into a dir of their
+                // own and tarball that up, instead of shipping a flat file
+                let (artifact_name, archive, copy_symbols_to) = match symbol_kind {
+                    SymbolKind::Dsym => {
+                        let zip_style = ZipStyle::Tar(CompressionImpl::Xzip);
+                        let artifact_name = format!("{dest_symbol_name}{}", zip_style.ext());
+                        let dir_path = dsym_dir_path;
+                        let copy_symbols_to = dir_path.join(&dest_symbol_name);
+                        let archive = Archive {
+                            with_root: None,
+                            dir_path,
+                            zip_style,
+                            static_assets: vec![],
+                            generated_assets: vec![],
+                            man_dir: None,
+                            strip_binaries: false,
+                        };
+                        (artifact_name, Some(archive), copy_symbols_to)
+                    }
+                    SymbolKind::Pdb | SymbolKind::Dwp => {
+                        let copy_symbols_to = dist_dir.join(&dest_symbol_name);
+                        (dest_symbol_name, None, copy_symbols_to)
+                    }
+                };
+                let artifact_path = dist_dir.join(&artifact_name);
 
                 let artifact = Artifact {
-                    id: dest_symbol_name,
+                    id: artifact_name,
                     target_triples: vec![binary.target.clone()],
-                    archive: None,
+                    archive,
                     file_path: artifact_path.clone(),
                     required_binaries: FastMap::new(),
                     kind: ArtifactKind::Symbols(Symbols { kind: symbol_kind }),
                     checksum: None,
+                    signature: None,
                     is_global: false,
+                    provenance: None,
+                    inputs: None,
                 };
 
                 // FIXME: strictly speaking a binary could plausibly be shared between Releases,
@@ -1145,12 +2732,13 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 // artifact to be strictly nested under one Variant.
                 //
                 // For now we pretend this isn't a thing.
-                let sym_artifact = self.add_local_artifact(for_variant, artifact);
+                let why = format!("symbols for binary {binary_id}");
+                let sym_artifact = self.add_local_artifact(for_variant, artifact, why);
 
                 // Record that we've made the symbols artifact for this binary
                 let binary = self.binary_mut(binary_idx);
                 binary.symbols_artifact = Some(sym_artifact);
-                binary.copy_symbols_to.push(artifact_path);
+                binary.copy_symbols_to.push(copy_symbols_to);
             }
         }
 
@@ -1160,7 +2748,8 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             .insert(binary_idx, dest_path);
     }
 
-    fn add_installer(
+    /// Add an installer to a release (after all its variants)
+    pub fn add_installer(
         &mut self,
         to_release: ReleaseIdx,
         installer: &InstallerStyle,
@@ -1170,7 +2759,21 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             InstallerStyle::Powershell => self.add_powershell_installer(to_release),
             InstallerStyle::Npm => self.add_npm_installer(to_release),
             InstallerStyle::Homebrew => self.add_homebrew_installer(to_release),
-            InstallerStyle::Msi => self.add_msi_installer(to_release)?,
+            InstallerStyle::Scoop => self.add_scoop_installer(to_release),
+            InstallerStyle::Msi => {
+                self.add_msi_installer(to_release)?;
+                self.add_winget_manifests(to_release);
+            }
+            InstallerStyle::Deb => self.add_deb_installer(to_release),
+            InstallerStyle::Rpm => self.add_rpm_installer(to_release),
+            InstallerStyle::AppImage => self.add_appimage_installer(to_release),
+            InstallerStyle::FreebsdPkg => self.add_freebsd_pkg_installer(to_release),
+            InstallerStyle::Flatpak => self.add_flatpak_installer(to_release),
+            InstallerStyle::Snap => self.add_snap_installer(to_release),
+            InstallerStyle::MacPkg => self.add_mac_pkg_installer(to_release),
+            InstallerStyle::Dmg => self.add_dmg_installer(to_release),
+            InstallerStyle::App => self.add_app_bundle_installer(to_release),
+            InstallerStyle::Nsis => self.add_nsis_installer(to_release),
         }
         Ok(())
     }
@@ -1179,6 +2782,10 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         if !self.global_artifacts_enabled() {
             return;
         }
+        if !self.archive_layout_enabled() {
+            info!("skipping shell installer: --artifacts=metadata doesn't compute archives");
+            return;
+        }
         let release = self.release(to_release);
         let release_id = &release.id;
         let Some(download_url) = &self.inner.artifact_download_url else {
@@ -1191,6 +2798,86 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         let hint = format!("curl --proto '=https' --tlsv1.2 -LsSf {installer_url} | sh");
         let desc = "Install prebuilt binaries via shell script".to_owned();
 
+        // Gather up the bundles the installer supports
+        let (artifacts, target_triples) = self.shell_installer_fragments(to_release);
+        if artifacts.is_empty() {
+            warn!("skipping shell installer: not building any supported platforms (use --artifacts=global)");
+            return;
+        };
+
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples: target_triples.into_iter().collect(),
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Shell(InstallerInfo {
+                dest_path: artifact_path,
+                app_name: release.app_name.clone(),
+                app_version: release.version.to_string(),
+                install_path: release.install_path.clone().into_jinja(),
+                offline: false,
+                embedded_archives: release.embed_archives.then(SortedMap::new),
+                base_url: download_url.clone(),
+                artifacts,
+                hint,
+                desc,
+            })),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "shell installer requested by release {}'s installers setting",
+            self.release(to_release).id
+        );
+        let installer_idx = self.add_global_artifact(to_release, installer_artifact, why);
+        self.add_artifact_signature(to_release, None, installer_idx);
+    }
+
+    /// The file names of the binaries (from an archive) an installer should install
+    ///
+    /// This is all of them, unless installer-bin-filter restricts this kind of installer.
+    /// Binaries that should be installed under another name (see bin-aliases) are also
+    /// returned as a map from their name in the archive to their installed name.
+    fn installer_binaries(
+        &self,
+        to_release: ReleaseIdx,
+        installer: InstallerStyle,
+        binaries: Vec<(BinaryIdx, Utf8PathBuf)>,
+    ) -> (Vec<String>, SortedMap<String, String>) {
+        let filter = self
+            .release(to_release)
+            .installer_bin_filter
+            .get(&installer);
+        let mut file_names = vec![];
+        let mut aliases = SortedMap::new();
+        for (binary_idx, dest_path) in binaries {
+            let binary = self.binary(binary_idx);
+            if !filter.is_none_or(|names| names.contains(&binary.name)) {
+                continue;
+            }
+            let file_name = dest_path.file_name().unwrap().to_owned();
+            if binary.installed_file_name != file_name {
+                aliases.insert(file_name.clone(), binary.installed_file_name.clone());
+            }
+            file_names.push(file_name);
+        }
+        (file_names, aliases)
+    }
+
+    /// Compute the archives a shell installer for this release should pick between
+    ///
+    /// That's the archive of every non-windows variant, plus a rosetta2 fallback
+    /// if there's an x64 macos build but no arm64 one.
+    fn shell_installer_fragments(
+        &self,
+        to_release: ReleaseIdx,
+    ) -> (Vec<ExecutableZipFragment>, SortedSet<TargetTriple>) {
+        let release = self.release(to_release);
         // If they have an x64 macos build but not an arm64 one, add a fallback entry
         // to try to install x64 on arm64 and let rosetta2 deal with it.
         //
@@ -1213,7 +2900,6 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         }
         let do_rosetta_fallback = has_x64_apple && !has_arm_apple;
 
-        // Gather up the bundles the installer supports
         let mut artifacts = vec![];
         let mut target_triples = SortedSet::new();
         for &variant_idx in &release.variants {
@@ -1227,15 +2913,24 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             // way to add artifacts to the graph and then say "ok but don't build it".
             let (artifact, binaries) =
                 self.make_executable_zip_for_variant(to_release, variant_idx);
+            let (binaries, bin_aliases) =
+                self.installer_binaries(to_release, InstallerStyle::Shell, binaries);
             target_triples.insert(target.clone());
+            let archive = artifact.archive.as_ref().unwrap();
+            // The script always wants /-separated paths
+            let man_pages = archive
+                .man_pages()
+                .iter()
+                .map(|page| page.iter().join("/"))
+                .collect();
             let fragment = ExecutableZipFragment {
+                checksum: self.fragment_checksum(to_release, &artifact.id),
+                zip_style: archive.zip_style,
                 id: artifact.id,
                 target_triples: artifact.target_triples,
-                zip_style: artifact.archive.as_ref().unwrap().zip_style,
-                binaries: binaries
-                    .into_iter()
-                    .map(|(_, dest_path)| dest_path.file_name().unwrap().to_owned())
-                    .collect(),
+                binaries,
+                bin_aliases,
+                man_pages,
             };
             if do_rosetta_fallback && target == X64_MACOS {
                 // Copy the info but respecify it to be arm64 macos
@@ -1245,38 +2940,17 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             }
             artifacts.push(fragment);
         }
-        if artifacts.is_empty() {
-            warn!("skipping shell installer: not building any supported platforms (use --artifacts=global)");
-            return;
-        };
-
-        let installer_artifact = Artifact {
-            id: artifact_name,
-            target_triples: target_triples.into_iter().collect(),
-            archive: None,
-            file_path: artifact_path.clone(),
-            required_binaries: FastMap::new(),
-            checksum: None,
-            kind: ArtifactKind::Installer(InstallerImpl::Shell(InstallerInfo {
-                dest_path: artifact_path,
-                app_name: release.app_name.clone(),
-                app_version: release.version.to_string(),
-                install_path: release.install_path.clone().into_jinja(),
-                base_url: download_url.clone(),
-                artifacts,
-                hint,
-                desc,
-            })),
-            is_global: true,
-        };
-
-        self.add_global_artifact(to_release, installer_artifact);
+        (artifacts, target_triples)
     }
 
     fn add_homebrew_installer(&mut self, to_release: ReleaseIdx) {
         if !self.global_artifacts_enabled() {
             return;
         }
+        if !self.archive_layout_enabled() {
+            info!("skipping homebrew installer: --artifacts=metadata doesn't compute archives");
+            return;
+        }
         let release = self.release(to_release);
         let release_id = &release.id;
         let Some(download_url) = &self.inner.artifact_download_url else {
@@ -1318,8 +2992,14 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         }
         let do_rosetta_fallback = has_x64_apple && !has_arm_apple;
 
+        // Linuxbrew only supports these (glibc) linux platforms
+        const X64_LINUX: &str = "x86_64-unknown-linux-gnu";
+        const ARM64_LINUX: &str = "aarch64-unknown-linux-gnu";
+
         let mut arm64 = None;
         let mut x86_64 = None;
+        let mut arm64_linux = None;
+        let mut x86_64_linux = None;
 
         // Gather up the bundles the installer supports
         let mut artifacts = vec![];
@@ -1327,7 +3007,8 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         for &variant_idx in &release.variants {
             let variant = self.variant(variant_idx);
             let target = &variant.target;
-            if target.contains("windows") || target.contains("linux-gnu") {
+            let linuxbrew_target = target == X64_LINUX || target == ARM64_LINUX;
+            if target.contains("windows") || (target.contains("linux-gnu") && !linuxbrew_target) {
                 continue;
             }
             // Compute the artifact zip this variant *would* make *if* it were built
@@ -1335,15 +3016,17 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             // way to add artifacts to the graph and then say "ok but don't build it".
             let (artifact, binaries) =
                 self.make_executable_zip_for_variant(to_release, variant_idx);
+            let (binaries, bin_aliases) =
+                self.installer_binaries(to_release, InstallerStyle::Homebrew, binaries);
             target_triples.insert(target.clone());
             let fragment = ExecutableZipFragment {
+                checksum: self.fragment_checksum(to_release, &artifact.id),
                 id: artifact.id,
                 target_triples: artifact.target_triples,
                 zip_style: artifact.archive.as_ref().unwrap().zip_style,
-                binaries: binaries
-                    .into_iter()
-                    .map(|(_, dest_path)| dest_path.file_name().unwrap().to_owned())
-                    .collect(),
+                binaries,
+                bin_aliases,
+                man_pages: vec![],
             };
 
             if target == X64_MACOS {
@@ -1352,6 +3035,12 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             if target == ARM64_MACOS {
                 arm64 = Some(fragment.clone());
             }
+            if target == X64_LINUX {
+                x86_64_linux = Some(fragment.clone());
+            }
+            if target == ARM64_LINUX {
+                arm64_linux = Some(fragment.clone());
+            }
 
             if do_rosetta_fallback && target == X64_MACOS {
                 // Copy the info but respecify it to be arm64 macos
@@ -1373,23 +3062,48 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         let app_license = release.app_license.clone();
         let app_homepage_url = release.app_homepage_url.clone();
         let tap = release.tap.clone();
+        let libexec = release.homebrew_libexec.clone();
+        for name in &libexec {
+            if !artifacts
+                .iter()
+                .any(|fragment| fragment.binaries.contains(name))
+            {
+                warn!("homebrew-libexec lists {name}, but {app_name}'s formula has no binary with that name");
+            }
+        }
 
-        if tap.is_some() && !self.inner.publish_jobs.contains(&PublishStyle::Homebrew) {
+        let publishes_homebrew = self.inner.publish_jobs.contains(&PublishStyle::Homebrew)
+            || self.inner.publish_jobs.contains(&PublishStyle::HomebrewPr);
+        if tap.is_some() && !publishes_homebrew {
             warn!("A Homebrew tap was specified but the Homebrew publish job is disabled\n  consider adding \"homebrew\" to publish-jobs in Cargo.toml");
         }
-        if self.inner.publish_jobs.contains(&PublishStyle::Homebrew) && tap.is_none() {
+        if publishes_homebrew && tap.is_none() {
             warn!("The Homebrew publish job is enabled but no tap was specified\n  consider setting the tap field in Cargo.toml");
         }
 
         let formula_name = to_class_case(&app_name);
 
+        // Point the formula at the archives' sha256 checksums now, and read those when we write it
+        let arm64_sha256_artifact = arm64
+            .as_ref()
+            .and_then(|fragment| self.sha256_checksum_artifact(to_release, &fragment.id));
+        let x86_64_sha256_artifact = x86_64
+            .as_ref()
+            .and_then(|fragment| self.sha256_checksum_artifact(to_release, &fragment.id));
+        let arm64_linux_sha256_artifact = arm64_linux
+            .as_ref()
+            .and_then(|fragment| self.sha256_checksum_artifact(to_release, &fragment.id));
+        let x86_64_linux_sha256_artifact = x86_64_linux
+            .as_ref()
+            .and_then(|fragment| self.sha256_checksum_artifact(to_release, &fragment.id));
+
         let dependencies: Vec<String> = release
             .system_dependencies
             .homebrew
             .clone()
             .into_iter()
             .filter(|(_, package)| package.0.stage_wanted(&DependencyKind::Run))
-            .map(|(name, _)| name)
+            .map(|(name, package)| package.0.homebrew_formula(&name))
             .collect();
 
         let installer_artifact = Artifact {
@@ -1399,11 +3113,20 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             file_path: artifact_path.clone(),
             required_binaries: FastMap::new(),
             checksum: None,
+            signature: None,
             kind: ArtifactKind::Installer(InstallerImpl::Homebrew(HomebrewInstallerInfo {
                 arm64,
                 arm64_sha256: None,
+                arm64_sha256_artifact,
                 x86_64,
                 x86_64_sha256: None,
+                x86_64_sha256_artifact,
+                arm64_linux,
+                arm64_linux_sha256: None,
+                arm64_linux_sha256_artifact,
+                x86_64_linux,
+                x86_64_linux_sha256: None,
+                x86_64_linux_sha256_artifact,
                 name: app_name,
                 formula_class: formula_name,
                 desc: app_desc,
@@ -1411,11 +3134,14 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 homepage: app_homepage_url,
                 tap,
                 dependencies,
+                libexec,
                 inner: InstallerInfo {
                     dest_path: artifact_path,
                     app_name: release.app_name.clone(),
                     app_version: release.version.to_string(),
                     install_path: release.install_path.clone().into_jinja(),
+                    offline: false,
+                    embedded_archives: None,
                     base_url: download_url.clone(),
                     artifacts,
                     hint,
@@ -1423,30 +3149,48 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 },
             })),
             is_global: true,
+            provenance: None,
+            inputs: None,
         };
 
-        self.add_global_artifact(to_release, installer_artifact);
+        let why = format!(
+            "homebrew installer requested by release {}'s installers setting",
+            self.release(to_release).id
+        );
+        let installer_idx = self.add_global_artifact(to_release, installer_artifact, why);
+        self.add_artifact_signature(to_release, None, installer_idx);
     }
 
-    fn add_powershell_installer(&mut self, to_release: ReleaseIdx) {
+    fn add_scoop_installer(&mut self, to_release: ReleaseIdx) {
         if !self.global_artifacts_enabled() {
             return;
         }
-
-        // Get the basic info about the installer
+        if !self.archive_layout_enabled() {
+            info!("skipping Scoop manifest: --artifacts=metadata doesn't compute archives");
+            return;
+        }
         let release = self.release(to_release);
         let release_id = &release.id;
         let Some(download_url) = &self.inner.artifact_download_url else {
-            warn!(
-                "skipping powershell installer: couldn't compute a URL to download artifacts from"
-            );
+            warn!("skipping Scoop manifest: couldn't compute a URL to download artifacts from");
             return;
         };
-        let artifact_name = format!("{release_id}-installer.ps1");
+
+        let artifact_name = format!("{release_id}.json");
         let artifact_path = self.inner.dist_dir.join(&artifact_name);
-        let installer_url = format!("{download_url}/{artifact_name}");
-        let hint = format!("irm {installer_url} | iex");
-        let desc = "Install prebuilt binaries via powershell script".to_owned();
+
+        // If bucket is specified, point at that in the `scoop install` message,
+        // otherwise Scoop can install straight from the manifest's URL
+        let hint = if let Some(bucket) = &release.bucket {
+            let bucket_name = bucket.rsplit('/').next().unwrap_or(bucket);
+            format!(
+                "scoop bucket add {bucket_name} https://github.com/{bucket}; scoop install {bucket_name}/{}",
+                release.app_name
+            )
+        } else {
+            format!("scoop install {download_url}/{artifact_name}")
+        };
+        let desc = "Install prebuilt binaries via Scoop".to_owned();
 
         // Gather up the bundles the installer supports
         let mut artifacts = vec![];
@@ -1462,46 +3206,188 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             // way to add artifacts to the graph and then say "ok but don't build it".
             let (artifact, binaries) =
                 self.make_executable_zip_for_variant(to_release, variant_idx);
+            let (binaries, bin_aliases) =
+                self.installer_binaries(to_release, InstallerStyle::Scoop, binaries);
             target_triples.insert(target.clone());
             artifacts.push(ExecutableZipFragment {
+                checksum: self.fragment_checksum(to_release, &artifact.id),
                 id: artifact.id,
                 target_triples: artifact.target_triples,
                 zip_style: artifact.archive.as_ref().unwrap().zip_style,
-                binaries: binaries
-                    .into_iter()
-                    .map(|(_, dest_path)| dest_path.file_name().unwrap().to_owned())
-                    .collect(),
+                binaries,
+                bin_aliases,
+                man_pages: vec![],
             });
         }
-        if artifacts.is_empty() {
-            warn!("skipping powershell installer: not building any supported platforms (use --artifacts=global)");
+        let (architecture, archives) = scoop_architectures(download_url, &artifacts);
+        if architecture.is_empty() {
+            warn!("skipping Scoop manifest: not building any supported platforms (use --artifacts=global)");
             return;
         };
 
+        let release = self.release(to_release);
+        let version = release.version.to_string();
+        let bucket = release.bucket.clone();
+        let publishes_scoop = self.inner.publish_jobs.contains(&PublishStyle::Scoop);
+        if bucket.is_some() && !publishes_scoop {
+            warn!("A Scoop bucket was specified but the Scoop publish job is disabled\n  consider adding \"scoop\" to publish-jobs in Cargo.toml");
+        }
+        if publishes_scoop && bucket.is_none() {
+            warn!("The Scoop publish job is enabled but no bucket was specified\n  consider setting the bucket field in Cargo.toml");
+        }
+
+        let autoupdate = scoop_autoupdate(download_url, &version, &artifacts, release.checksum);
+        // Scoop only knows how to check for new versions of GitHub Releases
+        let checkver = release
+            .app_repository_url
+            .as_ref()
+            .filter(|url| autoupdate.is_some() && url.starts_with("https://github.com/"))
+            .map(|url| ScoopCheckver {
+                github: url.clone(),
+            });
+
         let installer_artifact = Artifact {
             id: artifact_name,
             target_triples: target_triples.into_iter().collect(),
+            archive: None,
             file_path: artifact_path.clone(),
             required_binaries: FastMap::new(),
-            archive: None,
             checksum: None,
-            kind: ArtifactKind::Installer(InstallerImpl::Powershell(InstallerInfo {
-                dest_path: artifact_path,
-                app_name: release.app_name.clone(),
-                app_version: release.version.to_string(),
-                install_path: release.install_path.clone().into_jinja(),
-                base_url: download_url.clone(),
+            signature: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Scoop(ScoopInstallerInfo {
+                manifest: ScoopManifest {
+                    version: version.clone(),
+                    description: release.app_desc.clone(),
+                    homepage: release
+                        .app_homepage_url
+                        .clone()
+                        .or_else(|| release.app_repository_url.clone()),
+                    license: release.app_license.clone(),
+                    architecture,
+                    checkver,
+                    autoupdate,
+                },
+                archives,
+                inner: InstallerInfo {
+                    dest_path: artifact_path,
+                    app_name: release.app_name.clone(),
+                    app_version: version,
+                    install_path: release.install_path.clone().into_jinja(),
+                    offline: false,
+                    embedded_archives: None,
+                    base_url: download_url.clone(),
+                    artifacts,
+                    hint,
+                    desc,
+                },
+            })),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "Scoop installer requested by release {}'s installers setting",
+            self.release(to_release).id
+        );
+        let installer_idx = self.add_global_artifact(to_release, installer_artifact, why);
+        self.add_artifact_signature(to_release, None, installer_idx);
+    }
+    fn add_powershell_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() {
+            return;
+        }
+        if !self.archive_layout_enabled() {
+            info!("skipping powershell installer: --artifacts=metadata doesn't compute archives");
+            return;
+        }
+
+        // Get the basic info about the installer
+        let release = self.release(to_release);
+        let release_id = &release.id;
+        let Some(download_url) = &self.inner.artifact_download_url else {
+            warn!(
+                "skipping powershell installer: couldn't compute a URL to download artifacts from"
+            );
+            return;
+        };
+        let artifact_name = format!("{release_id}-installer.ps1");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let installer_url = format!("{download_url}/{artifact_name}");
+        let hint = format!("irm {installer_url} | iex");
+        let desc = "Install prebuilt binaries via powershell script".to_owned();
+
+        // Gather up the bundles the installer supports
+        let mut artifacts = vec![];
+        let mut target_triples = SortedSet::new();
+        for &variant_idx in &release.variants {
+            let variant = self.variant(variant_idx);
+            let target = &variant.target;
+            if !target.contains("windows") {
+                continue;
+            }
+            // Compute the artifact zip this variant *would* make *if* it were built
+            // FIXME: this is a kind of hacky workaround for the fact that we don't have a good
+            // way to add artifacts to the graph and then say "ok but don't build it".
+            let (artifact, binaries) =
+                self.make_executable_zip_for_variant(to_release, variant_idx);
+            let (binaries, bin_aliases) =
+                self.installer_binaries(to_release, InstallerStyle::Powershell, binaries);
+            target_triples.insert(target.clone());
+            artifacts.push(ExecutableZipFragment {
+                checksum: self.fragment_checksum(to_release, &artifact.id),
+                id: artifact.id,
+                target_triples: artifact.target_triples,
+                zip_style: artifact.archive.as_ref().unwrap().zip_style,
+                binaries,
+                bin_aliases,
+                man_pages: vec![],
+            });
+        }
+        if artifacts.is_empty() {
+            warn!("skipping powershell installer: not building any supported platforms (use --artifacts=global)");
+            return;
+        };
+
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples: target_triples.into_iter().collect(),
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            archive: None,
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Powershell(InstallerInfo {
+                dest_path: artifact_path,
+                app_name: release.app_name.clone(),
+                app_version: release.version.to_string(),
+                install_path: release.install_path.clone().into_jinja(),
+                offline: false,
+                embedded_archives: None,
+                base_url: download_url.clone(),
                 artifacts,
                 hint,
                 desc,
             })),
             is_global: true,
+            provenance: None,
+            inputs: None,
         };
 
-        self.add_global_artifact(to_release, installer_artifact);
+        let why = format!(
+            "powershell installer requested by release {}'s installers setting",
+            self.release(to_release).id
+        );
+        let installer_idx = self.add_global_artifact(to_release, installer_artifact, why);
+        self.add_artifact_signature(to_release, None, installer_idx);
     }
 
     fn add_npm_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.archive_layout_enabled() {
+            info!("skipping npm installer: --artifacts=metadata doesn't compute archives");
+            return;
+        }
+        self.add_npm_archives(to_release);
         if !self.global_artifacts_enabled() {
             return;
         }
@@ -1512,11 +3398,23 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             return;
         };
 
-        if release.bins.len() > 1 {
-            warn!("skipping npm installer: packages with multiple binaries are unsupported\n  let us know if you have a use for this, and what should happen!");
-            return;
+        let npm_filter = release.installer_bin_filter.get(&InstallerStyle::Npm);
+        let mut bins = vec![];
+        let mut bin_aliases = SortedMap::new();
+        for (pkg_idx, bin) in &release.bins {
+            if !npm_filter.is_none_or(|names| names.contains(bin)) {
+                continue;
+            }
+            let package_metadata = self.package_metadata(*pkg_idx);
+            if let Some(alias) = package_metadata
+                .bin_aliases
+                .as_ref()
+                .and_then(|aliases| aliases.get(bin))
+            {
+                bin_aliases.insert(bin.clone(), alias.clone());
+            }
+            bins.push(bin.clone());
         }
-        let bin = release.bins[0].1.clone();
 
         let npm_package_name = if let Some(scope) = &release.npm_scope {
             format!("{scope}/{}", release.app_name)
@@ -1545,184 +3443,1857 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         // Gather up the bundles the installer supports
         let mut artifacts = vec![];
         let mut target_triples = SortedSet::new();
-        let mut has_sketchy_archives = false;
         for &variant_idx in &release.variants {
             let variant = self.variant(variant_idx);
             let target = &variant.target;
             // Compute the artifact zip this variant *would* make *if* it were built
             // FIXME: this is a kind of hacky workaround for the fact that we don't have a good
             // way to add artifacts to the graph and then say "ok but don't build it".
-            let (artifact, binaries) =
-                self.make_executable_zip_for_variant(to_release, variant_idx);
+            let (artifact, binaries, _) =
+                self.make_npm_archive_for_variant(to_release, variant_idx);
+            let (binaries, bin_aliases) =
+                self.installer_binaries(to_release, InstallerStyle::Npm, binaries);
             target_triples.insert(target.clone());
 
-            let variant_zip_style = artifact.archive.as_ref().unwrap().zip_style;
-            if variant_zip_style != ZipStyle::Tar(CompressionImpl::Gzip) {
-                has_sketchy_archives = true;
-            }
-
             artifacts.push(ExecutableZipFragment {
+                checksum: self.fragment_checksum(to_release, &artifact.id),
                 id: artifact.id,
                 target_triples: artifact.target_triples,
-                zip_style: variant_zip_style,
-                binaries: binaries
-                    .into_iter()
-                    .map(|(_, dest_path)| dest_path.file_name().unwrap().to_owned())
-                    .collect(),
+                zip_style: artifact.archive.as_ref().unwrap().zip_style,
+                binaries,
+                bin_aliases,
+                man_pages: vec![],
+            });
+        }
+
+        if artifacts.is_empty() {
+            warn!("skipping npm installer: not building any supported platforms (use --artifacts=global)");
+            return;
+        };
+
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples: target_triples.into_iter().collect(),
+            archive: Some(Archive {
+                // npm specifically expects the dir inside the tarball to be called "package"
+                with_root: Some("package".into()),
+                dir_path: dir_path.clone(),
+                zip_style,
+                static_assets,
+                generated_assets: vec![],
+                man_dir: None,
+                strip_binaries: false,
+            }),
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Npm(NpmInstallerInfo {
+                npm_package_name,
+                npm_package_version,
+                npm_package_desc,
+                npm_package_authors,
+                npm_package_license,
+                npm_package_repository_url,
+                npm_package_homepage_url,
+                npm_package_keywords,
+                package_dir: dir_path,
+                bins,
+                bin_aliases,
+                inner: InstallerInfo {
+                    dest_path: artifact_path,
+                    app_name: release.app_name.clone(),
+                    app_version: release.version.to_string(),
+                    install_path: release.install_path.clone().into_jinja(),
+                    offline: false,
+                    embedded_archives: None,
+                    base_url: download_url.clone(),
+                    artifacts,
+                    hint,
+                    desc,
+                },
+            })),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "npm installer requested by release {}'s installers setting",
+            self.release(to_release).id
+        );
+        let installer_idx = self.add_global_artifact(to_release, installer_artifact, why);
+        self.add_artifact_signature(to_release, None, installer_idx);
+    }
+
+    fn add_winget_manifests(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() {
+            return;
+        }
+        let release = self.release(to_release);
+        let publishes_winget = self.inner.publish_jobs.contains(&PublishStyle::WinGet);
+        let Some(package_identifier) = release.winget_id.clone() else {
+            if publishes_winget {
+                warn!("The WinGet publish job is enabled but {} has no WinGet package identifier\n  consider setting winget-id in Cargo.toml", release.id);
+            }
+            return;
+        };
+        if !publishes_winget {
+            warn!("A WinGet package identifier was specified but the WinGet publish job is disabled\n  consider adding \"winget\" to publish-jobs in Cargo.toml");
+        }
+        let Some(download_url) = &self.inner.artifact_download_url else {
+            warn!("skipping WinGet manifests: couldn't compute a URL to download artifacts from");
+            return;
+        };
+        // WinGet only takes sha256, and it must come from the msi's checksum
+        if release.checksum != ChecksumStyle::Sha256 {
+            warn!("skipping WinGet manifests: they need msi checksums, but checksum = \"{}\" (it should be \"sha256\")", release.checksum.ext());
+            return;
+        }
+
+        // Compute the msis (and checksums) that add_msi_installer *would* make
+        let mut installers = vec![];
+        let mut target_triples = vec![];
+        for &variant_idx in &release.variants {
+            let variant = self.variant(variant_idx);
+            let target = &variant.target;
+            if !target.contains("windows") {
+                continue;
+            }
+            let Some(architecture) = winget_arch(target) else {
+                warn!("skipping {target} in WinGet manifests, as WinGet doesn't support its architecture");
+                continue;
+            };
+            let msi_name = format!("{}.msi", variant.id);
+            let checksum_name = format!("{msi_name}.{}", ChecksumStyle::Sha256.ext());
+            installers.push(WinGetInstaller {
+                architecture: architecture.to_owned(),
+                url: format!("{download_url}/{msi_name}"),
+                checksum_path: self.inner.dist_dir.join(checksum_name),
+                sha256: None,
+            });
+            target_triples.push(target.clone());
+        }
+        if installers.is_empty() {
+            warn!("skipping WinGet manifests: not building any msis for windows");
+            return;
+        }
+
+        let publisher = release
+            .app_authors
+            .first()
+            .map(|author| {
+                // Drop the email from `name <email>`
+                author.split('<').next().unwrap_or(author).trim().to_owned()
+            })
+            .filter(|author| !author.is_empty())
+            .unwrap_or_else(|| {
+                let publisher = package_identifier.split('.').next();
+                publisher.unwrap_or(&package_identifier).to_owned()
             });
+        let info = WinGetInstallerInfo {
+            dest_path: Utf8PathBuf::new(),
+            kind: WinGetManifestKind::Version,
+            package_identifier: package_identifier.clone(),
+            package_version: release.version.to_string(),
+            package_name: release.app_name.clone(),
+            publisher,
+            license: release
+                .app_license
+                .clone()
+                .unwrap_or_else(|| "Proprietary".to_owned()),
+            short_description: release
+                .app_desc
+                .clone()
+                .unwrap_or_else(|| release.app_name.clone()),
+            package_url: release
+                .app_homepage_url
+                .clone()
+                .or_else(|| release.app_repository_url.clone()),
+            installers,
+        };
+
+        for kind in [
+            WinGetManifestKind::Version,
+            WinGetManifestKind::Installer,
+            WinGetManifestKind::Locale,
+        ] {
+            let artifact_name = kind.file_name(&package_identifier);
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: target_triples.clone(),
+                archive: None,
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::WinGet(WinGetInstallerInfo {
+                    dest_path: artifact_path,
+                    kind,
+                    ..info.clone()
+                })),
+                is_global: true,
+                provenance: None,
+                inputs: None,
+            };
+            let why = format!(
+                "WinGet manifests requested by release {}'s winget-id setting",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_global_artifact(to_release, installer_artifact, why);
+            self.add_artifact_signature(to_release, None, installer_idx);
+        }
+    }
+
+    fn add_msi_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.local_artifacts_enabled() {
+            return Ok(());
         }
 
-        if has_sketchy_archives {
-            warn!("the npm installer currently only knows how to unpack .tar.gz archives\n  consider setting windows-archive and unix-archive to .tar.gz in your config");
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+
+        // Make an msi for every windows platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("windows") {
+                continue;
+            }
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.msi");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_name = format!("{variant_id}_msi");
+            let dir_path = self.staging_dir(target, &dir_name);
+
+            // Compute which package we're actually building, based on the binaries
+            let mut package_info: Option<(String, PackageIdx)> = None;
+            for &binary_idx in &binaries {
+                let binary = self.binary(binary_idx);
+                if let Some((existing_spec, _)) = &package_info {
+                    // cargo-wix doesn't clearly support multi-package, so bail
+                    if existing_spec != &binary.pkg_spec {
+                        return Err(DistError::MultiPackageMsi {
+                            artifact_name,
+                            spec1: existing_spec.clone(),
+                            spec2: binary.pkg_spec.clone(),
+                        })?;
+                    }
+                } else {
+                    package_info = Some((binary.pkg_spec.clone(), binary.pkg_idx));
+                }
+            }
+            let Some((pkg_spec, pkg_idx)) = package_info else {
+                return Err(DistError::NoPackageMsi { artifact_name })?;
+            };
+            let manifest_path = self.workspace.package(pkg_idx).manifest_path.clone();
+            let wxs_path = manifest_path
+                .parent()
+                .expect("Cargo.toml had no parent dir!?")
+                .join("wix")
+                .join("main.wxs");
+
+            // Gather up the bundles the installer supports
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Msi(MsiInstallerInfo {
+                    package_dir: dir_path.clone(),
+                    pkg_spec,
+                    target: target.clone(),
+                    profile: self.inner.build_profile.clone(),
+                    file_path: artifact_path.clone(),
+                    wxs_path,
+                    manifest_path,
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "msi installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+
+        Ok(())
+    }
+
+    fn add_nsis_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let app_name = release.app_name.clone();
+        let version = release.version.to_string();
+        // Authors are usually "Name <email>", and the email isn't much of a publisher
+        let publisher = release
+            .app_authors
+            .first()
+            .map(|author| author.split(" <").next().unwrap_or(author).to_owned());
+        let homepage = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+        let program_files = release.nsis_program_files;
+        let modify_path = release.nsis_modify_path;
+
+        // Make an installer for every windows platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("windows") {
+                continue;
+            }
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}-setup.exe");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_nsis"));
+            let install_root = if !program_files {
+                "$LOCALAPPDATA\\Programs"
+            } else if target.starts_with("i686") {
+                "$PROGRAMFILES"
+            } else {
+                "$PROGRAMFILES64"
+            };
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).file_name.clone())
+                .collect();
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Nsis(NsisInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    app_name: app_name.clone(),
+                    version: version.clone(),
+                    publisher: publisher.clone(),
+                    homepage: homepage.clone(),
+                    binaries: binary_names,
+                    install_root: install_root.to_owned(),
+                    program_files,
+                    modify_path,
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "nsis installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_deb_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let package = deb_package_name(&release.app_name);
+        let version = release.version.to_string();
+        let description = release
+            .app_desc
+            .clone()
+            .unwrap_or_else(|| release.app_name.clone());
+        let maintainer = release.app_authors.first().cloned();
+        let copyright = release.app_license.clone().map(|license| DebCopyright {
+            upstream_name: release.app_name.clone(),
+            holders: release.app_authors.clone(),
+            license,
+        });
+        let homepage = release.app_homepage_url.clone();
+        let apt_deps = release.system_dependencies.apt.clone();
+        let release_man_pages = release.man_pages.clone();
+
+        // Make a deb for every gnu linux platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("linux-gnu") {
+                continue;
+            }
+            let Some(architecture) = deb_arch(target) else {
+                warn!("skipping deb for {target}, as its architecture isn't known to Debian");
+                continue;
+            };
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.deb");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_deb"));
+            let control_dir = self.staging_dir(target, &format!("{variant_id}_deb_control"));
+
+            let deps_for = |kind: DependencyKind| {
+                apt_deps
+                    .iter()
+                    .filter(|(_, dep)| dep.0.stage_wanted(&kind) && dep.0.wanted_for_target(target))
+                    .map(|(name, dep)| match &dep.0.version {
+                        Some(version) => format!("{name} (>= {version})"),
+                        None => name.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let depends = deps_for(DependencyKind::Run);
+            let recommends = deps_for(DependencyKind::Recommend);
+            let suggests = deps_for(DependencyKind::Suggest);
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).file_name.clone())
+                .collect();
+
+            // Man pages go where man looks for them
+            let man_pages = release_man_pages
+                .iter()
+                .map(|page| (StaticAssetKind::ManPage, page.clone()))
+                .collect::<Vec<_>>();
+            let generated_man_pages = self.man_page_steps(
+                to_release,
+                variant_idx,
+                &dir_path.join(DEB_BIN_DIR),
+                &dir_path.join(DEB_MAN_DIR),
+            );
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: man_pages,
+                    generated_assets: generated_man_pages,
+                    man_dir: Some(Utf8PathBuf::from(DEB_MAN_DIR)),
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Deb(DebInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    control_dir,
+                    binaries: binary_names,
+                    control: DebControl {
+                        package: package.clone(),
+                        version: version.clone(),
+                        architecture: architecture.to_owned(),
+                        maintainer: maintainer.clone(),
+                        description: description.clone(),
+                        homepage: homepage.clone(),
+                        depends,
+                        recommends,
+                        suggests,
+                    },
+                    copyright: copyright.clone(),
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "deb installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(DEB_BIN_DIR).join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_rpm_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let name = release.app_name.clone();
+        let version = rpm_version(&release.version.to_string());
+        let summary = release
+            .app_desc
+            .clone()
+            .unwrap_or_else(|| release.app_name.clone());
+        let packager = release.app_authors.first().cloned();
+        let license = release.app_license.clone();
+        let url = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+        let dnf_deps = release.system_dependencies.dnf.clone();
+        let release_man_pages = release.man_pages.clone();
+
+        // Make an rpm for every linux platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("linux") {
+                continue;
+            }
+            let Some(arch) = rpm_arch(target) else {
+                warn!("skipping rpm for {target}, as its architecture isn't known to rpm");
+                continue;
+            };
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.rpm");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_rpm"));
+            let build_dir = self.staging_dir(target, &format!("{variant_id}_rpm_build"));
+
+            let deps_for = |kind: DependencyKind| {
+                dnf_deps
+                    .iter()
+                    .filter(|(_, dep)| dep.0.stage_wanted(&kind) && dep.0.wanted_for_target(target))
+                    .map(|(name, dep)| match &dep.0.version {
+                        Some(version) => format!("{name} >= {version}"),
+                        None => name.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let requires = deps_for(DependencyKind::Run);
+            let recommends = deps_for(DependencyKind::Recommend);
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).file_name.clone())
+                .collect();
+
+            // Man pages go where man looks for them
+            let man_pages = release_man_pages
+                .iter()
+                .map(|page| (StaticAssetKind::ManPage, page.clone()))
+                .collect::<Vec<_>>();
+            let generated_man_pages = self.man_page_steps(
+                to_release,
+                variant_idx,
+                &dir_path.join(RPM_BIN_DIR),
+                &dir_path.join(RPM_MAN_DIR),
+            );
+            let archive = Archive {
+                with_root: None,
+                dir_path: dir_path.clone(),
+                zip_style: ZipStyle::TempDir,
+                static_assets: man_pages,
+                generated_assets: generated_man_pages,
+                man_dir: Some(Utf8PathBuf::from(RPM_MAN_DIR)),
+                strip_binaries: false,
+            };
+            let man_pages = archive.man_pages();
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(archive),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Rpm(RpmInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    build_dir,
+                    binaries: binary_names,
+                    man_pages,
+                    spec: RpmSpec {
+                        name: name.clone(),
+                        version: version.clone(),
+                        arch: arch.to_owned(),
+                        summary: summary.clone(),
+                        license: license.clone(),
+                        url: url.clone(),
+                        packager: packager.clone(),
+                        requires,
+                        recommends,
+                    },
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "rpm installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(RPM_BIN_DIR).join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_appimage_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let Some(icon) = release.appimage_icon.clone() else {
+            warn!(
+                "skipping AppImages for {}: AppImages need an icon, please set appimage-icon",
+                release.id
+            );
+            return;
+        };
+        let Some(icon_name) = icon.file_stem().map(|stem| stem.to_owned()) else {
+            warn!(
+                "skipping AppImages for {}: appimage-icon isn't a file",
+                release.id
+            );
+            return;
+        };
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let name = release.app_name.clone();
+        let comment = release.app_desc.clone();
+
+        // Only complain about a missing appimagetool if we're actually going to build
+        let building = matches!(self.artifact_mode, ArtifactMode::Local | ArtifactMode::Host);
+        if building && self.inner.tools.appimagetool.is_none() {
+            warn!("appimage installers are enabled, but appimagetool isn't installed, so building them will fail");
+        }
+
+        // Make an AppImage for every linux platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("linux") {
+                continue;
+            }
+            let Some(arch) = appimage_arch(target) else {
+                warn!("skipping AppImage for {target}, as appimagetool doesn't support its architecture");
+                continue;
+            };
+
+            // Launch the binary named after the app if there is one, otherwise the first
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).name.clone())
+                .collect::<Vec<_>>();
+            let main_binary = binary_names
+                .iter()
+                .find(|bin| **bin == name)
+                .or_else(|| binary_names.first())
+                .cloned()
+                .unwrap_or_else(|| name.clone());
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.AppImage");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}.AppDir"));
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    // The icon goes in the root of the AppDir
+                    static_assets: vec![(StaticAssetKind::Other, icon.clone())],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::AppImage(AppImageInstallerInfo {
+                    file_path: artifact_path,
+                    app_dir: dir_path.clone(),
+                    main_binary: main_binary.clone(),
+                    arch: arch.to_owned(),
+                    desktop: DesktopEntry {
+                        name: name.clone(),
+                        exec: main_binary,
+                        icon: icon_name.clone(),
+                        comment: comment.clone(),
+                    },
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "appimage installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(APPIMAGE_BIN_DIR).join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_flatpak_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let name = release.app_name.clone();
+        let Some(app_id) = release.flatpak_app_id.clone().or_else(|| {
+            release
+                .app_repository_url
+                .as_deref()
+                .and_then(|url| flatpak_app_id(url, &name))
+        }) else {
+            warn!(
+                "skipping Flatpaks for {}: couldn't derive an app-id from the repository, please set flatpak-app-id",
+                release.id
+            );
+            return;
+        };
+        let icon = release.flatpak_icon.clone();
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let comment = release.app_desc.clone();
+
+        // flatpak-builder is a big install, so rather than failing the whole build
+        // when it's missing, just skip the bundles
+        let building = matches!(self.artifact_mode, ArtifactMode::Local | ArtifactMode::Host);
+        if building && self.inner.tools.flatpak_builder.is_none() {
+            warn!("skipping Flatpaks for {}: flatpak installers are enabled, but flatpak-builder isn't installed", self.release(to_release).id);
+            return;
+        }
+
+        // Make a Flatpak for every linux platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("linux") {
+                continue;
+            }
+            let Some(arch) = flatpak_arch(target) else {
+                warn!("skipping Flatpak for {target}, as flatpak doesn't support its architecture");
+                continue;
+            };
+
+            // Launch the binary named after the app if there is one, otherwise the first
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).name.clone())
+                .collect::<Vec<_>>();
+            let main_binary = binary_names
+                .iter()
+                .find(|bin| **bin == name)
+                .or_else(|| binary_names.first())
+                .cloned()
+                .unwrap_or_else(|| name.clone());
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.flatpak");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_flatpak"));
+            let build_dir = self.staging_dir(target, &format!("{variant_id}_flatpak_build"));
+
+            // The icon (and the desktop entry that needs it) are optional for Flatpaks
+            let static_assets = icon
+                .iter()
+                .map(|icon| (StaticAssetKind::Other, icon.clone()))
+                .collect();
+            let icon = icon.as_ref().and_then(|icon| icon.file_name()).map(|icon| {
+                (
+                    icon.to_owned(),
+                    DesktopEntry {
+                        name: name.clone(),
+                        exec: main_binary.clone(),
+                        icon: app_id.clone(),
+                        comment: comment.clone(),
+                    },
+                )
+            });
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets,
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Flatpak(FlatpakInstallerInfo {
+                    file_path: artifact_path,
+                    source_dir: dir_path.clone(),
+                    build_dir,
+                    arch: arch.to_owned(),
+                    app_id: app_id.clone(),
+                    binaries: binary_names,
+                    main_binary,
+                    icon,
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "flatpak installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_snap_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let name = snap_name(&release.app_name);
+        let version = release.version.to_string();
+        let summary = release
+            .app_desc
+            .clone()
+            .unwrap_or_else(|| release.app_name.clone());
+        let license = release.app_license.clone();
+        let confinement = release.snap_confinement;
+        let grade = release.snap_grade;
+
+        // Only complain about a missing snapcraft if we're actually going to build
+        let building = matches!(self.artifact_mode, ArtifactMode::Local | ArtifactMode::Host);
+        if building && self.inner.tools.snapcraft.is_none() {
+            warn!("snap installers are enabled, but snapcraft isn't installed, so building them will fail");
+        }
+
+        // Make a snap for every linux platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("linux") {
+                continue;
+            }
+            let Some(arch) = snap_arch(target) else {
+                warn!("skipping snap for {target}, as its architecture isn't known to snap");
+                continue;
+            };
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.snap");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_snap"));
+
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).file_name.clone())
+                .collect();
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Snap(SnapInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    binaries: binary_names,
+                    spec: SnapcraftSpec {
+                        name: name.clone(),
+                        version: version.clone(),
+                        arch: arch.to_owned(),
+                        summary: summary.clone(),
+                        description: summary.clone(),
+                        license: license.clone(),
+                        confinement,
+                        grade,
+                    },
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "snap installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(SNAP_BIN_DIR).join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_mac_pkg_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let name = release.app_name.clone();
+        let Some(identifier) = release.mac_pkg_identifier.clone().or_else(|| {
+            release
+                .app_repository_url
+                .as_deref()
+                .and_then(|url| flatpak_app_id(url, &name))
+        }) else {
+            warn!(
+                "skipping macOS pkgs for {}: couldn't derive an identifier from the repository, please set mac-pkg-identifier",
+                release.id
+            );
+            return;
+        };
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let version = release.version.to_string();
+        let install_location = release.mac_pkg_install_location.clone();
+        let sign_identity = release.mac_pkg_sign_identity.clone();
+
+        // Make a pkg for every apple platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("apple-darwin") {
+                continue;
+            }
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.pkg");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_pkg"));
+            let build_dir = self.staging_dir(target, &format!("{variant_id}_pkg_build"));
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::MacPkg(MacPkgInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    build_dir,
+                    identifier: identifier.clone(),
+                    version: version.clone(),
+                    install_location: install_location.clone(),
+                    sign_identity: sign_identity.clone(),
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "mac-pkg installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_dmg_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let volume_name = format!("{} {}", release.app_name, release.version);
+
+        // Make a dmg for every apple platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("apple-darwin") {
+                continue;
+            }
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.dmg");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_dmg"));
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    // Same README/LICENSE/etc as the archives
+                    static_assets: variant.static_assets.clone(),
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Dmg(DmgInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    volume_name: volume_name.clone(),
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "dmg installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_app_bundle_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let name = release.app_name.clone();
+        let Some(identifier) = release.app_bundle_identifier.clone().or_else(|| {
+            release
+                .app_repository_url
+                .as_deref()
+                .and_then(|url| flatpak_app_id(url, &name))
+        }) else {
+            warn!(
+                "skipping macOS app bundles for {}: couldn't derive an identifier from the repository, please set app-bundle-identifier",
+                release.id
+            );
+            return;
+        };
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let version = release.version.to_string();
+        let icon = release.app_bundle_icon.clone();
+
+        // Make an app bundle for every apple platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.contains("apple-darwin") {
+                continue;
+            }
+            // The first binary is the one that runs when the app is opened
+            let Some(&main_binary) = binaries.first() else {
+                continue;
+            };
+            let executable = self.binary(main_binary).file_name.clone();
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.app.zip");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let bundle_name = format!("{name}.app");
+            let bundle_dir = self
+                .staging_dir(target, &format!("{variant_id}_app"))
+                .join(&bundle_name);
+
+            let info = AppBundleInstallerInfo {
+                bundle_dir: bundle_dir.clone(),
+                app_name: name.clone(),
+                version: version.clone(),
+                identifier: identifier.clone(),
+                executable,
+                icon: icon.clone(),
+            };
+            let macos_dir = info.macos_dir();
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path,
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    // Unzipping should produce the Foo.app dir itself
+                    with_root: Some(bundle_name.into()),
+                    dir_path: bundle_dir,
+                    zip_style: ZipStyle::Zip,
+                    static_assets: vec![],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::AppBundle(info)),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "app bundle requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    macos_dir.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+
+    fn add_freebsd_pkg_installer(&mut self, to_release: ReleaseIdx) {
+        if !self.local_artifacts_enabled() {
+            return;
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let variants = release.variants.clone();
+        let checksum = release.checksum;
+        let name = deb_package_name(&release.app_name);
+        let version = freebsd_pkg_version(&release.version.to_string());
+        let desc = release
+            .app_desc
+            .clone()
+            .unwrap_or_else(|| release.app_name.clone());
+        let comment = desc.trim().lines().next().unwrap_or_default().to_owned();
+        let maintainer = release
+            .app_authors
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_owned());
+        let (licenselogic, licenses) = release
+            .app_license
+            .as_deref()
+            .map(freebsd_pkg_licenses)
+            .unwrap_or(("single", vec![]));
+        let www = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+        let pkg_deps = release.system_dependencies.pkg.clone();
+
+        // We can only package up binaries we actually build
+        let is_freebsd = |target: &str| target.ends_with("-unknown-freebsd");
+        if !variants
+            .iter()
+            .any(|&variant_idx| is_freebsd(&self.variant(variant_idx).target))
+        {
+            warn!(
+                "skipping FreeBSD pkgs for {}: none of its targets are *-unknown-freebsd",
+                release.id
+            );
+            return;
+        }
+
+        // Make a pkg for every freebsd platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !is_freebsd(target) {
+                continue;
+            }
+            let Some(arch) = freebsd_pkg_arch(target) else {
+                warn!("skipping FreeBSD pkg for {target}, as its architecture isn't known to pkg");
+                continue;
+            };
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.pkg");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.staging_dir(target, &format!("{variant_id}_pkg"));
+
+            // Dependencies are keyed by their origin ("category/name") where known
+            let deps = pkg_deps
+                .iter()
+                .filter(|(_, dep)| {
+                    dep.0.stage_wanted(&DependencyKind::Run) && dep.0.wanted_for_target(target)
+                })
+                .map(|(origin, dep)| {
+                    let dep_name = origin.rsplit('/').next().unwrap_or(origin).to_owned();
+                    let dep = FreebsdPkgDep {
+                        origin: origin.clone(),
+                        version: dep.0.version.clone(),
+                    };
+                    (dep_name, dep)
+                })
+                .collect();
+            let binary_names = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).file_name.clone())
+                .collect();
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                    generated_assets: vec![],
+                    man_dir: None,
+                    strip_binaries: false,
+                }),
+                checksum: None,
+                signature: None,
+                kind: ArtifactKind::Installer(InstallerImpl::FreebsdPkg(FreebsdPkgInstallerInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    binaries: binary_names,
+                    manifest: FreebsdPkgManifest {
+                        name: name.clone(),
+                        version: version.clone(),
+                        origin: format!("misc/{name}"),
+                        comment: comment.clone(),
+                        desc: desc.clone(),
+                        maintainer: maintainer.clone(),
+                        www: www.clone(),
+                        // Binaries built for one FreeBSD release run on later ones
+                        abi: format!("FreeBSD:*:{arch}"),
+                        prefix: FREEBSD_PKG_PREFIX.to_owned(),
+                        licenselogic: licenselogic.to_owned(),
+                        licenses: licenses.clone(),
+                        categories: vec!["misc".to_owned()],
+                        deps,
+                    },
+                })),
+                is_global: false,
+                provenance: None,
+                inputs: None,
+            };
+
+            // Register the artifact to various things
+            let why = format!(
+                "FreeBSD pkg installer requested by release {}'s installers setting for {target}",
+                self.release(to_release).id
+            );
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact, why);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(FREEBSD_PKG_BIN_DIR).join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(to_release, Some(variant_idx), installer_idx, checksum);
+            }
+            self.add_artifact_signature(to_release, Some(variant_idx), installer_idx);
+        }
+    }
+    fn compute_binstall(&mut self, to_release: ReleaseIdx, pkg_idx: PackageIdx, tag: &str) {
+        if !self
+            .package_metadata(pkg_idx)
+            .binstall_metadata
+            .unwrap_or(false)
+        {
+            return;
+        }
+        let Some(download_url) = &self.inner.artifact_download_url else {
+            warn!("skipping binstall metadata: couldn't compute a URL to download artifacts from");
+            return;
+        };
+        let release = self.release(to_release);
+        let Some(releases_url) = download_url.strip_suffix(tag) else {
+            return;
+        };
+        // Turn the announcement tag back into a template binstall can fill in
+        let version = release.version.to_string();
+        let tag_template = match tag.strip_prefix(&format!("{}-", release.app_name)) {
+            Some(tag) => format!("{{ name }}-{}", tag.replace(&version, "{ version }")),
+            None => tag.replace(&version, "{ version }"),
+        };
+        // This mirrors the naming in make_archive_for_variant
+        let mut dir_name = "{ name }-{ target }".to_owned();
+        if release.slim_archives {
+            dir_name.push_str("-slim");
+        }
+        let archive_prefix = format!("{releases_url}{tag_template}/{dir_name}");
+        let Some(format) = BinstallFormat::new(&archive_prefix, &dir_name, release.unix_archive)
+        else {
+            warn!("skipping binstall metadata: cargo-binstall can't unpack our unix archives");
+            return;
+        };
+        let mut overrides = SortedMap::new();
+        if release.windows_archive != release.unix_archive {
+            for target in release.targets.iter().filter(|t| t.contains("windows")) {
+                if let Some(format) =
+                    BinstallFormat::new(&archive_prefix, &dir_name, release.windows_archive)
+                {
+                    overrides.insert(target.clone(), format);
+                }
+            }
+        }
+        let manifest_path = self.workspace.package(pkg_idx).manifest_path.clone();
+        info!("computed binstall metadata for {}", release.id);
+
+        self.release_mut(to_release).binstall = Some(BinstallInfo {
+            manifest_path,
+            format,
+            overrides,
+        });
+    }
+
+    fn add_citation(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() {
+            return;
+        }
+        let release = self.release(to_release);
+        if !release.citation {
+            return;
+        }
+        let release_id = &release.id;
+        let artifact_name = format!("{release_id}-citation.cff");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        info!("adding citation metadata to release {release_id}");
+
+        let authors = release
+            .app_authors
+            .iter()
+            .map(|author| CitationAuthor::from_cargo_author(author))
+            .collect();
+
+        let citation_artifact = Artifact {
+            id: artifact_name,
+            target_triples: vec![],
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::Citation(CitationInfo {
+                dest_path: artifact_path,
+                app_name: release.app_name.clone(),
+                app_version: release.version.to_string(),
+                app_desc: release.app_desc.clone(),
+                authors,
+                licenses: cff_licenses(release.app_license.as_deref()),
+                app_repository_url: release.app_repository_url.clone(),
+                app_homepage_url: release.app_homepage_url.clone(),
+            }),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "citation metadata requested by release {}'s citation setting",
+            self.release(to_release).id
+        );
+        let citation_idx = self.add_global_artifact(to_release, citation_artifact, why);
+        self.add_artifact_signature(to_release, None, citation_idx);
+    }
+
+    fn add_delta_script(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() || self.inner.delta_from.is_none() {
+            return;
+        }
+        let release = self.release(to_release);
+        let artifact_name = format!("{}-apply-delta.sh", release.id);
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        info!("adding delta script to release {}", release.id);
+
+        let script_artifact = Artifact {
+            id: artifact_name,
+            target_triples: vec![],
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::DeltaScript(DeltaScriptImpl {
+                format: self.inner.delta_format,
+                app_name: release.app_name.clone(),
+                dest_path: artifact_path,
+            }),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = "delta-from is set, so deltas need a way to be applied".to_owned();
+        let script_idx = self.add_global_artifact(to_release, script_artifact, why);
+        self.add_artifact_signature(to_release, None, script_idx);
+    }
+
+    fn add_offline_bundle(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() || !self.archive_layout_enabled() {
+            return;
+        }
+        let release = self.release(to_release);
+        if !release.offline_bundle {
+            return;
+        }
+        let release_id = &release.id;
+        let bundle_name = format!("{release_id}-offline");
+        let zip_style = ZipStyle::Tar(CompressionImpl::Gzip);
+        let artifact_name = format!("{bundle_name}{}", zip_style.ext());
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let bundle_dir = self.inner.dist_dir.join(&bundle_name);
+        let hint = format!("tar xzf {artifact_name} && sh {bundle_name}/install.sh");
+        let desc = "Install prebuilt binaries without network access".to_owned();
+
+        // The installer picks between the same archives the shell installer would,
+        // but the bundle carries every variant's archive, windows included
+        let (artifacts, target_triples) = self.shell_installer_fragments(to_release);
+        let mut static_assets = vec![];
+        for &variant_idx in &release.variants {
+            let (artifact, _) = self.make_executable_zip_for_variant(to_release, variant_idx);
+            static_assets.push((StaticAssetKind::Other, artifact.file_path));
+        }
+        if static_assets.is_empty() {
+            warn!("skipping offline bundle: not building any platforms");
+            return;
+        }
+        info!("adding offline bundle to release {release_id}");
+
+        let bundle_artifact = Artifact {
+            id: artifact_name,
+            target_triples: target_triples.into_iter().collect(),
+            archive: Some(Archive {
+                with_root: Some(Utf8PathBuf::from(&bundle_name)),
+                dir_path: bundle_dir.clone(),
+                zip_style,
+                static_assets,
+                generated_assets: vec![],
+                man_dir: None,
+                strip_binaries: false,
+            }),
+            file_path: artifact_path,
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::Installer(InstallerImpl::OfflineBundle(InstallerInfo {
+                dest_path: bundle_dir.join("install.sh"),
+                app_name: release.app_name.clone(),
+                app_version: release.version.to_string(),
+                install_path: release.install_path.clone().into_jinja(),
+                offline: true,
+                embedded_archives: None,
+                base_url: String::new(),
+                artifacts,
+                hint,
+                desc,
+            })),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "offline bundle requested by release {}'s offline-bundle setting",
+            self.release(to_release).id
+        );
+        let bundle_idx = self.add_global_artifact(to_release, bundle_artifact, why);
+        self.add_artifact_signature(to_release, None, bundle_idx);
+    }
+
+    fn add_unified_checksum(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() || !self.archive_layout_enabled() {
+            return;
+        }
+        let release = self.release(to_release);
+        let checksum = release.checksum;
+        if !release.unified_checksum || checksum == ChecksumStyle::False {
+            return;
+        }
+        let release_id = &release.id;
+        let artifact_name = format!("{release_id}-{}", checksum.unified_file_name());
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        info!("adding unified checksum to release {release_id}");
+
+        let checksum_artifact = Artifact {
+            id: artifact_name,
+            target_triples: vec![],
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::UnifiedChecksum(UnifiedChecksumImpl {
+                checksum,
+                format: UnifiedChecksumFormat::Sums,
+                release: to_release,
+                dest_path: artifact_path,
+            }),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "unified checksum requested by release {}'s unified-checksum setting",
+            self.release(to_release).id
+        );
+        let checksum_idx = self.add_global_artifact(to_release, checksum_artifact, why);
+        self.add_artifact_signature(to_release, None, checksum_idx);
+    }
+
+    fn add_checksums_json(&mut self, to_release: ReleaseIdx) {
+        if !self.global_artifacts_enabled() || !self.archive_layout_enabled() {
+            return;
+        }
+        let release = self.release(to_release);
+        let checksum = release.checksum;
+        if !release.checksums_json || checksum == ChecksumStyle::False {
+            return;
+        }
+        let release_id = &release.id;
+        let artifact_name = format!("{release_id}-{CHECKSUMS_JSON_FILE_NAME}");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        info!("adding checksums.json to release {release_id}");
+
+        let checksum_artifact = Artifact {
+            id: artifact_name,
+            target_triples: vec![],
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::UnifiedChecksum(UnifiedChecksumImpl {
+                checksum,
+                format: UnifiedChecksumFormat::Json,
+                release: to_release,
+                dest_path: artifact_path,
+            }),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
+
+        let why = format!(
+            "checksums.json requested by release {}'s checksums-json setting",
+            self.release(to_release).id
+        );
+        let checksum_idx = self.add_global_artifact(to_release, checksum_artifact, why);
+        self.add_artifact_signature(to_release, None, checksum_idx);
+    }
+
+    fn add_release_notes(&mut self) {
+        if !self.global_artifacts_enabled() || !self.inner.release_notes_asset {
+            return;
         }
-        if artifacts.is_empty() {
-            warn!("skipping npm installer: not building any supported platforms (use --artifacts=global)");
+        if !self.inner.ci_style.contains(&CiStyle::Github) {
+            info!("not publishing to Github, skipping RELEASE_NOTES.md");
             return;
-        };
+        }
+        // There's only one set of release notes for the whole announcement,
+        // so just hang it off the first release
+        if self.inner.releases.is_empty() {
+            return;
+        }
+        let artifact_name = RELEASE_NOTES_FILE_NAME.to_owned();
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        info!("adding release notes to announcement");
 
-        let installer_artifact = Artifact {
+        let notes_artifact = Artifact {
             id: artifact_name,
-            target_triples: target_triples.into_iter().collect(),
-            archive: Some(Archive {
-                // npm specifically expects the dir inside the tarball to be called "package"
-                with_root: Some("package".into()),
-                dir_path: dir_path.clone(),
-                zip_style,
-                static_assets,
-            }),
+            target_triples: vec![],
+            archive: None,
             file_path: artifact_path.clone(),
             required_binaries: FastMap::new(),
             checksum: None,
-            kind: ArtifactKind::Installer(InstallerImpl::Npm(NpmInstallerInfo {
-                npm_package_name,
-                npm_package_version,
-                npm_package_desc,
-                npm_package_authors,
-                npm_package_license,
-                npm_package_repository_url,
-                npm_package_homepage_url,
-                npm_package_keywords,
-                package_dir: dir_path,
-                bin,
-                inner: InstallerInfo {
-                    dest_path: artifact_path,
-                    app_name: release.app_name.clone(),
-                    app_version: release.version.to_string(),
-                    install_path: release.install_path.clone().into_jinja(),
-                    base_url: download_url.clone(),
-                    artifacts,
-                    hint,
-                    desc,
-                },
-            })),
+            signature: None,
+            kind: ArtifactKind::ReleaseNotes(ReleaseNotesImpl {
+                dest_path: artifact_path,
+            }),
             is_global: true,
+            provenance: None,
+            inputs: None,
         };
 
-        self.add_global_artifact(to_release, installer_artifact);
+        let why = "release notes requested by the release-notes-asset setting".to_owned();
+        let notes_idx = self.add_global_artifact(ReleaseIdx(0), notes_artifact, why);
+        self.add_artifact_signature(ReleaseIdx(0), None, notes_idx);
     }
 
-    fn add_msi_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
-        if !self.local_artifacts_enabled() {
-            return Ok(());
+    fn add_shell_suite_installer(&mut self) {
+        if !self.global_artifacts_enabled() || !self.inner.shell_suite_installer {
+            return;
         }
-
-        // Clone info we need from the release to avoid borrowing across the loop
-        let release = self.release(to_release);
-        let variants = release.variants.clone();
-        let checksum = release.checksum;
-
-        // Make an msi for every windows platform
-        for variant_idx in variants {
-            let variant = self.variant(variant_idx);
-            let binaries = variant.binaries.clone();
-            let target = &variant.target;
-            if !target.contains("windows") {
-                continue;
-            }
-
-            let variant_id = &variant.id;
-            let artifact_name = format!("{variant_id}.msi");
-            let artifact_path = self.inner.dist_dir.join(&artifact_name);
-            let dir_name = format!("{variant_id}_msi");
-            let dir_path = self.inner.dist_dir.join(&dir_name);
-
-            // Compute which package we're actually building, based on the binaries
-            let mut package_info: Option<(String, PackageIdx)> = None;
-            for &binary_idx in &binaries {
-                let binary = self.binary(binary_idx);
-                if let Some((existing_spec, _)) = &package_info {
-                    // cargo-wix doesn't clearly support multi-package, so bail
-                    if existing_spec != &binary.pkg_spec {
-                        return Err(DistError::MultiPackageMsi {
-                            artifact_name,
-                            spec1: existing_spec.clone(),
-                            spec2: binary.pkg_spec.clone(),
-                        })?;
-                    }
-                } else {
-                    package_info = Some((binary.pkg_spec.clone(), binary.pkg_idx));
+        let Some(download_url) = &self.inner.artifact_download_url else {
+            warn!("skipping suite installer: couldn't compute a URL to download artifacts from");
+            return;
+        };
+        // Dispatch to the shell installer of every release we're announcing
+        let mut apps = vec![];
+        let mut target_triples = SortedSet::new();
+        for release in &self.inner.releases {
+            for &artifact_idx in &release.global_artifacts {
+                let artifact = self.artifact(artifact_idx);
+                if let ArtifactKind::Installer(InstallerImpl::Shell(info)) = &artifact.kind {
+                    target_triples.extend(artifact.target_triples.iter().cloned());
+                    apps.push(info.clone());
                 }
             }
-            let Some((pkg_spec, pkg_idx)) = package_info else {
-                return Err(DistError::NoPackageMsi { artifact_name })?;
-            };
-            let manifest_path = self.workspace.package(pkg_idx).manifest_path.clone();
-            let wxs_path = manifest_path
-                .parent()
-                .expect("Cargo.toml had no parent dir!?")
-                .join("wix")
-                .join("main.wxs");
+        }
+        if apps.len() < 2 {
+            info!("skipping suite installer: fewer than 2 apps have shell installers");
+            return;
+        }
+        let artifact_name = SHELL_SUITE_INSTALLER_FILE_NAME.to_owned();
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let installer_url = format!("{download_url}/{artifact_name}");
+        let hint = format!("curl --proto '=https' --tlsv1.2 -LsSf {installer_url} | sh");
+        let desc = "Install any of these apps via shell script".to_owned();
+        info!("adding suite installer to announcement");
 
-            // Gather up the bundles the installer supports
-            let installer_artifact = Artifact {
-                id: artifact_name,
-                target_triples: vec![target.clone()],
-                file_path: artifact_path.clone(),
-                required_binaries: FastMap::new(),
-                archive: Some(Archive {
-                    with_root: None,
-                    dir_path: dir_path.clone(),
-                    zip_style: ZipStyle::TempDir,
-                    static_assets: vec![],
-                }),
-                checksum: None,
-                kind: ArtifactKind::Installer(InstallerImpl::Msi(MsiInstallerInfo {
-                    package_dir: dir_path.clone(),
-                    pkg_spec,
-                    target: target.clone(),
-                    file_path: artifact_path.clone(),
-                    wxs_path,
-                    manifest_path,
-                })),
-                is_global: false,
-            };
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples: target_triples.into_iter().collect(),
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            signature: None,
+            kind: ArtifactKind::Installer(InstallerImpl::ShellSuite(ShellSuiteInstallerInfo {
+                dest_path: artifact_path,
+                desc,
+                hint,
+                apps,
+            })),
+            is_global: true,
+            provenance: None,
+            inputs: None,
+        };
 
-            // Register the artifact to various things
-            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
-            for binary_idx in binaries {
-                let binary = self.binary(binary_idx);
-                self.require_binary(
-                    installer_idx,
-                    variant_idx,
-                    binary_idx,
-                    dir_path.join(&binary.file_name),
-                );
-            }
-            if checksum != ChecksumStyle::False {
-                self.add_artifact_checksum(variant_idx, installer_idx, checksum);
-            }
+        // There's only one suite installer for the whole announcement,
+        // so just hang it off the first release
+        let why = "suite installer requested by the shell-suite-installer setting".to_owned();
+        let installer_idx = self.add_global_artifact(ReleaseIdx(0), installer_artifact, why);
+        let checksum = self.release(ReleaseIdx(0)).checksum;
+        if checksum != ChecksumStyle::False {
+            self.add_artifact_checksum(ReleaseIdx(0), None, installer_idx, checksum);
         }
-
-        Ok(())
+        self.add_artifact_signature(ReleaseIdx(0), None, installer_idx);
     }
 
     fn add_local_artifact(
         &mut self,
         to_variant: ReleaseVariantIdx,
-        artifact: Artifact,
+        mut artifact: Artifact,
+        why: String,
     ) -> ArtifactIdx {
         assert!(self.local_artifacts_enabled());
         assert!(!artifact.is_global);
+        artifact.provenance = Some(self.provenance(why, false));
 
         let idx = ArtifactIdx(self.inner.artifacts.len());
         let ReleaseVariant {
@@ -1734,9 +5305,15 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         idx
     }
 
-    fn add_global_artifact(&mut self, to_release: ReleaseIdx, artifact: Artifact) -> ArtifactIdx {
+    fn add_global_artifact(
+        &mut self,
+        to_release: ReleaseIdx,
+        mut artifact: Artifact,
+        why: String,
+    ) -> ArtifactIdx {
         assert!(self.global_artifacts_enabled());
         assert!(artifact.is_global);
+        artifact.provenance = Some(self.provenance(why, true));
 
         let idx = ArtifactIdx(self.inner.artifacts.len());
         let Release {
@@ -1748,9 +5325,107 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         idx
     }
 
-    fn compute_build_steps(&mut self) {
-        // FIXME: more intelligently schedule these in a proper graph?
+    /// Explain why an artifact (added for the given reason) survived the ArtifactMode
+    fn provenance(&self, why: String, is_global: bool) -> String {
+        let kept_because = match (self.artifact_mode, is_global) {
+            (ArtifactMode::Local, _) => "--artifacts=local includes target-specific artifacts",
+            (ArtifactMode::Global, _) => "--artifacts=global includes globally unique artifacts",
+            (ArtifactMode::Host, false) => {
+                "--artifacts=host includes target-specific artifacts for the host"
+            }
+            (ArtifactMode::Host, true) => "--artifacts=host includes globally unique artifacts",
+            (ArtifactMode::All, _) => "--artifacts=all includes everything",
+            (ArtifactMode::Metadata, _) => {
+                "--artifacts=metadata includes global artifacts that don't need archives"
+            }
+        };
+        format!("{why} (kept because {kept_because})")
+    }
+
+    /// Work out which files each artifact is derived from (for incremental builds)
+    ///
+    /// Archives and symbols only depend on the sources of the packages their binaries come
+    /// from (and of any local path dependencies of those), the static assets they include,
+    /// and the workspace's manifest and lockfile (which have the dist config and dependency
+    /// versions). Checksums and signatures depend on
+    /// whatever the file they're for depends on. Everything else depends on too much to
+    /// track, and is cheap to make anyway, so it's always rebuilt.
+    fn compute_artifact_inputs(&mut self) {
+        let workspace_inputs = [
+            self.workspace.manifest_path.clone(),
+            self.inner.workspace_dir.join("Cargo.lock"),
+        ];
+        let package_roots = |binary: &Binary| {
+            let package = self.workspace.package(binary.pkg_idx);
+            let mut roots = vec![package.package_root.clone()];
+            roots.extend(crate::incremental::local_dependency_roots(
+                &self.workspace.manifest_path,
+                &package.manifest_path,
+            ));
+            roots
+        };
+
+        let mut all_inputs = vec![];
+        for (artifact_idx, artifact) in self.inner.artifacts.iter().enumerate() {
+            let inputs = match &artifact.kind {
+                ArtifactKind::ExecutableZip(_) => {
+                    let mut inputs = workspace_inputs.to_vec();
+                    inputs.extend(
+                        artifact
+                            .required_binaries
+                            .keys()
+                            .flat_map(|&idx| package_roots(self.binary(idx))),
+                    );
+                    match &artifact.archive {
+                        // We can't know what the commands read, so always regenerate
+                        Some(archive) if !archive.generated_assets.is_empty() => None,
+                        Some(archive) => {
+                            inputs.extend(archive.static_assets.iter().map(|(_, src)| src.clone()));
+                            Some(inputs)
+                        }
+                        None => Some(inputs),
+                    }
+                }
+                ArtifactKind::Symbols(_) => {
+                    let mut inputs = workspace_inputs.to_vec();
+                    inputs.extend(
+                        self.inner
+                            .binaries
+                            .iter()
+                            .filter(|b| b.symbols_artifact == Some(ArtifactIdx(artifact_idx)))
+                            .flat_map(package_roots),
+                    );
+                    Some(inputs)
+                }
+                _ => None,
+            };
+            all_inputs.push(inputs);
+        }
+        // Checksums and signatures are as fresh as the thing they're for
+        for (artifact_idx, artifact) in self.inner.artifacts.iter().enumerate() {
+            for derived in artifact.checksum.iter().chain(&artifact.signature) {
+                all_inputs[derived.0] = all_inputs[artifact_idx].clone();
+            }
+        }
+
+        for (artifact, mut inputs) in self.inner.artifacts.iter_mut().zip(all_inputs) {
+            if let Some(inputs) = &mut inputs {
+                inputs.sort();
+                inputs.dedup();
+            }
+            artifact.inputs = inputs;
+        }
+    }
+
+    /// Finish the graph, computing the build steps for everything that was added
+    pub fn build(mut self) -> DistGraph {
+        self.compute_artifact_inputs();
+        self.compute_build_steps();
+        self.compute_ci();
+        self.inner
+    }
 
+    fn compute_build_steps(&mut self) {
         let mut build_steps = vec![];
         let cargo_builds = self.compute_cargo_builds();
         build_steps.extend(cargo_builds);
@@ -1764,19 +5439,81 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 .collect(),
             &mut build_steps,
         );
+        // The unified checksums are signed once they're computed, below
+        let unified_signatures = self
+            .inner
+            .artifacts
+            .iter()
+            .filter(|a| matches!(a.kind, ArtifactKind::UnifiedChecksum(_)))
+            .filter_map(|a| a.signature)
+            .collect::<Vec<_>>();
         Self::add_build_steps_for_artifacts(
             &self
                 .inner
                 .artifacts
                 .iter()
-                .filter(|a| a.is_global)
+                .enumerate()
+                .filter(|(idx, a)| a.is_global && !unified_signatures.contains(&ArtifactIdx(*idx)))
+                .map(|(_, a)| a)
                 .collect(),
             &mut build_steps,
         );
+        // These need every other artifact to exist, so they go last
+        let unified_checksums = self.compute_unified_checksum_steps();
+        build_steps.extend(unified_checksums);
 
+        self.inner.build_plan = BuildPlan::new(&self.inner, &build_steps);
         self.inner.build_steps = build_steps;
     }
 
+    fn compute_unified_checksum_steps(&self) -> Vec<BuildStep> {
+        let mut steps = vec![];
+        for artifact in &self.inner.artifacts {
+            let ArtifactKind::UnifiedChecksum(unified) = &artifact.kind else {
+                continue;
+            };
+            let release = self.release(unified.release);
+
+            // Every artifact of the release, except for other checksums (and signatures)
+            let mut src_paths = vec![];
+            let artifact_idxs = release.global_artifacts.iter().chain(
+                release
+                    .all_variants()
+                    .flat_map(|idx| &self.variant(idx).local_artifacts),
+            );
+            for &artifact_idx in artifact_idxs {
+                let artifact = self.artifact(artifact_idx);
+                if !matches!(
+                    artifact.kind,
+                    ArtifactKind::Checksum(_)
+                        | ArtifactKind::UnifiedChecksum(_)
+                        | ArtifactKind::Signature(_)
+                ) {
+                    src_paths.push(artifact.file_path.clone());
+                }
+            }
+            // If we're only building global artifacts, the local ones were built on
+            // other machines, but we still know what they made
+            if let Some(remote) = self.remote_artifacts.get(unified.release.0) {
+                src_paths.extend(remote.iter().cloned());
+            }
+            src_paths.sort();
+
+            steps.push(BuildStep::UnifiedChecksum(UnifiedChecksumStep {
+                checksum: unified.checksum,
+                format: unified.format,
+                src_paths,
+                dest_path: unified.dest_path.clone(),
+            }));
+            if let Some(signature_idx) = artifact.signature {
+                if let ArtifactKind::Signature(signature) = &self.artifact(signature_idx).kind {
+                    steps.push(BuildStep::Sign(signature.clone()));
+                }
+            }
+        }
+        steps
+    }
+
     fn add_build_steps_for_artifacts(artifacts: &Vec<&Artifact>, build_steps: &mut Vec<BuildStep>) {
         for artifact in artifacts {
             match &artifact.kind {
@@ -1789,29 +5526,44 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                             // No additional steps needed, the file is PERFECT (for now)
                         }
                         SymbolKind::Dsym => {
-                            // FIXME: compress the dSYM in a .tar.xz, it's a actually a directory!
+                            // The dSYM is a directory, artifact.archive tarballs it up below
                         }
                         SymbolKind::Dwp => {
                             // No additional steps needed?
                         }
                     }
                 }
-                ArtifactKind::Installer(installer) => {
-                    // Installer generation is complex enough that they just get monolithic impls
-                    build_steps.push(BuildStep::GenerateInstaller(installer.clone()));
+                ArtifactKind::Installer(_) => {
+                    // Handled below, as some installers need the static assets in place
                 }
                 ArtifactKind::Checksum(checksum) => {
                     build_steps.push(BuildStep::Checksum(checksum.clone()));
                 }
+                ArtifactKind::Signature(signature) => {
+                    build_steps.push(BuildStep::Sign(signature.clone()));
+                }
+                ArtifactKind::Citation(citation) => {
+                    build_steps.push(BuildStep::GenerateCitation(citation.clone()));
+                }
+                ArtifactKind::ReleaseNotes(notes) => {
+                    build_steps.push(BuildStep::GenerateReleaseNotes(notes.clone()));
+                }
+                ArtifactKind::Delta(delta) => {
+                    build_steps.push(BuildStep::Delta(delta.clone()));
+                }
+                ArtifactKind::DeltaScript(script) => {
+                    build_steps.push(BuildStep::GenerateDeltaScript(script.clone()));
+                }
+                ArtifactKind::UnifiedChecksum(_) => {
+                    // compute_unified_checksum_steps handles these once everything else is built
+                }
             }
 
             if let Some(archive) = &artifact.archive {
-                let artifact_dir = &archive.dir_path;
                 // Copy all the static assets
-                for (_, src_path) in &archive.static_assets {
+                for (kind, src_path) in &archive.static_assets {
                     let src_path = src_path.clone();
-                    let file_name = src_path.file_name().unwrap();
-                    let dest_path = artifact_dir.join(file_name);
+                    let dest_path = archive.static_asset_dest(*kind, &src_path);
                     if src_path.is_dir() {
                         build_steps.push(BuildStep::CopyDir(CopyDirStep {
                             src_path,
@@ -1824,6 +5576,41 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                         }))
                     }
                 }
+            }
+
+            if let Some(archive) = &artifact.archive {
+                // Generate the files that don't exist until we build them
+                for asset in &archive.generated_assets {
+                    build_steps.push(BuildStep::GenerateAsset(asset.clone()));
+                }
+            }
+
+            if let ArtifactKind::Installer(installer) = &artifact.kind {
+                // Installer generation is complex enough that they just get monolithic impls
+                build_steps.push(BuildStep::GenerateInstaller(installer.clone()));
+            }
+
+            if let Some(archive) = &artifact.archive {
+                let artifact_dir = &archive.dir_path;
+                // Remember which files are binaries so they can be marked executable
+                let mut executables = artifact
+                    .required_binaries
+                    .values()
+                    .filter_map(|dest_path| dest_path.strip_prefix(artifact_dir).ok())
+                    .map(|rel_path| rel_path.to_owned())
+                    .collect::<Vec<_>>();
+                executables.sort();
+
+                // Strip the binaries (but only the copies in this archive's dir!)
+                if archive.strip_binaries {
+                    let mut paths = artifact
+                        .required_binaries
+                        .values()
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    paths.sort();
+                    build_steps.push(BuildStep::Strip(StripStep { paths }));
+                }
 
                 // Zip up the artifact
                 build_steps.push(BuildStep::Zip(ZipDirStep {
@@ -1831,6 +5618,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     dest_path: artifact.file_path.clone(),
                     with_root: archive.with_root.clone(),
                     zip_style: archive.zip_style,
+                    executables,
                 }));
             }
         }
@@ -1867,19 +5655,34 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 rustflags.push_str(" -Ctarget-feature=+crt-static");
             }
 
-            // If we're trying to cross-compile on macOS, ensure the rustup toolchain
+            // musl is for making fully static binaries, so make sure that's what we get
+            // (and that no C code that's in the build links to the host's libc dynamically)
+            if target.contains("linux-musl") {
+                rustflags.push_str(" -Ctarget-feature=+crt-static");
+            }
+
+            // cross brings its own toolchains for linux targets, so we don't need rustup
+            let command = if self.inner.use_cross
+                && uses_cross(&self.inner.tools.cargo.host_target, &target)
+            {
+                CargoBuildCommand::Cross
+            } else {
+                CargoBuildCommand::Cargo
+            };
+
+            // If we're trying to cross-compile (to the same OS), ensure the rustup toolchain
             // is setup!
-            if target.ends_with("apple-darwin")
-                && self.inner.tools.cargo.host_target.ends_with("apple-darwin")
-                && target != self.inner.tools.cargo.host_target
+            if command == CargoBuildCommand::Cargo
+                && is_same_os_cross(&self.inner.tools.cargo.host_target, &target)
             {
                 if let Some(rustup) = self.inner.tools.rustup.clone() {
                     builds.push(BuildStep::Rustup(RustupStep {
                         rustup,
-                        target: target.clone(),
+                        toolchain: self.inner.desired_rust_toolchain.clone(),
+                        target: Some(target.clone()),
                     }));
                 } else {
-                    warn!("You're trying to cross-compile on macOS, but I can't find rustup to ensure you have the rust toolchains for it!")
+                    warn!("You're trying to cross-compile to {target}, but I can't find rustup to ensure you have the rust toolchains for it!")
                 }
             }
 
@@ -1897,26 +5700,29 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 }
                 for ((pkg_spec, features), expected_binaries) in builds_by_pkg_spec {
                     builds.push(BuildStep::Cargo(CargoBuildStep {
+                        command,
                         target_triple: target.clone(),
                         package: CargoTargetPackages::Package(pkg_spec),
                         features,
                         rustflags: rustflags.clone(),
-                        profile: String::from(PROFILE_DIST),
+                        profile: self.inner.build_profile.clone(),
                         expected_binaries,
                     }));
                 }
             } else {
-                // If we think a workspace build is possible, every binary agrees on the features, so take an arbitrary one
+                // If we think a workspace build is possible, every binary agrees on the features
+                // for this target (target-features can only vary them by target), so take an arbitrary one
                 let features = binaries
                     .first()
                     .map(|&idx| self.binary(idx).features.clone())
                     .unwrap_or_default();
                 builds.push(BuildStep::Cargo(CargoBuildStep {
+                    command,
                     target_triple: target.clone(),
                     package: CargoTargetPackages::Workspace,
                     features,
                     rustflags,
-                    profile: String::from(PROFILE_DIST),
+                    profile: self.inner.build_profile.clone(),
                     expected_binaries: binaries,
                 }));
             }
@@ -1924,79 +5730,173 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         builds
     }
 
-    fn compute_announcement_info(&mut self, announcing: &AnnouncementTag) {
+    fn compute_announcement_info(&mut self, announcing: &AnnouncementTag) -> DistResult<()> {
         // Default to using the tag as a title
         self.inner.announcement_title = Some(announcing.tag.clone());
         self.inner.announcement_tag = Some(announcing.tag.clone());
         self.inner.announcement_is_prerelease = announcing.prerelease;
 
-        self.compute_announcement_changelog(announcing);
+        self.compute_announcement_changelog(announcing)?;
+        if self.inner.contributors {
+            self.inner.announcement_contributors = commits::contributors(
+                &self.inner.workspace_dir,
+                &announcing.tag,
+                self.announcing_package_dir(announcing),
+            );
+        }
         self.compute_announcement_github();
+        Ok(())
     }
 
     /// Try to compute changelogs for the announcement
-    pub fn compute_announcement_changelog(&mut self, announcing: &AnnouncementTag) {
-        let info = if let Some(announcing_version) = &announcing.version {
+    ///
+    /// If no notes can be found this is silently skipped, unless require-changelog is set.
+    pub(crate) fn compute_announcement_changelog(
+        &mut self,
+        announcing: &AnnouncementTag,
+    ) -> DistResult<()> {
+        let (info, version, changelog_owner) = if let Some(announcing_version) = &announcing.version
+        {
             // Try to find the version we're announcing in the top level CHANGELOG/RELEASES
             let version = axoproject::Version::Cargo(announcing_version.clone());
-            let Ok(Some(info)) = self.workspace.changelog_for_version(&version) else {
-                info!(
-                    "failed to find {version} in workspace changelogs, skipping changelog generation"
-                );
-                return;
+            let info = if self.inner.git_cliff {
+                self.changelog_from_git_cliff(&announcing.tag, None)?
+            } else {
+                self.workspace
+                    .changelog_for_version(&version)
+                    .ok()
+                    .flatten()
             };
-
-            info
+            (info, version, "workspace".to_owned())
         } else if let Some(announcing_package) = announcing.package {
             // Try to find the package's specific CHANGELOG/RELEASES
             let package = self.workspace.package(announcing_package);
-            let package_name = &package.name;
             let version = package
                 .version
-                .as_ref()
+                .clone()
                 .expect("cargo package without a version!?");
-            let Ok(Some(info)) = self
-                .workspace
-                .package(announcing_package)
-                .changelog_for_version(version)
-            else {
-                info!(
-                    "failed to find {version} in {package_name} changelogs, skipping changelog generation"
-                );
-                return;
+            let info = if self.inner.git_cliff {
+                self.changelog_from_git_cliff(&announcing.tag, Some(announcing_package))?
+            } else {
+                package.changelog_for_version(&version).ok().flatten()
             };
-
-            info
+            (info, version, package.name.clone())
         } else {
             unreachable!("you're neither announcing a version or a package!?");
         };
 
+        // If the changelogs came up empty, we may be able to make some notes from git history
+        let info = match info {
+            None if self.inner.changelog_from_commits => {
+                let notes = commits::notes_from_commits(
+                    &self.inner.workspace_dir,
+                    &announcing.tag,
+                    self.announcing_package_dir(announcing),
+                )?;
+                notes.map(|body| ChangelogInfo {
+                    title: announcing.tag.clone(),
+                    body,
+                })
+            }
+            info => info,
+        };
+
+        // If notes are required, an empty section doesn't count as having notes
+        let info = match info {
+            Some(info) if !(self.inner.require_changelog && info.body.trim().is_empty()) => info,
+            _ if self.inner.require_changelog => {
+                return Err(DistError::ChangelogMissing {
+                    version: version.to_string(),
+                    changelog_owner,
+                });
+            }
+            _ => {
+                info!(
+                    "failed to find {version} in {changelog_owner} changelogs, skipping changelog generation"
+                );
+                return Ok(());
+            }
+        };
+
         info!("successfully parsed changelog!");
         self.inner.announcement_title = Some(info.title);
         // Those windows newlines get everywhere...
         let clean_notes = newline_converter::dos2unix(&info.body);
         self.inner.announcement_changelog = Some(clean_notes.into_owned());
+        Ok(())
+    }
+
+    /// The name and (workspace-relative) dir of the package we're announcing, if just one
+    fn announcing_package_dir(&self, announcing: &AnnouncementTag) -> Option<(&str, &Utf8Path)> {
+        announcing.package.map(|pkg_idx| {
+            let package = self.workspace.package(pkg_idx);
+            let dir = package
+                .package_root
+                .strip_prefix(&self.inner.workspace_dir)
+                .unwrap_or(&package.package_root);
+            (package.name.as_str(), dir)
+        })
+    }
+
+    /// Generate the release notes for an announcement tag with git-cliff
+    ///
+    /// For a single package's announcement only that package's tags (and files) are
+    /// considered, so other packages' commits don't end up in its notes.
+    fn changelog_from_git_cliff(
+        &self,
+        tag: &str,
+        package: Option<PackageIdx>,
+    ) -> DistResult<Option<ChangelogInfo>> {
+        let tool = self
+            .inner
+            .tools
+            .git_cliff
+            .as_ref()
+            .ok_or(DistError::GitCliffNotFound)?;
+        let mut command = Command::new(&tool.cmd);
+        command
+            .current_dir(&self.inner.workspace_dir)
+            .arg("--current")
+            .arg("--strip")
+            .arg("all");
+        if let Some(pkg_idx) = package {
+            let package = self.workspace.package(pkg_idx);
+            command
+                .arg("--tag-pattern")
+                .arg(format!("(^|/){}[-@/]", package.name));
+            if let Ok(path) = package.package_root.strip_prefix(&self.inner.workspace_dir) {
+                if !path.as_str().is_empty() {
+                    command.arg("--include-path").arg(format!("{path}/**/*"));
+                }
+            }
+        }
+        info!("generating release notes for {tag} with git-cliff");
+        let output = command.output().map_err(|_| DistError::GitCliffNotFound)?;
+        if !output.status.success() {
+            return Err(DistError::GitCliff {
+                tag: tag.to_owned(),
+                details: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            });
+        }
+        Ok(parse_cliff_notes(
+            &String::from_utf8_lossy(&output.stdout),
+            tag,
+        ))
     }
 
-    /// If we're publishing to Github, generate some Github notes
-    fn compute_announcement_github(&mut self) {
+    /// If we're publishing to Github (or another forge), generate some Github notes
+    pub(crate) fn compute_announcement_github(&mut self) {
         use std::fmt::Write;
 
-        if !self.inner.ci_style.contains(&CiStyle::Github) {
-            info!("not publishing to Github, skipping Github Release Notes");
+        if self.inner.ci_style.is_empty() {
+            info!("not publishing to any forge, skipping Github Release Notes");
             return;
         }
 
+        // The install instructions and download tables, which go in every version of the notes
         let mut gh_body = String::new();
         let download_url = self.inner.artifact_download_url.as_ref();
 
-        // add release notes
-        if let Some(changelog) = self.inner.announcement_changelog.as_ref() {
-            gh_body.push_str("## Release Notes\n\n");
-            gh_body.push_str(changelog);
-            gh_body.push_str("\n\n");
-        }
-
         // Add the contents of each Release to the body
         for release in &self.inner.releases {
             let heading_suffix = format!("{} {}", release.app_name, release.version);
@@ -2017,21 +5917,33 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 match &artifact.kind {
                     ArtifactKind::ExecutableZip(zip) => bundles.push((artifact, zip)),
                     ArtifactKind::Symbols(syms) => symbols.push((artifact, syms)),
-                    ArtifactKind::Checksum(_) => {}
+                    ArtifactKind::Checksum(_)
+                    | ArtifactKind::UnifiedChecksum(_)
+                    | ArtifactKind::Signature(_)
+                    | ArtifactKind::Citation(_)
+                    | ArtifactKind::ReleaseNotes(_)
+                    | ArtifactKind::Delta(_)
+                    | ArtifactKind::DeltaScript(_) => {}
                     ArtifactKind::Installer(installer) => {
                         global_installers.push((artifact, installer))
                     }
                 }
             }
 
-            for &variant_idx in &release.variants {
+            for variant_idx in release.all_variants() {
                 let variant = self.variant(variant_idx);
                 for &artifact_idx in &variant.local_artifacts {
                     let artifact = self.artifact(artifact_idx);
                     match &artifact.kind {
                         ArtifactKind::ExecutableZip(zip) => bundles.push((artifact, zip)),
                         ArtifactKind::Symbols(syms) => symbols.push((artifact, syms)),
-                        ArtifactKind::Checksum(_) => {}
+                        ArtifactKind::Checksum(_)
+                        | ArtifactKind::UnifiedChecksum(_)
+                        | ArtifactKind::Signature(_)
+                        | ArtifactKind::Citation(_)
+                        | ArtifactKind::ReleaseNotes(_)
+                        | ArtifactKind::Delta(_)
+                        | ArtifactKind::DeltaScript(_) => {}
                         ArtifactKind::Installer(installer) => {
                             local_installers.push((artifact, installer))
                         }
@@ -2042,18 +5954,37 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             if !global_installers.is_empty() {
                 writeln!(gh_body, "## Install {heading_suffix}\n").unwrap();
                 for (_installer, details) in global_installers {
-                    let info = match details {
+                    let (desc, hint) = match details {
                         InstallerImpl::Shell(info)
+                        | InstallerImpl::OfflineBundle(info)
                         | InstallerImpl::Homebrew(HomebrewInstallerInfo { inner: info, .. })
+                        | InstallerImpl::Scoop(ScoopInstallerInfo { inner: info, .. })
                         | InstallerImpl::Powershell(info)
-                        | InstallerImpl::Npm(NpmInstallerInfo { inner: info, .. }) => info,
-                        InstallerImpl::Msi(_) => {
+                        | InstallerImpl::Npm(NpmInstallerInfo { inner: info, .. }) => {
+                            (&info.desc, &info.hint)
+                        }
+                        InstallerImpl::ShellSuite(info) => (&info.desc, &info.hint),
+                        InstallerImpl::Msi(_)
+                        | InstallerImpl::Deb(_)
+                        | InstallerImpl::Rpm(_)
+                        | InstallerImpl::AppImage(_)
+                        | InstallerImpl::FreebsdPkg(_)
+                        | InstallerImpl::Flatpak(_)
+                        | InstallerImpl::Snap(_)
+                        | InstallerImpl::MacPkg(_)
+                        | InstallerImpl::Dmg(_)
+                        | InstallerImpl::AppBundle(_)
+                        | InstallerImpl::Nsis(_) => {
                             // Should be unreachable, but let's not crash over it
                             continue;
                         }
+                        InstallerImpl::WinGet(_) => {
+                            // Not something users can run themselves, it gets published
+                            continue;
+                        }
                     };
-                    writeln!(&mut gh_body, "### {}\n", info.desc).unwrap();
-                    writeln!(&mut gh_body, "```sh\n{}\n```\n", info.hint).unwrap();
+                    writeln!(&mut gh_body, "### {desc}\n").unwrap();
+                    writeln!(&mut gh_body, "```sh\n{hint}\n```\n").unwrap();
                 }
             }
 
@@ -2063,11 +5994,25 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 .chain(local_installers.iter().map(|i| i.0))
                 .chain(symbols.iter().map(|i| i.0))
                 .collect();
-            if !other_artifacts.is_empty() && download_url.is_some() {
-                let download_url = download_url.as_ref().unwrap();
+            if !other_artifacts.is_empty()
+                && download_url.is_some()
+                && release.download_table != DownloadTableStyle::None
+            {
+                let collapsed = release.download_table == DownloadTableStyle::Collapsed;
                 writeln!(gh_body, "## Download {heading_suffix}\n",).unwrap();
-                gh_body.push_str("|  File  | Platform | Checksum |\n");
-                gh_body.push_str("|--------|----------|----------|\n");
+                if collapsed {
+                    // The blank line after the summary is needed for the table to render
+                    gh_body.push_str("<details>\n<summary>Show all downloads</summary>\n\n");
+                }
+                // Only bother with a signature column if something was signed
+                let has_signatures = other_artifacts.iter().any(|a| a.signature.is_some());
+                if has_signatures {
+                    gh_body.push_str("|  File  | Platform | Checksum | Signature |\n");
+                    gh_body.push_str("|--------|----------|----------|-----------|\n");
+                } else {
+                    gh_body.push_str("|  File  | Platform | Checksum |\n");
+                    gh_body.push_str("|--------|----------|----------|\n");
+                }
 
                 for artifact in other_artifacts {
                     let mut targets = String::new();
@@ -2080,11 +6025,11 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                         multi_target = true;
                     }
                     let name = &artifact.id;
-                    let artifact_download_url = format!("{download_url}/{name}");
+                    let artifact_download_url = self.inner.artifact_url(artifact).unwrap();
                     let download = format!("[{name}]({artifact_download_url})");
                     let checksum = if let Some(checksum_idx) = artifact.checksum {
-                        let checksum_name = &self.artifact(checksum_idx).id;
-                        let checksum_download_url = format!("{download_url}/{checksum_name}");
+                        let checksum = self.artifact(checksum_idx);
+                        let checksum_download_url = self.inner.artifact_url(checksum).unwrap();
                         format!("[checksum]({checksum_download_url})")
                     } else {
                         String::new()
@@ -2097,15 +6042,88 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     if triple.is_empty() {
                         triple = "Unknown".to_string();
                     }
-                    writeln!(&mut gh_body, "| {download} | {triple} | {checksum} |").unwrap();
+                    if has_signatures {
+                        let signature = if let Some(signature_idx) = artifact.signature {
+                            let signature = self.artifact(signature_idx);
+                            let signature_download_url =
+                                self.inner.artifact_url(signature).unwrap();
+                            format!("[signature]({signature_download_url})")
+                        } else {
+                            String::new()
+                        };
+                        writeln!(
+                            &mut gh_body,
+                            "| {download} | {triple} | {checksum} | {signature} |"
+                        )
+                        .unwrap();
+                    } else {
+                        writeln!(&mut gh_body, "| {download} | {triple} | {checksum} |").unwrap();
+                    }
                 }
                 writeln!(&mut gh_body).unwrap();
+                if collapsed {
+                    gh_body.push_str("</details>\n\n");
+                }
             }
         }
 
+        // The full notes are the changelog followed by everything else
+        let mut full_body = String::new();
+        if let Some(changelog) = self.inner.announcement_changelog.as_ref() {
+            full_body.push_str("## Release Notes\n\n");
+            full_body.push_str(changelog);
+            full_body.push_str("\n\n");
+        }
+        if let Some(contributors) = &self.inner.announcement_contributors {
+            full_body.push_str("## Contributors\n\n");
+            full_body.push_str(contributors);
+            full_body.push('\n');
+        }
+        full_body.push_str(&gh_body);
+
         info!("successfully generated github release body!");
         // self.inner.artifact_download_url = Some(download_url);
-        self.inner.announcement_github_body = Some(gh_body);
+        if self.inner.release_notes_asset {
+            // Keep the body short and point to the asset for the full notes
+            let mut short_body = String::new();
+            if self.inner.announcement_changelog.is_some() {
+                // Without a download url, link to the asset relative to the Github Release
+                // page (github.com/{owner}/{repo}/releases/tag/{tag})
+                let notes_url = match (download_url, &self.inner.announcement_tag) {
+                    (Some(download_url), _) => {
+                        Some(format!("{download_url}/{RELEASE_NOTES_FILE_NAME}"))
+                    }
+                    (None, Some(tag)) => {
+                        Some(format!("../download/{tag}/{RELEASE_NOTES_FILE_NAME}"))
+                    }
+                    (None, None) => None,
+                };
+                short_body.push_str("## Release Notes\n\n");
+                if let Some(notes_url) = notes_url {
+                    writeln!(
+                        short_body,
+                        "See [{RELEASE_NOTES_FILE_NAME}]({notes_url}) for the full release notes.\n"
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(
+                        short_body,
+                        "See the {RELEASE_NOTES_FILE_NAME} attached to this release for the full release notes.\n"
+                    )
+                    .unwrap();
+                }
+            }
+            if let Some(contributors) = &self.inner.announcement_contributors {
+                short_body.push_str("## Contributors\n\n");
+                short_body.push_str(contributors);
+                short_body.push('\n');
+            }
+            short_body.push_str(&gh_body);
+            self.inner.announcement_github_body = Some(short_body);
+            self.inner.announcement_release_notes = Some(full_body);
+        } else {
+            self.inner.announcement_github_body = Some(full_body);
+        }
     }
 
     fn compute_ci(&mut self) {
@@ -2114,6 +6132,12 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 CiStyle::Github => {
                     self.inner.ci.github = Some(GithubCiInfo::new(&self.inner));
                 }
+                CiStyle::GitLab => {
+                    self.inner.ci.gitlab = Some(GitLabCiInfo::new(&self.inner));
+                }
+                CiStyle::Gitea => {
+                    self.inner.ci.gitea = Some(GiteaCiInfo::new(&self.inner));
+                }
             }
         }
     }
@@ -2145,12 +6169,24 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
     fn variant_mut(&mut self, idx: ReleaseVariantIdx) -> &mut ReleaseVariant {
         &mut self.inner.variants[idx.0]
     }
+    /// Get the path of a dir to stage a target's artifact in
+    ///
+    /// Usually this is just in the dist dir, but with per-target-staging-dirs it's
+    /// nested in a dir named after the target.
+    fn staging_dir(&self, target: &str, dir_name: &str) -> Utf8PathBuf {
+        if self.inner.per_target_staging_dirs {
+            self.inner.dist_dir.join(target_name(target)).join(dir_name)
+        } else {
+            self.inner.dist_dir.join(dir_name)
+        }
+    }
     fn local_artifacts_enabled(&self) -> bool {
         match self.artifact_mode {
             ArtifactMode::Local => true,
             ArtifactMode::Global => false,
             ArtifactMode::Host => true,
             ArtifactMode::All => true,
+            ArtifactMode::Metadata => false,
         }
     }
     fn global_artifacts_enabled(&self) -> bool {
@@ -2159,11 +6195,105 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             ArtifactMode::Global => true,
             ArtifactMode::Host => true,
             ArtifactMode::All => true,
+            ArtifactMode::Metadata => true,
+        }
+    }
+    /// Whether we're allowed to work out what archives would be built,
+    /// which is needed by any global artifact that refers to them (installers)
+    fn archive_layout_enabled(&self) -> bool {
+        match self.artifact_mode {
+            ArtifactMode::Local => true,
+            ArtifactMode::Global => true,
+            ArtifactMode::Host => true,
+            ArtifactMode::All => true,
+            ArtifactMode::Metadata => false,
         }
     }
 }
 
 impl DistGraph {
+    /// Get the announcement tag for the given version, according to tag-format
+    pub fn tag_for_version(&self, version: &str) -> String {
+        self.tag_format.replace(TAG_FORMAT_VERSION, version)
+    }
+    /// Get the version from a tag in tag-format (without any package prefix)
+    pub fn version_from_tag<'a>(&self, tag: &'a str) -> Option<&'a str> {
+        let (prefix, suffix) = self.tag_format.split_once(TAG_FORMAT_VERSION)?;
+        tag.strip_prefix(prefix)?.strip_suffix(suffix)
+    }
+    /// Get the URL an artifact will be downloadable from (if we know where they're uploaded)
+    pub fn artifact_url(&self, artifact: &Artifact) -> Option<String> {
+        let download_url = self.artifact_download_url.as_ref()?;
+        Some(format!("{download_url}/{}", artifact.id))
+    }
+    /// Whether generating the release notes (or contributors) needs the full git history
+    pub fn needs_git_history(&self) -> bool {
+        self.git_cliff || self.changelog_from_commits || self.contributors
+    }
+    /// Get a glob for the git tags that CI should react to
+    ///
+    /// Anything is allowed before the tag-format so that per-package tags like
+    /// `my-app-v1.0.0` are also matched.
+    pub fn tag_glob(&self) -> String {
+        let version_glob = "[0-9]+.[0-9]+.[0-9]+*";
+        if self.tag_format == DEFAULT_TAG_FORMAT {
+            // The "v" is optional by default
+            format!("**{version_glob}")
+        } else {
+            format!("**{}", self.tag_for_version(version_glob))
+        }
+    }
+    /// Get a regex for the git tags that CI should react to
+    ///
+    /// This is [`DistGraph::tag_glob`][] translated for CIs that match tags
+    /// with regexes, like GitLab.
+    pub fn tag_regex(&self) -> String {
+        let glob = self.tag_glob();
+        let mut regex = String::from("^");
+        let mut chars = glob.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    regex.push_str(".*");
+                }
+                '*' => regex.push_str("[^/]*"),
+                '.' | '(' | ')' | '{' | '}' | '|' | '^' | '$' | '\\' | '/' => {
+                    regex.push('\\');
+                    regex.push(c);
+                }
+                _ => regex.push(c),
+            }
+        }
+        regex.push('$');
+        regex
+    }
+    /// Get all the rustup invocations the builds will need, so that CI can
+    /// provision all the toolchains and targets up front
+    pub fn rustup_plan(&self) -> Vec<RustupStep> {
+        let mut steps = vec![];
+        if let (Some(rustup), Some(toolchain)) = (&self.tools.rustup, &self.desired_rust_toolchain)
+        {
+            steps.push(RustupStep {
+                rustup: rustup.clone(),
+                toolchain: Some(toolchain.clone()),
+                target: None,
+            });
+        }
+        for step in &self.build_steps {
+            if let BuildStep::Rustup(step) = step {
+                steps.push(step.clone());
+            }
+        }
+        steps
+    }
+    /// Get the commands for [`DistGraph::rustup_plan`][]
+    pub fn rustup_plan_commands(&self) -> Vec<String> {
+        self.rustup_plan()
+            .iter()
+            .map(|step| step.command())
+            .collect()
+    }
     /// Get a binary
     pub fn binary(&self, idx: BinaryIdx) -> &Binary {
         &self.binaries[idx.0]
@@ -2180,6 +6310,18 @@ impl DistGraph {
     pub fn variant(&self, idx: ReleaseVariantIdx) -> &ReleaseVariant {
         &self.variants[idx.0]
     }
+    /// Find an artifact by its id (its file name, which is globally unique)
+    pub fn artifact_by_id(&self, id: &str) -> Option<&Artifact> {
+        self.artifacts.iter().find(|artifact| artifact.id == id)
+    }
+    /// Find a release by its id
+    pub fn release_by_id(&self, id: &str) -> Option<&Release> {
+        self.releases.iter().find(|release| release.id == id)
+    }
+    /// Find a variant by its id
+    pub fn variant_by_id(&self, id: &str) -> Option<&ReleaseVariant> {
+        self.variants.iter().find(|variant| variant.id == id)
+    }
 }
 
 /// Precompute all the work this invocation will need to do
@@ -2187,8 +6329,23 @@ pub fn gather_work(cfg: &Config) -> Result<DistGraph> {
     info!("analyzing workspace:");
     let tools = tool_info()?;
     let workspace = crate::config::get_project()?;
+    gather_work_for_workspace(cfg, tools, &workspace)
+}
+
+/// The inner impl of gather_work, for a workspace (and tools) we've already found
+pub(crate) fn gather_work_for_workspace(
+    cfg: &Config,
+    tools: Tools,
+    workspace: &WorkspaceInfo,
+) -> Result<DistGraph> {
     let mut graph =
-        DistGraphBuilder::new(tools, &workspace, cfg.artifact_mode, cfg.allow_all_dirty)?;
+        DistGraphBuilder::new(tools, workspace, cfg.artifact_mode, cfg.allow_all_dirty)?;
+    if let Some(host_target) = &cfg.host_target {
+        check_host_target(host_target)?;
+        info!("overriding host target with {host_target}");
+        graph.inner.tools.cargo.host_target = host_target.clone();
+    }
+    exclude_packages(&mut graph, &cfg.exclude)?;
 
     // Prefer the CLI (cfg) if it's non-empty, but only select a subset
     // of what the workspace supports if it's non-empty
@@ -2209,7 +6366,11 @@ pub fn gather_work(cfg: &Config) -> Result<DistGraph> {
     let all_target_triples = graph
         .workspace
         .packages()
-        .flat_map(|(id, _)| graph.package_metadata(id).targets.iter().flatten())
+        .flat_map(|(id, _)| {
+            let package_config = graph.package_metadata(id);
+            let targets = package_config.targets.iter().flatten();
+            targets.chain(package_config.extra_targets.iter().flatten())
+        })
         .collect::<SortedSet<_>>()
         .into_iter()
         .cloned()
@@ -2247,10 +6408,12 @@ pub fn gather_work(cfg: &Config) -> Result<DistGraph> {
         cfg.needs_coherent_announcement_tag,
     )?;
 
-    if let Some(repo_url) = workspace.web_url()?.as_ref() {
-        let tag = &announcing.tag;
-        graph.inner.artifact_download_url = Some(format!("{repo_url}/releases/download/{tag}"));
-    }
+    graph.inner.artifact_download_url = artifact_download_url(
+        &graph.inner.ci_style,
+        workspace,
+        graph.workspace_metadata.artifact_download_url.as_deref(),
+        &announcing.tag,
+    )?;
 
     // Create a Release for each package
     for (pkg_idx, binaries) in &announcing.rust_releases {
@@ -2260,28 +6423,73 @@ pub fn gather_work(cfg: &Config) -> Result<DistGraph> {
         // Create a Release for this binary
         let release = graph.add_release(*pkg_idx);
 
-        // Tell the Release to include these binaries
+        // Tell the Release to include these binaries (which are built from the
+        // package that defines them, if that's not this one)
+        let borrowed = graph.borrowed_binaries(*pkg_idx)?;
         for binary in binaries {
-            graph.add_binary(release, *pkg_idx, (*binary).clone());
+            let owner = if graph.workspace.package(*pkg_idx).binaries.contains(binary) {
+                *pkg_idx
+            } else {
+                borrowed
+                    .iter()
+                    .find(|(_, name)| name == binary)
+                    .map(|(owner, _)| *owner)
+                    .unwrap_or(*pkg_idx)
+            };
+            graph.add_binary(release, owner, (*binary).clone());
         }
 
         // Create variants for this Release for each target
         for target in triples {
             // This logic ensures that (outside of host mode) we only select targets that are a
-            // subset of the ones the package claims to support
+            // subset of the ones the package claims to support (its targets and extra-targets)
             let use_target = bypass_package_target_prefs
                 || package_config
                     .targets
-                    .as_deref()
-                    .unwrap_or_default()
                     .iter()
+                    .flatten()
+                    .chain(package_config.extra_targets.iter().flatten())
                     .any(|t| t == target);
             if !use_target {
                 continue;
             }
 
             // Create the variant
-            graph.add_variant(release, target.clone());
+            graph.add_variant(release, target.clone(), None);
+        }
+        // Feature variants are built for every target the app is built for
+        for (name, variant) in package_config.feature_variants.iter().flatten() {
+            let valid_name = !name.is_empty()
+                && name != "demo"
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid_name {
+                return Err(DistError::FeatureVariantName {
+                    package: graph.workspace.package(*pkg_idx).name.clone(),
+                    name: name.clone(),
+                }
+                .into());
+            }
+            let features = CargoTargetFeatures {
+                default_features: variant.default_features.unwrap_or(true),
+                features: if let Some(true) = variant.all_features {
+                    CargoTargetFeatureList::All
+                } else {
+                    CargoTargetFeatureList::List(variant.features.clone())
+                },
+            };
+            for target in graph.release(release).targets.clone() {
+                graph.add_variant(release, target, Some((name.clone(), features.clone())));
+            }
+        }
+        // Demos only make sense next to a normal build of the same target
+        for target in package_config.demo_targets.as_deref().unwrap_or_default() {
+            if graph.release(release).targets.contains(target) {
+                graph.add_demo_variant(release, target.clone());
+            } else {
+                info!("skipping demo for {target}: not building that target");
+            }
         }
         // Add executable zips to the Release
         graph.add_executable_zip(release);
@@ -2310,17 +6518,279 @@ pub fn gather_work(cfg: &Config) -> Result<DistGraph> {
             // Create the variant
             graph.add_installer(release, installer)?;
         }
+
+        // Add release metadata to the Release
+        graph.add_citation(release);
+        graph.add_delta_script(release);
+        graph.add_offline_bundle(release);
+        graph.add_unified_checksum(release);
+        graph.add_checksums_json(release);
+        graph.compute_binstall(release, *pkg_idx, &announcing.tag);
     }
 
+    // Add announcement-wide metadata
+    graph.add_release_notes();
+    graph.add_shell_suite_installer();
+
     // Prep the announcement's release notes and whatnot
-    graph.compute_announcement_info(&announcing);
+    graph.compute_announcement_info(&announcing)?;
+
+    // The unified checksums list every artifact of their release, so in --artifacts=global
+    // mode we plan the local artifacts too, to find out what the other machines build
+    let wants_unified_checksum = matches!(cfg.artifact_mode, ArtifactMode::Global)
+        && graph.inner.releases.iter().any(|release| {
+            release.checksum != ChecksumStyle::False
+                && (release.unified_checksum || release.checksums_json)
+        });
+    if wants_unified_checksum {
+        let local_cfg = Config {
+            artifact_mode: ArtifactMode::Local,
+            targets: cfg.targets.clone(),
+            ci: cfg.ci.clone(),
+            installers: cfg.installers.clone(),
+            announcement_tag: cfg.announcement_tag.clone(),
+            host_target: cfg.host_target.clone(),
+            exclude: cfg.exclude.clone(),
+            ..*cfg
+        };
+        let local = gather_work_for_workspace(&local_cfg, graph.inner.tools.clone(), workspace)?;
+        graph.remote_artifacts = local
+            .releases
+            .iter()
+            .map(|release| {
+                release
+                    .all_variants()
+                    .flat_map(|idx| &local.variants[idx.0].local_artifacts)
+                    .map(|&idx| &local.artifacts[idx.0])
+                    .filter(|artifact| {
+                        !matches!(
+                            artifact.kind,
+                            ArtifactKind::Checksum(_) | ArtifactKind::Signature(_)
+                        )
+                    })
+                    .map(|artifact| artifact.file_path.clone())
+                    .collect()
+            })
+            .collect();
+    }
 
     // Finally compute all the build steps!
-    graph.compute_build_steps();
+    Ok(graph.build())
+}
+
+/// Add any packages excluded with --exclude to the ones exclude-packages excludes
+///
+/// Both have to name packages that actually exist, to catch typos.
+fn exclude_packages(graph: &mut DistGraphBuilder, exclude: &[String]) -> DistResult<()> {
+    if !exclude.is_empty() {
+        graph
+            .workspace_metadata
+            .exclude_packages
+            .get_or_insert_with(Vec::new)
+            .extend(exclude.iter().cloned());
+    }
+    for name in graph.workspace_metadata.exclude_packages.iter().flatten() {
+        if !graph
+            .workspace()
+            .packages()
+            .any(|(_, pkg)| &pkg.name == name)
+        {
+            return Err(DistError::UnknownExcludedPackage {
+                package: name.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Every package in the workspace, and what would be released for it
+///
+/// This is the output of `cargo dist plan --list`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DistListing {
+    /// Every package in the workspace (in workspace order)
+    pub packages: Vec<ListedPackage>,
+}
+
+/// A package in a [`DistListing`][]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ListedPackage {
+    /// The name of the package
+    pub name: String,
+    /// The version of the package
+    pub version: String,
+    /// Why the package won't be released, if it won't be
+    pub disabled_reason: Option<String>,
+    /// The binaries a release of the package would have
+    ///
+    /// For disabled packages this is just the binaries the package defines.
+    pub binaries: Vec<String>,
+    /// The --tags that would release the package
+    pub tags: Vec<String>,
+    /// The targets the package declares (both targets and extra-targets)
+    pub targets: Vec<String>,
+}
+
+/// List every package cargo-dist could release, without computing any artifacts
+pub fn list_apps(cfg: &Config) -> Result<DistListing> {
+    let tools = tool_info()?;
+    let workspace = crate::config::get_project()?;
+    list_apps_for_workspace(cfg, tools, &workspace)
+}
+
+/// The inner impl of list_apps, for a workspace (and tools) we've already found
+pub(crate) fn list_apps_for_workspace(
+    cfg: &Config,
+    tools: Tools,
+    workspace: &WorkspaceInfo,
+) -> Result<DistListing> {
+    let mut graph =
+        DistGraphBuilder::new(tools, workspace, cfg.artifact_mode, cfg.allow_all_dirty)?;
+    exclude_packages(&mut graph, &cfg.exclude)?;
+
+    // This is the same selection gather_work makes, so the reasons match real builds
+    let announcing = parse_tag(&graph, cfg.announcement_tag.as_deref())?;
+    let rust_releases = select_packages(&graph, &announcing)?;
+    let versions = possible_tags(&graph, rust_releases.iter().map(|(idx, _)| *idx));
+
+    let mut packages = vec![];
+    for (pkg_idx, pkg) in graph.workspace().packages() {
+        let version = pkg.version.as_ref().unwrap().cargo();
+        let package_config = graph.package_metadata(pkg_idx);
+        let release = rust_releases.iter().find(|(idx, _)| *idx == pkg_idx);
+        let mut disabled_reason = check_dist_package(&graph, pkg_idx, pkg, &announcing);
+        if release.is_none() && disabled_reason.is_none() {
+            disabled_reason = Some("bin-filter excludes every binary".to_owned());
+        }
+
+        // The unified tag for its version, the tag for just this package, and its groups' tags
+        let mut tags = vec![];
+        if release.is_some() {
+            let tag = graph.inner.tag_for_version(&version.to_string());
+            if versions
+                .get(version)
+                .is_some_and(|packages| packages.contains(&pkg_idx))
+            {
+                tags.push(tag.clone());
+            }
+            tags.push(format!("{}-{tag}", pkg.name));
+            for (group, members) in graph
+                .workspace_metadata
+                .announcement_groups
+                .iter()
+                .flatten()
+            {
+                if members.contains(&pkg.name) {
+                    tags.push(format!("{group}-{tag}"));
+                }
+            }
+        }
+
+        packages.push(ListedPackage {
+            name: pkg.name.clone(),
+            version: version.to_string(),
+            disabled_reason,
+            binaries: match release {
+                Some((_, binaries)) => binaries.clone(),
+                None => pkg.binaries.clone(),
+            },
+            tags,
+            targets: package_config
+                .targets
+                .iter()
+                .flatten()
+                .chain(package_config.extra_targets.iter().flatten())
+                .cloned()
+                .collect(),
+        });
+    }
+
+    Ok(DistListing { packages })
+}
+
+/// Compute the url that the announcement's artifacts will be downloadable from
+///
+/// If artifact-download-url is set that's the url, otherwise each forge has its own
+/// layout for this. If several CIs are enabled Github wins, otherwise the first one
+/// listed does.
+fn artifact_download_url(
+    ci_style: &[CiStyle],
+    workspace: &WorkspaceInfo,
+    custom_url: Option<&str>,
+    tag: &str,
+) -> Result<Option<String>> {
+    if let Some(custom_url) = custom_url {
+        let url = custom_url.trim_end_matches('/').replace("{tag}", tag);
+        return Ok(Some(url));
+    }
+    let forge = match ci_style.first() {
+        Some(&first) if !ci_style.contains(&CiStyle::Github) => first,
+        _ => CiStyle::Github,
+    };
+    let repo_url = match forge {
+        CiStyle::Github => workspace.web_url()?,
+        // These can be self-hosted, so we can't be as strict about the url
+        CiStyle::GitLab | CiStyle::Gitea => workspace.repository_url.as_deref().map(forge_web_url),
+    };
+    Ok(repo_url.map(|repo_url| {
+        forge
+            .artifact_download_url_template()
+            .replace("{repo_url}", &repo_url)
+            .replace("{tag}", tag)
+    }))
+}
+
+/// Get the web version of a repository url on a forge that might be self-hosted
+///
+/// Projects might be nested in groups, so this only strips the cruft Cargo.toml
+/// "repository" keys tend to have (and converts ssh urls).
+fn forge_web_url(repo_url: &str) -> String {
+    let url = repo_url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let url = url.strip_prefix("git+").unwrap_or(url);
+    match url.strip_prefix("git@") {
+        Some(ssh) => format!("https://{}", ssh.replacen(':', "/", 1)),
+        None => url.to_owned(),
+    }
+}
 
-    graph.compute_ci();
+/// Whether building for target on host is cross-compiling that rustup can help with
+///
+/// Cross-compiling to another OS is out of rustup's hands (you need a whole other
+/// toolchain for that), so this only covers other architectures of the same OS.
+fn is_same_os_cross(host: &str, target: &str) -> bool {
+    const OSES: &[&str] = &["apple-darwin", "linux", "windows"];
+    // rustup can't install anything for a custom target (that's what build-std is for)
+    host != target
+        && !is_custom_target_spec(target)
+        && OSES
+            .iter()
+            .any(|os| host.contains(os) && target.contains(os))
+}
+
+/// Whether a target is the path to a custom target spec (e.g. `targets/my-board.json`)
+/// instead of a target triple
+pub fn is_custom_target_spec(target: &str) -> bool {
+    target.ends_with(".json")
+}
+
+/// The name to use for a target in ids and file names
+///
+/// This is the target triple itself, or the file stem of a custom target spec
+/// (which is also what cargo names its dir in the target dir).
+pub fn target_name(target: &str) -> &str {
+    if is_custom_target_spec(target) {
+        Utf8Path::new(target).file_stem().unwrap_or(target)
+    } else {
+        target
+    }
+}
 
-    Ok(graph.inner)
+/// Whether a target should be built with cross (if use-cross is enabled)
+///
+/// That's every linux target, except the one we could just build natively.
+pub(crate) fn uses_cross(host: &str, target: &str) -> bool {
+    host != target && target.contains("linux") && !is_custom_target_spec(target)
 }
 
 /// See if we should dist this package.
@@ -2334,14 +6804,34 @@ pub fn gather_work(cfg: &Config) -> Result<DistGraph> {
 /// letting through all the packages that aren't intrinsically disqualified by things like
 /// publish=false. Later steps will then check if a coherent announcement tag exists that
 /// covers everything this function spat out.
-fn check_dist_package(
+pub(crate) fn check_dist_package(
     graph: &DistGraphBuilder,
     pkg_id: PackageIdx,
     pkg: &axoproject::PackageInfo,
     announcing: &PartialAnnouncementTag,
 ) -> Option<String> {
+    // Excluded packages are never published, no matter what else they say
+    let excluded = &graph.workspace_metadata.exclude_packages;
+    if excluded.iter().flatten().any(|name| name == &pkg.name) {
+        return Some("excluded".to_owned());
+    }
+
+    // If another package releases our binaries, we don't get our own release
+    let borrower = graph.workspace().packages().find(|(idx, _)| {
+        let binaries_from = graph.package_metadata(*idx).binaries_from.iter().flatten();
+        *idx != pkg_id && binaries_from.into_iter().any(|name| name == &pkg.name)
+    });
+    if let Some((_, borrower)) = borrower {
+        return Some(format!("binaries released by {}", borrower.name));
+    }
+
     // Nothing to publish if there's no binaries!
-    if pkg.binaries.is_empty() {
+    let binaries_from = graph
+        .package_metadata(pkg_id)
+        .binaries_from
+        .iter()
+        .flatten();
+    if pkg.binaries.is_empty() && binaries_from.count() == 0 {
         return Some("no binaries".to_owned());
     }
 
@@ -2353,7 +6843,8 @@ fn check_dist_package(
             true
         }
     } else {
-        false
+        // Otherwise the workspace can ask for publish = false to be ignored wholesale
+        graph.workspace_metadata.dist_unpublished.unwrap_or(false)
     };
 
     // Otherwise defer to Cargo's `publish = false`
@@ -2371,6 +6862,18 @@ fn check_dist_package(
         }
     }
 
+    // If we're announcing a group, reject everything outside of it (its packages can
+    // have whatever versions they like, the tag's version is the group's own)
+    if let Some((_, packages)) = &announcing.group {
+        if !packages.contains(&pkg_id) {
+            return Some(format!(
+                "didn't match tag {}",
+                announcing.tag.as_ref().unwrap()
+            ));
+        }
+        return None;
+    }
+
     // If we're announcing a version, ignore everything that doesn't match that
     if let Some(ver) = &announcing.version {
         if pkg.version.as_ref().unwrap().cargo() != ver {
@@ -2422,19 +6925,17 @@ pub fn get_host_target(cargo: String) -> Result<CargoInfo> {
 
 fn target_symbol_kind(target: &str) -> Option<SymbolKind> {
     #[allow(clippy::if_same_then_else)]
-    if target.contains("windows-msvc") {
+    if is_custom_target_spec(target) {
+        // We have no idea what platform a custom target is for, so don't guess
+        None
+    } else if target.contains("windows-msvc") {
         // Temporary disabled pending redesign of symbol handling!
 
         // Some(SymbolKind::Pdb)
         None
     } else if target.contains("apple") {
-        // Macos dSYM files are real and work but things
-        // freak out because it turns out they're directories
-        // and not "real" files? Temporarily disabling this
-        // until I have time to figure out what to do
-
-        // Some(SymbolKind::Dsym)
-        None
+        // Macos dSYM files are directories, so these get tarballed up
+        Some(SymbolKind::Dsym)
     } else {
         // Linux has DWPs but cargo doesn't properly uplift them
         // See: https://github.com/rust-lang/cargo/pull/11384
@@ -2444,18 +6945,102 @@ fn target_symbol_kind(target: &str) -> Option<SymbolKind> {
     }
 }
 
+/// Whether the given cargo profile (or whatever it inherits from) enables debuginfo
+fn profile_has_debuginfo(profiles: &axoproject::rust::CargoProfiles, profile_name: &str) -> bool {
+    let mut profile_name = profile_name;
+    // Only follow so many inherits, in case someone made a cycle
+    for _ in 0..8 {
+        let profile = profiles.get(profile_name);
+        if let Some(debug) = profile.and_then(|p| p.debug) {
+            return debug != 0;
+        }
+        match profile.and_then(|p| p.inherits.as_deref()) {
+            Some(parent) => profile_name = parent,
+            // Otherwise use cargo's builtin defaults
+            None => return matches!(profile_name, "dev" | "test"),
+        }
+    }
+    false
+}
+
+/// Make sure an overridden host target at least looks like a target triple
+///
+/// We can't know every target that exists, but they're all `arch-rest[-of-it]`.
+fn check_host_target(host_target: &str) -> DistResult<()> {
+    let parts = host_target.split('-').collect::<Vec<_>>();
+    let is_plausible = parts.len() >= 2
+        && parts.iter().all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        });
+    if !is_plausible {
+        return Err(DistError::InvalidHostTarget {
+            host_target: host_target.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Make sure dist-subdir won't collide with anything cargo puts in target/
+fn check_dist_subdir(
+    dist_subdir: &str,
+    build_profile: &str,
+    profiles: &axoproject::rust::CargoProfiles,
+) -> DistResult<()> {
+    let is_plain_name = !dist_subdir.is_empty()
+        && dist_subdir != "."
+        && dist_subdir != ".."
+        && !dist_subdir.contains(['/', '\\']);
+    if !is_plain_name {
+        return Err(DistError::DistSubdirInvalid {
+            subdir: dist_subdir.to_owned(),
+        });
+    }
+    if dist_subdir == PROFILE_DIST
+        || profiles.contains_key(dist_subdir)
+        || CARGO_TARGET_SUBDIRS.contains(&dist_subdir)
+    {
+        return Err(DistError::DistSubdirCollision {
+            subdir: dist_subdir.to_owned(),
+        });
+    }
+    // The profile we build with always gets a dir, whether or not it's in Cargo.toml
+    if dist_subdir == build_profile {
+        return Err(DistError::BuildProfileCollision {
+            profile: build_profile.to_owned(),
+        });
+    }
+    Ok(())
+}
+
 fn tool_info() -> Result<Tools> {
     let cargo_cmd = cargo()?;
     let cargo = get_host_target(cargo_cmd)?;
     Ok(Tools {
         cargo,
-        rustup: find_tool("rustup"),
+        rustup: find_tool("rustup", "-V"),
+        appimagetool: find_tool("appimagetool", "--version"),
+        flatpak_builder: find_tool("flatpak-builder", "--version"),
+        snapcraft: find_tool("snapcraft", "--version"),
+        oras: find_tool("oras", "version"),
+        zstd: find_tool("zstd", "--version"),
+        // bsdiff has no version flag, but happily prints its usage for anything
+        bsdiff: find_tool("bsdiff", "--version"),
+        cross: find_tool("cross", "--version"),
+        git_cliff: find_tool("git-cliff", "--version"),
     })
 }
 
-fn find_tool(name: &str) -> Option<Tool> {
-    let output = Command::new(name).arg("-V").output().ok()?;
-    let string_output = String::from_utf8(output.stdout).ok()?;
+fn find_tool(name: &str, version_flag: &str) -> Option<Tool> {
+    let output = Command::new(name).arg(version_flag).output().ok()?;
+    // Not every tool is polite enough to print its version to stdout
+    let string_output = if output.stdout.is_empty() {
+        String::from_utf8(output.stderr).ok()?
+    } else {
+        String::from_utf8(output.stdout).ok()?
+    };
     let version = string_output.lines().next()?;
     Some(Tool {
         cmd: name.to_owned(),
@@ -2478,13 +7063,19 @@ pub(crate) struct AnnouncementTag {
 }
 
 /// details on what we're announcing (partially computed)
-struct PartialAnnouncementTag {
+#[derive(Default)]
+pub(crate) struct PartialAnnouncementTag {
     /// The full tag
     pub tag: Option<String>,
     /// The version we're announcing (if doing a unified version announcement)
     pub version: Option<Version>,
     /// The package we're announcing (if doing a single-package announcement)
     pub package: Option<PackageIdx>,
+    /// The announcement-group we're announcing (if doing a group announcement)
+    ///
+    /// This is the group's name and packages. `version` is then the version in the tag,
+    /// which the packages don't have to match.
+    pub group: Option<(String, Vec<PackageIdx>)>,
     /// whether we're prereleasing
     pub prerelease: bool,
 }
@@ -2509,7 +7100,7 @@ pub(crate) fn select_tag(
     // Parse the tag
     let mut announcing = parse_tag(graph, tag)?;
     // Select which packages/binaries are available from that tag
-    let rust_releases = select_packages(graph, &announcing);
+    let rust_releases = select_packages(graph, &announcing)?;
 
     // Don't proceed if the conclusions don't make sense
     if rust_releases.is_empty() {
@@ -2523,9 +7114,25 @@ pub(crate) fn select_tag(
             //
             // To get better help messages, we explore a hypothetical world where they didn't pass
             // `--tag` so we can get all the options for a good help message.
-            let announcing = parse_tag(graph, None)?;
-            let rust_releases = select_packages(graph, &announcing);
+            let hypothetical = parse_tag(graph, None)?;
+            let rust_releases = select_packages(graph, &hypothetical)?;
             let versions = possible_tags(graph, rust_releases.iter().map(|(idx, _)| *idx));
+            // If there *are* things to release, the tag is probably just for the wrong version
+            // (a typo, or a version that hasn't been bumped yet), so say that
+            if let (Some(tag), Some(version)) = (&announcing.tag, &announcing.version) {
+                if !versions.is_empty() && announcing.group.is_none() {
+                    let help = tag_help(
+                        graph,
+                        versions,
+                        "Did you mean to pass one of these versions as --tag?",
+                    );
+                    return Err(DistError::NoPackageWithTagVersion {
+                        tag: tag.clone(),
+                        version: version.clone(),
+                        help,
+                    });
+                }
+            }
             let help = tag_help(graph, versions, "You may need to pass the current version as --tag, or need to give all your packages the same version");
             return Err(DistError::NothingToRelease { help });
         }
@@ -2538,10 +7145,10 @@ pub(crate) fn select_tag(
         if versions.len() == 1 {
             // Nice, one version, use it
             let version = *versions.first_key_value().unwrap().0;
-            let tag = format!("v{version}");
+            let tag = graph.inner.tag_for_version(&version.to_string());
             info!("inferred Announcement tag: {}", tag);
             announcing.tag = Some(tag);
-            announcing.prerelease = !version.pre.is_empty();
+            announcing.prerelease = graph.inner.versioning.is_prerelease(version);
             announcing.version = Some(version.clone());
         } else if needs_coherent_announcement_tag {
             // More than one version, give the user some suggestions
@@ -2556,7 +7163,7 @@ pub(crate) fn select_tag(
             // like `init` or `generate` which just wants us to hand it everything
             // and doesn't care about coherent announcements. So use a fake tag
             // and hand it the fully unconstrained list of rust_releases.
-            announcing.tag = Some("v1.0.0-FAKEVER".to_owned());
+            announcing.tag = Some(graph.inner.tag_for_version("1.0.0-FAKEVER"));
             announcing.prerelease = true;
             announcing.version = Some("1.0.0-FAKEVER".parse().unwrap());
         }
@@ -2580,6 +7187,7 @@ pub(crate) fn select_tag(
 fn parse_tag(graph: &DistGraphBuilder, tag: Option<&str>) -> DistResult<PartialAnnouncementTag> {
     // First thing's first: if they gave us an announcement tag then we should try to parse it
     let mut announcing_package = None;
+    let mut announcing_group = None;
     let mut announcing_version = None;
     let mut announcing_prerelease = false;
     let announcement_tag = tag.map(|t| t.to_owned());
@@ -2598,34 +7206,59 @@ fn parse_tag(graph: &DistGraphBuilder, tag: Option<&str>) -> DistResult<PartialA
             // component is exactly a package name (strip_prefix produces empty string)
             if let Some((package, "")) = strip_prefix_package(maybe_package, graph) {
                 announcing_package = Some(package);
+            } else if let Some((group, "")) = strip_prefix_group(maybe_package, graph) {
+                announcing_group = Some(group);
             }
             tag_suffix = suffix;
         } else {
             tag_suffix = tag;
         };
 
-        // If we don't have an announcing_package yet, check if this is "some-package-v1.0.0" format
-        if announcing_package.is_none() {
-            if let Some((package, suffix)) = strip_prefix_package(tag_suffix, graph) {
-                // Must be followed by a dash to be accepted
-                if let Some(suffix) = suffix.strip_prefix('-') {
-                    tag_suffix = suffix;
-                    announcing_package = Some(package);
+        // If we don't have an announcing_package yet, check if this is "some-package-v1.0.0"
+        // or "some-package@1.0.0" format (or the same for an announcement-group)
+        if announcing_package.is_none() && announcing_group.is_none() {
+            let package = strip_prefix_package(tag_suffix, graph);
+            let group = strip_prefix_group(tag_suffix, graph);
+            // Whichever name is longer is the more specific match
+            let group_wins = match (&package, &group) {
+                (Some((_, package_rest)), Some((_, group_rest))) => {
+                    group_rest.len() < package_rest.len()
+                }
+                (None, Some(_)) => true,
+                _ => false,
+            };
+            let rest = if group_wins {
+                group.as_ref().map(|(_, rest)| *rest)
+            } else {
+                package.map(|(_, rest)| rest)
+            };
+            // Must be followed by a dash or an @ to be accepted
+            if let Some(suffix) =
+                rest.and_then(|rest| rest.strip_prefix('-').or_else(|| rest.strip_prefix('@')))
+            {
+                tag_suffix = suffix;
+                if group_wins {
+                    announcing_group = group.map(|(group, _)| group);
+                } else {
+                    announcing_package = package.map(|(package, _)| package);
                 }
             }
         }
 
         // At this point, assuming the input is valid, tag_suffix should just be the version
-        // component with an optional "v" prefix, so strip that "v"
+        // component in tag-format (by default with an optional "v" prefix), so strip that
+        if let Some(version) = graph.inner.version_from_tag(tag_suffix) {
+            tag_suffix = version;
+        }
         if let Some(suffix) = tag_suffix.strip_prefix('v') {
             tag_suffix = suffix;
         }
 
         // Now parse the version out
-        match tag_suffix.parse::<Version>() {
+        match graph.inner.versioning.parse_version(tag_suffix) {
             Ok(version) => {
                 // Register whether we're announcing a prerelease
-                announcing_prerelease = !version.pre.is_empty();
+                announcing_prerelease = graph.inner.versioning.is_prerelease(&version);
 
                 // If there's an announcing package, validate that the version matches
                 if let Some(pkg_idx) = announcing_package {
@@ -2641,29 +7274,47 @@ fn parse_tag(graph: &DistGraphBuilder, tag: Option<&str>) -> DistResult<PartialA
                         }
                     }
                 } else {
-                    // We had no announcing_package, so looks like we're doing a unified release.
-                    // Set this value to indicate that.
+                    // We had no announcing_package, so looks like we're doing a unified release
+                    // (or a group release, which has its own version). Set this value to
+                    // indicate that.
                     announcing_version = Some(version);
                 }
             }
             Err(e) => {
+                // If this doesn't even look like a version, it's probably a package prefix
+                // we didn't understand, so explain which ones we do
+                if announcing_package.is_none()
+                    && !tag_suffix.starts_with(|c: char| c.is_ascii_digit())
+                {
+                    return Err(DistError::NoTagMatch {
+                        tag: tag.clone(),
+                        help: tag_format_help(graph, tag_suffix),
+                    });
+                }
                 return Err(DistError::TagVersionParse {
                     tag: tag.clone(),
                     details: e,
-                })
+                });
             }
         }
 
         // If none of the approaches work, refuse to proceed
         if announcing_package.is_none() && announcing_version.is_none() {
-            return Err(DistError::NoTagMatch { tag: tag.clone() });
+            return Err(DistError::NoTagMatch {
+                tag: tag.clone(),
+                help: tag_format_help(graph, tag_suffix),
+            });
         }
     }
+    let announcing_group = announcing_group
+        .map(|group| group_packages(graph, group))
+        .transpose()?;
     Ok(PartialAnnouncementTag {
         tag: announcement_tag,
         prerelease: announcing_prerelease,
         version: announcing_version,
         package: announcing_package,
+        group: announcing_group,
     })
 }
 
@@ -2674,7 +7325,7 @@ fn parse_tag(graph: &DistGraphBuilder, tag: Option<&str>) -> DistResult<PartialA
 fn select_packages(
     graph: &DistGraphBuilder,
     announcing: &PartialAnnouncementTag,
-) -> Vec<(PackageIdx, Vec<String>)> {
+) -> DistResult<Vec<(PackageIdx, Vec<String>)>> {
     info!("");
     info!("selecting packages from workspace: ");
     // Choose which binaries we want to release
@@ -2697,16 +7348,54 @@ fn select_packages(
             info!("  {}", sty.apply_to(pkg_name));
         }
 
-        // Report each binary and potentially add it to the Release for this package
+        // Report each binary (including any from binaries-from) and potentially add it
+        // to the Release for this package
+        let bin_filter = graph.package_metadata(pkg_id).bin_filter.as_ref();
+        let borrowed = graph.borrowed_binaries(pkg_id)?;
+        let binaries = pkg
+            .binaries
+            .iter()
+            .map(|binary| (binary, String::new()))
+            .chain(borrowed.iter().map(|(member, binary)| {
+                let member = &graph.workspace().package(*member).name;
+                (binary, format!(" (from {member})"))
+            }));
         let mut rust_binaries = vec![];
-        for binary in &pkg.binaries {
-            info!("    {}", sty.apply_to(format!("[bin] {}", binary)));
-            // In the future might want to allow this to be granular for each binary
-            if disabled_reason.is_none() {
+        for (binary, from) in binaries {
+            if bin_filter.is_some_and(|names| !names.contains(binary)) {
+                info!(
+                    "    {}",
+                    disabled_sty.apply_to(format!("[bin] {}{from} (bin-filter)", binary))
+                );
+                continue;
+            }
+            info!("    {}", sty.apply_to(format!("[bin] {}{from}", binary)));
+            if disabled_reason.is_none() && !rust_binaries.contains(binary) {
                 rust_binaries.push(binary.to_owned());
             }
         }
 
+        // Make sure nothing we were told to expect has gone missing (including
+        // when *all* the binaries have gone missing and the package looks like a library)
+        if disabled_reason.is_none() || pkg.binaries.is_empty() {
+            let required = graph.package_metadata(pkg_id).required_binaries.iter();
+            let available = pkg
+                .binaries
+                .iter()
+                .chain(borrowed.iter().map(|(_, binary)| binary))
+                .cloned()
+                .collect::<Vec<_>>();
+            for binary in required.flatten() {
+                if !available.contains(binary) {
+                    return Err(DistError::MissingRequiredBinary {
+                        package: pkg_name.clone(),
+                        binary: binary.clone(),
+                        available,
+                    });
+                }
+            }
+        }
+
         // If any binaries were accepted for this package, it's a Release!
         if !rust_binaries.is_empty() {
             rust_releases.push((pkg_id, rust_binaries));
@@ -2714,7 +7403,7 @@ fn select_packages(
     }
     info!("");
 
-    rust_releases
+    Ok(rust_releases)
 }
 
 /// Get a list of possible version --tags to use, given a list of packages we want to Announce
@@ -2758,7 +7447,8 @@ fn tag_help(
     help.push_str("\n\n");
     help.push_str("Here are some options:\n\n");
     for (version, packages) in &versions {
-        write!(help, "--tag=v{version} will Announce: ").unwrap();
+        let tag = graph.inner.tag_for_version(&version.to_string());
+        write!(help, "--tag={tag} will Announce: ").unwrap();
         let mut multi_package = false;
         for &pkg_id in packages {
             let info = &graph.workspace().package(pkg_id);
@@ -2774,9 +7464,11 @@ fn tag_help(
     help.push('\n');
     let info = &graph.workspace().package(*some_pkg);
     let some_tag = format!(
-        "--tag={}-v{}",
+        "--tag={}-{}",
         info.name,
-        info.version.as_ref().unwrap().cargo()
+        graph
+            .inner
+            .tag_for_version(&info.version.as_ref().unwrap().cargo().to_string())
     );
 
     writeln!(
@@ -2784,10 +7476,122 @@ fn tag_help(
         "you can also request any single package with {some_tag}"
     )
     .unwrap();
+    // Groups can be announced at any version, so just reuse one of the options
+    let group_version = graph
+        .inner
+        .tag_for_version(&versions.first_key_value().unwrap().0.to_string());
+    for (group, packages) in graph
+        .workspace_metadata
+        .announcement_groups
+        .iter()
+        .flatten()
+    {
+        writeln!(
+            help,
+            "or the packages in the {group} group ({}) with --tag={group}-{group_version}",
+            packages.join(", ")
+        )
+        .unwrap();
+    }
 
     help
 }
 
+/// Get a help printout for the shapes of --tag we accept, for a tag we couldn't parse
+///
+/// If the tag starts with the name of a package (but used some other delimiter) the
+/// examples use that package, otherwise they just use the first one in the workspace.
+fn tag_format_help(graph: &DistGraphBuilder, tag: &str) -> String {
+    let package = strip_prefix_package(tag, graph)
+        .map(|(pkg_idx, _)| pkg_idx)
+        .or_else(|| {
+            graph
+                .workspace()
+                .packages()
+                .next()
+                .map(|(pkg_idx, _)| pkg_idx)
+        });
+    let Some(package) = package.map(|pkg_idx| graph.workspace().package(pkg_idx)) else {
+        return "tags should look like v1.0.0".to_owned();
+    };
+    let name = &package.name;
+    let version = package
+        .version
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "1.0.0".to_owned());
+    let tag_version = graph.inner.tag_for_version(&version);
+    format!(
+        "tags for a single package should look like {name}-{tag_version}, {name}@{version}, or some/prefix/{name}/{tag_version}, and tags for every package with a version should look like {tag_version}"
+    )
+}
+
+/// Find the packages in an announcement-group
+fn group_packages(
+    graph: &DistGraphBuilder,
+    group: String,
+) -> DistResult<(String, Vec<PackageIdx>)> {
+    let names = graph
+        .workspace_metadata
+        .announcement_groups
+        .as_ref()
+        .and_then(|groups| groups.get(&group))
+        .expect("announcement-group went missing!?");
+    let mut packages = vec![];
+    for name in names {
+        let Some((pkg_idx, _)) = graph
+            .workspace()
+            .packages()
+            .find(|(_, package)| &package.name == name)
+        else {
+            return Err(DistError::UnknownGroupPackage {
+                group,
+                package: name.clone(),
+            });
+        };
+        packages.push(pkg_idx);
+    }
+    Ok((group, packages))
+}
+
+/// Split git-cliff's output for a release into a title and notes
+///
+/// Templates usually start with a heading for the release (like `## [1.0.0] - 2024-01-01`),
+/// which becomes the title. If there isn't one, the tag is the title.
+pub(crate) fn parse_cliff_notes(output: &str, tag: &str) -> Option<ChangelogInfo> {
+    let output = output.trim();
+    if output.is_empty() {
+        return None;
+    }
+    let (first_line, rest) = output.split_once('\n').unwrap_or((output, ""));
+    let info = if first_line.starts_with('#') {
+        ChangelogInfo {
+            title: first_line.trim_start_matches('#').trim().to_owned(),
+            body: rest.trim().to_owned(),
+        }
+    } else {
+        ChangelogInfo {
+            title: tag.to_owned(),
+            body: output.to_owned(),
+        }
+    };
+    Some(info)
+}
+
+/// Gather up the extra-publish-jobs from the config and the extra-publish-jobs-file
+fn load_extra_publish_jobs(
+    inline_jobs: &Option<Vec<ExtraPublishJob>>,
+    jobs_file: Option<&Utf8Path>,
+) -> DistResult<Vec<ExtraPublishJob>> {
+    let mut jobs = inline_jobs.clone().unwrap_or_default();
+    if let Some(jobs_file) = jobs_file {
+        let src = SourceFile::load_local(jobs_file)?;
+        let file: ExtraPublishJobsFile = src.deserialize_toml()?;
+        jobs.extend(file.publish_jobs);
+    }
+    Ok(jobs)
+}
+
 /// Try to strip-prefix a package name from the given input, preferring whichever one is longest
 /// (to disambiguate situations where you have `my-app` and `my-app-helper`).
 ///
@@ -2811,3 +7615,20 @@ fn strip_prefix_package<'a>(
     }
     result
 }
+
+/// Same as [`strip_prefix_package`][] but for the names of announcement-groups
+fn strip_prefix_group<'a>(input: &'a str, graph: &DistGraphBuilder) -> Option<(String, &'a str)> {
+    let groups = graph.workspace_metadata.announcement_groups.as_ref()?;
+    let mut result: Option<(String, &'a str)> = None;
+    for group in groups.keys() {
+        if let Some(rest) = input.strip_prefix(group.as_str()) {
+            if let Some((_, best)) = result {
+                if best.len() <= rest.len() {
+                    continue;
+                }
+            }
+            result = Some((group.clone(), rest))
+        }
+    }
+    result
+}
@@ -0,0 +1,120 @@
+//! Tests for generating announcement contents like Github Release bodies
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::{ArtifactMode, CiStyle},
+    gather_work_for_workspace, DistGraphBuilder, RELEASE_NOTES_FILE_NAME,
+};
+
+const CHANGELOG: &str = "* fixed the axolotl's gills\n* made the axolotl say more things";
+const DOWNLOAD_URL: &str = "https://github.com/axodotdev/axolotlsay/releases/download/v1.0.0";
+
+#[test]
+fn full_release_notes_in_body() {
+    let workspace = workspace_just_axo();
+    let tools = mock_tools();
+    let mut graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    graph.inner.ci_style = vec![CiStyle::Github];
+    graph.inner.artifact_download_url = Some(DOWNLOAD_URL.to_owned());
+    graph.inner.announcement_changelog = Some(CHANGELOG.to_owned());
+    graph.compute_announcement_github();
+
+    let body = graph.inner.announcement_github_body.unwrap();
+    assert!(body.contains(CHANGELOG));
+    assert_eq!(graph.inner.announcement_release_notes, None);
+}
+
+#[test]
+fn short_body_with_release_notes_asset() {
+    let workspace = workspace_just_axo();
+    let tools = mock_tools();
+    let mut graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    graph.inner.ci_style = vec![CiStyle::Github];
+    graph.inner.artifact_download_url = Some(DOWNLOAD_URL.to_owned());
+    graph.inner.announcement_changelog = Some(CHANGELOG.to_owned());
+    graph.inner.release_notes_asset = true;
+    graph.compute_announcement_github();
+
+    // The body just points at the notes...
+    let body = graph.inner.announcement_github_body.unwrap();
+    assert!(!body.contains(CHANGELOG));
+    assert!(body.contains(&format!("{DOWNLOAD_URL}/{RELEASE_NOTES_FILE_NAME}")));
+
+    // ...which have the whole changelog
+    let notes = graph.inner.announcement_release_notes.unwrap();
+    assert!(notes.contains(CHANGELOG));
+}
+
+#[test]
+fn short_body_without_download_url() {
+    let workspace = workspace_just_axo();
+    let short_body = |tag: Option<&str>| {
+        let mut graph =
+            DistGraphBuilder::new(mock_tools(), &workspace, ArtifactMode::All, true).unwrap();
+        graph.inner.ci_style = vec![CiStyle::Github];
+        graph.inner.announcement_tag = tag.map(|tag| tag.to_owned());
+        graph.inner.announcement_changelog = Some(CHANGELOG.to_owned());
+        graph.inner.release_notes_asset = true;
+        graph.compute_announcement_github();
+        graph.inner.announcement_github_body.unwrap()
+    };
+
+    // The notes are still pointed to, relative to the release's page
+    let body = short_body(Some("v1.0.0"));
+    assert!(!body.contains(CHANGELOG));
+    assert!(body.contains(&format!(
+        "See [{RELEASE_NOTES_FILE_NAME}](../download/v1.0.0/{RELEASE_NOTES_FILE_NAME})"
+    )));
+    // ...or at least mentioned
+    let body = short_body(None);
+    assert!(body.contains(&format!(
+        "See the {RELEASE_NOTES_FILE_NAME} attached to this release"
+    )));
+}
+
+fn body_with_download_table(download_table: Option<&str>) -> String {
+    let mut dist = json!({
+        "installers": ["shell"],
+        "targets": ["x86_64-unknown-linux-gnu"],
+    });
+    if let Some(download_table) = download_table {
+        dist["download-table"] = json!(download_table);
+    }
+    let mut workspace = workspace_axo_dist(dist);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = mock_config();
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    graph.announcement_github_body.unwrap()
+}
+
+#[test]
+fn download_table_by_default() {
+    let body = body_with_download_table(None);
+    assert!(body.contains("## Download "), "{body}");
+    assert!(
+        body.contains("|  File  | Platform | Checksum |\n"),
+        "{body}"
+    );
+    assert!(!body.contains("<details>"), "{body}");
+}
+
+#[test]
+fn collapsed_download_table() {
+    let body = body_with_download_table(Some("collapsed"));
+    let table =
+        "<details>\n<summary>Show all downloads</summary>\n\n|  File  | Platform | Checksum |\n";
+    let start = body.find(table).expect(&body);
+    assert!(body[start..].contains("|\n\n</details>\n"), "{body}");
+    // The install instructions are unchanged
+    assert!(body.contains("## Install "), "{body}");
+}
+
+#[test]
+fn no_download_table() {
+    let body = body_with_download_table(Some("none"));
+    assert!(!body.contains("## Download "), "{body}");
+    assert!(!body.contains("|  File  |"), "{body}");
+    assert!(body.contains("## Install "), "{body}");
+}
@@ -0,0 +1,88 @@
+//! Tests for macOS .app bundles
+
+use super::mock::*;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl,
+    config::{Config, InstallerStyle, ZipStyle},
+    gather_work_for_workspace, ArtifactKind, BuildStep, ZipDirStep,
+};
+
+const TARGETS: &[&str] = &["aarch64-apple-darwin", "x86_64-unknown-linux-gnu"];
+
+#[test]
+fn app_bundle_for_apple_variants() {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["app"],
+        "targets": TARGETS,
+        "app-bundle-identifier": "dev.axo.axolotlsay",
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::App],
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    // Only the apple variant gets a bundle
+    let bundles = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::AppBundle(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(bundles.len(), 1);
+    let (artifact, info) = bundles[0];
+    assert_eq!(
+        artifact.id,
+        format!("{BIN_AXO_NAME}-aarch64-apple-darwin.app.zip")
+    );
+    assert_eq!(artifact.target_triples, ["aarch64-apple-darwin"]);
+    assert!(!artifact.is_global);
+    assert_eq!(info.identifier, "dev.axo.axolotlsay");
+    assert_eq!(info.version, BIN_AXO_VER);
+    assert_eq!(info.executable, BIN_AXO_NAME);
+
+    // The binary goes under Contents/MacOS, and the zip unpacks to Foo.app
+    let app_name = format!("{BIN_AXO_NAME}.app");
+    assert_eq!(info.bundle_dir.file_name(), Some(&*app_name));
+    assert_eq!(
+        artifact.required_binaries.values().collect::<Vec<_>>(),
+        [&info.bundle_dir.join("Contents/MacOS").join(BIN_AXO_NAME)]
+    );
+    assert!(graph.build_steps.iter().any(|step| matches!(
+        step,
+        BuildStep::Zip(ZipDirStep { src_path, with_root: Some(root), zip_style: ZipStyle::Zip, executables, .. })
+            if *src_path == info.bundle_dir
+                && *root == app_name
+                && *executables == [Utf8PathBuf::from("Contents/MacOS").join(BIN_AXO_NAME)]
+    )));
+
+    // Building it lays out the rest of the bundle
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-app-bundle-{}", std::process::id()));
+    let icon = dir.join("icon.icns");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&icon, "icns").unwrap();
+    let mut info = info.clone();
+    info.bundle_dir = dir.join(&app_name);
+    info.icon = Some(icon);
+    info.build().unwrap();
+    let plist = std::fs::read_to_string(info.bundle_dir.join("Contents/Info.plist"));
+    let icon = std::fs::read_to_string(
+        info.bundle_dir
+            .join("Contents/Resources")
+            .join(format!("{BIN_AXO_NAME}.icns")),
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(plist.unwrap().contains(&format!(
+        "    <key>CFBundleExecutable</key>\n    <string>{BIN_AXO_NAME}</string>\n"
+    )));
+    assert_eq!(icon.unwrap(), "icns");
+}
@@ -0,0 +1,91 @@
+//! Tests for AppImage installers
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{appimage::APPIMAGE_BIN_DIR, InstallerImpl},
+    config::{Config, InstallerStyle},
+    ArtifactKind, BuildStep, DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-apple-darwin"];
+
+fn graph_with_appimages(icon: Option<&str>) -> DistGraph {
+    let mut dist = json!({
+        "installers": ["appimage"],
+        "targets": TARGETS,
+    });
+    if let Some(icon) = icon {
+        dist["appimage-icon"] = json!(icon);
+    }
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::AppImage],
+        ..mock_config()
+    };
+    graph_for_dist_metadata(dist, &cfg)
+}
+
+#[test]
+fn appimage_for_linux_only() {
+    let graph = graph_with_appimages(Some("assets/axolotl.png"));
+    let appimages = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::AppImage(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(appimages.len(), 1);
+    let (artifact, info) = appimages[0];
+    assert_eq!(artifact.target_triples, ["x86_64-unknown-linux-gnu"]);
+    assert!(artifact.id.ends_with(".AppImage"));
+    assert!(artifact.checksum.is_some());
+
+    // The binary goes in usr/bin and the icon in the root of the AppDir
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(
+        dest_path,
+        &info.app_dir.join(APPIMAGE_BIN_DIR).join(BIN_AXO_NAME)
+    );
+    let static_assets = &artifact.archive.as_ref().unwrap().static_assets;
+    assert_eq!(static_assets.len(), 1);
+    assert!(static_assets[0].1.ends_with("assets/axolotl.png"));
+
+    assert_eq!(info.main_binary, BIN_AXO_NAME);
+    assert_eq!(info.arch, "x86_64");
+    assert_eq!(info.desktop.name, BIN_AXO_NAME);
+    assert_eq!(info.desktop.icon, "axolotl");
+}
+
+#[test]
+fn appimage_icon_copied_before_generating() {
+    let graph = graph_with_appimages(Some("assets/axolotl.png"));
+    let copy_icon = graph
+        .build_steps
+        .iter()
+        .position(|step| matches!(step, BuildStep::CopyFile(copy) if copy.src_path.ends_with("axolotl.png")))
+        .expect("icon wasn't copied");
+    let generate = graph
+        .build_steps
+        .iter()
+        .position(|step| {
+            matches!(
+                step,
+                BuildStep::GenerateInstaller(InstallerImpl::AppImage(_))
+            )
+        })
+        .expect("AppImage wasn't generated");
+    assert!(copy_icon < generate);
+}
+
+#[test]
+fn no_appimage_without_icon() {
+    let graph = graph_with_appimages(None);
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::AppImage(_)))));
+}
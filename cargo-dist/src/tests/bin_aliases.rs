@@ -0,0 +1,109 @@
+//! Tests for installing binaries under another name
+
+use super::mock::*;
+use serde_json::json;
+use std::collections::BTreeSet;
+
+use crate::{
+    backend::{
+        installer::{shell::render_install_sh_script, InstallerImpl},
+        templates::TEMPLATE_INSTALLER_PS1,
+    },
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"];
+const ALIAS: &str = "helper";
+
+#[test]
+fn installers_use_bin_aliases() {
+    let mut workspace = mock_workspace(vec![pkg_helper_dist(json!({
+        "installers": ["shell", "powershell", "npm"],
+        "targets": TARGETS,
+        "bin-aliases": { BIN_HELPER_NAME: ALIAS },
+    }))]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![
+            InstallerStyle::Shell,
+            InstallerStyle::Powershell,
+            InstallerStyle::Npm,
+        ],
+        announcement_tag: Some(format!("v{BIN_HELPER_VER}")),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    // The archives (and builds) keep the original names
+    for artifact in &graph.artifacts {
+        if !matches!(artifact.kind, ArtifactKind::ExecutableZip(_)) {
+            continue;
+        }
+        let exe = if artifact.target_triples[0].contains("windows") {
+            ".exe"
+        } else {
+            ""
+        };
+        let names = artifact
+            .required_binaries
+            .values()
+            .map(|path| path.file_name().unwrap().to_owned())
+            .collect::<BTreeSet<_>>();
+        let expected = [
+            format!("{BIN_HELPER_NAME}{exe}"),
+            format!("{BIN_HELPER_NAME2}{exe}"),
+        ];
+        assert_eq!(names, expected.into_iter().collect());
+    }
+    for binary in &graph.binaries {
+        let exe = if binary.target.contains("windows") {
+            ".exe"
+        } else {
+            ""
+        };
+        assert_eq!(binary.file_name, format!("{}{exe}", binary.name));
+        if binary.name == BIN_HELPER_NAME {
+            assert_eq!(binary.installed_file_name, format!("{ALIAS}{exe}"));
+        } else {
+            assert_eq!(binary.installed_file_name, binary.file_name);
+        }
+    }
+
+    // The installers know to install the aliased binary under its alias
+    let mut checked = 0;
+    for artifact in &graph.artifacts {
+        match &artifact.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => {
+                for fragment in &info.artifacts {
+                    assert_eq!(fragment.bin_aliases.len(), 1);
+                }
+                let script = render_install_sh_script(&graph.templates, info).unwrap();
+                assert!(script.contains(&format!(
+                    "            _bins=\"{BIN_HELPER_NAME}:{ALIAS} {BIN_HELPER_NAME2}\"\n"
+                )));
+                assert!(script
+                    .contains("        ensure cp \"$_bin\" \"$_install_dir/$_installed_name\"\n"));
+                checked += 1;
+            }
+            ArtifactKind::Installer(InstallerImpl::Powershell(info)) => {
+                let script = graph
+                    .templates
+                    .render_file_to_clean_string(TEMPLATE_INSTALLER_PS1, info)
+                    .unwrap();
+                assert!(script.contains(&format!(
+                    "      \"aliases\" = @{{\n        \"{BIN_HELPER_NAME}.exe\" = \"{ALIAS}.exe\"\n      }}\n"
+                )));
+                checked += 1;
+            }
+            ArtifactKind::Installer(InstallerImpl::Npm(info)) => {
+                assert_eq!(info.bin_aliases[BIN_HELPER_NAME], ALIAS);
+                assert!(!info.bin_aliases.contains_key(BIN_HELPER_NAME2));
+                checked += 1;
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(checked, 3);
+}
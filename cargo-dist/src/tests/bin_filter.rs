@@ -0,0 +1,98 @@
+//! Tests for restricting which binaries get dist-ed/installed
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl, config::Config, gather_work_for_workspace, ArtifactKind,
+    DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"];
+
+fn graph_with(dist: serde_json::Value) -> DistGraph {
+    let mut workspace = mock_workspace(vec![pkg_helper_dist(dist)]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        announcement_tag: Some(format!("v{BIN_HELPER_VER}")),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn installer_bin_filter_restricts_installers() {
+    let graph = graph_with(json!({
+        "installers": ["shell", "powershell", "npm"],
+        "targets": TARGETS,
+        "installer-bin-filter": {
+            "npm": [BIN_HELPER_NAME2],
+            "powershell": [BIN_HELPER_NAME2],
+        },
+    }));
+
+    // The archives still have everything
+    let zip = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .unwrap();
+    assert_eq!(zip.required_binaries.len(), 2);
+
+    let mut checked = 0;
+    for artifact in &graph.artifacts {
+        let (fragments, filtered) = match &artifact.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => (&info.artifacts, false),
+            ArtifactKind::Installer(InstallerImpl::Powershell(info)) => (&info.artifacts, true),
+            ArtifactKind::Installer(InstallerImpl::Npm(info)) => {
+                assert_eq!(info.bins, [BIN_HELPER_NAME2]);
+                (&info.inner.artifacts, true)
+            }
+            _ => continue,
+        };
+        for fragment in fragments {
+            let exe = if fragment.target_triples[0].contains("windows") {
+                ".exe"
+            } else {
+                ""
+            };
+            let mut expected = vec![format!("{BIN_HELPER_NAME2}{exe}")];
+            if !filtered {
+                expected.insert(0, format!("{BIN_HELPER_NAME}{exe}"));
+            }
+            assert_eq!(fragment.binaries, expected, "{}", artifact.id);
+        }
+        checked += 1;
+    }
+    assert_eq!(checked, 3);
+}
+
+#[test]
+fn bin_filter_restricts_release() {
+    let graph = graph_with(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+        "bin-filter": [BIN_HELPER_NAME],
+    }));
+
+    let release = &graph.releases[0];
+    assert_eq!(release.bins.len(), 1);
+    assert_eq!(release.bins[0].1, BIN_HELPER_NAME);
+    for &variant_idx in &release.variants {
+        let variant = graph.variant(variant_idx);
+        assert_eq!(variant.binaries.len(), 1);
+        assert_eq!(graph.binary(variant.binaries[0]).name, BIN_HELPER_NAME);
+    }
+    let shell = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap();
+    for fragment in &shell.artifacts {
+        assert_eq!(fragment.binaries, [BIN_HELPER_NAME]);
+    }
+}
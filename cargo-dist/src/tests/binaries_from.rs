@@ -0,0 +1,89 @@
+//! Tests for releasing a package's binaries-from (binaries defined by other packages)
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    errors::{DistError, Result},
+    gather_work_for_workspace, BuildStep, CargoTargetPackages, DistGraph,
+};
+
+const CLI_NAME: &str = "axolotlsay-cli";
+
+/// A thin axolotlsay package whose binary is defined by axolotlsay-cli
+fn graph_with(package_dist: serde_json::Value) -> Result<DistGraph> {
+    let mut workspace = mock_workspace(vec![
+        PackageInfo {
+            binaries: vec![],
+            ..pkg_axo_dist(package_dist)
+        },
+        PackageInfo {
+            binaries: vec![BIN_AXO_NAME.to_owned()],
+            cargo_package_id: Some(guppy::PackageId::new(format!(
+                "{CLI_NAME} {BIN_AXO_VER} (path+file:///axolotlsay/cli)"
+            ))),
+            ..mock_package(CLI_NAME, BIN_AXO_VER)
+        },
+    ]);
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "targets": ["x86_64-unknown-linux-gnu"],
+            "precise-builds": true,
+        }
+    }));
+    gather_work_for_workspace(&mock_config(), mock_tools(), &workspace)
+}
+
+#[test]
+fn release_builds_binary_from_member() {
+    let graph = graph_with(json!({ "binaries-from": [CLI_NAME] })).unwrap();
+
+    // The member crate doesn't get its own release, the thin package releases its binary
+    assert_eq!(graph.releases.len(), 1);
+    let release = &graph.releases[0];
+    assert_eq!(release.app_name, BIN_AXO_NAME);
+    let binaries = graph
+        .binaries
+        .iter()
+        .map(|binary| (binary.name.as_str(), binary.pkg_spec.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(binaries, vec![(BIN_AXO_NAME, CLI_NAME)]);
+    assert_eq!(
+        graph.binaries[0].pkg_id.repr(),
+        format!("{CLI_NAME} {BIN_AXO_VER} (path+file:///axolotlsay/cli)")
+    );
+
+    // So it's built with --package=axolotlsay-cli
+    let packages = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(step) => Some(&step.package),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(matches!(
+        &packages[..],
+        [CargoTargetPackages::Package(spec)] if spec == CLI_NAME
+    ));
+}
+
+#[test]
+fn member_is_released_without_binaries_from() {
+    let graph = graph_with(json!({})).unwrap();
+    assert_eq!(graph.releases.len(), 1);
+    assert_eq!(graph.releases[0].app_name, CLI_NAME);
+}
+
+#[test]
+fn binaries_from_unknown_package() {
+    let err = graph_with(json!({ "binaries-from": ["axolotlsay-typo"] })).unwrap_err();
+    let Some(DistError::UnknownBinariesFromPackage { package, member }) =
+        err.downcast_ref::<DistError>()
+    else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(package, BIN_AXO_NAME);
+    assert_eq!(member, "axolotlsay-typo");
+}
@@ -0,0 +1,104 @@
+//! Tests for `[package.metadata.binstall]` generation
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{backend::binstall::BinstallFormat, config::Config, ArtifactKind, DistGraph};
+
+const TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+];
+
+fn graph_with_binstall(dist: serde_json::Value) -> DistGraph {
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    graph_for_dist_metadata(dist, &cfg)
+}
+
+/// Fill in a template the way cargo-binstall would
+fn resolve(template: &str, target: &str) -> String {
+    let binary_ext = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    template
+        .replace("{ name }", BIN_AXO_NAME)
+        .replace("{ version }", BIN_AXO_VER)
+        .replace("{ target }", target)
+        .replace("{ bin }", BIN_AXO_NAME)
+        .replace("{ binary-ext }", binary_ext)
+}
+
+#[test]
+fn binstall_templates_resolve_to_archives() {
+    let graph = graph_with_binstall(json!({
+        "binstall-metadata": true,
+        "targets": TARGETS,
+    }));
+    let binstall = graph.releases[0]
+        .binstall
+        .as_ref()
+        .expect("no binstall metadata!?");
+    assert_eq!(binstall.format.pkg_fmt, "txz");
+    assert_eq!(
+        binstall.overrides.keys().collect::<Vec<_>>(),
+        ["x86_64-pc-windows-msvc"]
+    );
+
+    let download_url = graph.artifact_download_url.as_ref().unwrap();
+    for target in TARGETS {
+        let format: &BinstallFormat = binstall.overrides.get(*target).unwrap_or(&binstall.format);
+        let archive = graph
+            .artifacts
+            .iter()
+            .find(|a| {
+                matches!(a.kind, ArtifactKind::ExecutableZip(_))
+                    && a.target_triples == [target.to_string()]
+            })
+            .expect("no archive for target!?");
+        assert_eq!(
+            resolve(&format.pkg_url, target),
+            format!("{download_url}/{}", archive.id)
+        );
+
+        // bin-dir has to find the binary wherever the archive put it
+        let zip = archive.archive.as_ref().unwrap();
+        let binary = graph
+            .binaries
+            .iter()
+            .find(|b| b.name == BIN_AXO_NAME && b.target == *target)
+            .unwrap();
+        let expected = match &zip.with_root {
+            Some(root) => root.join(&binary.file_name).to_string(),
+            None => binary.file_name.clone(),
+        };
+        assert_eq!(resolve(&format.bin_dir, target), expected);
+    }
+}
+
+#[test]
+fn binstall_templates_follow_slim_archives() {
+    let graph = graph_with_binstall(json!({
+        "binstall-metadata": true,
+        "slim-archives": true,
+        "targets": TARGETS,
+    }));
+    let binstall = graph.releases[0].binstall.as_ref().unwrap();
+    let target = "x86_64-unknown-linux-gnu";
+    let url = resolve(&binstall.format.pkg_url, target);
+    assert!(
+        url.ends_with(&format!("/{BIN_AXO_NAME}-{target}-slim.tar.xz")),
+        "{url}"
+    );
+}
+
+#[test]
+fn no_binstall_by_default() {
+    let graph = graph_with_binstall(json!({ "targets": TARGETS }));
+    assert!(graph.releases[0].binstall.is_none());
+}
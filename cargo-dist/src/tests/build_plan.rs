@@ -0,0 +1,115 @@
+//! Tests for the dependencies between build steps
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, BuildStep, DistGraph};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"];
+
+fn graph() -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+fn steps_where(graph: &DistGraph, pred: impl Fn(&BuildStep) -> bool) -> Vec<usize> {
+    graph
+        .build_steps
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| pred(step))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Which wave of the plan each step is in
+fn wave_of(graph: &DistGraph) -> Vec<usize> {
+    let mut wave_of = vec![0; graph.build_steps.len()];
+    for (wave, steps) in graph.build_plan.waves().iter().enumerate() {
+        for &step in steps {
+            wave_of[step] = wave;
+        }
+    }
+    wave_of
+}
+
+#[test]
+fn plan_covers_every_step_once() {
+    let graph = graph();
+    let mut planned = graph
+        .build_plan
+        .waves()
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    planned.sort();
+    assert_eq!(planned, (0..graph.build_steps.len()).collect::<Vec<_>>());
+    for (step, deps) in graph.build_plan.deps.iter().enumerate() {
+        assert!(deps.iter().all(|&dep| dep < step));
+    }
+}
+
+#[test]
+fn checksums_wait_for_their_archives() {
+    let graph = graph();
+    let wave_of = wave_of(&graph);
+    let mut archive_checksums = 0;
+    for checksum_idx in steps_where(&graph, |step| matches!(step, BuildStep::Checksum(_))) {
+        let BuildStep::Checksum(checksum) = &graph.build_steps[checksum_idx] else {
+            unreachable!()
+        };
+        let zips = steps_where(
+            &graph,
+            |step| matches!(step, BuildStep::Zip(zip) if zip.dest_path == checksum.src_path),
+        );
+        let Some(&zip_idx) = zips.first() else {
+            // (the installer's checksum, installers are covered below)
+            continue;
+        };
+        archive_checksums += 1;
+        assert!(zip_idx < checksum_idx);
+        assert!(graph.build_plan.deps[checksum_idx].contains(&zip_idx));
+        assert!(wave_of[zip_idx] < wave_of[checksum_idx]);
+    }
+    assert_eq!(archive_checksums, TARGETS.len());
+}
+
+#[test]
+fn independent_zips_can_run_together() {
+    let graph = graph();
+    let zips = steps_where(&graph, |step| matches!(step, BuildStep::Zip(_)));
+    assert_eq!(zips.len(), TARGETS.len());
+    assert!(!graph.build_plan.depends_on(zips[1], zips[0]));
+
+    // Each zip waits for the build of its binaries (and the builds go one at a time)
+    let builds = steps_where(&graph, |step| matches!(step, BuildStep::Cargo(_)));
+    assert_eq!(builds.len(), TARGETS.len());
+    assert!(graph.build_plan.depends_on(builds[1], builds[0]));
+    for &zip in &zips {
+        assert!(builds
+            .iter()
+            .any(|&build| graph.build_plan.deps[zip].contains(&build)));
+    }
+}
+
+#[test]
+fn installers_wait_for_everything_before_them() {
+    let graph = graph();
+    let installers = steps_where(&graph, |step| {
+        matches!(step, BuildStep::GenerateInstaller(_))
+    });
+    assert_eq!(installers.len(), 1);
+    let installer = installers[0];
+    assert_eq!(
+        graph.build_plan.deps[installer],
+        (0..installer).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,61 @@
+//! Tests for build-profile
+
+use super::mock::*;
+use axoproject::WorkspaceInfo;
+use serde_json::json;
+
+use crate::{
+    config::ArtifactMode, errors::DistError, gather_work_for_workspace, BuildStep,
+    DistGraphBuilder, PROFILE_DIST,
+};
+
+fn workspace_with(workspace_dist: serde_json::Value) -> WorkspaceInfo {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": ["x86_64-unknown-linux-gnu"],
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    workspace
+}
+
+fn profiles(workspace: &WorkspaceInfo) -> Vec<String> {
+    let cfg = mock_config();
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), workspace).unwrap();
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(build) => Some(build.profile.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn dist_profile_by_default() {
+    let workspace = workspace_with(json!({}));
+    assert_eq!(profiles(&workspace), [PROFILE_DIST]);
+}
+
+#[test]
+fn build_profile_override() {
+    let workspace = workspace_with(json!({ "build-profile": "release-lto" }));
+    assert_eq!(profiles(&workspace), ["release-lto"]);
+}
+
+#[test]
+fn build_profile_cant_be_dist_subdir() {
+    let workspace = workspace_with(json!({ "build-profile": "distrib" }));
+    let res = DistGraphBuilder::new(mock_tools(), &workspace, ArtifactMode::All, true);
+    assert!(matches!(
+        res,
+        Err(DistError::BuildProfileCollision { profile }) if profile == "distrib"
+    ));
+
+    // ...whatever dist-subdir is
+    let workspace = workspace_with(json!({
+        "build-profile": "dist-output",
+        "dist-subdir": "dist-output",
+    }));
+    let res = DistGraphBuilder::new(mock_tools(), &workspace, ArtifactMode::All, true);
+    assert!(matches!(res, Err(DistError::BuildProfileCollision { .. })));
+}
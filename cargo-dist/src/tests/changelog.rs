@@ -0,0 +1,220 @@
+//! Tests for requiring release notes in the changelog (or generating them with git-cliff)
+
+use super::mock::*;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    config::Config,
+    errors::{DistError, Result},
+    gather_work_for_workspace, parse_cliff_notes, DistGraph, Tool, Tools,
+};
+
+fn graph_with(
+    workspace_dist: serde_json::Value,
+    changelog: Option<Utf8PathBuf>,
+) -> Result<DistGraph> {
+    graph_with_tag(
+        workspace_dist,
+        changelog,
+        None,
+        mock_tools(),
+        format!("v{BIN_AXO_VER}"),
+    )
+}
+
+fn graph_with_tag(
+    workspace_dist: serde_json::Value,
+    changelog: Option<Utf8PathBuf>,
+    workspace_dir: Option<Utf8PathBuf>,
+    tools: Tools,
+    tag: String,
+) -> Result<DistGraph> {
+    let mut workspace = workspace_axo_dist(json!({}));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    workspace.root_auto_includes.changelog = changelog;
+    if let Some(dir) = workspace_dir {
+        workspace.package_info[0].package_root = dir.join(BIN_AXO_NAME);
+        workspace.workspace_dir = dir;
+    }
+    let cfg = Config {
+        announcement_tag: Some(tag),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, tools, &workspace)
+}
+
+/// A temp dir for this test run
+fn test_dir() -> Utf8PathBuf {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write a changelog to a temp file
+fn changelog(name: &str, contents: &str) -> Utf8PathBuf {
+    let path = test_dir().join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn missing_changelog_is_skipped_by_default() {
+    let graph = graph_with(json!({}), None).unwrap();
+    assert!(graph.announcement_changelog.is_none());
+}
+
+#[test]
+fn missing_changelog_errors_when_required() {
+    let err = graph_with(json!({ "require-changelog": true }), None).unwrap_err();
+    let err = err.downcast_ref::<DistError>().unwrap();
+    let DistError::ChangelogMissing {
+        version,
+        changelog_owner,
+    } = err
+    else {
+        panic!("unexpected error {err:?}");
+    };
+    assert_eq!(version, BIN_AXO_VER);
+    assert_eq!(changelog_owner, "workspace");
+
+    // A changelog that doesn't mention the version doesn't help
+    let path = changelog(
+        "OLD_CHANGELOG.md",
+        "# Changelog\n\n## 0.9.0\n\n* an older release\n",
+    );
+    let err = graph_with(json!({ "require-changelog": true }), Some(path)).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DistError>(),
+        Some(DistError::ChangelogMissing { .. })
+    ));
+}
+
+#[test]
+fn empty_changelog_errors_when_required() {
+    let contents = format!("# Changelog\n\n## {BIN_AXO_VER}\n\n## 0.9.0\n\n* an older release\n");
+    let path = changelog("EMPTY_CHANGELOG.md", &contents);
+    let err = graph_with(json!({ "require-changelog": true }), Some(path.clone())).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DistError>(),
+        Some(DistError::ChangelogMissing { .. })
+    ));
+    // ...but an empty section is fine normally
+    graph_with(json!({}), Some(path)).unwrap();
+}
+
+#[test]
+fn changelog_satisfies_requirement() {
+    let contents = format!("# Changelog\n\n## {BIN_AXO_VER}\n\n* fixed the axolotl's gills\n");
+    let path = changelog("CHANGELOG.md", &contents);
+    let graph = graph_with(json!({ "require-changelog": true }), Some(path)).unwrap();
+    assert!(graph
+        .announcement_changelog
+        .unwrap()
+        .contains("fixed the axolotl's gills"));
+}
+
+#[test]
+fn cliff_notes_title() {
+    let output =
+        "## [1.0.0] - 2024-01-01\r\n\r\n### Features\r\n\r\n- taught the axolotl to wave\r\n";
+    let info = parse_cliff_notes(output, "v1.0.0").unwrap();
+    assert_eq!(info.title, "[1.0.0] - 2024-01-01");
+    assert_eq!(
+        info.body,
+        "### Features\r\n\r\n- taught the axolotl to wave"
+    );
+
+    // Without a heading the tag is the title
+    let info = parse_cliff_notes("- taught the axolotl to wave\n", "v1.0.0").unwrap();
+    assert_eq!(info.title, "v1.0.0");
+    assert_eq!(info.body, "- taught the axolotl to wave");
+
+    assert!(parse_cliff_notes("\n\n", "v1.0.0").is_none());
+}
+
+#[test]
+fn git_cliff_must_be_installed() {
+    let err = graph_with(json!({ "git-cliff": true }), None).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<DistError>(),
+        Some(DistError::GitCliffNotFound)
+    ));
+}
+
+/// Write a fake git-cliff that records its args and prints some canned notes
+#[cfg(unix)]
+fn stub_git_cliff(name: &str) -> (Utf8PathBuf, Tools) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = test_dir().join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("git-cliff");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\necho \"$@\" > cliff-args.txt\nprintf '## [1.0.0] - 2024-01-01\\n\\n### Features\\n\\n- taught the axolotl to wave\\n'\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let mut tools = mock_tools();
+    tools.git_cliff = Some(Tool {
+        cmd: script.to_string(),
+        version: "git-cliff 2.0.0".to_owned(),
+    });
+    (dir, tools)
+}
+
+#[cfg(unix)]
+#[test]
+fn git_cliff_unified_notes() {
+    // The changelog file is ignored in favour of git-cliff
+    let path = changelog(
+        "CLIFF_CHANGELOG.md",
+        &format!("# Changelog\n\n## {BIN_AXO_VER}\n\n* from the changelog file\n"),
+    );
+    let (dir, tools) = stub_git_cliff("cliff-unified");
+    let graph = graph_with_tag(
+        json!({ "git-cliff": true, "require-changelog": true }),
+        Some(path),
+        Some(dir.clone()),
+        tools,
+        format!("v{BIN_AXO_VER}"),
+    )
+    .unwrap();
+    assert_eq!(
+        graph.announcement_title.as_deref(),
+        Some("[1.0.0] - 2024-01-01")
+    );
+    assert_eq!(
+        graph.announcement_changelog.as_deref(),
+        Some("### Features\n\n- taught the axolotl to wave")
+    );
+    let args = std::fs::read_to_string(dir.join("cliff-args.txt")).unwrap();
+    assert_eq!(args.trim(), "--current --strip all");
+}
+
+#[cfg(unix)]
+#[test]
+fn git_cliff_package_notes() {
+    // A single package's notes only look at its own tags and files
+    let (dir, tools) = stub_git_cliff("cliff-package");
+    let graph = graph_with_tag(
+        json!({ "git-cliff": true }),
+        None,
+        Some(dir.clone()),
+        tools,
+        format!("{BIN_AXO_NAME}-v{BIN_AXO_VER}"),
+    )
+    .unwrap();
+    assert!(graph
+        .announcement_changelog
+        .unwrap()
+        .contains("taught the axolotl to wave"));
+    let args = std::fs::read_to_string(dir.join("cliff-args.txt")).unwrap();
+    assert_eq!(
+        args.trim(),
+        format!("--current --strip all --tag-pattern (^|/){BIN_AXO_NAME}[-@/] --include-path {BIN_AXO_NAME}/**/*")
+    );
+}
@@ -0,0 +1,299 @@
+//! Tests for checksum files
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::json;
+
+use super::mock::*;
+use crate::{
+    config::{ArtifactMode, ChecksumStyle, Config, InstallerStyle},
+    format_unified_checksum, generate_and_write_unified_checksum, generate_checksums, ArtifactKind,
+    BuildPlan, BuildStep, ChecksumImpl, CopyFileStep, DistGraphBuilder, UnifiedChecksumFormat,
+    UnifiedChecksumStep,
+};
+
+#[test]
+fn unified_checksum_format() {
+    let checksums = [
+        (
+            "0123456789abcdef".to_owned(),
+            Utf8Path::new("target/distrib/axolotlsay-x86_64-unknown-linux-gnu.tar.xz"),
+        ),
+        (
+            "fedcba9876543210".to_owned(),
+            Utf8Path::new("target/distrib/axolotlsay-installer.sh"),
+        ),
+    ];
+    // Just the file names, separated from the hashes by two spaces
+    assert_eq!(
+        format_unified_checksum(&checksums),
+        "0123456789abcdef  axolotlsay-x86_64-unknown-linux-gnu.tar.xz\n\
+         fedcba9876543210  axolotlsay-installer.sh\n"
+    );
+}
+
+#[test]
+fn unified_checksum_file_names() {
+    assert_eq!(ChecksumStyle::Sha256.unified_file_name(), "SHA256SUMS");
+    assert_eq!(ChecksumStyle::Sha512.unified_file_name(), "SHA512SUMS");
+}
+
+#[test]
+fn checksums_json_lists_sizes_and_hashes() {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-checksums-json-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let archive = dir.join("axolotlsay-x86_64-unknown-linux-gnu.tar.xz");
+    let installer = dir.join("axolotlsay-installer.sh");
+    std::fs::write(&archive, "hello").unwrap();
+    std::fs::write(&installer, "#!/bin/sh\n").unwrap();
+    let dest_path = dir.join("checksums.json");
+    generate_and_write_unified_checksum(
+        &ChecksumStyle::Sha256,
+        UnifiedChecksumFormat::Json,
+        &[archive, installer],
+        &dest_path,
+        2,
+    )
+    .unwrap();
+    let contents = std::fs::read_to_string(&dest_path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let checksums: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    assert_eq!(
+        checksums,
+        json!([
+            {
+                "file": "axolotlsay-x86_64-unknown-linux-gnu.tar.xz",
+                "algorithm": "sha256",
+                "hash": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+                "size": 5,
+            },
+            {
+                "file": "axolotlsay-installer.sh",
+                "algorithm": "sha256",
+                "hash": "a8076d3d28d21e02012b20eaf7dbf75409a6277134439025f282e368e3305abf",
+                "size": 10,
+            },
+        ])
+    );
+}
+
+#[test]
+fn parallel_checksums_are_stable() {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-checksums-many-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Give the files different sizes so they don't all finish at the same rate
+    let files = (0..64)
+        .map(|i| {
+            let path = dir.join(format!("artifact-{i:02}.tar.gz"));
+            std::fs::write(&path, format!("{i}").repeat((64 - i) * 1024)).unwrap();
+            path
+        })
+        .collect::<Vec<_>>();
+
+    let serial = generate_checksums(&ChecksumStyle::Sha256, &files, 1).unwrap();
+    let serial_sums = dir.join("serial.sha256sums");
+    generate_and_write_unified_checksum(
+        &ChecksumStyle::Sha256,
+        UnifiedChecksumFormat::Sums,
+        &files,
+        &serial_sums,
+        1,
+    )
+    .unwrap();
+    let expected = std::fs::read_to_string(&serial_sums).unwrap();
+
+    for _ in 0..4 {
+        let parallel = generate_checksums(&ChecksumStyle::Sha256, &files, 8).unwrap();
+        assert_eq!(parallel, serial);
+
+        let parallel_sums = dir.join("parallel.sha256sums");
+        generate_and_write_unified_checksum(
+            &ChecksumStyle::Sha256,
+            UnifiedChecksumFormat::Sums,
+            &files,
+            &parallel_sums,
+            8,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&parallel_sums).unwrap(), expected);
+    }
+
+    // The error for the first bad file wins, regardless of which fails first
+    let mut broken = files.clone();
+    broken[40] = dir.join("missing-40");
+    broken[3] = dir.join("missing-03");
+    let err = generate_checksums(&ChecksumStyle::Sha256, &broken, 8).unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(format!("{err:?}").contains("missing-03"), "{err:?}");
+}
+
+#[test]
+fn checksums_json_covers_every_artifact() {
+    let cfg = Config {
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "x86_64-pc-windows-msvc".to_owned(),
+        ],
+        installers: vec![InstallerStyle::Shell, InstallerStyle::Powershell],
+        ..mock_config()
+    };
+    let graph = graph_for_dist_metadata(
+        json!({
+            "targets": ["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"],
+            "installers": ["shell", "powershell"],
+            "checksums-json": true,
+            "unified-checksum": true,
+        }),
+        &cfg,
+    );
+
+    let json_step = graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::UnifiedChecksum(
+                step @ UnifiedChecksumStep {
+                    format: UnifiedChecksumFormat::Json,
+                    ..
+                },
+            ) => Some(step),
+            _ => None,
+        })
+        .expect("no checksums.json was built");
+    assert_eq!(
+        json_step.dest_path.file_name(),
+        Some(format!("{BIN_AXO_NAME}-checksums.json").as_str())
+    );
+
+    // Every artifact but the checksums is listed (not even the SHA256SUMS)
+    let mut expected = graph
+        .artifacts
+        .iter()
+        .filter(|artifact| {
+            !matches!(
+                artifact.kind,
+                ArtifactKind::Checksum(_)
+                    | ArtifactKind::UnifiedChecksum(_)
+                    | ArtifactKind::Signature(_)
+            )
+        })
+        .map(|artifact| artifact.file_path.clone())
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(json_step.src_paths, expected);
+    assert!(expected.len() >= 4, "{expected:?}");
+}
+
+#[test]
+fn global_unified_checksum_lists_remote_artifacts() {
+    let dist = json!({
+        "targets": ["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"],
+        "installers": ["shell", "deb"],
+        "unified-checksum": true,
+    });
+    let sums = |artifact_mode| {
+        let cfg = Config {
+            artifact_mode,
+            targets: vec![
+                "x86_64-unknown-linux-gnu".to_owned(),
+                "x86_64-pc-windows-msvc".to_owned(),
+            ],
+            installers: vec![InstallerStyle::Shell, InstallerStyle::Deb],
+            ..mock_config()
+        };
+        let graph = graph_for_dist_metadata(dist.clone(), &cfg);
+        graph
+            .build_steps
+            .iter()
+            .find_map(|step| match step {
+                BuildStep::UnifiedChecksum(step) => Some(step.src_paths.clone()),
+                _ => None,
+            })
+            .expect("no SHA256SUMS was built")
+    };
+
+    // The global machine lists what the local ones built (not just the archives),
+    // exactly like a machine that builds everything would
+    let global = sums(ArtifactMode::Global);
+    assert!(global.iter().any(|path| path.extension() == Some("deb")));
+    assert_eq!(global, sums(ArtifactMode::All));
+}
+
+fn checksum_step(src_path: &str) -> BuildStep {
+    BuildStep::Checksum(ChecksumImpl {
+        checksum: ChecksumStyle::Sha256,
+        src_path: Utf8PathBuf::from(src_path),
+        dest_path: Utf8PathBuf::from(format!("{src_path}.sha256")),
+    })
+}
+
+/// Plan some build steps that don't involve any binaries
+fn plan_for(steps: &[BuildStep]) -> BuildPlan {
+    let workspace = mock_workspace(vec![pkg_axo_bin()]);
+    let graph = DistGraphBuilder::new(mock_tools(), &workspace, ArtifactMode::All, true)
+        .unwrap()
+        .inner;
+    BuildPlan::new(&graph, steps)
+}
+
+#[test]
+fn checksums_are_batched_together() {
+    let steps = vec![
+        checksum_step("dist/a.tar.xz"),
+        BuildStep::CopyFile(CopyFileStep {
+            src_path: "README.md".into(),
+            dest_path: "dist/b/README.md".into(),
+        }),
+        checksum_step("dist/b.tar.xz"),
+    ];
+    let plan = plan_for(&steps);
+    assert_eq!(plan.waves(), [vec![0, 1, 2]]);
+}
+
+#[test]
+fn checksums_run_before_their_readers() {
+    let steps = vec![
+        checksum_step("dist/a.tar.xz"),
+        BuildStep::CopyFile(CopyFileStep {
+            src_path: "dist/a.tar.xz.sha256".into(),
+            dest_path: "elsewhere/a.tar.xz.sha256".into(),
+        }),
+        checksum_step("dist/b.tar.xz"),
+    ];
+    let plan = plan_for(&steps);
+    assert_eq!(plan.deps, [vec![], vec![0], vec![]]);
+    assert_eq!(plan.waves(), [vec![0, 2], vec![1]]);
+}
+
+#[test]
+fn archive_checksums_are_schedulable_together() {
+    let cfg = Config {
+        artifact_mode: ArtifactMode::Local,
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "aarch64-apple-darwin".to_owned(),
+        ],
+        ..mock_config()
+    };
+    let graph = graph_for_dist_metadata(
+        json!({
+            "targets": ["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"],
+        }),
+        &cfg,
+    );
+
+    // Both archives get a checksum, and they can be computed at the same time
+    let checksums = graph
+        .build_steps
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| matches!(step, BuildStep::Checksum(_)))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    assert_eq!(checksums.len(), 2);
+    assert!(!graph.build_plan.depends_on(checksums[1], checksums[0]));
+}
@@ -0,0 +1,88 @@
+//! Tests for caching builds in Github CI
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, BuildStep, DistGraph};
+
+const TARGETS: &[&str] = &["x86_64-pc-windows-msvc", "x86_64-unknown-linux-gnu"];
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+fn workflow(graph: &DistGraph) -> String {
+    let ci = graph.ci.github.as_ref().expect("no github ci");
+    ci.generate_github_ci(graph).unwrap()
+}
+
+/// The RUSTFLAGS of each cargo build
+fn rustflags(graph: &DistGraph) -> Vec<String> {
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(build) => Some(build.rustflags.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn rust_cache_by_default() {
+    let graph = graph_with(json!({ "ci": ["github"] }));
+    let workflow = workflow(&graph);
+    assert!(workflow.contains("      - uses: swatinem/rust-cache@v2\n"));
+    assert!(!workflow.contains("sccache"));
+
+    let graph = graph_with(json!({ "ci": ["github"], "ci-cache": "none" }));
+    let workflow = self::workflow(&graph);
+    assert!(!workflow.contains("rust-cache"));
+    assert!(!workflow.contains("sccache"));
+}
+
+#[test]
+fn sccache_workflow() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "ci-cache": "sccache",
+        // Pin this so the snapshot doesn't change with every release
+        "cargo-dist-version": "0.3.1",
+    }));
+    insta::assert_snapshot!(workflow(&graph));
+}
+
+#[test]
+fn sccache_backends() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "ci-cache": "sccache",
+        "sccache-backend": "s3",
+    }));
+    let workflow = workflow(&graph);
+    assert!(workflow.contains("      RUSTC_WRAPPER: \"sccache\"\n"));
+    assert!(workflow.contains("      SCCACHE_BUCKET: \"${{ vars.SCCACHE_BUCKET }}\"\n"));
+    assert!(workflow
+        .contains("      AWS_SECRET_ACCESS_KEY: \"${{ secrets.AWS_SECRET_ACCESS_KEY }}\"\n"));
+    assert!(!workflow.contains("SCCACHE_GHA_ENABLED"));
+    assert!(!workflow.contains("rust-cache"));
+}
+
+#[test]
+fn sccache_leaves_rustflags_alone() {
+    let default = graph_with(json!({ "ci": ["github"] }));
+    let sccache = graph_with(json!({ "ci": ["github"], "ci-cache": "sccache" }));
+    assert_eq!(rustflags(&default), rustflags(&sccache));
+    assert!(rustflags(&sccache)
+        .iter()
+        .any(|flags| flags.contains("-Ctarget-feature=+crt-static")));
+}
@@ -0,0 +1,232 @@
+//! Tests for generating release notes (and contributor lists) from git history
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::json;
+use std::process::Command;
+
+use crate::{
+    commits::{contributors, notes_from_commits},
+    config::Config,
+    gather_work_for_workspace,
+};
+
+/// Run git in the fixture repo (with a fixed author, so it works anywhere)
+fn git(dir: &Utf8Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(dir)
+        .args([
+            "-c",
+            "user.name=Axolotl",
+            "-c",
+            "user.email=axolotl@example.com",
+        ])
+        .args(["-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// Make a commit that touches path
+fn commit(dir: &Utf8Path, path: &str, message: &str) {
+    let path = dir.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, message).unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
+/// Make a commit by someone else
+fn commit_by(dir: &Utf8Path, author: &str, path: &str, message: &str) {
+    std::fs::write(dir.join(path), message).unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-q", "--author", author, "-m", message]);
+}
+
+/// A repo with a tagged release, followed by a mix of commits for the next one
+fn fixture_repo(name: &str) -> Utf8PathBuf {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-test-{}", std::process::id()))
+        .join(name);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    git(&dir, &["init", "-q"]);
+    commit(&dir, "axolotlsay/src/main.rs", "feat: say hello");
+    git(&dir, &["tag", "v0.9.0"]);
+    git(&dir, &["tag", "axolotlsay-v0.9.0"]);
+    commit(&dir, "axolotlsay/src/main.rs", "feat(cli): add --gills");
+    commit(
+        &dir,
+        "some-lib/src/lib.rs",
+        "fix: don't panic on empty input",
+    );
+    commit(&dir, "README.md", "docs: explain the gills");
+    commit(
+        &dir,
+        "axolotlsay/src/main.rs",
+        "fix(cli)!: rename --gils to --gills",
+    );
+    commit(&dir, "axolotlsay/Cargo.toml", "Bump dependencies");
+    dir
+}
+
+#[test]
+fn notes_grouped_by_type() {
+    let dir = fixture_repo("unified");
+    git(&dir, &["tag", "v1.0.0"]);
+    let notes = notes_from_commits(&dir, "v1.0.0", None).unwrap().unwrap();
+    assert_eq!(
+        notes,
+        r#"### Features
+
+- **cli:** add --gills
+
+### Bug Fixes
+
+- don't panic on empty input
+- **cli:** rename --gils to --gills
+
+### Documentation
+
+- explain the gills
+
+### Other Changes
+
+- Bump dependencies
+"#
+    );
+}
+
+#[test]
+fn notes_for_untagged_head() {
+    // The tag doesn't exist yet (e.g. `cargo dist plan --tag=v1.0.0` before tagging)
+    let dir = fixture_repo("untagged");
+    let notes = notes_from_commits(&dir, "v1.0.0", None).unwrap().unwrap();
+    assert!(notes.contains("- **cli:** add --gills"));
+    assert!(!notes.contains("say hello"));
+}
+
+#[test]
+fn notes_for_one_package() {
+    // Only the package's own commits, since its own previous tag
+    let dir = fixture_repo("package");
+    git(&dir, &["tag", "axolotlsay-v1.0.0"]);
+    let package_dir = Utf8Path::new("axolotlsay");
+    let notes = notes_from_commits(&dir, "axolotlsay-v1.0.0", Some(("axolotlsay", package_dir)))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        notes,
+        r#"### Features
+
+- **cli:** add --gills
+
+### Bug Fixes
+
+- **cli:** rename --gils to --gills
+
+### Other Changes
+
+- Bump dependencies
+"#
+    );
+}
+
+#[test]
+fn tags_on_the_same_commit() {
+    // Another package's tag for the same commit isn't the previous release
+    let dir = fixture_repo("same-commit");
+    git(&dir, &["tag", "v1.0.0"]);
+    git(&dir, &["tag", "axolotlsay-v1.0.0"]);
+    let notes = notes_from_commits(&dir, "v1.0.0", None).unwrap().unwrap();
+    assert!(notes.contains("- **cli:** add --gills"));
+}
+
+#[test]
+fn no_commits_no_notes() {
+    // Nothing touched this package since its last release
+    let dir = fixture_repo("empty");
+    let package_dir = Utf8Path::new("helper-bin");
+    let notes = notes_from_commits(&dir, "helper-bin-v1.0.0", Some(("helper-bin", package_dir)));
+    assert_eq!(notes.unwrap(), None);
+}
+
+#[test]
+fn announcement_uses_commits_without_changelog() {
+    let dir = fixture_repo("announcement");
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        package_root: dir.join(BIN_AXO_NAME),
+        ..pkg_axo_dist(json!({}))
+    }]);
+    workspace.workspace_dir = dir;
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "changelog-from-commits": true,
+            "require-changelog": true,
+            "contributors": true,
+            "ci": ["github"],
+        }
+    }));
+    let cfg = Config {
+        announcement_tag: Some(format!("{BIN_AXO_NAME}-v{BIN_AXO_VER}")),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    let notes = graph.announcement_changelog.as_deref().unwrap();
+    assert!(notes.contains("- **cli:** add --gills"));
+    assert!(!notes.contains("don't panic on empty input"));
+
+    let body = graph.announcement_github_body.as_deref().unwrap();
+    assert!(body.contains("## Release Notes\n\n### Features\n"));
+    assert!(body.contains("\n\n## Contributors\n\n- Axolotl\n\n"));
+
+    // CI needs the whole history to find the previous tag
+    let ci = graph.ci.github.as_ref().unwrap();
+    assert!(ci.fetch_history);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("          submodules: recursive\n          # The release notes are generated from the git history\n          fetch-depth: 0\n"));
+}
+
+#[test]
+fn contributors_deduplicated() {
+    let dir = fixture_repo("contributors");
+    let someone = "Someone <1234+someone@users.noreply.github.com>";
+    commit_by(&dir, someone, "README.md", "docs: fix a typo");
+    commit_by(&dir, someone, "README.md", "docs: fix another typo");
+    // The same GitHub user committing from somewhere else
+    let laptop = "Someone on a Laptop <someone@users.noreply.github.com>";
+    commit_by(&dir, laptop, "README.md", "docs: fix the last typo");
+    commit_by(
+        &dir,
+        "Ms. Axolotl <axolotl@example.org>",
+        "README.md",
+        "docs: draw an axolotl",
+    );
+    git(&dir, &["tag", "v1.0.0"]);
+
+    let list = contributors(&dir, "v1.0.0", None).unwrap();
+    assert_eq!(
+        list,
+        "- Axolotl\n- @someone (first contribution!)\n- Ms. Axolotl (first contribution!)\n"
+    );
+
+    // Nobody is a first-time contributor to the first release
+    let list = contributors(&dir, "v0.9.0", None).unwrap();
+    assert_eq!(list, "- Axolotl\n");
+}
+
+#[test]
+fn contributors_without_git() {
+    // Not a git repo, so there's nobody to thank (but nothing breaks)
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-test-{}", std::process::id()))
+        .join("not-a-repo");
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(contributors(&dir, "v1.0.0", None), None);
+}
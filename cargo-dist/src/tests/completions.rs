@@ -0,0 +1,192 @@
+//! Tests for generating shell completions by running the built binaries
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    config::Config, gather_work_for_workspace, run_build_step, BuildStep, DistGraph,
+    GenerateAssetStep, ZipDirStep,
+};
+
+const HOST: &str = "x86_64-unknown-linux-gnu";
+const CROSS: &str = "aarch64-unknown-linux-gnu";
+
+fn config_with(targets: &[&str]) -> Config {
+    Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    }
+}
+
+fn graph_with(targets: &[&str], dist: serde_json::Value) -> DistGraph {
+    let mut dist = dist;
+    dist["targets"] = json!(targets);
+    dist["unix-archive"] = json!(".tar.gz");
+    let workspace = mock_workspace(vec![PackageInfo {
+        package_root: "axolotlsay".into(),
+        ..pkg_axo_dist(dist)
+    }]);
+    gather_work_for_workspace(&config_with(targets), mock_tools(), &workspace).unwrap()
+}
+
+fn generate_steps(graph: &DistGraph) -> Vec<&GenerateAssetStep> {
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::GenerateAsset(step) => Some(step),
+            _ => None,
+        })
+        .collect()
+}
+
+fn archive_zip(graph: &DistGraph) -> &ZipDirStep {
+    graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::Zip(zip) => Some(zip),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn completions_run_the_host_binary() {
+    let graph = graph_with(&[HOST], json!({ "completions": ["bash", "zsh"] }));
+    let zip = archive_zip(&graph);
+    let binary = zip.src_path.join(BIN_AXO_NAME);
+    let steps = generate_steps(&graph)
+        .into_iter()
+        .map(|step| {
+            let dest = step.dest_path.strip_prefix(&zip.src_path).unwrap();
+            (step.command.clone(), dest.to_string())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        steps,
+        vec![
+            (
+                vec![
+                    binary.to_string(),
+                    "completions".to_owned(),
+                    "bash".to_owned()
+                ],
+                "completions/axolotlsay.bash".to_owned()
+            ),
+            (
+                vec![
+                    binary.to_string(),
+                    "completions".to_owned(),
+                    "zsh".to_owned()
+                ],
+                "completions/_axolotlsay".to_owned()
+            ),
+        ]
+    );
+
+    // The binary has to be built (and copied into the archive's dir) before we can run it
+    let step_idx =
+        |pred: &dyn Fn(&BuildStep) -> bool| graph.build_steps.iter().position(pred).unwrap();
+    let cargo = step_idx(&|step| matches!(step, BuildStep::Cargo(_)));
+    let generate = step_idx(&|step| matches!(step, BuildStep::GenerateAsset(_)));
+    let archive = step_idx(&|step| matches!(step, BuildStep::Zip(_)));
+    assert!(graph.build_plan.depends_on(generate, cargo));
+    assert!(graph.build_plan.depends_on(archive, generate));
+}
+
+#[test]
+fn completions_args_are_configurable() {
+    let graph = graph_with(
+        &[HOST],
+        json!({
+            "completions": ["fish"],
+            "completions-args": ["generate-shell-completions", "--shell={shell}"],
+        }),
+    );
+    let steps = generate_steps(&graph);
+    assert_eq!(steps.len(), 1);
+    assert_eq!(
+        steps[0].command[1..],
+        ["generate-shell-completions", "--shell=fish"]
+    );
+    assert!(steps[0].dest_path.ends_with("completions/axolotlsay.fish"));
+}
+
+#[test]
+fn completions_skipped_for_cross_builds() {
+    let graph = graph_with(&[HOST, CROSS], json!({ "completions": ["bash"] }));
+    let steps = generate_steps(&graph);
+    assert_eq!(steps.len(), 1);
+    assert!(steps[0].dest_path.as_str().contains(HOST));
+    assert!(!graph.build_steps.iter().any(|step| matches!(
+        step,
+        BuildStep::GenerateAsset(step) if step.dest_path.as_str().contains(CROSS)
+    )));
+}
+
+#[cfg(unix)]
+#[test]
+fn completions_land_in_archive() {
+    use std::io::Read;
+    use std::os::unix::fs::PermissionsExt;
+
+    let graph = graph_with(&[HOST], json!({ "completions": ["bash"] }));
+    let zip = archive_zip(&graph);
+    let step = generate_steps(&graph)[0];
+
+    // Build the archive for real, somewhere else, with a stand-in for the binary
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-completions-{}", std::process::id()));
+    let archive_dir = dir.join("axolotlsay-x86_64-unknown-linux-gnu");
+    let archive_path = dir.join("axolotlsay-x86_64-unknown-linux-gnu.tar.gz");
+    let binary = archive_dir.join(BIN_AXO_NAME);
+    std::fs::create_dir_all(&archive_dir).unwrap();
+    std::fs::write(
+        &binary,
+        "#!/bin/sh\necho \"complete -F _axolotlsay axolotlsay # $1 $2\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let generate = BuildStep::GenerateAsset(GenerateAssetStep {
+        command: std::iter::once(binary.to_string())
+            .chain(step.command[1..].iter().cloned())
+            .collect(),
+        working_dir: dir.clone(),
+        dest_path: archive_dir.join(step.dest_path.strip_prefix(&zip.src_path).unwrap()),
+    });
+    let archive = BuildStep::Zip(ZipDirStep {
+        src_path: archive_dir,
+        dest_path: archive_path.clone(),
+        with_root: zip.with_root.clone(),
+        zip_style: zip.zip_style,
+        executables: vec![BIN_AXO_NAME.into()],
+    });
+    run_build_step(&graph, &generate).unwrap();
+    run_build_step(&graph, &archive).unwrap();
+
+    let tarball = std::fs::File::open(&archive_path).unwrap();
+    let mut tarball = tar::Archive::new(flate2::read::GzDecoder::new(tarball));
+    let mut completion = None;
+    for entry in tarball.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        if entry
+            .path()
+            .unwrap()
+            .ends_with("completions/axolotlsay.bash")
+        {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            completion = Some(contents);
+        }
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        completion.expect("completions weren't archived"),
+        "complete -F _axolotlsay axolotlsay # completions bash\n"
+    );
+}
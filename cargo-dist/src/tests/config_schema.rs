@@ -0,0 +1,165 @@
+//! Tests for the JSON Schema of the [workspace.metadata.dist] config
+
+use axoasset::SourceFile;
+use serde_json::Value;
+
+use crate::config::DistMetadata;
+
+/// A Cargo.toml using a good spread of the config
+const CARGO_TOML: &str = r#"
+[workspace]
+members = ["axolotlsay"]
+
+[workspace.metadata.dist]
+cargo-dist-version = "0.3.1"
+ci = ["github"]
+installers = ["shell", "powershell", "homebrew", "npm"]
+tap = "axodotdev/homebrew-tap"
+publish-jobs = ["homebrew", "./my-publish-job"]
+pr-run-mode = "plan"
+targets = ["x86_64-unknown-linux-gnu", "aarch64-apple-darwin", "x86_64-pc-windows-msvc"]
+unix-archive = ".tar.xz"
+windows-archive = ".zip"
+checksum = "sha512"
+checksum-jobs = 4
+install-path = "~/.axolotlsay/bin"
+include = ["README.md", "LICENSE"]
+
+[workspace.metadata.dist.dependencies.homebrew]
+cmake = "3.26"
+protobuf = "*"
+
+[workspace.metadata.dist.dependencies.apt]
+libssl-dev = { version = "3.0", stage = ["build", "run"], targets = ["x86_64-unknown-linux-gnu"] }
+"#;
+
+/// Get the [workspace.metadata.dist] table out of a Cargo.toml (as json)
+fn dist_table(cargo_toml: &str) -> Value {
+    let src = SourceFile::new("Cargo.toml", cargo_toml.to_owned());
+    let manifest: Value = src.deserialize_toml().unwrap();
+    manifest["workspace"]["metadata"]["dist"].clone()
+}
+
+/// Check value against the config schema, returning everything wrong with it
+fn validate(value: &Value) -> Vec<String> {
+    let schema = serde_json::to_value(DistMetadata::json_schema()).unwrap();
+    let mut errors = vec![];
+    validate_against(&schema, &schema, value, "dist", &mut errors);
+    errors
+}
+
+/// Just enough of a JSON Schema validator to cover the keywords schemars emits for us
+///
+/// (`pattern` isn't checked, as we don't have a regex engine handy.)
+fn validate_against(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference
+            .strip_prefix("#/definitions/")
+            .expect("unsupported $ref");
+        let definition = &root["definitions"][name];
+        assert!(!definition.is_null(), "dangling $ref {reference}");
+        validate_against(root, definition, value, path, errors);
+    }
+    if let Some(types) = schema.get("type") {
+        let types = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            types => vec![types.as_str().unwrap()],
+        };
+        let matches = types.iter().any(|ty| match *ty {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "string" => value.is_string(),
+            "integer" => value.is_u64() || value.is_i64(),
+            "number" => value.is_number(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            ty => panic!("unsupported type {ty}"),
+        });
+        if !matches {
+            errors.push(format!("{path}: expected {types:?}, got {value}"));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!("{path}: {value} is not one of {options:?}"));
+        }
+    }
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        for subschema in all_of {
+            validate_against(root, subschema, value, path, errors);
+        }
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(options) = schema.get(keyword).and_then(Value::as_array) {
+            let passes = options.iter().any(|subschema| {
+                let mut sub_errors = vec![];
+                validate_against(root, subschema, value, path, &mut sub_errors);
+                sub_errors.is_empty()
+            });
+            if !passes {
+                errors.push(format!(
+                    "{path}: {value} doesn't match any {keyword} option"
+                ));
+            }
+        }
+    }
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (idx, item) in array.iter().enumerate() {
+            validate_against(root, items, item, &format!("{path}[{idx}]"), errors);
+        }
+    }
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, field) in object {
+            let field_path = format!("{path}.{key}");
+            if let Some(property) = properties.and_then(|properties| properties.get(key)) {
+                validate_against(root, property, field, &field_path, errors);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => errors.push(format!("{field_path}: unknown key")),
+                Some(additional @ Value::Object(_)) => {
+                    validate_against(root, additional, field, &field_path, errors)
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn schema_accepts_representative_config() {
+    let dist = dist_table(CARGO_TOML);
+    assert_eq!(validate(&dist), Vec::<String>::new());
+
+    // And it really is a config we can load
+    let meta: DistMetadata = serde_json::from_value(dist).unwrap();
+    assert_eq!(meta.checksum_jobs, Some(4));
+}
+
+#[test]
+fn schema_rejects_unknown_key() {
+    let cargo_toml = CARGO_TOML.replace("installers = ", "installer = ");
+    let errors = validate(&dist_table(&cargo_toml));
+    assert_eq!(errors, vec!["dist.installer: unknown key".to_owned()]);
+}
+
+#[test]
+fn schema_rejects_bad_values() {
+    let cargo_toml = CARGO_TOML
+        .replace(r#"unix-archive = ".tar.xz""#, r#"unix-archive = ".rar""#)
+        .replace(r#"ci = ["github"]"#, r#"ci = ["jenkins"]"#)
+        .replace(r#"cmake = "3.26""#, "cmake = 3");
+    let errors = validate(&dist_table(&cargo_toml));
+    assert_eq!(errors.len(), 3, "{errors:#?}");
+    assert!(errors[0].starts_with("dist.ci[0]:"), "{errors:#?}");
+    assert!(errors[1].starts_with("dist.dependencies:"), "{errors:#?}");
+    assert!(errors[2].starts_with("dist.unix-archive:"), "{errors:#?}");
+}
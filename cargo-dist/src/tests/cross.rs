@@ -0,0 +1,94 @@
+//! Tests for building linux targets with cross
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::Config, gather_work_for_workspace, BuildStep, CargoBuildCommand, CargoBuildStep,
+    DistGraph, Tool,
+};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+const LINUX_MUSL: &str = "x86_64-unknown-linux-musl";
+const TARGETS: &[&str] = &[LINUX, LINUX_MUSL];
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    let mut tools = mock_tools();
+    tools.rustup = Some(Tool {
+        cmd: "rustup".to_owned(),
+        version: "1.26.0".to_owned(),
+    });
+    gather_work_for_workspace(&cfg, tools, &workspace).unwrap()
+}
+
+fn build_for<'a>(graph: &'a DistGraph, target: &str) -> &'a CargoBuildStep {
+    graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::Cargo(build) if build.target_triple == target => Some(build),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no build for {target}"))
+}
+
+fn rustup_targets(graph: &DistGraph) -> Vec<&str> {
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Rustup(step) => step.target.as_deref(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn musl_builds_with_cross() {
+    let graph = graph_with(json!({ "ci": ["github"], "use-cross": true }));
+    let musl = build_for(&graph, LINUX_MUSL);
+    assert_eq!(musl.command, CargoBuildCommand::Cross);
+    // The usual profile and RUSTFLAGS still get passed along to the container
+    assert_eq!(musl.profile, "dist");
+    assert!(musl.rustflags.contains("-Ctarget-feature=+crt-static"));
+    // The host target doesn't need cross
+    assert_eq!(build_for(&graph, LINUX).command, CargoBuildCommand::Cargo);
+    // ...and cross brings its own toolchains
+    assert!(rustup_targets(&graph).is_empty());
+}
+
+#[test]
+fn cargo_by_default() {
+    let graph = graph_with(json!({ "ci": ["github"] }));
+    assert!(!graph.use_cross);
+    assert_eq!(
+        build_for(&graph, LINUX_MUSL).command,
+        CargoBuildCommand::Cargo
+    );
+    assert_eq!(rustup_targets(&graph), [LINUX_MUSL]);
+
+    let ci = graph.ci.github.as_ref().unwrap();
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(!ci.install_cross);
+    assert!(!workflow.contains("cargo install cross"));
+}
+
+#[test]
+fn github_installs_cross() {
+    let graph = graph_with(json!({ "ci": ["github"], "use-cross": true }));
+    let ci = graph.ci.github.as_ref().unwrap();
+    assert!(ci.install_cross);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains(
+        "      - name: Install cross\n        if: ${{ runner.os == 'Linux' }}\n        run: cargo install cross --locked\n"
+    ));
+}
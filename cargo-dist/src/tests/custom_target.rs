@@ -0,0 +1,65 @@
+//! Tests for building with custom target specs
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::Config, gather_work_for_workspace, target_name, ArtifactKind, BuildStep, Tool,
+};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+const CUSTOM: &str = "targets/x86_64-linux-sparkly.json";
+
+#[test]
+fn custom_target_gets_a_build_and_archive() {
+    let targets = [LINUX, CUSTOM];
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": targets,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": {} }));
+    let cfg = Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    let mut tools = mock_tools();
+    tools.rustup = Some(Tool {
+        cmd: "rustup".to_owned(),
+        version: "1.26.0".to_owned(),
+    });
+    let graph = gather_work_for_workspace(&cfg, tools, &workspace).unwrap();
+
+    // The spec is passed along to cargo as-is
+    let builds = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(build) => Some(build.target_triple.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(builds, [CUSTOM, LINUX]);
+    // ...but rustup can't do anything for it
+    assert!(!graph
+        .build_steps
+        .iter()
+        .any(|step| matches!(step, BuildStep::Rustup(_))));
+
+    // Things named after the target use the spec's name
+    let variant = graph.variants.iter().find(|v| v.target == CUSTOM).unwrap();
+    assert_eq!(variant.id, format!("{BIN_AXO_NAME}-x86_64-linux-sparkly"));
+    assert!(graph.artifacts.iter().any(|a| {
+        matches!(a.kind, ArtifactKind::ExecutableZip(_))
+            && a.id == format!("{BIN_AXO_NAME}-x86_64-linux-sparkly.tar.xz")
+    }));
+}
+
+#[test]
+fn custom_target_names() {
+    assert_eq!(target_name(LINUX), LINUX);
+    assert_eq!(target_name(CUSTOM), "x86_64-linux-sparkly");
+    assert_eq!(
+        target_name("/abs/path/thumbv7em-board.json"),
+        "thumbv7em-board"
+    );
+}
@@ -0,0 +1,102 @@
+//! Tests for deb installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{deb::DEB_BIN_DIR, InstallerImpl},
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+fn graph_with_debs() -> DistGraph {
+    let workspace = mock_workspace(vec![PackageInfo {
+        authors: vec!["axodotdev <hello@axo.dev>".to_owned()],
+        license: Some("MIT OR Apache-2.0".to_owned()),
+        ..pkg_axo_dist(json!({
+            "installers": ["deb"],
+            "targets": [
+                "x86_64-unknown-linux-gnu",
+                "x86_64-unknown-linux-musl",
+                "x86_64-pc-windows-msvc",
+            ],
+            "dependencies": {
+                "apt": {
+                    "libssl3": { "version": "3.0.2", "stage": ["run"] },
+                    "bash-completion": { "stage": ["recommend"] },
+                    "fzf": { "stage": ["suggest"] },
+                    "cmake": "*",
+                },
+            },
+        }))
+    }]);
+    let cfg = Config {
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "x86_64-unknown-linux-musl".to_owned(),
+            "x86_64-pc-windows-msvc".to_owned(),
+        ],
+        installers: vec![InstallerStyle::Deb],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn debs_for_gnu_linux_only() {
+    let graph = graph_with_debs();
+    let debs = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::Deb(_))))
+        .collect::<Vec<_>>();
+    assert_eq!(debs.len(), 1);
+    assert_eq!(debs[0].target_triples, ["x86_64-unknown-linux-gnu"]);
+    assert!(debs[0].id.ends_with(".deb"));
+}
+
+#[test]
+fn deb_contents_and_control() {
+    let graph = graph_with_debs();
+    let (artifact, info) = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Deb(info)) => Some((a, info)),
+            _ => None,
+        })
+        .expect("no deb!?");
+
+    // The binary goes to /usr/bin
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(
+        dest_path,
+        &info.package_dir.join(DEB_BIN_DIR).join(BIN_AXO_NAME)
+    );
+
+    let control = &info.control;
+    assert_eq!(control.package, BIN_AXO_NAME);
+    assert_eq!(control.version, BIN_AXO_VER);
+    assert_eq!(control.architecture, "amd64");
+    assert_eq!(
+        control.maintainer.as_deref(),
+        Some("axodotdev <hello@axo.dev>")
+    );
+    // Only runtime apt dependencies end up in Depends
+    assert_eq!(control.depends, ["libssl3 (>= 3.0.2)"]);
+    // Weak dependencies get their own fields
+    assert_eq!(control.recommends, ["bash-completion"]);
+    assert_eq!(control.suggests, ["fzf"]);
+    let rendered = control.render();
+    assert!(
+        rendered.contains("\nRecommends: bash-completion\n"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("\nSuggests: fzf\n"), "{rendered}");
+    // The license isn't a control field, it goes in the copyright file
+    assert!(!rendered.contains("License"), "{rendered}");
+    let copyright = info.copyright.as_ref().expect("no copyright!?");
+    assert_eq!(copyright.license, "MIT OR Apache-2.0");
+    assert_eq!(copyright.holders, ["axodotdev <hello@axo.dev>"]);
+}
@@ -0,0 +1,162 @@
+//! Tests for building deltas against the previous release
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::{Config, DeltaFormat},
+    gather_work_for_workspace, ArtifactKind, BuildStep, DeltaImpl, DistGraph, Tool, Tools,
+    ZipDirStep,
+};
+
+const TARGETS: &[&str] = &["x86_64-pc-windows-msvc", "x86_64-unknown-linux-gnu"];
+const PREVIOUS_RELEASE: &str = "https://github.com/axodotdev/axolotlsay/releases/download/v0.9.0";
+
+fn stub_tools() -> Tools {
+    let stub = |cmd: &str| {
+        Some(Tool {
+            cmd: cmd.to_owned(),
+            version: "stub".to_owned(),
+        })
+    };
+    Tools {
+        zstd: stub("zstd"),
+        bsdiff: stub("bsdiff"),
+        ..mock_tools()
+    }
+}
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, stub_tools(), &workspace).unwrap()
+}
+
+fn delta_steps(graph: &DistGraph) -> Vec<&DeltaImpl> {
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Delta(delta) => Some(delta),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn no_deltas_by_default() {
+    let graph = graph_with(json!({}));
+    assert!(delta_steps(&graph).is_empty());
+    assert!(!graph.artifacts.iter().any(|a| matches!(
+        a.kind,
+        ArtifactKind::Delta(_) | ArtifactKind::DeltaScript(_)
+    )));
+}
+
+#[test]
+fn deltas_scheduled_for_previous_release() {
+    let graph = graph_with(json!({ "delta-from": format!("{PREVIOUS_RELEASE}/") }));
+    let deltas = delta_steps(&graph);
+    assert_eq!(deltas.len(), TARGETS.len());
+
+    for delta in deltas {
+        assert_eq!(delta.format, DeltaFormat::Zstd);
+        let archive = delta.src_path.file_name().unwrap();
+        assert_eq!(delta.old_path, format!("{PREVIOUS_RELEASE}/{archive}"));
+        assert_eq!(
+            delta.dest_path.file_name().unwrap(),
+            format!("{archive}.patch.zst")
+        );
+
+        // The delta can only be built once the archive has been
+        let zip_pos = graph
+            .build_steps
+            .iter()
+            .position(|step| {
+                matches!(step, BuildStep::Zip(ZipDirStep { dest_path, .. }) if *dest_path == delta.src_path)
+            })
+            .expect("archive isn't built");
+        let delta_pos = graph
+            .build_steps
+            .iter()
+            .position(|step| matches!(step, BuildStep::Delta(d) if d.dest_path == delta.dest_path))
+            .unwrap();
+        assert!(zip_pos < delta_pos);
+
+        // Deltas are local artifacts with their own checksums
+        let artifact = graph
+            .artifacts
+            .iter()
+            .find(|a| a.file_path == delta.dest_path)
+            .unwrap();
+        assert!(!artifact.is_global);
+        assert!(artifact.checksum.is_some());
+    }
+
+    // And the release gets a script for applying them
+    let scripts = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::DeltaScript(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(scripts.len(), 1);
+    assert!(scripts[0].is_global);
+    assert_eq!(scripts[0].id, format!("{BIN_AXO_NAME}-apply-delta.sh"));
+    assert!(graph
+        .build_steps
+        .iter()
+        .any(|step| matches!(step, BuildStep::GenerateDeltaScript(_))));
+}
+
+#[test]
+fn bsdiff_deltas_from_local_dir() {
+    let graph = graph_with(json!({
+        "delta-from": "previous-release",
+        "delta-format": "bsdiff",
+    }));
+    let deltas = delta_steps(&graph);
+    assert_eq!(deltas.len(), TARGETS.len());
+    for delta in deltas {
+        assert_eq!(delta.format, DeltaFormat::Bsdiff);
+        let archive = delta.src_path.file_name().unwrap();
+        assert_eq!(
+            delta.old_path,
+            format!("{}/{archive}", graph.workspace_dir.join("previous-release"))
+        );
+        assert_eq!(
+            delta.dest_path.file_name().unwrap(),
+            format!("{archive}.bsdiff")
+        );
+    }
+}
+
+#[test]
+fn deltas_are_signed() {
+    let graph = graph_with(json!({
+        "delta-from": format!("{PREVIOUS_RELEASE}/"),
+        "gpg-key": "ABCD1234",
+    }));
+    let deltas = graph
+        .artifacts
+        .iter()
+        .filter(|a| {
+            matches!(
+                a.kind,
+                ArtifactKind::Delta(_) | ArtifactKind::DeltaScript(_)
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(deltas.len(), TARGETS.len() + 1);
+    for delta in deltas {
+        let signature = graph.artifact(delta.signature.expect("delta wasn't signed"));
+        assert_eq!(signature.id, format!("{}.asc", delta.id));
+        assert_eq!(signature.is_global, delta.is_global);
+    }
+}
@@ -0,0 +1,84 @@
+//! Tests for all-features demo variants
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl, config::Config, gather_work_for_workspace, ArtifactKind,
+    BuildStep, CargoBuildStep, CargoTargetFeatureList, CargoTargetFeatures,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"];
+const DEMO_TARGET: &str = "x86_64-unknown-linux-gnu";
+
+#[test]
+fn demo_builds_with_all_features_separately() {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+        "features": ["fancy"],
+        "demo-targets": [DEMO_TARGET],
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    // The demo is an extra variant, only for the demo target
+    let release = &graph.releases[0];
+    assert_eq!(release.variants.len(), TARGETS.len());
+    assert_eq!(release.demo_variants.len(), 1);
+    let demo = graph.variant(release.demo_variants[0]);
+    assert_eq!(demo.target, DEMO_TARGET);
+    assert_eq!(demo.id, format!("{BIN_AXO_NAME}-{DEMO_TARGET}-demo"));
+    assert!(graph.artifacts.iter().any(|a| {
+        matches!(a.kind, ArtifactKind::ExecutableZip(_))
+            && a.id == format!("{BIN_AXO_NAME}-{DEMO_TARGET}-demo.tar.xz")
+    }));
+
+    // The demo target gets two builds with different features, the other target just one
+    let builds = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(build) if build.target_triple == DEMO_TARGET => Some(build),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(builds.len(), 2);
+    let build_with = |features: CargoTargetFeatures| -> &CargoBuildStep {
+        builds.iter().find(|b| b.features == features).unwrap()
+    };
+    let normal = build_with(CargoTargetFeatures {
+        default_features: true,
+        features: CargoTargetFeatureList::List(vec!["fancy".to_owned()]),
+    });
+    let all = build_with(CargoTargetFeatures {
+        default_features: true,
+        features: CargoTargetFeatureList::All,
+    });
+    assert_eq!(all.expected_binaries, demo.binaries);
+    assert_ne!(normal.expected_binaries, all.expected_binaries);
+    let other_builds = graph
+        .build_steps
+        .iter()
+        .filter(
+            |step| matches!(step, BuildStep::Cargo(build) if build.target_triple != DEMO_TARGET),
+        )
+        .count();
+    assert_eq!(other_builds, 1);
+
+    // Installers still only know about the normal build
+    let shell = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(shell.artifacts.len(), TARGETS.len());
+    assert!(shell.artifacts.iter().all(|a| !a.id.contains("-demo")));
+}
@@ -0,0 +1,103 @@
+//! Tests for which generated files are allowed to be dirty
+
+use camino::Utf8PathBuf;
+use cargo_dist_schema::PrRunMode;
+use serde_json::json;
+
+use super::mock::*;
+use crate::{
+    backend::ci::is_pull_request_env,
+    check_generated_files,
+    config::{Config, DirtyMode, GenerateMode},
+    gather_work_for_workspace,
+};
+
+#[test]
+fn pr_plan_tolerates_ci_drift() {
+    // With no allow-dirty config, regenerated CI scripts normally have to match
+    let mode = DirtyMode::AllowList(vec![]);
+    assert!(mode.should_run(GenerateMode::Ci));
+
+    // ...but a PR that's only planning a release doesn't care if they drifted
+    let mode = mode.for_pr_plan(PrRunMode::Plan);
+    assert!(matches!(mode, DirtyMode::GeneratedOnly));
+    assert!(!mode.should_run(GenerateMode::Ci));
+    assert!(!mode.should_run(GenerateMode::Msi));
+}
+
+#[test]
+fn pr_upload_still_checks_ci_drift() {
+    // A PR that builds and uploads artifacts should still be held to the committed config
+    let mode = DirtyMode::AllowList(vec![GenerateMode::Msi]).for_pr_plan(PrRunMode::Upload);
+    assert!(mode.should_run(GenerateMode::Ci));
+    assert!(!mode.should_run(GenerateMode::Msi));
+}
+
+#[test]
+fn pr_plan_keeps_allow_all() {
+    // --allow-dirty already skips every check, including cargo-dist-version
+    let mode = DirtyMode::AllowAll.for_pr_plan(PrRunMode::Plan);
+    assert!(matches!(mode, DirtyMode::AllowAll));
+}
+
+#[test]
+fn pull_requests_of_every_ci() {
+    let env = |name: &'static str, value: &'static str| {
+        move |var: &str| (var == name).then(|| value.to_owned())
+    };
+    assert!(is_pull_request_env(env(
+        "GITHUB_EVENT_NAME",
+        "pull_request"
+    )));
+    assert!(is_pull_request_env(env(
+        "GITHUB_EVENT_NAME",
+        "pull_request_target"
+    )));
+    assert!(is_pull_request_env(env(
+        "CI_PIPELINE_SOURCE",
+        "merge_request_event"
+    )));
+    assert!(is_pull_request_env(env(
+        "CI_PIPELINE_SOURCE",
+        "external_pull_request_event"
+    )));
+
+    assert!(!is_pull_request_env(env("GITHUB_EVENT_NAME", "push")));
+    assert!(!is_pull_request_env(env("CI_PIPELINE_SOURCE", "push")));
+    assert!(!is_pull_request_env(|_| None));
+}
+
+#[test]
+fn integrity_check_tolerates_ci_drift_on_pull_requests() {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-dirty-{}", std::process::id()));
+    let ci_file = dir.join(".github/workflows/release.yml");
+    std::fs::create_dir_all(ci_file.parent().unwrap()).unwrap();
+    std::fs::write(&ci_file, "name: Release\n").unwrap();
+
+    let mut workspace = workspace_axo_dist(json!({ "targets": ["x86_64-unknown-linux-gnu"] }));
+    workspace.workspace_dir = dir.clone();
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "cargo-dist-version": env!("CARGO_PKG_VERSION"),
+            "ci": ["github"],
+        }
+    }));
+    let check = |is_pull_request| {
+        let cfg = Config {
+            allow_all_dirty: false,
+            ..mock_config()
+        };
+        let mut dist = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+        // (as if there was a [profile.dist])
+        dist.is_init = true;
+        check_generated_files(&mut dist, is_pull_request)
+    };
+    let on_push = check(false);
+    let on_pr = check(true);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(on_push.is_err());
+    on_pr.unwrap();
+}
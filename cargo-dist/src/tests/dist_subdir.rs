@@ -0,0 +1,87 @@
+//! Tests for dist-subdir
+
+use super::mock::*;
+use axoproject::{rust::CargoProfile, WorkspaceInfo};
+use serde_json::json;
+
+use crate::{
+    backend::ci::github::GithubCiInfo, config::ArtifactMode, errors::DistError,
+    gather_work_for_workspace, DistGraphBuilder,
+};
+
+fn workspace_with_subdir(dist_subdir: Option<&str>) -> WorkspaceInfo {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": ["x86_64-unknown-linux-gnu"],
+    }));
+    workspace.target_dir = "/axolotlsay/target".into();
+    let mut dist = json!({ "ci": ["github"], "ssldotcom-windows-sign": "test" });
+    if let Some(dist_subdir) = dist_subdir {
+        dist["dist-subdir"] = json!(dist_subdir);
+    }
+    workspace.cargo_metadata_table = Some(json!({ "dist": dist }));
+    workspace
+}
+
+fn builder_result(workspace: &WorkspaceInfo) -> Result<DistGraphBuilder<'_>, DistError> {
+    DistGraphBuilder::new(mock_tools(), workspace, ArtifactMode::All, true)
+}
+
+#[test]
+fn default_dist_subdir() {
+    let workspace = workspace_with_subdir(None);
+    let builder = builder_result(&workspace).unwrap();
+    assert_eq!(builder.inner.dist_dir, "/axolotlsay/target/distrib");
+}
+
+#[test]
+fn custom_dist_subdir() {
+    let workspace = workspace_with_subdir(Some("dist-output"));
+    let cfg = mock_config();
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    assert_eq!(graph.dist_dir, "/axolotlsay/target/dist-output");
+    assert!(graph
+        .artifacts
+        .iter()
+        .all(|artifact| artifact.file_path.starts_with(&graph.dist_dir)));
+
+    let ci = GithubCiInfo::new(&graph);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("SIGN_DIR_IN: target/dist-output/sign-input"));
+    assert!(!workflow.contains("target/distrib"));
+}
+
+#[test]
+fn dist_subdir_collides_with_profile() {
+    for subdir in ["dist", "release", "debug"] {
+        let workspace = workspace_with_subdir(Some(subdir));
+        let res = builder_result(&workspace);
+        assert!(
+            matches!(res, Err(DistError::DistSubdirCollision { .. })),
+            "{subdir} was allowed"
+        );
+    }
+
+    let mut workspace = workspace_with_subdir(Some("fast"));
+    workspace.cargo_profiles.insert(
+        "fast".to_owned(),
+        CargoProfile {
+            inherits: Some("release".to_owned()),
+            debug: None,
+            split_debuginfo: None,
+        },
+    );
+    let res = builder_result(&workspace);
+    assert!(matches!(res, Err(DistError::DistSubdirCollision { .. })));
+}
+
+#[test]
+fn dist_subdir_must_be_a_dir_name() {
+    for subdir in ["", "..", "out/dist"] {
+        let workspace = workspace_with_subdir(Some(subdir));
+        let res = builder_result(&workspace);
+        assert!(
+            matches!(res, Err(DistError::DistSubdirInvalid { .. })),
+            "{subdir:?} was allowed"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Tests for macOS dmg installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl,
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, BuildStep, CopyFileStep,
+};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-apple-darwin",
+    "x86_64-unknown-linux-gnu",
+];
+
+#[test]
+fn dmgs_for_apple_only() {
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        readme_file: Some(Utf8PathBuf::from("README.md")),
+        ..pkg_axo_dist(json!({
+            "installers": ["dmg"],
+            "targets": TARGETS,
+        }))
+    }]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Dmg],
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    let mut dmgs = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Dmg(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    dmgs.sort_by_key(|(a, _)| a.id.clone());
+    assert_eq!(dmgs.len(), 2);
+    let (artifact, info) = dmgs[0];
+    assert_eq!(artifact.target_triples, ["aarch64-apple-darwin"]);
+    assert_eq!(
+        artifact.id,
+        format!("{BIN_AXO_NAME}-aarch64-apple-darwin.dmg")
+    );
+    assert!(artifact.checksum.is_some());
+    assert_eq!(info.volume_name, format!("{BIN_AXO_NAME} {BIN_AXO_VER}"));
+    // The binaries are staged at the root of the image
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(dest_path, &info.package_dir.join(BIN_AXO_NAME));
+
+    // The README gets copied in next to them
+    let readme_dest = info.package_dir.join("README.md");
+    assert!(graph.build_steps.iter().any(|step| matches!(
+        step,
+        BuildStep::CopyFile(CopyFileStep { dest_path, .. }) if *dest_path == readme_dest
+    )));
+
+    // They show up in the download table
+    let body = graph.announcement_github_body.as_ref().unwrap();
+    assert!(body.contains(&artifact.id), "{body}");
+}
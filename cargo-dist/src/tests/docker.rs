@@ -0,0 +1,105 @@
+//! Tests for building and pushing Docker images
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::ci::github::GithubCiInfo,
+    config::Config,
+    errors::{DistError, Result},
+    gather_work_for_workspace, DistGraph,
+};
+
+fn graph_with_docker(targets: &[&str], workspace_dist: serde_json::Value) -> Result<DistGraph> {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": targets,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace)
+}
+
+fn docker_config() -> serde_json::Value {
+    json!({
+        "publish-jobs": ["docker"],
+        "docker-registry": "ghcr.io",
+        "docker-image": "axodotdev/axolotlsay",
+    })
+}
+
+#[test]
+fn docker_multi_arch() {
+    let graph = graph_with_docker(
+        &[
+            "x86_64-unknown-linux-musl",
+            "x86_64-unknown-linux-gnu",
+            "aarch64-unknown-linux-gnu",
+            "x86_64-pc-windows-msvc",
+        ],
+        docker_config(),
+    )
+    .unwrap();
+    let ci = GithubCiInfo::new(&graph);
+    let docker = ci.docker.as_ref().expect("no docker job");
+    assert_eq!(docker.image, "ghcr.io/axodotdev/axolotlsay");
+    assert_eq!(docker.base_image, "gcr.io/distroless/cc-debian12");
+    assert_eq!(docker.platform_list, "linux/amd64,linux/arm64");
+
+    // gnu builds win over musl ones
+    let amd64 = &docker.platforms[0];
+    assert_eq!(
+        amd64.archive,
+        format!("{BIN_AXO_NAME}-x86_64-unknown-linux-gnu.tar.xz")
+    );
+    assert_eq!(
+        amd64.executables,
+        [format!(
+            "{BIN_AXO_NAME}-x86_64-unknown-linux-gnu/{BIN_AXO_NAME}"
+        )]
+    );
+
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("publish-docker-image:"));
+    assert!(workflow.contains("FROM gcr.io/distroless/cc-debian12\n"));
+    assert!(workflow.contains("ghcr.io/axodotdev/axolotlsay:latest\n"));
+}
+
+#[test]
+fn docker_base_image() {
+    let mut config = docker_config();
+    config["docker-base-image"] = json!("scratch");
+    let graph = graph_with_docker(&["x86_64-unknown-linux-musl"], config).unwrap();
+    let ci = GithubCiInfo::new(&graph);
+    let docker = ci.docker.as_ref().expect("no docker job");
+    assert_eq!(docker.base_image, "scratch");
+    assert_eq!(docker.platform_list, "linux/amd64");
+}
+
+#[test]
+fn no_docker_without_linux() {
+    let graph = graph_with_docker(&["x86_64-pc-windows-msvc"], docker_config()).unwrap();
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.docker.is_none());
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(!workflow.contains("publish-docker-image:"));
+}
+
+#[test]
+fn docker_needs_registry() {
+    let err = graph_with_docker(
+        &["x86_64-unknown-linux-gnu"],
+        json!({
+            "publish-jobs": ["docker"],
+            "docker-image": "axodotdev/axolotlsay",
+        }),
+    )
+    .unwrap_err();
+    let err = err.downcast_ref::<DistError>().unwrap();
+    assert!(
+        matches!(err, DistError::DockerConfigMissing { setting } if setting == "docker-registry"),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,64 @@
+//! Tests for the download URLs of artifacts
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{build_manifest, config::Config, gather_work_for_workspace, DistGraph};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+
+fn graph_with(workspace_dist: serde_json::Value, repository: bool) -> (Config, DistGraph) {
+    let repository_url = repository.then(|| REPO_URL.to_owned());
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        repository_url: repository_url.clone(),
+        ..pkg_axo_dist(json!({ "targets": [LINUX] }))
+    }]);
+    workspace.repository_url = repository_url;
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: vec![LINUX.to_owned()],
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    (cfg, graph)
+}
+
+#[test]
+fn archive_and_checksum_urls() {
+    let (cfg, graph) = graph_with(json!({ "ci": ["github"] }), true);
+    let manifest = build_manifest(&cfg, &graph);
+    let base = format!("{REPO_URL}/releases/download/v{BIN_AXO_VER}");
+
+    let archive_name = format!("{BIN_AXO_NAME}-{LINUX}.tar.xz");
+    let archive = &manifest.artifacts[&archive_name];
+    assert_eq!(archive.download_url, Some(format!("{base}/{archive_name}")));
+
+    let checksum_name = archive.checksum.as_ref().unwrap();
+    assert_eq!(checksum_name, &format!("{archive_name}.sha256"));
+    let checksum = &manifest.artifacts[checksum_name];
+    assert_eq!(
+        checksum.download_url,
+        Some(format!("{base}/{archive_name}.sha256"))
+    );
+
+    // The graph agrees with the manifest
+    let artifact = graph
+        .artifacts
+        .iter()
+        .find(|a| a.id == archive_name)
+        .unwrap();
+    assert_eq!(graph.artifact_url(artifact), archive.download_url);
+}
+
+#[test]
+fn no_urls_without_upload() {
+    // Without a repository there's nowhere we know the artifacts will be uploaded to
+    let (cfg, graph) = graph_with(json!({}), false);
+    assert!(graph.artifact_download_url.is_none());
+    let manifest = build_manifest(&cfg, &graph);
+    assert!(!manifest.artifacts.is_empty());
+    for artifact in manifest.artifacts.values() {
+        assert_eq!(artifact.download_url, None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Tests for leaving packages out of an announcement with exclude-packages/--exclude
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    check_dist_package,
+    config::{ArtifactMode, Config},
+    errors::DistError,
+    gather_work_for_workspace, select_tag, DistGraphBuilder, PartialAnnouncementTag,
+};
+
+fn config(exclude: &[&str]) -> Config {
+    Config {
+        exclude: exclude.iter().map(|name| name.to_string()).collect(),
+        ..mock_config()
+    }
+}
+
+#[test]
+fn excluded_package_is_skipped() {
+    let mut workspace = workspace_unified();
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": { "exclude-packages": [BIN_HELPER_NAME] }
+    }));
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+
+    // The reason is checked before anything else
+    let helper = workspace.package(BIN_HELPER_IDX);
+    let reason = check_dist_package(
+        &graph,
+        BIN_HELPER_IDX,
+        helper,
+        &PartialAnnouncementTag::default(),
+    );
+    assert_eq!(reason.as_deref(), Some("excluded"));
+
+    let announcing = select_tag(&graph, Some(&format!("v{BIN_AXO_VER}")), true).unwrap();
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+}
+
+#[test]
+fn exclude_from_cli() {
+    // --exclude adds to the config
+    let workspace = workspace_unified();
+    let graph =
+        gather_work_for_workspace(&config(&[BIN_HELPER_NAME]), mock_tools(), &workspace).unwrap();
+    let releases = graph
+        .releases
+        .iter()
+        .map(|release| release.app_name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(releases, vec![BIN_AXO_NAME]);
+}
+
+#[test]
+fn exclude_unknown_package() {
+    let workspace = workspace_unified();
+    let res = gather_work_for_workspace(&config(&["not-a-package"]), mock_tools(), &workspace);
+    let err = res.unwrap_err();
+    let Some(DistError::UnknownExcludedPackage { package }) = err.downcast_ref::<DistError>()
+    else {
+        panic!("expected UnknownExcludedPackage, got {err:?}");
+    };
+    assert_eq!(package, "not-a-package");
+}
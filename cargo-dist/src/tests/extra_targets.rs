@@ -0,0 +1,57 @@
+//! Tests for packages that build extra targets on top of the workspace's
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, DistGraph};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+const MAC: &str = "aarch64-apple-darwin";
+
+/// The targets of each release
+fn release_targets(graph: &DistGraph) -> Vec<(&str, Vec<&str>)> {
+    graph
+        .releases
+        .iter()
+        .map(|release| {
+            let targets = release.targets.iter().map(|t| t.as_str()).collect();
+            (release.app_name.as_str(), targets)
+        })
+        .collect()
+}
+
+/// A workspace that builds for linux, where helper-bin also builds for mac
+fn graph_with_extra_target(targets: &[&str]) -> DistGraph {
+    let mut workspace = mock_workspace(vec![
+        pkg_axo_dist(json!({})),
+        pkg_helper_dist(json!({ "extra-targets": [MAC] })),
+    ]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "targets": [LINUX] } }));
+    let cfg = Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn extra_targets_add_to_workspace_targets() {
+    let graph = graph_with_extra_target(&[]);
+    assert_eq!(
+        release_targets(&graph),
+        vec![
+            (BIN_AXO_NAME, vec![LINUX]),
+            (BIN_HELPER_NAME, vec![MAC, LINUX])
+        ]
+    );
+}
+
+#[test]
+fn extra_targets_respect_explicit_targets() {
+    // e.g. the CI job for the extra target only builds the package that wants it
+    let graph = graph_with_extra_target(&[MAC]);
+    assert_eq!(
+        release_targets(&graph),
+        vec![(BIN_AXO_NAME, vec![]), (BIN_HELPER_NAME, vec![MAC])]
+    );
+}
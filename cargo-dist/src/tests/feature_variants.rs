@@ -0,0 +1,132 @@
+//! Tests for building the same target with different features
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl, config::Config, errors::DistError,
+    gather_work_for_workspace, ArtifactKind, BuildStep, CargoTargetFeatureList,
+    CargoTargetFeatures, DistGraph,
+};
+
+const TARGET: &str = "x86_64-unknown-linux-gnu";
+
+fn graph_with(package_dist: serde_json::Value) -> miette::Result<DistGraph> {
+    let mut workspace = workspace_axo_dist(package_dist);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: vec![TARGET.to_owned()],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace)
+}
+
+#[test]
+fn feature_variants_get_their_own_archives() {
+    let graph = graph_with(json!({
+        "installers": ["shell"],
+        "targets": [TARGET],
+        "feature-variants": {
+            "full": { "all-features": true },
+            "minimal": { "default-features": false, "features": ["tiny"] },
+        },
+    }))
+    .unwrap();
+
+    let release = &graph.releases[0];
+    assert_eq!(release.variants.len(), 1);
+    assert_eq!(release.targets, [TARGET]);
+    let ids = release
+        .feature_variants
+        .iter()
+        .map(|&idx| graph.variant(idx).id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            format!("{BIN_AXO_NAME}-{TARGET}-full"),
+            format!("{BIN_AXO_NAME}-{TARGET}-minimal"),
+        ]
+    );
+
+    // One archive for each variant of the one target
+    let mut archives = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .map(|a| a.id.as_str())
+        .collect::<Vec<_>>();
+    archives.sort();
+    assert_eq!(
+        archives,
+        [
+            format!("{BIN_AXO_NAME}-{TARGET}-full.tar.xz"),
+            format!("{BIN_AXO_NAME}-{TARGET}-minimal.tar.xz"),
+            format!("{BIN_AXO_NAME}-{TARGET}.tar.xz"),
+        ]
+    );
+
+    // The binaries don't collide, and each gets a build with its own features
+    let mut binary_ids = graph.binaries.iter().map(|b| &b.id).collect::<Vec<_>>();
+    binary_ids.sort();
+    binary_ids.dedup();
+    assert_eq!(binary_ids.len(), 3);
+    let mut features = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(build) => {
+                assert_eq!(build.expected_binaries.len(), 1);
+                Some(build.features.clone())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    features.sort();
+    assert_eq!(
+        features,
+        [
+            CargoTargetFeatures {
+                default_features: false,
+                features: CargoTargetFeatureList::List(vec!["tiny".to_owned()]),
+            },
+            CargoTargetFeatures {
+                default_features: true,
+                features: CargoTargetFeatureList::All,
+            },
+            CargoTargetFeatures {
+                default_features: true,
+                features: CargoTargetFeatureList::List(vec![]),
+            },
+        ]
+    );
+
+    // Installers only know about the normal build
+    let shell = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(shell.artifacts.len(), 1);
+}
+
+#[test]
+fn feature_variant_names_are_checked() {
+    for name in ["demo", "has space", ""] {
+        let err = graph_with(json!({
+            "targets": [TARGET],
+            "feature-variants": { name: {} },
+        }))
+        .unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<DistError>(),
+                Some(DistError::FeatureVariantName { .. })
+            ),
+            "{name}"
+        );
+    }
+}
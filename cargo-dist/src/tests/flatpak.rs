@@ -0,0 +1,91 @@
+//! Tests for Flatpak installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl,
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-apple-darwin"];
+
+fn graph_with_flatpaks(dist: serde_json::Value, repository_url: Option<&str>) -> DistGraph {
+    let mut dist = dist;
+    dist["installers"] = json!(["flatpak"]);
+    dist["targets"] = json!(TARGETS);
+    let workspace = mock_workspace(vec![PackageInfo {
+        repository_url: repository_url.map(|url| url.to_owned()),
+        ..pkg_axo_dist(dist)
+    }]);
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Flatpak],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+fn flatpaks(
+    graph: &DistGraph,
+) -> Vec<(
+    &crate::Artifact,
+    &crate::backend::installer::flatpak::FlatpakInstallerInfo,
+)> {
+    graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Flatpak(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn flatpak_for_linux_only() {
+    let graph = graph_with_flatpaks(json!({}), Some(REPO_URL));
+    let flatpaks = flatpaks(&graph);
+    assert_eq!(flatpaks.len(), 1);
+    let (artifact, info) = flatpaks[0];
+    assert_eq!(artifact.target_triples, ["x86_64-unknown-linux-gnu"]);
+    assert!(artifact.id.ends_with(".flatpak"));
+    assert!(artifact.checksum.is_some());
+
+    // The app-id comes from the repository, and there's no icon to export
+    assert_eq!(info.app_id, "io.github.axodotdev.axolotlsay");
+    assert_eq!(info.main_binary, BIN_AXO_NAME);
+    assert_eq!(info.arch, "x86_64");
+    assert!(info.icon.is_none());
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(dest_path, &info.source_dir.join(BIN_AXO_NAME));
+}
+
+#[test]
+fn flatpak_with_icon_and_app_id() {
+    let graph = graph_with_flatpaks(
+        json!({
+            "flatpak-app-id": "dev.axo.Axolotlsay",
+            "flatpak-icon": "assets/axolotl.svg",
+        }),
+        None,
+    );
+    let flatpaks = flatpaks(&graph);
+    assert_eq!(flatpaks.len(), 1);
+    let (artifact, info) = flatpaks[0];
+    assert_eq!(info.app_id, "dev.axo.Axolotlsay");
+    let (icon, desktop) = info.icon.as_ref().unwrap();
+    assert_eq!(icon, "axolotl.svg");
+    assert_eq!(desktop.icon, "dev.axo.Axolotlsay");
+    let static_assets = &artifact.archive.as_ref().unwrap().static_assets;
+    assert_eq!(static_assets.len(), 1);
+    assert!(static_assets[0].1.ends_with("assets/axolotl.svg"));
+}
+
+#[test]
+fn no_flatpak_without_app_id() {
+    let graph = graph_with_flatpaks(json!({}), None);
+    assert!(flatpaks(&graph).is_empty());
+}
@@ -0,0 +1,82 @@
+//! Tests for FreeBSD pkg installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{freebsd::FREEBSD_PKG_BIN_DIR, InstallerImpl},
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+fn graph_with_pkgs(targets: &[&str]) -> DistGraph {
+    let workspace = mock_workspace(vec![PackageInfo {
+        license: Some("MIT OR Apache-2.0".to_owned()),
+        ..pkg_axo_dist(json!({
+            "installers": ["freebsd-pkg"],
+            "targets": targets,
+            "dependencies": {
+                "pkg": {
+                    "security/openssl": { "version": "3.0", "stage": ["run"] },
+                    "devel/cmake": "*",
+                },
+            },
+        }))
+    }]);
+    let cfg = Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::FreebsdPkg],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn pkg_manifest_for_freebsd_variant() {
+    let graph = graph_with_pkgs(&["x86_64-unknown-freebsd", "x86_64-unknown-linux-gnu"]);
+    let pkgs = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::FreebsdPkg(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(pkgs.len(), 1);
+    let (artifact, info) = pkgs[0];
+    assert_eq!(artifact.target_triples, ["x86_64-unknown-freebsd"]);
+    assert!(artifact.id.ends_with(".pkg"));
+
+    // The binary goes to /usr/local/bin
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(
+        dest_path,
+        &info
+            .package_dir
+            .join(FREEBSD_PKG_BIN_DIR)
+            .join(BIN_AXO_NAME)
+    );
+
+    let manifest = &info.manifest;
+    assert_eq!(manifest.name, BIN_AXO_NAME);
+    assert_eq!(manifest.version, BIN_AXO_VER);
+    assert_eq!(manifest.abi, "FreeBSD:*:amd64");
+    assert_eq!(manifest.licenselogic, "or");
+    assert_eq!(manifest.licenses, ["MIT", "Apache-2.0"]);
+    // Only runtime pkg dependencies end up in deps
+    let deps = manifest.deps.iter().collect::<Vec<_>>();
+    assert_eq!(deps.len(), 1);
+    assert_eq!(deps[0].0, "openssl");
+    assert_eq!(deps[0].1.origin, "security/openssl");
+    assert_eq!(deps[0].1.version.as_deref(), Some("3.0"));
+}
+
+#[test]
+fn no_pkgs_without_freebsd_targets() {
+    let graph = graph_with_pkgs(&["x86_64-unknown-linux-gnu"]);
+    assert!(!graph.artifacts.iter().any(|a| matches!(
+        a.kind,
+        ArtifactKind::Installer(InstallerImpl::FreebsdPkg(_))
+    )));
+}
@@ -0,0 +1,38 @@
+//! Tests for writing generated files
+
+use camino::Utf8PathBuf;
+
+use crate::backend::write_if_changed;
+
+#[test]
+fn unchanged_file_isnt_rewritten() {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-generate-{}", std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    let file = dir.join(".github/workflows/release.yml");
+
+    // Missing files get written (with their parent dirs)
+    assert!(write_if_changed(&file, "name: Release\n").unwrap());
+    let mtime = std::fs::metadata(&file).unwrap().modified().unwrap();
+
+    // If nothing changed, the file is left as-is, even if its line endings differ
+    std::fs::write(&file, "name: Release\r\n").unwrap();
+    let mtime_crlf = std::fs::metadata(&file).unwrap().modified().unwrap();
+    assert!(mtime_crlf >= mtime);
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    assert!(!write_if_changed(&file, "name: Release\n").unwrap());
+    assert_eq!(
+        std::fs::metadata(&file).unwrap().modified().unwrap(),
+        mtime_crlf
+    );
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "name: Release\r\n");
+
+    // Actual changes still get written
+    assert!(write_if_changed(&file, "name: Release 2\n").unwrap());
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "name: Release 2\n");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
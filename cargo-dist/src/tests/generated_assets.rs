@@ -0,0 +1,156 @@
+//! Tests for files generated by running commands (generated-assets)
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    gather_work_for_workspace, run_build_step, BuildStep, DistGraph, GenerateAssetStep, ZipDirStep,
+};
+
+const COMPLETION: &str = "complete -F _axolotlsay axolotlsay";
+
+fn graph_with_completions() -> DistGraph {
+    let workspace = mock_workspace(vec![PackageInfo {
+        package_root: "axolotlsay".into(),
+        ..pkg_axo_dist(json!({
+            "targets": ["x86_64-unknown-linux-gnu"],
+            "unix-archive": ".tar.gz",
+            "generated-assets": [{
+                "path": "completions/axolotlsay.bash",
+                "command": ["echo", COMPLETION],
+            }],
+        }))
+    }]);
+    gather_work_for_workspace(&mock_config(), mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn generated_asset_runs_before_archiving() {
+    let graph = graph_with_completions();
+    let (generate_idx, generate) = graph
+        .build_steps
+        .iter()
+        .enumerate()
+        .find_map(|(idx, step)| match step {
+            BuildStep::GenerateAsset(step) => Some((idx, step)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(generate.command, ["echo", COMPLETION]);
+    assert_eq!(generate.working_dir, "axolotlsay");
+
+    // It's written into the archive's dir, which is zipped up after
+    let (zip_idx, zip) = graph
+        .build_steps
+        .iter()
+        .enumerate()
+        .find_map(|(idx, step)| match step {
+            BuildStep::Zip(zip) if generate.dest_path.starts_with(&zip.src_path) => {
+                Some((idx, zip))
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        generate.dest_path.strip_prefix(&zip.src_path).unwrap(),
+        "completions/axolotlsay.bash"
+    );
+    assert!(generate_idx < zip_idx);
+    assert!(graph.build_plan.depends_on(zip_idx, generate_idx));
+
+    // We can't know what the command reads, so the archive is never considered up to date
+    let archive = graph
+        .artifacts
+        .iter()
+        .find(|artifact| artifact.file_path == zip.dest_path)
+        .unwrap();
+    assert!(archive.inputs.is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn generated_asset_ends_up_in_archive() {
+    use std::io::Read;
+
+    let graph = graph_with_completions();
+    let generate = graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::GenerateAsset(step) => Some(step),
+            _ => None,
+        })
+        .unwrap();
+    let zip = graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::Zip(zip) if generate.dest_path.starts_with(&zip.src_path) => Some(zip),
+            _ => None,
+        })
+        .unwrap();
+
+    // Run the two steps for real, just somewhere else
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!(
+            "cargo-dist-generated-assets-{}",
+            std::process::id()
+        ));
+    let archive_dir = dir.join("axolotlsay-x86_64-unknown-linux-gnu");
+    let archive_path = dir.join("axolotlsay-x86_64-unknown-linux-gnu.tar.gz");
+    std::fs::create_dir_all(&dir).unwrap();
+    let generate = BuildStep::GenerateAsset(GenerateAssetStep {
+        command: generate.command.clone(),
+        working_dir: dir.clone(),
+        dest_path: archive_dir.join(generate.dest_path.strip_prefix(&zip.src_path).unwrap()),
+    });
+    let zip = BuildStep::Zip(ZipDirStep {
+        src_path: archive_dir,
+        dest_path: archive_path.clone(),
+        with_root: zip.with_root.clone(),
+        zip_style: zip.zip_style,
+        executables: vec![],
+    });
+    run_build_step(&graph, &generate).unwrap();
+    run_build_step(&graph, &zip).unwrap();
+
+    let tarball = std::fs::File::open(&archive_path).unwrap();
+    let mut tarball = tar::Archive::new(flate2::read::GzDecoder::new(tarball));
+    let mut completion = None;
+    for entry in tarball.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().into_owned();
+        if path.ends_with("completions/axolotlsay.bash") {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            completion = Some((path, contents));
+        }
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let (path, contents) = completion.expect("completion wasn't archived");
+    assert_eq!(
+        path,
+        std::path::Path::new("axolotlsay-x86_64-unknown-linux-gnu/completions/axolotlsay.bash")
+    );
+    assert_eq!(contents, format!("{COMPLETION}\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn failed_command_is_an_error() {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir()).unwrap();
+    let step = BuildStep::GenerateAsset(GenerateAssetStep {
+        command: vec!["false".to_owned()],
+        working_dir: dir.clone(),
+        dest_path: dir.join(format!("cargo-dist-failed-asset-{}", std::process::id())),
+    });
+    let err = run_build_step(&graph_with_completions(), &step).unwrap_err();
+    assert!(
+        err.to_string().starts_with("false failed to generate"),
+        "{err}"
+    );
+}
@@ -0,0 +1,66 @@
+//! Tests for generating Gitea Actions
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, DistGraph};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+const GITEA_REPO_URL: &str = "https://codeberg.org/axodotdev/axolotlsay.git";
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    workspace.repository_url = Some(GITEA_REPO_URL.to_owned());
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn gitea_workflow() {
+    let graph = graph_with(json!({
+        "ci": ["gitea"],
+        // Pin this so the snapshot doesn't change with every release
+        "cargo-dist-version": "0.3.1",
+    }));
+    assert!(graph.ci.github.is_none());
+    let ci = graph.ci.gitea.as_ref().expect("no gitea ci");
+    assert_eq!(ci.artifacts_matrix.include.len(), TARGETS.len());
+    assert!(ci.global_task.is_some());
+
+    // Gitea has the same layout as Github, but can live anywhere
+    assert_eq!(
+        graph.artifact_download_url.as_deref(),
+        Some(&*format!(
+            "https://codeberg.org/axodotdev/axolotlsay/releases/download/v{BIN_AXO_VER}"
+        ))
+    );
+    // The release notes still get generated for the release
+    assert!(graph.announcement_github_body.is_some());
+
+    insta::assert_snapshot!(ci.generate_gitea_ci(&graph).unwrap());
+}
+
+#[test]
+fn gitea_existing_release() {
+    let graph = graph_with(json!({
+        "ci": ["gitea"],
+        "create-release": false,
+        "pr-run-mode": "skip",
+    }));
+    let ci = graph.ci.gitea.as_ref().unwrap();
+    let workflow = ci.generate_gitea_ci(&graph).unwrap();
+    assert!(!workflow.contains("  pull_request:\n"));
+    assert!(workflow.contains("release_id=\"$(api \"$GITEA_API/releases/tags/$TAG\" | jq .id)\""));
+    assert!(workflow.contains("--data '{\"draft\": false}'"));
+}
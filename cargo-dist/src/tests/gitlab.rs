@@ -0,0 +1,146 @@
+//! Tests for generating GitLab CI
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, DistGraph};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    graph_for_repo(workspace_dist, REPO_URL)
+}
+
+fn graph_for_repo(workspace_dist: serde_json::Value, repo_url: &str) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    workspace.repository_url = Some(repo_url.to_owned());
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn gitlab_jobs_per_target() {
+    let graph = graph_with(json!({ "ci": ["gitlab"] }));
+    assert!(graph.ci.github.is_none());
+    let ci = graph.ci.gitlab.as_ref().expect("no gitlab ci");
+
+    let names = ci
+        .local_jobs
+        .iter()
+        .map(|job| job.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        TARGETS
+            .iter()
+            .map(|t| format!("build-local-{t}"))
+            .collect::<Vec<_>>()
+    );
+    let windows = &ci.local_jobs[2];
+    assert_eq!(windows.os, "windows");
+    assert_eq!(
+        windows.dist_args,
+        "--artifacts=local --target=x86_64-pc-windows-msvc"
+    );
+    assert_eq!(ci.global_job.as_ref().unwrap().os, "linux");
+
+    let pipeline = ci.generate_gitlab_ci(&graph).unwrap();
+    assert!(pipeline.contains(&format!(
+        "    - if: $CI_COMMIT_TAG =~ /{}/\n",
+        graph.tag_regex()
+    )));
+    // The default pr-run-mode only plans merge requests
+    assert!(pipeline.contains("    - if: $CI_PIPELINE_SOURCE == \"merge_request_event\"\n"));
+    assert!(pipeline.contains(
+        "build-local-x86_64-pc-windows-msvc:\n  stage: build\n  extends: .dist-windows\n  needs: [plan]\n  rules:\n    - if: $CI_COMMIT_TAG\n"
+    ));
+    assert!(pipeline.contains("    - build-local-x86_64-unknown-linux-gnu\n    - build-global\n"));
+    assert!(pipeline.contains("      release-cli create "));
+    assert!(!pipeline.contains("auto_cancel:"));
+}
+
+#[test]
+fn gitlab_respects_ci_settings() {
+    let graph = graph_with(json!({
+        "ci": ["gitlab"],
+        "merge-tasks": true,
+        "fail-fast": true,
+        "pr-run-mode": "upload",
+        "create-release": false,
+    }));
+    let ci = graph.ci.gitlab.as_ref().unwrap();
+    let names = ci
+        .local_jobs
+        .iter()
+        .map(|job| job.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "build-local-x86_64-unknown-linux-gnu",
+            "build-local-macos",
+            "build-local-x86_64-pc-windows-msvc"
+        ]
+    );
+    assert_eq!(
+        ci.local_jobs[1].dist_args,
+        "--artifacts=local --target=aarch64-apple-darwin --target=x86_64-apple-darwin"
+    );
+
+    let pipeline = ci.generate_gitlab_ci(&graph).unwrap();
+    assert!(pipeline.contains("  auto_cancel:\n    on_job_failure: all\n"));
+    // Builds run on merge requests too, so they aren't restricted to tags
+    assert!(pipeline.contains("extends: .dist-macos\n  needs: [plan]\n  script:\n"));
+    assert!(!pipeline.contains("release-cli create"));
+    assert!(pipeline.contains("/assets/links\"\n"));
+}
+
+#[test]
+fn gitlab_download_urls() {
+    let graph = graph_with(json!({ "ci": ["gitlab"] }));
+    assert_eq!(
+        graph.artifact_download_url.as_deref(),
+        Some(&*format!(
+            "{}/-/releases/v{BIN_AXO_VER}/downloads",
+            REPO_URL
+        ))
+    );
+
+    // Github wins if both are enabled
+    let graph = graph_with(json!({ "ci": ["github", "gitlab"] }));
+    assert!(graph.ci.github.is_some() && graph.ci.gitlab.is_some());
+    assert_eq!(
+        graph.artifact_download_url.as_deref(),
+        Some(&*format!("{}/releases/download/v{BIN_AXO_VER}", REPO_URL))
+    );
+
+    // Self-hosted instances and nested groups are fine
+    let graph = graph_for_repo(
+        json!({ "ci": ["gitlab"] }),
+        "git@gitlab.example.com:axodotdev/tools/axolotlsay.git",
+    );
+    assert_eq!(
+        graph.artifact_download_url.as_deref(),
+        Some(&*format!(
+            "https://gitlab.example.com/axodotdev/tools/axolotlsay/-/releases/v{BIN_AXO_VER}/downloads"
+        ))
+    );
+}
+
+#[test]
+fn gitlab_tag_regex() {
+    let graph = graph_with(json!({ "ci": ["gitlab"] }));
+    assert_eq!(graph.tag_regex(), "^.*[0-9]+\\.[0-9]+\\.[0-9]+[^/]*$");
+}
@@ -0,0 +1,100 @@
+//! Tests for dumping the DistGraph as json
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::Config,
+    gather_work_for_workspace,
+    graph::{format_build_steps, GraphJson},
+    DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"];
+
+fn graph() -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell", "powershell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+fn graph_json() -> serde_json::Value {
+    serde_json::to_value(GraphJson::new(&graph())).unwrap()
+}
+
+#[test]
+fn two_target_graph() {
+    insta::assert_snapshot!(serde_json::to_string_pretty(&graph_json()).unwrap());
+}
+
+#[test]
+fn graph_indices_are_consistent() {
+    let graph = graph_json();
+    let artifacts = graph["artifacts"].as_array().unwrap();
+    let binaries = graph["binaries"].as_array().unwrap();
+    let steps = graph["build_steps"].as_array().unwrap();
+
+    let release = &graph["releases"][0];
+    for variant in release["variants"].as_array().unwrap() {
+        let variant = &graph["variants"][variant.as_u64().unwrap() as usize];
+        for binary in variant["binaries"].as_array().unwrap() {
+            assert!(binaries.get(binary.as_u64().unwrap() as usize).is_some());
+        }
+        for artifact in variant["local_artifacts"].as_array().unwrap() {
+            assert!(artifacts.get(artifact.as_u64().unwrap() as usize).is_some());
+        }
+    }
+    // Steps only ever depend on earlier steps
+    for (idx, step) in steps.iter().enumerate() {
+        for dep in step["deps"].as_array().unwrap() {
+            assert!((dep.as_u64().unwrap() as usize) < idx);
+        }
+    }
+    // Every target gets built
+    let built = steps
+        .iter()
+        .filter_map(|step| step["cargo"]["target_triple"].as_str())
+        .collect::<Vec<_>>();
+    for target in TARGETS {
+        assert!(built.contains(target), "{built:?}");
+    }
+}
+
+#[test]
+fn dry_run_lists_steps() {
+    let graph = graph();
+    let steps = format_build_steps(&graph, &[]);
+    assert_eq!(
+        steps,
+        r#"  1. cargo build --profile dist --target x86_64-pc-windows-msvc --workspace
+       RUSTFLAGS=" -Ctarget-feature=+crt-static"
+       expected binaries: axolotlsay-v1.0.0-x86_64-pc-windows-msvc
+  2. cargo build --profile dist --target x86_64-unknown-linux-gnu --workspace
+       RUSTFLAGS=""
+       expected binaries: axolotlsay-v1.0.0-x86_64-unknown-linux-gnu
+  3. zip distrib/axolotlsay-x86_64-unknown-linux-gnu -> distrib/axolotlsay-x86_64-unknown-linux-gnu.tar.xz
+  4. checksum (sha256) distrib/axolotlsay-x86_64-unknown-linux-gnu.tar.xz -> distrib/axolotlsay-x86_64-unknown-linux-gnu.tar.xz.sha256
+  5. zip distrib/axolotlsay-x86_64-pc-windows-msvc -> distrib/axolotlsay-x86_64-pc-windows-msvc.zip
+  6. checksum (sha256) distrib/axolotlsay-x86_64-pc-windows-msvc.zip -> distrib/axolotlsay-x86_64-pc-windows-msvc.zip.sha256
+  7. generate shell installer
+  8. generate powershell installer
+"#
+    );
+}
+
+#[test]
+fn dry_run_marks_skipped_steps() {
+    let graph = graph();
+    let mut skipped = vec![false; graph.build_steps.len()];
+    skipped[2] = true;
+    let steps = format_build_steps(&graph, &skipped);
+    assert!(steps.contains(".tar.xz (up to date, skipped)\n  4. checksum"));
+    assert_eq!(steps.matches("skipped").count(), 1);
+}
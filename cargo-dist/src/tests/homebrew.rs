@@ -0,0 +1,186 @@
+//! Tests for Homebrew formulae
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::{
+        installer::{
+            homebrew::{render_homebrew_formula, HomebrewInstallerInfo},
+            InstallerImpl,
+        },
+        templates::Templates,
+    },
+    config::{ArtifactMode, Config},
+    ArtifactKind, BuildStep, ChecksumImpl, DistGraph,
+};
+
+fn graph_with_checksum(checksum: &str) -> DistGraph {
+    graph_for_targets(&["aarch64-apple-darwin", "x86_64-apple-darwin"], checksum)
+}
+
+fn graph_for_targets(targets: &[&str], checksum: &str) -> DistGraph {
+    graph_for_mode(targets, checksum, ArtifactMode::All)
+}
+
+fn graph_for_mode(targets: &[&str], checksum: &str, artifact_mode: ArtifactMode) -> DistGraph {
+    let dist = json!({
+        "installers": ["homebrew"],
+        "targets": targets,
+        "checksum": checksum,
+    });
+    graph_for_dist(targets, dist, artifact_mode)
+}
+
+fn graph_for_dist(
+    targets: &[&str],
+    dist: serde_json::Value,
+    artifact_mode: ArtifactMode,
+) -> DistGraph {
+    let cfg = Config {
+        artifact_mode,
+        targets: targets.iter().map(|target| target.to_string()).collect(),
+        ..mock_config()
+    };
+    graph_for_dist_metadata(dist, &cfg)
+}
+
+fn formula(graph: &DistGraph) -> &HomebrewInstallerInfo {
+    graph
+        .artifacts
+        .iter()
+        .find_map(|artifact| match &artifact.kind {
+            ArtifactKind::Installer(InstallerImpl::Homebrew(info)) => Some(info),
+            _ => None,
+        })
+        .expect("no homebrew formula")
+}
+
+#[test]
+fn formula_references_archive_checksums() {
+    let graph = graph_with_checksum("sha256");
+    let info = formula(&graph);
+    let arm64 = info.arm64_sha256_artifact.as_deref().unwrap();
+    let x86_64 = info.x86_64_sha256_artifact.as_deref().unwrap();
+    assert_eq!(arm64, format!("{}.sha256", info.arm64.as_ref().unwrap().id));
+    assert_eq!(
+        x86_64,
+        format!("{}.sha256", info.x86_64.as_ref().unwrap().id)
+    );
+
+    // Both checksum files actually get built
+    let checksummed = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Checksum(ChecksumImpl { dest_path, .. }) => dest_path.file_name(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(checksummed.contains(&arm64));
+    assert!(checksummed.contains(&x86_64));
+}
+
+#[test]
+fn formula_references_checksums_in_global_mode() {
+    // The archives aren't in the graph when only building global artifacts (like in CI),
+    // but the formula still needs to find their checksums
+    let graph = graph_for_mode(
+        &["aarch64-apple-darwin", "x86_64-apple-darwin"],
+        "sha256",
+        ArtifactMode::Global,
+    );
+    let info = formula(&graph);
+    assert_eq!(
+        info.arm64_sha256_artifact,
+        Some(format!("{}.sha256", info.arm64.as_ref().unwrap().id))
+    );
+    assert_eq!(
+        info.x86_64_sha256_artifact,
+        Some(format!("{}.sha256", info.x86_64.as_ref().unwrap().id))
+    );
+}
+
+#[test]
+fn formula_ignores_other_checksums() {
+    // Homebrew only understands sha256, so we'll have to hash the archives ourselves
+    let graph = graph_with_checksum("sha512");
+    let info = formula(&graph);
+    assert_eq!(info.arm64_sha256_artifact, None);
+    assert_eq!(info.x86_64_sha256_artifact, None);
+}
+
+#[test]
+fn formula_for_linuxbrew() {
+    let graph = graph_for_targets(
+        &[
+            "aarch64-apple-darwin",
+            "x86_64-apple-darwin",
+            "x86_64-unknown-linux-gnu",
+        ],
+        "sha256",
+    );
+    let info = formula(&graph);
+    let linux = info.x86_64_linux.as_ref().unwrap();
+    assert_eq!(linux.target_triples, vec!["x86_64-unknown-linux-gnu"]);
+    assert!(info.arm64_linux.is_none());
+    assert_eq!(
+        info.x86_64_linux_sha256_artifact,
+        Some(format!("{}.sha256", linux.id))
+    );
+
+    let templates = Templates::new().unwrap();
+    let formula = render_homebrew_formula(&templates, info).unwrap();
+    assert!(formula.starts_with("class "));
+    let base_url = &info.inner.base_url;
+    let arm64 = &info.arm64.as_ref().unwrap().id;
+    let x86_64 = &info.x86_64.as_ref().unwrap().id;
+    assert!(formula.contains(&format!(
+        "  on_macos do\n    on_arm do\n      url \"{base_url}/{arm64}\"\n    end\n    on_intel do\n      url \"{base_url}/{x86_64}\"\n    end\n  end\n"
+    )));
+    assert!(formula.contains(&format!(
+        "  on_linux do\n    on_intel do\n      url \"{base_url}/{}\"\n    end\n  end\n",
+        linux.id
+    )));
+}
+
+#[test]
+fn formula_skips_other_linux_targets() {
+    let graph = graph_for_targets(
+        &["x86_64-apple-darwin", "armv7-unknown-linux-gnueabihf"],
+        "sha256",
+    );
+    let info = formula(&graph);
+    assert!(info.x86_64_linux.is_none());
+    assert!(info.arm64_linux.is_none());
+    assert!(!info
+        .inner
+        .artifacts
+        .iter()
+        .any(|artifact| artifact.id.contains("linux")));
+}
+
+#[test]
+fn formula_pins_dependency_versions() {
+    let targets = ["aarch64-apple-darwin"];
+    let dist = json!({
+        "installers": ["homebrew"],
+        "targets": targets,
+        "dependencies": {
+            "homebrew": {
+                "openssl": { "version": "3", "stage": ["run"] },
+                "xz": { "stage": ["run"] },
+                "cmake": "3.27",
+            }
+        },
+    });
+    let graph = graph_for_dist(&targets, dist, ArtifactMode::All);
+    let info = formula(&graph);
+    // Versions pick a versioned formula, and build-only dependencies aren't included
+    assert_eq!(info.dependencies, vec!["openssl@3", "xz"]);
+
+    let templates = Templates::new().unwrap();
+    let formula = render_homebrew_formula(&templates, info).unwrap();
+    assert!(formula.contains("  depends_on \"openssl@3\"\n  depends_on \"xz\"\n"));
+    assert!(!formula.contains("cmake"));
+}
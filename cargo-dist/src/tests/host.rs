@@ -0,0 +1,72 @@
+//! Tests for overriding the host target
+
+use super::mock::*;
+use axoproject::WorkspaceInfo;
+use serde_json::json;
+
+use crate::{
+    config::{ArtifactMode, Config},
+    gather_work_for_workspace, DistGraph,
+};
+
+fn workspace_with_host_target(host_target: Option<&str>) -> WorkspaceInfo {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": ["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"],
+    }));
+    if let Some(host_target) = host_target {
+        workspace.cargo_metadata_table = Some(json!({ "dist": { "host-target": host_target } }));
+    }
+    workspace
+}
+
+fn host_mode_graph(
+    workspace: &WorkspaceInfo,
+    host_target: Option<&str>,
+) -> miette::Result<DistGraph> {
+    let cfg = Config {
+        artifact_mode: ArtifactMode::Host,
+        targets: vec![],
+        host_target: host_target.map(|t| t.to_owned()),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), workspace)
+}
+
+#[test]
+fn host_mode_uses_detected_host() {
+    let workspace = workspace_with_host_target(None);
+    let graph = host_mode_graph(&workspace, None).unwrap();
+    assert_eq!(graph.releases[0].targets, ["x86_64-unknown-linux-gnu"]);
+}
+
+#[test]
+fn host_mode_uses_overridden_host() {
+    let workspace = workspace_with_host_target(None);
+    let graph = host_mode_graph(&workspace, Some("aarch64-apple-darwin")).unwrap();
+    assert_eq!(graph.releases[0].targets, ["aarch64-apple-darwin"]);
+    assert_eq!(graph.tools.cargo.host_target, "aarch64-apple-darwin");
+}
+
+#[test]
+fn host_target_config_and_cli() {
+    let workspace = workspace_with_host_target(Some("aarch64-apple-darwin"));
+    let graph = host_mode_graph(&workspace, None).unwrap();
+    assert_eq!(graph.releases[0].targets, ["aarch64-apple-darwin"]);
+
+    // The CLI wins over the config
+    let graph = host_mode_graph(&workspace, Some("x86_64-pc-windows-msvc")).unwrap();
+    assert_eq!(graph.releases[0].targets, ["x86_64-pc-windows-msvc"]);
+}
+
+#[test]
+fn implausible_host_target() {
+    let workspace = workspace_with_host_target(None);
+    for host_target in ["linux", "x86_64--linux", "x86_64 linux-gnu", ""] {
+        let err = host_mode_graph(&workspace, Some(host_target)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("doesn't look like a target triple"),
+            "{host_target}: {err}"
+        );
+    }
+}
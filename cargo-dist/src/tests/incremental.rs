@@ -0,0 +1,192 @@
+//! Tests for incremental builds
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::json;
+
+use crate::{
+    config::Config, gather_work_for_workspace, incremental::Freshness, BuildStep, DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu"];
+
+/// A scratch workspace with a real Cargo.toml and sources to fingerprint
+fn scratch_workspace(name: &str) -> Utf8PathBuf {
+    let root = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!(
+            "cargo-dist-incremental-{name}-{}",
+            std::process::id()
+        ));
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"axolotlsay\"\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+    root
+}
+
+fn graph(root: &Utf8Path) -> DistGraph {
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        manifest_path: root.join("Cargo.toml"),
+        package_root: root.to_owned(),
+        ..pkg_axo_dist(json!({
+            "installers": ["shell"],
+            "targets": TARGETS,
+        }))
+    }]);
+    workspace.workspace_dir = root.to_owned();
+    workspace.target_dir = root.join("target");
+    workspace.manifest_path = root.join("Cargo.toml");
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+/// Pretend to do a full incremental build
+fn fake_build(graph: &DistGraph) {
+    let freshness = Freshness::check(graph);
+    for artifact in &graph.artifacts {
+        std::fs::create_dir_all(artifact.file_path.parent().unwrap()).unwrap();
+        std::fs::write(&artifact.file_path, &artifact.id).unwrap();
+    }
+    freshness.save(graph).unwrap();
+}
+
+/// The kinds of steps an incremental build would still run
+fn steps_to_run(graph: &DistGraph) -> Vec<&'static str> {
+    let skipped = Freshness::check(graph).skipped_steps(graph);
+    graph
+        .build_steps
+        .iter()
+        .zip(skipped)
+        .filter(|(_, skipped)| !skipped)
+        .map(|(step, _)| match step {
+            BuildStep::Cargo(_) => "cargo",
+            BuildStep::CopyFile(_) | BuildStep::CopyDir(_) => "copy",
+            BuildStep::Zip(_) => "zip",
+            BuildStep::Checksum(_) => "checksum",
+            BuildStep::GenerateInstaller(_) => "installer",
+            BuildStep::UnifiedChecksum(_) => "unified-checksum",
+            _ => "other",
+        })
+        .collect()
+}
+
+#[test]
+fn first_run_builds_everything() {
+    let root = scratch_workspace("first");
+    let graph = graph(&root);
+    let freshness = Freshness::check(&graph);
+    let skipped = freshness.skipped_steps(&graph);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert!(freshness.fresh.iter().all(|&fresh| !fresh));
+    assert!(skipped.iter().all(|&skipped| !skipped));
+}
+
+#[test]
+fn second_run_skips_unchanged_artifacts() {
+    let root = scratch_workspace("second");
+    fake_build(&graph(&root));
+    let graph = graph(&root);
+    let steps = steps_to_run(&graph);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    // No builds or zips, only the things we don't track
+    assert!(!steps.contains(&"cargo"), "{steps:?}");
+    assert!(!steps.contains(&"zip"), "{steps:?}");
+    assert!(!steps.contains(&"copy"), "{steps:?}");
+    assert!(!steps.contains(&"checksum"), "{steps:?}");
+    assert!(steps.contains(&"installer"), "{steps:?}");
+}
+
+#[test]
+fn changed_sources_are_rebuilt() {
+    let root = scratch_workspace("changed");
+    fake_build(&graph(&root));
+    std::fs::write(
+        root.join("src/main.rs"),
+        "fn main() { println!(\"hi\"); }\n",
+    )
+    .unwrap();
+    let changed = graph(&root);
+    let changed_steps = steps_to_run(&changed);
+
+    // Deleting the archive also makes it stale
+    fake_build(&changed);
+    let archive = changed
+        .artifacts
+        .iter()
+        .find(|a| a.archive.is_some())
+        .unwrap();
+    std::fs::remove_file(&archive.file_path).unwrap();
+    let deleted_steps = steps_to_run(&graph(&root));
+    std::fs::remove_dir_all(&root).unwrap();
+
+    for steps in [changed_steps, deleted_steps] {
+        assert!(steps.contains(&"cargo"), "{steps:?}");
+        assert!(steps.contains(&"zip"), "{steps:?}");
+    }
+}
+
+#[test]
+fn target_dir_is_not_an_input() {
+    let root = scratch_workspace("target");
+    fake_build(&graph(&root));
+    std::fs::create_dir_all(root.join("target/dist/build")).unwrap();
+    std::fs::write(root.join("target/dist/build/noise"), "lots of noise").unwrap();
+    let steps = steps_to_run(&graph(&root));
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert!(!steps.contains(&"cargo"), "{steps:?}");
+    assert!(!steps.contains(&"zip"), "{steps:?}");
+}
+
+#[test]
+fn changed_path_dependencies_are_rebuilt() {
+    // Two local deps outside the package: one pulled in through [workspace.dependencies],
+    // and one that the first depends on by path
+    let root = scratch_workspace("path-deps");
+    let deps = root.with_file_name(format!("{}-deps", root.file_name().unwrap()));
+    for (dep, manifest) in [
+        (
+            "core",
+            "[package]\nname = \"core\"\n\n[dependencies]\nutil = { path = \"../util\" }\n",
+        ),
+        ("util", "[package]\nname = \"util\"\n"),
+    ] {
+        std::fs::create_dir_all(deps.join(dep).join("src")).unwrap();
+        std::fs::write(deps.join(dep).join("Cargo.toml"), manifest).unwrap();
+        std::fs::write(deps.join(dep).join("src/lib.rs"), "").unwrap();
+    }
+    std::fs::write(
+        root.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"axolotlsay\"\n\n\
+             [workspace.dependencies]\ncore = {{ path = \"{deps}/core\" }}\n\n\
+             [dependencies]\ncore = {{ workspace = true }}\n"
+        ),
+    )
+    .unwrap();
+
+    let mut steps = vec![];
+    for dep in ["core", "util"] {
+        fake_build(&graph(&root));
+        std::fs::write(deps.join(dep).join("src/lib.rs"), "pub fn hi() {}\n").unwrap();
+        steps.push(steps_to_run(&graph(&root)));
+    }
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_dir_all(&deps).unwrap();
+
+    for steps in steps {
+        assert!(steps.contains(&"cargo"), "{steps:?}");
+        assert!(steps.contains(&"zip"), "{steps:?}");
+    }
+}
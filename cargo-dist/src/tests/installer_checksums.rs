@@ -0,0 +1,122 @@
+//! Tests for installers verifying the checksums of the archives they fetch
+
+use super::mock::*;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{
+        fill_checksums, powershell::render_install_ps_script, shell::render_install_sh_script,
+        InstallerImpl, InstallerInfo,
+    },
+    config::Config,
+    ArtifactKind, DistGraph,
+};
+
+fn graph_with_checksum(installer: &str, target: &str, checksum: &str) -> DistGraph {
+    let cfg = Config {
+        targets: vec![target.to_owned()],
+        ..mock_config()
+    };
+    graph_for_dist_metadata(
+        json!({
+            "installers": [installer],
+            "targets": [target],
+            "checksum": checksum,
+        }),
+        &cfg,
+    )
+}
+
+/// Write a checksum file for each of the installer's archives to a temp dir, and fill them in
+fn fill_fake_checksums(info: &mut InstallerInfo, name: &str, digest: &str) {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for artifact in &info.artifacts {
+        let checksum = artifact.checksum.as_ref().unwrap();
+        std::fs::write(
+            dir.join(&checksum.artifact),
+            format!("{digest} *{}\n", artifact.id),
+        )
+        .unwrap();
+    }
+    fill_checksums(&dir, &mut info.artifacts).unwrap();
+}
+
+fn powershell_info(graph: &DistGraph) -> InstallerInfo {
+    graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Powershell(info)) => Some(info.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn shell_info(graph: &DistGraph) -> InstallerInfo {
+    graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn powershell_verifies_checksums() {
+    let graph = graph_with_checksum("powershell", "x86_64-pc-windows-msvc", "sha256");
+    let mut info = powershell_info(&graph);
+    let digest = "0123456789abcdef".repeat(4);
+    fill_fake_checksums(&mut info, "powershell", &digest);
+
+    let script = render_install_ps_script(&graph.templates, &info).unwrap();
+    assert!(script.contains(&format!("      \"checksum\" = \"{digest}\"\n")));
+    assert!(script.contains("      \"checksum_style\" = \"sha256\"\n"));
+    assert!(script.contains(
+        "    $actual = (Get-FileHash -Path $dir_path -Algorithm $checksum_style).Hash.ToLower()\n"
+    ));
+    assert!(script.contains("    if ($actual -ne $checksum) {\n"));
+}
+
+#[test]
+fn powershell_without_checksums() {
+    let graph = graph_with_checksum("powershell", "x86_64-pc-windows-msvc", "false");
+    let info = powershell_info(&graph);
+    assert!(info.artifacts.iter().all(|a| a.checksum.is_none()));
+    let script = render_install_ps_script(&graph.templates, &info).unwrap();
+    assert!(!script.contains("\"checksum\" ="));
+}
+
+#[test]
+fn shell_verifies_checksums() {
+    let graph = graph_with_checksum("shell", "x86_64-unknown-linux-gnu", "sha256");
+    let mut info = shell_info(&graph);
+    let digest = "fedcba9876543210".repeat(4);
+    fill_fake_checksums(&mut info, "shell", &digest);
+
+    let script = render_install_sh_script(&graph.templates, &info).unwrap();
+    assert!(script.contains("            _checksum_style=\"sha256\"\n"));
+    assert!(script.contains(&format!("            _checksum_value=\"{digest}\"\n")));
+    assert!(script
+        .contains("    verify_checksum \"$_file\" \"$_checksum_style\" \"$_checksum_value\"\n"));
+    // Either tool will do
+    assert!(
+        script.contains("                _calculated_checksum=\"$(sha256sum -b \"$_file\")\"\n")
+    );
+    assert!(script
+        .contains("                _calculated_checksum=\"$(shasum -a 256 -b \"$_file\")\"\n"));
+}
+
+#[test]
+fn shell_without_checksums() {
+    let graph = graph_with_checksum("shell", "x86_64-unknown-linux-gnu", "false");
+    let info = shell_info(&graph);
+    assert!(info.artifacts.iter().all(|a| a.checksum.is_none()));
+    let script = render_install_sh_script(&graph.templates, &info).unwrap();
+    assert!(!script.contains("            _checksum_value=\""));
+}
@@ -0,0 +1,88 @@
+//! Tests for selecting license files and recording licenses
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{build_manifest, gather_work_for_workspace, ArtifactKind, DistGraph, StaticAssetKind};
+
+const LICENSE: &str = "MIT OR Apache-2.0";
+
+fn graph_with(mut package_dist: serde_json::Value) -> DistGraph {
+    package_dist["targets"] = json!(["x86_64-unknown-linux-gnu"]);
+    let workspace = mock_workspace(vec![PackageInfo {
+        license: Some(LICENSE.to_owned()),
+        license_files: ["LICENSE-APACHE", "LICENSE-MIT", "LICENSE-OLD"]
+            .into_iter()
+            .map(Utf8PathBuf::from)
+            .collect(),
+        ..pkg_axo_dist(package_dist)
+    }]);
+    gather_work_for_workspace(&mock_config(), mock_tools(), &workspace).unwrap()
+}
+
+/// Get the license files that ended up in the linux archive
+fn archived_licenses(graph: &DistGraph) -> Vec<String> {
+    let archive = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .and_then(|a| a.archive.as_ref())
+        .expect("no archive");
+    archive
+        .static_assets
+        .iter()
+        .filter(|(kind, _)| matches!(kind, StaticAssetKind::License))
+        .map(|(_, path)| path.to_string())
+        .collect()
+}
+
+#[test]
+fn auto_detected_licenses_by_default() {
+    let graph = graph_with(json!({}));
+    assert_eq!(
+        archived_licenses(&graph),
+        ["LICENSE-APACHE", "LICENSE-MIT", "LICENSE-OLD"]
+    );
+}
+
+#[test]
+fn only_selected_licenses_are_included() {
+    let graph = graph_with(json!({
+        "license-files": ["LICENSE-APACHE", "LICENSE-MIT", "legal/THIRDPARTY.md"],
+    }));
+    assert_eq!(
+        archived_licenses(&graph),
+        ["LICENSE-APACHE", "LICENSE-MIT", "legal/THIRDPARTY.md"]
+    );
+
+    // Explicitly selected licenses don't care about auto-includes
+    let graph = graph_with(json!({
+        "auto-includes": false,
+        "license-files": ["LICENSE-MIT"],
+    }));
+    assert_eq!(archived_licenses(&graph), ["LICENSE-MIT"]);
+}
+
+#[test]
+fn manifest_records_spdx_license() {
+    let graph = graph_with(json!({ "license-files": ["LICENSE-MIT"] }));
+    let manifest = build_manifest(&mock_config(), &graph);
+    assert!(!manifest.artifacts.is_empty());
+    for artifact in manifest.artifacts.values() {
+        assert_eq!(artifact.license.as_deref(), Some(LICENSE));
+    }
+    let archive = manifest
+        .artifacts
+        .values()
+        .find(|a| matches!(a.kind, cargo_dist_schema::ArtifactKind::ExecutableZip))
+        .unwrap();
+    let licenses = archive
+        .assets
+        .iter()
+        .filter(|asset| matches!(asset.kind, cargo_dist_schema::AssetKind::License))
+        .filter_map(|asset| asset.name.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(licenses, ["LICENSE-MIT"]);
+}
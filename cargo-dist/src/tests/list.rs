@@ -0,0 +1,73 @@
+//! Tests for `cargo dist plan --list`
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{config::Config, list_apps_for_workspace, DistListing};
+
+fn config_with(tag: Option<&str>) -> Config {
+    Config {
+        needs_coherent_announcement_tag: false,
+        targets: vec![],
+        announcement_tag: tag.map(|tag| tag.to_owned()),
+        ..mock_config()
+    }
+}
+
+/// A workspace with a normal binary, a library, a publish=false binary, and a
+/// publish=false binary that sets dist=true
+fn listing(tag: Option<&str>) -> DistListing {
+    let mut workspace = mock_workspace(vec![
+        PackageInfo {
+            cargo_metadata_table: Some(json!({
+                "dist": {
+                    "targets": ["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"],
+                    "extra-targets": ["x86_64-unknown-linux-musl"],
+                }
+            })),
+            ..pkg_axo_bin()
+        },
+        pkg_some_lib(),
+        PackageInfo {
+            cargo_metadata_table: Some(json!({
+                "dist": {
+                    "dist": true,
+                    "targets": ["x86_64-pc-windows-msvc"],
+                }
+            })),
+            ..pkg_forced_bin()
+        },
+        pkg_test_bin1(),
+    ]);
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "targets": ["x86_64-unknown-linux-gnu"],
+        }
+    }));
+    list_apps_for_workspace(&config_with(tag), mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn list_mixed_workspace() {
+    insta::assert_snapshot!(serde_json::to_string_pretty(&listing(None)).unwrap());
+}
+
+#[test]
+fn list_respects_tag() {
+    let listing = listing(Some(&format!("{BIN_AXO_NAME}-v{BIN_AXO_VER}")));
+    let reasons = listing
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), package.disabled_reason.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        reasons,
+        vec![
+            (BIN_AXO_NAME, None),
+            (LIB_SOME_NAME, Some("no binaries")),
+            (BIN_FORCED_NAME, Some("didn't match tag axolotlsay-v1.0.0")),
+            (BIN_TEST1_NAME, Some("publish = false")),
+        ]
+    );
+}
@@ -0,0 +1,48 @@
+//! Tests for looking things up in the DistGraph by id
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, DistGraph};
+
+const TARGET: &str = "x86_64-unknown-linux-gnu";
+
+fn graph() -> DistGraph {
+    let cfg = Config {
+        targets: vec![TARGET.to_owned()],
+        ..mock_config()
+    };
+    graph_for_dist_metadata(
+        json!({
+            "targets": [TARGET],
+        }),
+        &cfg,
+    )
+}
+
+#[test]
+fn artifact_by_id() {
+    let graph = graph();
+    let id = format!("{BIN_AXO_NAME}-{TARGET}.tar.xz");
+    let artifact = graph.artifact_by_id(&id).expect("archive wasn't found");
+    assert_eq!(artifact.id, id);
+    assert_eq!(artifact.target_triples, [TARGET]);
+    assert!(graph
+        .artifact_by_id("axolotlsay-mips-unknown-linux-gnu.tar.xz")
+        .is_none());
+}
+
+#[test]
+fn release_and_variant_by_id() {
+    let graph = graph();
+    let release = graph
+        .release_by_id(BIN_AXO_NAME)
+        .expect("release wasn't found");
+    assert_eq!(release.app_name, BIN_AXO_NAME);
+    assert!(graph.release_by_id("axolotlsing").is_none());
+
+    let id = format!("{BIN_AXO_NAME}-{TARGET}");
+    let variant = graph.variant_by_id(&id).expect("variant wasn't found");
+    assert_eq!(variant.target, TARGET);
+    assert!(graph.variant_by_id(TARGET).is_none());
+}
@@ -0,0 +1,86 @@
+//! Tests for macOS pkg installers
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{macpkg::MAC_PKG_INSTALL_LOCATION, InstallerImpl},
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-apple-darwin",
+    "x86_64-unknown-linux-gnu",
+];
+
+fn graph_with_pkgs(dist: serde_json::Value) -> DistGraph {
+    let mut dist = dist;
+    dist["installers"] = json!(["mac-pkg"]);
+    dist["targets"] = json!(TARGETS);
+    let mut workspace = workspace_axo_dist(dist);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::MacPkg],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn pkgs_for_apple_only() {
+    let graph = graph_with_pkgs(json!({}));
+    let mut pkgs = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::MacPkg(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    pkgs.sort_by_key(|(a, _)| a.id.clone());
+    assert_eq!(pkgs.len(), 2);
+    let (artifact, info) = pkgs[0];
+    assert_eq!(artifact.target_triples, ["aarch64-apple-darwin"]);
+    assert_eq!(
+        artifact.id,
+        format!("{BIN_AXO_NAME}-aarch64-apple-darwin.pkg")
+    );
+    assert!(artifact.checksum.is_some());
+    // The binaries are staged at the root of the payload
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(dest_path, &info.package_dir.join(BIN_AXO_NAME));
+
+    // Without any config the identifier comes from the repository
+    assert_eq!(info.identifier, "io.github.axodotdev.axolotlsay");
+    assert_eq!(info.install_location, MAC_PKG_INSTALL_LOCATION);
+    assert_eq!(info.sign_identity, None);
+
+    // They show up in the download table
+    let body = graph.announcement_github_body.as_ref().unwrap();
+    assert!(body.contains(&artifact.id), "{body}");
+}
+
+#[test]
+fn pkg_config() {
+    let graph = graph_with_pkgs(json!({
+        "mac-pkg-identifier": "dev.axo.axolotlsay",
+        "mac-pkg-install-location": "/opt/axolotlsay/bin",
+        "mac-pkg-sign-identity": "Developer ID Installer: Axo (ABCDE12345)",
+    }));
+    let info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::MacPkg(info)) => Some(info),
+            _ => None,
+        })
+        .expect("no pkg!?");
+    assert_eq!(info.identifier, "dev.axo.axolotlsay");
+    assert_eq!(info.install_location, "/opt/axolotlsay/bin");
+    assert!(info
+        .productbuild_args()
+        .contains(&"Developer ID Installer: Axo (ABCDE12345)".to_owned()));
+}
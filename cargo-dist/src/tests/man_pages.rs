@@ -0,0 +1,319 @@
+//! Tests for bundling man pages into archives and installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{
+        deb::{DebInstallerInfo, DEB_BIN_DIR, DEB_MAN_DIR},
+        rpm::{RpmInstallerInfo, RPM_BIN_DIR},
+        shell::render_install_sh_script,
+        InstallerImpl, InstallerInfo,
+    },
+    config::Config,
+    gather_work_for_workspace, run_build_step, ArtifactKind, BuildStep, CopyFileStep, DistGraph,
+    ZipDirStep,
+};
+
+const HOST: &str = "x86_64-unknown-linux-gnu";
+const CROSS: &str = "aarch64-unknown-linux-gnu";
+
+fn config_with(targets: &[&str]) -> Config {
+    Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    }
+}
+
+/// A package dir with a man dir containing a man page (and a file that isn't one)
+fn package_dir(name: &str) -> Utf8PathBuf {
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!(
+            "cargo-dist-man-pages-{name}-{}",
+            std::process::id()
+        ));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(dir.join("man")).unwrap();
+    std::fs::write(dir.join("man").join("axolotlsay.1"), ".TH AXOLOTLSAY 1\n").unwrap();
+    std::fs::write(dir.join("man").join("README.md"), "how to write man pages").unwrap();
+    dir
+}
+
+fn graph_with(package_root: &Utf8PathBuf, targets: &[&str], dist: serde_json::Value) -> DistGraph {
+    let mut dist = dist;
+    dist["targets"] = json!(targets);
+    dist["unix-archive"] = json!(".tar.gz");
+    dist["installers"] = json!(["shell", "deb", "rpm"]);
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        package_root: package_root.clone(),
+        ..pkg_axo_dist(dist)
+    }]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    gather_work_for_workspace(&config_with(targets), mock_tools(), &workspace).unwrap()
+}
+
+fn archive_zip(graph: &DistGraph) -> &ZipDirStep {
+    graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::Zip(zip) if zip.dest_path.as_str().ends_with(".tar.gz") => Some(zip),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn copies_of<'a>(graph: &'a DistGraph, src_path: &Utf8PathBuf) -> Vec<&'a CopyFileStep> {
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::CopyFile(copy) if &copy.src_path == src_path => Some(copy),
+            _ => None,
+        })
+        .collect()
+}
+
+fn deb_for<'a>(graph: &'a DistGraph, target: &str) -> &'a DebInstallerInfo {
+    graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Deb(info)) if a.target_triples[0] == target => {
+                Some(info)
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn rpm_for<'a>(graph: &'a DistGraph, target: &str) -> &'a RpmInstallerInfo {
+    graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Rpm(info)) if a.target_triples[0] == target => {
+                Some(info)
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn shell_info(graph: &DistGraph) -> &InstallerInfo {
+    graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn shell_script(graph: &DistGraph) -> String {
+    render_install_sh_script(&graph.templates, shell_info(graph)).unwrap()
+}
+
+#[test]
+fn man_pages_copied_into_archives_and_installers() {
+    let dir = package_dir("copied");
+    let graph = graph_with(&dir, &[HOST], json!({ "man-dir": "man" }));
+    let page = dir.join("man").join("axolotlsay.1");
+    let zip = archive_zip(&graph);
+
+    // The archive has it in man/man1, the deb and rpm where it gets installed
+    let mut dests = copies_of(&graph, &page)
+        .into_iter()
+        .map(|copy| copy.dest_path.clone())
+        .collect::<Vec<_>>();
+    dests.sort();
+    let deb = deb_for(&graph, HOST);
+    let rpm = rpm_for(&graph, HOST);
+    let mut expected = vec![
+        zip.src_path.join("man/man1/axolotlsay.1"),
+        deb.package_dir.join(DEB_MAN_DIR).join("man1/axolotlsay.1"),
+        rpm.package_dir.join("usr/share/man/man1/axolotlsay.1"),
+    ];
+    expected.sort();
+    assert_eq!(dests, expected);
+    // Files that aren't named for a section aren't man pages
+    assert!(copies_of(&graph, &dir.join("man").join("README.md")).is_empty());
+
+    // The rpm installs it and owns it
+    let spec = rpm.render_spec();
+    assert!(spec.contains(&format!(
+        "install -D -p -m 0644 {} %{{buildroot}}/usr/share/man/man1/axolotlsay.1\n",
+        rpm.package_dir.join("usr/share/man/man1/axolotlsay.1")
+    )));
+    assert!(spec.contains("%attr(0644, root, root) /usr/share/man/man1/axolotlsay.1\n"));
+    assert!(spec.contains(&format!("/{RPM_BIN_DIR}/{BIN_AXO_NAME}\n")));
+
+    // The shell installer copies it out of the archive and writes it in the receipt
+    let script = shell_script(&graph);
+    assert!(script.contains("            _man_pages=\"man/man1/axolotlsay.1\"\n"));
+    assert!(script.contains("    install \"$_dir\" \"$_bins\" \"$_man_pages\" \"$@\"\n"));
+    assert!(script.contains("            ensure cp \"$_src_dir/$_page\" \"$_dest\"\n"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn man_pages_generated_by_host_binaries() {
+    let dir = package_dir("generated");
+    let graph = graph_with(&dir, &[HOST, CROSS], json!({ "man-pages-args": ["--man"] }));
+    let mut steps = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::GenerateAsset(step) => Some((step.command.clone(), step.dest_path.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    steps.sort();
+
+    // Each artifact for the host runs the binary it contains
+    let zip = archive_zip(&graph);
+    let deb = deb_for(&graph, HOST).package_dir.clone();
+    let rpm = rpm_for(&graph, HOST).package_dir.clone();
+    let mut expected = vec![
+        (zip.src_path.clone(), zip.src_path.join("man")),
+        (deb.join(DEB_BIN_DIR), deb.join(DEB_MAN_DIR)),
+        (rpm.join(RPM_BIN_DIR), rpm.join("usr/share/man")),
+    ]
+    .into_iter()
+    .map(|(bin_dir, man_dir)| {
+        (
+            vec![bin_dir.join(BIN_AXO_NAME).to_string(), "--man".to_owned()],
+            man_dir.join("man1/axolotlsay.1"),
+        )
+    })
+    .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(steps, expected);
+
+    // The host's shell installer has them, the cross-compiled one doesn't
+    let script = shell_script(&graph);
+    assert!(script.contains("            _man_pages=\"man/man1/axolotlsay.1\"\n"));
+    assert_eq!(script.matches("            _man_pages=\"").count(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn shell_installer_without_man_pages() {
+    let dir = package_dir("none");
+    let graph = graph_with(&dir, &[HOST], json!({}));
+    assert!(!shell_script(&graph).contains("_man_pages"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn man_page_lands_in_archive() {
+    use std::io::Read;
+
+    let dir = package_dir("archived");
+    let graph = graph_with(&dir, &[HOST], json!({ "man-dir": "man" }));
+    let zip = archive_zip(&graph);
+    let page = dir.join("man").join("axolotlsay.1");
+    let copy = copies_of(&graph, &page)
+        .into_iter()
+        .find(|copy| copy.dest_path.starts_with(&zip.src_path))
+        .unwrap();
+
+    // Build the archive for real, somewhere else
+    let archive_dir = dir.join("axolotlsay-x86_64-unknown-linux-gnu");
+    let archive_path = dir.join("axolotlsay-x86_64-unknown-linux-gnu.tar.gz");
+    std::fs::create_dir_all(&archive_dir).unwrap();
+    let copy = BuildStep::CopyFile(CopyFileStep {
+        src_path: page,
+        dest_path: archive_dir.join(copy.dest_path.strip_prefix(&zip.src_path).unwrap()),
+    });
+    let archive = BuildStep::Zip(ZipDirStep {
+        src_path: archive_dir,
+        dest_path: archive_path.clone(),
+        with_root: zip.with_root.clone(),
+        zip_style: zip.zip_style,
+        executables: vec![],
+    });
+    run_build_step(&graph, &copy).unwrap();
+    run_build_step(&graph, &archive).unwrap();
+
+    let tarball = std::fs::File::open(&archive_path).unwrap();
+    let mut tarball = tar::Archive::new(flate2::read::GzDecoder::new(tarball));
+    let mut man_page = None;
+    for entry in tarball.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        if entry.path().unwrap().ends_with("man/man1/axolotlsay.1") {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            man_page = Some(contents);
+        }
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        man_page.expect("man page wasn't archived"),
+        ".TH AXOLOTLSAY 1\n"
+    );
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64", target_env = "gnu"))]
+#[test]
+fn installer_runs_with_and_without_man_pages() {
+    let dir = package_dir("installer");
+    let graph = graph_with(&dir, &[HOST, CROSS], json!({ "man-dir": "man" }));
+    // Install from archives sitting next to the script, so there's nothing to download
+    let mut info = shell_info(&graph).clone();
+    info.offline = true;
+    let host_archive = info
+        .artifacts
+        .iter()
+        .find(|a| a.target_triples == [HOST])
+        .unwrap()
+        .id
+        .clone();
+    let archive_name = host_archive.strip_suffix(".tar.gz").unwrap();
+    let files: &[(&str, &str)] = &[
+        (BIN_AXO_NAME, "#!/bin/sh\necho hello\n"),
+        ("man/man1/axolotlsay.1", ".TH AXOLOTLSAY 1\n"),
+    ];
+    let install = |info: &InstallerInfo, run_dir: Utf8PathBuf| {
+        let script = render_install_sh_script(&graph.templates, info).unwrap();
+        let bin_dir = run_dir.join("bin");
+        let output = run_offline_shell_installer(
+            &run_dir,
+            &script,
+            &[(archive_name, files)],
+            &["--no-modify-path", "--prefix", bin_dir.as_str()],
+        );
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(bin_dir.join(BIN_AXO_NAME).exists());
+        run_dir.join("home/.local/share/man/man1/axolotlsay.1")
+    };
+
+    // The page goes where man looks for it
+    let page = install(&info, dir.join("with-pages"));
+    assert_eq!(std::fs::read_to_string(page).unwrap(), ".TH AXOLOTLSAY 1\n");
+
+    // Platforms whose archive has no pages still install, even if others' do
+    for artifact in &mut info.artifacts {
+        if artifact.target_triples == [HOST] {
+            artifact.man_pages.clear();
+        }
+    }
+    assert!(info.artifacts.iter().any(|a| !a.man_pages.is_empty()));
+    let page = install(&info, dir.join("without-pages"));
+    assert!(!page.exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,83 @@
+//! Tests for --artifacts=metadata
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    build_manifest,
+    config::{ArtifactMode, Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind,
+};
+
+fn config_with(artifact_mode: ArtifactMode) -> Config {
+    Config {
+        artifact_mode,
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "x86_64-pc-windows-msvc".to_owned(),
+        ],
+        installers: vec![
+            InstallerStyle::Shell,
+            InstallerStyle::Powershell,
+            InstallerStyle::Npm,
+            InstallerStyle::Homebrew,
+        ],
+        ..mock_config()
+    }
+}
+
+fn workspace_axo() -> axoproject::WorkspaceInfo {
+    workspace_axo_dist(json!({
+        "installers": ["shell", "powershell", "npm", "homebrew"],
+        "targets": ["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"],
+        "citation": true,
+        "unified-checksum": true,
+    }))
+}
+
+#[test]
+fn metadata_mode_skips_archive_dependent_artifacts() {
+    let workspace = workspace_axo();
+    let cfg = config_with(ArtifactMode::Metadata);
+    // make_executable_zip_for_variant debug_asserts that it isn't run in this mode,
+    // so getting a graph at all means we never computed installer fragments
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    assert!(graph.artifacts.iter().all(|a| matches!(
+        a.kind,
+        ArtifactKind::Citation(_) | ArtifactKind::ReleaseNotes(_)
+    )));
+    assert!(graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Citation(_))));
+    assert!(graph
+        .build_steps
+        .iter()
+        .all(|step| !matches!(step, crate::BuildStep::Cargo(_) | crate::BuildStep::Zip(_))));
+
+    // But we can still describe the release in a manifest
+    let manifest = build_manifest(&cfg, &graph);
+    assert_eq!(manifest.releases.len(), 1);
+    assert_eq!(manifest.releases[0].app_name, BIN_AXO_NAME);
+    assert!(manifest
+        .artifacts
+        .values()
+        .all(|a| matches!(a.kind, cargo_dist_schema::ArtifactKind::Citation)));
+}
+
+#[test]
+fn global_mode_still_has_installers() {
+    let workspace = workspace_axo();
+    let cfg = config_with(ArtifactMode::Global);
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    assert!(graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(_))));
+    assert!(graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::UnifiedChecksum(_))));
+}
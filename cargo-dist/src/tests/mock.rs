@@ -1,7 +1,10 @@
 //! Mock testing utils, mostly you want the `workspace_*` functions,
 //! but other functions/consts will help you assert the results
 
-use crate::{CargoInfo, Tools};
+use crate::{
+    config::{ArtifactMode, Config},
+    gather_work_for_workspace, CargoInfo, DistGraph, Tools,
+};
 use axoproject::{AutoIncludes, PackageIdx, PackageInfo, WorkspaceInfo};
 use serde_json::json;
 
@@ -33,6 +36,7 @@ pub const BIN_FORCED_IDX: PackageIdx = PackageIdx(5);
 
 pub const BIN_TEST1_NAME: &str = "test-bin1";
 pub const BIN_TEST1_VER: &str = BIN_AXO_VER;
+pub const BIN_TEST1_IDX: PackageIdx = PackageIdx(6);
 
 pub const BIN_TEST2_NAME: &str = "test-bin2";
 pub const BIN_TEST2_VER: &str = BIN_AXO_VER;
@@ -45,6 +49,33 @@ pub fn mock_tools() -> Tools {
             host_target: "x86_64-unknown-linux-gnu".to_owned(),
         },
         rustup: None,
+        appimagetool: None,
+        flatpak_builder: None,
+        snapcraft: None,
+        oras: None,
+        zstd: None,
+        bsdiff: None,
+        cross: None,
+        git_cliff: None,
+    }
+}
+
+/// The config most tests plan with: every artifact of axolotlsay's release, for x86_64 linux
+///
+/// Tests override just what they care about with `Config { ..., ..mock_config() }`.
+pub fn mock_config() -> Config {
+    Config {
+        needs_coherent_announcement_tag: true,
+        artifact_mode: ArtifactMode::All,
+        no_local_paths: true,
+        allow_all_dirty: true,
+        verbose_plan: false,
+        targets: vec!["x86_64-unknown-linux-gnu".to_owned()],
+        ci: vec![],
+        installers: vec![],
+        announcement_tag: Some(format!("v{BIN_AXO_VER}")),
+        host_target: None,
+        exclude: vec![],
     }
 }
 
@@ -178,6 +209,9 @@ pub fn pkg_test_bin1() -> PackageInfo {
         ..mock_package(BIN_TEST1_NAME, BIN_TEST1_VER)
     }
 }
+pub fn entry_test_bin1() -> (PackageIdx, Vec<String>) {
+    (BIN_TEST1_IDX, vec![BIN_TEST1_NAME.to_owned()])
+}
 /// test-bin2 1.0.0
 ///
 /// has dist=false set
@@ -192,6 +226,30 @@ pub fn pkg_test_bin2() -> PackageInfo {
         ..mock_package(BIN_TEST2_NAME, BIN_TEST2_VER)
     }
 }
+/// axolotlsay 1.0.0, with the given `[package.metadata.dist]`
+///
+/// It also gets a package id, which the graph needs to plan builds of its binaries.
+pub fn pkg_axo_dist(dist: serde_json::Value) -> PackageInfo {
+    PackageInfo {
+        cargo_metadata_table: Some(json!({ "dist": dist })),
+        cargo_package_id: Some(guppy::PackageId::new(format!(
+            "{BIN_AXO_NAME} {BIN_AXO_VER} (path+file:///{BIN_AXO_NAME})"
+        ))),
+        ..pkg_axo_bin()
+    }
+}
+
+/// helper-bin 1.0.0, with the given `[package.metadata.dist]` (and a package id)
+pub fn pkg_helper_dist(dist: serde_json::Value) -> PackageInfo {
+    PackageInfo {
+        cargo_metadata_table: Some(json!({ "dist": dist })),
+        cargo_package_id: Some(guppy::PackageId::new(format!(
+            "{BIN_HELPER_NAME} {BIN_HELPER_VER} (path+file:///{BIN_HELPER_NAME})"
+        ))),
+        ..pkg_helper_bin()
+    }
+}
+
 /// axolotlsay
 pub fn workspace_just_axo() -> WorkspaceInfo {
     mock_workspace(vec![pkg_axo_bin()])
@@ -202,6 +260,11 @@ pub fn workspace_just_axo_alpha() -> WorkspaceInfo {
     mock_workspace(vec![pkg_axo_bin_alpha()])
 }
 
+/// axolotlsay, with the given `[package.metadata.dist]`
+pub fn workspace_axo_dist(dist: serde_json::Value) -> WorkspaceInfo {
+    mock_workspace(vec![pkg_axo_dist(dist)])
+}
+
 /// axolotlsay, some-lib, helper-bin -- all same version
 pub fn workspace_unified() -> WorkspaceInfo {
     mock_workspace(vec![pkg_axo_bin(), pkg_some_lib(), pkg_helper_bin()])
@@ -226,3 +289,50 @@ pub fn workspace_disjoint() -> WorkspaceInfo {
         pkg_test_bin2(),
     ])
 }
+
+/// Plan axolotlsay's release with the given `[package.metadata.dist]`
+///
+/// This is the graph most tests look at; the ones that need more than one package,
+/// or workspace-level config, build their own from [`workspace_axo_dist`].
+pub fn graph_for_dist_metadata(dist: serde_json::Value, cfg: &Config) -> DistGraph {
+    gather_work_for_workspace(cfg, mock_tools(), &workspace_axo_dist(dist)).unwrap()
+}
+
+/// Run a rendered offline shell installer (`sh install.sh <args>`) in `dir`, next to archives
+///
+/// Each archive is a `.tar.gz` named `<name>.tar.gz`, holding a `<name>/` dir with the
+/// given (path, contents) files, like the ones we build.
+#[cfg(unix)]
+pub fn run_offline_shell_installer(
+    dir: &camino::Utf8Path,
+    script: &str,
+    archives: &[(&str, &[(&str, &str)])],
+    args: &[&str],
+) -> std::process::Output {
+    std::fs::create_dir_all(dir).unwrap();
+    for (name, files) in archives {
+        let tarball = std::fs::File::create(dir.join(format!("{name}.tar.gz"))).unwrap();
+        let encoder = flate2::write::GzEncoder::new(tarball, flate2::Compression::default());
+        let mut tarball = tar::Builder::new(encoder);
+        for (path, contents) in *files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            tarball
+                .append_data(&mut header, format!("{name}/{path}"), contents.as_bytes())
+                .unwrap();
+        }
+        tarball.into_inner().unwrap().finish().unwrap();
+    }
+    let script_path = dir.join("install.sh");
+    std::fs::write(&script_path, script).unwrap();
+    std::process::Command::new("sh")
+        .arg(&script_path)
+        .args(args)
+        .env("HOME", dir.join("home"))
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("INSTALLER_INSTALL_PATH")
+        .output()
+        .unwrap()
+}
@@ -1,2 +1,72 @@
+mod announcement;
+mod app;
+mod appimage;
+mod bin_aliases;
+mod bin_filter;
+mod binaries_from;
+mod binstall;
+mod build_plan;
+mod build_profile;
+mod changelog;
+mod checksum;
+mod ci_cache;
+mod commits;
+mod completions;
+mod config_schema;
+mod cross;
+mod custom_target;
+mod deb;
+mod delta;
+mod demo;
+mod dirty;
+mod dist_subdir;
+mod dmg;
+mod docker;
+mod download_url;
+mod exclude;
+mod extra_targets;
+mod feature_variants;
+mod flatpak;
+mod freebsd;
+mod generate;
+mod generated_assets;
+mod gitea;
+mod gitlab;
+mod graph;
+mod homebrew;
+mod host;
+mod incremental;
+mod installer_checksums;
+mod license;
+mod list;
+mod lookup;
+mod macpkg;
+mod man_pages;
+mod metadata;
 mod mock;
+mod musl;
+mod npm;
+mod nsis;
+mod oci;
+mod offline;
+mod pr_publish;
+mod provenance;
+mod publish;
+mod required;
+mod rpm;
+mod runners;
+mod rustup;
+mod scoop;
+mod sentry;
+mod shell;
+mod signature;
+mod slim;
+mod snap;
+mod staging;
+mod suite;
+mod symbols;
+mod system_deps;
 mod tag;
+mod target_features;
+mod unknown_keys;
+mod winget;
@@ -0,0 +1,71 @@
+//! Tests for building static musl binaries
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, BuildStep, DistGraph};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+const LINUX_MUSL: &str = "x86_64-unknown-linux-musl";
+const TARGETS: &[&str] = &[LINUX, LINUX_MUSL];
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+fn rustflags_for(graph: &DistGraph, target: &str) -> String {
+    graph
+        .build_steps
+        .iter()
+        .find_map(|step| match step {
+            BuildStep::Cargo(build) if build.target_triple == target => {
+                Some(build.rustflags.clone())
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no build for {target}"))
+}
+
+#[test]
+fn musl_links_crt_statically() {
+    let graph = graph_with(json!({ "ci": ["github"] }));
+    assert!(rustflags_for(&graph, LINUX_MUSL).contains("-Ctarget-feature=+crt-static"));
+    assert!(!rustflags_for(&graph, LINUX).contains("crt-static"));
+}
+
+#[test]
+fn github_installs_musl_tools() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "dependencies": { "apt": { "libssl-dev": "*" } },
+    }));
+    let ci = graph.ci.github.as_ref().unwrap();
+    let install_for = |target: &str| {
+        ci.artifacts_matrix
+            .include
+            .iter()
+            .find(|task| {
+                task.dist_args
+                    .as_deref()
+                    .is_some_and(|args| args.ends_with(&format!("--target={target}")))
+            })
+            .and_then(|task| task.packages_install.clone())
+    };
+    assert_eq!(
+        install_for(LINUX_MUSL).as_deref(),
+        Some("sudo apt-get install musl-tools libssl-dev")
+    );
+    assert_eq!(
+        install_for(LINUX).as_deref(),
+        Some("sudo apt-get install libssl-dev")
+    );
+}
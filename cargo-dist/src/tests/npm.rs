@@ -0,0 +1,144 @@
+//! Tests for npm installers
+
+use super::mock::*;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::json;
+
+use crate::{
+    backend::installer::{npm::render_npm_project, InstallerImpl},
+    config::{CompressionImpl, Config, InstallerStyle, ZipStyle},
+    build_manifest, gather_work_for_workspace, ArtifactKind,
+};
+
+#[test]
+fn npm_package_runs_every_binary() {
+    let mut workspace = mock_workspace(vec![pkg_helper_dist(json!({
+        "installers": ["npm"],
+        "targets": ["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"],
+    }))]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "x86_64-pc-windows-msvc".to_owned(),
+        ],
+        installers: vec![InstallerStyle::Npm],
+        announcement_tag: Some(format!("v{BIN_HELPER_VER}")),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    let info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Npm(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(info.bins, [BIN_HELPER_NAME, BIN_HELPER_NAME2]);
+
+    let files = render_npm_project(&graph.templates, info).unwrap();
+    let package_json: serde_json::Value =
+        serde_json::from_str(&files[&Utf8PathBuf::from("package.json")]).unwrap();
+    assert_eq!(
+        package_json["bin"],
+        json!({
+            BIN_HELPER_NAME: format!("run-{BIN_HELPER_NAME}.js"),
+            BIN_HELPER_NAME2: format!("run-{BIN_HELPER_NAME2}.js"),
+        })
+    );
+    // Each script runs its own binary
+    for bin in [BIN_HELPER_NAME, BIN_HELPER_NAME2] {
+        let script = &files[&Utf8PathBuf::from(format!("run-{bin}.js"))];
+        assert!(
+            script.contains(&format!("maybeInstall(true).then(() => run(\"{bin}\"));\n")),
+            "{script}"
+        );
+    }
+    assert!(!files.contains_key(&Utf8PathBuf::from("run.js")));
+
+    // The manifest lists the scripts too
+    let manifest = build_manifest(&cfg, &graph);
+    let package = &manifest.artifacts[&format!("{BIN_HELPER_NAME}-npm-package.tar.gz")];
+    let assets = package
+        .assets
+        .iter()
+        .filter_map(|asset| asset.name.as_deref())
+        .collect::<Vec<_>>();
+    for bin in [BIN_HELPER_NAME, BIN_HELPER_NAME2] {
+        assert!(assets.contains(&&*format!("run-{bin}.js")), "{assets:?}");
+    }
+    assert!(!assets.contains(&"run.js"));
+}
+
+#[test]
+fn npm_package_uses_its_own_gzip_archives() {
+    let cfg = Config {
+        targets: vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "x86_64-pc-windows-msvc".to_owned(),
+        ],
+        installers: vec![InstallerStyle::Npm],
+        ..mock_config()
+    };
+    let graph = graph_for_dist_metadata(
+        json!({
+            "installers": ["npm"],
+            "targets": ["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"],
+            "unix-archive": ".tar.xz",
+            "windows-archive": ".tar.gz",
+        }),
+        &cfg,
+    );
+
+    let info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Npm(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap();
+    let linux = format!("{BIN_AXO_NAME}-x86_64-unknown-linux-gnu");
+    let windows = format!("{BIN_AXO_NAME}-x86_64-pc-windows-msvc");
+    let npm_archives = info
+        .inner
+        .artifacts
+        .iter()
+        .map(|fragment| (fragment.id.clone(), fragment.zip_style))
+        .collect::<Vec<_>>();
+    let gzip = ZipStyle::Tar(CompressionImpl::Gzip);
+    assert_eq!(
+        npm_archives,
+        vec![
+            (format!("{linux}.tar.gz"), gzip),
+            (format!("{windows}.tar.gz"), gzip)
+        ]
+    );
+
+    // The user-facing archive is still an xz, with a gzip alongside it just for npm
+    let archives = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .map(|a| a.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        archives,
+        vec![
+            format!("{linux}.tar.xz"),
+            format!("{windows}.tar.gz"),
+            format!("{linux}.tar.gz")
+        ]
+    );
+    let npm_linux = graph
+        .artifacts
+        .iter()
+        .find(|a| a.id == format!("{linux}.tar.gz"))
+        .unwrap();
+    let archive = npm_linux.archive.as_ref().unwrap();
+    assert_eq!(archive.with_root.as_deref(), Some(Utf8Path::new(&linux)));
+    assert!(archive.dir_path.as_str().ends_with("-npm"));
+    assert!(npm_linux.checksum.is_some());
+}
@@ -0,0 +1,83 @@
+//! Tests for NSIS installers
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{nsis::NsisInstallerInfo, InstallerImpl},
+    config::{Config, InstallerStyle},
+    Artifact, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &[
+    "x86_64-pc-windows-msvc",
+    "i686-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+fn graph_with_nsis(dist: serde_json::Value) -> DistGraph {
+    let mut dist = dist;
+    dist["installers"] = json!(["nsis"]);
+    dist["targets"] = json!(TARGETS);
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Nsis],
+        ..mock_config()
+    };
+    graph_for_dist_metadata(dist, &cfg)
+}
+
+fn nsis_installers(graph: &DistGraph) -> Vec<(&Artifact, &NsisInstallerInfo)> {
+    let mut installers = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Nsis(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    installers.sort_by_key(|(a, _)| a.id.clone());
+    installers
+}
+
+#[test]
+fn nsis_for_windows_only() {
+    let graph = graph_with_nsis(json!({}));
+    let installers = nsis_installers(&graph);
+    assert_eq!(installers.len(), 2);
+    let (artifact, info) = installers[1];
+    assert_eq!(artifact.target_triples, ["x86_64-pc-windows-msvc"]);
+    assert_eq!(
+        artifact.id,
+        format!("{BIN_AXO_NAME}-x86_64-pc-windows-msvc-setup.exe")
+    );
+    assert!(artifact.checksum.is_some());
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(
+        dest_path,
+        &info.package_dir.join(format!("{BIN_AXO_NAME}.exe"))
+    );
+    assert_eq!(info.binaries, [format!("{BIN_AXO_NAME}.exe")]);
+
+    // By default it's a per-user install that updates PATH
+    assert_eq!(info.install_root, "$LOCALAPPDATA\\Programs");
+    assert!(!info.program_files);
+    assert!(info.modify_path);
+}
+
+#[test]
+fn nsis_program_files() {
+    let graph = graph_with_nsis(json!({
+        "nsis-program-files": true,
+        "nsis-modify-path": false,
+    }));
+    let installers = nsis_installers(&graph);
+    let roots = installers
+        .iter()
+        .map(|(_, info)| info.install_root.as_str())
+        .collect::<Vec<_>>();
+    // 32-bit windows has its own Program Files
+    assert_eq!(roots, ["$PROGRAMFILES", "$PROGRAMFILES64"]);
+    assert!(installers.iter().all(|(_, info)| info.program_files));
+    assert!(installers.iter().all(|(_, info)| !info.modify_path));
+}
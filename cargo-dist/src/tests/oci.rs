@@ -0,0 +1,103 @@
+//! Tests for pushing release artifacts to a registry as OCI artifacts
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::ci::github::GithubCiInfo,
+    config::Config,
+    errors::{DistError, Result},
+    gather_work_for_workspace, DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"];
+const REFERENCE: &str = "ghcr.io/axodotdev/axolotlsay-artifacts";
+
+fn graph_with_oci(workspace_dist: serde_json::Value) -> Result<DistGraph> {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace)
+}
+
+#[test]
+fn oci_pushes_release_artifacts() {
+    let graph = graph_with_oci(json!({
+        "publish-jobs": ["oci"],
+        "oci-registry": REFERENCE,
+    }))
+    .unwrap();
+    let ci = GithubCiInfo::new(&graph);
+    let oci = ci.oci.as_ref().expect("no oci job");
+    assert_eq!(oci.registry, "ghcr.io");
+    assert_eq!(oci.reference, REFERENCE);
+
+    // Every artifact gets pushed, except the checksums, which become annotations
+    let names = oci
+        .files
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect::<Vec<_>>();
+    let linux = format!("{BIN_AXO_NAME}-x86_64-unknown-linux-gnu.tar.xz");
+    let windows = format!("{BIN_AXO_NAME}-x86_64-pc-windows-msvc.zip");
+    let shell = format!("{BIN_AXO_NAME}-installer.sh");
+    for expected in [&linux, &windows, &shell] {
+        assert!(names.contains(&expected.as_str()), "{names:?}");
+    }
+    assert!(!names.iter().any(|name| name.ends_with(".sha256")));
+    let expected_len = graph
+        .artifacts
+        .iter()
+        .filter(|a| !a.id.ends_with(".sha256"))
+        .count();
+    assert_eq!(names.len(), expected_len);
+
+    let linux = oci.files.iter().find(|f| f.name == linux).unwrap();
+    assert_eq!(linux.media_type, "application/x-xz");
+    assert_eq!(
+        linux.checksum_annotation.as_deref(),
+        Some("dev.cargo-dist.checksum.sha256")
+    );
+    assert_eq!(
+        linux.checksum_file.as_deref(),
+        Some(&*format!("{}.sha256", linux.name))
+    );
+    let windows = oci.files.iter().find(|f| f.name == windows).unwrap();
+    assert_eq!(windows.media_type, "application/zip");
+
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("publish-oci-artifacts:"));
+    assert!(workflow.contains(&format!(
+        "--annotation-file ../annotations.json \"{REFERENCE}:$version\" \\\n"
+    )));
+    assert!(workflow.contains(&format!(
+        "            \"{}\":application/x-xz\n",
+        linux.name
+    )));
+    assert!(workflow.contains(&format!(
+        "          annotate \"{}\" \"dev.cargo-dist.checksum.sha256\" \"{}.sha256\"\n",
+        linux.name, linux.name
+    )));
+}
+
+#[test]
+fn no_oci_by_default() {
+    let graph = graph_with_oci(json!({ "oci-registry": REFERENCE })).unwrap();
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.oci.is_none());
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(!workflow.contains("publish-oci-artifacts:"));
+}
+
+#[test]
+fn oci_needs_registry() {
+    let err = graph_with_oci(json!({ "publish-jobs": ["oci"] })).unwrap_err();
+    let err = err.downcast_ref::<DistError>().unwrap();
+    assert!(matches!(err, DistError::OciRegistryMissing), "{err:?}");
+}
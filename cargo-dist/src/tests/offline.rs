@@ -0,0 +1,122 @@
+//! Tests for offline bundles
+
+use super::mock::*;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    backend::{installer::InstallerImpl, templates::TEMPLATE_INSTALLER_SH},
+    config::Config,
+    gather_work_for_workspace, ArtifactKind, BuildStep, CopyFileStep, ZipDirStep,
+};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+#[test]
+fn offline_bundle_includes_every_variant() {
+    let mut workspace = workspace_axo_dist(json!({
+        "offline-bundle": true,
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    let (bundle, info) = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::OfflineBundle(info)) => Some((a, info)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(bundle.id, format!("{BIN_AXO_NAME}-offline.tar.gz"));
+    assert!(bundle.is_global);
+    let archive = bundle.archive.as_ref().unwrap();
+
+    // Every variant's archive (windows included) gets copied into the bundle
+    let zips = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(zips.len(), TARGETS.len());
+    for zip in &zips {
+        let dest = archive.dir_path.join(zip.file_path.file_name().unwrap());
+        assert!(
+            graph.build_steps.iter().any(|step| matches!(
+                step,
+                BuildStep::CopyFile(CopyFileStep { src_path, dest_path })
+                    if *src_path == zip.file_path && *dest_path == dest
+            )),
+            "{} isn't bundled",
+            zip.id
+        );
+    }
+    // ...which then gets tarred up with the install script
+    assert_eq!(info.dest_path, archive.dir_path.join("install.sh"));
+    assert!(graph.build_steps.iter().any(|step| matches!(
+        step,
+        BuildStep::Zip(ZipDirStep { src_path, dest_path, .. })
+            if *src_path == archive.dir_path && *dest_path == bundle.file_path
+    )));
+
+    // The script installs from the bundled archives instead of downloading them
+    assert!(info.offline);
+    let script = graph
+        .templates
+        .render_file_to_clean_string(TEMPLATE_INSTALLER_SH, info)
+        .unwrap();
+    assert!(script.contains(&format!(
+        "_artifact_name=\"{BIN_AXO_NAME}-x86_64-unknown-linux-gnu.tar.xz\""
+    )));
+    assert!(script.contains("local _file=\"$BUNDLE_DIR/$_artifact_name\"\n"));
+    assert!(!script.contains("downloader --check"));
+    assert!(!script.contains("ARTIFACT_DOWNLOAD_URL"));
+}
+
+#[test]
+fn offline_bundle_is_signed() {
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    let dist = json!({
+        "offline-bundle": true,
+        "targets": TARGETS,
+        "gpg-key": "ABCD1234",
+    });
+    let graph = graph_for_dist_metadata(dist, &cfg);
+
+    let bundle = graph
+        .artifacts
+        .iter()
+        .find(|a| {
+            matches!(
+                a.kind,
+                ArtifactKind::Installer(InstallerImpl::OfflineBundle(_))
+            )
+        })
+        .unwrap();
+    let signature = graph.artifact(bundle.signature.expect("bundle wasn't signed"));
+    assert_eq!(signature.id, format!("{}.asc", bundle.id));
+
+    // The signature is of the finished tarball
+    let step_writing = |path: &Utf8PathBuf| {
+        graph.build_steps.iter().position(|step| match step {
+            BuildStep::Zip(ZipDirStep { dest_path, .. }) => dest_path == path,
+            BuildStep::Sign(sig) => sig.dest_path == *path,
+            _ => false,
+        })
+    };
+    let zip_step = step_writing(&bundle.file_path).unwrap();
+    let sign_step = step_writing(&signature.file_path).unwrap();
+    assert!(graph.build_plan.depends_on(sign_step, zip_step));
+}
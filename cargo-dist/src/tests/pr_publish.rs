@@ -0,0 +1,67 @@
+//! Tests for publish jobs that open pull requests
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::ci::github::GithubCiInfo, config::Config, gather_work_for_workspace, DistGraph,
+};
+
+fn graph_with_publish_jobs(installers: &[&str], publish_jobs: &[&str]) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": installers,
+        "targets": ["aarch64-apple-darwin", "x86_64-apple-darwin"],
+    }));
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "ci": ["github"],
+            "tap": "axodotdev/homebrew-tap",
+            "publish-jobs": publish_jobs,
+        }
+    }));
+    let cfg = Config {
+        targets: vec![
+            "aarch64-apple-darwin".to_owned(),
+            "x86_64-apple-darwin".to_owned(),
+        ],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn homebrew_pr_job() {
+    let graph = graph_with_publish_jobs(&["homebrew"], &["homebrew-pr"]);
+    let ci = GithubCiInfo::new(&graph);
+    assert_eq!(ci.pr_publish_jobs.len(), 1);
+    let job = &ci.pr_publish_jobs[0];
+    assert_eq!(job.repo, "axodotdev/homebrew-tap");
+    assert_eq!(job.fork_name, "homebrew-tap");
+    assert_eq!(job.files.len(), 1);
+    assert_eq!(job.files[0].artifact, format!("{BIN_AXO_NAME}.rb"));
+    assert_eq!(job.files[0].dest_path, format!("Formula/{BIN_AXO_NAME}.rb"));
+
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("  publish-homebrew-pr:\n"), "{workflow}");
+    assert!(workflow.contains(r#"gh repo fork "axodotdev/homebrew-tap" --clone=false"#));
+    assert!(workflow.contains(&format!(
+        r#"cp "../artifacts/""{BIN_AXO_NAME}.rb" "Formula/{BIN_AXO_NAME}.rb""#
+    )));
+    assert!(workflow.contains(r#"gh pr create --repo "axodotdev/homebrew-tap""#));
+    // The tap gets a PR instead of a direct push
+    assert!(!workflow.contains("publish-homebrew-formula:"));
+}
+
+#[test]
+fn no_homebrew_pr_without_installer() {
+    let graph = graph_with_publish_jobs(&["shell"], &["homebrew-pr"]);
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.pr_publish_jobs.is_empty());
+}
+
+#[test]
+fn no_homebrew_pr_unless_requested() {
+    let graph = graph_with_publish_jobs(&["homebrew"], &["homebrew"]);
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.pr_publish_jobs.is_empty());
+}
@@ -0,0 +1,70 @@
+//! Tests for explaining why artifacts are in the graph
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::{ArtifactMode, Config, InstallerStyle},
+    ArtifactKind, DistGraph, InstallerImpl,
+};
+
+fn config_with(artifact_mode: ArtifactMode) -> Config {
+    Config {
+        artifact_mode,
+        verbose_plan: true,
+        installers: vec![InstallerStyle::Shell],
+        ..mock_config()
+    }
+}
+
+fn graph_with(artifact_mode: ArtifactMode) -> DistGraph {
+    let dist = json!({
+        "installers": ["shell"],
+        "targets": ["x86_64-unknown-linux-gnu"],
+    });
+    graph_for_dist_metadata(dist, &config_with(artifact_mode))
+}
+
+#[test]
+fn shell_installer_provenance() {
+    let graph = graph_with(ArtifactMode::Global);
+
+    let installer = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::Shell(_))))
+        .expect("no shell installer!?");
+    assert_eq!(
+        installer.provenance.as_deref(),
+        Some(
+            "shell installer requested by release axolotlsay's installers setting \
+             (kept because --artifacts=global includes globally unique artifacts)"
+        )
+    );
+
+    // Every artifact that made it into the graph should know why it's there
+    assert!(graph.artifacts.iter().all(|a| a.provenance.is_some()));
+}
+
+#[test]
+fn archive_provenance() {
+    let graph = graph_with(ArtifactMode::Local);
+
+    let archive = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .expect("no archive!?");
+    assert_eq!(
+        archive.provenance.as_deref(),
+        Some(
+            "archive of release axolotlsay's binaries for x86_64-unknown-linux-gnu \
+             (kept because --artifacts=local includes target-specific artifacts)"
+        )
+    );
+    // And there's no installer because local artifacts were requested
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(_))));
+}
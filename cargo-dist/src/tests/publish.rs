@@ -0,0 +1,98 @@
+//! Tests for extra-publish-jobs
+
+use super::mock::*;
+use axoproject::WorkspaceInfo;
+use serde_json::json;
+
+use crate::{
+    backend::ci::github::GithubCiInfo, config::ArtifactMode, errors::DistError,
+    gather_work_for_workspace, DistGraph, DistGraphBuilder,
+};
+
+fn workspace_with_dist_config(dist: serde_json::Value) -> WorkspaceInfo {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": ["x86_64-unknown-linux-gnu"],
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": dist }));
+    workspace
+}
+
+fn graph_for(workspace: &WorkspaceInfo) -> DistGraph {
+    let cfg = mock_config();
+    gather_work_for_workspace(&cfg, mock_tools(), workspace).unwrap()
+}
+
+#[test]
+fn inline_publish_job_in_workflow() {
+    let workspace = workspace_with_dist_config(json!({
+        "extra-publish-jobs": [{
+            "name": "crates-io",
+            "command": "cargo publish --token $CARGO_REGISTRY_TOKEN",
+            "needs": ["publish-homebrew-formula"],
+            "env": { "CARGO_REGISTRY_TOKEN": "${{ secrets.CARGO_REGISTRY_TOKEN }}" },
+        }],
+    }));
+    let graph = graph_for(&workspace);
+    let ci = GithubCiInfo::new(&graph);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+
+    assert!(workflow.contains("  custom-crates-io:\n"), "{workflow}");
+    assert!(workflow.contains("    needs: [plan, should-publish, publish-homebrew-formula]\n"));
+    assert!(
+        workflow.contains(r#"      CARGO_REGISTRY_TOKEN: "${{ secrets.CARGO_REGISTRY_TOKEN }}""#)
+    );
+    assert!(workflow.contains(r#"        run: "cargo publish --token $CARGO_REGISTRY_TOKEN""#));
+}
+
+#[test]
+fn publish_jobs_from_file() {
+    let dir = camino::Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-publish-jobs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let jobs_file = dir.join("publish-jobs.toml");
+    std::fs::write(
+        &jobs_file,
+        r#"
+[[publish-job]]
+name = "announce"
+command = "./announce.sh"
+"#,
+    )
+    .unwrap();
+
+    let workspace = workspace_with_dist_config(json!({
+        "extra-publish-jobs": [{ "name": "crates-io", "command": "cargo publish" }],
+        "extra-publish-jobs-file": jobs_file,
+    }));
+    let graph = graph_for(&workspace);
+    let names = graph
+        .extra_publish_jobs
+        .iter()
+        .map(|job| job.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["crates-io", "announce"]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn publish_job_names_are_unique() {
+    for dist in [
+        json!({ "extra-publish-jobs": [{ "name": "homebrew", "command": "true" }] }),
+        json!({
+            "publish-jobs": ["./crates-io"],
+            "extra-publish-jobs": [{ "name": "crates-io", "command": "true" }],
+        }),
+        json!({
+            "extra-publish-jobs": [
+                { "name": "crates-io", "command": "true" },
+                { "name": "crates-io", "command": "false" },
+            ],
+        }),
+    ] {
+        let workspace = workspace_with_dist_config(dist);
+        let res = DistGraphBuilder::new(mock_tools(), &workspace, ArtifactMode::All, true);
+        assert!(matches!(res, Err(DistError::DuplicatePublishJob { .. })));
+    }
+}
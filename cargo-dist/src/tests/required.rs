@@ -0,0 +1,52 @@
+//! Tests for required-binaries
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    errors::{DistError, Result},
+    gather_work_for_workspace, DistGraph,
+};
+
+fn graph_requiring(binaries: Vec<String>, required: &[&str]) -> Result<DistGraph> {
+    let workspace = mock_workspace(vec![PackageInfo {
+        binaries,
+        ..pkg_axo_dist(json!({
+            "targets": ["x86_64-unknown-linux-gnu"],
+            "required-binaries": required,
+        }))
+    }]);
+    let cfg = mock_config();
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace)
+}
+
+#[test]
+fn required_binary_present() {
+    let graph = graph_requiring(vec![BIN_AXO_NAME.to_owned()], &[BIN_AXO_NAME]).unwrap();
+    assert_eq!(graph.binaries.len(), 1);
+}
+
+#[test]
+fn required_binary_missing() {
+    let err = graph_requiring(
+        vec![BIN_AXO_NAME.to_owned()],
+        &[BIN_AXO_NAME, "axolotlsing"],
+    )
+    .unwrap_err();
+    let err = err.downcast_ref::<DistError>().unwrap();
+    assert!(
+        matches!(err, DistError::MissingRequiredBinary { binary, .. } if binary == "axolotlsing"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn required_binary_missing_from_package_with_no_binaries() {
+    let err = graph_requiring(vec![], &[BIN_AXO_NAME]).unwrap_err();
+    let err = err.downcast_ref::<DistError>().unwrap();
+    assert!(
+        matches!(err, DistError::MissingRequiredBinary { binary, .. } if binary == BIN_AXO_NAME),
+        "{err:?}"
+    );
+}
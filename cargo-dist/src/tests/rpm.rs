@@ -0,0 +1,107 @@
+//! Tests for rpm installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{rpm::RPM_BIN_DIR, InstallerImpl},
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-musl",
+    "x86_64-apple-darwin",
+];
+
+fn graph_with_rpms(checksum: &str) -> DistGraph {
+    let workspace = mock_workspace(vec![PackageInfo {
+        license: Some("MIT".to_owned()),
+        ..pkg_axo_dist(json!({
+            "installers": ["rpm"],
+            "targets": TARGETS,
+            "checksum": checksum,
+            "dependencies": {
+                "dnf": {
+                    "openssl-libs": { "version": "3.0", "stage": ["run"] },
+                    "bash-completion": { "stage": ["recommend"] },
+                    "cmake": "*",
+                },
+            },
+        }))
+    }]);
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Rpm],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn rpms_for_linux_only() {
+    let graph = graph_with_rpms("sha256");
+    let mut rpm_targets = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::Rpm(_))))
+        .map(|a| {
+            assert!(a.id.ends_with(".rpm"));
+            assert!(a.checksum.is_some(), "{} had no checksum", a.id);
+            a.target_triples[0].as_str()
+        })
+        .collect::<Vec<_>>();
+    rpm_targets.sort();
+    assert_eq!(
+        rpm_targets,
+        ["aarch64-unknown-linux-musl", "x86_64-unknown-linux-gnu"]
+    );
+}
+
+#[test]
+fn rpm_contents_and_spec() {
+    let graph = graph_with_rpms("sha256");
+    let (artifact, info) = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Rpm(info))
+                if a.target_triples[0] == "x86_64-unknown-linux-gnu" =>
+            {
+                Some((a, info))
+            }
+            _ => None,
+        })
+        .expect("no rpm!?");
+
+    // The binary goes to /usr/bin
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(
+        dest_path,
+        &info.package_dir.join(RPM_BIN_DIR).join(BIN_AXO_NAME)
+    );
+
+    let spec = &info.spec;
+    assert_eq!(spec.name, BIN_AXO_NAME);
+    assert_eq!(spec.version, BIN_AXO_VER);
+    assert_eq!(spec.arch, "x86_64");
+    assert_eq!(spec.license.as_deref(), Some("MIT"));
+    // Only runtime dnf dependencies end up in Requires
+    assert_eq!(spec.requires, ["openssl-libs >= 3.0"]);
+    assert_eq!(spec.recommends, ["bash-completion"]);
+    assert!(info
+        .render_spec()
+        .contains("\nRecommends: bash-completion\n"));
+}
+
+#[test]
+fn rpms_without_checksums() {
+    let graph = graph_with_rpms("false");
+    assert!(graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::Rpm(_))))
+        .all(|a| a.checksum.is_none()));
+}
@@ -0,0 +1,127 @@
+//! Tests for picking custom Github runners for targets
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, DistGraph};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+/// Get the (runner, dist_args, install_dist) of each local task
+fn local_tasks(graph: &DistGraph) -> Vec<(String, String, String)> {
+    let ci = graph.ci.github.as_ref().expect("no github ci");
+    ci.artifacts_matrix
+        .include
+        .iter()
+        .map(|task| {
+            (
+                task.runner.clone().unwrap(),
+                task.dist_args.clone().unwrap(),
+                task.install_dist.clone().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn default_runners() {
+    let graph = graph_with(json!({ "ci": ["github"] }));
+    let runners = local_tasks(&graph)
+        .into_iter()
+        .map(|(runner, _, _)| runner)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        runners,
+        ["macos-11", "macos-11", "windows-2019", "ubuntu-20.04"]
+    );
+}
+
+#[test]
+fn custom_runners_override_defaults() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "github-custom-runners": {
+            "aarch64-apple-darwin": "macos-14",
+            "x86_64-pc-windows-msvc": "windows-big-runner",
+        },
+    }));
+    let ci = graph.ci.github.as_ref().unwrap();
+    let tasks = local_tasks(&graph);
+    assert_eq!(
+        tasks[0],
+        (
+            "macos-14".to_owned(),
+            "--artifacts=local --target=aarch64-apple-darwin".to_owned(),
+            ci.install_dist_sh.clone()
+        )
+    );
+    // Targets that aren't listed keep their defaults
+    assert_eq!(tasks[1].0, "macos-11");
+    assert_eq!(tasks[3].0, "ubuntu-20.04");
+    // Custom runners for windows targets still install cargo-dist with powershell
+    assert_eq!(
+        tasks[2],
+        (
+            "windows-big-runner".to_owned(),
+            "--artifacts=local --target=x86_64-pc-windows-msvc".to_owned(),
+            ci.install_dist_ps1.clone()
+        )
+    );
+}
+
+#[test]
+fn custom_runners_split_merged_tasks() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "merge-tasks": true,
+        "github-custom-runners": {
+            "aarch64-apple-darwin": "macos-14",
+            "x86_64-unknown-linux-gnu": "self-hosted",
+        },
+    }));
+    let mut tasks = local_tasks(&graph)
+        .into_iter()
+        .map(|(runner, dist_args, _)| (runner, dist_args))
+        .collect::<Vec<_>>();
+    tasks.sort();
+    // The two macos targets no longer share a runner, so they can't be merged
+    assert_eq!(
+        tasks,
+        [
+            (
+                "macos-11".to_owned(),
+                "--artifacts=local --target=x86_64-apple-darwin".to_owned()
+            ),
+            (
+                "macos-14".to_owned(),
+                "--artifacts=local --target=aarch64-apple-darwin".to_owned()
+            ),
+            (
+                "self-hosted".to_owned(),
+                "--artifacts=local --target=x86_64-unknown-linux-gnu".to_owned()
+            ),
+            (
+                "windows-2019".to_owned(),
+                "--artifacts=local --target=x86_64-pc-windows-msvc".to_owned()
+            ),
+        ]
+    );
+}
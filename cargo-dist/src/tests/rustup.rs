@@ -0,0 +1,69 @@
+//! Tests for planning the rustup toolchains/targets builds need
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, DistGraph, Tool};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+const LINUX_ARM: &str = "aarch64-unknown-linux-gnu";
+const MAC: &str = "x86_64-apple-darwin";
+const MAC_ARM: &str = "aarch64-apple-darwin";
+
+fn graph_with(targets: &[&str], host_target: &str, workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({ "targets": targets }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        host_target: Some(host_target.to_owned()),
+        ..mock_config()
+    };
+    let mut tools = mock_tools();
+    tools.rustup = Some(Tool {
+        cmd: "rustup".to_owned(),
+        version: "1.26.0".to_owned(),
+    });
+    gather_work_for_workspace(&cfg, tools, &workspace).unwrap()
+}
+
+#[test]
+fn cross_targets_need_rustup() {
+    let graph = graph_with(&[LINUX, LINUX_ARM, MAC], LINUX, json!({}));
+    let plan = graph.rustup_plan();
+    // Only the other linux arch is something rustup can help with
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].target.as_deref(), Some(LINUX_ARM));
+    assert_eq!(plan[0].toolchain, None);
+    assert_eq!(
+        graph.rustup_plan_commands(),
+        [format!("rustup target add {LINUX_ARM}")]
+    );
+
+    let graph = graph_with(&[MAC, MAC_ARM], MAC_ARM, json!({}));
+    assert_eq!(
+        graph.rustup_plan_commands(),
+        [format!("rustup target add {MAC}")]
+    );
+}
+
+#[test]
+fn rustup_plan_uses_toolchain() {
+    let graph = graph_with(
+        &[MAC, MAC_ARM],
+        MAC,
+        json!({ "rust-toolchain-version": "1.70.0" }),
+    );
+    assert_eq!(
+        graph.rustup_plan_commands(),
+        [
+            "rustup toolchain install 1.70.0".to_owned(),
+            format!("rustup target add {MAC_ARM} --toolchain=1.70.0"),
+        ]
+    );
+}
+
+#[test]
+fn no_cross_no_rustup() {
+    let graph = graph_with(&[LINUX], LINUX, json!({}));
+    assert!(graph.rustup_plan().is_empty());
+}
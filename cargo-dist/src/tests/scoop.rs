@@ -0,0 +1,102 @@
+//! Tests for Scoop manifests
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::{
+        ci::github::GithubCiInfo,
+        installer::{scoop::ScoopBin, InstallerImpl},
+    },
+    config::Config,
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &[
+    "x86_64-pc-windows-msvc",
+    "aarch64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+fn graph_with_scoop(workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["scoop"],
+        "targets": TARGETS,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn manifest_lists_windows_archives() {
+    let graph = graph_with_scoop(json!({}));
+    let (artifact, info) = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Scoop(info)) => Some((a, info)),
+            _ => None,
+        })
+        .expect("no Scoop manifest!?");
+    assert_eq!(artifact.id, format!("{BIN_AXO_NAME}.json"));
+    assert!(artifact.is_global);
+
+    let manifest = &info.manifest;
+    assert_eq!(manifest.version, BIN_AXO_VER);
+    let arches = manifest.architecture.keys().collect::<Vec<_>>();
+    assert_eq!(arches, ["64bit", "arm64"]);
+    let x64 = &manifest.architecture["64bit"];
+    assert!(
+        x64.url.ends_with("-x86_64-pc-windows-msvc.zip"),
+        "{}",
+        x64.url
+    );
+    assert_eq!(x64.bin, [ScoopBin::Path(format!("{BIN_AXO_NAME}.exe"))]);
+    // Without a bucket, Scoop installs straight from the manifest's URL
+    assert!(info.inner.hint.starts_with("scoop install https://"));
+}
+
+#[test]
+fn bucket_publish_job() {
+    let graph = graph_with_scoop(json!({
+        "ci": ["github"],
+        "bucket": "axodotdev/scoop-bucket",
+        "publish-jobs": ["scoop"],
+    }));
+    let info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Scoop(info)) => Some(info),
+            _ => None,
+        })
+        .expect("no Scoop manifest!?");
+    assert_eq!(
+        info.inner.hint,
+        format!("scoop bucket add scoop-bucket https://github.com/axodotdev/scoop-bucket; scoop install scoop-bucket/{BIN_AXO_NAME}")
+    );
+
+    let ci = GithubCiInfo::new(&graph);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(
+        workflow.contains("  publish-scoop-manifest:\n"),
+        "{workflow}"
+    );
+    assert!(workflow.contains("repository: \"axodotdev/scoop-bucket\""));
+    assert!(workflow.contains("git add bucket/${name}.json"));
+}
+
+#[test]
+fn no_bucket_publish_job_without_publish_job() {
+    let graph = graph_with_scoop(json!({
+        "ci": ["github"],
+        "bucket": "axodotdev/scoop-bucket",
+    }));
+    let ci = GithubCiInfo::new(&graph);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(!workflow.contains("publish-scoop-manifest:"));
+}
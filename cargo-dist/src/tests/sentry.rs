@@ -0,0 +1,72 @@
+//! Tests for uploading debug symbols to Sentry
+
+use super::mock::*;
+use axoproject::rust::CargoProfile;
+use serde_json::json;
+
+use crate::{
+    backend::ci::github::GithubCiInfo, config::Config, gather_work_for_workspace, DistGraph,
+};
+
+fn graph_for_target(target: &str, publish_jobs: &[&str]) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": [target],
+    }));
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "publish-jobs": publish_jobs,
+            "sentry-org": "axodotdev",
+            "sentry-project": "axolotlsay",
+        }
+    }));
+    // dSYMs need debuginfo
+    workspace.cargo_profiles.insert(
+        "dist".to_owned(),
+        CargoProfile {
+            inherits: Some("release".to_owned()),
+            debug: Some(1),
+            split_debuginfo: None,
+        },
+    );
+    let cfg = Config {
+        targets: vec![target.to_owned()],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn sentry_job_with_symbols() {
+    let graph = graph_for_target("aarch64-apple-darwin", &["sentry"]);
+    let ci = GithubCiInfo::new(&graph);
+    let sentry = ci
+        .sentry
+        .expect("no sentry job even though there are dSYMs");
+    assert_eq!(sentry.org, "axodotdev");
+    assert_eq!(sentry.project, "axolotlsay");
+}
+
+#[test]
+fn sentry_job_in_workflow() {
+    let graph = graph_for_target("aarch64-apple-darwin", &["sentry"]);
+    let ci = GithubCiInfo::new(&graph);
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("publish-sentry-symbols:"));
+    assert!(workflow.contains(r#"SENTRY_ORG: "axodotdev""#));
+    assert!(workflow.contains("sentry-cli debug-files upload symbols/"));
+}
+
+#[test]
+fn no_sentry_job_without_symbols() {
+    // We don't make symbols for linux targets
+    let graph = graph_for_target("x86_64-unknown-linux-gnu", &["sentry"]);
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.sentry.is_none());
+}
+
+#[test]
+fn no_sentry_job_unless_requested() {
+    let graph = graph_for_target("aarch64-apple-darwin", &["homebrew"]);
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.sentry.is_none());
+}
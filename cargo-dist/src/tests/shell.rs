@@ -0,0 +1,412 @@
+//! Tests for shell installers
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use base64::Engine;
+use camino::Utf8PathBuf;
+use serde_json::json;
+
+use crate::{
+    backend::{
+        installer::{
+            shell::{fill_embedded_archives, render_install_sh_script},
+            InstallerImpl,
+        },
+        templates::TEMPLATE_INSTALLER_PS1,
+    },
+    config::{Config, InstallerStyle},
+    errors::DistError,
+    gather_work_for_workspace, ArtifactKind,
+};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+#[test]
+fn shell_installer_can_uninstall() {
+    let mut workspace = mock_workspace(vec![pkg_helper_dist(json!({
+        "installers": ["shell"],
+        "targets": ["x86_64-unknown-linux-gnu"],
+        "install-path": "~/.helper",
+    }))]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        announcement_tag: Some(format!("v{BIN_HELPER_VER}")),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    let script = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => {
+                Some(render_install_sh_script(&graph.templates, info).unwrap())
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    assert!(script.contains("            --uninstall)\n                UNINSTALL=1\n"));
+    // Installing writes down every binary it copied
+    assert!(script.contains("_receipt_path=\"${_env_script_path%/*}/$APP_NAME-receipt\"\n"));
+    assert!(script.contains("ensure printf '%s' \"$_receipt\" > \"$_receipt_path\"\n"));
+    // Uninstalling without a receipt falls back to the archive's binaries
+    assert!(script.contains(&format!(
+        "            _bins=\"{BIN_HELPER_NAME} {BIN_HELPER_NAME2}\"\n"
+    )));
+    let uninstall = &script[script.find("\nuninstall() {\n").unwrap()..];
+    assert!(uninstall.contains("done < \"$_receipt_path\"\n"));
+    assert!(uninstall.contains("        select_artifact \"$_arch\"\n"));
+    // ...from wherever install-path says they went
+    assert!(uninstall.contains("    get_install_dir\n"));
+    assert!(script.contains("        _install_dir=\"$HOME/.helper\"\n"));
+}
+
+#[test]
+fn xdg_install_path() {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell", "powershell", "homebrew", "npm"],
+        "targets": TARGETS,
+        "install-path": "xdg",
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![
+            InstallerStyle::Shell,
+            InstallerStyle::Powershell,
+            InstallerStyle::Homebrew,
+            InstallerStyle::Npm,
+        ],
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    assert_eq!(graph.releases[0].install_path.to_string(), "xdg");
+
+    let mut rendered = 0;
+    for artifact in &graph.artifacts {
+        match &artifact.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => {
+                let script = render_install_sh_script(&graph.templates, info).unwrap();
+                assert!(script.contains("        _install_dir=\"$XDG_BIN_HOME\"\n"));
+                assert!(script.contains("        _install_dir_expr='$HOME/.local/bin'\n"));
+                assert!(script.contains(
+                    "add_install_dir_to_path \"$_install_dir_expr\" \"$_env_script_path\""
+                ));
+                rendered += 1;
+            }
+            ArtifactKind::Installer(InstallerImpl::Powershell(info)) => {
+                let script = graph
+                    .templates
+                    .render_file_to_clean_string(TEMPLATE_INSTALLER_PS1, info)
+                    .unwrap();
+                assert!(script.contains("if (($base_dir = $env:XDG_BIN_HOME)) {\n"));
+                assert!(script.contains("Join-Path $base_dir \".local\\bin\"\n"));
+                assert!(script.contains("if (Add-Path $dest_dir)"));
+                rendered += 1;
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(rendered, 2);
+    // Installers that don't touch PATH themselves still get planned
+    assert!(graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::Homebrew(_)))));
+    assert!(graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::Npm(_)))));
+}
+
+#[test]
+fn installers_only_add_to_path_once() {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell", "powershell"],
+        "targets": TARGETS,
+        "install-path": "~/.axolotlsay/bin",
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Shell, InstallerStyle::Powershell],
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    let mut rendered = 0;
+    for artifact in &graph.artifacts {
+        match &artifact.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => {
+                let script = render_install_sh_script(&graph.templates, info).unwrap();
+                // The dir we check for is the one we install to
+                assert!(script.contains("        _install_dir_expr='$HOME/.axolotlsay/bin'\n"));
+                assert!(script.contains(
+                    "        add_install_dir_to_path \"$_install_dir_expr\" \"$_env_script_path\" \"$_env_script_path_expr\" \"$_install_dir\"\n"
+                ));
+                // The rcfile is only appended to if nothing in it already handles PATH
+                assert!(script.contains(
+                    "        if grep -F \"$_install_dir_expr\" \"$_rcfile\" 2>/dev/null | grep -F \"PATH\""
+                ));
+                assert!(script.contains(
+                    "                _late_expr=\"\\$HOME/${_env_script_path#\"$HOME\"/}\"\n"
+                ));
+                assert!(script.contains("        if [ \"0\" = \"$_already_on_path\" ]\n"));
+                rendered += 1;
+            }
+            ArtifactKind::Installer(InstallerImpl::Powershell(info)) => {
+                let script = graph
+                    .templates
+                    .render_file_to_clean_string(TEMPLATE_INSTALLER_PS1, info)
+                    .unwrap();
+                assert!(script.contains("    Join-Path $base_dir \".axolotlsay/bin\"\n"));
+                assert!(script.contains("    if (Add-Path $dest_dir) {\n"));
+                assert!(script.contains(
+                    "  $Entries = $OldPath -split \";\" | ForEach-Object { $_.TrimEnd(\"\\\") }\n"
+                ));
+                assert!(
+                    script.contains("  if ($Entries -contains $OrigPathToAdd.TrimEnd(\"\\\")) {\n")
+                );
+                assert!(!script.contains("-like \"*;$OrigPathToAdd;*\""));
+                rendered += 1;
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(rendered, 2);
+}
+
+#[test]
+fn custom_artifact_download_url() {
+    // No repository, so without artifact-download-url there'd be no installer at all
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        repository_url: None,
+        ..pkg_axo_dist(json!({
+            "installers": ["shell"],
+            "targets": ["x86_64-unknown-linux-gnu"],
+        }))
+    }]);
+    workspace.repository_url = None;
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": { "artifact-download-url": "https://cdn.example.com/axolotlsay/{tag}/" }
+    }));
+    let cfg = mock_config();
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    let base_url = format!("https://cdn.example.com/axolotlsay/v{BIN_AXO_VER}");
+    assert_eq!(graph.artifact_download_url.as_deref(), Some(&*base_url));
+
+    let info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info),
+            _ => None,
+        })
+        .expect("no shell installer");
+    assert_eq!(info.base_url, base_url);
+    let installer_url = format!("{base_url}/{BIN_AXO_NAME}-installer.sh");
+    assert_eq!(
+        info.hint,
+        format!("curl --proto '=https' --tlsv1.2 -LsSf {installer_url} | sh")
+    );
+}
+
+#[test]
+fn install_prefix_override() {
+    for install_path in ["CARGO_HOME", "xdg"] {
+        let cfg = mock_config();
+        let graph = graph_for_dist_metadata(
+            json!({
+                "installers": ["shell"],
+                "targets": ["x86_64-unknown-linux-gnu"],
+                "install-path": install_path,
+            }),
+            &cfg,
+        );
+        let script = graph
+            .artifacts
+            .iter()
+            .find_map(|a| match &a.kind {
+                ArtifactKind::Installer(InstallerImpl::Shell(info)) => {
+                    Some(render_install_sh_script(&graph.templates, info).unwrap())
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(script.contains("INSTALL_PREFIX=${INSTALLER_INSTALL_PATH:-}\n"));
+        assert!(script.contains(
+            "            --prefix=*)\n                INSTALL_PREFIX=\"${arg#--prefix=}\"\n"
+        ));
+        // The override wins over the configured install-path, which still works otherwise
+        let get_install_dir = &script[script.find("\nget_install_dir() {\n").unwrap()..];
+        let prefix = get_install_dir
+            .find("    if [ -n \"$INSTALL_PREFIX\" ]; then\n")
+            .unwrap();
+        let default = if install_path == "xdg" {
+            "        _install_dir=\"$XDG_BIN_HOME\"\n"
+        } else {
+            "        _install_dir=\"$CARGO_HOME/bin\"\n"
+        };
+        assert!(prefix < get_install_dir.find(default).unwrap());
+
+        #[cfg(unix)]
+        {
+            // Actually ask the script where it would install to
+            let script = script.replace(
+                "download_binary_and_run_installer \"$@\" || exit 1\n",
+                "get_install_dir\necho \"$_install_dir $_receipt_path\"\n",
+            );
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(&script)
+                .env("INSTALLER_INSTALL_PATH", "/opt/axolotlsay/bin")
+                .output()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                format!("/opt/axolotlsay/bin /opt/axolotlsay/bin/{BIN_AXO_NAME}-receipt\n")
+            );
+        }
+    }
+}
+
+#[test]
+fn embedded_archives() {
+    let host = mock_tools().cargo.host_target;
+    let targets = vec![host.clone(), "aarch64-apple-darwin".to_owned()];
+    let cfg = Config {
+        targets: targets.clone(),
+        ..mock_config()
+    };
+    let graph = graph_for_dist_metadata(
+        json!({
+            "installers": ["shell"],
+            "targets": targets,
+            "embed-archives": true,
+        }),
+        &cfg,
+    );
+    let mut info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(info.embedded_archives, Some(Default::default()));
+
+    // The archives have to be built first
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-embedded-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert!(matches!(
+        fill_embedded_archives(&dir, &mut info.clone()),
+        Err(DistError::MissingEmbeddedArchive { .. })
+    ));
+
+    // Enough fake archive to need a few lines of base64
+    let host_archive = info
+        .artifacts
+        .iter()
+        .find(|a| a.target_triples == [host.clone()])
+        .unwrap()
+        .id
+        .clone();
+    let host_contents = (0..=255u8).cycle().take(1000).collect::<Vec<u8>>();
+    for artifact in &info.artifacts {
+        let contents = if artifact.id == host_archive {
+            host_contents.clone()
+        } else {
+            b"some other platform".to_vec()
+        };
+        std::fs::write(dir.join(&artifact.id), contents).unwrap();
+    }
+    fill_embedded_archives(&dir, &mut info).unwrap();
+    let script = render_install_sh_script(&graph.templates, &info).unwrap();
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&host_contents);
+    let payload = encoded
+        .as_bytes()
+        .chunks(76)
+        .map(|line| std::str::from_utf8(line).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(script.contains(&format!(
+        "        \"{host_archive}\")\n            cat <<'ARCHIVE'\n{payload}\nARCHIVE\n"
+    )));
+    assert!(script.contains("    extract_embedded_archive \"$_artifact_name\" \"$_file\"\n"));
+    // Nothing gets downloaded
+    assert!(!script.contains("ARTIFACT_DOWNLOAD_URL"));
+    assert!(!script.contains("    downloader --check\n"));
+
+    #[cfg(unix)]
+    {
+        // Actually get the archive back out of the script
+        let out = dir.join("decoded");
+        let script = script.replace(
+            "download_binary_and_run_installer \"$@\" || exit 1\n",
+            &format!("extract_embedded_archive \"{host_archive}\" \"{out}\"\n"),
+        );
+        let status = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(&script)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(std::fs::read(&out).unwrap(), host_contents);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64", target_env = "gnu"))]
+#[test]
+fn installer_runs_without_checksums() {
+    let mut workspace = workspace_axo_dist(json!({
+        "installers": ["shell"],
+        "targets": ["x86_64-unknown-linux-gnu"],
+        "unix-archive": ".tar.gz",
+        "checksum": "false",
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "ci": ["github"] } }));
+    let graph = gather_work_for_workspace(&mock_config(), mock_tools(), &workspace).unwrap();
+    let mut info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert!(info.artifacts.iter().all(|a| a.checksum.is_none()));
+    // Install from an archive sitting next to the script, so there's nothing to download
+    info.offline = true;
+    let script = render_install_sh_script(&graph.templates, &info).unwrap();
+
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .unwrap()
+        .join(format!("cargo-dist-no-checksums-{}", std::process::id()));
+    let archive = info.artifacts[0].id.strip_suffix(".tar.gz").unwrap();
+    let bin_dir = dir.join("bin");
+    let output = run_offline_shell_installer(
+        &dir,
+        &script,
+        &[(archive, &[(BIN_AXO_NAME, "#!/bin/sh\necho hello\n")])],
+        &["--no-modify-path", "--prefix", bin_dir.as_str()],
+    );
+    let installed = bin_dir.join(BIN_AXO_NAME).exists();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(installed);
+}
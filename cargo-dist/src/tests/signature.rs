@@ -0,0 +1,121 @@
+//! Tests for gpg signatures of artifacts
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::{ArtifactMode, Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, BuildStep,
+};
+
+fn config_with(artifact_mode: ArtifactMode) -> Config {
+    Config {
+        artifact_mode,
+        installers: vec![InstallerStyle::Shell],
+        ..mock_config()
+    }
+}
+
+fn workspace_axo(gpg_key: Option<&str>) -> axoproject::WorkspaceInfo {
+    let mut dist = json!({
+        "installers": ["shell"],
+        "targets": ["x86_64-unknown-linux-gnu"],
+    });
+    if let Some(key) = gpg_key {
+        dist["gpg-key"] = json!(key);
+    }
+    workspace_axo_dist(dist)
+}
+
+#[test]
+fn archives_are_signed() {
+    let workspace = workspace_axo(Some("ABCD1234"));
+    let cfg = config_with(ArtifactMode::Local);
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    let archive = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .expect("no archive!?");
+    let signature = graph.artifact(archive.signature.expect("archive wasn't signed"));
+    assert_eq!(signature.id, format!("{}.asc", archive.id));
+    let ArtifactKind::Signature(sig) = &signature.kind else {
+        panic!("signature artifact had the wrong kind");
+    };
+    assert_eq!(sig.key, "ABCD1234");
+
+    // Signing happens after the archive (and its checksum) is built
+    let sign_step = graph
+        .build_steps
+        .iter()
+        .position(|s| matches!(s, BuildStep::Sign(_)))
+        .expect("no signing step");
+    let zip_step = graph
+        .build_steps
+        .iter()
+        .position(|s| matches!(s, BuildStep::Zip(_)))
+        .expect("no zip step");
+    assert!(zip_step < sign_step);
+}
+
+#[test]
+fn installers_are_signed() {
+    let workspace = workspace_axo(Some("ABCD1234"));
+    let cfg = config_with(ArtifactMode::Global);
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    let installer = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(a.kind, ArtifactKind::Installer(_)))
+        .expect("no installer!?");
+    assert!(installer.signature.is_some());
+}
+
+#[test]
+fn global_artifacts_are_signed() {
+    let dist = json!({
+        "installers": ["shell"],
+        "targets": ["x86_64-unknown-linux-gnu"],
+        "gpg-key": "ABCD1234",
+        "citation": true,
+        "unified-checksum": true,
+        "checksums-json": true,
+    });
+    let graph = graph_for_dist_metadata(dist, &config_with(ArtifactMode::Global));
+
+    let unsigned = graph
+        .artifacts
+        .iter()
+        .filter(|a| !matches!(a.kind, ArtifactKind::Signature(_)) && a.signature.is_none())
+        .map(|a| &a.id)
+        .collect::<Vec<_>>();
+    assert!(unsigned.is_empty(), "{unsigned:?}");
+
+    // The unified checksums are only signed once they're written
+    for (idx, step) in graph.build_steps.iter().enumerate() {
+        let BuildStep::UnifiedChecksum(unified) = step else {
+            continue;
+        };
+        let sign_step = graph
+            .build_steps
+            .iter()
+            .position(|s| matches!(s, BuildStep::Sign(sig) if sig.src_path == unified.dest_path))
+            .expect("unified checksum wasn't signed");
+        assert!(graph.build_plan.depends_on(sign_step, idx));
+    }
+}
+
+#[test]
+fn nothing_signed_without_key() {
+    let workspace = workspace_axo(None);
+    let cfg = config_with(ArtifactMode::All);
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+
+    assert!(graph.artifacts.iter().all(|a| a.signature.is_none()));
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Signature(_))));
+}
@@ -0,0 +1,103 @@
+//! Tests for slim-archives (stripped + full archive pairs)
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::InstallerImpl,
+    config::{Config, InstallerStyle},
+    ArtifactKind, BuildStep, DistGraph,
+};
+
+fn graph_with_slim_archives(target: &str, slim_archives: bool) -> DistGraph {
+    let cfg = Config {
+        targets: vec![target.to_owned()],
+        installers: vec![InstallerStyle::Shell],
+        ..mock_config()
+    };
+    graph_for_dist_metadata(
+        json!({
+            "targets": [target],
+            "installers": ["shell"],
+            "slim-archives": slim_archives,
+        }),
+        &cfg,
+    )
+}
+
+fn strip_steps(graph: &DistGraph) -> Vec<&Vec<camino::Utf8PathBuf>> {
+    graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Strip(strip) => Some(&strip.paths),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn slim_and_full_archives() {
+    let graph = graph_with_slim_archives("x86_64-unknown-linux-gnu", true);
+
+    let zips = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(zips.len(), 2);
+    let slim = zips
+        .iter()
+        .find(|a| a.id.ends_with("-slim.tar.xz"))
+        .expect("no slim archive!?");
+    let full = zips
+        .iter()
+        .find(|a| a.id.ends_with("-full.tar.xz"))
+        .expect("no full archive!?");
+    assert!(slim.archive.as_ref().unwrap().strip_binaries);
+    assert!(!full.archive.as_ref().unwrap().strip_binaries);
+
+    // Both archives get their own copy of the binary, but only the slim one is stripped
+    let binary = &graph.binaries[0];
+    assert_eq!(binary.copy_exe_to.len(), 2);
+    let strips = strip_steps(&graph);
+    assert_eq!(strips.len(), 1);
+    assert_eq!(strips[0].len(), 1);
+    assert!(strips[0][0].starts_with(&slim.archive.as_ref().unwrap().dir_path));
+
+    // Both archives are checksummed
+    assert!(slim.checksum.is_some());
+    assert!(full.checksum.is_some());
+
+    // Installers fetch the slim one
+    let installer = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Shell(info)) => Some(info),
+            _ => None,
+        })
+        .expect("no shell installer!?");
+    assert_eq!(installer.artifacts.len(), 1);
+    assert_eq!(installer.artifacts[0].id, slim.id);
+}
+
+#[test]
+fn no_slim_archives_by_default() {
+    let graph = graph_with_slim_archives("x86_64-unknown-linux-gnu", false);
+    let zips = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(zips.len(), 1);
+    assert!(!zips[0].id.contains("-slim") && !zips[0].id.contains("-full"));
+    assert!(strip_steps(&graph).is_empty());
+}
+
+#[test]
+fn msvc_slim_archives_arent_stripped() {
+    let graph = graph_with_slim_archives("x86_64-pc-windows-msvc", true);
+    assert!(graph.artifacts.iter().any(|a| a.id.ends_with("-slim.zip")));
+    assert!(strip_steps(&graph).is_empty());
+}
@@ -0,0 +1,106 @@
+//! Tests for snap installers and publishing them to the Snap Store
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::{
+        ci::github::GithubCiInfo,
+        installer::{snap::SNAP_BIN_DIR, InstallerImpl},
+    },
+    config::{Config, InstallerStyle, SnapConfinement, SnapGrade},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "x86_64-pc-windows-msvc"];
+
+fn graph_with_snaps(dist: serde_json::Value, workspace_dist: serde_json::Value) -> DistGraph {
+    let mut dist = dist;
+    dist["installers"] = json!(["snap"]);
+    dist["targets"] = json!(TARGETS);
+    let mut workspace = workspace_axo_dist(dist);
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Snap],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn snap_for_linux_only() {
+    let graph = graph_with_snaps(json!({}), json!({}));
+    let snaps = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Snap(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(snaps.len(), 1);
+    let (artifact, info) = snaps[0];
+    assert_eq!(artifact.target_triples, ["x86_64-unknown-linux-gnu"]);
+    assert!(artifact.id.ends_with(".snap"));
+    assert!(artifact.checksum.is_some());
+    let dest_path = artifact.required_binaries.values().next().unwrap();
+    assert_eq!(
+        dest_path,
+        &info.package_dir.join(SNAP_BIN_DIR).join(BIN_AXO_NAME)
+    );
+
+    // Safe defaults
+    assert_eq!(info.spec.arch, "amd64");
+    assert_eq!(info.spec.confinement, SnapConfinement::Strict);
+    assert_eq!(info.spec.grade, SnapGrade::Stable);
+}
+
+#[test]
+fn snap_confinement_and_grade() {
+    let graph = graph_with_snaps(
+        json!({
+            "snap-confinement": "classic",
+            "snap-grade": "devel",
+        }),
+        json!({}),
+    );
+    let info = graph
+        .artifacts
+        .iter()
+        .find_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::Snap(info)) => Some(info),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(info.spec.confinement, SnapConfinement::Classic);
+    assert_eq!(info.spec.grade, SnapGrade::Devel);
+    let yaml = info.render_snapcraft_yaml();
+    assert!(yaml.contains("confinement: classic\n"));
+    assert!(yaml.contains("grade: devel\n"));
+}
+
+#[test]
+fn snap_publish_job() {
+    let graph = graph_with_snaps(
+        json!({}),
+        json!({
+            "publish-jobs": ["snap"],
+            "snap-channel": "edge",
+        }),
+    );
+    let ci = GithubCiInfo::new(&graph);
+    assert_eq!(ci.snap.as_ref().unwrap().channel, "edge");
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("publish-snaps:"));
+    assert!(workflow.contains(r#"snapcraft upload --release="edge""#));
+}
+
+#[test]
+fn no_snap_publish_job_unless_requested() {
+    let graph = graph_with_snaps(json!({}), json!({}));
+    let ci = GithubCiInfo::new(&graph);
+    assert!(ci.snap.is_none());
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(!workflow.contains("publish-snaps:"));
+}
@@ -0,0 +1,99 @@
+//! Tests for per-target staging dirs
+
+use super::mock::*;
+use axoproject::rust::CargoProfile;
+use serde_json::json;
+
+use crate::{config::Config, gather_work_for_workspace, ArtifactKind, DistGraph, SymbolKind};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"];
+
+fn graph_with_staging(per_target: bool) -> DistGraph {
+    graph_for_targets(per_target, TARGETS)
+}
+
+fn graph_for_targets(per_target: bool, targets: &[&str]) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": targets,
+    }));
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "per-target-staging-dirs": per_target,
+        }
+    }));
+    // Debuginfo, so that apple targets get dSYMs
+    workspace.cargo_profiles.insert(
+        "dist".to_owned(),
+        CargoProfile {
+            inherits: Some("release".to_owned()),
+            debug: Some(1),
+            split_debuginfo: None,
+        },
+    );
+    let cfg = Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn variants_get_distinct_staging_dirs() {
+    let graph = graph_with_staging(true);
+    let zips = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(zips.len(), 2);
+
+    let mut staging_dirs = vec![];
+    for zip in zips {
+        let target = &zip.target_triples[0];
+        let dir_path = &zip.archive.as_ref().unwrap().dir_path;
+        // Staged under a dir named after the target...
+        assert_eq!(
+            dir_path.parent().unwrap(),
+            graph.dist_dir.join(target),
+            "{dir_path}"
+        );
+        // ...but the final artifact keeps its usual place
+        assert_eq!(zip.file_path, graph.dist_dir.join(&zip.id));
+        staging_dirs.push(dir_path.clone());
+    }
+    assert_ne!(staging_dirs[0], staging_dirs[1]);
+}
+
+#[test]
+fn staging_dirs_flat_by_default() {
+    let graph = graph_with_staging(false);
+    for zip in graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::ExecutableZip(_)))
+    {
+        let dir_path = &zip.archive.as_ref().unwrap().dir_path;
+        assert_eq!(dir_path.parent().unwrap(), graph.dist_dir);
+    }
+}
+
+#[test]
+fn dsyms_get_per_target_staging_dirs() {
+    let target = "aarch64-apple-darwin";
+    for per_target in [true, false] {
+        let graph = graph_for_targets(per_target, &[target]);
+        let dsym = graph
+            .artifacts
+            .iter()
+            .find(|a| matches!(&a.kind, ArtifactKind::Symbols(s) if matches!(s.kind, SymbolKind::Dsym)))
+            .expect("no dSYM artifact!?");
+        let dir_path = &dsym.archive.as_ref().unwrap().dir_path;
+        let expected_parent = if per_target {
+            graph.dist_dir.join(target)
+        } else {
+            graph.dist_dir.clone()
+        };
+        assert_eq!(dir_path.parent().unwrap(), expected_parent, "{dir_path}");
+        assert_eq!(dsym.file_path, graph.dist_dir.join(&dsym.id));
+    }
+}
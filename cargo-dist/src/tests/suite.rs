@@ -0,0 +1,88 @@
+//! Tests for the install.sh that can install any app in an announcement
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    backend::installer::{shell::render_install_suite_sh_script, InstallerImpl},
+    config::Config,
+    errors::Result,
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "aarch64-apple-darwin"];
+
+fn graph_with_suite(workspace_dist: serde_json::Value) -> Result<DistGraph> {
+    let dist = json!({
+        "installers": ["shell"],
+        "targets": TARGETS,
+    });
+    let mut workspace = mock_workspace(vec![pkg_axo_dist(dist.clone()), pkg_helper_dist(dist)]);
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace)
+}
+
+fn suite_installer(graph: &DistGraph) -> Option<String> {
+    graph.artifacts.iter().find_map(|a| match &a.kind {
+        ArtifactKind::Installer(InstallerImpl::ShellSuite(info)) => {
+            Some(render_install_suite_sh_script(&graph.templates, info).unwrap())
+        }
+        _ => None,
+    })
+}
+
+#[test]
+fn suite_installer_dispatches_to_every_app() {
+    let graph = graph_with_suite(json!({
+        "ci": ["github"],
+        "shell-suite-installer": true,
+    }))
+    .unwrap();
+    let script = suite_installer(&graph).expect("no suite installer!?");
+
+    assert!(script.contains(&format!("ALL_APPS=\"{BIN_AXO_NAME} {BIN_HELPER_NAME}\"")));
+    for app in [BIN_AXO_NAME, BIN_HELPER_NAME] {
+        assert!(script.contains(&format!("        {app})\n")), "{script}");
+        for target in TARGETS {
+            let archive = format!("{app}-{target}.tar.xz");
+            assert!(script.contains(&archive), "missing {archive}");
+        }
+    }
+}
+
+#[test]
+fn no_suite_installer_by_default() {
+    let graph = graph_with_suite(json!({ "ci": ["github"] })).unwrap();
+    assert!(suite_installer(&graph).is_none());
+}
+
+#[test]
+fn suite_installer_is_checksummed_and_signed() {
+    let graph = graph_with_suite(json!({
+        "ci": ["github"],
+        "shell-suite-installer": true,
+        "gpg-key": "ABCD1234",
+    }))
+    .unwrap();
+    let suite = graph
+        .artifacts
+        .iter()
+        .find(|a| {
+            matches!(
+                a.kind,
+                ArtifactKind::Installer(InstallerImpl::ShellSuite(_))
+            )
+        })
+        .expect("no suite installer!?");
+
+    let checksum = graph.artifact(suite.checksum.expect("suite installer wasn't checksummed"));
+    assert_eq!(checksum.id, "install.sh.sha256");
+    assert!(checksum.is_global);
+    let signature = graph.artifact(suite.signature.expect("suite installer wasn't signed"));
+    assert_eq!(signature.id, "install.sh.asc");
+    assert!(signature.is_global);
+}
@@ -0,0 +1,79 @@
+//! Tests for symbols (debuginfo) artifacts
+
+use super::mock::*;
+use axoproject::rust::CargoProfile;
+use serde_json::json;
+
+use crate::{
+    config::{ArtifactMode, CompressionImpl, Config, ZipStyle},
+    gather_work_for_workspace, ArtifactKind, BuildStep, DistGraph, SymbolKind,
+};
+
+fn graph_for_target(target: &str, dist_debug: Option<i64>) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": [target],
+    }));
+    workspace.cargo_profiles.insert(
+        "dist".to_owned(),
+        CargoProfile {
+            inherits: Some("release".to_owned()),
+            debug: dist_debug,
+            split_debuginfo: None,
+        },
+    );
+    let cfg = Config {
+        artifact_mode: ArtifactMode::Local,
+        targets: vec![target.to_owned()],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn apple_dsyms_are_tarballed() {
+    let graph = graph_for_target("aarch64-apple-darwin", Some(1));
+
+    let symbols = graph
+        .artifacts
+        .iter()
+        .find(|a| matches!(&a.kind, ArtifactKind::Symbols(s) if matches!(s.kind, SymbolKind::Dsym)))
+        .expect("no dSYM artifact!?");
+    assert!(symbols.id.ends_with(".dSYM.tar.xz"), "{}", symbols.id);
+    assert_eq!(symbols.file_path.file_name(), Some(&*symbols.id));
+
+    // The dSYM directory gets copied into a dir of its own, which is what we tarball
+    let archive = symbols.archive.as_ref().expect("dSYM wasn't archived");
+    assert_eq!(archive.zip_style, ZipStyle::Tar(CompressionImpl::Xzip));
+    let binary = graph
+        .binaries
+        .iter()
+        .find(|b| b.symbols_artifact.is_some())
+        .unwrap();
+    assert_eq!(binary.copy_symbols_to.len(), 1);
+    assert!(binary.copy_symbols_to[0].starts_with(&archive.dir_path));
+    assert_eq!(binary.copy_symbols_to[0].extension(), Some("dSYM"));
+
+    assert!(graph.build_steps.iter().any(|step| matches!(
+        step,
+        BuildStep::Zip(zip) if zip.dest_path == symbols.file_path && zip.src_path == archive.dir_path
+    )));
+}
+
+#[test]
+fn linux_has_no_symbols() {
+    let graph = graph_for_target("x86_64-unknown-linux-gnu", Some(1));
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Symbols(_))));
+}
+
+#[test]
+fn no_dsyms_without_debuginfo() {
+    // dist inherits release, which has no debuginfo by default
+    let graph = graph_for_target("aarch64-apple-darwin", None);
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Symbols(_))));
+}
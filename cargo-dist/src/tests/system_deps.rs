@@ -0,0 +1,239 @@
+//! Tests for installing system dependencies before building in CI
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{build_manifest, config::Config, gather_work_for_workspace, DistGraph};
+
+const TARGETS: &[&str] = &[
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+fn config_with(targets: &[&str]) -> Config {
+    Config {
+        targets: targets.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    }
+}
+
+fn graph_with(workspace_dist: serde_json::Value) -> DistGraph {
+    graph_for(TARGETS, workspace_dist)
+}
+
+fn graph_for(targets: &[&str], workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(json!({
+        "targets": targets,
+    }));
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    gather_work_for_workspace(&config_with(targets), mock_tools(), &workspace).unwrap()
+}
+
+fn deps() -> serde_json::Value {
+    json!({
+        "apt": {
+            "libssl-dev": "*",
+            "libgtk-3-0": { "stage": ["run"] },
+        },
+        "homebrew": { "protobuf": "*" },
+        "chocolatey": { "nasm": "*" },
+    })
+}
+
+#[test]
+fn github_setup_installs_apt_build_deps() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "dependencies": deps(),
+    }));
+    // The "Install dependencies" step of the build jobs runs the matrix's packages_install
+    let manifest = build_manifest(&config_with(TARGETS), &graph);
+    let matrix = &manifest
+        .ci
+        .unwrap()
+        .github
+        .unwrap()
+        .artifacts_matrix
+        .unwrap();
+    let install_for = |target: &str| {
+        matrix
+            .include
+            .iter()
+            .find(|task| {
+                task.dist_args
+                    .as_deref()
+                    .is_some_and(|args| args.ends_with(&format!("--target={target}")))
+            })
+            .and_then(|task| task.packages_install.clone())
+    };
+
+    // Runtime-only dependencies don't need to be installed to build
+    assert_eq!(
+        install_for("x86_64-unknown-linux-gnu").as_deref(),
+        Some("sudo apt-get install libssl-dev")
+    );
+    assert_eq!(
+        install_for("x86_64-pc-windows-msvc").as_deref(),
+        Some("choco install nasm")
+    );
+    assert!(install_for("aarch64-apple-darwin")
+        .unwrap()
+        .contains(r#"brew "protobuf""#));
+
+    let workflow = graph
+        .ci
+        .github
+        .as_ref()
+        .unwrap()
+        .generate_github_ci(&graph)
+        .unwrap();
+    let install = workflow.find("- name: Install dependencies").unwrap();
+    let build = workflow.find("- name: Build artifacts").unwrap();
+    assert!(install < build);
+}
+
+#[test]
+fn gitlab_installs_build_deps() {
+    let graph = graph_with(json!({
+        "ci": ["gitlab"],
+        "dependencies": deps(),
+    }));
+    let ci = graph.ci.gitlab.as_ref().unwrap();
+    let job_for = |target: &str| {
+        ci.local_jobs
+            .iter()
+            .find(|job| job.name == format!("build-local-{target}"))
+            .unwrap()
+    };
+
+    // The linux image runs as root and has no sudo
+    let linux = job_for("x86_64-unknown-linux-gnu");
+    assert_eq!(
+        linux.packages_install.as_deref(),
+        Some("apt-get install -y libssl-dev")
+    );
+    assert_eq!(
+        job_for("x86_64-pc-windows-msvc")
+            .packages_install
+            .as_deref(),
+        Some("choco install nasm")
+    );
+
+    let pipeline = ci.generate_gitlab_ci(&graph).unwrap();
+    let job = pipeline
+        .find("build-local-x86_64-unknown-linux-gnu:")
+        .unwrap();
+    let install = job
+        + pipeline[job..]
+            .find("apt-get install -y libssl-dev")
+            .unwrap();
+    let build = job + pipeline[job..].find("cargo dist build").unwrap();
+    assert!(install < build);
+    assert!(!pipeline.contains("libgtk-3-0"));
+    // Homebrew builds happen inside the bundle, like on Github
+    assert!(pipeline.contains("- brew bundle exec -- cargo dist build"));
+}
+
+#[test]
+fn no_deps_no_install() {
+    let graph = graph_with(json!({ "ci": ["github", "gitlab"] }));
+    let github = graph.ci.github.as_ref().unwrap();
+    assert!(github
+        .artifacts_matrix
+        .include
+        .iter()
+        .all(|task| task.packages_install.is_none()));
+    let gitlab = graph.ci.gitlab.as_ref().unwrap();
+    assert!(gitlab
+        .local_jobs
+        .iter()
+        .all(|job| job.packages_install.is_none()));
+    let pipeline = gitlab.generate_gitlab_ci(&graph).unwrap();
+    assert!(!pipeline.contains("Install the system dependencies"));
+}
+
+/// The packages_install of each Github build job, by the targets it builds
+fn github_installs(graph: &DistGraph) -> Vec<(String, Option<String>)> {
+    let ci = graph.ci.github.as_ref().unwrap();
+    ci.artifacts_matrix
+        .include
+        .iter()
+        .map(|task| {
+            let args = task.dist_args.clone().unwrap();
+            (args, task.packages_install.clone())
+        })
+        .collect()
+}
+
+#[test]
+fn github_installs_apt_deps_for_any_linux_target() {
+    let graph = graph_for(
+        &["aarch64-unknown-linux-gnu", "armv7-unknown-linux-gnueabihf"],
+        json!({
+            "ci": ["github"],
+            "dependencies": { "apt": { "pkg-config": "*" } },
+        }),
+    );
+    let installs = github_installs(&graph);
+    assert_eq!(installs.len(), 2);
+    for (args, install) in installs {
+        assert_eq!(
+            install.as_deref(),
+            Some("sudo apt-get install pkg-config"),
+            "{args}"
+        );
+    }
+}
+
+#[test]
+fn merged_tasks_install_deps_for_every_target() {
+    let graph = graph_for(
+        &["x86_64-unknown-linux-gnu", "x86_64-unknown-linux-musl"],
+        json!({
+            "ci": ["github"],
+            "merge-tasks": true,
+            "dependencies": {
+                "apt": {
+                    "libssl-dev": "*",
+                    "libmusl-only": { "targets": ["x86_64-unknown-linux-musl"] },
+                },
+            },
+        }),
+    );
+    let installs = github_installs(&graph);
+    assert_eq!(
+        installs,
+        vec![(
+            "--artifacts=local --target=x86_64-unknown-linux-gnu --target=x86_64-unknown-linux-musl"
+                .to_owned(),
+            Some("sudo apt-get install musl-tools libssl-dev libmusl-only".to_owned())
+        )]
+    );
+}
+
+#[test]
+fn ci_installs_pinned_versions() {
+    let graph = graph_with(json!({
+        "ci": ["github"],
+        "dependencies": {
+            "apt": { "libssl-dev": "3.0.2-0ubuntu1" },
+            "homebrew": { "openssl": "3" },
+            "chocolatey": { "nasm": { "version": "2.16.1" } },
+        },
+    }));
+    let installs = github_installs(&graph)
+        .into_iter()
+        .map(|(_, install)| install.unwrap())
+        .collect::<Vec<_>>();
+    assert!(
+        installs[0].contains("brew \"openssl@3\""),
+        "{}",
+        installs[0]
+    );
+    assert_eq!(installs[1], "choco install nasm --version=2.16.1");
+    assert_eq!(
+        installs[2],
+        "sudo apt-get install libssl-dev=3.0.2-0ubuntu1"
+    );
+}
@@ -6,8 +6,13 @@
 
 use super::mock::*;
 use semver::Version;
+use serde_json::json;
 
-use crate::{config::ArtifactMode, select_tag, DistGraphBuilder};
+use crate::{
+    config::{ArtifactMode, Config},
+    errors::DistError,
+    gather_work_for_workspace, select_tag, DistGraphBuilder,
+};
 
 #[test]
 fn parse_one() {
@@ -247,6 +252,83 @@ fn parse_one_package_slash() {
     assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
 }
 
+#[test]
+fn parse_one_package_at() {
+    // "axolotlsay@1.0.0" in a one package workspace
+    let workspace = workspace_just_axo();
+    let version: Version = BIN_AXO_VER.parse().unwrap();
+    let tag = format!("{BIN_AXO_NAME}@{version}");
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+
+    assert!(!announcing.prerelease);
+    assert_eq!(announcing.tag, tag);
+    assert_eq!(announcing.version, None);
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+}
+
+#[test]
+fn parse_unified_package_forms() {
+    // "helper-bin@1.0.0", "helper-bin-v1.0.0", and "monorepo/helper-bin/v1.0.0" all pick
+    // out the same package in a unified workspace
+    let workspace = workspace_unified();
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+
+    for tag in [
+        format!("{BIN_HELPER_NAME}@{BIN_HELPER_VER}"),
+        format!("{BIN_HELPER_NAME}-v{BIN_HELPER_VER}"),
+        format!("monorepo/{BIN_HELPER_NAME}/v{BIN_HELPER_VER}"),
+    ] {
+        let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+        assert!(!announcing.prerelease);
+        assert_eq!(announcing.tag, tag);
+        assert_eq!(announcing.version, None);
+        assert_eq!(announcing.rust_releases, vec![entry_helper_bin()], "{tag}");
+    }
+}
+
+#[test]
+fn parse_unknown_package_at() {
+    // "some-other-app@1.0.0" doesn't name anything in the workspace
+    let workspace = workspace_unified();
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let res = select_tag(&graph, Some("some-other-app@1.0.0"), true);
+
+    let Err(DistError::NoTagMatch { help, .. }) = res else {
+        panic!("expected NoTagMatch, got {:?}", res.err());
+    };
+    assert!(
+        help.contains(&format!("{BIN_AXO_NAME}@{BIN_AXO_VER}")),
+        "{help}"
+    );
+    assert!(
+        help.contains(&format!("{BIN_AXO_NAME}-v{BIN_AXO_VER}")),
+        "{help}"
+    );
+}
+
+#[test]
+fn parse_package_bad_delimiter() {
+    // "helper-bin_v1.0.0" has the right package, but an underscore isn't a delimiter
+    let workspace = workspace_unified();
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let res = select_tag(&graph, Some(&format!("{BIN_HELPER_NAME}_v1.0.0")), true);
+
+    let Err(DistError::NoTagMatch { help, .. }) = res else {
+        panic!("expected NoTagMatch, got {:?}", res.err());
+    };
+    // The suggestions use the package we think you meant
+    assert!(
+        help.contains(&format!("{BIN_HELPER_NAME}@{BIN_HELPER_VER}")),
+        "{help}"
+    );
+}
+
 #[test]
 fn parse_one_infer() {
     // Provide no explicit tag in a one package workspace
@@ -341,6 +423,41 @@ fn parse_disjoint_v() {
     );
 }
 
+#[test]
+fn parse_disjoint_dist_unpublished() {
+    // selecting the bulk packages in a disjoint workspace that dists publish = false packages
+    let mut workspace = workspace_disjoint();
+    workspace.cargo_metadata_table = Some(json!({ "dist": { "dist-unpublished": true } }));
+    let version: Version = BIN_AXO_VER.parse().unwrap();
+    let tag = format!("v{version}");
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+
+    // test-bin1 (publish = false) is now included, test-bin2 (dist = false) still isn't
+    assert_eq!(
+        announcing.rust_releases,
+        vec![
+            entry_axo_bin(),
+            entry_helper_bin(),
+            entry_forced_bin(),
+            entry_test_bin1()
+        ]
+    );
+
+    // dist = false still opts a publish = false package out
+    workspace.package_info[BIN_TEST1_IDX.0].cargo_metadata_table =
+        Some(json!({ "dist": { "dist": false } }));
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+    assert_eq!(
+        announcing.rust_releases,
+        vec![entry_axo_bin(), entry_helper_bin(), entry_forced_bin()]
+    );
+}
+
 #[test]
 #[should_panic = "TooManyUnrelatedApps"]
 fn parse_disjoint_infer() {
@@ -395,3 +512,258 @@ fn parse_disjoint_lib() {
     assert_eq!(announcing.version, None);
     assert_eq!(announcing.rust_releases, vec![]);
 }
+
+fn workspace_just_axo_with_tag_format(tag_format: &str) -> axoproject::WorkspaceInfo {
+    let mut workspace = workspace_just_axo();
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "tag-format": tag_format,
+        }
+    }));
+    workspace
+}
+
+#[test]
+fn tag_format_no_v() {
+    // tag-format = "{version}" should infer (and produce URLs for) "1.0.0"
+    let workspace = workspace_just_axo_with_tag_format("{version}");
+    let version: Version = BIN_AXO_VER.parse().unwrap();
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, None, true).unwrap();
+    assert_eq!(announcing.tag, BIN_AXO_VER);
+    assert_eq!(announcing.version, Some(version));
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+
+    let cfg = Config {
+        announcement_tag: None,
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    assert_eq!(graph.announcement_tag.as_deref(), Some(BIN_AXO_VER));
+    assert_eq!(
+        graph.artifact_download_url,
+        Some(format!("{REPO_URL}/releases/download/{BIN_AXO_VER}"))
+    );
+    assert_eq!(graph.tag_glob(), "**[0-9]+.[0-9]+.[0-9]+*");
+}
+
+#[test]
+fn tag_format_custom() {
+    // "release-1.0.0" with tag-format = "release-{version}"
+    let workspace = workspace_just_axo_with_tag_format("release-{version}");
+    let version: Version = BIN_AXO_VER.parse().unwrap();
+    let tag = format!("release-{version}");
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let inferred = select_tag(&graph, None, true).unwrap();
+    assert_eq!(inferred.tag, tag);
+
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+    assert_eq!(announcing.tag, tag);
+    assert_eq!(announcing.version, Some(version));
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+
+    // Package-specific tags wrap the format
+    let tag = format!("{BIN_AXO_NAME}-release-{BIN_AXO_VER}");
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+    assert_eq!(announcing.version, None);
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+    assert_eq!(graph.inner.tag_glob(), "**release-[0-9]+.[0-9]+.[0-9]+*");
+}
+
+#[test]
+fn tag_format_needs_version() {
+    let workspace = workspace_just_axo_with_tag_format("latest");
+    let tools = mock_tools();
+    let res = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true);
+    assert!(matches!(res, Err(DistError::TagFormatVersion { .. })));
+}
+
+fn workspace_calver_axo(version: &str) -> axoproject::WorkspaceInfo {
+    let mut workspace = mock_workspace(vec![axoproject::PackageInfo {
+        binaries: vec![BIN_AXO_NAME.to_owned()],
+        ..mock_package(BIN_AXO_NAME, version)
+    }]);
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "versioning": "calver",
+        }
+    }));
+    workspace
+}
+
+#[test]
+fn calver_release() {
+    // "v2024.06.1" (and "axolotlsay-v2024.06.1") for axolotlsay 2024.6.1
+    let workspace = workspace_calver_axo("2024.6.1");
+    let version: Version = "2024.6.1".parse().unwrap();
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, Some("v2024.06.1"), true).unwrap();
+    assert!(!announcing.prerelease);
+    assert_eq!(announcing.tag, "v2024.06.1");
+    assert_eq!(announcing.version, Some(version.clone()));
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+
+    let tag = format!("{BIN_AXO_NAME}-v2024.06.1");
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+    assert!(!announcing.prerelease);
+    assert_eq!(announcing.version, None);
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+
+    let inferred = select_tag(&graph, None, true).unwrap();
+    assert!(!inferred.prerelease);
+    assert_eq!(inferred.tag, "v2024.6.1");
+    assert_eq!(inferred.version, Some(version));
+}
+
+#[test]
+fn calver_rc() {
+    // "v2024.06.1-rc.1" is a prerelease because of the explicit marker
+    let workspace = workspace_calver_axo("2024.6.1-rc.1");
+    let version: Version = "2024.6.1-rc.1".parse().unwrap();
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, Some("v2024.06.1-rc.1"), true).unwrap();
+    assert!(announcing.prerelease);
+    assert_eq!(announcing.version, Some(version));
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+
+    let inferred = select_tag(&graph, None, true).unwrap();
+    assert!(inferred.prerelease);
+    assert_eq!(inferred.tag, "v2024.6.1-rc.1");
+}
+
+#[test]
+fn calver_respin_is_not_prerelease() {
+    // "v2024.06.1-2" has a suffix, but no marker, so it's a full release
+    let workspace = workspace_calver_axo("2024.6.1-2");
+
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let announcing = select_tag(&graph, Some("v2024.06.1-2"), true).unwrap();
+    assert!(!announcing.prerelease);
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+    assert!(!select_tag(&graph, None, true).unwrap().prerelease);
+
+    // ...whereas with semver any suffix is a prerelease
+    let workspace = mock_workspace(vec![axoproject::PackageInfo {
+        binaries: vec![BIN_AXO_NAME.to_owned()],
+        ..mock_package(BIN_AXO_NAME, "2024.6.1-2")
+    }]);
+    let graph = DistGraphBuilder::new(mock_tools(), &workspace, ArtifactMode::All, true).unwrap();
+    assert!(
+        select_tag(&graph, Some("v2024.6.1-2"), true)
+            .unwrap()
+            .prerelease
+    );
+}
+
+#[test]
+fn semver_rejects_leading_zeros() {
+    let workspace = workspace_just_axo();
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let res = select_tag(&graph, Some("v2024.06.1"), true);
+    assert!(matches!(res.err(), Some(DistError::TagVersionParse { .. })));
+}
+
+#[test]
+fn parse_unified_wrong_version() {
+    // "v9.9.9" in a workspace where everything is 1.0.0
+    let workspace = workspace_unified();
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let res = select_tag(&graph, Some("v9.9.9"), true);
+
+    let Err(DistError::NoPackageWithTagVersion { tag, version, help }) = res else {
+        panic!("expected NoPackageWithTagVersion, got {:?}", res.err());
+    };
+    assert_eq!(tag, "v9.9.9");
+    assert_eq!(version, "9.9.9".parse::<Version>().unwrap());
+    // The versions that would have worked are listed
+    assert!(
+        help.contains(&format!(
+            "--tag=v{BIN_AXO_VER} will Announce: {BIN_AXO_NAME}"
+        )),
+        "{help}"
+    );
+}
+
+fn workspace_disjoint_with_group(packages: &[&str]) -> axoproject::WorkspaceInfo {
+    let mut workspace = workspace_disjoint();
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "announcement-groups": { "tools": packages },
+        }
+    }));
+    workspace
+}
+
+#[test]
+fn parse_group() {
+    // "tools-v2.0.0" announces axolotlsay 1.0.0 and oddball-bin 0.1.0 together
+    let workspace = workspace_disjoint_with_group(&[BIN_AXO_NAME, BIN_ODDBALL_NAME]);
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+
+    for tag in ["tools-v2.0.0", "tools@2.0.0", "some/prefix/tools/v2.0.0"] {
+        let announcing = select_tag(&graph, Some(tag), true).unwrap();
+        assert!(!announcing.prerelease);
+        assert_eq!(announcing.tag, tag);
+        assert_eq!(announcing.version, Some("2.0.0".parse().unwrap()));
+        assert_eq!(announcing.package, None);
+        assert_eq!(
+            announcing.rust_releases,
+            vec![entry_axo_bin(), entry_oddball_bin()]
+        );
+    }
+
+    // Packages still win when they're the better match
+    let tag = format!("{BIN_AXO_NAME}-v{BIN_AXO_VER}");
+    let announcing = select_tag(&graph, Some(&tag), true).unwrap();
+    assert_eq!(announcing.rust_releases, vec![entry_axo_bin()]);
+}
+
+#[test]
+fn parse_group_unknown_package() {
+    let workspace = workspace_disjoint_with_group(&[BIN_AXO_NAME, "not-a-package"]);
+    let tools = mock_tools();
+    let graph = DistGraphBuilder::new(tools, &workspace, ArtifactMode::All, true).unwrap();
+    let res = select_tag(&graph, Some("tools-v2.0.0"), true);
+
+    let Err(DistError::UnknownGroupPackage { group, package }) = res else {
+        panic!("expected UnknownGroupPackage, got {:?}", res.err());
+    };
+    assert_eq!(group, "tools");
+    assert_eq!(package, "not-a-package");
+}
+
+#[test]
+fn group_releases() {
+    // Each package in the group gets its own Release, at its own version
+    let workspace = workspace_disjoint_with_group(&[BIN_AXO_NAME, BIN_ODDBALL_NAME]);
+    let cfg = Config {
+        announcement_tag: Some("tools-v2.0.0".to_owned()),
+        ..mock_config()
+    };
+    let graph = gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap();
+    let releases = graph
+        .releases
+        .iter()
+        .map(|release| (release.app_name.as_str(), release.version.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        releases,
+        vec![
+            (BIN_AXO_NAME, BIN_AXO_VER.to_owned()),
+            (BIN_ODDBALL_NAME, BIN_ODDBALL_VER.to_owned())
+        ]
+    );
+    assert_eq!(graph.announcement_tag.as_deref(), Some("tools-v2.0.0"));
+}
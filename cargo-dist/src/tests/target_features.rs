@@ -0,0 +1,107 @@
+//! Tests for per-target feature settings
+
+use super::mock::*;
+use serde_json::json;
+
+use crate::{
+    config::{target_matches_pattern, Config},
+    gather_work_for_workspace, BuildStep, CargoBuildStep, CargoTargetFeatureList,
+    CargoTargetPackages, DistGraph,
+};
+
+const LINUX: &str = "x86_64-unknown-linux-gnu";
+const MAC: &str = "aarch64-apple-darwin";
+const TARGETS: &[&str] = &[LINUX, MAC];
+
+fn graph_with(package_dist: serde_json::Value, workspace_dist: serde_json::Value) -> DistGraph {
+    let mut workspace = workspace_axo_dist(package_dist);
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+fn build_for<'a>(graph: &'a DistGraph, target: &str) -> &'a CargoBuildStep {
+    let builds = graph
+        .build_steps
+        .iter()
+        .filter_map(|step| match step {
+            BuildStep::Cargo(build) if build.target_triple == target => Some(build),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(builds.len(), 1, "{target}");
+    builds[0]
+}
+
+#[test]
+fn linux_only_feature() {
+    let graph = graph_with(
+        json!({ "targets": TARGETS }),
+        json!({
+            "ci": ["github"],
+            "features": ["fancy"],
+            "target-features": { "*-linux-*": { "features": ["dbus"] } },
+        }),
+    );
+
+    // The feature only gets enabled for the linux binaries
+    for binary in &graph.binaries {
+        let expected = if binary.target == LINUX {
+            vec!["fancy".to_owned(), "dbus".to_owned()]
+        } else {
+            vec!["fancy".to_owned()]
+        };
+        assert_eq!(
+            binary.features.features,
+            CargoTargetFeatureList::List(expected),
+            "{}",
+            binary.id
+        );
+    }
+
+    // Every package agrees with the workspace, so workspace builds still work,
+    // they just get different features for each target
+    assert!(!graph.precise_builds);
+    let linux = build_for(&graph, LINUX);
+    assert!(matches!(linux.package, CargoTargetPackages::Workspace));
+    assert_eq!(
+        linux.features.features,
+        CargoTargetFeatureList::List(vec!["fancy".to_owned(), "dbus".to_owned()])
+    );
+    let mac = build_for(&graph, MAC);
+    assert_eq!(
+        mac.features.features,
+        CargoTargetFeatureList::List(vec!["fancy".to_owned()])
+    );
+}
+
+#[test]
+fn package_target_features_need_precise_builds() {
+    let graph = graph_with(
+        json!({
+            "targets": TARGETS,
+            "target-features": { MAC: { "default-features": false } },
+        }),
+        json!({ "ci": ["github"] }),
+    );
+    assert!(graph.precise_builds);
+    let mac = build_for(&graph, MAC);
+    assert!(matches!(mac.package, CargoTargetPackages::Package(_)));
+    assert!(!mac.features.default_features);
+    assert!(build_for(&graph, LINUX).features.default_features);
+}
+
+#[test]
+fn target_patterns() {
+    assert!(target_matches_pattern(LINUX, LINUX));
+    assert!(!target_matches_pattern(LINUX, "x86_64-unknown-linux-musl"));
+    assert!(target_matches_pattern("*-linux-*", LINUX));
+    assert!(!target_matches_pattern("*-linux-*", MAC));
+    assert!(target_matches_pattern("x86_64-*", LINUX));
+    assert!(target_matches_pattern("*-gnu", LINUX));
+    assert!(target_matches_pattern("*", MAC));
+    assert!(!target_matches_pattern("*-apple-*-x", MAC));
+}
@@ -0,0 +1,76 @@
+//! Tests for rejecting unknown (probably misspelled) metadata.dist keys
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{errors::DistError, gather_work_for_workspace};
+
+fn workspace_axo(workspace_dist: serde_json::Value) -> axoproject::WorkspaceInfo {
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        manifest_path: "axolotlsay/Cargo.toml".into(),
+        ..pkg_axo_dist(json!({
+            "targets": ["x86_64-unknown-linux-gnu"],
+        }))
+    }]);
+    workspace.manifest_path = "Cargo.toml".into();
+    workspace.cargo_metadata_table = Some(json!({ "dist": workspace_dist }));
+    workspace
+}
+
+#[test]
+fn misspelled_key_is_an_error() {
+    let workspace = workspace_axo(json!({
+        "installer": ["shell"],
+    }));
+    let err = gather_work_for_workspace(&mock_config(), mock_tools(), &workspace).unwrap_err();
+
+    let Some(DistError::UnknownConfigKey {
+        manifest_path,
+        key,
+        help,
+    }) = err.downcast_ref::<DistError>()
+    else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(manifest_path, "Cargo.toml");
+    assert_eq!(key, "installer");
+    assert_eq!(help, "did you mean installers?");
+    assert_eq!(
+        err.to_string(),
+        "Unknown key installer in metadata.dist in Cargo.toml"
+    );
+}
+
+#[test]
+fn misspelled_package_key_is_an_error() {
+    let mut workspace = workspace_axo(json!({}));
+    workspace.package_info[0].cargo_metadata_table = Some(json!({
+        "dist": {
+            "unix_archive": ".tar.gz",
+        }
+    }));
+    let err = gather_work_for_workspace(&mock_config(), mock_tools(), &workspace).unwrap_err();
+
+    let Some(DistError::UnknownConfigKey {
+        manifest_path,
+        key,
+        help,
+    }) = err.downcast_ref::<DistError>()
+    else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(manifest_path, "axolotlsay/Cargo.toml");
+    assert_eq!(key, "unix_archive");
+    assert_eq!(help, "did you mean unix-archive?");
+}
+
+#[test]
+fn unknown_key_from_newer_cargo_dist_is_allowed() {
+    // This might be something a newer cargo-dist knows about, so we let it slide
+    let workspace = workspace_axo(json!({
+        "cargo-dist-version": "999.0.0",
+        "some-future-setting": true,
+    }));
+    gather_work_for_workspace(&mock_config(), mock_tools(), &workspace).unwrap();
+}
@@ -0,0 +1,169 @@
+//! Tests for WinGet manifests
+
+use super::mock::*;
+use axoproject::PackageInfo;
+use serde_json::json;
+
+use crate::{
+    backend::{
+        ci::github::GithubCiInfo,
+        installer::{winget::WinGetManifestKind, InstallerImpl},
+    },
+    config::{Config, InstallerStyle},
+    gather_work_for_workspace, ArtifactKind, DistGraph,
+};
+
+const TARGETS: &[&str] = &[
+    "x86_64-pc-windows-msvc",
+    "aarch64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+];
+
+/// Plan msis with WinGet manifests, with some extra `[package.metadata.dist]` settings
+fn graph_with_winget(winget_id: Option<&str>, extra_dist: serde_json::Value) -> DistGraph {
+    let mut dist = json!({
+        "installers": ["msi"],
+        "targets": TARGETS,
+    });
+    for (key, value) in extra_dist.as_object().unwrap() {
+        dist[key] = value.clone();
+    }
+    if let Some(winget_id) = winget_id {
+        dist["winget-id"] = json!(winget_id);
+    }
+    let mut workspace = mock_workspace(vec![PackageInfo {
+        license: Some("MIT".to_owned()),
+        authors: vec!["axodotdev <hello@axo.dev>".to_owned()],
+        manifest_path: "/axolotlsay/Cargo.toml".into(),
+        ..pkg_axo_dist(dist)
+    }]);
+    workspace.cargo_metadata_table = Some(json!({
+        "dist": {
+            "ci": ["github"],
+            "publish-jobs": ["winget"],
+        }
+    }));
+    let cfg = Config {
+        targets: TARGETS.iter().map(|t| t.to_string()).collect(),
+        installers: vec![InstallerStyle::Msi],
+        ..mock_config()
+    };
+    gather_work_for_workspace(&cfg, mock_tools(), &workspace).unwrap()
+}
+
+#[test]
+fn winget_manifests_use_msi_checksums() {
+    let graph = graph_with_winget(
+        Some("Axodotdev.Axolotlsay"),
+        json!({ "checksum": "sha256" }),
+    );
+    let manifests = graph
+        .artifacts
+        .iter()
+        .filter_map(|a| match &a.kind {
+            ArtifactKind::Installer(InstallerImpl::WinGet(info)) => Some((a, info)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let ids = manifests
+        .iter()
+        .map(|(a, _)| a.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            "Axodotdev.Axolotlsay.yaml",
+            "Axodotdev.Axolotlsay.installer.yaml",
+            "Axodotdev.Axolotlsay.locale.en-US.yaml",
+        ]
+    );
+    assert!(manifests.iter().all(|(a, _)| a.is_global));
+
+    let (_, info) = manifests
+        .iter()
+        .find(|(_, info)| info.kind == WinGetManifestKind::Installer)
+        .unwrap();
+    assert_eq!(info.publisher, "axodotdev");
+    assert_eq!(info.license, "MIT");
+    let arches = info
+        .installers
+        .iter()
+        .map(|i| i.architecture.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(arches, ["x64", "arm64"]);
+
+    // Each installer must point at the checksum the msi actually gets
+    for installer in &info.installers {
+        let msi_name = installer.url.rsplit('/').next().unwrap();
+        let msi = graph
+            .artifacts
+            .iter()
+            .find(|a| a.id == msi_name)
+            .expect("winget references an msi that doesn't exist");
+        let checksum = graph.artifact(msi.checksum.expect("msi has no checksum"));
+        assert_eq!(installer.checksum_path, checksum.file_path);
+    }
+}
+
+#[test]
+fn winget_publish_job() {
+    let graph = graph_with_winget(
+        Some("Axodotdev.Axolotlsay"),
+        json!({ "checksum": "sha256" }),
+    );
+    let ci = GithubCiInfo::new(&graph);
+    let job = ci
+        .pr_publish_jobs
+        .iter()
+        .find(|job| job.name == "winget")
+        .expect("no winget publish job");
+    assert_eq!(job.repo, "microsoft/winget-pkgs");
+    assert_eq!(job.files.len(), 3);
+
+    let workflow = ci.generate_github_ci(&graph).unwrap();
+    assert!(workflow.contains("  publish-winget:\n"), "{workflow}");
+    assert!(workflow.contains(r#"gh repo fork "microsoft/winget-pkgs" --clone=false"#));
+    assert!(workflow.contains(
+        r#""manifests/a/Axodotdev/Axolotlsay/${version}/Axodotdev.Axolotlsay.installer.yaml""#
+    ));
+}
+
+#[test]
+fn no_winget_without_id() {
+    let graph = graph_with_winget(None, json!({ "checksum": "sha256" }));
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::WinGet(_)))));
+    assert!(GithubCiInfo::new(&graph).pr_publish_jobs.is_empty());
+}
+
+#[test]
+fn no_winget_without_sha256() {
+    let graph = graph_with_winget(
+        Some("Axodotdev.Axolotlsay"),
+        json!({ "checksum": "sha512" }),
+    );
+    assert!(!graph
+        .artifacts
+        .iter()
+        .any(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::WinGet(_)))));
+}
+
+#[test]
+fn winget_manifests_are_signed() {
+    let graph = graph_with_winget(
+        Some("Axodotdev.Axolotlsay"),
+        json!({ "checksum": "sha256", "gpg-key": "ABCD1234" }),
+    );
+    let manifests = graph
+        .artifacts
+        .iter()
+        .filter(|a| matches!(a.kind, ArtifactKind::Installer(InstallerImpl::WinGet(_))))
+        .collect::<Vec<_>>();
+    assert_eq!(manifests.len(), 3);
+    for manifest in manifests {
+        let signature = graph.artifact(manifest.signature.expect("manifest wasn't signed"));
+        assert_eq!(signature.id, format!("{}.asc", manifest.id));
+    }
+}
@@ -41,7 +41,7 @@ targets = ["x86_64-unknown-linux-gnu", "x86_64-apple-darwin", "x86_64-pc-windows
 ci = ["github"]
 unix-archive = ".tar.gz"
 windows-archive = ".tar.gz"
-scope = "@axodotdev"
+npm-scope = "@axodotdev"
 
 [package.metadata.wix]
 upgrade-guid = "B36177BE-EA4D-44FB-B05C-EDDABDAA95CA"
@@ -76,7 +76,7 @@ targets = ["x86_64-unknown-linux-gnu", "x86_64-apple-darwin", "x86_64-pc-windows
 ci = ["github"]
 unix-archive = ".tar.gz"
 windows-archive = ".tar.gz"
-scope = "@axodotdev"
+npm-scope = "@axodotdev"
 
 "#
         ))?;
@@ -108,7 +108,7 @@ targets = ["x86_64-unknown-linux-gnu", "x86_64-apple-darwin", "x86_64-pc-windows
 ci = ["github"]
 unix-archive = ".tar.gz"
 windows-archive = ".tar.gz"
-scope = "@axodotdev"
+npm-scope = "@axodotdev"
 create-release = false
 
 "#
@@ -209,7 +209,7 @@ targets = ["x86_64-unknown-linux-gnu", "x86_64-apple-darwin", "x86_64-pc-windows
 ci = ["github"]
 unix-archive = ".tar.gz"
 windows-archive = ".tar.gz"
-scope = "@axodotdev"
+npm-scope = "@axodotdev"
 "#
         ))?;
 